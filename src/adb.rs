@@ -0,0 +1,177 @@
+//! Installed application module.
+//!
+//! With `--adb <package-id>`, the application is pulled from a device connected with _adb_, so
+//! that applications that are only available in some regions or devices can be analyzed. The
+//! device is chosen by _adb_ itself, so the `ANDROID_SERIAL` environment variable can be used to
+//! select it when more than one device is connected.
+//!
+//! Applications installed as a single APK are stored as `<package-id>.apk` in the downloads
+//! folder. Applications installed as split APKs are stored as a split APK set,
+//! `<package-id>.apks`, that is merged before the analysis like any other split APK set.
+
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use failure::{bail, format_err, Error, ResultExt};
+use tempfile::Builder;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::print_message;
+
+/// Prefix of the paths printed by `pm path`.
+const PATH_PREFIX: &str = "package:";
+
+/// Pulls the APK files of the given installed application to the downloads folder, returning the
+/// path of the application package.
+pub(crate) fn pull(adb: &Path, package: &str, downloads_folder: &Path) -> Result<PathBuf, Error> {
+    if !is_valid_package_id(package) {
+        bail!("invalid package ID `{}`", package);
+    }
+
+    let output = adb_output(adb, &["shell", "pm", "path", package])?;
+    let remote_paths = parse_pm_path(&output);
+    if remote_paths.is_empty() {
+        bail!("{} is not installed in the device", package);
+    }
+
+    fs::create_dir_all(downloads_folder).context(format_err!(
+        "could not create the downloads folder `{}`",
+        downloads_folder.display()
+    ))?;
+
+    if let [remote_path] = remote_paths.as_slice() {
+        let path = downloads_folder.join(format!("{}.apk", package));
+        print_message(format!("Pulling {} from the device…", package));
+        pull_file(adb, remote_path, &path)?;
+        return Ok(path);
+    }
+
+    let path = downloads_folder.join(format!("{}.apks", package));
+    print_message(format!(
+        "Pulling the {} split APKs of {} from the device…",
+        remote_paths.len(),
+        package
+    ));
+    let temp_folder = Builder::new()
+        .prefix("super-adb-")
+        .tempdir()
+        .context("could not create the temporary split APK folder")?;
+    pull_split_set(adb, &remote_paths, temp_folder.path(), &path).map(|_| path)
+}
+
+/// Checks if the given package ID is valid, so that it can be passed to the device shell and used
+/// as a file name.
+///
+/// Package IDs can only contain ASCII letters, digits, underscores and dots.
+fn is_valid_package_id(package: &str) -> bool {
+    !package.is_empty()
+        && !package.starts_with('.')
+        && package
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Pulls the given split APKs to the given temporary folder and packs them in a split APK set.
+fn pull_split_set(
+    adb: &Path,
+    remote_paths: &[&str],
+    temp_folder: &Path,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut writer = ZipWriter::new(File::create(path).context(format_err!(
+        "could not create the split APK set `{}`",
+        path.display()
+    ))?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+    for remote_path in remote_paths {
+        let name = Path::new(remote_path)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("invalid APK path `{}` in the device", remote_path))?;
+        let local_path = temp_folder.join(name);
+        pull_file(adb, remote_path, &local_path)?;
+
+        writer.start_file(name, options)?;
+        let _ = io::copy(&mut File::open(&local_path)?, &mut writer)?;
+    }
+    let _ = writer.finish()?;
+
+    Ok(())
+}
+
+/// Pulls the given file of the device to the given local path.
+fn pull_file(adb: &Path, remote_path: &str, path: &Path) -> Result<(), Error> {
+    let local_path = path
+        .to_str()
+        .ok_or_else(|| format_err!("invalid path `{}`", path.display()))?;
+    let _ = adb_output(adb, &["pull", remote_path, local_path])?;
+    Ok(())
+}
+
+/// Runs _adb_ with the given arguments, returning its standard output.
+fn adb_output(adb: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new(adb).args(args).output().context(format_err!(
+        "could not run adb at `{}`, check the `adb_file` option",
+        adb.display()
+    ))?;
+    if !output.status.success() {
+        bail!(
+            "adb exited with {}. Stderr: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses the output of `pm path`, returning the paths of the APK files in the device.
+fn parse_pm_path(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(PATH_PREFIX))
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_package_id, parse_pm_path};
+
+    /// Checks the validation of the package IDs.
+    #[test]
+    fn it_valid_package_id() {
+        assert!(is_valid_package_id("com.example.app"));
+        assert!(is_valid_package_id("com.example_app2"));
+        assert!(!is_valid_package_id(""));
+        assert!(!is_valid_package_id(".."));
+        assert!(!is_valid_package_id("../com.example"));
+        assert!(!is_valid_package_id("com.example; reboot"));
+        assert!(!is_valid_package_id("com-example"));
+    }
+
+    /// Checks the parsing of the output of `pm path`.
+    #[test]
+    fn it_parse_pm_path() {
+        assert_eq!(
+            parse_pm_path("package:/data/app/com.example.app-1/base.apk\n"),
+            vec!["/data/app/com.example.app-1/base.apk"]
+        );
+        assert_eq!(
+            parse_pm_path(
+                "package:/data/app/~~Xy==/com.example.app-Ab==/base.apk\r\n\
+                 package:/data/app/~~Xy==/com.example.app-Ab==/split_config.arm64_v8a.apk\r\n\
+                 package:/data/app/~~Xy==/com.example.app-Ab==/split_config.en.apk\r\n"
+            )
+            .len(),
+            3
+        );
+        assert!(parse_pm_path("").is_empty());
+    }
+}
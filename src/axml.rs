@@ -0,0 +1,86 @@
+//! Binary XML decoding module.
+//!
+//! The XML files of APK packages, such as `AndroidManifest.xml`, are compiled by `aapt` to a
+//! binary format, usually known as AXML. They are decoded back to text XML with `abxml`, the same
+//! library used to decompress the packages, so that the manifest can be analyzed even if the
+//! package could not be decompressed, or if it was extracted without decoding its XML files.
+//!
+//! References to resources are resolved with the `resources.arsc` resource table of the package,
+//! if it is available, and with the resources of the Android framework otherwise.
+
+use std::{
+    fs::{self, File},
+    io::{Read, Seek},
+    path::Path,
+};
+
+use abxml::{decoder::Decoder, STR_ARSC};
+use failure::{bail, Error, ResultExt};
+use zip::ZipArchive;
+
+/// Type of the chunk of a binary XML document.
+const RES_XML_TYPE: u16 = 0x0003;
+
+/// Reads and decodes the `AndroidManifest.xml` file of the given APK package.
+pub fn manifest_from_apk<P: AsRef<Path>>(apk: P) -> Result<String, Error> {
+    let mut archive =
+        ZipArchive::new(File::open(apk.as_ref())?).context("the APK is not a valid ZIP archive")?;
+    let resources = read_entry(&mut archive, "resources.arsc").ok();
+    let bytes = read_entry(&mut archive, "AndroidManifest.xml")
+        .context("the APK does not contain an `AndroidManifest.xml` file")?;
+
+    Ok(decode(&bytes, resources.as_ref().map(Vec::as_slice))
+        .context("could not decode the binary `AndroidManifest.xml` file")?)
+}
+
+/// Checks if the given contents are a binary XML document.
+pub fn is_binary_xml(bytes: &[u8]) -> bool {
+    bytes.len() >= 8 && bytes[0..2] == RES_XML_TYPE.to_le_bytes()
+}
+
+/// Decodes the given binary XML document of the decompressed application in the given folder.
+///
+/// References to resources are resolved with the `resources.arsc` file of the folder, if any.
+pub fn decode_in<P: AsRef<Path>>(bytes: &[u8], app_folder: P) -> Result<String, Error> {
+    let resources = fs::read(app_folder.as_ref().join("resources.arsc")).ok();
+    decode(bytes, resources.as_ref().map(Vec::as_slice))
+}
+
+/// Decodes the given binary XML document to text XML, resolving the references to resources with
+/// the given resource table, if any.
+pub fn decode(bytes: &[u8], resources: Option<&[u8]>) -> Result<String, Error> {
+    if !is_binary_xml(bytes) {
+        bail!("the file is not a binary XML document");
+    }
+
+    let decoder =
+        Decoder::from_buffer(resources.unwrap_or(STR_ARSC)).context("invalid resource table")?;
+    Ok(decoder
+        .as_xml(&bytes)
+        .context("invalid binary XML document")?)
+}
+
+/// Reads the entry with the given name of the given ZIP archive.
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, Error> {
+    let mut entry = archive.by_name(name)?;
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    let _ = entry.read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, is_binary_xml};
+
+    /// Checks the detection of binary XML documents.
+    #[test]
+    fn it_is_binary_xml() {
+        assert!(is_binary_xml(&[
+            0x03, 0x00, 0x08, 0x00, 0x08, 0x00, 0x00, 0x00
+        ]));
+        assert!(!is_binary_xml(&[0x03, 0x00, 0x08, 0x00]));
+        assert!(!is_binary_xml(b"<?xml version=\"1.0\"?>"));
+        assert!(decode(b"<manifest/>", None).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+//! Benchmarks module.
+//!
+//! With `--bench`, SUPER measures the duration of each phase of the analysis of each package, as
+//! well as the time spent in the code analysis by file type and by rule. Besides being printed,
+//! the benchmarks are written as JSON to the `benchmarks.json` file of the results folder, with
+//! the rules that take most of the code analysis time as hot spots, so that the regexes that
+//! dominate the runtime can be found and optimized.
+
+use std::{cmp::Ordering, collections::BTreeMap, fs, path::Path, sync::Mutex, time::Duration};
+
+use failure::{format_err, Error, ResultExt};
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+use crate::utils::Benchmark;
+
+/// Number of rules reported as hot spots.
+const HOT_SPOTS: usize = 5;
+
+lazy_static! {
+    /// Code analysis profiles of the packages analyzed in this run, by package name.
+    static ref CODE_PROFILES: Mutex<BTreeMap<String, CodeProfile>> = Mutex::new(BTreeMap::new());
+}
+
+/// Converts the given duration to seconds.
+pub(crate) fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
+
+/// Records the time spent in the code analysis of the given package, by rule and by file type.
+pub(crate) fn record_code_profile<S: Into<String>>(
+    package: S,
+    mut rules: Vec<RuleProfile>,
+    file_types: BTreeMap<String, FileTypeProfile>,
+) {
+    rules.sort_by(|a, b| b.seconds.partial_cmp(&a.seconds).unwrap_or(Ordering::Equal));
+    if let Ok(mut profiles) = CODE_PROFILES.lock() {
+        let _ = profiles.insert(package.into(), CodeProfile { rules, file_types });
+    }
+}
+
+/// Gets the rules that took most of the code analysis time of the given package, with their
+/// evaluation time and the share of the time of all the rules they took.
+pub fn hot_spots(package: &str) -> Vec<HotSpot> {
+    CODE_PROFILES.lock().ok().map_or_else(Vec::new, |profiles| {
+        profiles
+            .get(package)
+            .map_or_else(Vec::new, CodeProfile::hot_spots)
+    })
+}
+
+/// Renders the given benchmarks of each package, and the total duration of the run, as JSON.
+pub fn render(
+    benchmarks: &BTreeMap<String, Vec<Benchmark>>,
+    total: Duration,
+) -> Result<String, Error> {
+    let profiles = CODE_PROFILES
+        .lock()
+        .map_err(|_| format_err!("the code analysis profiles are poisoned"))?;
+    let packages = benchmarks
+        .iter()
+        .map(|(package, phases)| {
+            let profile = profiles.get(package);
+            PackageBenchmarks {
+                package,
+                phases: phases
+                    .iter()
+                    .map(|phase| PhaseBenchmark {
+                        phase: phase.label(),
+                        seconds: seconds(phase.duration()),
+                    })
+                    .collect(),
+                file_types: profile.map(|profile| &profile.file_types),
+                rules: profile.map(|profile| profile.rules.as_slice()),
+                hot_spots: profile.map(CodeProfile::hot_spots),
+            }
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&Benchmarks {
+        total_seconds: seconds(total),
+        packages,
+    })?)
+}
+
+/// Writes the given benchmarks of each package, and the total duration of the run, to the given
+/// file.
+pub fn write<P: AsRef<Path>>(
+    path: P,
+    benchmarks: &BTreeMap<String, Vec<Benchmark>>,
+    total: Duration,
+) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut json = render(benchmarks, total)?;
+    json.push('\n');
+    fs::write(path, json).context(format_err!(
+        "could not write the benchmarks to `{}`",
+        path.display()
+    ))?;
+    Ok(())
+}
+
+/// Benchmarks of a run.
+#[derive(Debug, Serialize)]
+struct Benchmarks<'b> {
+    /// Total duration of the run, in seconds.
+    total_seconds: f64,
+    /// Benchmarks of each analyzed package.
+    packages: Vec<PackageBenchmarks<'b>>,
+}
+
+/// Benchmarks of the analysis of a package.
+#[derive(Debug, Serialize)]
+struct PackageBenchmarks<'b> {
+    /// Package of the application.
+    package: &'b str,
+    /// Duration of each phase of the analysis.
+    phases: Vec<PhaseBenchmark<'b>>,
+    /// Time spent in the code analysis by file type, if the code was analyzed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_types: Option<&'b BTreeMap<String, FileTypeProfile>>,
+    /// Time spent in the code analysis by rule, slowest first, if the code was analyzed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rules: Option<&'b [RuleProfile]>,
+    /// Rules that took most of the code analysis time, if the code was analyzed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hot_spots: Option<Vec<HotSpot>>,
+}
+
+/// Duration of a phase of the analysis.
+#[derive(Debug, Serialize)]
+struct PhaseBenchmark<'b> {
+    /// Name of the phase.
+    phase: &'b str,
+    /// Duration of the phase, in seconds.
+    seconds: f64,
+}
+
+/// Time spent in the code analysis of a package, by rule and by file type.
+#[derive(Debug)]
+struct CodeProfile {
+    /// Time spent in each rule, slowest first.
+    rules: Vec<RuleProfile>,
+    /// Time spent in each file type, by extension.
+    file_types: BTreeMap<String, FileTypeProfile>,
+}
+
+impl CodeProfile {
+    /// Gets the rules that took most of the code analysis time.
+    fn hot_spots(&self) -> Vec<HotSpot> {
+        let total = self.rules.iter().map(|rule| rule.seconds).sum::<f64>();
+        self.rules
+            .iter()
+            .take(HOT_SPOTS)
+            .filter(|rule| rule.seconds > 0.0)
+            .map(|rule| HotSpot {
+                rule: rule.id.clone(),
+                seconds: rule.seconds,
+                share: rule.seconds / total,
+            })
+            .collect()
+    }
+}
+
+/// Time spent evaluating a rule in the code analysis.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RuleProfile {
+    /// Identifier of the rule.
+    id: String,
+    /// Number of files the rule was evaluated in.
+    evaluations: usize,
+    /// Total evaluation time, in seconds.
+    seconds: f64,
+}
+
+impl RuleProfile {
+    /// Creates the profile of the rule with the given identifier.
+    pub(crate) fn new(id: String, evaluations: usize, duration: Duration) -> Self {
+        Self {
+            id,
+            evaluations,
+            seconds: seconds(duration),
+        }
+    }
+}
+
+/// Time spent in the code analysis of the files of a type.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub(crate) struct FileTypeProfile {
+    /// Number of analyzed files.
+    files: usize,
+    /// Total analysis time, in seconds.
+    seconds: f64,
+}
+
+impl FileTypeProfile {
+    /// Records the analysis of a file that took the given time.
+    pub(crate) fn record(&mut self, duration: Duration) {
+        self.files += 1;
+        self.seconds += seconds(duration);
+    }
+}
+
+/// Rule that took a big part of the code analysis time.
+#[derive(Debug, Clone, Serialize)]
+pub struct HotSpot {
+    /// Identifier of the rule.
+    rule: String,
+    /// Evaluation time of the rule, in seconds.
+    seconds: f64,
+    /// Share of the evaluation time of all the rules taken by the rule, between 0 and 1.
+    share: f64,
+}
+
+impl HotSpot {
+    /// Gets the identifier of the rule.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the evaluation time of the rule, in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    /// Gets the share of the evaluation time of all the rules taken by the rule, between 0
+    /// and 1.
+    pub fn share(&self) -> f64 {
+        self.share
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use super::{hot_spots, record_code_profile, render, FileTypeProfile, RuleProfile};
+    use crate::utils::Benchmark;
+
+    /// Checks the rendering of the benchmarks and the hot spots.
+    #[test]
+    fn it_render() {
+        let rules = vec![
+            RuleProfile::new("fast".to_owned(), 10, Duration::from_millis(100)),
+            RuleProfile::new("slow".to_owned(), 10, Duration::from_millis(300)),
+            RuleProfile::new("unused".to_owned(), 0, Duration::from_millis(0)),
+        ];
+        let mut file_types = BTreeMap::new();
+        file_types
+            .entry("java".to_owned())
+            .or_insert_with(FileTypeProfile::default)
+            .record(Duration::from_millis(400));
+        record_code_profile("com.example.bench", rules, file_types);
+
+        let hot_spots = hot_spots("com.example.bench");
+        assert_eq!(hot_spots.len(), 2);
+        assert_eq!(hot_spots[0].rule(), "slow");
+        assert!((hot_spots[0].share() - 0.75).abs() < 1e-9);
+        assert_eq!(hot_spots[1].rule(), "fast");
+
+        let mut benchmarks = BTreeMap::new();
+        let _ = benchmarks.insert(
+            "com.example.bench".to_owned(),
+            vec![Benchmark::new("Code analysis", Duration::from_secs(1))],
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&benchmarks, Duration::from_secs(2)).unwrap()).unwrap();
+        let package = &json["packages"][0];
+        assert_eq!(json["total_seconds"], 2.0);
+        assert_eq!(package["phases"][0]["phase"], "Code analysis");
+        assert_eq!(package["file_types"]["java"]["files"], 1);
+        assert_eq!(package["rules"][0]["id"], "slow");
+        assert_eq!(package["hot_spots"][0]["rule"], "slow");
+    }
+}
@@ -0,0 +1,468 @@
+//! Android App Bundle and split APK module.
+//!
+//! Besides single APK files, applications are distributed as Android App Bundles (`.aab` files),
+//! that contain every module of the application, and as split APK sets (`.apks` and `.xapk`
+//! files), that contain a base APK and a number of configuration and feature split APKs. This
+//! module extracts them to the distribution folder as if they were a single APK, merging the DEX
+//! files, native libraries, assets and resources of every module or split, so that the rest of
+//! the analysis sees the whole application.
+//!
+//! DEX files are renumbered while merging, so that the DEX files of the base module or APK keep
+//! their names (`classes.dex`, `classes2.dex`…) and the ones of the rest of modules or splits
+//! follow them.
+
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use abxml::apk::Apk;
+use failure::{bail, format_err, Error, ResultExt};
+use tempfile::Builder;
+use zip::ZipArchive;
+
+use crate::print_warning;
+
+/// Extensions of the supported application packages.
+pub(crate) const PACKAGE_EXTENSIONS: [&str; 4] = ["apk", "aab", "apks", "xapk"];
+
+/// Name of the base module of Android App Bundles.
+const BASE_MODULE: &str = "base";
+
+/// Format of an application package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PackageFormat {
+    /// Single APK file.
+    Apk,
+    /// Android App Bundle (`.aab` file).
+    Bundle,
+    /// Split APK set (`.apks` or `.xapk` file).
+    SplitSet,
+}
+
+impl PackageFormat {
+    /// Gets the format of the given package, from its extension.
+    pub(crate) fn of<P: AsRef<Path>>(package: P) -> Self {
+        match package
+            .as_ref()
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("aab") => PackageFormat::Bundle,
+            Some("apks") | Some("xapk") => PackageFormat::SplitSet,
+            _ => PackageFormat::Apk,
+        }
+    }
+}
+
+/// Checks if the given extension is the extension of a supported application package.
+pub(crate) fn is_package_extension(extension: &OsStr) -> bool {
+    extension.to_str().map_or(false, |extension| {
+        PACKAGE_EXTENSIONS
+            .iter()
+            .any(|supported| extension.eq_ignore_ascii_case(supported))
+    })
+}
+
+/// Extracts the given Android App Bundle to the given folder, merging all its modules.
+///
+/// Note that the manifest and the resources of bundles are compiled in the protocol buffer format
+/// of `aapt2`, so they are extracted as they are.
+pub(crate) fn extract_bundle<P: AsRef<Path>, D: AsRef<Path>>(
+    package: P,
+    dist_folder: D,
+) -> Result<(), Error> {
+    let dist_folder = dist_folder.as_ref();
+    let mut archive = ZipArchive::new(File::open(package.as_ref())?)
+        .context("the bundle is not a valid ZIP archive")?;
+
+    let mut names = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        names.push(archive.by_index(i)?.name().to_owned());
+    }
+    names.sort_by_key(|name| bundle_order(name));
+
+    fs::create_dir_all(dist_folder)?;
+    let mut dex_count = 0;
+    for name in names {
+        let target = match bundle_target(&name) {
+            Some(BundleEntry::Dex) => {
+                dex_count += 1;
+                PathBuf::from(dex_name(dex_count))
+            }
+            Some(BundleEntry::File(target)) => target,
+            None => continue,
+        };
+
+        let path = dist_folder.join(target);
+        if path.exists() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut entry = archive.by_name(&name)?;
+        let mut file = File::create(&path).context(format_err!(
+            "could not create the file `{}`",
+            path.display()
+        ))?;
+        let _ = io::copy(&mut entry, &mut file)?;
+    }
+
+    if dex_count == 0 {
+        bail!("the bundle does not contain any DEX file");
+    }
+    Ok(())
+}
+
+/// Entry of an Android App Bundle to extract.
+#[derive(Debug, PartialEq)]
+enum BundleEntry {
+    /// DEX file, that will be renumbered.
+    Dex,
+    /// Any other file, with its path in the distribution folder.
+    File(PathBuf),
+}
+
+/// Gets where the given entry of an Android App Bundle is extracted, if it is extracted at all.
+///
+/// Only the manifest of the base module is extracted, and bundle metadata is ignored. Entries that
+/// would be extracted outside of the distribution folder are ignored too.
+fn bundle_target(name: &str) -> Option<BundleEntry> {
+    let mut parts = name.splitn(3, '/');
+    let module = parts.next()?;
+    let folder = parts.next()?;
+    let path = parts.next().filter(|path| !path.is_empty())?;
+    if module == "BUNDLE-METADATA" || module == "META-INF" {
+        return None;
+    }
+
+    let target = match folder {
+        "manifest" if module == BASE_MODULE && path == "AndroidManifest.xml" => PathBuf::from(path),
+        "dex" if path.ends_with(".dex") => return Some(BundleEntry::Dex),
+        "lib" | "assets" | "res" => Path::new(folder).join(path),
+        "root" => PathBuf::from(path),
+        _ => return None,
+    };
+    if is_enclosed(&target) {
+        Some(BundleEntry::File(target))
+    } else {
+        None
+    }
+}
+
+/// Checks that the given relative path stays inside the folder it is joined to.
+///
+/// Paths with root, prefix, `.` or `..` components are rejected, so that malicious archives
+/// cannot write files outside of the distribution folder.
+fn is_enclosed(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Gets the order in which the entries of an Android App Bundle are extracted.
+///
+/// The base module goes first, and the DEX files of each module go in their numeric order.
+fn bundle_order(name: &str) -> (bool, String, usize, String) {
+    let module = name.split('/').next().unwrap_or_default();
+    let file_name = name.rsplit('/').next().unwrap_or_default();
+    (
+        module != BASE_MODULE,
+        module.to_owned(),
+        dex_index(file_name).unwrap_or(0),
+        name.to_owned(),
+    )
+}
+
+/// Extracts the given split APK set to the given folder, merging all its splits.
+///
+/// The base APK is decompressed first, and then the rest of splits are decompressed and merged
+/// into it. Files of the base APK take precedence over the files of the splits.
+pub(crate) fn extract_split_set<P: AsRef<Path>, D: AsRef<Path>>(
+    package: P,
+    dist_folder: D,
+) -> Result<(), Error> {
+    let dist_folder = dist_folder.as_ref();
+    let mut archive = ZipArchive::new(File::open(package.as_ref())?)
+        .context("the split APK set is not a valid ZIP archive")?;
+
+    let mut splits = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        if entry.name().ends_with(".apk") {
+            splits.push((entry.name().to_owned(), entry.size()));
+        }
+    }
+    let base = match base_apk(&splits) {
+        Some(base) => base.to_owned(),
+        None => bail!("the split APK set does not contain any APK file"),
+    };
+
+    let temp_folder = Builder::new()
+        .prefix("super-splits-")
+        .tempdir()
+        .context("could not create the temporary split APK folder")?;
+    let result = extract_splits(
+        &mut archive,
+        &base,
+        &splits,
+        temp_folder.path(),
+        dist_folder,
+    );
+    if let Err(e) = temp_folder.close() {
+        print_warning(format!(
+            "there was an error when removing the temporary split APK folder: {}",
+            e
+        ));
+    }
+    result
+}
+
+/// Extracts and merges the given splits, using the given temporary folder.
+fn extract_splits(
+    archive: &mut ZipArchive<File>,
+    base: &str,
+    splits: &[(String, u64)],
+    temp_folder: &Path,
+    dist_folder: &Path,
+) -> Result<(), Error> {
+    let mut apk_paths = Vec::with_capacity(splits.len());
+    for (i, (name, _)) in splits.iter().enumerate() {
+        let path = temp_folder.join(format!("{}.apk", i));
+        let mut entry = archive.by_name(name)?;
+        let _ = io::copy(&mut entry, &mut File::create(&path)?)?;
+        apk_paths.push((name.as_str(), path));
+    }
+
+    let base_path = &apk_paths
+        .iter()
+        .find(|(name, _)| *name == base)
+        .expect("the base APK must be in the split APK set")
+        .1;
+    let mut apk = Apk::from_path(base_path).context("error loading the base apk file")?;
+    apk.export(dist_folder, true).context(format_err!(
+        "could not decompress the base apk file `{}`",
+        base
+    ))?;
+    let mut dex_count = (1..)
+        .take_while(|&i| dist_folder.join(dex_name(i)).exists())
+        .count();
+
+    for (name, path) in apk_paths.iter().filter(|(name, _)| *name != base) {
+        let split_folder = path.with_extension("");
+        let exported = Apk::from_path(path)
+            .and_then(|mut apk| apk.export(&split_folder, true))
+            .and_then(|_| merge_split(&split_folder, dist_folder, &mut dex_count));
+        if let Err(e) = exported {
+            print_warning(format!(
+                "the split APK `{}` could not be merged, so it will not be analyzed: {}",
+                name, e
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Merges the decompressed split in the given folder into the distribution folder.
+fn merge_split(
+    split_folder: &Path,
+    dist_folder: &Path,
+    dex_count: &mut usize,
+) -> Result<(), Error> {
+    let mut dex_files = Vec::new();
+    for entry in fs::read_dir(split_folder)? {
+        let path = entry?.path();
+        let file_name = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default()
+            .to_owned();
+        if let Some(index) = dex_index(&file_name) {
+            dex_files.push((index, path));
+        } else if file_name == "AndroidManifest.xml" || file_name == "META-INF" {
+            continue;
+        } else {
+            merge_path(&path, &dist_folder.join(&file_name))?;
+        }
+    }
+
+    dex_files.sort();
+    for (_, path) in dex_files {
+        *dex_count += 1;
+        let _ = fs::copy(path, dist_folder.join(dex_name(*dex_count)))?;
+    }
+    Ok(())
+}
+
+/// Copies the given file or folder to the given path, without overwriting existing files.
+fn merge_path(from: &Path, to: &Path) -> Result<(), Error> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let path = entry?.path();
+            let file_name = path.file_name().expect("expected file name");
+            merge_path(&path, &to.join(file_name))?;
+        }
+    } else if !to.exists() {
+        let _ = fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Chooses the base APK of a split APK set, from the names and sizes of its APK files.
+///
+/// The base APK is named `base.apk` or `base-master.apk` in the sets generated by `bundletool`.
+/// Other tools name it after the package, so the biggest APK that is not a configuration split
+/// is chosen in that case.
+fn base_apk(splits: &[(String, u64)]) -> Option<&str> {
+    let stem = |name: &str| {
+        let file_name = name.rsplit('/').next().unwrap_or_default();
+        file_name[..file_name.len() - ".apk".len()].to_owned()
+    };
+
+    splits
+        .iter()
+        .find(|(name, _)| {
+            let stem = stem(name);
+            stem == "base" || stem == "base-master"
+        })
+        .or_else(|| {
+            splits
+                .iter()
+                .filter(|(name, _)| {
+                    let stem = stem(name);
+                    !stem.starts_with("config.") && !stem.starts_with("split_")
+                })
+                .max_by_key(|(_, size)| *size)
+        })
+        .or_else(|| splits.iter().max_by_key(|(_, size)| *size))
+        .map(|(name, _)| name.as_str())
+}
+
+/// Gets the index of the given DEX file name (1 for `classes.dex`, 2 for `classes2.dex`…).
+pub(crate) fn dex_index(file_name: &str) -> Option<usize> {
+    if !file_name.starts_with("classes") || !file_name.ends_with(".dex") {
+        return None;
+    }
+    let number = &file_name["classes".len()..file_name.len() - ".dex".len()];
+    if number.is_empty() {
+        Some(1)
+    } else {
+        number.parse().ok().filter(|&index| index > 1)
+    }
+}
+
+/// Gets the name of the DEX file with the given index.
+fn dex_name(index: usize) -> String {
+    if index <= 1 {
+        String::from("classes.dex")
+    } else {
+        format!("classes{}.dex", index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsStr, path::PathBuf};
+
+    use super::{
+        base_apk, bundle_order, bundle_target, dex_index, is_package_extension, BundleEntry,
+        PackageFormat,
+    };
+
+    /// Checks the detection of the package formats.
+    #[test]
+    fn it_package_format() {
+        assert_eq!(PackageFormat::of("app.apk"), PackageFormat::Apk);
+        assert_eq!(PackageFormat::of("app"), PackageFormat::Apk);
+        assert_eq!(PackageFormat::of("app.AAB"), PackageFormat::Bundle);
+        assert_eq!(PackageFormat::of("app.apks"), PackageFormat::SplitSet);
+        assert_eq!(PackageFormat::of("app.xapk"), PackageFormat::SplitSet);
+
+        assert!(is_package_extension(OsStr::new("xapk")));
+        assert!(!is_package_extension(OsStr::new("zip")));
+    }
+
+    /// Checks the extraction targets of the bundle entries.
+    #[test]
+    fn it_bundle_target() {
+        assert_eq!(
+            bundle_target("base/manifest/AndroidManifest.xml"),
+            Some(BundleEntry::File(PathBuf::from("AndroidManifest.xml")))
+        );
+        assert_eq!(bundle_target("feature/manifest/AndroidManifest.xml"), None);
+        assert_eq!(
+            bundle_target("feature/dex/classes.dex"),
+            Some(BundleEntry::Dex)
+        );
+        assert_eq!(
+            bundle_target("base/lib/arm64-v8a/libapp.so"),
+            Some(BundleEntry::File(PathBuf::from("lib/arm64-v8a/libapp.so")))
+        );
+        assert_eq!(
+            bundle_target("base/root/kotlin/kotlin.kotlin_builtins"),
+            Some(BundleEntry::File(PathBuf::from(
+                "kotlin/kotlin.kotlin_builtins"
+            )))
+        );
+        assert_eq!(bundle_target("base/resources.pb"), None);
+        assert_eq!(
+            bundle_target("BUNDLE-METADATA/com.android.tools/r8.json"),
+            None
+        );
+        assert_eq!(bundle_target("BundleConfig.pb"), None);
+        assert_eq!(bundle_target("base/root/../../.bashrc"), None);
+        assert_eq!(bundle_target("base/assets/../../../etc/passwd"), None);
+        assert_eq!(bundle_target("base/root//etc/passwd"), None);
+
+        let mut names = vec![
+            "feature/dex/classes.dex",
+            "base/dex/classes10.dex",
+            "base/dex/classes2.dex",
+            "base/dex/classes.dex",
+        ];
+        names.sort_by_key(|name| bundle_order(name));
+        assert_eq!(
+            names,
+            vec![
+                "base/dex/classes.dex",
+                "base/dex/classes2.dex",
+                "base/dex/classes10.dex",
+                "feature/dex/classes.dex",
+            ]
+        );
+    }
+
+    /// Checks the choice of the base APK of split APK sets.
+    #[test]
+    fn it_base_apk() {
+        let bundletool = vec![
+            (String::from("splits/base-arm64_v8a.apk"), 4000),
+            (String::from("splits/base-master.apk"), 1000),
+            (String::from("splits/base-es.apk"), 200),
+        ];
+        assert_eq!(base_apk(&bundletool), Some("splits/base-master.apk"));
+
+        let xapk = vec![
+            (String::from("config.arm64_v8a.apk"), 4000),
+            (String::from("com.example.app.apk"), 1000),
+            (String::from("config.es.apk"), 200),
+        ];
+        assert_eq!(base_apk(&xapk), Some("com.example.app.apk"));
+        assert_eq!(base_apk(&[]), None);
+    }
+
+    /// Checks the indexes of the DEX files.
+    #[test]
+    fn it_dex_index() {
+        assert_eq!(dex_index("classes.dex"), Some(1));
+        assert_eq!(dex_index("classes12.dex"), Some(12));
+        assert_eq!(dex_index("classes1.dex"), None);
+        assert_eq!(dex_index("resources.arsc"), None);
+    }
+}
@@ -0,0 +1,285 @@
+//! Decompilation cache module.
+//!
+//! Converting the DEX files to JAR files and decompiling them are the slowest steps of the
+//! analysis, and their output only depends on the application package and on the decompiler. The
+//! JAR files and the decompiled sources are stored in the cache folder, keyed by the SHA-256 hash
+//! of the package, so that they can be reused by later analyses of the same package, even with
+//! `--force`, that then only redoes the analysis:
+//!
+//! ```text
+//! cache/
+//!     <sha256>/
+//!         last_used
+//!         jars/classes.jar
+//!         jd-cmd/classes/...
+//! ```
+//!
+//! The cache can be disabled with the `decompilation_cache` option of the configuration or with
+//! the `--no-cache` flag, and it can be cleaned with `super clean --cache`, that can also keep the
+//! most recently used entries up to a maximum size.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::{decompilation::Decompiler, print_message, utils::sha256_hex, Config};
+
+/// Name of the file of each cache entry with the time it was last used, in seconds since the
+/// UNIX epoch.
+const LAST_USED_FILE: &str = "last_used";
+
+/// Entry of the decompilation cache for an application package.
+#[derive(Debug)]
+pub(crate) struct CacheEntry {
+    /// Folder of the entry.
+    folder: PathBuf,
+}
+
+impl CacheEntry {
+    /// Gets the cache entry of the given package, or `None` if the cache is disabled.
+    pub(crate) fn of<P: AsRef<Path>>(config: &Config, package: P) -> Result<Option<Self>, Error> {
+        if !config.is_decompilation_cache() {
+            return Ok(None);
+        }
+        let data = fs::read(package.as_ref()).context(format_err!(
+            "could not read the package `{}`",
+            package.as_ref().display()
+        ))?;
+
+        Ok(Some(Self {
+            folder: config.cache_folder().join(sha256_hex(&data)),
+        }))
+    }
+
+    /// Copies the cached JAR files of the given DEX files to the given folder.
+    ///
+    /// Returns `false` if the JAR file of any of the DEX files is not cached.
+    pub(crate) fn restore_jars(&self, jars: &[String], dist_folder: &Path) -> Result<bool, Error> {
+        let cached = self.folder.join("jars");
+        if jars.is_empty() || !jars.iter().all(|jar| cached.join(jar).is_file()) {
+            return Ok(false);
+        }
+        for jar in jars {
+            let _ = fs::copy(cached.join(jar), dist_folder.join(jar))?;
+        }
+        self.touch()?;
+        Ok(true)
+    }
+
+    /// Stores the given JAR files of the given folder in the cache.
+    pub(crate) fn store_jars(&self, jars: &[String], dist_folder: &Path) -> Result<(), Error> {
+        let cached = self.folder.join("jars");
+        fs::create_dir_all(&cached)?;
+        for jar in jars {
+            let _ = fs::copy(dist_folder.join(jar), cached.join(jar))?;
+        }
+        self.touch()
+    }
+
+    /// Copies the cached sources decompiled with the given decompiler to the given folder.
+    ///
+    /// Returns `false` if there are no cached sources for the decompiler.
+    pub(crate) fn restore_sources(
+        &self,
+        decompiler: Decompiler,
+        out_path: &Path,
+    ) -> Result<bool, Error> {
+        let cached = self.sources_folder(decompiler);
+        if !cached.is_dir() {
+            return Ok(false);
+        }
+        copy_folder(&cached, out_path)?;
+        self.touch()?;
+        Ok(true)
+    }
+
+    /// Stores the sources of the given folder, decompiled with the given decompiler, in the
+    /// cache.
+    pub(crate) fn store_sources(
+        &self,
+        decompiler: Decompiler,
+        out_path: &Path,
+    ) -> Result<(), Error> {
+        let cached = self.sources_folder(decompiler);
+        if cached.exists() {
+            fs::remove_dir_all(&cached)?;
+        }
+        copy_folder(out_path, &cached)?;
+        self.touch()
+    }
+
+    /// Gets the folder of the sources decompiled with the given decompiler.
+    fn sources_folder(&self, decompiler: Decompiler) -> PathBuf {
+        self.folder
+            .join(decompiler.to_string().to_lowercase())
+            .join("classes")
+    }
+
+    /// Records that the entry has been used now.
+    fn touch(&self) -> Result<(), Error> {
+        fs::create_dir_all(&self.folder)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        fs::write(self.folder.join(LAST_USED_FILE), now.to_string())?;
+        Ok(())
+    }
+}
+
+/// Copies the given folder, recursively, to the given destination.
+fn copy_folder(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_folder(&entry.path(), &destination)?;
+        } else {
+            let _ = fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Gets the size of the given folder, in bytes, recursively.
+fn folder_size(path: &Path) -> u64 {
+    fs::read_dir(path).map_or(0, |entries| {
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| match entry.metadata() {
+                Ok(ref metadata) if metadata.is_dir() => folder_size(&entry.path()),
+                Ok(metadata) => metadata.len(),
+                Err(_) => 0,
+            })
+            .sum()
+    })
+}
+
+/// Cleans the decompilation cache in the given folder.
+///
+/// If a maximum size, in bytes, is given, only the least recently used entries are removed, until
+/// the cache fits in it. Returns the number of removed entries and the number of freed bytes.
+pub fn clean<P: AsRef<Path>>(
+    cache_folder: P,
+    max_size: Option<u64>,
+) -> Result<(usize, u64), Error> {
+    let cache_folder = cache_folder.as_ref();
+    if !cache_folder.is_dir() {
+        return Ok((0, 0));
+    }
+
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(cache_folder)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let last_used = fs::read_to_string(path.join(LAST_USED_FILE))
+            .ok()
+            .and_then(|last_used| last_used.trim().parse::<u64>().ok())
+            .unwrap_or_default();
+        let size = folder_size(&path);
+        entries.push((last_used, size, path));
+    }
+    entries.sort();
+
+    let mut total_size = entries.iter().map(|&(_, size, _)| size).sum::<u64>();
+    let mut removed = 0;
+    let mut freed = 0;
+    for (_, size, path) in entries {
+        if max_size.map_or(false, |max_size| total_size <= max_size) {
+            break;
+        }
+        fs::remove_dir_all(&path).context(format_err!(
+            "could not remove the cache entry `{}`",
+            path.display()
+        ))?;
+        total_size -= size;
+        freed += size;
+        removed += 1;
+    }
+    Ok((removed, freed))
+}
+
+/// Runs the `clean` subcommand.
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    if !cli.is_present("cache") && !cli.is_present("dist") {
+        bail!("nothing to clean, use `--cache` or `--dist`");
+    }
+
+    if cli.is_present("cache") {
+        let max_size = match cli.value_of("max-size") {
+            Some(max_size) => Some(
+                max_size
+                    .parse::<u64>()
+                    .context("the maximum size of the cache must be a number of MiB")?
+                    * 1024
+                    * 1024,
+            ),
+            None => None,
+        };
+        let (removed, freed) = clean(config.cache_folder(), max_size)?;
+        if !config.is_quiet() {
+            print_message(format!(
+                "{} cache entries were removed, {} MiB were freed.",
+                format!("{}", removed).bold(),
+                format!("{}", freed / 1024 / 1024).bold()
+            ));
+        }
+    }
+
+    if cli.is_present("dist") && config.dist_folder().exists() {
+        fs::remove_dir_all(config.dist_folder()).context(format_err!(
+            "could not remove the dist folder `{}`",
+            config.dist_folder().display()
+        ))?;
+        if !config.is_quiet() {
+            print_message("The decompressed applications were removed.");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{clean, LAST_USED_FILE};
+
+    /// Checks that cleaning the cache with a maximum size removes the least recently used entries.
+    #[test]
+    fn it_clean() {
+        let cache_folder = env::temp_dir().join("super-cache-test");
+        let _ = fs::remove_dir_all(&cache_folder);
+        for (entry, last_used) in &[("old", 10), ("recent", 30), ("middle", 20)] {
+            let folder = cache_folder.join(entry).join("jars");
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join("classes.jar"), vec![0; 1000]).unwrap();
+            fs::write(
+                cache_folder.join(entry).join(LAST_USED_FILE),
+                last_used.to_string(),
+            )
+            .unwrap();
+        }
+
+        let (removed, _) = clean(&cache_folder, Some(2100)).unwrap();
+        assert_eq!(removed, 1);
+        assert!(!cache_folder.join("old").exists());
+        assert!(cache_folder.join("middle").exists());
+        assert!(cache_folder.join("recent").exists());
+
+        let (removed, _) = clean(&cache_folder, None).unwrap();
+        assert_eq!(removed, 2);
+        assert!(!cache_folder.join("recent").exists());
+
+        fs::remove_dir_all(&cache_folder).unwrap();
+    }
+}
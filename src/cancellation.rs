@@ -0,0 +1,56 @@
+//! Cancellation module.
+//!
+//! An analysis can be cancelled with Ctrl-C in the CLI, or by a host application calling
+//! [`cancel()`] from another thread. The analysis does not stop abruptly: the external tools, such
+//! as dex2jar or the decompilers, are killed, the code analysis threads stop after the file they
+//! are analyzing, and the phases that did not run are skipped. The interrupted phase is recorded
+//! with the `cancelled` code, not as a failure of its tool. A partial report is then written, with
+//! an `analysis` stage that failed because the analysis was cancelled, so that it is marked as
+//! incomplete.
+//!
+//! Pressing Ctrl-C a second time exits immediately.
+
+use std::{
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use failure::Error;
+
+use crate::print_warning;
+
+/// Whether the analysis has been cancelled.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code when the analysis is interrupted twice with Ctrl-C.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Cancels the running analysis.
+pub fn cancel() {
+    CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Checks if the analysis has been cancelled.
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Clears the cancellation, so that new analyses can run.
+pub fn reset() {
+    CANCELLED.store(false, Ordering::SeqCst);
+}
+
+/// Cancels the analysis when Ctrl-C is pressed, and exits if it is pressed again.
+pub fn handle_ctrl_c() -> Result<(), Error> {
+    ctrlc::set_handler(|| {
+        if is_cancelled() {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+        print_warning(
+            "cancelling the analysis, a partial report will be written. Press Ctrl-C again to \
+             exit immediately",
+        );
+        cancel();
+    })?;
+    Ok(())
+}
@@ -4,20 +4,42 @@
 //! for the SUPER launcher. It's also used to generate command line completion scripts in the
 //! `build.rs` file.
 
-use clap::{crate_version, App, Arg};
+use clap::{crate_version, App, AppSettings, Arg, SubCommand};
 
 /// Generates the command line interface.
 pub fn generate() -> App<'static, 'static> {
     App::new("SUPER Android Analyzer")
         .version(crate_version!())
         .author("SUPER Team <contact@superanalyzer.rocks>")
-        .about("Audits Android apps (.apk files) for vulnerabilities")
+        .about("Audits Android apps (.apk, .aab, .apks and .xapk files) for vulnerabilities")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("package")
-                .help("The package string of the application to test")
+                .help(
+                    "The package string of the application to test, or - to read the \
+                     application from the standard input",
+                )
                 .value_name("package")
-                .required_unless("test-all")
-                .conflicts_with("test-all")
+                .required_unless_one(&[
+                    "test-all",
+                    "input-list",
+                    "watch",
+                    "list-rules",
+                    "explain",
+                    "adb",
+                ])
+                .conflicts_with_all(&["test-all", "adb"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("package-name")
+                .long("package-name")
+                .value_name("NAME")
+                .requires("package")
+                .help(
+                    "Name of the application read from the standard input, used for its \
+                     analysis folders and reports",
+                )
                 .takes_value(true),
         )
         .arg(
@@ -26,7 +48,29 @@ pub fn generate() -> App<'static, 'static> {
                 .long("test-all")
                 .conflicts_with("package")
                 .conflicts_with("open")
-                .help("Test all .apk files in the downloads directory"),
+                .help("Test all .apk, .aab, .apks and .xapk files in the downloads directory"),
+        )
+        .arg(
+            Arg::with_name("input-list")
+                .long("input-list")
+                .value_name("FILE")
+                .conflicts_with_all(&["package", "test-all", "open"])
+                .help(
+                    "Tests the applications listed in the given file, one path or URL per line, \
+                     optionally followed by options for the application",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .value_name("DIR")
+                .conflicts_with_all(&["package", "test-all", "input-list", "open"])
+                .help(
+                    "Watches the given folder and analyzes the new applications that appear in \
+                     it, updating a summary index in the results folder",
+                )
+                .takes_value(true),
         )
         .arg(
             Arg::with_name("verbose")
@@ -52,6 +96,22 @@ pub fn generate() -> App<'static, 'static> {
                 .conflicts_with("verbose")
                 .help("If you'd like a zen auditor that won't output anything in stdout"),
         )
+        .arg(
+            Arg::with_name("output-mode")
+                .long("output-mode")
+                .help("Where the messages for the user are sent")
+                .possible_values(&["console", "log", "silent"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .help("When to use colors in the output")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("open")
                 .long("open")
@@ -68,12 +128,73 @@ pub fn generate() -> App<'static, 'static> {
                 .long("html")
                 .help("Generates the results in HTML format"),
         )
+        .arg(
+            Arg::with_name("defectdojo")
+                .long("defectdojo")
+                .help("Generates the results in DefectDojo's generic findings format"),
+        )
+        .arg(
+            Arg::with_name("sarif")
+                .long("sarif")
+                .help("Generates the results in SARIF 2.1.0 format"),
+        )
+        .arg(
+            Arg::with_name("sbom")
+                .long("sbom")
+                .help("Generates a CycloneDX SBOM with the libraries bundled in the application"),
+        )
+        .arg(
+            Arg::with_name("output-format")
+                .long("output-format")
+                .value_name("FORMAT")
+                .help("Generates the results in the given formats, separated by commas")
+                .possible_values(&[
+                    "html",
+                    "json",
+                    "defectdojo",
+                    "sarif",
+                    "sbom",
+                    "csv",
+                    "markdown",
+                ])
+                .multiple(true)
+                .require_delimiter(true)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("min_criticality")
                 .long("min-criticality")
                 .help("Set a minimum criticality to analyze (Critical, High, Medium, Low)")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("fail-on")
+                .long("fail-on")
+                .value_name("CRITICALITY")
+                .help("Exits with code 2 if findings of the given criticality or higher are found")
+                .possible_values(&["critical", "high", "medium", "low", "warning"])
+                .case_insensitive(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("keep-going")
+                .long("keep-going")
+                .help(
+                    "Writes a partial report if a phase of the analysis fails, and analyzes the \
+                     rest of applications if the analysis of one fails",
+                ),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .help(
+                    "Analysis profile: `quick` skips the decompilation and only analyzes the \
+                     manifest and the resources, `deep` also runs the online checks and never \
+                     disables the slow rules",
+                )
+                .possible_values(&["quick", "standard", "deep"])
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("threads")
                 .short("t")
@@ -114,6 +235,75 @@ pub fn generate() -> App<'static, 'static> {
                 .help("Path to the jd-cmd file")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("jadx")
+                .long("jadx")
+                .help("Path to the jadx binary")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("cfr")
+                .long("cfr")
+                .help("Path to the CFR JAR file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("procyon")
+                .long("procyon")
+                .help("Path to the Procyon JAR file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tools")
+                .long("tools")
+                .value_name("DIR")
+                .help("Folder where the `setup` subcommand installs the tools")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("decompiler")
+                .long("decompiler")
+                .help("Decompiler to use first, the rest are used if it fails")
+                .possible_values(&["jd-cmd", "jadx", "cfr", "procyon"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("adb")
+                .long("adb")
+                .value_name("PACKAGE_ID")
+                .help(
+                    "Pulls the installed application with the given package ID from the device \
+                     connected with adb and analyzes it",
+                )
+                .conflicts_with_all(&["test-all", "input-list"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("download")
+                .long("download")
+                .help(
+                    "Downloads the application with the given package ID from the configured \
+                     source before the analysis",
+                )
+                .requires("package")
+                .conflicts_with_all(&["test-all", "input-list"]),
+        )
+        .arg(
+            Arg::with_name("download-source")
+                .long("download-source")
+                .help("Source of the applications downloaded with --download")
+                .possible_values(&["url", "fdroid", "apkpure"])
+                .requires("download")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("sha256")
+                .long("sha256")
+                .value_name("CHECKSUM")
+                .help("Expected SHA-256 checksum of the application downloaded with --download")
+                .requires("download")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("template")
                 .long("template")
@@ -123,7 +313,358 @@ pub fn generate() -> App<'static, 'static> {
         .arg(
             Arg::with_name("rules")
                 .long("rules")
-                .help("Path to a JSON rules file")
+                .help(
+                    "Path to a rules file (JSON, YAML or TOML) or a folder of rule packs. It can \
+                     be repeated, and later rules override earlier rules with the same ID",
+                )
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("require-signed-rules")
+                .long("require-signed-rules")
+                .help("Refuses to load rules that are not signed by a trusted key"),
+        )
+        .arg(
+            Arg::with_name("no-cache")
+                .long("no-cache")
+                .help("Does not reuse nor store the JAR files and decompiled sources in the cache"),
+        )
+        .arg(
+            Arg::with_name("disable-slow-rules")
+                .long("disable-slow-rules")
+                .help("Stops checking the code rules that exceed their time budget"),
+        )
+        .arg(
+            Arg::with_name("online-checks")
+                .long("online-checks")
+                .help(
+                    "Checks without credentials whether the Firebase databases and buckets of the \
+                     application are open (requires the `integrations` feature)",
+                ),
+        )
+        .arg(
+            Arg::with_name("baseline")
+                .long("baseline")
+                .value_name("FILE")
+                .help(
+                    "Suppresses the known findings in the given baseline file (super.baseline.json \
+                     by default)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("update-baseline")
+                .long("update-baseline")
+                .help("Replaces the baseline file with the findings of the analysis"),
+        )
+        .arg(
+            Arg::with_name("metrics-file")
+                .long("metrics-file")
+                .value_name("FILE")
+                .help("Writes Prometheus metrics of the analyses to the given file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("stats-json")
+                .long("stats-json")
+                .value_name("FILE")
+                .help(
+                    "Writes statistics of the run as JSON to the given file, or to the standard \
+                     output if it is -",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("history-db")
+                .long("history-db")
+                .value_name("FILE")
+                .help(
+                    "Records each analysis in the given SQLite database, to show trends over time \
+                     (requires the `history` feature)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("import-mobsf")
+                .long("import-mobsf")
+                .value_name("FILE")
+                .help("Imports the findings of a MobSF JSON report into the results")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("import-androbugs")
+                .long("import-androbugs")
+                .value_name("FILE")
+                .help("Imports the findings of an AndroBugs text report into the results")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("list-rules")
+                .long("list-rules")
+                .conflicts_with_all(&["package", "test-all", "input-list", "explain"])
+                .help(
+                    "Lists the loaded rules with their criticality, target files and required \
+                     permissions",
+                ),
+        )
+        .arg(
+            Arg::with_name("explain")
+                .long("explain")
+                .value_name("RULE_ID")
+                .conflicts_with_all(&["package", "test-all", "input-list"])
+                .help("Explains in detail the loaded rule with the given ID")
                 .takes_value(true),
         )
+        .subcommand(
+            SubCommand::with_name("rules")
+                .about("Works with the loaded rule set")
+                .subcommand(
+                    SubCommand::with_name("doc")
+                        .about("Generates the reference documentation of the rules")
+                        .arg(
+                            Arg::with_name("format")
+                                .long("format")
+                                .help("Format of the documentation")
+                                .possible_values(&["markdown", "html"])
+                                .default_value("markdown")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .short("o")
+                                .long("output")
+                                .value_name("FILE")
+                                .help("File where the documentation will be written")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .about(
+                            "Downloads, verifies and installs the signed rules bundle (requires \
+                             the `integrations` feature)",
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .long("url")
+                                .value_name("URL")
+                                .help("URL of the rules bundle, instead of `rules_update_url`")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .short("o")
+                                .long("output")
+                                .value_name("FILE")
+                                .help(
+                                    "File where the rules will be installed, instead of the first \
+                                     rules path",
+                                )
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("proxy")
+                                .long("proxy")
+                                .value_name("URL")
+                                .help("Proxy to use, instead of the one in the environment")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("allow-unsigned")
+                                .long("allow-unsigned")
+                                .help(
+                                    "Installs the rules without a trusted signature, verifying \
+                                     only their checksum",
+                                ),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("test-rules")
+                .about("Checks the rules against their own examples and counter-examples")
+                .arg(
+                    Arg::with_name("rule")
+                        .long("rule")
+                        .value_name("RULE_ID")
+                        .help("Only tests the rule with the given ID")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("self-update")
+                .about("Updates SUPER to the latest release (requires the `self-update` feature)")
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .help("Only checks if there is a new version, without installing it"),
+                )
+                .arg(
+                    Arg::with_name("proxy")
+                        .long("proxy")
+                        .value_name("URL")
+                        .help("Proxy to use, instead of the one in the environment")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("allow-unsigned")
+                        .long("allow-unsigned")
+                        .help(
+                            "Installs the new version without a trusted signature, verifying \
+                             only its checksum",
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("setup")
+                .about(
+                    "Downloads and installs pinned versions of the tools needed for the analysis \
+                     (requires the `setup` feature)",
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Installs the tools even if they are already available"),
+                )
+                .arg(
+                    Arg::with_name("proxy")
+                        .long("proxy")
+                        .value_name("URL")
+                        .help("Proxy to use, instead of the one in the environment")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("allow-unpinned")
+                        .long("allow-unpinned")
+                        .help("Installs the tools without a pinned checksum"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("clean")
+                .about("Cleans the decompilation cache or the decompressed applications")
+                .arg(
+                    Arg::with_name("cache")
+                        .long("cache")
+                        .help("Removes the cached JAR files and decompiled sources"),
+                )
+                .arg(
+                    Arg::with_name("max-size")
+                        .long("max-size")
+                        .value_name("MiB")
+                        .requires("cache")
+                        .help(
+                            "Only removes the least recently used entries of the cache, until it \
+                             takes at most the given size",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("dist")
+                        .long("dist")
+                        .help("Removes the decompressed applications of the dist folder"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about(
+                    "Analyzes two versions of an application and reports the new, fixed and \
+                     unchanged vulnerabilities, and the permission and SDK changes",
+                )
+                .arg(
+                    Arg::with_name("old")
+                        .help("The package of the old version of the application")
+                        .value_name("OLD")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("new")
+                        .help("The package of the new version of the application")
+                        .value_name("NEW")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .value_name("FILE")
+                        .help("Writes the differences in JSON format to the given file")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about(
+                    "Shows the trend of the recorded analyses of a package (requires the \
+                     `history` feature)",
+                )
+                .arg(
+                    Arg::with_name("package")
+                        .help("The package of the application, such as com.example.app")
+                        .value_name("PACKAGE")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("N")
+                        .help("Number of analyses to show, 20 by default")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Runs SUPER as a service with a REST API (requires the `server` feature)")
+                .arg(
+                    Arg::with_name("address")
+                        .long("address")
+                        .value_name("ADDRESS")
+                        .help("Address where the server listens")
+                        .default_value("127.0.0.1:8080")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Works with the configuration")
+                .subcommand(
+                    SubCommand::with_name("check")
+                        .about("Checks the configuration, and prints where each error comes from"),
+                )
+                .subcommand(
+                    SubCommand::with_name("schema")
+                        .about("Prints the JSON Schema of the configuration file")
+                        .arg(
+                            Arg::with_name("output")
+                                .short("o")
+                                .long("output")
+                                .value_name("FILE")
+                                .help("File where the schema will be written")
+                                .takes_value(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about(
+                    "Verifies that a JSON report corresponds to the given application and to the \
+                     current rules and configuration",
+                )
+                .arg(
+                    Arg::with_name("package")
+                        .help("The application package (APK) that was analyzed")
+                        .value_name("APK")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("report")
+                        .help("The `results.json` report of the analysis")
+                        .value_name("REPORT")
+                        .required(true),
+                ),
+        )
 }
@@ -4,9 +4,10 @@
 
 use std::{
     cmp::{Ordering, PartialOrd},
-    collections::{btree_set::Iter, BTreeSet},
+    collections::{btree_set::Iter, BTreeMap, BTreeSet},
     convert::From,
-    fs, i64,
+    env, fmt, fs, i64,
+    io::{self, Read, Write},
     path::{Path, PathBuf},
     slice::Iter as VecIter,
     str::FromStr,
@@ -15,12 +16,92 @@ use std::{
 
 use clap::ArgMatches;
 use colored::Colorize;
-use failure::{format_err, Error, ResultExt};
+use failure::{bail, format_err, Error, ResultExt};
 use num_cpus;
 use serde::{de, Deserialize, Deserializer};
+use tempfile::Builder;
 use toml::{self, value::Value};
 
-use crate::{criticality::Criticality, print_warning, static_analysis::manifest};
+use crate::{
+    adb,
+    bundle::is_package_extension,
+    correlation::CorrelationRule,
+    criticality::Criticality,
+    decompilation::Decompiler,
+    download::{self, DownloadConfig, DownloadSource},
+    external::ExternalAnalyzer,
+    get_package_name,
+    input_list::{self, PackageOptions},
+    limits::ResourceLimits,
+    print_warning,
+    results::{DefectDojoApi, DependencyTrackConfig, ImportFormat, JiraConfig, RiskWeights},
+    setup::{self, ToolKind},
+    static_analysis::{dependencies, manifest, resilience},
+    utils::{is_valid_package_name, sha256_hex, OutputMode},
+};
+
+/// Package argument used to read the application from the standard input.
+const STDIN_PACKAGE: &str = "-";
+
+/// Prefix of the environment variables that override the options of the configuration file.
+pub(crate) const ENV_PREFIX: &str = "SUPER_";
+
+/// Analysis profile, that bundles the phases that run and how deep they go.
+///
+/// The profiles are built in, and they cannot be defined in the configuration file. They only
+/// change the options that they document, so the rest of the analysis, such as the data flow of
+/// the code rules or the analysis of the native libraries, is the same with `standard` and `deep`.
+/// Other combinations can be set with the individual options, such as `online_checks` and
+/// `disable_slow_rules`, with the `standard` profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// Only the manifest and the resources are analyzed: the application is not decompiled, and
+    /// neither its code nor its native libraries are analyzed.
+    Quick,
+    /// The default analysis, with every phase, configured by the individual options.
+    Standard,
+    /// The standard analysis, but the online checks always run, as if `online_checks` was set,
+    /// and the slow rules are never disabled, even if `disable_slow_rules` is set. It does not
+    /// enable any other analysis.
+    Deep,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile::Standard
+    }
+}
+
+impl FromStr for Profile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "quick" => Ok(Profile::Quick),
+            "standard" => Ok(Profile::Standard),
+            "deep" => Ok(Profile::Deep),
+            _ => Err(format_err!(
+                "unknown profile `{}`, it must be `quick`, `standard` or `deep`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Profile::Quick => "quick",
+                Profile::Standard => "standard",
+                Profile::Deep => "deep",
+            }
+        )
+    }
+}
 
 /// Config structure.
 ///
@@ -31,10 +112,24 @@ use crate::{criticality::Criticality, print_warning, static_analysis::manifest};
 pub struct Config {
     /// Application packages to analyze.
     app_packages: Vec<PathBuf>,
+    /// Options for the analysis of specific packages, from the input list.
+    #[serde(skip)]
+    package_options: BTreeMap<PathBuf, PackageOptions>,
+    /// Options for the analysis of the current package.
+    #[serde(skip)]
+    current_options: PackageOptions,
+    /// Names of the packages that cannot be named after their path.
+    #[serde(skip)]
+    package_names: BTreeMap<PathBuf, String>,
+    /// Temporary package files, removed after the analysis.
+    #[serde(skip)]
+    temporary_packages: Vec<PathBuf>,
     /// Boolean to represent `--verbose` mode.
     verbose: bool,
     /// Boolean to represent `--quiet` mode.
     quiet: bool,
+    /// Destination of the messages for the user.
+    output_mode: OutputMode,
     /// Boolean to represent overall `--force` mode.
     overall_force: bool,
     /// Boolean to represent current `--force` mode.
@@ -47,8 +142,40 @@ pub struct Config {
     json: bool,
     /// Boolean to represent `--html` mode.
     html: bool,
+    /// Boolean to represent `--defectdojo` mode.
+    defectdojo: bool,
+    /// Boolean to represent `--sarif` mode.
+    sarif: bool,
+    /// Boolean to represent `--sbom` mode.
+    sbom: bool,
+    /// Boolean to represent `--output-format csv` mode.
+    csv: bool,
+    /// Boolean to represent `--output-format markdown` mode.
+    markdown: bool,
     /// Minimum criticality to analyze
     min_criticality: Criticality,
+    /// Criticality of the findings that make the analysis fail, if any.
+    fail_on: Option<Criticality>,
+    /// Whether the analysis goes on when a phase fails, and the rest of packages are analyzed
+    /// when the analysis of a package fails.
+    keep_going: bool,
+    /// Analysis profile.
+    profile: Profile,
+    /// Whether the logging and `StrictMode` hygiene findings are only reported for applications
+    /// that are not debuggable.
+    hygiene_release_only: bool,
+    /// Categories of applications whose missing resilience checks are reported.
+    resilience_categories: BTreeSet<resilience::AppCategory>,
+    /// Extensions of the files analyzed with the code rules.
+    code_extensions: Vec<String>,
+    /// Folders of the decompiled applications, relative to their folder, that are not analyzed.
+    ignored_folders: Vec<PathBuf>,
+    /// Maximum size of the analyzed files, in KiB, if limited.
+    max_file_size: Option<u64>,
+    /// Maximum time each code rule can take in the analysis of an application, in seconds.
+    rule_time_budget: u64,
+    /// Whether the code rules that exceed their time budget stop being checked.
+    disable_slow_rules: bool,
     /// Number of threads.
     #[serde(deserialize_with = "ConfigDeserializer::deserialize_threads")]
     threads: usize,
@@ -56,23 +183,100 @@ pub struct Config {
     downloads_folder: PathBuf,
     /// Folder with files from analyzed applications.
     dist_folder: PathBuf,
+    /// Folder where the JAR files and the decompiled sources are cached.
+    cache_folder: PathBuf,
+    /// Whether the JAR files and the decompiled sources are cached across analyses.
+    decompilation_cache: bool,
     /// Folder to store the results of analysis.
     results_folder: PathBuf,
     /// Path to the _Dex2jar_ binaries.
     dex2jar_folder: PathBuf,
     /// Path to the _JD\_CMD_ binary.
     jd_cmd_file: PathBuf,
-    /// Path to the `rules.json` file.
-    rules_json: PathBuf,
+    /// Path to the _jadx_ binary.
+    jadx_file: PathBuf,
+    /// Path to the _CFR_ JAR file.
+    cfr_file: PathBuf,
+    /// Path to the _Procyon_ JAR file.
+    procyon_file: PathBuf,
+    /// Decompiler to use first.
+    decompiler: Decompiler,
+    /// Folder where the `setup` subcommand installs the tools.
+    tools_folder: PathBuf,
+    /// Path to the _adb_ binary, used to pull installed applications with `--adb`.
+    adb_file: PathBuf,
+    /// Paths to the rules files or folders of rule packs, loaded in order, so that the rules of
+    /// the later ones override the rules of the earlier ones with the same identifier.
+    #[serde(deserialize_with = "ConfigDeserializer::deserialize_rules_json")]
+    rules_json: Vec<PathBuf>,
+    /// Public keys trusted to sign the rules, in minisign format.
+    trusted_keys: Vec<String>,
+    /// Public keys trusted to sign the releases of SUPER installed by `self-update`, in minisign
+    /// format.
+    update_keys: Vec<String>,
+    /// Whether unsigned rules should be refused.
+    require_signed_rules: bool,
+    /// URL of the signed rules bundle installed by `rules update`, if any.
+    rules_update_url: Option<String>,
+    /// Whether the Firebase databases and buckets are checked online for open access.
+    online_checks: bool,
+    /// Folder where the WebAssembly analysis plugins are stored.
+    plugins_folder: PathBuf,
+    /// Maximum number of WebAssembly instructions of each call to a plugin, and of operations of
+    /// each call to a script hook.
+    plugin_fuel: u64,
+    /// Folder where the Rhai analysis scripts are stored.
+    scripts_folder: PathBuf,
+    /// External analyzers to run for each application.
+    external_analyzers: Vec<ExternalAnalyzer>,
+    /// Correlation rules, that combine the findings of the analysis.
+    correlations: Vec<CorrelationRule>,
+    /// File with the baseline of known findings, suppressed in the results.
+    baseline: Option<PathBuf>,
+    /// Whether the baseline has to be replaced with the findings of the analysis.
+    #[serde(skip)]
+    update_baseline: bool,
+    /// File where the Prometheus metrics will be written.
+    metrics_file: Option<PathBuf>,
+    /// File where the run statistics will be written, `-` for the standard output.
+    stats_json: Option<PathBuf>,
+    /// SQLite database where the history of the analyses is recorded.
+    history_db: Option<PathBuf>,
+    /// Resource limits for the decompilation tools.
+    limits: ResourceLimits,
+    /// Sources of the applications downloaded with `--download`.
+    download: DownloadConfig,
+    /// Weights of the risk factors of the overall risk grade.
+    risk: RiskWeights,
+    /// Reports from other scanners to import into the results.
+    #[serde(skip)]
+    imported_reports: Vec<(ImportFormat, PathBuf)>,
+    /// DefectDojo API configuration, used to upload the DefectDojo reports.
+    defectdojo_api: Option<DefectDojoApi>,
+    /// Jira configuration, used to create issues for the vulnerabilities.
+    jira: Option<JiraConfig>,
+    /// Dependency-Track configuration, used to upload the SBOMs.
+    dependency_track: Option<DependencyTrackConfig>,
     /// The folder where the templates are stored.
     templates_folder: PathBuf,
     /// The name of the template to use.
     template: String,
+    /// Language of the messages, detected from the environment if not set.
+    locale: Option<String>,
     /// Represents an unknown permission.
     #[serde(deserialize_with = "ConfigDeserializer::deserialize_unknown_permission")]
     unknown_permission: (Criticality, String),
     /// List of permissions to analyze.
     permissions: BTreeSet<Permission>,
+    /// Catalog of the known permissions, the built-in one extended with the configured file.
+    #[serde(deserialize_with = "ConfigDeserializer::deserialize_permission_catalog")]
+    permission_catalog: manifest::PermissionCatalog,
+    /// Known vulnerabilities of the bundled libraries, the built-in dataset extended with the
+    /// configured file.
+    #[serde(deserialize_with = "ConfigDeserializer::deserialize_library_vulnerabilities")]
+    library_vulnerabilities: dependencies::VulnerabilityDatabase,
+    /// Risky combinations of permissions.
+    permission_combinations: Vec<manifest::PermissionCombination>,
     /// Checker for the loaded files
     loaded_files: Vec<PathBuf>,
 }
@@ -116,6 +320,33 @@ impl ConfigDeserializer {
         }
     }
 
+    /// Deserialize `rules_json` field, a path or a list of paths
+    pub fn deserialize_rules_json<'de, D>(de: D) -> Result<Vec<PathBuf>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserialize_result: Value = Deserialize::deserialize(de)?;
+
+        #[allow(clippy::use_debug)]
+        match deserialize_result {
+            Value::String(path) => Ok(vec![PathBuf::from(path)]),
+            Value::Array(paths) => paths
+                .into_iter()
+                .map(|path| match path {
+                    Value::String(path) => Ok(PathBuf::from(path)),
+                    _ => Err(de::Error::custom(format!(
+                        "unexpected rules path: {:?}",
+                        path
+                    ))),
+                })
+                .collect(),
+            _ => Err(de::Error::custom(format!(
+                "unexpected value: {:?}",
+                deserialize_result
+            ))),
+        }
+    }
+
     /// Deserialize `unknown_permission` field
     pub fn deserialize_unknown_permission<'de, D>(de: D) -> Result<CriticalityString, D::Error>
     where
@@ -154,6 +385,37 @@ impl ConfigDeserializer {
             ))),
         }
     }
+
+    /// Deserialize `permission_catalog` field, loading the catalog file and adding it to the
+    /// built-in catalog
+    pub fn deserialize_permission_catalog<'de, D>(
+        de: D,
+    ) -> Result<manifest::PermissionCatalog, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path: PathBuf = Deserialize::deserialize(de)?;
+        let mut catalog = manifest::PermissionCatalog::builtin().clone();
+        catalog.extend(manifest::PermissionCatalog::load(&path).map_err(de::Error::custom)?);
+
+        Ok(catalog)
+    }
+
+    /// Deserialize `library_vulnerabilities` field, loading the dataset file and adding it to the
+    /// built-in dataset
+    pub fn deserialize_library_vulnerabilities<'de, D>(
+        de: D,
+    ) -> Result<dependencies::VulnerabilityDatabase, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path: PathBuf = Deserialize::deserialize(de)?;
+        let custom = dependencies::VulnerabilityDatabase::load(&path).map_err(de::Error::custom)?;
+        let mut database = dependencies::VulnerabilityDatabase::builtin().clone();
+        database.extend(custom);
+
+        Ok(database)
+    }
 }
 
 impl Config {
@@ -180,7 +442,12 @@ impl Config {
     }
 
     /// Decorates the loaded config with the given flags from CLI
+    ///
+    /// The `SUPER_*` environment variables are applied first, so that they override the options
+    /// of the configuration file, but not the flags.
     pub fn decorate_with_cli(&mut self, cli: &ArgMatches<'static>) -> Result<(), Error> {
+        self.set_env_options(|name| env::var(name).ok())
+            .context("invalid environment variable")?;
         self.set_options(cli);
 
         self.verbose = cli.is_present("verbose");
@@ -191,15 +458,112 @@ impl Config {
         self.open = cli.is_present("open");
         self.json = cli.is_present("json");
         self.html = cli.is_present("html");
+        self.defectdojo = cli.is_present("defectdojo");
+        self.sarif = cli.is_present("sarif");
+        self.sbom = cli.is_present("sbom");
+        for format in cli.values_of("output-format").into_iter().flatten() {
+            match format {
+                "html" => self.html = true,
+                "json" => self.json = true,
+                "defectdojo" => self.defectdojo = true,
+                "sarif" => self.sarif = true,
+                "sbom" => self.sbom = true,
+                "csv" => self.csv = true,
+                "markdown" => self.markdown = true,
+                _ => unreachable!("unknown output format `{}`", format),
+            }
+        }
 
         if cli.is_present("test-all") {
             self.read_apks()
                 .context("error loading all the downloaded APKs")?;
-        } else {
-            self.add_app_package(
-                cli.value_of("package")
-                    .expect("expected a value for the package CLI attribute"),
-            );
+        } else if let Some(input_list) = cli.value_of("input-list") {
+            self.read_input_list(input_list)
+                .context("error loading the applications of the input list")?;
+        } else if cli.value_of("package") == Some(STDIN_PACKAGE) {
+            self.read_stdin_package(cli.value_of("package-name"))
+                .context("error reading the application from the standard input")?;
+        } else if let Some(package) = cli.value_of("adb") {
+            let package = adb::pull(&self.adb_file, package, &self.downloads_folder)
+                .context(format_err!("error pulling {} from the device", package))?;
+            self.app_packages.push(package);
+        } else if let Some(package) = cli.value_of("package") {
+            if cli.is_present("download") {
+                self.download_package(package, cli.value_of("sha256"))
+                    .context(format_err!("error downloading {}", package))?;
+            }
+            self.add_app_package(package);
+        }
+
+        Ok(())
+    }
+
+    /// Modifies the options from the `SUPER_*` environment variables, read with the given
+    /// function.
+    ///
+    /// Each variable is named after the option of the configuration file it overrides, in upper
+    /// case, such as `SUPER_RESULTS_FOLDER` for `results_folder`.
+    fn set_env_options<F>(&mut self, var: F) -> Result<(), Error>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |option: &str| {
+            let name = format!("{}{}", ENV_PREFIX, option.to_uppercase());
+            var(&name).map(|value| (name, value))
+        };
+
+        if let Some((name, value)) = var("output_mode") {
+            self.output_mode = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("min_criticality") {
+            self.min_criticality = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("fail_on") {
+            self.fail_on = Some(parse_env(&name, &value)?);
+        }
+        if let Some((name, value)) = var("keep_going") {
+            self.keep_going = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("profile") {
+            self.profile = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("threads") {
+            match parse_env(&name, &value)? {
+                0 => bail!("`{}` must be an integer greater than 0", name),
+                threads => self.threads = threads,
+            }
+        }
+        if let Some((name, value)) = var("max_file_size") {
+            self.max_file_size = Some(parse_env(&name, &value)?);
+        }
+        if let Some((name, value)) = var("decompiler") {
+            self.decompiler = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("decompilation_cache") {
+            self.decompilation_cache = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("require_signed_rules") {
+            self.require_signed_rules = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = var("online_checks") {
+            self.online_checks = parse_env(&name, &value)?;
+        }
+        if let Some((_, template)) = var("template") {
+            self.template = template;
+        }
+        for (option, path) in vec![
+            ("downloads_folder", &mut self.downloads_folder),
+            ("dist_folder", &mut self.dist_folder),
+            ("cache_folder", &mut self.cache_folder),
+            ("results_folder", &mut self.results_folder),
+            ("tools_folder", &mut self.tools_folder),
+        ] {
+            if let Some((_, value)) = var(option) {
+                *path = PathBuf::from(value);
+            }
+        }
+        if let Some((_, rules_json)) = var("rules_json") {
+            self.rules_json = env::split_paths(&rules_json).collect();
         }
 
         Ok(())
@@ -222,6 +586,16 @@ impl Config {
                 ));
             }
         }
+        if let Some(fail_on) = cli.value_of("fail-on") {
+            // The criticality is validated by the CLI, so invalid values never get here.
+            self.fail_on = Some(fail_on.parse().expect("invalid fail-on criticality"));
+        }
+        if let Some(output_mode) = cli.value_of("output-mode") {
+            match output_mode.parse() {
+                Ok(output_mode) => self.output_mode = output_mode,
+                Err(e) => print_warning(format!("{}. Using the console.", e)),
+            }
+        }
         if let Some(threads) = cli.value_of("threads") {
             match threads.parse() {
                 Ok(t) if t > 0_usize => {
@@ -250,11 +624,82 @@ impl Config {
         if let Some(jd_cmd_file) = cli.value_of("jd-cmd") {
             self.jd_cmd_file = PathBuf::from(jd_cmd_file);
         }
+        if let Some(jadx_file) = cli.value_of("jadx") {
+            self.jadx_file = PathBuf::from(jadx_file);
+        }
+        if let Some(cfr_file) = cli.value_of("cfr") {
+            self.cfr_file = PathBuf::from(cfr_file);
+        }
+        if let Some(procyon_file) = cli.value_of("procyon") {
+            self.procyon_file = PathBuf::from(procyon_file);
+        }
+        if let Some(tools_folder) = cli.value_of("tools") {
+            self.tools_folder = PathBuf::from(tools_folder);
+        }
+        if let Some(decompiler) = cli.value_of("decompiler") {
+            match decompiler.parse() {
+                Ok(decompiler) => self.decompiler = decompiler,
+                Err(e) => print_warning(format!("{}. Using {}.", e, self.decompiler)),
+            }
+        }
+        if let Some(source) = cli.value_of("download-source") {
+            self.download.set_source(match source {
+                "url" => DownloadSource::Url,
+                "fdroid" => DownloadSource::FDroid,
+                "apkpure" => DownloadSource::ApkPure,
+                _ => unreachable!("unknown download source `{}`", source),
+            });
+        }
         if let Some(template_name) = cli.value_of("template") {
             self.template = template_name.to_owned();
         }
-        if let Some(rules_json) = cli.value_of("rules") {
-            self.rules_json = PathBuf::from(rules_json);
+        if let Some(rules_json) = cli.values_of("rules") {
+            self.rules_json = rules_json.map(PathBuf::from).collect();
+        }
+        if cli.is_present("require-signed-rules") {
+            self.require_signed_rules = true;
+        }
+        if cli.is_present("online-checks") {
+            self.online_checks = true;
+        }
+        if cli.is_present("keep-going") {
+            self.keep_going = true;
+        }
+        if let Some(profile) = cli.value_of("profile") {
+            match profile.parse() {
+                Ok(profile) => self.profile = profile,
+                Err(e) => print_warning(format!("{}. Using {}.", e, self.profile)),
+            }
+        }
+        if cli.is_present("no-cache") {
+            self.decompilation_cache = false;
+        }
+        if cli.is_present("disable-slow-rules") {
+            self.disable_slow_rules = true;
+        }
+        if let Some(baseline) = cli.value_of("baseline") {
+            self.baseline = Some(PathBuf::from(baseline));
+        }
+        if cli.is_present("update-baseline") {
+            self.update_baseline = true;
+        }
+        if let Some(metrics_file) = cli.value_of("metrics-file") {
+            self.metrics_file = Some(PathBuf::from(metrics_file));
+        }
+        if let Some(stats_json) = cli.value_of("stats-json") {
+            self.stats_json = Some(PathBuf::from(stats_json));
+        }
+        if let Some(history_db) = cli.value_of("history-db") {
+            self.history_db = Some(PathBuf::from(history_db));
+        }
+        for &(arg, format) in &[
+            ("import-mobsf", ImportFormat::MobSF),
+            ("import-androbugs", ImportFormat::AndroBugs),
+        ] {
+            if let Some(reports) = cli.values_of(arg) {
+                self.imported_reports
+                    .extend(reports.map(|report| (format, PathBuf::from(report))));
+            }
         }
     }
 
@@ -275,6 +720,8 @@ impl Config {
                                     .to_string_lossy()
                                     .into_owned(),
                             )
+                        } else if is_package_extension(ext) {
+                            self.add_app_package(entry.file_name())
                         }
                     }
                 }
@@ -290,13 +737,106 @@ impl Config {
         Ok(())
     }
 
+    /// Downloads the application with the given ID to the downloads folder, from the configured
+    /// source.
+    ///
+    /// The application is not downloaded again if it already exists, unless the analysis is forced.
+    fn download_package(&mut self, package: &str, sha256: Option<&str>) -> Result<(), Error> {
+        let path = self.package_path(package);
+        if self.overall_force || !path.exists() {
+            fs::create_dir_all(&self.downloads_folder).context(format_err!(
+                "could not create the downloads folder `{}`",
+                self.downloads_folder.display()
+            ))?;
+            download::download(&self.download, package, &path, sha256)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the applications of the given input list and adds them to the configuration.
+    ///
+    /// Applications given as URLs are downloaded to the downloads folder.
+    fn read_input_list<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        for entry in input_list::read(path)? {
+            if entry.is_url() {
+                let force = self.overall_force || entry.options().is_force();
+                let package = input_list::download(entry.source(), &self.downloads_folder, force)?;
+                self.app_packages.push(package);
+            } else {
+                self.add_app_package(entry.source());
+            }
+
+            if *entry.options() != PackageOptions::default() {
+                let package = self
+                    .app_packages
+                    .last()
+                    .expect("expected a package to be added")
+                    .clone();
+                let _ = self.package_options.insert(package, *entry.options());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the application from the standard input and adds it to the configuration.
+    ///
+    /// The application is stored in a new temporary file, only readable by the current user, that
+    /// is removed after the analysis. If no name is given, it is named after the hash of its
+    /// contents, so that the same application gets the same name in every run. Given names are
+    /// used as folder names, so they can only contain ASCII letters, digits, `.`, `_` and `-`.
+    fn read_stdin_package(&mut self, name: Option<&str>) -> Result<(), Error> {
+        let mut apk = Vec::new();
+        let _ = io::stdin()
+            .read_to_end(&mut apk)
+            .context("could not read the standard input")?;
+        if apk.is_empty() {
+            bail!("no application was received in the standard input");
+        }
+
+        let name = match name {
+            Some(name) if is_valid_package_name(name) => name.to_owned(),
+            Some(name) => bail!("invalid package name `{}`", name),
+            None => format!("stdin-{}", &sha256_hex(&apk)[..16]),
+        };
+        let (mut file, path) = Builder::new()
+            .prefix("super-stdin-")
+            .suffix(".apk")
+            .tempfile()
+            .and_then(|file| file.keep().map_err(|e| e.error))
+            .context("could not create the temporary application file")?;
+        file.write_all(&apk).context(format_err!(
+            "could not write the application to `{}`",
+            path.display()
+        ))?;
+
+        let _ = self.package_names.insert(path.clone(), name);
+        self.temporary_packages.push(path.clone());
+        self.app_packages.push(path);
+        Ok(())
+    }
+
+    /// Removes the temporary package files, such as the applications read from the standard
+    /// input.
+    pub fn remove_temporary_packages(&mut self) {
+        for package in self.temporary_packages.drain(..) {
+            if let Err(e) = fs::remove_file(&package) {
+                print_warning(format!(
+                    "could not remove the temporary file `{}`: {}",
+                    package.display(),
+                    e
+                ));
+            }
+        }
+    }
+
     /// Checks if all the needed folders and files exist.
     pub fn check(&self) -> bool {
         let check = self.downloads_folder.exists()
             && self.dex2jar_folder.exists()
-            && self.jd_cmd_file.exists()
+            && self.decompiler_file(self.decompiler).exists()
             && self.template_path().exists()
-            && self.rules_json.exists();
+            && self.rules_json.iter().all(|path| path.exists());
         if check {
             for package in &self.app_packages {
                 if !package.exists() {
@@ -311,50 +851,74 @@ impl Config {
 
     /// Returns the folders and files that do not exist.
     pub fn errors(&self) -> Vec<String> {
+        self.option_errors()
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect()
+    }
+
+    /// Returns the folders and files that do not exist, with the option they are set with.
+    pub(crate) fn option_errors(&self) -> Vec<(&'static str, String)> {
         let mut errors = Vec::new();
         if !self.downloads_folder.exists() {
-            errors.push(format!(
-                "The downloads folder `{}` does not exist",
-                self.downloads_folder.display()
+            errors.push((
+                "downloads_folder",
+                format!(
+                    "The downloads folder `{}` does not exist",
+                    self.downloads_folder.display()
+                ),
             ));
         }
         for package in &self.app_packages {
             if !package.exists() {
-                errors.push(format!(
-                    "The APK file `{}` does not exist",
-                    package.display()
+                errors.push((
+                    "app_packages",
+                    format!("The APK file `{}` does not exist", package.display()),
                 ));
             }
         }
         if !self.dex2jar_folder.exists() {
-            errors.push(format!(
-                "The Dex2Jar folder `{}` does not exist",
-                self.dex2jar_folder.display()
+            errors.push((
+                "dex2jar_folder",
+                format!(
+                    "The Dex2Jar folder `{}` does not exist",
+                    self.dex2jar_folder.display()
+                ),
             ));
         }
-        if !self.jd_cmd_file.exists() {
-            errors.push(format!(
-                "The jd-cmd file `{}` does not exist",
-                self.jd_cmd_file.display()
+        if !self.decompiler_file(self.decompiler).exists() {
+            errors.push((
+                self.decompiler_option(),
+                format!(
+                    "The {} file `{}` does not exist",
+                    self.decompiler,
+                    self.decompiler_file(self.decompiler).display()
+                ),
             ));
         }
         if !self.templates_folder.exists() {
-            errors.push(format!(
-                "the templates folder `{}` does not exist",
-                self.templates_folder.display()
+            errors.push((
+                "templates_folder",
+                format!(
+                    "the templates folder `{}` does not exist",
+                    self.templates_folder.display()
+                ),
             ));
         }
         if !self.template_path().exists() {
-            errors.push(format!(
-                "the template `{}` does not exist in `{}`",
-                self.template,
-                self.templates_folder.display()
+            errors.push((
+                "template",
+                format!(
+                    "the template `{}` does not exist in `{}`",
+                    self.template,
+                    self.templates_folder.display()
+                ),
             ));
         }
-        if !self.rules_json.exists() {
-            errors.push(format!(
-                "The `{}` rule file does not exist",
-                self.rules_json.display()
+        for rules_json in self.rules_json.iter().filter(|path| !path.exists()) {
+            errors.push((
+                "rules_json",
+                format!("The `{}` rule file does not exist", rules_json.display()),
             ));
         }
         errors
@@ -370,8 +934,26 @@ impl Config {
         self.app_packages.clone()
     }
 
+    /// Returns the name of the given package, used for its analysis folders and reports.
+    ///
+    /// It is the name of the package file, without extension, unless the package was given
+    /// through the standard input.
+    pub fn package_name<P: AsRef<Path>>(&self, package: P) -> String {
+        self.package_names
+            .get(package.as_ref())
+            .cloned()
+            .unwrap_or_else(|| get_package_name(package))
+    }
+
     /// Adds a package to check.
     pub(crate) fn add_app_package<P: AsRef<Path>>(&mut self, app_package: P) {
+        let package_path = self.package_path(app_package);
+        self.app_packages.push(package_path);
+    }
+
+    /// Resolves the path of the given package, relative to the downloads folder, adding the
+    /// `.apk` extension if it does not have a package extension.
+    pub(crate) fn package_path<P: AsRef<Path>>(&self, app_package: P) -> PathBuf {
         let mut package_path = self.downloads_folder.join(app_package);
         if package_path.extension().is_none() {
             let updated = package_path.set_extension("apk");
@@ -379,11 +961,11 @@ impl Config {
                 updated,
                 "did not update package path extension, no file name"
             );
-        } else if package_path
-            .extension()
-            .expect("expected extension in package path")
-            != "apk"
-        {
+        } else if !is_package_extension(
+            package_path
+                .extension()
+                .expect("expected extension in package path"),
+        ) {
             let mut file_name = package_path
                 .file_name()
                 .expect("expected file name in package path")
@@ -393,7 +975,7 @@ impl Config {
             package_path.set_file_name(file_name);
         }
 
-        self.app_packages.push(package_path);
+        package_path
     }
 
     /// Returns true if the application is running in `--verbose` mode, false otherwise.
@@ -406,6 +988,19 @@ impl Config {
         self.quiet
     }
 
+    /// Returns the destination of the messages for the user.
+    pub fn output_mode(&self) -> OutputMode {
+        self.output_mode
+    }
+
+    /// Sets the destination of the messages for the user.
+    ///
+    /// Applications embedding the analyzer can use it to capture the messages with their logger,
+    /// or to suppress them completely.
+    pub fn set_output_mode(&mut self, output_mode: OutputMode) {
+        self.output_mode = output_mode;
+    }
+
     /// Returns true if the application is running in `--force` mode, false otherwise.
     pub fn is_force(&self) -> bool {
         self.force
@@ -418,7 +1013,20 @@ impl Config {
 
     /// Resets the `--force` option, so that it gets reset to the configured force option.
     pub fn reset_force(&mut self) {
-        self.force = self.overall_force
+        self.force = self.overall_force || self.current_options.is_force()
+    }
+
+    /// Prepares the configuration for the analysis of the given package.
+    ///
+    /// It applies the options given to the package in the input list, if any, and resets the
+    /// `--force` option.
+    pub fn select_package<P: AsRef<Path>>(&mut self, package: P) {
+        self.current_options = self
+            .package_options
+            .get(package.as_ref())
+            .cloned()
+            .unwrap_or_default();
+        self.reset_force();
     }
 
     /// Returns true if the application is running in `--bench` mode, false otherwise.
@@ -436,14 +1044,131 @@ impl Config {
         self.json
     }
 
+    /// Sets the application to generate the results in JSON format.
+    pub fn set_json(&mut self) {
+        self.json = true;
+    }
+
     /// Returns true if the application has to generate result in HTML format.
     pub fn has_to_generate_html(&self) -> bool {
         !self.json || self.html
     }
 
+    /// Returns true if the application has to generate result in DefectDojo format.
+    pub fn has_to_generate_defectdojo(&self) -> bool {
+        self.defectdojo
+    }
+
+    /// Returns true if the application has to generate result in SARIF format.
+    pub fn has_to_generate_sarif(&self) -> bool {
+        self.sarif
+    }
+
+    /// Returns true if the application has to generate a CycloneDX SBOM.
+    pub fn has_to_generate_sbom(&self) -> bool {
+        self.sbom
+    }
+
+    /// Returns true if the application has to generate result in CSV format.
+    pub fn has_to_generate_csv(&self) -> bool {
+        self.csv
+    }
+
+    /// Returns true if the application has to generate result in Markdown format.
+    pub fn has_to_generate_markdown(&self) -> bool {
+        self.markdown
+    }
+
+    /// Returns the DefectDojo API configuration, if any.
+    pub fn defectdojo_api(&self) -> Option<&DefectDojoApi> {
+        self.defectdojo_api.as_ref()
+    }
+
+    /// Returns the Jira configuration, if any.
+    pub fn jira(&self) -> Option<&JiraConfig> {
+        self.jira.as_ref()
+    }
+
+    /// Returns the Dependency-Track configuration, if any.
+    pub fn dependency_track(&self) -> Option<&DependencyTrackConfig> {
+        self.dependency_track.as_ref()
+    }
+
     /// Returns the `min_criticality` field.
+    ///
+    /// It can be overridden for the current package in the input list.
     pub fn min_criticality(&self) -> Criticality {
-        self.min_criticality
+        self.current_options
+            .min_criticality()
+            .unwrap_or(self.min_criticality)
+    }
+
+    /// Returns the criticality of the findings that make the analysis fail, if set.
+    pub fn fail_on(&self) -> Option<Criticality> {
+        self.fail_on
+    }
+
+    /// Returns whether the analysis goes on when a phase fails, and the rest of packages are
+    /// analyzed when the analysis of a package fails.
+    pub fn is_keep_going(&self) -> bool {
+        self.keep_going
+    }
+
+    /// Returns the analysis profile.
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Returns whether the application is decompiled and its code analyzed, that is, unless the
+    /// profile is `quick`.
+    pub fn is_decompilation(&self) -> bool {
+        self.profile != Profile::Quick
+    }
+
+    /// Returns whether the native libraries, and the Flutter and .NET binaries, are analyzed,
+    /// that is, unless the profile is `quick`.
+    pub fn is_native_analysis(&self) -> bool {
+        self.profile != Profile::Quick
+    }
+
+    /// Returns whether the hygiene findings are only reported for applications that are not
+    /// debuggable.
+    pub fn is_hygiene_release_only(&self) -> bool {
+        self.hygiene_release_only
+    }
+
+    /// Returns the categories of applications whose missing resilience checks are reported.
+    pub fn resilience_categories(&self) -> Iter<resilience::AppCategory> {
+        self.resilience_categories.iter()
+    }
+
+    /// Returns the extensions of the files analyzed with the code rules.
+    pub fn code_extensions(&self) -> VecIter<String> {
+        self.code_extensions.iter()
+    }
+
+    /// Returns the folders of the decompiled applications that are not analyzed, relative to
+    /// their folder.
+    pub fn ignored_folders(&self) -> VecIter<PathBuf> {
+        self.ignored_folders.iter()
+    }
+
+    /// Returns the maximum size of the analyzed files, in KiB, if limited.
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.max_file_size
+    }
+
+    /// Returns the maximum time each code rule can take in the analysis of an application, in
+    /// seconds.
+    pub fn rule_time_budget(&self) -> u64 {
+        self.rule_time_budget
+    }
+
+    /// Returns whether the code rules that exceed their time budget stop being checked.
+    ///
+    /// Slow rules are never disabled with the `deep` profile.
+    pub fn is_disable_slow_rules(&self) -> bool {
+        self.disable_slow_rules && self.profile != Profile::Deep
     }
 
     /// Returns the `threads` field.
@@ -456,6 +1181,21 @@ impl Config {
         &self.dist_folder
     }
 
+    /// Returns the path to the `downloads_folder`.
+    pub fn downloads_folder(&self) -> &Path {
+        &self.downloads_folder
+    }
+
+    /// Returns the folder where the JAR files and the decompiled sources are cached.
+    pub fn cache_folder(&self) -> &Path {
+        &self.cache_folder
+    }
+
+    /// Returns whether the JAR files and the decompiled sources are cached across analyses.
+    pub fn is_decompilation_cache(&self) -> bool {
+        self.decompilation_cache
+    }
+
     /// Returns the path to the `results_folder`.
     pub fn results_folder(&self) -> &Path {
         &self.results_folder
@@ -471,6 +1211,106 @@ impl Config {
         &self.jd_cmd_file
     }
 
+    /// Returns the path to the `jadx_file`.
+    pub fn jadx_file(&self) -> &Path {
+        &self.jadx_file
+    }
+
+    /// Returns the path to the `cfr_file`.
+    pub fn cfr_file(&self) -> &Path {
+        &self.cfr_file
+    }
+
+    /// Returns the path to the `procyon_file`.
+    pub fn procyon_file(&self) -> &Path {
+        &self.procyon_file
+    }
+
+    /// Returns the path to the `adb_file`.
+    pub fn adb_file(&self) -> &Path {
+        &self.adb_file
+    }
+
+    /// Returns the decompiler to use first.
+    pub fn decompiler(&self) -> Decompiler {
+        self.decompiler
+    }
+
+    /// Returns the folder where the `setup` subcommand installs the tools.
+    pub fn tools_folder(&self) -> &Path {
+        &self.tools_folder
+    }
+
+    /// Uses the tools installed by the `setup` subcommand for the configured paths that do not
+    /// exist.
+    pub(crate) fn use_installed_tools(&mut self) {
+        for tool in &setup::TOOLS {
+            let path = tool.path(&self.tools_folder);
+            if !self.tool_path(tool.kind()).exists() && path.exists() {
+                self.set_tool_path(tool.kind(), path);
+            }
+        }
+    }
+
+    /// Makes sure that the tools needed for the analysis are available, downloading and
+    /// installing the missing ones in the tools folder.
+    ///
+    /// If `force` is set, the tools are installed even if they are already available.
+    pub fn ensure_tools(
+        &mut self,
+        force: bool,
+        proxy: Option<&str>,
+        allow_unpinned: bool,
+    ) -> Result<(), Error> {
+        if !force {
+            self.use_installed_tools();
+        }
+        for tool in &setup::TOOLS {
+            if force || !self.tool_path(tool.kind()).exists() {
+                let path = setup::install(tool, &self.tools_folder, proxy, allow_unpinned)?;
+                self.set_tool_path(tool.kind(), path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the configured path of the given kind of tool.
+    fn tool_path(&self, kind: ToolKind) -> &Path {
+        match kind {
+            ToolKind::Dex2jar => &self.dex2jar_folder,
+            ToolKind::Jadx => &self.jadx_file,
+        }
+    }
+
+    /// Sets the path of the given kind of tool.
+    fn set_tool_path(&mut self, kind: ToolKind, path: PathBuf) {
+        match kind {
+            ToolKind::Dex2jar => self.dex2jar_folder = path,
+            ToolKind::Jadx => self.jadx_file = path,
+        }
+    }
+
+    /// Returns the option with the path to the binary or JAR file of the decompiler to use
+    /// first.
+    fn decompiler_option(&self) -> &'static str {
+        match self.decompiler {
+            Decompiler::JdCmd => "jd_cmd_file",
+            Decompiler::Jadx => "jadx_file",
+            Decompiler::Cfr => "cfr_file",
+            Decompiler::Procyon => "procyon_file",
+        }
+    }
+
+    /// Returns the path to the binary or JAR file of the given decompiler.
+    pub fn decompiler_file(&self, decompiler: Decompiler) -> &Path {
+        match decompiler {
+            Decompiler::JdCmd => &self.jd_cmd_file,
+            Decompiler::Jadx => &self.jadx_file,
+            Decompiler::Cfr => &self.cfr_file,
+            Decompiler::Procyon => &self.procyon_file,
+        }
+    }
+
     /// Gets the path to the template.
     pub fn template_path(&self) -> PathBuf {
         self.templates_folder.join(&self.template)
@@ -486,11 +1326,117 @@ impl Config {
         &self.template
     }
 
-    /// Returns the path to the `rules_json`.
-    pub fn rules_json(&self) -> &Path {
+    /// Returns the paths of the `rules_json`, rules files or folders of rule packs, in the order
+    /// they are loaded.
+    pub fn rules_paths(&self) -> &[PathBuf] {
         &self.rules_json
     }
 
+    /// Returns the public keys trusted to sign the rules.
+    pub fn trusted_keys(&self) -> VecIter<String> {
+        self.trusted_keys.iter()
+    }
+
+    /// Returns the public keys trusted to sign the releases of SUPER.
+    pub fn update_keys(&self) -> VecIter<String> {
+        self.update_keys.iter()
+    }
+
+    /// Returns true if unsigned rules should be refused.
+    pub fn is_require_signed_rules(&self) -> bool {
+        self.require_signed_rules
+    }
+
+    /// Returns the URL of the signed rules bundle installed by `rules update`, if set.
+    pub fn rules_update_url(&self) -> Option<&str> {
+        self.rules_update_url.as_ref().map(String::as_str)
+    }
+
+    /// Returns whether the Firebase databases and buckets are checked online for open access.
+    ///
+    /// They are always checked with the `deep` profile.
+    pub fn is_online_checks(&self) -> bool {
+        self.online_checks || self.profile == Profile::Deep
+    }
+
+    /// Returns the path to the `plugins_folder`.
+    pub fn plugins_folder(&self) -> &Path {
+        &self.plugins_folder
+    }
+
+    /// Returns the maximum number of WebAssembly instructions of each call to a plugin, and of
+    /// operations of each call to a script hook.
+    pub fn plugin_fuel(&self) -> u64 {
+        self.plugin_fuel
+    }
+
+    /// Returns the language of the messages, if set.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_ref().map(String::as_str)
+    }
+
+    /// Returns the path to the `scripts_folder`.
+    pub fn scripts_folder(&self) -> &Path {
+        &self.scripts_folder
+    }
+
+    /// Returns the configured `external_analyzers`.
+    pub fn external_analyzers(&self) -> VecIter<ExternalAnalyzer> {
+        self.external_analyzers.iter()
+    }
+
+    /// Returns the configured `correlations` rules.
+    pub fn correlations(&self) -> VecIter<CorrelationRule> {
+        self.correlations.iter()
+    }
+
+    /// Returns the file with the baseline of known findings, if set.
+    pub fn baseline(&self) -> Option<&Path> {
+        self.baseline.as_ref().map(PathBuf::as_path)
+    }
+
+    /// Returns true if the baseline has to be replaced with the findings of the analysis.
+    pub fn is_update_baseline(&self) -> bool {
+        self.update_baseline
+    }
+
+    /// Returns the file where the Prometheus metrics will be written, if any.
+    pub fn metrics_file(&self) -> Option<&Path> {
+        self.metrics_file.as_ref().map(PathBuf::as_path)
+    }
+
+    /// Returns the file where the run statistics will be written, if any.
+    ///
+    /// A `-` path means that they will be written to the standard output.
+    pub fn stats_json(&self) -> Option<&Path> {
+        self.stats_json.as_ref().map(PathBuf::as_path)
+    }
+
+    /// Returns the SQLite database where the history of the analyses is recorded, if any.
+    pub fn history_db(&self) -> Option<&Path> {
+        self.history_db.as_ref().map(PathBuf::as_path)
+    }
+
+    /// Returns the resource limits for the decompilation tools.
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+
+    /// Returns the configuration of the application downloads.
+    pub fn download(&self) -> &DownloadConfig {
+        &self.download
+    }
+
+    /// Returns the weights of the risk factors of the overall risk grade.
+    pub fn risk(&self) -> &RiskWeights {
+        &self.risk
+    }
+
+    /// Returns the reports from other scanners to import, along with their format.
+    pub fn imported_reports(&self) -> VecIter<(ImportFormat, PathBuf)> {
+        self.imported_reports.iter()
+    }
+
     /// Returns the criticality of the `unknown_permission` field.
     pub fn unknown_permission_criticality(&self) -> Criticality {
         self.unknown_permission.0
@@ -506,28 +1452,108 @@ impl Config {
         self.permissions.iter()
     }
 
+    /// Returns the `permission_catalog` of the known permissions.
+    pub fn permission_catalog(&self) -> &manifest::PermissionCatalog {
+        &self.permission_catalog
+    }
+
+    /// Returns the `library_vulnerabilities` dataset of the known vulnerabilities of libraries.
+    pub fn library_vulnerabilities(&self) -> &dependencies::VulnerabilityDatabase {
+        &self.library_vulnerabilities
+    }
+
+    /// Returns the configured risky `permission_combinations`.
+    pub fn permission_combinations(&self) -> VecIter<manifest::PermissionCombination> {
+        self.permission_combinations.iter()
+    }
+
     /// Returns the default `Config` struct.
     fn local_default() -> Self {
         Self {
             app_packages: Vec::new(),
+            package_options: BTreeMap::new(),
+            current_options: PackageOptions::default(),
+            package_names: BTreeMap::new(),
+            temporary_packages: Vec::new(),
             verbose: false,
             quiet: false,
+            output_mode: OutputMode::Console,
             overall_force: false,
             force: false,
             bench: false,
             open: false,
             json: false,
             html: false,
+            defectdojo: false,
+            sarif: false,
+            sbom: false,
+            csv: false,
+            markdown: false,
             threads: num_cpus::get(),
             min_criticality: Criticality::Warning,
+            fail_on: None,
+            keep_going: false,
+            profile: Profile::Standard,
+            hygiene_release_only: false,
+            resilience_categories: [
+                resilience::AppCategory::Finance,
+                resilience::AppCategory::Health,
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            code_extensions: vec!["java".to_owned(), "xml".to_owned(), "js".to_owned()],
+            ignored_folders: vec![
+                Path::new("classes").join("android"),
+                Path::new("classes")
+                    .join("com")
+                    .join("google")
+                    .join("android")
+                    .join("gms"),
+                PathBuf::from("smali"),
+            ],
+            max_file_size: None,
+            rule_time_budget: 60,
+            disable_slow_rules: false,
             downloads_folder: PathBuf::from("."),
             dist_folder: PathBuf::from("dist"),
+            cache_folder: PathBuf::from("cache"),
+            decompilation_cache: true,
             results_folder: PathBuf::from("results"),
             dex2jar_folder: Path::new("vendor").join("dex2jar-2.1-SNAPSHOT"),
             jd_cmd_file: Path::new("vendor").join("jd-cmd.jar"),
+            jadx_file: Path::new("vendor").join("jadx").join("bin").join("jadx"),
+            cfr_file: Path::new("vendor").join("cfr.jar"),
+            procyon_file: Path::new("vendor").join("procyon.jar"),
+            decompiler: Decompiler::JdCmd,
+            tools_folder: PathBuf::from("tools"),
+            adb_file: PathBuf::from("adb"),
             templates_folder: PathBuf::from("templates"),
             template: String::from("super"),
-            rules_json: PathBuf::from("rules.json"),
+            locale: None,
+            rules_json: vec![PathBuf::from("rules.json")],
+            trusted_keys: Vec::new(),
+            update_keys: Vec::new(),
+            require_signed_rules: false,
+            rules_update_url: None,
+            online_checks: false,
+            plugins_folder: PathBuf::from("plugins"),
+            plugin_fuel: 10_000_000_000,
+            scripts_folder: PathBuf::from("scripts"),
+            external_analyzers: Vec::new(),
+            correlations: Vec::new(),
+            baseline: None,
+            update_baseline: false,
+            metrics_file: None,
+            stats_json: None,
+            history_db: None,
+            limits: ResourceLimits::default(),
+            download: DownloadConfig::default(),
+            risk: RiskWeights::default(),
+            imported_reports: Vec::new(),
+            defectdojo_api: None,
+            jira: None,
+            dependency_track: None,
             unknown_permission: (
                 Criticality::Low,
                 String::from(
@@ -537,11 +1563,26 @@ impl Config {
                 ),
             ),
             permissions: BTreeSet::new(),
+            permission_catalog: manifest::PermissionCatalog::builtin().clone(),
+            library_vulnerabilities: dependencies::VulnerabilityDatabase::builtin().clone(),
+            permission_combinations: Vec::new(),
             loaded_files: Vec::new(),
         }
     }
 }
 
+/// Parses the value of the environment variable with the given name.
+fn parse_env<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format_err!("invalid value `{}` for `{}`: {}", value, name, e))
+}
+
 impl Default for Config {
     /// Creates the default `Config` struct in Unix systems.
     #[cfg(target_family = "unix")]
@@ -549,7 +1590,7 @@ impl Default for Config {
         let mut config = Self::local_default();
         let etc_rules = PathBuf::from("/etc/super-analyzer/rules.json");
         if etc_rules.exists() {
-            config.rules_json = etc_rules;
+            config.rules_json = vec![etc_rules];
         }
         let share_path = Path::new(if cfg!(target_os = "macos") {
             "/usr/local/super-analyzer"
@@ -559,6 +1600,9 @@ impl Default for Config {
         if share_path.exists() {
             config.dex2jar_folder = share_path.join("vendor/dex2jar-2.1-SNAPSHOT");
             config.jd_cmd_file = share_path.join("vendor/jd-cmd.jar");
+            config.jadx_file = share_path.join("vendor/jadx/bin/jadx");
+            config.cfr_file = share_path.join("vendor/cfr.jar");
+            config.procyon_file = share_path.join("vendor/procyon.jar");
             config.templates_folder = share_path.join("templates");
         }
         config
@@ -600,9 +1644,9 @@ impl PartialOrd for Permission {
 }
 
 impl Permission {
-    /// Returns the enum that represents the `name`.
-    pub fn name(&self) -> manifest::Permission {
-        self.name
+    /// Returns the `name` of the permission.
+    pub fn name(&self) -> &manifest::Permission {
+        &self.name
     }
 
     /// Returns the permission's `criticality`.
@@ -625,14 +1669,22 @@ impl Permission {
 #[cfg(test)]
 mod tests {
     use std::{
-        fs,
+        env, fs,
         path::{Path, PathBuf},
     };
 
     use num_cpus;
 
-    use super::Config;
-    use crate::{criticality::Criticality, static_analysis::manifest};
+    use super::{Config, Profile};
+    use crate::{
+        criticality::Criticality,
+        decompilation::Decompiler,
+        download::DownloadConfig,
+        limits::ResourceLimits,
+        results::RiskWeights,
+        static_analysis::{dependencies, manifest, resilience},
+        utils::OutputMode,
+    };
 
     /// Test for the default configuration function.
     #[allow(clippy::cyclomatic_complexity)]
@@ -645,12 +1697,43 @@ mod tests {
         assert!(config.app_packages().is_empty());
         assert!(!config.is_verbose());
         assert!(!config.is_quiet());
+        assert_eq!(config.output_mode(), OutputMode::Console);
         assert!(!config.is_force());
         assert!(!config.is_bench());
         assert!(!config.is_open());
         assert_eq!(config.threads(), num_cpus::get());
+        assert!(config.fail_on().is_none());
+        assert!(!config.is_keep_going());
+        assert_eq!(config.profile(), Profile::Standard);
+        assert!(config.is_decompilation());
+        assert!(config.is_native_analysis());
+        assert!(!config.is_hygiene_release_only());
+        assert_eq!(
+            config.resilience_categories().collect::<Vec<_>>(),
+            vec![
+                &resilience::AppCategory::Finance,
+                &resilience::AppCategory::Health
+            ]
+        );
+        assert_eq!(
+            config.code_extensions().collect::<Vec<_>>(),
+            vec!["java", "xml", "js"]
+        );
+        assert_eq!(
+            config.ignored_folders().collect::<Vec<_>>(),
+            vec![
+                Path::new("classes/android"),
+                Path::new("classes/com/google/android/gms"),
+                Path::new("smali")
+            ]
+        );
+        assert!(config.max_file_size().is_none());
+        assert_eq!(config.rule_time_budget(), 60);
+        assert!(!config.is_disable_slow_rules());
         assert_eq!(config.downloads_folder, Path::new("."));
         assert_eq!(config.dist_folder(), Path::new("dist"));
+        assert_eq!(config.cache_folder(), Path::new("cache"));
+        assert!(config.is_decompilation_cache());
         assert_eq!(config.results_folder(), Path::new("results"));
         assert_eq!(config.template_name(), "super");
         let share_path = Path::new(if cfg!(target_os = "macos") {
@@ -673,6 +1756,22 @@ mod tests {
             config.jd_cmd_file(),
             share_path.join("vendor").join("jd-cmd.jar")
         );
+        assert_eq!(
+            config.jadx_file(),
+            share_path
+                .join("vendor")
+                .join("jadx")
+                .join("bin")
+                .join("jadx")
+        );
+        assert_eq!(config.cfr_file(), share_path.join("vendor").join("cfr.jar"));
+        assert_eq!(
+            config.procyon_file(),
+            share_path.join("vendor").join("procyon.jar")
+        );
+        assert_eq!(config.decompiler(), Decompiler::JdCmd);
+        assert_eq!(config.tools_folder(), Path::new("tools"));
+        assert_eq!(config.adb_file(), Path::new("adb"));
         assert_eq!(config.templates_folder(), share_path.join("templates"));
         assert_eq!(
             config.template_path(),
@@ -680,12 +1779,40 @@ mod tests {
         );
         if cfg!(target_family = "unix") && Path::new("/etc/super-analyzer/rules.json").exists() {
             assert_eq!(
-                config.rules_json(),
-                Path::new("/etc/super-analyzer/rules.json")
+                config.rules_paths(),
+                [PathBuf::from("/etc/super-analyzer/rules.json")]
             );
         } else {
-            assert_eq!(config.rules_json(), Path::new("rules.json"));
+            assert_eq!(config.rules_paths(), [PathBuf::from("rules.json")]);
         }
+        assert_eq!(config.trusted_keys().len(), 0);
+        assert_eq!(config.update_keys().len(), 0);
+        assert!(!config.is_require_signed_rules());
+        assert!(config.rules_update_url().is_none());
+        assert!(!config.is_online_checks());
+        assert_eq!(config.plugins_folder(), Path::new("plugins"));
+        assert_eq!(config.plugin_fuel(), 10_000_000_000);
+        assert_eq!(config.scripts_folder(), Path::new("scripts"));
+        assert_eq!(config.external_analyzers().len(), 0);
+        assert_eq!(config.correlations().len(), 0);
+        assert!(config.baseline().is_none());
+        assert!(!config.is_update_baseline());
+        assert!(config.metrics_file().is_none());
+        assert!(config.stats_json().is_none());
+        assert!(config.history_db().is_none());
+        assert!(config.locale().is_none());
+        assert_eq!(config.limits(), &ResourceLimits::default());
+        assert_eq!(config.download(), &DownloadConfig::default());
+        assert_eq!(config.risk(), &RiskWeights::default());
+        assert_eq!(config.imported_reports().len(), 0);
+        assert!(!config.has_to_generate_defectdojo());
+        assert!(!config.has_to_generate_sarif());
+        assert!(!config.has_to_generate_csv());
+        assert!(!config.has_to_generate_markdown());
+        assert!(config.defectdojo_api().is_none());
+        assert!(config.jira().is_none());
+        assert!(!config.has_to_generate_sbom());
+        assert!(config.dependency_track().is_none());
         assert_eq!(config.unknown_permission_criticality(), Criticality::Low);
         assert_eq!(
             config.unknown_permission_description(),
@@ -693,6 +1820,15 @@ mod tests {
              since it can lead to misunderstanding between developers."
         );
         assert_eq!(config.permissions().next(), None);
+        assert_eq!(config.permission_combinations().len(), 0);
+        assert_eq!(
+            config.permission_catalog(),
+            manifest::PermissionCatalog::builtin()
+        );
+        assert_eq!(
+            config.library_vulnerabilities(),
+            dependencies::VulnerabilityDatabase::builtin()
+        );
 
         if !config.downloads_folder.exists() {
             fs::create_dir(&config.downloads_folder).unwrap();
@@ -772,8 +1908,8 @@ mod tests {
         );
         assert_eq!(config.template_name(), "super");
         assert_eq!(
-            config.rules_json(),
-            Path::new("/etc/super-analyzer/rules.json")
+            config.rules_paths(),
+            [PathBuf::from("/etc/super-analyzer/rules.json")]
         );
         assert_eq!(config.unknown_permission_criticality(), Criticality::Low);
         assert_eq!(
@@ -783,10 +1919,7 @@ mod tests {
         );
 
         let permission = config.permissions().next().unwrap();
-        assert_eq!(
-            permission.name(),
-            manifest::Permission::AndroidPermissionInternet
-        );
+        assert_eq!(permission.name().as_str(), "android.permission.INTERNET");
         assert_eq!(permission.criticality(), Criticality::Warning);
         assert_eq!(permission.label(), "Internet permission");
         assert_eq!(
@@ -808,4 +1941,101 @@ mod tests {
         assert!(final_config.has_to_generate_html());
         assert!(!final_config.has_to_generate_json());
     }
+
+    /// Checks the phases enabled by each analysis profile.
+    #[test]
+    fn it_profile() {
+        assert_eq!("Quick".parse::<Profile>().unwrap(), Profile::Quick);
+        assert!("thorough".parse::<Profile>().is_err());
+
+        let mut config = Config::default();
+        config.disable_slow_rules = true;
+        config.profile = Profile::Quick;
+        assert!(!config.is_decompilation());
+        assert!(!config.is_native_analysis());
+        assert!(!config.is_online_checks());
+        assert!(config.is_disable_slow_rules());
+
+        config.profile = Profile::Deep;
+        assert!(config.is_decompilation());
+        assert!(config.is_native_analysis());
+        assert!(config.is_online_checks());
+        assert!(!config.is_disable_slow_rules());
+    }
+
+    /// Checks that the rules can be loaded from one or several paths.
+    #[test]
+    fn it_rules_json() {
+        let config: Config = toml::from_str("rules_json = \"rules.json\"").unwrap();
+        assert_eq!(config.rules_paths(), [PathBuf::from("rules.json")]);
+
+        let config: Config =
+            toml::from_str("rules_json = [\"rules.json\", \"org-rules\"]").unwrap();
+        assert_eq!(
+            config.rules_paths(),
+            [PathBuf::from("rules.json"), PathBuf::from("org-rules")]
+        );
+        assert!(toml::from_str::<Config>("rules_json = 3").is_err());
+    }
+
+    /// Checks the overrides of the environment variables.
+    #[test]
+    fn it_env_options() {
+        let mut config = Config::default();
+        config
+            .set_env_options(|name| match name {
+                "SUPER_RESULTS_FOLDER" => Some("/tmp/results".to_owned()),
+                "SUPER_THREADS" => Some("3".to_owned()),
+                "SUPER_MIN_CRITICALITY" => Some("high".to_owned()),
+                "SUPER_ONLINE_CHECKS" => Some("true".to_owned()),
+                "SUPER_PROFILE" => Some("quick".to_owned()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config.results_folder(), Path::new("/tmp/results"));
+        assert_eq!(config.threads(), 3);
+        assert_eq!(config.min_criticality(), Criticality::High);
+        assert!(config.is_online_checks());
+        assert_eq!(config.profile(), Profile::Quick);
+        assert_eq!(config.dist_folder(), Path::new("dist"));
+
+        let error = config
+            .set_env_options(|name| match name {
+                "SUPER_THREADS" => Some("many".to_owned()),
+                _ => None,
+            })
+            .unwrap_err();
+        assert!(error.to_string().contains("SUPER_THREADS"));
+        assert_eq!(config.threads(), 3);
+    }
+
+    /// Checks the options given to the packages in the input list.
+    #[test]
+    fn it_input_list() {
+        let input_list = env::temp_dir().join("super_it_input_list.txt");
+        fs::write(
+            &input_list,
+            "# Test applications.\nfirst_app\nsecond_app.apk force min_criticality=high\n",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.read_input_list(&input_list).unwrap();
+        fs::remove_file(&input_list).unwrap();
+
+        let packages = config.app_packages();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(
+            &packages[1],
+            &config.downloads_folder.join("second_app.apk")
+        );
+
+        config.select_package(&packages[1]);
+        assert!(config.is_force());
+        assert_eq!(config.min_criticality(), Criticality::High);
+
+        config.select_package(&packages[0]);
+        assert!(!config.is_force());
+        assert_eq!(config.min_criticality(), Criticality::Warning);
+    }
 }
@@ -0,0 +1,154 @@
+//! Configuration check module.
+//!
+//! The `config check` subcommand loads the configuration as an analysis would, and prints each of
+//! its errors with the option and the file or environment variable it was set in. It also reports
+//! the options of the configuration files that SUPER does not know, since they are silently
+//! ignored otherwise, usually because of a typo.
+//!
+//! The `config schema` subcommand prints the JSON Schema of the configuration file, that editors
+//! can use to validate and autocomplete `config.toml`.
+
+use std::{collections::BTreeSet, env, fs, path::Path};
+
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+use serde_json::Value;
+
+use crate::{config::ENV_PREFIX, Config};
+
+/// JSON Schema of the configuration file.
+pub const SCHEMA: &str = include_str!("../config.schema.json");
+
+/// Gets the options of the configuration file described in the schema.
+fn known_options() -> BTreeSet<String> {
+    let schema: Value = serde_json::from_str(SCHEMA).expect("the configuration schema is invalid");
+    schema["properties"]
+        .as_object()
+        .map_or_else(BTreeSet::new, |properties| {
+            properties.keys().cloned().collect()
+        })
+}
+
+/// Gets the top-level options of the given configuration file that SUPER does not know.
+pub fn unknown_options<P: AsRef<Path>>(config_file: P) -> Result<Vec<String>, Error> {
+    let config_file = config_file.as_ref();
+    let content = fs::read_to_string(config_file).context(format_err!(
+        "could not read the configuration file `{}`",
+        config_file.display()
+    ))?;
+    let table: toml::value::Table = toml::from_str(&content).context(format_err!(
+        "could not decode the configuration file `{}`",
+        config_file.display()
+    ))?;
+
+    let known = known_options();
+    Ok(table
+        .keys()
+        .filter(|option| !known.contains(option.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Gets where the given option of the configuration was set.
+///
+/// Environment variables override the configuration files, and the last loaded file overrides
+/// the previous ones. Options that are in none of them come from the default configuration or
+/// from the command line.
+fn option_source(config: &Config, option: &str) -> String {
+    let variable = format!("{}{}", ENV_PREFIX, option.to_uppercase());
+    if env::var_os(&variable).is_some() {
+        return format!("the `{}` environment variable", variable);
+    }
+    config
+        .loaded_config_files()
+        .rev()
+        .find(|file| {
+            fs::read_to_string(file)
+                .ok()
+                .and_then(|content| toml::from_str::<toml::value::Table>(&content).ok())
+                .map_or(false, |table| table.contains_key(option))
+        })
+        .map_or_else(
+            || String::from("the default configuration or the command line"),
+            |file| format!("`{}`", file.display()),
+        )
+}
+
+/// Runs the `config check` subcommand.
+#[allow(clippy::print_stdout)]
+fn check(config: &Config) -> Result<(), Error> {
+    let mut errors = 0;
+    for (option, error) in config.option_errors() {
+        println!(
+            "{} `{}`, set in {}: {}",
+            "error:".red().bold(),
+            option,
+            option_source(config, option),
+            error
+        );
+        errors += 1;
+    }
+    for file in config.loaded_config_files() {
+        for option in unknown_options(file)? {
+            println!(
+                "{} `{}`, set in `{}`: unknown option",
+                "error:".red().bold(),
+                option,
+                file.display()
+            );
+            errors += 1;
+        }
+    }
+
+    if errors > 0 {
+        bail!("{} configuration errors were found", errors);
+    }
+    println!("{}", "The configuration is valid.".green());
+    Ok(())
+}
+
+/// Runs the `config schema` subcommand.
+#[allow(clippy::print_stdout)]
+fn schema(cli: &ArgMatches<'static>) -> Result<(), Error> {
+    if let Some(output) = cli.value_of("output") {
+        fs::write(output, SCHEMA).context(format_err!(
+            "could not write the configuration schema to `{}`",
+            output
+        ))?;
+    } else {
+        print!("{}", SCHEMA);
+    }
+    Ok(())
+}
+
+/// Runs the `config` subcommand.
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    match cli.subcommand() {
+        ("check", Some(_)) => check(config),
+        ("schema", Some(schema_cli)) => schema(schema_cli),
+        _ => bail!("unknown config subcommand, use `check` or `schema`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{known_options, unknown_options};
+
+    /// Checks that the schema describes the options of the configuration file, and that the
+    /// unknown ones are found.
+    #[test]
+    fn it_unknown_options() {
+        let known = known_options();
+        assert!(known.contains("threads"));
+        assert!(known.contains("permission_combinations"));
+        assert!(unknown_options("config.toml").unwrap().is_empty());
+
+        let config_file = env::temp_dir().join("super-config-check-test.toml");
+        fs::write(&config_file, "threads = 2\nthread = 4\n").unwrap();
+        assert_eq!(unknown_options(&config_file).unwrap(), vec!["thread"]);
+        fs::remove_file(&config_file).unwrap();
+    }
+}
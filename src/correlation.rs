@@ -0,0 +1,171 @@
+//! Finding correlation module.
+//!
+//! Findings that are not serious by themselves can be serious when they appear together. For
+//! example, an application that allows backups and stores its tokens in world readable files
+//! exposes them to anyone with access to a backup. Correlation rules, configured in the
+//! `config.toml` file, combine the findings of the analysis to create composite findings:
+//!
+//! ```toml
+//! [[correlations]]
+//! name = "Data-at-rest Exposure"
+//! description = "The application allows backups and stores world readable files."
+//! criticality = "critical"
+//! all_of = ["Allows Backup", "world-readable-permissions"]
+//! escalate = true
+//! ```
+//!
+//! Each entry of `all_of` matches the findings with that name or rule ID, ignoring case, and the
+//! rule applies when all of them are found. The composite finding references the IDs of its
+//! constituent findings, and, if `escalate` is set, the constituents are also raised to the
+//! criticality of the rule.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    criticality::Criticality,
+    print_message, print_vulnerability,
+    results::{Results, Vulnerability},
+    Config,
+};
+
+/// Correlation rule configuration.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CorrelationRule {
+    /// Name of the composite finding.
+    name: String,
+    /// Description of the composite finding.
+    description: String,
+    /// Criticality of the composite finding.
+    criticality: Criticality,
+    /// Names or rule IDs of the findings that must all be present.
+    all_of: Vec<String>,
+    /// Whether the constituent findings are escalated to the criticality of the rule.
+    #[serde(default)]
+    escalate: bool,
+    /// CWE identifier of the composite finding.
+    #[serde(default)]
+    cwe: Option<u32>,
+}
+
+impl CorrelationRule {
+    /// Returns the name of the composite finding.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the criticality of the composite finding.
+    pub fn criticality(&self) -> Criticality {
+        self.criticality
+    }
+
+    /// Returns the IDs of the findings that match the rule, if all its requirements are met.
+    fn matches(&self, vulnerabilities: &[&Vulnerability]) -> Option<BTreeSet<String>> {
+        if self.all_of.is_empty() {
+            return None;
+        }
+
+        let mut ids = BTreeSet::new();
+        for requirement in &self.all_of {
+            let mut found = false;
+            for vulnerability in vulnerabilities.iter().filter(|v| is_match(v, requirement)) {
+                let _ = ids.insert(vulnerability.id());
+                found = true;
+            }
+            if !found {
+                return None;
+            }
+        }
+
+        Some(ids)
+    }
+}
+
+/// Checks if the given vulnerability has the given name or rule ID, ignoring case.
+fn is_match(vulnerability: &Vulnerability, requirement: &str) -> bool {
+    vulnerability.name().eq_ignore_ascii_case(requirement)
+        || vulnerability
+            .rule_id()
+            .map_or(false, |id| id.eq_ignore_ascii_case(requirement))
+}
+
+/// Runs the configured correlation rules on the results.
+pub fn analysis(config: &Config, results: &mut Results) {
+    let mut composites = Vec::new();
+    for rule in config.correlations() {
+        // Composite findings are not correlated again, so that rules do not depend on their order.
+        let vulnerabilities = results
+            .vulnerabilities()
+            .filter(|v| v.related().is_empty())
+            .collect::<Vec<_>>();
+        let ids = match rule.matches(&vulnerabilities) {
+            Some(ids) => ids,
+            None => continue,
+        };
+
+        if config.is_verbose() {
+            print_message(format!(
+                "The {} correlation rule matched {} findings.",
+                rule.name(),
+                ids.len()
+            ));
+        }
+        if rule.escalate {
+            results.escalate_vulnerabilities(&ids, rule.criticality);
+        }
+        if rule.criticality >= config.min_criticality() {
+            let mut builder = Vulnerability::builder(
+                rule.criticality,
+                rule.name.as_str(),
+                rule.description.as_str(),
+            )
+            .rule_id(format!("correlation:{}", rule.name))
+            .evidence(ids.iter().cloned().collect::<Vec<_>>().join(", "))
+            .related(ids);
+            if let Some(cwe) = rule.cwe {
+                builder = builder.cwe(cwe);
+            }
+            composites.push(builder.build());
+        }
+    }
+
+    for composite in composites {
+        print_vulnerability(composite.description(), composite.get_criticality());
+        results.add_vulnerability(composite);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorrelationRule;
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the matching of correlation rules.
+    #[test]
+    fn it_matches() {
+        let rule: CorrelationRule = toml::from_str(
+            r#"
+                name = "Data-at-rest Exposure"
+                description = "Backups expose world readable files."
+                criticality = "critical"
+                all_of = ["allows backup", "world-readable-permissions"]
+                escalate = true
+            "#,
+        )
+        .unwrap();
+        let backup = Vulnerability::builder(Criticality::Medium, "Allows Backup", "").build();
+        let readable = Vulnerability::builder(Criticality::Low, "World readable permissions", "")
+            .file("classes/com/example/Storage.java")
+            .rule_id("world-readable-permissions")
+            .build();
+        let other = Vulnerability::builder(Criticality::High, "Weak Algorithms", "").build();
+
+        let vulnerabilities = vec![&backup, &readable, &other];
+        let ids = rule.matches(&vulnerabilities).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&backup.id()));
+        assert!(ids.contains(&readable.id()));
+
+        assert!(rule.matches(&vulnerabilities[1..]).is_none());
+        assert_eq!(rule.criticality(), Criticality::Critical);
+    }
+}
@@ -1,24 +1,36 @@
 //! Decompilation module.
 //!
-//! Handles the extraction, decompression and  decompilation of `_.apks_`
+//! Handles the extraction, decompression and  decompilation of `_.apks_`, Android App Bundles
+//! and split APK sets. The JAR files and the decompiled sources are reused from the decompilation
+//! cache when they are available.
 
-use std::{fs, path::Path, process::Command};
+use std::{fmt, fs, path::Path, str::FromStr};
 
 use abxml::apk::Apk;
 use colored::Colorize;
 use failure::{bail, format_err, Error, ResultExt};
 
-use crate::{get_package_name, print_warning, Config};
+use crate::{
+    bundle::{self, PackageFormat},
+    cache::CacheEntry,
+    cancellation, error,
+    i18n::{tr, tr_args},
+    print_message, print_warning,
+    results::DexFiles,
+    Config,
+};
 
 /// Decompresses the application using `_Apktool_`.
 pub fn decompress<P: AsRef<Path>>(config: &mut Config, package: P) -> Result<(), Error> {
     let path = config
         .dist_folder()
-        .join(package.as_ref().file_stem().unwrap());
+        .join(config.package_name(package.as_ref()));
     if !path.exists() || config.is_force() {
         if path.exists() {
             if config.is_verbose() {
-                println!("The application decompression folder exists. But no more…");
+                print_message(tr(
+                    "The application decompression folder exists. But no more…",
+                ));
             }
 
             if let Err(e) = fs::remove_dir_all(&path) {
@@ -31,157 +43,545 @@ pub fn decompress<P: AsRef<Path>>(config: &mut Config, package: P) -> Result<(),
         config.set_force();
 
         if config.is_verbose() {
-            println!();
-            println!("Decompressing the application…");
+            print_message("");
+            print_message(tr("Decompressing the application…"));
         }
 
-        let mut apk = Apk::from_path(package.as_ref()).context("error loading apk file")?;
-        apk.export(&path, true).context(format_err!(
-            "could not decompress the apk file. Tried to decompile at: {}",
-            path.display()
-        ))?;
+        match PackageFormat::of(package.as_ref()) {
+            PackageFormat::Apk => {
+                let mut apk = Apk::from_path(package.as_ref())
+                    .context("error loading apk file")
+                    .context(unpack_error(&path))?;
+                apk.export(&path, true)
+                    .context(format_err!(
+                        "could not decompress the apk file. Tried to decompile at: {}",
+                        path.display()
+                    ))
+                    .context(unpack_error(&path))?;
+            }
+            PackageFormat::Bundle => {
+                bundle::extract_bundle(package.as_ref(), &path)
+                    .context(format_err!(
+                        "could not extract the app bundle. Tried to extract at: {}",
+                        path.display()
+                    ))
+                    .context(unpack_error(&path))?;
+                print_warning(
+                    "the manifest and the resources of app bundles are compiled in the protocol \
+                     buffer format, so the manifest analysis will not be able to read them",
+                );
+            }
+            PackageFormat::SplitSet => {
+                bundle::extract_split_set(package.as_ref(), &path)
+                    .context(format_err!(
+                        "could not merge the split APK set. Tried to decompile at: {}",
+                        path.display()
+                    ))
+                    .context(unpack_error(&path))?;
+            }
+        }
 
         if config.is_verbose() {
-            println!(
-                "{}",
-                format!(
+            print_message(
+                tr_args(
                     "The application has been decompressed in {}.",
-                    path.display()
+                    &[&path.display()],
                 )
-                .green()
+                .green(),
             );
         } else if !config.is_quiet() {
-            println!("Application decompressed.");
+            print_message(tr("Application decompressed."));
         }
     } else if config.is_verbose() {
-        println!(
-            "Seems that the application has already been decompressed. There is no need to do it \
-             again."
-        );
+        print_message(tr(
+            "Seems that the application has already been decompressed. There is no need to do \
+             it again.",
+        ));
     } else {
-        println!("Skipping decompression.");
+        print_message(tr("Skipping decompression."));
     }
 
     Ok(())
 }
 
-/// Converts `_.dex_` files to `_.jar_` using `_Dex2jar_`.
-pub fn dex_to_jar<P: AsRef<Path>>(config: &mut Config, package: P) -> Result<(), Error> {
-    let package_name = get_package_name(package.as_ref());
-    let classes = config.dist_folder().join(&package_name).join("classes.jar");
-    if config.is_force() || !classes.exists() {
+/// Creates the error of an application that could not be unpacked at the given path.
+fn unpack_error(path: &Path) -> error::Kind {
+    error::Kind::UnpackError {
+        path: path.display().to_string(),
+    }
+}
+
+/// Converts every `_classes*.dex_` file to `_.jar_` using `_Dex2jar_`.
+///
+/// Each DEX file is converted to a JAR file with the same name. The conversion only fails if no
+/// DEX file could be converted, and the DEX files that could not be converted are returned.
+pub fn dex_to_jar<P: AsRef<Path>>(config: &mut Config, package: P) -> Result<DexFiles, Error> {
+    let package_name = config.package_name(package.as_ref());
+    let dist_folder = config.dist_folder().join(&package_name);
+    let dex_files = list_dex_files(&dist_folder)?;
+    if dex_files.is_empty() {
+        bail!(
+            "the application does not contain any {} file",
+            ".dex".italic()
+        );
+    }
+
+    let mut result = DexFiles::new(dex_files.len());
+    let force = config.is_force()
+        || dex_files
+            .iter()
+            .any(|dex| !dist_folder.join(jar_name(dex)).exists());
+    if force {
         config.set_force();
 
-        // Command to convert .dex to .jar. using dex2jar.
-        // "-o path" to specify an output file
-        let output = Command::new(config.dex2jar_folder().join(
-            if cfg!(target_family = "windows") {
-                "d2j-dex2jar.bat"
-            } else {
-                "d2j-dex2jar.sh"
-            },
-        ))
-        .arg(config.dist_folder().join(&package_name).join("classes.dex"))
-        .arg("-f")
-        .arg("-o")
-        .arg(&classes)
-        .output()
-        .context(format_err!(
-            "there was an error when executing the {} to {} conversion command",
-            ".dex".italic(),
-            ".jar".italic()
-        ))?;
-
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Here a small hack: seems that dex2jar outputs in stderr even if everything went well,
-        // and the status is always success. So the only difference is if we detect the actual
-        // exception that was produced. But in some cases it does not return an exception, so we
-        // have to check if errors such as "use certain option" occur.
-        let mut call_ok = output.status.success() || !stderr.contains("use");
-        if stderr.find('\n') != Some(stderr.len() - 1) {
-            if stderr.starts_with("Picked up _JAVA_OPTIONS:") {
-                call_ok = stderr.lines().count() == 2;
-            } else {
-                call_ok = false;
-            }
-        }
-        if !call_ok {
-            bail!(
-                "the {} to {} conversion command returned an error. More info: {}",
-                ".dex".italic(),
-                ".jar".italic(),
-                stderr
-            );
+        let jars = dex_files
+            .iter()
+            .map(|dex| jar_name(dex))
+            .collect::<Vec<_>>();
+        let cache = cache_entry(config, package.as_ref());
+        if let Some(ref cache) = cache {
+            match cache.restore_jars(&jars, &dist_folder) {
+                Ok(true) => {
+                    if !config.is_quiet() {
+                        print_message(tr_args(
+                            "The {} files were restored from the decompilation cache.",
+                            &[&".jar".italic()],
+                        ));
+                    }
+                    return Ok(result);
+                }
+                Ok(false) => {}
+                Err(e) => print_warning(format!(
+                    "the {} files could not be restored from the decompilation cache: {}",
+                    ".jar".italic(),
+                    e
+                )),
+            }
         }
 
-        if config.is_verbose() {
-            println!(
-                "{}",
-                format!(
-                    "The application {} {} {}",
+        let mut first_error = None;
+        for (i, dex) in dex_files.iter().enumerate() {
+            if !config.is_quiet() && dex_files.len() > 1 {
+                print_message(tr_args(
+                    "Converting {} ({}/{})…",
+                    &[&dex.italic(), &(i + 1), &dex_files.len()],
+                ));
+            }
+            let conversion = convert_dex(config, &dist_folder, dex);
+            if cancellation::is_cancelled() {
+                // The JAR file might be incomplete, so it is not reused by the next analysis.
+                let _ = fs::remove_file(dist_folder.join(jar_name(dex)));
+                return Err(error::Kind::Cancelled.into());
+            }
+            if let Err(e) = conversion {
+                print_warning(format!(
+                    "{} could not be converted, its code will not be decompiled: {}",
+                    dex, e
+                ));
+                result.add_failure(dex.as_str(), &e);
+                let _ = first_error.get_or_insert(e);
+            }
+        }
+        if result.processed() == 0 {
+            return Err(first_error.expect("expected a failure for each DEX file"));
+        }
+        if let (Some(cache), 0) = (cache, result.failed().count()) {
+            if let Err(e) = cache.store_jars(&jars, &dist_folder) {
+                print_warning(format!(
+                    "the {} files could not be stored in the decompilation cache: {}",
                     ".jar".italic(),
-                    "file has been generated in".green(),
-                    format!("{}", classes.display()).green()
+                    e
+                ));
+            }
+        }
+
+        if config.is_verbose() {
+            print_message(
+                tr_args(
+                    "The application {} file has been generated in {}.",
+                    &[&".jar".italic(), &dist_folder.display()],
                 )
-                .green()
+                .green(),
             );
         } else if !config.is_quiet() {
-            println!("Jar file generated.");
+            print_message(tr("Jar file generated."));
         }
     } else if config.is_verbose() {
-        println!(
+        print_message(tr_args(
             "Seems that there is already a {} file for the application. There is no need to \
              create it again.",
-            ".jar".italic()
-        );
+            &[&".jar".italic()],
+        ));
     } else {
-        println!("Skipping {} file generation.", ".jar".italic());
+        print_message(tr_args("Skipping {} file generation.", &[&".jar".italic()]));
+    }
+
+    Ok(result)
+}
+
+/// Converts the given DEX file of the given distribution folder to a JAR file.
+fn convert_dex(config: &Config, dist_folder: &Path, dex: &str) -> Result<(), Error> {
+    // Command to convert .dex to .jar. using dex2jar.
+    // "-o path" to specify an output file
+    let script = config
+        .dex2jar_folder()
+        .join(if cfg!(target_family = "windows") {
+            "d2j-dex2jar.bat"
+        } else {
+            "d2j-dex2jar.sh"
+        });
+    if !script.exists() {
+        return Err(error::Kind::ToolMissing {
+            tool: "dex2jar".to_owned(),
+            path: script.display().to_string(),
+        }
+        .into());
+    }
+    let mut command = config.limits().command(script);
+    let _ = command
+        .arg(dist_folder.join(dex))
+        .arg("-f")
+        .arg("-o")
+        .arg(dist_folder.join(jar_name(dex)));
+    let output = config.limits().output(&mut command).context(format_err!(
+        "there was an error when executing the {} to {} conversion command",
+        ".dex".italic(),
+        ".jar".italic()
+    ))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Here a small hack: seems that dex2jar outputs in stderr even if everything went well,
+    // and the status is always success. So the only difference is if we detect the actual
+    // exception that was produced. But in some cases it does not return an exception, so we
+    // have to check if errors such as "use certain option" occur.
+    let mut call_ok = output.status.success() || !stderr.contains("use");
+    if stderr.find('\n') != Some(stderr.len() - 1) {
+        if stderr.starts_with("Picked up _JAVA_OPTIONS:") {
+            call_ok = stderr.lines().count() == 2;
+        } else {
+            call_ok = false;
+        }
+    }
+    if !call_ok {
+        return Err(error::Kind::ToolFailed {
+            tool: "dex2jar".to_owned(),
+            stderr: stderr.into_owned(),
+        }
+        .into());
     }
 
     Ok(())
 }
 
-/// Decompiles the application using `_jd\_cmd_`.
-pub fn decompile<P: AsRef<Path>>(config: &mut Config, package: P) -> Result<(), Error> {
-    let package_name = get_package_name(package.as_ref());
-    let out_path = config.dist_folder().join(&package_name).join("classes");
+/// Decompiles the JAR files of the application using the configured decompiler.
+///
+/// If the decompiler fails or does not produce any source file for a JAR file, the rest of the
+/// available decompilers are tried, in order. The DEX files whose JAR files could not be
+/// decompiled are added to the failures of the given DEX files, and the decompilation only fails
+/// if no JAR file could be decompiled.
+pub fn decompile<P: AsRef<Path>>(
+    config: &mut Config,
+    package: P,
+    dex_files: &mut DexFiles,
+) -> Result<(), Error> {
+    let package_name = config.package_name(package.as_ref());
+    let dist_folder = config.dist_folder().join(&package_name);
+    let out_path = dist_folder.join("classes");
     if config.is_force() || !out_path.exists() {
         config.set_force();
 
-        // Command to decompile the application using `jd_cmd`.
-        // "-od path" to specify an output directory
-        let output = Command::new("java")
-            .arg("-jar")
-            .arg(config.jd_cmd_file())
-            .arg(config.dist_folder().join(&package_name).join("classes.jar"))
-            .arg("-od")
-            .arg(&out_path)
-            .output()
-            .context("there was an unknown error decompiling the application")?;
+        if out_path.exists() {
+            fs::remove_dir_all(&out_path).context(format_err!(
+                "could not remove the previous sources at `{}`",
+                out_path.display()
+            ))?;
+        }
+        let cache = cache_entry(config, package.as_ref());
+        if let Some(ref cache) = cache {
+            match cache.restore_sources(config.decompiler(), &out_path) {
+                Ok(true) => {
+                    if !config.is_quiet() {
+                        print_message(tr(
+                            "The sources were restored from the decompilation cache.",
+                        ));
+                    }
+                    return Ok(());
+                }
+                Ok(false) => {}
+                Err(e) => print_warning(format!(
+                    "the sources could not be restored from the decompilation cache: {}",
+                    e
+                )),
+            }
+        }
+        let mut decompilers = vec![config.decompiler()];
+        decompilers.extend(Decompiler::ALL.iter().cloned().filter(|&decompiler| {
+            decompiler != config.decompiler() && config.decompiler_file(decompiler).exists()
+        }));
 
-        if !output.status.success() {
-            bail!(
-                "the decompilation command returned an error. More info:\n{}",
-                String::from_utf8_lossy(&output.stdout)
-            );
+        let jars = list_dex_files(&dist_folder)?
+            .into_iter()
+            .filter(|dex| dist_folder.join(jar_name(dex)).exists())
+            .collect::<Vec<_>>();
+        let mut decompiled = 0;
+        let mut last_error = None;
+        for (i, dex) in jars.iter().enumerate() {
+            let jar = jar_name(dex);
+            if !config.is_quiet() && jars.len() > 1 {
+                print_message(tr_args(
+                    "Decompiling {} ({}/{})…",
+                    &[&jar.italic(), &(i + 1), &jars.len()],
+                ));
+            }
+
+            let mut result = Ok(());
+            for (j, &decompiler) in decompilers.iter().enumerate() {
+                result = decompiler.decompile(config, &dist_folder.join(&jar), &out_path);
+                if cancellation::is_cancelled() {
+                    break;
+                }
+                match (&result, decompilers.get(j + 1)) {
+                    (Ok(()), _) => break,
+                    (Err(e), Some(next)) => print_warning(format!(
+                        "the {} decompiler failed for {}, trying with {}: {}",
+                        decompiler, jar, next, e
+                    )),
+                    (Err(_), None) => {}
+                }
+            }
+            if cancellation::is_cancelled() {
+                // The sources might be incomplete, so they are not reused by the next analysis.
+                let _ = fs::remove_dir_all(&out_path);
+                return Err(error::Kind::Cancelled.into());
+            }
+            match result {
+                Ok(()) => decompiled += 1,
+                Err(e) => {
+                    print_warning(format!(
+                        "{} could not be decompiled, its code will not be analyzed: {}",
+                        jar, e
+                    ));
+                    dex_files.add_failure(dex.as_str(), &e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        if decompiled == 0 {
+            match last_error {
+                Some(e) => return Err(e),
+                None => bail!("there is no {} file to decompile", ".jar".italic()),
+            }
+        }
+        if let (Some(cache), true) = (cache, decompiled == jars.len()) {
+            if let Err(e) = cache.store_sources(config.decompiler(), &out_path) {
+                print_warning(format!(
+                    "the sources could not be stored in the decompilation cache: {}",
+                    e
+                ));
+            }
         }
 
         if config.is_verbose() {
-            println!(
-                "{}",
-                "The application has been successfully decompiled!".green()
-            );
+            print_message(tr("The application has been successfully decompiled!").green());
         } else if !config.is_quiet() {
-            println!("Application decompiled.");
+            print_message(tr("Application decompiled."));
         }
     } else if config.is_verbose() {
-        println!(
-            "Seems that there is already a source folder for the application. There is no need to \
-             decompile it again."
-        );
+        print_message(tr(
+            "Seems that there is already a source folder for the application. There is no \
+             need to decompile it again.",
+        ));
     } else {
-        println!("Skipping decompilation.");
+        print_message(tr("Skipping decompilation."));
     }
 
     Ok(())
 }
+
+/// Gets the decompilation cache entry of the given package, if the cache is enabled.
+///
+/// If the package cannot be read, the cache is not used.
+fn cache_entry(config: &Config, package: &Path) -> Option<CacheEntry> {
+    CacheEntry::of(config, package).unwrap_or_else(|e| {
+        print_warning(format!("the decompilation cache will not be used: {}", e));
+        None
+    })
+}
+
+/// Lists the `classes*.dex` files of the given distribution folder, in order.
+fn list_dex_files(dist_folder: &Path) -> Result<Vec<String>, Error> {
+    let mut dex_files = fs::read_dir(dist_folder)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|name| bundle::dex_index(&name).map(|index| (index, name)))
+        .collect::<Vec<_>>();
+    dex_files.sort();
+    Ok(dex_files.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Gets the name of the JAR file of the given DEX file.
+fn jar_name(dex: &str) -> String {
+    format!("{}.jar", dex.trim_end_matches(".dex"))
+}
+
+/// Decompiler used to get the Java sources of the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Decompiler {
+    /// _JD-CMD_, the command line version of _JD-GUI_.
+    JdCmd,
+    /// _jadx_, that also handles some obfuscated code.
+    Jadx,
+    /// _CFR_, that supports modern Java features.
+    Cfr,
+    /// _Procyon_.
+    Procyon,
+}
+
+impl Decompiler {
+    /// All the decompilers, in fallback order.
+    pub const ALL: [Decompiler; 4] = [
+        Decompiler::JdCmd,
+        Decompiler::Jadx,
+        Decompiler::Cfr,
+        Decompiler::Procyon,
+    ];
+
+    /// Decompiles the given JAR file to the given output folder.
+    ///
+    /// Fails if the decompiler returns an error or if it does not add any source file to the output
+    /// folder.
+    fn decompile(self, config: &Config, jar: &Path, out_path: &Path) -> Result<(), Error> {
+        let sources = count_sources(out_path);
+        let file = config.decompiler_file(self);
+        if !file.exists() {
+            return Err(error::Kind::ToolMissing {
+                tool: self.to_string(),
+                path: file.display().to_string(),
+            }
+            .into());
+        }
+        let mut command = if self == Decompiler::Jadx {
+            config.limits().command(file)
+        } else {
+            let mut command = config.limits().command("java");
+            let _ = command.arg("-jar").arg(file);
+            command
+        };
+        let _ = match self {
+            Decompiler::JdCmd => command.arg(jar).arg("-od").arg(out_path),
+            Decompiler::Jadx => command
+                .arg("--no-res")
+                .arg("--output-dir-src")
+                .arg(out_path)
+                .arg(jar),
+            Decompiler::Cfr => command.arg(jar).arg("--outputdir").arg(out_path),
+            Decompiler::Procyon => command.arg("-jar").arg(jar).arg("-o").arg(out_path),
+        };
+
+        let output = config
+            .limits()
+            .output(&mut command)
+            .context(format_err!("there was an unknown error running {}", self))?;
+        if !output.status.success() {
+            return Err(error::Kind::ToolFailed {
+                tool: self.to_string(),
+                stderr: format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            }
+            .into());
+        }
+        if count_sources(out_path) <= sources {
+            bail!("the decompilation command did not produce any source file");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Decompiler {
+    fn default() -> Self {
+        Decompiler::JdCmd
+    }
+}
+
+impl FromStr for Decompiler {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jd-cmd" => Ok(Decompiler::JdCmd),
+            "jadx" => Ok(Decompiler::Jadx),
+            "cfr" => Ok(Decompiler::Cfr),
+            "procyon" => Ok(Decompiler::Procyon),
+            _ => Err(format_err!(
+                "unknown decompiler `{}`, it must be `jd-cmd`, `jadx`, `cfr` or `procyon`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Decompiler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Decompiler::JdCmd => "jd-cmd",
+                Decompiler::Jadx => "jadx",
+                Decompiler::Cfr => "CFR",
+                Decompiler::Procyon => "Procyon",
+            }
+        )
+    }
+}
+
+/// Counts the Java source files in the given folder, recursively.
+fn count_sources(path: &Path) -> usize {
+    fs::read_dir(path).map_or(0, |entries| {
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let path = entry.path();
+                if path.is_dir() {
+                    count_sources(&path)
+                } else if path
+                    .extension()
+                    .map_or(false, |extension| extension == "java")
+                {
+                    1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jar_name, Decompiler};
+
+    /// Checks the parsing of the decompiler names.
+    #[test]
+    fn it_decompiler_from_str() {
+        assert_eq!("jd-cmd".parse::<Decompiler>().unwrap(), Decompiler::JdCmd);
+        assert_eq!("JADX".parse::<Decompiler>().unwrap(), Decompiler::Jadx);
+        assert_eq!("cfr".parse::<Decompiler>().unwrap(), Decompiler::Cfr);
+        assert_eq!(
+            "procyon".parse::<Decompiler>().unwrap(),
+            Decompiler::Procyon
+        );
+        assert!("fernflower".parse::<Decompiler>().is_err());
+    }
+
+    /// Checks the names of the JAR files of the DEX files.
+    #[test]
+    fn it_jar_name() {
+        assert_eq!(jar_name("classes.dex"), "classes.jar");
+        assert_eq!(jar_name("classes12.dex"), "classes12.jar");
+    }
+}
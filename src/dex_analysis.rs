@@ -0,0 +1,1068 @@
+//! DEX analysis module.
+//!
+//! The code of the application is usually analyzed in the Java source code generated by
+//! `dex2jar` and `jd-cli`. When the Java toolchain is missing, or any of those tools fails, this
+//! module parses the DEX files directly and generates a listing of each class, with a Java-like
+//! syntax, containing its methods, the string constants they use and the methods they invoke:
+//!
+//! ```java
+//! package com.example;
+//!
+//! import java.security.MessageDigest;
+//!
+//! class Hash extends Object {
+//!     byte[] digest(byte[]) {
+//!         MessageDigest.getInstance("MD5").digest(v2);
+//!     }
+//! }
+//! ```
+//!
+//! The listings are stored in the `dex_listing` folder of the distribution folder, so that the
+//! code analysis rules run on them. They are not a decompilation of the code: only invocations,
+//! object creations, string constants and static field reads are listed, so the rules that depend
+//! on the control flow of the code will not match.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::OsStr,
+    fs,
+};
+
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::Config;
+
+/// Name of the folder where the listings are stored, in the distribution folder.
+pub const LISTING_FOLDER: &str = "dex_listing";
+
+/// Size of the header of DEX files.
+const HEADER_SIZE: usize = 0x70;
+
+/// Endianness tag of little endian DEX files.
+const ENDIAN_CONSTANT: u32 = 0x1234_5678;
+
+/// Value of absent indexes.
+const NO_INDEX: u32 = 0xffff_ffff;
+
+/// Access flag of static methods.
+const ACC_STATIC: u32 = 0x8;
+
+/// Packages whose classes are not listed, since they are part of the Android framework and Google
+/// Play services.
+const IGNORED_PACKAGES: [&str; 2] = ["Landroid/", "Lcom/google/android/gms/"];
+
+/// Generates the listings of the classes of every DEX file of the application.
+///
+/// Returns the number of classes listed.
+pub fn generate_listings<S: AsRef<str>>(config: &Config, package: S) -> Result<usize, Error> {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let listing_folder = dist_folder.join(LISTING_FOLDER);
+    remove_listings(config, package.as_ref())?;
+
+    let mut dex_files = fs::read_dir(&dist_folder)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(OsStr::to_str)
+                .map_or(false, |name| {
+                    name.starts_with("classes") && name.ends_with(".dex")
+                })
+        })
+        .collect::<Vec<_>>();
+    if dex_files.is_empty() {
+        bail!("the application does not contain any DEX file");
+    }
+    dex_files.sort();
+
+    let mut classes = 0;
+    for path in dex_files {
+        let dex = DexFile::parse(&fs::read(&path)?)
+            .context(format_err!("could not parse `{}`", path.display()))?;
+        for class in dex.classes.iter().filter(|class| {
+            let descriptor = dex.type_descriptor(class.class);
+            !IGNORED_PACKAGES
+                .iter()
+                .any(|package| descriptor.starts_with(package))
+        }) {
+            let descriptor = dex.type_descriptor(class.class);
+            let file = listing_folder.join(format!("{}.java", class_name(descriptor)));
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file, render_class(&dex, class)).context(format_err!(
+                "could not write the listing `{}`",
+                file.display()
+            ))?;
+            classes += 1;
+        }
+    }
+
+    Ok(classes)
+}
+
+/// Removes the listings of the application, if any.
+pub fn remove_listings<S: AsRef<str>>(config: &Config, package: S) -> Result<(), Error> {
+    let listing_folder = config
+        .dist_folder()
+        .join(package.as_ref())
+        .join(LISTING_FOLDER);
+    if listing_folder.exists() {
+        fs::remove_dir_all(&listing_folder).context(format_err!(
+            "could not remove the DEX listing folder `{}`",
+            listing_folder.display()
+        ))?;
+    }
+    Ok(())
+}
+
+/// Parsed DEX file.
+#[derive(Debug, Default)]
+struct DexFile {
+    /// String constants.
+    strings: Vec<String>,
+    /// Type descriptors, as indexes of the strings.
+    types: Vec<u32>,
+    /// Method prototypes.
+    protos: Vec<Proto>,
+    /// Field references.
+    fields: Vec<FieldRef>,
+    /// Method references.
+    methods: Vec<MethodRef>,
+    /// Classes defined in the file.
+    classes: Vec<ClassDef>,
+}
+
+/// Method prototype.
+#[derive(Debug)]
+struct Proto {
+    /// Return type.
+    return_type: u32,
+    /// Parameter types.
+    parameters: Vec<u32>,
+}
+
+/// Field reference.
+#[derive(Debug)]
+struct FieldRef {
+    /// Type of the class of the field.
+    class: u32,
+    /// Name of the field.
+    name: u32,
+}
+
+/// Method reference.
+#[derive(Debug)]
+struct MethodRef {
+    /// Type of the class of the method.
+    class: u32,
+    /// Prototype of the method.
+    proto: u32,
+    /// Name of the method.
+    name: u32,
+}
+
+/// Class definition.
+#[derive(Debug)]
+struct ClassDef {
+    /// Type of the class.
+    class: u32,
+    /// Type of the superclass, if any.
+    superclass: Option<u32>,
+    /// Methods of the class.
+    methods: Vec<Method>,
+}
+
+/// Method of a class.
+#[derive(Debug)]
+struct Method {
+    /// Method reference.
+    method: u32,
+    /// Access flags.
+    access_flags: u32,
+    /// Code of the method, if it is not abstract or native.
+    code: Option<Code>,
+}
+
+/// Code of a method.
+#[derive(Debug)]
+struct Code {
+    /// Number of registers.
+    registers_size: u16,
+    /// Number of registers of the parameters, including the receiver.
+    ins_size: u16,
+    /// Instructions, in 16-bit code units.
+    insns: Vec<u16>,
+}
+
+impl DexFile {
+    /// Parses the given DEX file.
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < HEADER_SIZE || &data[..4] != b"dex\n" || data[7] != 0 {
+            bail!("it is not a DEX file");
+        }
+        if read_u32(data, 40)? != ENDIAN_CONSTANT {
+            bail!("big endian DEX files are not supported");
+        }
+
+        let table = |offset| -> Result<(usize, usize), Error> {
+            Ok((
+                read_u32(data, offset)? as usize,
+                read_u32(data, offset + 4)? as usize,
+            ))
+        };
+        let (strings_size, strings_off) = table(56)?;
+        let (types_size, types_off) = table(64)?;
+        let (protos_size, protos_off) = table(72)?;
+        let (fields_size, fields_off) = table(80)?;
+        let (methods_size, methods_off) = table(88)?;
+        let (classes_size, classes_off) = table(96)?;
+
+        let mut dex = Self::default();
+        for i in 0..strings_size {
+            let mut offset = read_u32(data, strings_off + i * 4)? as usize;
+            let _utf16_size = read_uleb128(data, &mut offset)?;
+            dex.strings.push(decode_mutf8(&data[offset..]));
+        }
+        for i in 0..types_size {
+            dex.types.push(read_u32(data, types_off + i * 4)?);
+        }
+        for i in 0..protos_size {
+            let offset = protos_off + i * 12;
+            let parameters_off = read_u32(data, offset + 8)? as usize;
+            let mut parameters = Vec::new();
+            if parameters_off != 0 {
+                for j in 0..read_u32(data, parameters_off)? as usize {
+                    parameters.push(u32::from(read_u16(data, parameters_off + 4 + j * 2)?));
+                }
+            }
+            dex.protos.push(Proto {
+                return_type: read_u32(data, offset + 4)?,
+                parameters,
+            });
+        }
+        for i in 0..fields_size {
+            let offset = fields_off + i * 8;
+            dex.fields.push(FieldRef {
+                class: u32::from(read_u16(data, offset)?),
+                name: read_u32(data, offset + 4)?,
+            });
+        }
+        for i in 0..methods_size {
+            let offset = methods_off + i * 8;
+            dex.methods.push(MethodRef {
+                class: u32::from(read_u16(data, offset)?),
+                proto: u32::from(read_u16(data, offset + 2)?),
+                name: read_u32(data, offset + 4)?,
+            });
+        }
+        for i in 0..classes_size {
+            let offset = classes_off + i * 32;
+            let superclass = read_u32(data, offset + 8)?;
+            let class_data_off = read_u32(data, offset + 24)? as usize;
+            dex.classes.push(ClassDef {
+                class: read_u32(data, offset)?,
+                superclass: if superclass == NO_INDEX {
+                    None
+                } else {
+                    Some(superclass)
+                },
+                methods: if class_data_off == 0 {
+                    Vec::new()
+                } else {
+                    parse_class_data(data, class_data_off)?
+                },
+            });
+        }
+
+        Ok(dex)
+    }
+
+    /// Gets the string with the given index.
+    fn string(&self, index: u32) -> &str {
+        self.strings.get(index as usize).map_or("", String::as_str)
+    }
+
+    /// Gets the descriptor of the type with the given index.
+    fn type_descriptor(&self, index: u32) -> &str {
+        self.types
+            .get(index as usize)
+            .map_or("", |&string| self.string(string))
+    }
+}
+
+/// Parses the class data item at the given offset, returning the methods of the class.
+fn parse_class_data(data: &[u8], mut offset: usize) -> Result<Vec<Method>, Error> {
+    let static_fields = read_uleb128(data, &mut offset)?;
+    let instance_fields = read_uleb128(data, &mut offset)?;
+    let direct_methods = read_uleb128(data, &mut offset)?;
+    let virtual_methods = read_uleb128(data, &mut offset)?;
+    let fields = static_fields
+        .checked_add(instance_fields)
+        .and_then(|fields| fields.checked_mul(2))
+        .ok_or_else(|| format_err!("invalid number of fields at offset {}", offset))?;
+    for _ in 0..fields {
+        let _ = read_uleb128(data, &mut offset)?;
+    }
+
+    let mut methods = Vec::new();
+    for &count in &[direct_methods, virtual_methods] {
+        let mut method: u32 = 0;
+        for _ in 0..count {
+            method = method
+                .checked_add(read_uleb128(data, &mut offset)?)
+                .ok_or_else(|| format_err!("invalid method index at offset {}", offset))?;
+            let access_flags = read_uleb128(data, &mut offset)?;
+            let code_off = read_uleb128(data, &mut offset)? as usize;
+            let code = if code_off == 0 {
+                None
+            } else {
+                let insns_size = read_u32(data, code_off + 12)? as usize;
+                // The size is checked before allocating, since it comes from the file.
+                let fits = insns_size
+                    .checked_mul(2)
+                    .and_then(|size| size.checked_add(code_off))
+                    .and_then(|end| end.checked_add(16))
+                    .map_or(false, |end| end <= data.len());
+                if !fits {
+                    bail!("the DEX file is truncated at offset {}", code_off);
+                }
+                let mut insns = Vec::with_capacity(insns_size);
+                for i in 0..insns_size {
+                    insns.push(read_u16(data, code_off + 16 + i * 2)?);
+                }
+                Some(Code {
+                    registers_size: read_u16(data, code_off)?,
+                    ins_size: read_u16(data, code_off + 2)?,
+                    insns,
+                })
+            };
+            methods.push(Method {
+                method,
+                access_flags,
+                code,
+            });
+        }
+    }
+
+    Ok(methods)
+}
+
+/// Reads a little endian 16-bit integer at the given offset.
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from(bytes[0]) | u16::from(bytes[1]) << 8)
+        .ok_or_else(|| format_err!("the DEX file is truncated at offset {}", offset))
+}
+
+/// Reads a little endian 32-bit integer at the given offset.
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    Ok(u32::from(read_u16(data, offset)?) | u32::from(read_u16(data, offset + 2)?) << 16)
+}
+
+/// Reads an unsigned LEB128 integer at the given offset, advancing it.
+fn read_uleb128(data: &[u8], offset: &mut usize) -> Result<u32, Error> {
+    let mut result = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *data
+            .get(*offset)
+            .ok_or_else(|| format_err!("the DEX file is truncated at offset {}", offset))?;
+        *offset += 1;
+        result |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// Decodes a null-terminated string in the modified UTF-8 encoding of DEX files.
+fn decode_mutf8(data: &[u8]) -> String {
+    let mut units = Vec::new();
+    let mut i = 0;
+    while let Some(&byte) = data.get(i) {
+        let byte = u16::from(byte);
+        let next = |n: usize| data.get(i + n).map_or(0, |&b| u16::from(b) & 0x3f);
+        if byte == 0 {
+            break;
+        } else if byte < 0x80 {
+            units.push(byte);
+            i += 1;
+        } else if byte & 0xe0 == 0xc0 {
+            units.push((byte & 0x1f) << 6 | next(1));
+            i += 2;
+        } else if byte & 0xf0 == 0xe0 {
+            units.push((byte & 0x0f) << 12 | next(1) << 6 | next(2));
+            i += 3;
+        } else {
+            units.push(0xfffd);
+            i += 1;
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// Converts a type descriptor to its Java name, such as `java.lang.String[]` for
+/// `[Ljava/lang/String;`.
+fn java_type(descriptor: &str) -> String {
+    let dimensions = descriptor.chars().take_while(|&c| c == '[').count();
+    let mut name = match &descriptor[dimensions..] {
+        "V" => String::from("void"),
+        "Z" => String::from("boolean"),
+        "B" => String::from("byte"),
+        "S" => String::from("short"),
+        "C" => String::from("char"),
+        "I" => String::from("int"),
+        "J" => String::from("long"),
+        "F" => String::from("float"),
+        "D" => String::from("double"),
+        class => class_name(class).replace('/', ".").replace('$', "."),
+    };
+    for _ in 0..dimensions {
+        name.push_str("[]");
+    }
+    name
+}
+
+/// Gets the binary name of the given class descriptor, such as `java/lang/String` for
+/// `Ljava/lang/String;`.
+fn class_name(descriptor: &str) -> &str {
+    if descriptor.starts_with('L') && descriptor.ends_with(';') {
+        &descriptor[1..descriptor.len() - 1]
+    } else {
+        descriptor
+    }
+}
+
+/// Gets the simple Java name of the given type descriptor, without its package.
+fn simple_name(descriptor: &str) -> String {
+    let java_type = java_type(descriptor);
+    match class_name(descriptor.trim_start_matches('[')).rfind('/') {
+        Some(i) => java_type[i + 1..].to_owned(),
+        None => java_type,
+    }
+}
+
+/// Gets the package of the given class descriptor.
+fn package_of(descriptor: &str) -> String {
+    let class = class_name(descriptor.trim_start_matches('['));
+    class[..class.rfind('/').unwrap_or(0)].replace('/', ".")
+}
+
+/// Renders the listing of the given class.
+fn render_class(dex: &DexFile, class: &ClassDef) -> String {
+    let descriptor = dex.type_descriptor(class.class);
+    let package = package_of(descriptor);
+    let mut imports = BTreeSet::new();
+    let mut body = String::new();
+
+    for method in &class.methods {
+        let reference = match dex.methods.get(method.method as usize) {
+            Some(reference) => reference,
+            None => continue,
+        };
+        let is_static = method.access_flags & ACC_STATIC != 0;
+        let name = dex.string(reference.name);
+        let proto = dex.protos.get(reference.proto as usize);
+        let parameters = proto.map_or_else(String::new, |proto| {
+            proto
+                .parameters
+                .iter()
+                .map(|&parameter| simple_name(dex.type_descriptor(parameter)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let header = match name {
+            "<clinit>" => String::from("static"),
+            "<init>" => format!("{}({})", simple_name(descriptor), parameters),
+            _ => format!(
+                "{}{} {}({})",
+                if is_static { "static " } else { "" },
+                proto.map_or_else(
+                    || String::from("void"),
+                    |proto| simple_name(dex.type_descriptor(proto.return_type))
+                ),
+                name,
+                parameters
+            ),
+        };
+
+        match &method.code {
+            Some(code) => {
+                body.push_str(&format!("    {} {{\n", header));
+                for statement in render_code(dex, code, is_static, &mut imports) {
+                    body.push_str(&format!("        {};\n", statement));
+                }
+                body.push_str("    }\n");
+            }
+            None => body.push_str(&format!("    {};\n", header)),
+        }
+    }
+
+    let mut listing = String::from(
+        "// Listing generated from the DEX code by SUPER, since the code could not be decompiled.\n",
+    );
+    if !package.is_empty() {
+        listing.push_str(&format!("package {};\n", package));
+    }
+    listing.push('\n');
+    let imports = imports
+        .iter()
+        .filter(|import| package_of(import) != package && package_of(import) != "java.lang")
+        .collect::<Vec<_>>();
+    for import in &imports {
+        listing.push_str(&format!("import {};\n", java_type(import)));
+    }
+    if !imports.is_empty() {
+        listing.push('\n');
+    }
+    listing.push_str(&format!("class {}", simple_name(descriptor)));
+    if let Some(superclass) = class.superclass {
+        listing.push_str(&format!(
+            " extends {}",
+            simple_name(dex.type_descriptor(superclass))
+        ));
+    }
+    listing.push_str(" {\n");
+    listing.push_str(&body);
+    listing.push_str("}\n");
+    listing
+}
+
+/// Value stored in a register, while rendering the code of a method.
+#[derive(Debug, Clone)]
+struct Value {
+    /// Java expression of the value.
+    expression: String,
+    /// Kind of value.
+    kind: ValueKind,
+    /// Whether the value has been used as an argument or a receiver.
+    used: bool,
+}
+
+/// Kind of a value stored in a register.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    /// Numeric literal.
+    Literal(i64),
+    /// Expression that is listed if it is not used.
+    Expression,
+    /// Copy of another value.
+    Copy,
+    /// Object created but not initialized yet.
+    NewInstance,
+}
+
+impl Value {
+    /// Creates a new value of the given kind.
+    fn new<E: Into<String>>(expression: E, kind: ValueKind) -> Self {
+        Self {
+            expression: expression.into(),
+            kind,
+            used: false,
+        }
+    }
+}
+
+/// State of the rendering of the code of a method.
+#[derive(Debug)]
+struct CodeRenderer<'d> {
+    /// DEX file of the method.
+    dex: &'d DexFile,
+    /// Register of the receiver of the method, if it is not static.
+    this: Option<u16>,
+    /// Values of the registers.
+    registers: HashMap<u16, Value>,
+    /// Rendered statements.
+    statements: Vec<String>,
+    /// Descriptors of the referenced classes.
+    imports: &'d mut BTreeSet<String>,
+}
+
+impl<'d> CodeRenderer<'d> {
+    /// Sets the value of a register, listing its previous value if it was not used.
+    fn set(&mut self, register: u16, value: Value) {
+        self.discard(register);
+        let _ = self.registers.insert(register, value);
+    }
+
+    /// Drops the value of a register, listing it if it was not used.
+    fn discard(&mut self, register: u16) {
+        if let Some(value) = self.registers.remove(&register) {
+            if value.kind == ValueKind::Expression && !value.used {
+                self.statements.push(value.expression);
+            }
+        }
+    }
+
+    /// Renders the value of a register as an operand of the given type.
+    fn operand(&mut self, register: u16, descriptor: Option<&str>) -> String {
+        match self.registers.get_mut(&register) {
+            Some(value) => match value.kind {
+                ValueKind::Literal(literal) => match (descriptor, literal) {
+                    (Some("Z"), 0) => String::from("false"),
+                    (Some("Z"), 1) => String::from("true"),
+                    _ => literal.to_string(),
+                },
+                ValueKind::Expression | ValueKind::Copy if !value.used => {
+                    value.used = true;
+                    value.expression.clone()
+                }
+                _ => format!("v{}", register),
+            },
+            None if Some(register) == self.this => String::from("this"),
+            None => format!("v{}", register),
+        }
+    }
+
+    /// Records a referenced class, so that it is imported.
+    fn import(&mut self, descriptor: &str) {
+        let element = descriptor.trim_start_matches('[');
+        if element.starts_with('L') {
+            let _ = self.imports.insert(element.to_owned());
+        }
+    }
+
+    /// Renders an invocation of the given method, with the given argument registers, returning
+    /// the expression if it has to be listed.
+    fn invoke(&mut self, method: u32, arguments: &[u16], is_static: bool) -> Option<String> {
+        let dex = self.dex;
+        let reference = dex.methods.get(method as usize)?;
+        let class = dex.type_descriptor(reference.class);
+        let name = dex.string(reference.name);
+        let parameters = dex
+            .protos
+            .get(reference.proto as usize)
+            .map_or(&[][..], |proto| &proto.parameters[..]);
+        self.import(class);
+
+        let mut registers = arguments.iter();
+        let receiver = if is_static {
+            None
+        } else {
+            registers.next().copied()
+        };
+        let mut rendered = Vec::with_capacity(parameters.len());
+        for &parameter in parameters {
+            let descriptor = dex.type_descriptor(parameter);
+            if let Some(&register) = registers.next() {
+                rendered.push(self.operand(register, Some(descriptor)));
+            }
+            // Wide values take two registers.
+            if descriptor == "J" || descriptor == "D" {
+                let _ = registers.next();
+            }
+        }
+        let rendered = rendered.join(", ");
+
+        match receiver {
+            None => Some(format!("{}.{}({})", simple_name(class), name, rendered)),
+            Some(receiver) if name == "<init>" => {
+                let new_instance = self
+                    .registers
+                    .get(&receiver)
+                    .map_or(false, |value| value.kind == ValueKind::NewInstance);
+                if new_instance {
+                    let expression = format!("new {}({})", simple_name(class), rendered);
+                    self.set(receiver, Value::new(expression, ValueKind::Expression));
+                    None
+                } else if rendered.is_empty() {
+                    None
+                } else {
+                    Some(format!("super({})", rendered))
+                }
+            }
+            Some(receiver) => {
+                let receiver = self.operand(receiver, None);
+                Some(format!("{}.{}({})", receiver, name, rendered))
+            }
+        }
+    }
+}
+
+/// Renders the statements of the given code.
+fn render_code(
+    dex: &DexFile,
+    code: &Code,
+    is_static: bool,
+    imports: &mut BTreeSet<String>,
+) -> Vec<String> {
+    let mut renderer = CodeRenderer {
+        dex,
+        this: if is_static {
+            None
+        } else {
+            code.registers_size.checked_sub(code.ins_size)
+        },
+        registers: HashMap::new(),
+        statements: Vec::new(),
+        imports,
+    };
+    let insns = &code.insns;
+    let mut pending_result: Option<String> = None;
+    let mut pc = 0;
+
+    while pc < insns.len() {
+        let width = instruction_width(&insns[pc..]);
+        if pc + width > insns.len() {
+            break;
+        }
+        let ins = &insns[pc..pc + width];
+        let opcode = ins[0] & 0xff;
+        let aa = ins[0] >> 8;
+        let a = (ins[0] >> 8) & 0xf;
+        let b = ins[0] >> 12;
+
+        if let Some(result) = pending_result.take() {
+            match opcode {
+                0x0a..=0x0c => {
+                    renderer.set(aa, Value::new(result, ValueKind::Expression));
+                    pc += width;
+                    continue;
+                }
+                _ => renderer.statements.push(result),
+            }
+        }
+
+        match opcode {
+            0x1a | 0x1b => {
+                let index = if opcode == 0x1a {
+                    u32::from(ins[1])
+                } else {
+                    u32::from(ins[1]) | u32::from(ins[2]) << 16
+                };
+                let literal = java_string(dex.string(index));
+                renderer.set(aa, Value::new(literal, ValueKind::Expression));
+            }
+            0x12 => {
+                // Sign extension of the 4-bit literal.
+                let literal = i64::from(((b << 12) as i16) >> 12);
+                renderer.set(a, Value::new(String::new(), ValueKind::Literal(literal)));
+            }
+            0x13 => {
+                let literal = i64::from(ins[1] as i16);
+                renderer.set(aa, Value::new(String::new(), ValueKind::Literal(literal)));
+            }
+            0x14 => {
+                let literal = i64::from((u32::from(ins[1]) | u32::from(ins[2]) << 16) as i32);
+                renderer.set(aa, Value::new(String::new(), ValueKind::Literal(literal)));
+            }
+            0x07..=0x09 => {
+                let (to, from) = match opcode {
+                    0x07 => (a, b),
+                    0x08 => (aa, ins[1]),
+                    _ => (ins[1], ins[2]),
+                };
+                let value = renderer.registers.get(&from).cloned().map(|mut value| {
+                    if value.kind == ValueKind::Expression {
+                        value.kind = ValueKind::Copy;
+                    }
+                    value
+                });
+                match value {
+                    Some(value) => renderer.set(to, value),
+                    None => renderer.discard(to),
+                }
+            }
+            0x22 => {
+                let class = dex.type_descriptor(u32::from(ins[1]));
+                renderer.import(class);
+                renderer.set(aa, Value::new(String::new(), ValueKind::NewInstance));
+            }
+            0x60..=0x66 => {
+                if let Some(field) = dex.fields.get(usize::from(ins[1])) {
+                    let class = dex.type_descriptor(field.class);
+                    renderer.import(class);
+                    let expression = format!("{}.{}", simple_name(class), dex.string(field.name));
+                    renderer.set(aa, Value::new(expression, ValueKind::Expression));
+                }
+            }
+            0x6e..=0x72 => {
+                let registers = [
+                    ins[2] & 0xf,
+                    (ins[2] >> 4) & 0xf,
+                    (ins[2] >> 8) & 0xf,
+                    ins[2] >> 12,
+                    a,
+                ];
+                let count = usize::from(b).min(registers.len());
+                pending_result =
+                    renderer.invoke(u32::from(ins[1]), &registers[..count], opcode == 0x71);
+            }
+            0x74..=0x78 => {
+                let registers = (0..aa).map(|i| ins[2].wrapping_add(i)).collect::<Vec<_>>();
+                pending_result = renderer.invoke(u32::from(ins[1]), &registers, opcode == 0x77);
+            }
+            _ => {
+                if let Some(destination) = destination(ins) {
+                    // Wide values use two registers, so the next one is also dropped.
+                    renderer.discard(destination);
+                    renderer.discard(destination.wrapping_add(1));
+                }
+            }
+        }
+
+        pc += width;
+    }
+
+    renderer.statements.extend(pending_result);
+    let mut registers = renderer.registers.keys().copied().collect::<Vec<_>>();
+    registers.sort();
+    for register in registers {
+        renderer.discard(register);
+    }
+    renderer.statements
+}
+
+/// Gets the destination register of the given instruction, if it writes to a register.
+fn destination(ins: &[u16]) -> Option<u16> {
+    let aa = ins[0] >> 8;
+    let a = (ins[0] >> 8) & 0xf;
+    match ins[0] & 0xff {
+        0x01 | 0x04 | 0x12 | 0x20 | 0x21 | 0x23 => Some(a),
+        0x02 | 0x05 | 0x0a..=0x0d | 0x13..=0x19 | 0x1c | 0x2d..=0x31 => Some(aa),
+        0x03 | 0x06 => ins.get(1).copied(),
+        0x44..=0x4a | 0x60..=0x66 | 0x90..=0xaf | 0xd8..=0xe2 | 0xfe | 0xff => Some(aa),
+        0x52..=0x58 | 0x7b..=0x8f | 0xb0..=0xd7 => Some(a),
+        _ => None,
+    }
+}
+
+/// Gets the width of the instruction at the start of the given code units, in code units.
+fn instruction_width(insns: &[u16]) -> usize {
+    let unit = |i: usize| insns.get(i).map_or(0, |&unit| usize::from(unit));
+    match insns[0] {
+        // Packed switch payload.
+        0x0100 => unit(1) * 2 + 4,
+        // Sparse switch payload.
+        0x0200 => unit(1) * 4 + 2,
+        // Fill array data payload.
+        0x0300 => (unit(1) * (unit(2) | unit(3) << 16) + 1) / 2 + 4,
+        _ => match insns[0] & 0xff {
+            0x02
+            | 0x05
+            | 0x08
+            | 0x13
+            | 0x15
+            | 0x16
+            | 0x19
+            | 0x1a
+            | 0x1c
+            | 0x1f
+            | 0x20
+            | 0x22
+            | 0x23
+            | 0x29
+            | 0x2d..=0x3d
+            | 0x44..=0x6d
+            | 0x90..=0xaf
+            | 0xd0..=0xe2
+            | 0xfe
+            | 0xff => 2,
+            0x03
+            | 0x06
+            | 0x09
+            | 0x14
+            | 0x17
+            | 0x1b
+            | 0x24..=0x26
+            | 0x2a..=0x2c
+            | 0x6e..=0x72
+            | 0x74..=0x78
+            | 0xfc
+            | 0xfd => 3,
+            0xfa | 0xfb => 4,
+            0x18 => 5,
+            _ => 1,
+        },
+    }
+}
+
+/// Renders the given string as a Java string literal.
+fn java_string(string: &str) -> String {
+    let mut literal = String::with_capacity(string.len() + 2);
+    literal.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{
+        decode_mutf8, instruction_width, java_string, java_type, parse_class_data, read_uleb128,
+        render_code, simple_name, Code, DexFile, MethodRef, Proto,
+    };
+
+    /// Creates a DEX file with the given strings as types and the given methods.
+    fn dex(strings: &[&str], methods: &[(u32, u32, u32)], protos: Vec<Proto>) -> DexFile {
+        DexFile {
+            strings: strings.iter().map(|&s| s.to_owned()).collect(),
+            types: (0..strings.len() as u32).collect(),
+            protos,
+            methods: methods
+                .iter()
+                .map(|&(class, proto, name)| MethodRef { class, proto, name })
+                .collect(),
+            ..DexFile::default()
+        }
+    }
+
+    /// Checks the decoding of the basic DEX data types.
+    #[test]
+    fn it_decode() {
+        let mut offset = 0;
+        assert_eq!(
+            read_uleb128(&[0xe5, 0x8e, 0x26], &mut offset).unwrap(),
+            624_485
+        );
+        assert_eq!(offset, 3);
+        assert!(read_uleb128(&[0x80], &mut 0).is_err());
+
+        assert_eq!(decode_mutf8(b"classes\0ignored"), "classes");
+        assert_eq!(
+            decode_mutf8(&[0x63, 0x61, 0xc3, 0xb1, 0x61, 0xc0, 0x80, 0x00]),
+            "caña\0"
+        );
+        assert_eq!(decode_mutf8(&[0xe2, 0x82, 0xac, 0x00]), "€");
+
+        assert_eq!(java_type("[Ljava/lang/String;"), "java.lang.String[]");
+        assert_eq!(
+            java_type("Lcom/example/Outer$Inner;"),
+            "com.example.Outer.Inner"
+        );
+        assert_eq!(java_type("[[I"), "int[][]");
+        assert_eq!(java_type("LLogger;"), "Logger");
+        assert_eq!(
+            simple_name("[Ljava/security/MessageDigest;"),
+            "MessageDigest[]"
+        );
+        assert_eq!(java_string("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+
+        assert!(DexFile::parse(b"PK\x03\x04").is_err());
+    }
+
+    /// Checks that invalid class data fails instead of overflowing or allocating too much memory.
+    #[test]
+    fn it_parse_class_data() {
+        let mut data = vec![0, 0, 1, 0, 0, 0, 8, 0];
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&[1, 0, 0, 0, 0x0e, 0]);
+        let methods = parse_class_data(&data, 0).unwrap();
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].code.as_ref().unwrap().insns, vec![0x000e]);
+
+        data[20..24].copy_from_slice(&[0xff; 4]);
+        assert!(parse_class_data(&data, 0).is_err());
+
+        let fields = [0xff, 0xff, 0xff, 0xff, 0x0f, 0x01, 0x00, 0x00];
+        assert!(parse_class_data(&fields, 0).is_err());
+
+        let methods = [0, 0, 2, 0, 0xff, 0xff, 0xff, 0xff, 0x0f, 0, 0, 1, 0, 0];
+        assert!(parse_class_data(&methods, 0).is_err());
+    }
+
+    /// Checks the widths of the instructions.
+    #[test]
+    fn it_instruction_width() {
+        assert_eq!(instruction_width(&[0x000e]), 1);
+        assert_eq!(instruction_width(&[0x001a, 0x0001]), 2);
+        assert_eq!(instruction_width(&[0x206e, 0x0001, 0x0010]), 3);
+        assert_eq!(instruction_width(&[0x0018, 0, 0, 0, 0]), 5);
+        assert_eq!(instruction_width(&[0x0100, 0x0002, 0, 0, 0, 0, 0, 0]), 8);
+        assert_eq!(instruction_width(&[0x0300, 0x0001, 0x0003, 0x0000]), 6);
+    }
+
+    /// Checks the rendering of the code of methods.
+    #[test]
+    fn it_render_code() {
+        let dex = dex(
+            &[
+                "Ljava/security/MessageDigest;",
+                "Ljava/lang/String;",
+                "getInstance",
+                "digest",
+                "[B",
+                "MD5",
+                "Landroid/webkit/WebSettings;",
+                "setJavaScriptEnabled",
+                "Z",
+                "V",
+                "Ljava/util/Random;",
+                "<init>",
+                "https://example.com/api",
+            ],
+            &[(0, 0, 2), (0, 1, 3), (6, 2, 7), (10, 3, 11)],
+            vec![
+                Proto {
+                    return_type: 0,
+                    parameters: vec![1],
+                },
+                Proto {
+                    return_type: 4,
+                    parameters: vec![4],
+                },
+                Proto {
+                    return_type: 9,
+                    parameters: vec![8],
+                },
+                Proto {
+                    return_type: 9,
+                    parameters: Vec::new(),
+                },
+            ],
+        );
+        let code = Code {
+            registers_size: 5,
+            ins_size: 2,
+            insns: [
+                // const-string v0, "MD5"
+                &[0x001a, 0x0005][..],
+                // invoke-static {v0}, MessageDigest.getInstance(String)
+                &[0x1071, 0x0000, 0x0000],
+                // move-result-object v1
+                &[0x010c],
+                // invoke-virtual {v1, v4}, MessageDigest.digest(byte[])
+                &[0x206e, 0x0001, 0x0041],
+                // const/4 v2, 1
+                &[0x1212],
+                // invoke-virtual {v3, v2}, WebSettings.setJavaScriptEnabled(boolean)
+                &[0x206e, 0x0002, 0x0023],
+                // new-instance v0, Random
+                &[0x0022, 0x000a],
+                // invoke-direct {v0}, Random.<init>()
+                &[0x1070, 0x0003, 0x0000],
+                // const-string v2, "https://example.com/api"
+                &[0x021a, 0x000c],
+                // return-void
+                &[0x000e],
+            ]
+            .concat(),
+        };
+
+        let mut imports = BTreeSet::new();
+        assert_eq!(
+            render_code(&dex, &code, false, &mut imports),
+            vec![
+                "MessageDigest.getInstance(\"MD5\").digest(v4)",
+                "this.setJavaScriptEnabled(true)",
+                "new Random()",
+                "\"https://example.com/api\"",
+            ]
+        );
+        assert!(imports.contains("Landroid/webkit/WebSettings;"));
+        assert!(imports.contains("Ljava/util/Random;"));
+    }
+}
@@ -0,0 +1,400 @@
+//! Application diffing module.
+//!
+//! This module implements the `diff` subcommand, that analyzes two versions of an application and
+//! reports the differences between them: the new, fixed and unchanged vulnerabilities, the added
+//! and removed permissions, and the changes in the version and the SDK levels. It allows tracking
+//! security regressions from one release to the next.
+//!
+//! Vulnerabilities are matched by their fingerprint, so a vulnerability is not reported as new
+//! just because unrelated changes moved it to other lines. If `--fail-on` is set, the command
+//! fails if there are new vulnerabilities at or above the given criticality.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::Path,
+};
+
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+use serde::Serialize;
+
+use crate::{
+    analyze_package,
+    criticality::Criticality,
+    error,
+    i18n::{tr, tr_args},
+    print_message, print_warning,
+    results::{Results, Vulnerability},
+    static_analysis::manifest::Manifest,
+    Config,
+};
+
+/// Version information of an analyzed application.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct AppVersion {
+    /// Package of the application.
+    package: String,
+    /// Version string of the application.
+    version: String,
+    /// Version number of the application.
+    version_number: u32,
+    /// Minimum SDK of the application.
+    min_sdk: u32,
+    /// Target SDK of the application, if declared.
+    target_sdk: Option<u32>,
+}
+
+impl AppVersion {
+    /// Gets the version information of the given results.
+    fn of(results: &Results) -> Self {
+        Self {
+            package: results.app_package().to_owned(),
+            version: results.app_version().to_owned(),
+            version_number: results.app_version_num(),
+            min_sdk: results.app_min_sdk(),
+            target_sdk: results.app_target_sdk(),
+        }
+    }
+}
+
+/// Analysis of a version of an application.
+#[derive(Debug)]
+struct Analysis {
+    /// Version information of the application.
+    version: AppVersion,
+    /// Vulnerabilities found in the application.
+    vulnerabilities: Vec<Vulnerability>,
+    /// Permissions requested by the application.
+    permissions: BTreeSet<String>,
+}
+
+/// Differences between the analyses of two versions of an application.
+#[derive(Debug, Serialize)]
+struct Delta {
+    /// Old version of the application.
+    old: AppVersion,
+    /// New version of the application.
+    new: AppVersion,
+    /// Vulnerabilities only found in the new version.
+    new_vulnerabilities: Vec<Vulnerability>,
+    /// Vulnerabilities only found in the old version.
+    fixed_vulnerabilities: Vec<Vulnerability>,
+    /// Vulnerabilities found in both versions.
+    unchanged_vulnerabilities: Vec<Vulnerability>,
+    /// Permissions only requested by the new version.
+    added_permissions: Vec<String>,
+    /// Permissions only requested by the old version.
+    removed_permissions: Vec<String>,
+}
+
+impl Delta {
+    /// Computes the differences between the analyses of the old and the new version.
+    fn new(old: Analysis, new: Analysis) -> Self {
+        let old_fingerprints = old
+            .vulnerabilities
+            .iter()
+            .map(Vulnerability::fingerprint)
+            .collect::<BTreeSet<_>>();
+        let new_fingerprints = new
+            .vulnerabilities
+            .iter()
+            .map(Vulnerability::fingerprint)
+            .collect::<BTreeSet<_>>();
+
+        let (unchanged_vulnerabilities, new_vulnerabilities) = new
+            .vulnerabilities
+            .into_iter()
+            .partition::<Vec<_>, _>(|v| old_fingerprints.contains(&v.fingerprint()));
+        let fixed_vulnerabilities = old
+            .vulnerabilities
+            .into_iter()
+            .filter(|v| !new_fingerprints.contains(&v.fingerprint()))
+            .collect();
+
+        Self {
+            added_permissions: new
+                .permissions
+                .difference(&old.permissions)
+                .cloned()
+                .collect(),
+            removed_permissions: old
+                .permissions
+                .difference(&new.permissions)
+                .cloned()
+                .collect(),
+            old: old.version,
+            new: new.version,
+            new_vulnerabilities,
+            fixed_vulnerabilities,
+            unchanged_vulnerabilities,
+        }
+    }
+
+    /// Counts the new vulnerabilities with the given criticality or higher.
+    fn count_new(&self, criticality: Criticality) -> usize {
+        self.new_vulnerabilities
+            .iter()
+            .filter(|v| v.get_criticality() >= criticality)
+            .count()
+    }
+}
+
+/// Formats an optional SDK level.
+fn format_sdk(sdk: Option<u32>) -> String {
+    sdk.map_or_else(|| String::from("-"), |sdk| sdk.to_string())
+}
+
+/// Writes a list of vulnerabilities, prefixed with the given sign.
+fn write_vulnerabilities(
+    f: &mut fmt::Formatter,
+    sign: &str,
+    vulnerabilities: &[Vulnerability],
+) -> fmt::Result {
+    for vulnerability in vulnerabilities {
+        write!(
+            f,
+            "  {} {:<8} {}",
+            sign,
+            vulnerability.get_criticality().to_string(),
+            vulnerability.name()
+        )?;
+        if let Some(file) = vulnerability.file() {
+            write!(f, " ({}", file.display())?;
+            if let Some(line) = vulnerability.start_line() {
+                write!(f, ":{}", line + 1)?;
+            }
+            write!(f, ")")?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            tr_args(
+                "Differences between {} {} and {}:",
+                &[
+                    &self.old.package.italic(),
+                    &self.old.version,
+                    &self.new.version
+                ],
+            )
+            .bold()
+        )?;
+        writeln!(
+            f,
+            "  {:<12} {} ({}) → {} ({})",
+            tr("version"),
+            self.old.version,
+            self.old.version_number,
+            self.new.version,
+            self.new.version_number
+        )?;
+        if self.old.min_sdk != self.new.min_sdk {
+            writeln!(
+                f,
+                "  {:<12} {} → {}",
+                tr("min SDK"),
+                self.old.min_sdk,
+                self.new.min_sdk
+            )?;
+        }
+        if self.old.target_sdk != self.new.target_sdk {
+            writeln!(
+                f,
+                "  {:<12} {} → {}",
+                tr("target SDK"),
+                format_sdk(self.old.target_sdk),
+                format_sdk(self.new.target_sdk)
+            )?;
+        }
+
+        if !self.added_permissions.is_empty() || !self.removed_permissions.is_empty() {
+            writeln!(f, "{}", tr("Permissions:").bold())?;
+            for permission in &self.added_permissions {
+                writeln!(f, "{}", format!("  + {}", permission).red())?;
+            }
+            for permission in &self.removed_permissions {
+                writeln!(f, "{}", format!("  - {}", permission).green())?;
+            }
+        }
+
+        writeln!(
+            f,
+            "{}",
+            tr_args(
+                "New vulnerabilities: {}",
+                &[&self.new_vulnerabilities.len()]
+            )
+            .bold()
+        )?;
+        write_vulnerabilities(f, "+", &self.new_vulnerabilities)?;
+        writeln!(
+            f,
+            "{}",
+            tr_args(
+                "Fixed vulnerabilities: {}",
+                &[&self.fixed_vulnerabilities.len()]
+            )
+            .bold()
+        )?;
+        write_vulnerabilities(f, "-", &self.fixed_vulnerabilities)?;
+        writeln!(
+            f,
+            "{}",
+            tr_args(
+                "Unchanged vulnerabilities: {}",
+                &[&self.unchanged_vulnerabilities.len()]
+            )
+            .bold()
+        )
+    }
+}
+
+/// Analyzes the given package.
+fn analyze(config: &mut Config, package: &Path) -> Result<Analysis, Error> {
+    config.select_package(package);
+    let results = analyze_package(package, config, &mut BTreeMap::new()).context(format_err!(
+        "the analysis of `{}` failed",
+        package.display()
+    ))?;
+
+    let dist_folder = config.dist_folder().join(config.package_name(package));
+    let permissions = match Manifest::load(dist_folder) {
+        Ok(manifest) => manifest.requested_permissions().cloned().collect(),
+        Err(e) => {
+            print_warning(format!(
+                "could not load the manifest of `{}`, its permissions will not be compared: {}",
+                package.display(),
+                e
+            ));
+            BTreeSet::new()
+        }
+    };
+
+    Ok(Analysis {
+        version: AppVersion::of(&results),
+        vulnerabilities: results.into_vulnerabilities(),
+        permissions,
+    })
+}
+
+/// Runs the `diff` subcommand.
+pub fn run(mut config: Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let old = config.package_path(cli.value_of("old").expect("the old package is required"));
+    let new = config.package_path(cli.value_of("new").expect("the new package is required"));
+    if config.package_name(&old) == config.package_name(&new) {
+        bail!(
+            "both packages are named `{}`, rename one of them so that their analyses do not \
+             overwrite each other",
+            config.package_name(&old)
+        );
+    }
+
+    let old = analyze(&mut config, &old)?;
+    let new = analyze(&mut config, &new)?;
+    let delta = Delta::new(old, new);
+
+    print_message("");
+    print_message(delta.to_string().trim_end());
+    if let Some(output) = cli.value_of("output") {
+        fs::write(output, serde_json::to_string_pretty(&delta)?).context(format_err!(
+            "could not write the differences to `{}`",
+            output
+        ))?;
+    }
+
+    if let Some(criticality) = config.fail_on() {
+        let count = delta.count_new(criticality);
+        if count > 0 {
+            return Err(error::Kind::FindingsFound { count, criticality }.into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Analysis, AppVersion, Delta};
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Creates the analysis of a version of an application.
+    fn analysis(
+        version: &str,
+        vulnerabilities: &[(&str, Criticality)],
+        permissions: &[&str],
+    ) -> Analysis {
+        Analysis {
+            version: AppVersion {
+                package: String::from("com.example.app"),
+                version: version.to_owned(),
+                version_number: 1,
+                min_sdk: 21,
+                target_sdk: None,
+            },
+            vulnerabilities: vulnerabilities
+                .iter()
+                .map(|&(name, criticality)| {
+                    Vulnerability::new(
+                        criticality,
+                        name,
+                        "Description",
+                        Some("src/Main.java"),
+                        Some(1),
+                        Some(1),
+                        Some(name),
+                    )
+                })
+                .collect(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    /// Checks the differences between two versions of an application.
+    #[test]
+    fn it_delta() {
+        let old = analysis(
+            "1.0",
+            &[
+                ("Weak algorithm", Criticality::High),
+                ("Log usage", Criticality::Warning),
+            ],
+            &["android.permission.INTERNET", "android.permission.VIBRATE"],
+        );
+        let new = analysis(
+            "1.1",
+            &[
+                ("Weak algorithm", Criticality::High),
+                ("SQL injection", Criticality::Critical),
+            ],
+            &["android.permission.CAMERA", "android.permission.INTERNET"],
+        );
+
+        let delta = Delta::new(old, new);
+        assert_eq!(delta.new_vulnerabilities.len(), 1);
+        assert_eq!(delta.new_vulnerabilities[0].name(), "SQL injection");
+        assert_eq!(delta.fixed_vulnerabilities.len(), 1);
+        assert_eq!(delta.fixed_vulnerabilities[0].name(), "Log usage");
+        assert_eq!(delta.unchanged_vulnerabilities.len(), 1);
+        assert_eq!(delta.added_permissions, vec!["android.permission.CAMERA"]);
+        assert_eq!(
+            delta.removed_permissions,
+            vec!["android.permission.VIBRATE"]
+        );
+        assert_eq!(delta.count_new(Criticality::High), 1);
+        assert_eq!(delta.count_new(Criticality::Critical), 1);
+
+        colored::control::set_override(false);
+        let report = delta.to_string();
+        assert!(report.contains("  + android.permission.CAMERA\n"));
+        assert!(report.contains("  + critical SQL injection (src/Main.java:2)\n"));
+        assert!(report.contains("Unchanged vulnerabilities: 1"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Application download module.
+//!
+//! With `--download`, the `package` argument is an application ID, such as `org.fdroid.fdroid`,
+//! and the application is downloaded to the downloads folder before the analysis. The source is
+//! configured in the `[download]` section of the configuration:
+//!
+//! ```toml
+//! [download]
+//! source = "fdroid"                                      # "url", "fdroid" or "apkpure"
+//! url = "https://apks.example.com/{package}.apk"         # for the "url" source
+//! fdroid_repository = "https://f-droid.org/repo"
+//! apkpure_mirror = "https://d.apkpure.com/b/APK/{package}?version=latest"
+//! ```
+//!
+//! `{package}` is replaced with the application ID. F-Droid downloads the version suggested by
+//! the repository and verifies it with the SHA-256 checksum of the repository index. For the rest
+//! of sources, the checksum can be given with `--sha256`, otherwise it is only printed, so that it
+//! can be checked manually.
+
+use std::path::Path;
+#[cfg(feature = "integrations")]
+use std::{collections::BTreeMap, fs};
+
+#[cfg(not(feature = "integrations"))]
+use failure::bail;
+use failure::Error;
+#[cfg(feature = "integrations")]
+use failure::{bail, format_err, ResultExt};
+
+#[cfg(feature = "integrations")]
+use crate::{
+    http::{self, USER_AGENT},
+    input_list, print_message, print_warning,
+    utils::sha256_hex,
+};
+
+/// Placeholder of the application ID in the URLs.
+#[cfg(feature = "integrations")]
+const PACKAGE_PLACEHOLDER: &str = "{package}";
+
+/// Source of the downloaded applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadSource {
+    /// URL of the configuration, with the application ID.
+    Url,
+    /// F-Droid repository.
+    FDroid,
+    /// APKPure mirror.
+    ApkPure,
+}
+
+impl Default for DownloadSource {
+    fn default() -> Self {
+        DownloadSource::FDroid
+    }
+}
+
+/// Configuration of the application downloads.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// Source of the applications.
+    source: DownloadSource,
+    /// URL of the applications for the `url` source, with `{package}` as placeholder.
+    url: Option<String>,
+    /// URL of the F-Droid repository.
+    fdroid_repository: String,
+    /// URL of the APKPure mirror, with `{package}` as placeholder.
+    apkpure_mirror: String,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            source: DownloadSource::default(),
+            url: None,
+            fdroid_repository: String::from("https://f-droid.org/repo"),
+            apkpure_mirror: String::from("https://d.apkpure.com/b/APK/{package}?version=latest"),
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Returns the source of the applications.
+    pub fn source(&self) -> DownloadSource {
+        self.source
+    }
+
+    /// Returns the URL of the applications for the `url` source, if configured.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_ref().map(String::as_str)
+    }
+
+    /// Returns the URL of the F-Droid repository.
+    pub fn fdroid_repository(&self) -> &str {
+        &self.fdroid_repository
+    }
+
+    /// Returns the URL of the APKPure mirror.
+    pub fn apkpure_mirror(&self) -> &str {
+        &self.apkpure_mirror
+    }
+
+    /// Sets the source of the applications.
+    pub(crate) fn set_source(&mut self, source: DownloadSource) {
+        self.source = source;
+    }
+}
+
+/// Downloads the application with the given ID to the given path.
+///
+/// The download is verified with the given SHA-256 checksum or, for F-Droid, with the checksum of
+/// the repository index.
+#[cfg(feature = "integrations")]
+pub(crate) fn download(
+    config: &DownloadConfig,
+    package: &str,
+    path: &Path,
+    sha256: Option<&str>,
+) -> Result<(), Error> {
+    let (url, expected) = match config.source {
+        DownloadSource::Url => match config.url {
+            Some(ref url) => (url.replace(PACKAGE_PLACEHOLDER, package), None),
+            None => {
+                bail!("the `url` download source requires the `url` of the `[download]` section")
+            }
+        },
+        DownloadSource::ApkPure => (
+            config.apkpure_mirror.replace(PACKAGE_PLACEHOLDER, package),
+            None,
+        ),
+        DownloadSource::FDroid => {
+            let (url, hash) = fdroid_apk(&config.fdroid_repository, package)?;
+            (url, Some(hash))
+        }
+    };
+
+    print_message(format!("Downloading {} from {}…", package, url));
+    input_list::download_to(&url, path)?;
+
+    let actual =
+        sha256_hex(&fs::read(path).context(format_err!("could not read `{}`", path.display()))?);
+    let expected = match (sha256, expected.as_ref()) {
+        (Some(sha256), _) => sha256,
+        (None, Some(hash)) => hash.as_str(),
+        (None, None) => {
+            print_warning(format!(
+                "the download of {} could not be verified, its SHA-256 checksum is {}",
+                package, actual
+            ));
+            return Ok(());
+        }
+    };
+    if !actual.eq_ignore_ascii_case(expected) {
+        let _ = fs::remove_file(path);
+        bail!(
+            "the checksum of the downloaded {} does not match: expected {}, got {}",
+            package,
+            expected,
+            actual
+        );
+    }
+
+    Ok(())
+}
+
+/// Downloads the application with the given ID to the given path.
+///
+/// Downloading applications requires the `integrations` feature.
+#[cfg(not(feature = "integrations"))]
+pub(crate) fn download(
+    _config: &DownloadConfig,
+    package: &str,
+    _path: &Path,
+    _sha256: Option<&str>,
+) -> Result<(), Error> {
+    bail!(
+        "could not download {}, downloading applications requires the `integrations` feature",
+        package
+    )
+}
+
+/// Index of an F-Droid repository, in the `index-v1.json` format.
+#[cfg(feature = "integrations")]
+#[derive(Debug, Deserialize)]
+struct FDroidIndex {
+    /// Applications of the repository.
+    apps: Vec<FDroidApp>,
+    /// Packages of each application, by application ID.
+    packages: BTreeMap<String, Vec<FDroidPackage>>,
+}
+
+/// Application in an F-Droid repository.
+#[cfg(feature = "integrations")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FDroidApp {
+    /// ID of the application.
+    package_name: String,
+    /// Version code suggested by the repository, if any.
+    suggested_version_code: Option<String>,
+}
+
+/// Package of an application in an F-Droid repository.
+#[cfg(feature = "integrations")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FDroidPackage {
+    /// File name of the package in the repository.
+    apk_name: String,
+    /// Version code of the package.
+    version_code: u64,
+    /// Checksum of the package.
+    hash: String,
+    /// Algorithm of the checksum.
+    hash_type: String,
+}
+
+/// Gets the URL and the SHA-256 checksum of the suggested version of the given application in the
+/// given F-Droid repository.
+#[cfg(feature = "integrations")]
+fn fdroid_apk(repository: &str, package: &str) -> Result<(String, String), Error> {
+    let repository = repository.trim_end_matches('/');
+    let index: FDroidIndex = http::client(None)?
+        .get(&format!("{}/index-v1.json", repository))
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .and_then(reqwest::Response::error_for_status)
+        .and_then(|mut response| response.json())
+        .context(format_err!(
+            "could not read the index of the F-Droid repository `{}`",
+            repository
+        ))?;
+
+    let suggested = index
+        .apps
+        .iter()
+        .find(|app| app.package_name == package)
+        .and_then(|app| app.suggested_version_code.as_ref())
+        .and_then(|code| code.parse::<u64>().ok());
+    let packages = index
+        .packages
+        .get(package)
+        .ok_or_else(|| format_err!("{} is not in the F-Droid repository", package))?;
+    let apk = packages
+        .iter()
+        .find(|apk| Some(apk.version_code) == suggested)
+        .or_else(|| packages.iter().max_by_key(|apk| apk.version_code))
+        .ok_or_else(|| format_err!("{} has no package in the F-Droid repository", package))?;
+    if apk.hash_type != "sha256" {
+        bail!(
+            "unsupported checksum `{}` in the F-Droid repository",
+            apk.hash_type
+        );
+    }
+
+    Ok((format!("{}/{}", repository, apk.apk_name), apk.hash.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DownloadConfig, DownloadSource};
+
+    /// Checks the parsing of the download configuration.
+    #[test]
+    fn it_download_config() {
+        let config: DownloadConfig = toml::from_str(
+            "source = \"url\"\n\
+             url = \"https://apks.example.com/{package}.apk\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.source(), DownloadSource::Url);
+        assert_eq!(config.url(), Some("https://apks.example.com/{package}.apk"));
+        assert_eq!(config.fdroid_repository(), "https://f-droid.org/repo");
+
+        let config: DownloadConfig = toml::from_str("source = \"apkpure\"").unwrap();
+        assert_eq!(config.source(), DownloadSource::ApkPure);
+        assert!(toml::from_str::<DownloadConfig>("source = \"play\"").is_err());
+    }
+}
@@ -1,4 +1,13 @@
 //! Module containing the definition of error types.
+//!
+//! Each error kind has a machine-readable code, included in the failed stages of the JSON report,
+//! so that automated consumers can tell, for example, a missing tool from an invalid application.
+
+use std::{fmt, io};
+
+use failure::{Context, Error};
+
+use crate::criticality::Criticality;
 
 /// Enumeration of the different error kinds.
 #[derive(Debug, Fail)]
@@ -21,4 +30,206 @@ pub enum Kind {
     /// Code not found.
     #[fail(display = "no code was found in the file")]
     CodeNotFound,
+    /// Invalid application package.
+    #[fail(display = "`{}` is not a valid application: {}", path, problem)]
+    InvalidPackage {
+        /// Path of the application package.
+        path: String,
+        /// Problem found in the package.
+        problem: PackageProblem,
+    },
+    /// Findings at or above the `--fail-on` criticality were found.
+    #[fail(
+        display = "{} findings with {} criticality or higher were found",
+        count, criticality
+    )]
+    FindingsFound {
+        /// Number of findings at or above the criticality.
+        count: usize,
+        /// Criticality of the findings that make the analysis fail.
+        criticality: Criticality,
+    },
+    /// The analysis was cancelled.
+    #[fail(display = "the analysis was cancelled")]
+    Cancelled,
+    /// An external tool could not be found.
+    #[fail(display = "{} could not be found at `{}`", tool, path)]
+    ToolMissing {
+        /// Name of the tool.
+        tool: String,
+        /// Path where the tool was expected.
+        path: String,
+    },
+    /// An external tool failed.
+    #[fail(display = "{} failed: {}", tool, stderr)]
+    ToolFailed {
+        /// Name of the tool.
+        tool: String,
+        /// Standard error of the tool.
+        stderr: String,
+    },
+    /// The application package could not be unpacked.
+    #[fail(display = "the application could not be unpacked at `{}`", path)]
+    UnpackError {
+        /// Path where the application was being unpacked.
+        path: String,
+    },
+    /// The manifest of the application could not be parsed.
+    #[fail(display = "the manifest could not be parsed: {}", message)]
+    ManifestParse {
+        /// Error message.
+        message: String,
+    },
+    /// A rules file could not be loaded.
+    #[fail(display = "invalid rules file `{}`", path)]
+    RuleLoad {
+        /// Path of the rules file.
+        path: String,
+    },
+    /// The reports could not be written.
+    #[fail(display = "the reports could not be written to `{}`", path)]
+    ReportWrite {
+        /// Path of the results folder.
+        path: String,
+    },
+}
+
+impl Kind {
+    /// Gets the machine-readable code of the error kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Kind::Config { .. } => "config",
+            Kind::Parse => "parse",
+            Kind::TemplateName { .. } => "template_name",
+            Kind::CodeNotFound => "code_not_found",
+            Kind::InvalidPackage { .. } => "invalid_package",
+            Kind::FindingsFound { .. } => "findings_found",
+            Kind::Cancelled => "cancelled",
+            Kind::ToolMissing { .. } => "tool_missing",
+            Kind::ToolFailed { .. } => "tool_failed",
+            Kind::UnpackError { .. } => "unpack_error",
+            Kind::ManifestParse { .. } => "manifest_parse",
+            Kind::RuleLoad { .. } => "rule_load",
+            Kind::ReportWrite { .. } => "report_write",
+        }
+    }
+}
+
+/// Gets the machine-readable code of the given error.
+///
+/// The code is the one of the outermost error kind in the chain of causes of the error, either
+/// as the error itself or as its context. Input and output errors without a kind have the `io`
+/// code, and the rest of errors the `internal` code.
+pub fn code(error: &Error) -> &'static str {
+    for cause in error.iter_chain() {
+        if let Some(kind) = cause.downcast_ref::<Kind>() {
+            return kind.code();
+        }
+        if let Some(context) = cause.downcast_ref::<Context<Kind>>() {
+            return context.get_context().code();
+        }
+    }
+    if error
+        .iter_chain()
+        .any(|cause| cause.downcast_ref::<io::Error>().is_some())
+    {
+        "io"
+    } else {
+        "internal"
+    }
+}
+
+/// Problems that make an application package invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageProblem {
+    /// The package does not exist.
+    NotFound,
+    /// The package is not a file.
+    NotAFile,
+    /// The package cannot be read.
+    Unreadable {
+        /// Error message.
+        message: String,
+    },
+    /// The size of the package is not plausible for an application.
+    ImplausibleSize {
+        /// Size of the package, in bytes.
+        size: u64,
+    },
+    /// The package is not a ZIP archive.
+    NotAZipArchive,
+    /// The package is a corrupt ZIP archive.
+    CorruptArchive {
+        /// Error message.
+        message: String,
+    },
+    /// The package has no `AndroidManifest.xml` file.
+    MissingManifest,
+    /// The package has no DEX files or native libraries.
+    MissingCode,
+    /// The split APK set has no APK files.
+    MissingApks,
+}
+
+impl fmt::Display for PackageProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackageProblem::NotFound => write!(f, "the file does not exist"),
+            PackageProblem::NotAFile => write!(f, "it is not a file"),
+            PackageProblem::Unreadable { message } => {
+                write!(f, "the file cannot be read: {}", message)
+            }
+            PackageProblem::ImplausibleSize { size } => write!(
+                f,
+                "its size ({} bytes) is not plausible for an Android application",
+                size
+            ),
+            PackageProblem::NotAZipArchive => {
+                write!(f, "it is not a ZIP archive, so it cannot be an APK file")
+            }
+            PackageProblem::CorruptArchive { message } => {
+                write!(f, "it is a corrupt ZIP archive: {}", message)
+            }
+            PackageProblem::MissingManifest => {
+                write!(f, "it does not contain an AndroidManifest.xml file")
+            }
+            PackageProblem::MissingCode => {
+                write!(f, "it does not contain any DEX file or native library")
+            }
+            PackageProblem::MissingApks => write!(f, "it does not contain any APK file"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use failure::{format_err, Error, ResultExt};
+
+    use super::{code, Kind};
+
+    /// Checks the codes of the errors, through their contexts and causes.
+    #[test]
+    fn it_code() {
+        let missing: Error = Kind::ToolMissing {
+            tool: "dex2jar".to_owned(),
+            path: "vendor/dex2jar".to_owned(),
+        }
+        .into();
+        assert_eq!(code(&missing), "tool_missing");
+
+        let result: Result<(), Error> = Err(format_err!("unexpected end of file"));
+        let rules = result
+            .context(Kind::RuleLoad {
+                path: "rules.json".to_owned(),
+            })
+            .context("rules must be objects")
+            .unwrap_err();
+        assert_eq!(code(&rules.into()), "rule_load");
+
+        let io_error: Error = io::Error::new(io::ErrorKind::NotFound, "not found").into();
+        assert_eq!(code(&io_error), "io");
+        assert_eq!(code(&format_err!("unknown")), "internal");
+    }
 }
@@ -0,0 +1,327 @@
+//! External analyzers module.
+//!
+//! External analyzers are arbitrary commands, configured in the `config.toml` file, whose findings
+//! are merged into the SUPER results. They can be run once per application or once per source
+//! file:
+//!
+//! ```toml
+//! [[external_analyzers]]
+//! name = "semgrep"
+//! command = "semgrep-to-super"
+//! args = ["{dist}"]
+//!
+//! [[external_analyzers]]
+//! name = "custom"
+//! command = "./custom-check.sh"
+//! args = ["{file}"]
+//! scope = "file"
+//! extensions = ["java", "xml"]
+//! ```
+//!
+//! The following placeholders are replaced in the arguments:
+//!
+//! * `{apk}`: path to the APK file of the application.
+//! * `{package}`: name of the package of the application.
+//! * `{dist}`: distribution folder of the application, where it was decompiled.
+//! * `{file}`: path of the analyzed file, relative to the distribution folder (only for the
+//!   `file` scope).
+//!
+//! Commands are run in the distribution folder of the application, with the resource limits of
+//! the configuration, and they must print a JSON array of findings to the standard output,
+//! following the contract described in the `results::finding` module. An empty output is
+//! considered an empty array. Relative commands, and the `{apk}` and `{dist}` placeholders, are
+//! resolved against the current directory, so they keep working from the distribution folder.
+
+use std::{
+    env,
+    fs::DirEntry,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use failure::{Error, ResultExt};
+
+use crate::{
+    cancellation, error,
+    i18n::{tr, tr_args},
+    print_message, print_warning,
+    results::{Finding, Results},
+    static_analysis::code::add_files_to_vec,
+    Config,
+};
+
+/// External analyzer configuration.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExternalAnalyzer {
+    /// Name of the analyzer, used in messages.
+    name: String,
+    /// Command to run.
+    command: PathBuf,
+    /// Arguments of the command, with placeholders.
+    #[serde(default)]
+    args: Vec<String>,
+    /// Whether the command runs once per application or once per file.
+    #[serde(default)]
+    scope: AnalyzerScope,
+    /// File extensions to analyze, for the `file` scope. All files are analyzed if empty.
+    #[serde(default)]
+    extensions: Vec<String>,
+}
+
+/// Scope of an external analyzer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyzerScope {
+    /// The analyzer runs once per application.
+    Apk,
+    /// The analyzer runs once per source file.
+    File,
+}
+
+impl Default for AnalyzerScope {
+    fn default() -> Self {
+        AnalyzerScope::Apk
+    }
+}
+
+impl ExternalAnalyzer {
+    /// Returns the name of the analyzer.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the command of the analyzer.
+    pub fn command(&self) -> &Path {
+        &self.command
+    }
+
+    /// Returns the scope of the analyzer.
+    pub fn scope(&self) -> AnalyzerScope {
+        self.scope
+    }
+
+    /// Checks if the analyzer has to run for the given file.
+    pub fn has_to_check<P: AsRef<Path>>(&self, file: P) -> bool {
+        self.extensions.is_empty()
+            || file.as_ref().extension().map_or(false, |ext| {
+                self.extensions
+                    .iter()
+                    .any(|e| e.trim_start_matches('.') == ext)
+            })
+    }
+
+    /// Gets the arguments of the command, replacing the placeholders with the given values.
+    fn args(&self, placeholders: &[(&str, &str)]) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|arg| {
+                placeholders
+                    .iter()
+                    .fold(arg.clone(), |arg, (k, v)| arg.replace(k, v))
+            })
+            .collect()
+    }
+
+    /// Runs the command with the given arguments in the distribution folder, and parses its
+    /// findings.
+    fn run(
+        &self,
+        args: &[String],
+        config: &Config,
+        dist_folder: &Path,
+    ) -> Result<Vec<Finding>, Error> {
+        // Commands in the `PATH` are kept as they are, but relative paths are resolved before
+        // changing the working directory.
+        let command = if self.command.components().count() > 1 {
+            absolute(&self.command)
+        } else {
+            self.command.clone()
+        };
+        let mut command = config.limits().command(command);
+        let _ = command.args(args).current_dir(dist_folder);
+        let output = config.limits().output(&mut command).context(format!(
+            "could not run the `{}` command",
+            self.command.display()
+        ))?;
+
+        if !output.status.success() {
+            return Err(error::Kind::ToolFailed {
+                tool: self.name.clone(),
+                stderr: format!(
+                    "the command exited with {}. Stderr: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            }
+            .into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(serde_json::from_str(&stdout)
+            .context("the command output is not a valid list of findings")?)
+    }
+}
+
+/// Runs all the configured external analyzers for the given application.
+pub fn analysis<P: AsRef<Path>, S: AsRef<str>>(
+    config: &Config,
+    apk: P,
+    package: S,
+    results: &mut Results,
+) {
+    if config.external_analyzers().next().is_none() {
+        return;
+    }
+
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let apk = absolute(apk.as_ref());
+    let apk = apk.to_string_lossy();
+    let absolute_dist_folder = absolute(&dist_folder);
+    let dist = absolute_dist_folder.to_string_lossy();
+
+    let mut files: Vec<DirEntry> = Vec::new();
+    if config
+        .external_analyzers()
+        .any(|a| a.scope() == AnalyzerScope::File)
+    {
+        if let Err(e) = add_files_to_vec("", &mut files, package.as_ref(), config) {
+            print_warning(format!(
+                "An error occurred when reading files for the external analyzers, the results \
+                 might be incomplete. Error: {}",
+                e
+            ));
+        }
+    }
+
+    for analyzer in config.external_analyzers() {
+        if cancellation::is_cancelled() {
+            return;
+        }
+        if config.is_verbose() {
+            print_message(tr_args(
+                "Running the {} external analyzer.",
+                &[&analyzer.name().italic()],
+            ));
+        }
+
+        let placeholders = [
+            ("{apk}", &*apk),
+            ("{package}", package.as_ref()),
+            ("{dist}", &*dist),
+        ];
+        match analyzer.scope() {
+            AnalyzerScope::Apk => {
+                let args = analyzer.args(&placeholders);
+                run_analyzer(analyzer, &args, config, package.as_ref(), results);
+            }
+            AnalyzerScope::File => {
+                for file in &files {
+                    if cancellation::is_cancelled() {
+                        return;
+                    }
+                    let path = file.path();
+                    let relative_path = path.strip_prefix(&dist_folder).unwrap_or(&path);
+                    if !analyzer.has_to_check(relative_path) {
+                        continue;
+                    }
+
+                    let relative_path = relative_path.to_string_lossy();
+                    let mut file_placeholders = placeholders.to_vec();
+                    file_placeholders.push(("{file}", &*relative_path));
+                    let args = analyzer.args(&file_placeholders);
+                    run_analyzer(analyzer, &args, config, package.as_ref(), results);
+                }
+            }
+        }
+    }
+
+    if config.is_verbose() {
+        print_message("");
+        print_message(tr("The external analyzers were run correctly!").green());
+    } else if !config.is_quiet() {
+        print_message(tr("External analyzers run."));
+    }
+}
+
+/// Runs the given analyzer with the given arguments and adds its findings to the results.
+fn run_analyzer(
+    analyzer: &ExternalAnalyzer,
+    args: &[String],
+    config: &Config,
+    package: &str,
+    results: &mut Results,
+) {
+    match analyzer.run(args, config, &config.dist_folder().join(package)) {
+        Ok(findings) => {
+            for finding in findings {
+                finding.add_to(config, package, results);
+            }
+        }
+        Err(e) => print_warning(format!(
+            "the `{}` external analyzer failed. The analysis will continue, though. Error: {}",
+            analyzer.name(),
+            e
+        )),
+    }
+}
+
+/// Gets the absolute path of the given path, resolving it against the current directory.
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().map_or_else(|_| path.to_path_buf(), |dir| dir.join(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::Path};
+
+    use super::{absolute, AnalyzerScope, ExternalAnalyzer};
+
+    /// Checks the parsing of the external analyzers configuration.
+    #[test]
+    fn it_external_analyzer() {
+        let analyzer: ExternalAnalyzer = toml::from_str(
+            r#"
+            name = "custom"
+            command = "./check.sh"
+            args = ["--apk", "{apk}", "{file}"]
+            scope = "file"
+            extensions = [".java", "xml"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(analyzer.name(), "custom");
+        assert_eq!(analyzer.scope(), AnalyzerScope::File);
+        assert!(analyzer.has_to_check("com/example/A.java"));
+        assert!(analyzer.has_to_check("res/layout/main.xml"));
+        assert!(!analyzer.has_to_check("classes.dex"));
+        assert_eq!(
+            analyzer.args(&[("{apk}", "app.apk"), ("{file}", "A.java")]),
+            vec!["--apk", "app.apk", "A.java"]
+        );
+
+        let analyzer: ExternalAnalyzer =
+            toml::from_str("name = \"apk\"\ncommand = \"check\"").unwrap();
+        assert_eq!(analyzer.scope(), AnalyzerScope::Apk);
+        assert!(analyzer.has_to_check("classes.dex"));
+    }
+
+    /// Checks that the paths passed to the external analyzers are absolute.
+    #[test]
+    fn it_absolute() {
+        assert_eq!(
+            absolute(Path::new("dist/com.example")),
+            env::current_dir().unwrap().join("dist/com.example")
+        );
+        let current_dir = env::current_dir().unwrap();
+        assert_eq!(absolute(&current_dir), current_dir);
+    }
+}
@@ -0,0 +1,333 @@
+//! Results history module.
+//!
+//! If a history database is configured with `--history-db` or the `history_db` configuration
+//! option, each analysis is recorded in it: the fingerprint and version of the application, the
+//! number of vulnerabilities by criticality and the number of hits of each rule. The `history`
+//! subcommand then shows the trend of the analyses of a package over time.
+//!
+//! The database is a SQLite file, so it can also be queried with other tools. It is only available
+//! with the `history` feature.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use chrono::Local;
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+use rusqlite::{params, Connection};
+
+use crate::{criticality::Criticality, i18n::tr, print_message, results::Results, Config};
+
+/// Default number of analyses shown by the `history` subcommand.
+const DEFAULT_LIMIT: &str = "20";
+
+/// Schema of the history database.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS analyses (
+        id INTEGER PRIMARY KEY,
+        package TEXT NOT NULL,
+        version TEXT NOT NULL,
+        version_number INTEGER NOT NULL,
+        sha256 TEXT NOT NULL,
+        analyzed_at TEXT NOT NULL,
+        critical INTEGER NOT NULL,
+        high INTEGER NOT NULL,
+        medium INTEGER NOT NULL,
+        low INTEGER NOT NULL,
+        warnings INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS analyses_package ON analyses (package);
+    CREATE TABLE IF NOT EXISTS rule_hits (
+        analysis_id INTEGER NOT NULL REFERENCES analyses (id),
+        rule TEXT NOT NULL,
+        hits INTEGER NOT NULL,
+        PRIMARY KEY (analysis_id, rule)
+    );
+";
+
+/// Analysis recorded in the history.
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    /// Package of the application.
+    package: String,
+    /// Version string of the application.
+    version: String,
+    /// Version number of the application.
+    version_number: u32,
+    /// SHA-256 hash of the application package.
+    sha256: String,
+    /// Date of the analysis, in RFC 3339 format.
+    analyzed_at: String,
+    /// Number of vulnerabilities by criticality, from critical to warnings.
+    counts: [u32; 5],
+    /// Number of vulnerabilities found by each rule.
+    rule_hits: BTreeMap<String, u32>,
+}
+
+impl Record {
+    /// Creates the record of the given results.
+    fn of(results: &Results) -> Self {
+        let mut counts = [0; 5];
+        let mut rule_hits = BTreeMap::new();
+        for vulnerability in results.vulnerabilities() {
+            let index = match vulnerability.get_criticality() {
+                Criticality::Critical => 0,
+                Criticality::High => 1,
+                Criticality::Medium => 2,
+                Criticality::Low => 3,
+                Criticality::Warning => 4,
+            };
+            counts[index] += 1;
+            *rule_hits
+                .entry(
+                    vulnerability
+                        .rule_id()
+                        .unwrap_or_else(|| vulnerability.name())
+                        .to_owned(),
+                )
+                .or_insert(0) += 1;
+        }
+
+        Self {
+            package: results.app_package().to_owned(),
+            version: results.app_version().to_owned(),
+            version_number: results.app_version_num(),
+            sha256: results.app_sha256(),
+            analyzed_at: Local::now().to_rfc3339(),
+            counts,
+            rule_hits,
+        }
+    }
+
+    /// Gets the total number of vulnerabilities.
+    fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Opens the history database in the given path, creating it if needed.
+fn open<P: AsRef<Path>>(path: P) -> Result<Connection, Error> {
+    let path = path.as_ref();
+    let connection = Connection::open(path).context(format_err!(
+        "could not open the history database `{}`",
+        path.display()
+    ))?;
+    connection
+        .execute_batch(SCHEMA)
+        .context("could not create the history schema")?;
+    Ok(connection)
+}
+
+/// Inserts the given record in the history.
+fn insert(connection: &mut Connection, record: &Record) -> Result<(), Error> {
+    let transaction = connection.transaction()?;
+    let _ = transaction.execute(
+        "INSERT INTO analyses (package, version, version_number, sha256, analyzed_at, critical, \
+         high, medium, low, warnings) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            record.package,
+            record.version,
+            record.version_number,
+            record.sha256,
+            record.analyzed_at,
+            record.counts[0],
+            record.counts[1],
+            record.counts[2],
+            record.counts[3],
+            record.counts[4],
+        ],
+    )?;
+    let analysis_id = transaction.last_insert_rowid();
+    for (rule, hits) in &record.rule_hits {
+        let _ = transaction.execute(
+            "INSERT INTO rule_hits (analysis_id, rule, hits) VALUES (?1, ?2, ?3)",
+            params![analysis_id, rule, hits],
+        )?;
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Gets the last analyses of the given package, from the oldest to the newest.
+fn query(connection: &Connection, package: &str, limit: u32) -> Result<Vec<Record>, Error> {
+    let mut statement = connection.prepare(
+        "SELECT id, package, version, version_number, sha256, analyzed_at, critical, high, \
+         medium, low, warnings FROM analyses WHERE package = ?1 ORDER BY id DESC LIMIT ?2",
+    )?;
+    let mut hits_statement =
+        connection.prepare("SELECT rule, hits FROM rule_hits WHERE analysis_id = ?1")?;
+
+    let rows = statement.query_map(params![package, limit], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            Record {
+                package: row.get(1)?,
+                version: row.get(2)?,
+                version_number: row.get(3)?,
+                sha256: row.get(4)?,
+                analyzed_at: row.get(5)?,
+                counts: [
+                    row.get(6)?,
+                    row.get(7)?,
+                    row.get(8)?,
+                    row.get(9)?,
+                    row.get(10)?,
+                ],
+                rule_hits: BTreeMap::new(),
+            },
+        ))
+    })?;
+
+    let mut records = Vec::new();
+    for row in rows {
+        let (id, mut record) = row?;
+        for hit in hits_statement.query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (rule, hits) = hit?;
+            let _ = record.rule_hits.insert(rule, hits);
+        }
+        records.push(record);
+    }
+    records.reverse();
+    Ok(records)
+}
+
+/// Records the given results in the history database.
+pub(crate) fn record<P: AsRef<Path>>(path: P, results: &Results) -> Result<(), Error> {
+    let mut connection = open(path)?;
+    insert(&mut connection, &Record::of(results))
+}
+
+/// Renders the trend of the given analyses, from the oldest to the newest.
+fn render(records: &[Record]) -> String {
+    let mut output = format!(
+        "{:<20} {:<16} {:>8} {:>5} {:>6} {:>4} {:>8} {:>6} {:>7}\n",
+        "DATE", "VERSION", "CRITICAL", "HIGH", "MEDIUM", "LOW", "WARNINGS", "TOTAL", "CHANGE"
+    );
+    let mut previous: Option<&Record> = None;
+    for record in records {
+        let change = previous.map_or_else(String::new, |previous| {
+            format!(
+                "{:+}",
+                i64::from(record.total()) - i64::from(previous.total())
+            )
+        });
+        output.push_str(&format!(
+            "{:<20} {:<16} {:>8} {:>5} {:>6} {:>4} {:>8} {:>6} {:>7}\n",
+            record.analyzed_at.get(..19).unwrap_or(&record.analyzed_at),
+            record.version,
+            record.counts[0],
+            record.counts[1],
+            record.counts[2],
+            record.counts[3],
+            record.counts[4],
+            record.total(),
+            change
+        ));
+        previous = Some(record);
+    }
+
+    if let [.., previous, last] = records {
+        let mut changes = String::new();
+        let rules = previous
+            .rule_hits
+            .keys()
+            .chain(last.rule_hits.keys())
+            .collect::<BTreeSet<_>>();
+        for rule in rules {
+            let before = previous.rule_hits.get(rule).cloned().unwrap_or(0);
+            let after = last.rule_hits.get(rule).cloned().unwrap_or(0);
+            if before != after {
+                changes.push_str(&format!("  {:>5} → {:<5} {}\n", before, after, rule));
+            }
+        }
+        if !changes.is_empty() {
+            output.push_str(&format!(
+                "\n{}\n{}",
+                tr("Rule hits changed since the previous analysis:").bold(),
+                changes
+            ));
+        }
+    }
+
+    output
+}
+
+/// Runs the `history` subcommand.
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let path = match config.history_db() {
+        Some(path) => path,
+        None => bail!(
+            "no history database is configured, set it with `--history-db` or the `history_db` \
+             configuration option"
+        ),
+    };
+    let package = cli.value_of("package").expect("the package is required");
+    let limit = cli
+        .value_of("limit")
+        .unwrap_or(DEFAULT_LIMIT)
+        .parse::<u32>()
+        .context("the limit must be a positive integer")?;
+
+    let records = query(&open(path)?, package, limit)?;
+    if records.is_empty() {
+        bail!("there are no analyses of `{}` in the history", package);
+    }
+    print_message(render(&records).trim_end());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rusqlite::Connection;
+
+    use super::{insert, query, render, Record, SCHEMA};
+
+    /// Creates a record of the given version, with the given hits of a rule.
+    fn record(version: &str, counts: [u32; 5], hits: u32) -> Record {
+        let mut rule_hits = BTreeMap::new();
+        let _ = rule_hits.insert(String::from("weak_algorithm"), hits);
+        Record {
+            package: String::from("com.example.app"),
+            version: version.to_owned(),
+            version_number: 1,
+            sha256: String::from("aabb"),
+            analyzed_at: String::from("2018-12-01T10:00:00+01:00"),
+            counts,
+            rule_hits,
+        }
+    }
+
+    /// Checks the recording and querying of the history.
+    #[test]
+    fn it_history() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+
+        let first = record("1.0", [0, 2, 1, 0, 3], 2);
+        let second = record("1.1", [1, 1, 1, 0, 3], 1);
+        insert(&mut connection, &first).unwrap();
+        insert(&mut connection, &second).unwrap();
+
+        let records = query(&connection, "com.example.app", 20).unwrap();
+        assert_eq!(records, vec![first, second.clone()]);
+        assert_eq!(
+            query(&connection, "com.example.app", 1).unwrap(),
+            vec![second]
+        );
+        assert!(query(&connection, "com.example.other", 20)
+            .unwrap()
+            .is_empty());
+
+        colored::control::set_override(false);
+        let output = render(&records);
+        let lines = output.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with("2018-12-01T10:00:00  1.0"));
+        assert!(lines[2].ends_with(" 6      +0"));
+        assert!(output.contains("      2 → 1     weak_algorithm\n"));
+    }
+}
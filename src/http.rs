@@ -0,0 +1,92 @@
+//! HTTP client module.
+//!
+//! The subcommands that download files, such as `self-update`, `setup` and `rules update`, share
+//! the HTTP client created here, and the verification of the SHA-256 checksums of the downloaded
+//! files. Proxies can be set with the `--proxy` option of those subcommands or with the
+//! `HTTPS_PROXY` and `ALL_PROXY` environment variables.
+
+use std::{env, io::Read};
+
+#[cfg(any(feature = "self-update", feature = "integrations"))]
+use failure::bail;
+use failure::{format_err, Error, ResultExt};
+use reqwest::{Client, Proxy};
+
+#[cfg(any(feature = "self-update", feature = "integrations"))]
+use crate::utils::sha256_hex;
+
+/// User agent of the HTTP requests, required by the GitHub API.
+pub(crate) const USER_AGENT: &str = concat!("super-analyzer/", env!("CARGO_PKG_VERSION"));
+
+/// Environment variables that can contain the proxy, in order of preference.
+const PROXY_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"];
+
+/// Creates the HTTP client, using the given proxy or the proxy of the environment, if any.
+pub(crate) fn client(proxy: Option<&str>) -> Result<Client, Error> {
+    let proxy = proxy.map(str::to_owned).or_else(|| {
+        PROXY_VARS
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+    });
+
+    let mut builder = Client::builder();
+    if let Some(proxy) = proxy {
+        builder =
+            builder.proxy(Proxy::all(&proxy).context(format_err!("invalid proxy `{}`", proxy))?);
+    }
+    Ok(builder
+        .build()
+        .context("could not create the HTTP client")?)
+}
+
+/// Downloads the file in the given URL with the given client.
+pub(crate) fn download(client: &Client, url: &str) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    let _ = client
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .and_then(reqwest::Response::error_for_status)
+        .context(format_err!("could not download `{}`", url))?
+        .read_to_end(&mut data)
+        .context(format_err!("could not download `{}`", url))?;
+    Ok(data)
+}
+
+/// Verifies the SHA-256 checksum of the given data.
+///
+/// The checksum can be given in the format of `sha256sum`, with the file name after it.
+#[cfg(any(feature = "self-update", feature = "integrations"))]
+pub(crate) fn verify_checksum(data: &[u8], checksum: &str) -> Result<(), Error> {
+    let expected = checksum
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| format_err!("the checksum is empty"))?;
+    let actual = sha256_hex(data);
+
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        bail!(
+            "the checksum does not match: expected {}, got {}",
+            expected,
+            actual
+        )
+    }
+}
+
+#[cfg(all(test, any(feature = "self-update", feature = "integrations")))]
+mod tests {
+    use super::verify_checksum;
+
+    /// Checks the verification of the checksums.
+    #[test]
+    fn it_verify_checksum() {
+        let checksum = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_checksum(b"hello", checksum).is_ok());
+        assert!(verify_checksum(b"hello", &format!("{}  super-linux-x86_64\n", checksum)).is_ok());
+        assert!(verify_checksum(b"hello!", checksum).is_err());
+        assert!(verify_checksum(b"hello", "").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Localization module.
+//!
+//! Messages are written in English in the code, and they are used as keys to look up their
+//! translations in the message catalog of the selected language. Catalogs are TOML files in the
+//! `locales` folder, built into the binary, mapping each English message to its translation.
+//! Messages without a translation are shown in English.
+//!
+//! The language is selected with the `locale` configuration option or, if not set, detected from
+//! the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables. Arguments are inserted in the
+//! `{}` placeholders of the messages, in order.
+//!
+//! The same catalogs are available to the report templates, through the `t` Handlebars helper.
+
+use std::{collections::BTreeMap, env, fmt::Display, sync::RwLock};
+
+use failure::{bail, format_err, Error, ResultExt};
+use lazy_static::lazy_static;
+
+/// Built-in message catalogs, by language.
+const CATALOGS: &[(&str, &str)] = &[("es", include_str!("../locales/es.toml"))];
+
+lazy_static! {
+    /// Message catalog of the selected language.
+    static ref CATALOG: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+}
+
+/// Selects the language of the messages.
+///
+/// The locale can be a language code, such as `es`, or a full POSIX locale, such as
+/// `es_ES.UTF-8`. English is always supported, since it is the language of the messages.
+pub fn set_locale<S: AsRef<str>>(locale: S) -> Result<(), Error> {
+    let language = parse_locale(locale.as_ref())
+        .ok_or_else(|| format_err!("invalid locale `{}`", locale.as_ref()))?;
+    let catalog = if language == "en" {
+        BTreeMap::new()
+    } else {
+        let source = match CATALOGS.iter().find(|&&(lang, _)| lang == language) {
+            Some(&(_, source)) => source,
+            None => bail!("there are no translations for the `{}` language", language),
+        };
+        toml::from_str(source).context(format_err!(
+            "invalid message catalog for the `{}` language",
+            language
+        ))?
+    };
+
+    *CATALOG
+        .write()
+        .map_err(|_| format_err!("the message catalog is poisoned"))? = catalog;
+    Ok(())
+}
+
+/// Detects the locale of the user from the environment.
+pub fn detect_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .filter(|value| parse_locale(value).is_some())
+}
+
+/// Translates the given message to the selected language.
+pub fn tr(message: &str) -> String {
+    CATALOG
+        .read()
+        .ok()
+        .and_then(|catalog| catalog.get(message).cloned())
+        .unwrap_or_else(|| message.to_owned())
+}
+
+/// Translates the given message to the selected language, inserting the given arguments in its
+/// placeholders.
+pub fn tr_args(message: &str, args: &[&dyn Display]) -> String {
+    format_message(&tr(message), args)
+}
+
+/// Inserts the given arguments in the `{}` placeholders of the message, in order.
+fn format_message(message: &str, args: &[&dyn Display]) -> String {
+    let mut parts = message.split("{}");
+    let mut formatted = parts.next().unwrap_or_default().to_owned();
+    let mut args = args.iter();
+    for part in parts {
+        match args.next() {
+            Some(arg) => formatted.push_str(&arg.to_string()),
+            None => formatted.push_str("{}"),
+        }
+        formatted.push_str(part);
+    }
+    formatted
+}
+
+/// Gets the language code of the given POSIX locale, such as `es_ES.UTF-8`.
+///
+/// The `C` and `POSIX` locales are considered English.
+fn parse_locale(locale: &str) -> Option<String> {
+    let language = locale
+        .split(|c| c == '_' || c == '-' || c == '.' || c == '@')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match language.as_str() {
+        "c" | "posix" => Some(String::from("en")),
+        _ if !language.is_empty() && language.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(language)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{format_message, parse_locale, CATALOGS};
+
+    /// Checks the parsing of the locales.
+    #[test]
+    fn it_parse_locale() {
+        assert_eq!(parse_locale("es_ES.UTF-8").unwrap(), "es");
+        assert_eq!(parse_locale("de-DE").unwrap(), "de");
+        assert_eq!(parse_locale("ca_ES@valencia").unwrap(), "ca");
+        assert_eq!(parse_locale("C").unwrap(), "en");
+        assert!(parse_locale("").is_none());
+        assert!(parse_locale("../es").is_none());
+    }
+
+    /// Checks the insertion of the arguments in the messages.
+    #[test]
+    fn it_format_message() {
+        assert_eq!(
+            format_message("Starting analysis of {}.", &[&"app"]),
+            "Starting analysis of app."
+        );
+        assert_eq!(format_message("{} of {}", &[&1, &2]), "1 of 2");
+        assert_eq!(format_message("{} of {}", &[&1]), "1 of {}");
+    }
+
+    /// Checks that the built-in catalogs are valid and keep the placeholders of the messages.
+    #[test]
+    fn it_catalogs() {
+        for (language, source) in CATALOGS {
+            let catalog: BTreeMap<String, String> = toml::from_str(source).unwrap();
+            for (message, translation) in catalog {
+                assert_eq!(
+                    message.matches("{}").count(),
+                    translation.matches("{}").count(),
+                    "wrong placeholders in the `{}` translation of `{}`",
+                    language,
+                    message
+                );
+            }
+        }
+    }
+}
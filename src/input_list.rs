@@ -0,0 +1,240 @@
+//! Input list module.
+//!
+//! Large scans can be driven by an input list, passed with `--input-list`, instead of enumerating
+//! the packages in the command line or in the configuration. It contains one application per line,
+//! optionally followed by options that only apply to that application:
+//!
+//! ```text
+//! # Applications of the weekly fleet scan.
+//! com.example.app
+//! /srv/apks/com.example.other.apk force
+//! https://example.com/builds/latest.apk min_criticality=high
+//! ```
+//!
+//! Applications are given as paths, resolved like the `package` argument of the command line, or
+//! as HTTP(S) URLs, that are downloaded to the downloads folder before the analysis. Empty lines
+//! and lines starting with `#` are ignored. The supported options are `force`, to force the
+//! analysis of the application, and `min_criticality=<criticality>`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::criticality::Criticality;
+#[cfg(feature = "integrations")]
+use crate::{
+    bundle::is_package_extension,
+    http::{self, USER_AGENT},
+};
+
+/// Application in the input list.
+#[derive(Debug, PartialEq)]
+pub(crate) struct InputEntry {
+    /// Path or URL of the application.
+    source: String,
+    /// Options for the analysis of the application.
+    options: PackageOptions,
+}
+
+impl InputEntry {
+    /// Returns the path or URL of the application.
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the options for the analysis of the application.
+    pub(crate) fn options(&self) -> &PackageOptions {
+        &self.options
+    }
+
+    /// Checks if the application has to be downloaded from a URL.
+    pub(crate) fn is_url(&self) -> bool {
+        self.source.starts_with("http://") || self.source.starts_with("https://")
+    }
+}
+
+/// Options for the analysis of a single application.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct PackageOptions {
+    /// Whether the analysis of the application has to be forced.
+    force: bool,
+    /// Minimum criticality of the vulnerabilities to report for the application.
+    min_criticality: Option<Criticality>,
+}
+
+impl PackageOptions {
+    /// Returns whether the analysis of the application has to be forced.
+    pub(crate) fn is_force(&self) -> bool {
+        self.force
+    }
+
+    /// Returns the minimum criticality for the application, if overridden.
+    pub(crate) fn min_criticality(&self) -> Option<Criticality> {
+        self.min_criticality
+    }
+}
+
+/// Reads the input list in the given path.
+pub(crate) fn read<P: AsRef<Path>>(path: P) -> Result<Vec<InputEntry>, Error> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).context(format_err!(
+        "could not read the input list `{}`",
+        path.display()
+    ))?;
+
+    Ok(parse(&content).context(format_err!("invalid input list `{}`", path.display()))?)
+}
+
+/// Parses the contents of an input list.
+fn parse(content: &str) -> Result<Vec<InputEntry>, Error> {
+    let mut entries = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let source = fields.next().unwrap_or_default().to_owned();
+        let mut options = PackageOptions::default();
+        for option in fields {
+            match option.splitn(2, '=').collect::<Vec<_>>().as_slice() {
+                ["force"] => options.force = true,
+                ["min_criticality", criticality] => {
+                    options.min_criticality = Some(criticality.parse().map_err(|_| {
+                        format_err!(
+                            "invalid criticality `{}` in line {}",
+                            criticality,
+                            number + 1
+                        )
+                    })?);
+                }
+                _ => bail!("unknown option `{}` in line {}", option, number + 1),
+            }
+        }
+
+        entries.push(InputEntry { source, options });
+    }
+
+    Ok(entries)
+}
+
+/// Downloads the application in the given URL to the downloads folder, returning its path.
+///
+/// The application is not downloaded again if it already exists, unless the download is forced.
+#[cfg(feature = "integrations")]
+pub(crate) fn download<P: AsRef<Path>>(
+    url: &str,
+    downloads_folder: P,
+    force: bool,
+) -> Result<PathBuf, Error> {
+    let file_name = url
+        .split(|c| c == '?' || c == '#')
+        .next()
+        .and_then(|url| url.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| format_err!("could not get the file name of the URL `{}`", url))?;
+    let mut path = downloads_folder.as_ref().join(file_name);
+    if path
+        .extension()
+        .map_or(true, |extension| !is_package_extension(extension))
+    {
+        let _ = path.set_extension("apk");
+    }
+
+    if force || !path.exists() {
+        download_to(url, &path)?;
+    }
+
+    Ok(path)
+}
+
+/// Downloads the file in the given URL to the given path, replacing it if it exists.
+#[cfg(feature = "integrations")]
+pub(crate) fn download_to(url: &str, path: &Path) -> Result<(), Error> {
+    let mut response = http::client(None)?
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .and_then(reqwest::Response::error_for_status)
+        .context(format_err!("could not download `{}`", url))?;
+    let mut file = fs::File::create(path).context(format_err!(
+        "could not create the file `{}`",
+        path.display()
+    ))?;
+    let _ = response
+        .copy_to(&mut file)
+        .context(format_err!("could not download `{}`", url))?;
+
+    Ok(())
+}
+
+/// Downloads the application in the given URL to the downloads folder, returning its path.
+///
+/// Downloading applications requires the `integrations` feature.
+#[cfg(not(feature = "integrations"))]
+pub(crate) fn download<P: AsRef<Path>>(
+    url: &str,
+    _downloads_folder: P,
+    _force: bool,
+) -> Result<PathBuf, Error> {
+    bail!(
+        "could not download `{}`, downloading applications requires the `integrations` feature",
+        url
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, InputEntry, PackageOptions};
+    use crate::criticality::Criticality;
+
+    /// Checks the parsing of input lists.
+    #[test]
+    fn it_parse() {
+        let entries = parse(
+            "# Fleet scan.\n\
+             com.example.app\n\
+             \n\
+             /srv/apks/other.apk force\n   \
+             https://example.com/latest.apk min_criticality=high force\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                InputEntry {
+                    source: String::from("com.example.app"),
+                    options: PackageOptions::default(),
+                },
+                InputEntry {
+                    source: String::from("/srv/apks/other.apk"),
+                    options: PackageOptions {
+                        force: true,
+                        min_criticality: None,
+                    },
+                },
+                InputEntry {
+                    source: String::from("https://example.com/latest.apk"),
+                    options: PackageOptions {
+                        force: true,
+                        min_criticality: Some(Criticality::High),
+                    },
+                },
+            ]
+        );
+        assert!(!entries[1].is_url());
+        assert!(entries[2].is_url());
+    }
+
+    /// Checks that invalid options are rejected.
+    #[test]
+    fn it_parse_invalid() {
+        assert!(parse("app min_criticality=extreme").is_err());
+        assert!(parse("app\napp --force").is_err());
+    }
+}
@@ -3,6 +3,9 @@
 //! This library contains the code for analyzing Android applications. It's called by the
 //! launcher and contains the main logic of the analysis, with the configuration management,
 //! the logger initialization and some utility functions.
+//!
+//! It can also be embedded in other tools: `analyze_package()` returns the [`Results`] of the
+//! analysis, whose findings can be iterated without parsing the generated reports.
 
 #![forbid(anonymous_parameters)]
 #![warn(clippy::pedantic)]
@@ -37,14 +40,52 @@ extern crate log;
 #[macro_use]
 extern crate serde_derive;
 
+mod adb;
+mod axml;
+pub mod bench;
+mod bundle;
+pub mod cache;
+pub mod cancellation;
 pub mod cli;
 mod config;
+pub mod config_check;
+mod correlation;
 mod criticality;
 mod decompilation;
+mod dex_analysis;
+pub mod diff;
+mod download;
 pub mod error;
+mod external;
+#[cfg(feature = "history")]
+pub mod history;
+#[cfg(any(feature = "self-update", feature = "setup", feature = "integrations"))]
+mod http;
+pub mod i18n;
+mod input_list;
+mod limits;
+pub mod metrics;
+#[cfg(feature = "plugins")]
+mod plugins;
+mod preflight;
+pub mod progress;
 mod results;
+pub mod rules;
+#[cfg(feature = "integrations")]
+mod rules_update;
+#[cfg(feature = "scripting")]
+mod scripting;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod setup;
+mod signature;
 mod static_analysis;
+pub mod stats;
+#[cfg(feature = "self-update")]
+pub mod update;
 mod utils;
+pub mod verify;
+pub mod watch;
 
 use std::{
     collections::BTreeMap,
@@ -59,21 +100,57 @@ use colored::Colorize;
 use failure::{bail, format_err, Error, ResultExt};
 
 pub use crate::{
-    config::Config,
+    config::{Config, Profile},
+    correlation::CorrelationRule,
+    criticality::Criticality,
+    decompilation::Decompiler,
+    download::{DownloadConfig, DownloadSource},
+    external::{AnalyzerScope, ExternalAnalyzer},
+    limits::ResourceLimits,
+    results::{
+        register_generator, Confidence, Cvss, DefectDojoApi, DependencyTrackConfig, Generator,
+        Grade, ImportFormat, JiraConfig, Results, Risk, RiskWeights, Stage, StageStatus,
+        Vulnerability, VulnerabilityBuilder,
+    },
+    static_analysis::{
+        analyzer::{register_analyzer, AnalysisContext, Analyzer},
+        backup::{BackupPath, BackupRules, BackupSection},
+        dependencies::{LibraryVulnerability, VulnerabilityDatabase},
+        endpoints::Endpoint,
+        firebase::FirebaseConfig,
+        frameworks::Framework,
+        libraries::Library,
+        manifest::{
+            Component, ComponentKind, CustomPermission, InstallLocation, IntentData, IntentFilter,
+            Manifest, Permission, PermissionCatalog, PermissionChecklist, PermissionCombination,
+        },
+        native::{NativeLibrary, Relro},
+        permission_usage::PermissionUsage,
+        resilience::{AppCategory, Protection, ProtectionCheck},
+        secrets::{Secret, SecretKind},
+    },
     utils::{
-        get_code, get_package_name, get_string, print_vulnerability, print_warning, Benchmark,
+        get_code, get_package_name, get_string, is_no_color_set, output_mode, print_message,
+        print_vulnerability, print_warning, set_output_mode, Benchmark, ColorChoice, OutputMode,
         PARSER_CONFIG,
     },
 };
 use crate::{
     decompilation::{decompile, decompress, dex_to_jar},
-    results::Results,
+    i18n::{tr, tr_args},
+    progress::Phase,
     static_analysis::static_analysis,
 };
 
 /// Logo ASCII art, used in verbose mode.
 pub static BANNER: &str = include_str!("banner.txt");
 
+/// Reason why the phases that the `quick` profile does not run are skipped.
+const QUICK_PROFILE_REASON: &str = "the quick profile only analyzes the manifest and the resources";
+
+/// Reason why the phases that did not start before the analysis was cancelled are skipped.
+const CANCELLED_REASON: &str = "the analysis was cancelled";
+
 /// Initialize the config with the config files and command line options.
 ///
 /// On UNIX, if local file, `config.toml`, does not exist, but the global one does
@@ -103,29 +180,81 @@ pub fn initialize_config(cli: &ArgMatches<'static>) -> Result<Config, Error> {
     config
         .decorate_with_cli(cli)
         .context("there was an error reading the configuration from the CLI")?;
+    config.use_installed_tools();
+    set_output_mode(config.output_mode());
+
+    if let Some(locale) = config.locale() {
+        if let Err(e) = i18n::set_locale(locale) {
+            print_warning(format!("{}. Using English", e));
+        }
+    } else if let Some(locale) = i18n::detect_locale() {
+        // Unsupported system locales silently fall back to English.
+        let _ = i18n::set_locale(locale);
+    }
 
     Ok(config)
 }
 
 /// Analyzes the given package with the given configuration.
-#[allow(clippy::print_stdout)]
+///
+/// The reports are written to the results folder, and the populated results are also returned,
+/// so that the findings can be used in memory by tools that embed the analyzer.
+///
+/// If the analysis is cancelled with [`cancellation::cancel()`] once the application has been
+/// validated, the interrupted phase is recorded as cancelled instead of failed, the remaining
+/// phases are skipped and a partial report, marked as incomplete, is written and returned.
+///
+/// With `--keep-going`, the analysis also goes on if the decompression fails, with the files that
+/// could be extracted, and the failure is recorded in the results.
+///
+/// The phases that run depend on the [`Profile`] of the configuration: the `quick` profile does
+/// not decompile the application, and only analyzes its manifest and its resources.
 pub fn analyze_package<P: AsRef<Path>>(
     package: P,
     config: &mut Config,
     benchmarks: &mut BTreeMap<String, Vec<Benchmark>>,
-) -> Result<(), Error> {
-    let package_name = get_package_name(&package);
+) -> Result<Results, Error> {
+    set_output_mode(config.output_mode());
+    let package_name = config.package_name(&package);
     if config.is_bench() {
         let _ = benchmarks.insert(package_name.clone(), Vec::with_capacity(4));
     }
     if !config.is_quiet() {
-        println!();
-        println!("Starting analysis of {}.", package_name.italic());
+        print_message("");
+        print_message(tr_args(
+            "Starting analysis of {}.",
+            &[&package_name.italic()],
+        ));
     }
+    metrics::record_analysis();
+    stats::start_package(package_name.as_str());
+
+    // Pre-flight validation of the package.
+    preflight::validate(&package).map_err(|e| metrics::record_failure("validation", e))?;
 
     // Apk decompression.
     let start_time = Instant::now();
-    decompress(config, &package).context("apk decompression failed")?;
+    progress::phase_started(&package_name, Phase::Decompression);
+    let decompression = decompress(config, &package);
+    progress::phase_finished(&package_name, Phase::Decompression, start_time.elapsed());
+    let decompression_status = match decompression {
+        Ok(()) => StageStatus::Ok,
+        Err(_) if cancellation::is_cancelled() => StageStatus::failed(error::Kind::Cancelled),
+        Err(e) => {
+            let e = metrics::record_failure("decompression", e);
+            if !config.is_keep_going() {
+                return Err(e.context("apk decompression failed").into());
+            }
+            print_warning(format!(
+                "apk decompression failed, the analysis will go on with the extracted files: {}",
+                e
+            ));
+            StageStatus::failed(e)
+        }
+    };
+    metrics::record_duration("decompression", start_time.elapsed());
+    stats::record_duration(&package_name, "decompression", start_time.elapsed());
+    let mut stages = vec![("decompression", decompression_status)];
 
     if config.is_bench() {
         benchmarks
@@ -136,7 +265,32 @@ pub fn analyze_package<P: AsRef<Path>>(
 
     // Converting the .dex to .jar.
     let dex_jar_time = Instant::now();
-    dex_to_jar(config, &package).context("conversion from DEX to JAR failed")?;
+    progress::phase_started(&package_name, Phase::DexToJar);
+    let mut dex_files = None;
+    let dex_to_jar_status = if !config.is_decompilation() {
+        StageStatus::skipped(QUICK_PROFILE_REASON)
+    } else if cancellation::is_cancelled() {
+        StageStatus::skipped(CANCELLED_REASON)
+    } else {
+        match dex_to_jar(config, &package) {
+            Ok(files) => {
+                dex_files = Some(files);
+                StageStatus::Ok
+            }
+            Err(_) if cancellation::is_cancelled() => StageStatus::failed(error::Kind::Cancelled),
+            Err(e) => {
+                let e = metrics::record_failure("dex_to_jar", e);
+                print_warning(format!(
+                    "conversion from DEX to JAR failed, the code will not be decompiled: {}",
+                    e
+                ));
+                StageStatus::failed(e)
+            }
+        }
+    };
+    progress::phase_finished(&package_name, Phase::DexToJar, dex_jar_time.elapsed());
+    metrics::record_duration("dex_to_jar", dex_jar_time.elapsed());
+    stats::record_duration(&package_name, "dex_to_jar", dex_jar_time.elapsed());
 
     if config.is_bench() {
         benchmarks
@@ -149,16 +303,80 @@ pub fn analyze_package<P: AsRef<Path>>(
     }
 
     if config.is_verbose() {
-        println!();
-        println!(
-            "Now it's time for the actual decompilation of the source code. We'll translate
-             Android JVM bytecode to Java, so that we can check the code afterwards."
-        );
+        print_message("");
+        print_message(tr(
+            "Now it's time for the actual decompilation of the source code. We'll translate \
+             Android JVM bytecode to Java, so that we can check the code afterwards.",
+        ));
     }
 
     // Decompiling the app
     let decompile_start = Instant::now();
-    decompile(config, &package).context("JAR decompression failed")?;
+    progress::phase_started(&package_name, Phase::Decompilation);
+    let decompile_status = if !config.is_decompilation() {
+        StageStatus::skipped(QUICK_PROFILE_REASON)
+    } else if cancellation::is_cancelled() {
+        StageStatus::skipped(CANCELLED_REASON)
+    } else if let Some(dex_files) = dex_files.as_mut() {
+        match decompile(config, &package, dex_files) {
+            Ok(()) => StageStatus::Ok,
+            Err(_) if cancellation::is_cancelled() => StageStatus::failed(error::Kind::Cancelled),
+            Err(e) => {
+                let e = metrics::record_failure("decompilation", e);
+                print_warning(format!(
+                    "JAR decompilation failed, the code analysis will be incomplete: {}",
+                    e
+                ));
+                StageStatus::failed(e)
+            }
+        }
+    } else {
+        StageStatus::skipped("the DEX files could not be converted to JAR")
+    };
+    progress::phase_finished(
+        &package_name,
+        Phase::Decompilation,
+        decompile_start.elapsed(),
+    );
+    metrics::record_duration("decompilation", decompile_start.elapsed());
+    stats::record_duration(&package_name, "decompilation", decompile_start.elapsed());
+
+    // Direct analysis of the DEX files, if they could not be decompiled
+    let dex_analysis_status = if cancellation::is_cancelled() || !config.is_decompilation() {
+        None
+    } else if decompile_status.is_ok() {
+        if let Err(e) = dex_analysis::remove_listings(config, &package_name) {
+            print_warning(e.to_string());
+        }
+        None
+    } else {
+        let status = match dex_analysis::generate_listings(config, &package_name) {
+            Ok(classes) => {
+                if !config.is_quiet() {
+                    print_message(tr_args(
+                        "The DEX code of {} classes has been listed for the analysis.",
+                        &[&classes],
+                    ));
+                }
+                StageStatus::Ok
+            }
+            Err(e) => {
+                let e = metrics::record_failure("dex_analysis", e);
+                print_warning(format!(
+                    "the DEX files could not be analyzed, the code analysis will be incomplete: {}",
+                    e
+                ));
+                StageStatus::failed(e)
+            }
+        };
+        Some(status)
+    };
+    // The phases skipped by the profile do not make the report incomplete.
+    if config.is_decompilation() {
+        stages.push(("dex_to_jar", dex_to_jar_status));
+        stages.push(("decompilation", decompile_status));
+    }
+    stages.extend(dex_analysis_status.map(|status| ("dex_analysis", status)));
 
     if config.is_bench() {
         benchmarks
@@ -171,11 +389,53 @@ pub fn analyze_package<P: AsRef<Path>>(
     }
 
     // Initialize results structure
-    let mut results = Results::init(config, &package)?;
+    let mut results =
+        Results::init(config, &package).map_err(|e| metrics::record_failure("results", e))?;
+    for (stage, status) in stages {
+        results.set_stage_status(stage, status);
+    }
+    if let Some(dex_files) = dex_files {
+        results.set_dex_files(dex_files);
+    }
 
     // Static application analysis
     let static_start = Instant::now();
-    static_analysis(config, &package_name, &mut results);
+    if !cancellation::is_cancelled() {
+        static_analysis(config, &package_name, &mut results);
+    }
+
+    // External analyzers
+    if !cancellation::is_cancelled() {
+        external::analysis(config, &package, &package_name, &mut results);
+    }
+
+    // Findings from other scanners
+    results::import_reports(config, &package_name, &mut results);
+
+    // Distinct IDs for repeated findings, before the correlation rules refer to them
+    results.number_occurrences();
+
+    // Composite findings from the correlation rules
+    correlation::analysis(config, &mut results);
+
+    // Partial results of a cancelled analysis
+    if cancellation::is_cancelled() {
+        print_warning("the analysis was cancelled, the report will be incomplete");
+        results.set_stage_status("analysis", StageStatus::failed(error::Kind::Cancelled));
+    }
+
+    // Previously analyzed applications similar to this one
+    results::find_similar_applications(config, &mut results);
+
+    // Known findings in the baseline
+    results::apply_baseline(config, &mut results);
+
+    // Overall risk grade of the application
+    results.compute_risk(config.risk());
+    metrics::record_duration("static_analysis", static_start.elapsed());
+    stats::record_duration(&package_name, "static_analysis", static_start.elapsed());
+    metrics::record_findings(&results);
+    stats::record_results(&package_name, &results);
 
     if config.is_bench() {
         benchmarks
@@ -188,27 +448,72 @@ pub fn analyze_package<P: AsRef<Path>>(
     }
 
     if !config.is_quiet() {
-        println!();
+        print_message("");
     }
 
     // Generate results report.
     let report_start = Instant::now();
-    results
-        .generate_report(config, &package_name)
+    progress::phase_started(&package_name, Phase::Report);
+    let report = results.generate_report(config);
+    progress::phase_finished(&package_name, Phase::Report, report_start.elapsed());
+    report
+        .map_err(|e| metrics::record_failure("report", e))
         .context(format_err!(
             "there was an error generating the results report at: {}",
             config.results_folder().join(&package_name).display()
         ))?;
+    metrics::record_duration("report", report_start.elapsed());
+    stats::record_duration(&package_name, "report", report_start.elapsed());
+
+    // Record the analysis in the history database.
+    if let Some(history_db) = config.history_db() {
+        #[cfg(feature = "history")]
+        {
+            if let Err(e) = history::record(history_db, &results) {
+                print_warning(format!(
+                    "could not record the analysis in the history: {}",
+                    e
+                ));
+            }
+        }
+        #[cfg(not(feature = "history"))]
+        {
+            let _ = history_db;
+            print_warning(
+                "SUPER was built without the `history` feature, the analysis will not be \
+                 recorded in the history",
+            );
+        }
+    }
+
+    // Jira issues synchronization.
+    #[cfg(feature = "integrations")]
+    {
+        if let Some(jira) = config.jira() {
+            results::sync_jira_issues(config, jira, &results);
+        }
+    }
+
+    if !config.is_quiet() {
+        print_message("");
+        print_message(
+            results::Summary::new(config, &results)
+                .to_string()
+                .trim_end(),
+        );
+    }
 
     if config.is_verbose() {
-        println!("Everything went smoothly, you can now check all the results.");
-        println!();
-        println!("I will now analyze myself for vulnerabilities…");
+        print_message(tr(
+            "Everything went smoothly, you can now check all the results.",
+        ));
+        print_message("");
+        print_message(tr("I will now analyze myself for vulnerabilities…"));
         sleep(Duration::from_millis(1500));
-        println!(
+        print_message(tr_args(
             "Nah, just kidding, I've been developed in {}!",
-            "Rust".bold().green()
-        )
+            &[&"Rust".bold().green()],
+        ))
     }
 
     if config.is_bench() {
@@ -246,7 +551,7 @@ pub fn analyze_package<P: AsRef<Path>>(
         }
     }
 
-    Ok(())
+    Ok(results)
 }
 
 /// Copies the contents of `from` to `to`
@@ -282,10 +587,13 @@ pub fn copy_folder<P: AsRef<Path>>(from: P, to: P) -> Result<(), Error> {
 ///
 /// This will initialize the environment logger structure so that it generates the
 /// proper messages using the right colors. It's called from the launcher.
+///
+/// The colors of the log messages follow the given color choice, checking if the standard error
+/// is a terminal in the automatic mode.
 #[allow(clippy::print_stdout)]
-pub fn initialize_logger(is_verbose: bool) -> Result<(), log::SetLoggerError> {
+pub fn initialize_logger(is_verbose: bool, color: ColorChoice) -> Result<(), log::SetLoggerError> {
     use env_logger::fmt::{Color, Formatter};
-    use env_logger::Builder;
+    use env_logger::{Builder, WriteStyle};
     use log::{Level, LevelFilter, Record};
     use std::io::Write;
 
@@ -320,7 +628,15 @@ pub fn initialize_logger(is_verbose: bool) -> Result<(), log::SetLoggerError> {
         LevelFilter::Info
     };
 
+    let write_style = match color {
+        ColorChoice::Always => WriteStyle::Always,
+        ColorChoice::Never => WriteStyle::Never,
+        ColorChoice::Auto if is_no_color_set() => WriteStyle::Never,
+        ColorChoice::Auto => WriteStyle::Auto,
+    };
+
     let mut builder = Builder::new();
+    let _ = builder.write_style(write_style);
 
     // Initialize the logger.
     if let Ok(env_log) = env::var("RUST_LOG") {
@@ -461,9 +777,11 @@ mod tests {
         config.add_app_package("downloads/test_app");
 
         // Run the analysis
-        analyze_package("downloads/test_app.apk", &mut config, &mut benchmarks).unwrap();
+        let results =
+            analyze_package("downloads/test_app.apk", &mut config, &mut benchmarks).unwrap();
+        assert_eq!(results.app_package(), "com.javiersantos.mlmanager");
 
-        // TODO: check results.
+        // TODO: check the findings.
 
         // Remove generated files.
         if need_to_create {
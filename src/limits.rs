@@ -0,0 +1,340 @@
+//! Resource limits for external tools.
+//!
+//! The dex2jar and jd-cmd tools are run as child processes, and they process untrusted input. To
+//! avoid a single hostile application exhausting the resources of the machine, their CPU time,
+//! memory and output size can be limited in the `[limits]` section of the configuration:
+//!
+//! ```toml
+//! [limits]
+//! cpu_time = 600    # seconds
+//! memory = 4096     # MiB
+//! output_size = 64  # MiB, for both the standard output and the standard error
+//! ```
+//!
+//! On UNIX systems, CPU time and memory are limited with `ulimit`, that needs no privileges,
+//! instead of cgroups, that need root or a delegated cgroup. Note that the memory limit applies to
+//! the virtual memory of the process, and that the JVM reserves more virtual memory than it uses,
+//! so it should be generous.
+//!
+//! On Windows, the processes are assigned to a Job Object right after they are started, that
+//! limits the user mode CPU time and the committed memory of each process of the job, and that
+//! kills the processes that are still running when the tool finishes. On other systems only the
+//! output size is limited.
+//!
+//! Child processes are also killed if the analysis is cancelled.
+
+use std::{
+    ffi::OsStr,
+    io::{self, Read},
+    process::{Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use failure::{bail, Error, ResultExt};
+
+use crate::{cancellation, error};
+
+/// Interval at which the running child processes are checked for cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Resource limits for the child processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct ResourceLimits {
+    /// Maximum CPU time, in seconds.
+    cpu_time: Option<u64>,
+    /// Maximum virtual memory, in MiB.
+    memory: Option<u64>,
+    /// Maximum size of the standard output and standard error, in MiB.
+    output_size: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Returns the maximum CPU time, in seconds.
+    pub fn cpu_time(&self) -> Option<u64> {
+        self.cpu_time
+    }
+
+    /// Returns the maximum virtual memory, in MiB.
+    pub fn memory(&self) -> Option<u64> {
+        self.memory
+    }
+
+    /// Returns the maximum size of the standard output and standard error, in MiB.
+    pub fn output_size(&self) -> Option<u64> {
+        self.output_size
+    }
+
+    /// Creates the command for the given program, applying the CPU time and memory limits.
+    ///
+    /// In UNIX systems, the limits are applied by running the program through `sh`, that sets
+    /// them with `ulimit` before executing the program. In Windows, they are applied by
+    /// `output()`, once the program starts.
+    pub(crate) fn command<S: AsRef<OsStr>>(&self, program: S) -> Command {
+        let mut ulimits = String::new();
+        if cfg!(target_family = "unix") {
+            if let Some(cpu_time) = self.cpu_time {
+                ulimits.push_str(&format!("ulimit -t {} && ", cpu_time));
+            }
+            if let Some(memory) = self.memory {
+                ulimits.push_str(&format!("ulimit -v {} && ", memory * 1024));
+            }
+        }
+
+        if ulimits.is_empty() {
+            Command::new(program)
+        } else {
+            let mut command = Command::new("sh");
+            let _ = command
+                .arg("-c")
+                .arg(format!("{}exec \"$0\" \"$@\"", ulimits))
+                .arg(program);
+            command
+        }
+    }
+
+    /// Runs the given command, applying the output size limit, and collects its output.
+    ///
+    /// The process is killed if the analysis is cancelled while it runs.
+    pub(crate) fn output(&self, command: &mut Command) -> Result<Output, Error> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        #[cfg(windows)]
+        let _job = self.limit(&mut child)?;
+        let limit = self.output_size.map(|size| size * 1024 * 1024);
+        let exceeded = Arc::new(AtomicBool::new(false));
+
+        let stdout = child
+            .stdout
+            .take()
+            .map(|pipe| read_limited(pipe, limit, Arc::clone(&exceeded)));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|pipe| read_limited(pipe, limit, Arc::clone(&exceeded)));
+
+        let status = loop {
+            if let Some(status) = child
+                .try_wait()
+                .context("could not wait for the child process")?
+            {
+                break status;
+            }
+            if cancellation::is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(error::Kind::Cancelled.into());
+            }
+            // If any of the outputs exceeds the limit, the process is killed, so that it does not
+            // keep running without anyone reading its output.
+            if exceeded.load(Ordering::SeqCst) {
+                let _ = child.kill();
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        let stdout = join_output(stdout);
+        let stderr = join_output(stderr);
+
+        let (stdout, stderr) = match (stdout, stderr) {
+            (Some(stdout), Some(stderr)) => (stdout, stderr),
+            _ => bail!(
+                "the command output exceeded the limit of {} MiB",
+                self.output_size.unwrap_or_default()
+            ),
+        };
+
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Assigns the given child process to a Job Object with the CPU time and memory limits, if
+    /// any.
+    ///
+    /// The child process is killed if the limits cannot be applied.
+    #[cfg(windows)]
+    fn limit(&self, child: &mut std::process::Child) -> Result<Option<job::Job>, Error> {
+        if self.cpu_time.is_none() && self.memory.is_none() {
+            return Ok(None);
+        }
+
+        match job::Job::new(self.cpu_time, self.memory)
+            .and_then(|job| job.assign(child).map(|()| job))
+        {
+            Ok(job) => Ok(Some(job)),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(
+                    failure::Fail::context(e, "could not limit the resources of the child process")
+                        .into(),
+                )
+            }
+        }
+    }
+}
+
+/// Reads the given pipe in a new thread, up to the given limit in bytes.
+///
+/// The thread returns an error, and sets the given flag, if the limit is exceeded.
+fn read_limited<R: Read + Send + 'static>(
+    pipe: R,
+    limit: Option<u64>,
+    exceeded: Arc<AtomicBool>,
+) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        match limit {
+            Some(limit) => {
+                let _ = pipe.take(limit + 1).read_to_end(&mut buffer)?;
+                if buffer.len() as u64 > limit {
+                    exceeded.store(true, Ordering::SeqCst);
+                    return Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        "output size limit exceeded",
+                    ));
+                }
+            }
+            None => {
+                let mut pipe = pipe;
+                let _ = pipe.read_to_end(&mut buffer)?;
+            }
+        }
+        Ok(buffer)
+    })
+}
+
+/// Joins the thread reading the output of a pipe.
+///
+/// Returns `None` if the output could not be read or if it exceeded the limit.
+fn join_output(handle: Option<thread::JoinHandle<io::Result<Vec<u8>>>>) -> Option<Vec<u8>> {
+    match handle {
+        Some(handle) => handle.join().ok().and_then(Result::ok),
+        None => Some(Vec::new()),
+    }
+}
+
+/// Windows Job Objects, that limit the resources of the child processes.
+#[cfg(windows)]
+#[allow(unsafe_code)]
+mod job {
+    use std::{io, mem, os::windows::io::AsRawHandle, process::Child, ptr};
+
+    use winapi::um::{
+        handleapi::CloseHandle,
+        jobapi2::{AssignProcessToJobObject, CreateJobObjectW, SetInformationJobObject},
+        winnt::{
+            JobObjectExtendedLimitInformation, HANDLE, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+            JOB_OBJECT_LIMIT_PROCESS_TIME,
+        },
+    };
+
+    /// Job Object that limits the resources of the processes assigned to it.
+    ///
+    /// The processes of the job that are still running are killed when it is dropped.
+    #[derive(Debug)]
+    pub(super) struct Job(HANDLE);
+
+    impl Job {
+        /// Creates a job with the given limits of CPU time, in seconds, and memory, in MiB, for
+        /// each of its processes.
+        pub(super) fn new(cpu_time: Option<u64>, memory: Option<u64>) -> io::Result<Self> {
+            let handle = unsafe { CreateJobObjectW(ptr::null_mut(), ptr::null()) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Job(handle);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { mem::zeroed() };
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if let Some(cpu_time) = cpu_time {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                // The CPU time limit is set in units of 100 nanoseconds.
+                unsafe {
+                    *info
+                        .BasicLimitInformation
+                        .PerProcessUserTimeLimit
+                        .QuadPart_mut() = (cpu_time * 10_000_000) as i64;
+                }
+            }
+            if let Some(memory) = memory {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                info.ProcessMemoryLimit = (memory * 1024 * 1024) as usize;
+            }
+
+            let result = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &mut info as *mut JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *mut _,
+                    mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                )
+            };
+            if result == 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(job)
+        }
+
+        /// Assigns the given child process to the job.
+        pub(super) fn assign(&self, child: &Child) -> io::Result<()> {
+            let result =
+                unsafe { AssignProcessToJobObject(self.0, child.as_raw_handle() as HANDLE) };
+            if result == 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            let _ = unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceLimits;
+
+    /// Checks the parsing of the resource limits.
+    #[test]
+    fn it_resource_limits() {
+        let limits: ResourceLimits = toml::from_str("cpu_time = 60\noutput_size = 1").unwrap();
+
+        assert_eq!(limits.cpu_time(), Some(60));
+        assert_eq!(limits.memory(), None);
+        assert_eq!(limits.output_size(), Some(1));
+        assert_eq!(ResourceLimits::default().cpu_time(), None);
+    }
+
+    /// Checks that the output size limit is enforced.
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn it_output_limit() {
+        let limits: ResourceLimits = toml::from_str("cpu_time = 10\noutput_size = 1").unwrap();
+
+        let output = limits
+            .output(limits.command("echo").arg("limited"))
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"limited\n");
+
+        assert!(limits
+            .output(limits.command("head").args(&["-c", "2000000", "/dev/zero"]))
+            .is_err());
+    }
+}
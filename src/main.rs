@@ -30,33 +30,52 @@ use std::{
 };
 
 use colored::Colorize;
-use failure::{Error, ResultExt};
+use failure::{bail, Error, ResultExt};
 use log::Level;
 
 use super_analyzer_core::{
-    analyze_package, cli, error, initialize_config, initialize_logger, Benchmark, BANNER,
+    analyze_package, bench, cache, cancellation, cli, config_check, diff, error,
+    i18n::{tr, tr_args},
+    initialize_config, initialize_logger, metrics, print_warning,
+    progress::{self, VerboseProgress},
+    rules, setup, stats, verify, watch, Benchmark, ColorChoice, BANNER,
 };
 
+#[cfg(feature = "history")]
+use super_analyzer_core::history;
+#[cfg(feature = "server")]
+use super_analyzer_core::server;
+#[cfg(feature = "self-update")]
+use super_analyzer_core::update;
+
 /// Program entry point.
 ///
 /// This function will just call the `run()` function and report any fatal error that comes out
-/// of it. It will also exit with a non-zero exit code if things go wrong.
+/// of it. It will also exit with a non-zero exit code if things go wrong, or with the exit code 2
+/// if findings at or above the `--fail-on` criticality were found.
 fn main() {
     // Call the `run()` function and check for errors.
     if let Err(e) = run() {
+        if let Some(error::Kind::FindingsFound { .. }) = e.downcast_ref::<error::Kind>() {
+            error!("{}", e);
+            ::std::process::exit(2);
+        }
         error!("{}", e);
 
         // After printing the error, print the causes, in order.
         for e in e.iter_causes() {
-            println!("\t{}{}", "Caused by: ".bold(), e);
+            println!("\t{} {}", tr("Caused by:").bold(), e);
         }
 
         // If the verbose mode is not enabled, we add a message so that the user knows that can
         // get further information with the `-v` flag in the CLI.
         if !log_enabled!(Level::Debug) {
             println!(
-                "If you need more information, try to run the program again with the {} flag.",
-                "-v".bold()
+                "{}",
+                tr_args(
+                    "If you need more information, try to run the program again with the {} flag.",
+                    &[&"-v".bold()]
+                )
             );
         }
 
@@ -74,12 +93,77 @@ fn run() -> Result<(), Error> {
     // Check the CLI arguments.
     let cli = cli::generate().get_matches();
     let verbose = cli.is_present("verbose");
+    let color = cli
+        .value_of("color")
+        .unwrap_or("auto")
+        .parse::<ColorChoice>()?;
+    color.apply();
     // Initialize all logger, specifying if the user wanted verbose mode.
-    initialize_logger(verbose).context("could not initialize the logger")?;
+    initialize_logger(verbose, color).context("could not initialize the logger")?;
 
     // Load the configuration.
     let mut config = initialize_config(&cli)?;
 
+    // Run the rules commands, that do not analyze any application.
+    if cli.is_present("list-rules") {
+        return rules::list(&config);
+    }
+    if let Some(rule_id) = cli.value_of("explain") {
+        return rules::explain(&config, rule_id);
+    }
+    if let Some(rules_cli) = cli.subcommand_matches("rules") {
+        return rules::run(&config, rules_cli);
+    }
+    if let Some(test_cli) = cli.subcommand_matches("test-rules") {
+        return rules::test(&config, test_cli);
+    }
+
+    // Update SUPER, if requested.
+    if let Some(update_cli) = cli.subcommand_matches("self-update") {
+        #[cfg(feature = "self-update")]
+        {
+            return update::run(&config, update_cli);
+        }
+        #[cfg(not(feature = "self-update"))]
+        {
+            let _ = update_cli;
+            bail!("SUPER was built without the `self-update` feature");
+        }
+    }
+
+    // Install the tools, if requested.
+    if let Some(setup_cli) = cli.subcommand_matches("setup") {
+        return setup::run(&mut config, setup_cli);
+    }
+
+    // Clean the decompilation cache, if requested.
+    if let Some(clean_cli) = cli.subcommand_matches("clean") {
+        return cache::run(&config, clean_cli);
+    }
+
+    // Show the history of a package, if requested.
+    if let Some(history_cli) = cli.subcommand_matches("history") {
+        #[cfg(feature = "history")]
+        {
+            return history::run(&config, history_cli);
+        }
+        #[cfg(not(feature = "history"))]
+        {
+            let _ = history_cli;
+            bail!("SUPER was built without the `history` feature");
+        }
+    }
+
+    // Verify a report, if requested.
+    if let Some(verify_cli) = cli.subcommand_matches("verify") {
+        return verify::run(&config, verify_cli);
+    }
+
+    // Check the configuration or print its schema, if requested.
+    if let Some(config_cli) = cli.subcommand_matches("config") {
+        return config_check::run(&config, config_cli);
+    }
+
     // Check the configuration and return an error with the loaded files.
     if !config.check() {
         let mut error_string = String::from("configuration errors were found:\n");
@@ -94,6 +178,7 @@ fn run() -> Result<(), Error> {
         for file in config.loaded_config_files() {
             error_string.push_str(&format!("\t- {}\n", file.display()));
         }
+        config.remove_temporary_packages();
 
         return Err(error::Kind::Config {
             message: error_string,
@@ -101,6 +186,24 @@ fn run() -> Result<(), Error> {
         .into());
     }
 
+    // Compare two versions of an application, if requested.
+    if let Some(diff_cli) = cli.subcommand_matches("diff") {
+        return diff::run(config, diff_cli);
+    }
+
+    // Run the REST API server, if requested.
+    if let Some(serve_cli) = cli.subcommand_matches("serve") {
+        #[cfg(feature = "server")]
+        {
+            return server::run(config, serve_cli);
+        }
+        #[cfg(not(feature = "server"))]
+        {
+            let _ = serve_cli;
+            bail!("SUPER was built without the `server` feature");
+        }
+    }
+
     // Print the banner if we are in verbose mode.
     if config.is_verbose() {
         for c in BANNER.chars() {
@@ -109,14 +212,32 @@ fn run() -> Result<(), Error> {
             sleep(Duration::from_millis(3));
         }
         println!(
-            "Welcome to the SUPER Android Analyzer. We will now try to audit the given application."
+            "{}",
+            tr(
+                "Welcome to the SUPER Android Analyzer. We will now try to audit the given \
+                 application."
+            )
         );
         println!(
-            "You activated the verbose mode. {}",
-            "May Tux be with you!".bold()
+            "{} {}",
+            tr("You activated the verbose mode."),
+            tr("May Tux be with you!").bold()
         );
         println!();
         sleep(Duration::from_millis(1250));
+
+        // Print the progress of the code analysis.
+        progress::set_observer(VerboseProgress::default());
+    }
+
+    // Watch a folder for new applications, if requested.
+    if let Some(folder) = cli.value_of("watch") {
+        return watch::run(config, folder);
+    }
+
+    // Stop the analysis cleanly with Ctrl-C.
+    if let Err(e) = cancellation::handle_ctrl_c() {
+        print_warning(format!("could not handle Ctrl-C: {}", e));
     }
 
     // Start benchmarks.
@@ -124,25 +245,85 @@ fn run() -> Result<(), Error> {
 
     let total_start = Instant::now();
     // Analyze each apk one by one.
+    let mut analysis = Ok(());
+    let mut failed_packages = 0;
     for package in config.app_packages() {
-        config.reset_force();
-        analyze_package(package, &mut config, &mut benchmarks)
-            .context("application analysis failed")?;
+        config.select_package(&package);
+        let package_name = config.package_name(&package);
+        analysis = analyze_package(package, &mut config, &mut benchmarks).map(|_| ());
+        if let Some(metrics_file) = config.metrics_file() {
+            if let Err(e) = metrics::write_textfile(metrics_file) {
+                print_warning(format!("could not export the metrics: {}", e));
+            }
+        }
+        if let Err(e) = &analysis {
+            if !config.is_keep_going() || cancellation::is_cancelled() {
+                break;
+            }
+            print_warning(format!(
+                "the analysis of {} failed, the rest of applications will be analyzed: {}",
+                package_name, e
+            ));
+            failed_packages += 1;
+            analysis = Ok(());
+        }
+        if cancellation::is_cancelled() {
+            analysis = Err(error::Kind::Cancelled.into());
+            break;
+        }
+    }
+
+    config.remove_temporary_packages();
+
+    // Write the run statistics, even if an analysis failed.
+    if let Some(stats_json) = config.stats_json() {
+        if let Err(e) = stats::write(stats_json) {
+            print_warning(format!("could not write the run statistics: {}", e));
+        }
+    }
+    analysis.context("application analysis failed")?;
+    if failed_packages > 0 {
+        bail!("the analysis of {} applications failed", failed_packages);
     }
 
     // Print benchmarks if in benchmark mode.
     if config.is_bench() {
         let total_time = Benchmark::new("Total time", total_start.elapsed());
         println!();
-        println!("{}", "Benchmarks:".bold());
-        for (package_name, benchmarks) in benchmarks {
+        println!("{}", tr("Benchmarks:").bold());
+        for (package_name, benchmarks) in &benchmarks {
             println!("{}:", package_name.italic());
             for bench in benchmarks {
                 println!("{}", bench);
             }
+            let hot_spots = bench::hot_spots(package_name);
+            if !hot_spots.is_empty() {
+                println!("{}", tr("Slowest rules:"));
+                for hot_spot in hot_spots {
+                    println!(
+                        "\t{}: {:.3}s ({:.1}%)",
+                        hot_spot.rule(),
+                        hot_spot.seconds(),
+                        hot_spot.share() * 100.0
+                    );
+                }
+            }
             println!();
         }
         println!("{}", total_time);
+
+        let benchmarks_json = config.results_folder().join("benchmarks.json");
+        if let Err(e) = bench::write(&benchmarks_json, &benchmarks, total_time.duration()) {
+            print_warning(format!("could not write the benchmarks: {}", e));
+        }
+    }
+
+    // Fail if there are findings at or above the `--fail-on` criticality.
+    if let Some(criticality) = config.fail_on() {
+        let count = stats::count_findings(criticality);
+        if count > 0 {
+            return Err(error::Kind::FindingsFound { count, criticality }.into());
+        }
     }
 
     Ok(())
@@ -0,0 +1,233 @@
+//! Metrics module.
+//!
+//! SUPER keeps some metrics about the analyses it runs: the number of analyses, the failures and
+//! duration of each stage of the analysis and the number of findings by criticality. They can be
+//! rendered in the Prometheus text exposition format, so that they can be scraped from the
+//! `/metrics` endpoint of the `serve` subcommand or exported through the textfile collector of the
+//! node exporter.
+
+use std::{
+    collections::BTreeMap, fmt::Write as FmtWrite, fs, path::Path, sync::Mutex, time::Duration,
+};
+
+use failure::{Error, ResultExt};
+use lazy_static::lazy_static;
+
+use crate::{criticality::Criticality, results::Results};
+
+/// Upper bounds of the duration histogram buckets, in seconds.
+const DURATION_BUCKETS: [f64; 10] = [0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+lazy_static! {
+    /// Global metrics registry.
+    static ref REGISTRY: Mutex<Registry> = Mutex::new(Registry::default());
+}
+
+/// Records the start of a new analysis.
+pub fn record_analysis() {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.analyses += 1;
+    }
+}
+
+/// Records a failure in the given stage of the analysis, returning the given error.
+///
+/// It is meant to be used with `Result::map_err()`.
+pub fn record_failure<E>(stage: &str, error: E) -> E {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        *registry.failures.entry(stage.to_owned()).or_insert(0) += 1;
+    }
+    error
+}
+
+/// Records the duration of the given stage of the analysis.
+pub fn record_duration(stage: &str, duration: Duration) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.record_duration(stage, duration);
+    }
+}
+
+/// Records the findings of the given results.
+pub(crate) fn record_findings(results: &Results) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        for vulnerability in results.vulnerabilities() {
+            *registry
+                .findings
+                .entry(vulnerability.get_criticality())
+                .or_insert(0) += 1;
+        }
+    }
+}
+
+/// Renders the current metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    REGISTRY
+        .lock()
+        .map(|registry| registry.render())
+        .unwrap_or_default()
+}
+
+/// Writes the current metrics to the given file, for the node exporter textfile collector.
+///
+/// The metrics are written to a temporary file first, and then moved to the given path, so that
+/// the collector never reads a partially written file.
+pub fn write_textfile<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    let tmp_path = path.with_extension("prom.tmp");
+    fs::write(&tmp_path, render()).context("could not write the metrics file")?;
+    fs::rename(&tmp_path, path).context("could not move the metrics file")?;
+
+    Ok(())
+}
+
+/// Metrics registry.
+#[derive(Debug, Default)]
+struct Registry {
+    /// Number of analyses run.
+    analyses: u64,
+    /// Number of failures per stage.
+    failures: BTreeMap<String, u64>,
+    /// Duration histograms per stage.
+    durations: BTreeMap<String, Histogram>,
+    /// Number of findings per criticality.
+    findings: BTreeMap<Criticality, u64>,
+}
+
+impl Registry {
+    /// Records the duration of the given stage.
+    fn record_duration(&mut self, stage: &str, duration: Duration) {
+        let seconds = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+        let histogram = self.durations.entry(stage.to_owned()).or_default();
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(DURATION_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "super_analyses_total", "counter", "Analyses run.");
+        let _ = writeln!(out, "super_analyses_total {}", self.analyses);
+
+        header(
+            &mut out,
+            "super_stage_failures_total",
+            "counter",
+            "Failures in each stage of the analysis.",
+        );
+        for (stage, failures) in &self.failures {
+            let _ = writeln!(
+                out,
+                "super_stage_failures_total{{stage=\"{}\"}} {}",
+                stage, failures
+            );
+        }
+
+        header(
+            &mut out,
+            "super_stage_duration_seconds",
+            "histogram",
+            "Duration of each stage of the analysis.",
+        );
+        for (stage, histogram) in &self.durations {
+            for (bucket, bound) in histogram.buckets.iter().zip(DURATION_BUCKETS.iter()) {
+                let _ = writeln!(
+                    out,
+                    "super_stage_duration_seconds_bucket{{stage=\"{}\",le=\"{}\"}} {}",
+                    stage, bound, bucket
+                );
+            }
+            let _ = writeln!(
+                out,
+                "super_stage_duration_seconds_bucket{{stage=\"{}\",le=\"+Inf\"}} {}",
+                stage, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "super_stage_duration_seconds_sum{{stage=\"{}\"}} {}",
+                stage, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "super_stage_duration_seconds_count{{stage=\"{}\"}} {}",
+                stage, histogram.count
+            );
+        }
+
+        header(
+            &mut out,
+            "super_findings_total",
+            "counter",
+            "Findings by criticality.",
+        );
+        for (criticality, findings) in &self.findings {
+            let _ = writeln!(
+                out,
+                "super_findings_total{{criticality=\"{}\"}} {}",
+                criticality, findings
+            );
+        }
+
+        out
+    }
+}
+
+/// Cumulative histogram.
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Number of observations in each bucket.
+    buckets: [u64; 10],
+    /// Sum of all the observations.
+    sum: f64,
+    /// Number of observations.
+    count: u64,
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric.
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Registry;
+    use crate::criticality::Criticality;
+
+    /// Checks the rendering of the metrics.
+    #[test]
+    fn it_render() {
+        let mut registry = Registry::default();
+        registry.analyses = 2;
+        let _ = registry.failures.insert(String::from("decompilation"), 1);
+        let _ = registry.findings.insert(Criticality::High, 3);
+        registry.record_duration("decompression", Duration::from_millis(700));
+        registry.record_duration("decompression", Duration::from_secs(20));
+
+        let rendered = registry.render();
+        assert!(rendered.contains("# TYPE super_analyses_total counter\nsuper_analyses_total 2\n"));
+        assert!(rendered.contains("super_stage_failures_total{stage=\"decompilation\"} 1\n"));
+        assert!(rendered.contains(
+            "super_stage_duration_seconds_bucket{stage=\"decompression\",le=\"0.5\"} 0\n"
+        ));
+        assert!(rendered
+            .contains("super_stage_duration_seconds_bucket{stage=\"decompression\",le=\"1\"} 1\n"));
+        assert!(rendered.contains(
+            "super_stage_duration_seconds_bucket{stage=\"decompression\",le=\"30\"} 2\n"
+        ));
+        assert!(rendered.contains(
+            "super_stage_duration_seconds_bucket{stage=\"decompression\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(
+            rendered.contains("super_stage_duration_seconds_count{stage=\"decompression\"} 2\n")
+        );
+        assert!(rendered.contains("super_findings_total{criticality=\"high\"} 3\n"));
+    }
+}
@@ -0,0 +1,515 @@
+//! WebAssembly plugin module.
+//!
+//! Plugins are WebAssembly modules stored in the configured plugins folder with the `.wasm`
+//! extension. They let organizations run their own checks inside the SUPER pipeline without
+//! forking the analyzer. Each plugin must export the following items:
+//!
+//! * `memory`: the linear memory of the module.
+//! * `super_alloc(size: i32) -> i32`: allocates `size` bytes in the memory of the module and
+//!   returns a pointer to them. SUPER uses it to write the input of the analysis functions.
+//!
+//! And at least one of these analysis functions:
+//!
+//! * `super_analyze_manifest(ptr: i32, len: i32) -> i64`: receives a JSON object with the
+//!   manifest data of the application, including its source in `xml`:
+//!
+//! ```json
+//! {
+//!     "package": "com.example",
+//!     "label": "Example",
+//!     "version_name": "1.2",
+//!     "version_code": 12,
+//!     "min_sdk": 21,
+//!     "target_sdk": 34,
+//!     "debuggable": false,
+//!     "allow_backup": true,
+//!     "unknown_permissions": ["com.example.permission.CUSTOM"],
+//!     "components": [{
+//!         "kind": "activity",
+//!         "name": ".MainActivity",
+//!         "exported": true,
+//!         "exposed": true
+//!     }],
+//!     "xml": "<?xml version=\"1.0\" encoding=\"utf-8\"?>..."
+//! }
+//! ```
+//!
+//! * `super_analyze_file(ptr: i32, len: i32) -> i64`: receives a JSON object with the `path` and
+//!   the `contents` of each analyzed source file, and the `manifest` metadata of the application,
+//!   or `null` if the manifest could not be analyzed:
+//!
+//! ```json
+//! {
+//!     "path": "classes/com/example/Main.java",
+//!     "contents": "package com.example; ...",
+//!     "manifest": {
+//!         "package": "com.example",
+//!         "version_code": 12,
+//!         "version_name": "1.2",
+//!         "min_sdk": 21,
+//!         "target_sdk": 34,
+//!         "debuggable": false,
+//!         "permissions": ["android.permission.INTERNET"]
+//!     }
+//! }
+//! ```
+//!
+//! Analysis functions must return a pointer to their output in the upper 32 bits of the result
+//! and its length in the lower 32 bits. The output must be a JSON array of findings, following the
+//! contract described in the `results::finding` module.
+//!
+//! Each call to an analysis function can execute at most the number of WebAssembly instructions
+//! set in the `plugin_fuel` configuration option, so that a plugin that never returns cannot hang
+//! the analysis.
+//!
+//! Plugins that cannot be loaded or that fail do not stop the analysis, but the `plugins` stage of
+//! the results is marked as failed, so that the report shows that it is incomplete.
+
+use std::{
+    convert::TryFrom,
+    fs::{self, DirEntry},
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use failure::{format_err, Error, ResultExt};
+use serde_json::{self, json, Value};
+use wasmtime::{Config as EngineConfig, Engine, Instance, Memory, Module, Store};
+
+use crate::{
+    i18n::{tr, tr_args},
+    print_message, print_warning,
+    results::{Finding, Results, StageStatus},
+    static_analysis::{code::add_files_to_vec, manifest::Manifest},
+    Config,
+};
+
+/// Name of the exported allocation function.
+const ALLOC_FN: &str = "super_alloc";
+/// Name of the exported manifest analysis function.
+const MANIFEST_FN: &str = "super_analyze_manifest";
+/// Name of the exported file analysis function.
+const FILE_FN: &str = "super_analyze_file";
+
+/// Runs all the plugins in the plugins folder for the given application.
+pub fn analysis<S: AsRef<str>>(
+    config: &Config,
+    package: S,
+    manifest: Option<&Manifest>,
+    results: &mut Results,
+) {
+    if !config.plugins_folder().exists() {
+        return;
+    }
+
+    let plugin_paths = match plugin_paths(config.plugins_folder()) {
+        Ok(p) => p,
+        Err(e) => {
+            print_warning(format!(
+                "An error occurred when reading the plugins folder. Plugins will not run. Error: \
+                 {}",
+                e
+            ));
+            return;
+        }
+    };
+    if plugin_paths.is_empty() {
+        return;
+    }
+
+    let engine = match engine() {
+        Ok(e) => e,
+        Err(e) => {
+            print_warning(format!(
+                "could not create the WebAssembly engine. Plugins will not run. Error: {}",
+                e
+            ));
+            results.set_stage_status("plugins", StageStatus::failed(e));
+            return;
+        }
+    };
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut files: Vec<DirEntry> = Vec::new();
+    if let Err(e) = add_files_to_vec("", &mut files, package.as_ref(), config) {
+        print_warning(format!(
+            "An error occurred when reading files for the plugins, the results might be \
+             incomplete. Error: {}",
+            e
+        ));
+    }
+
+    let metadata = manifest.map_or(Value::Null, manifest_metadata);
+    let mut status = StageStatus::Ok;
+    for path in plugin_paths {
+        if config.is_verbose() {
+            print_message(tr_args("Running the {} plugin.", &[&path.display()]));
+        }
+
+        let plugin = match Plugin::load(&engine, &path, config.plugin_fuel()) {
+            Ok(p) => p,
+            Err(e) => {
+                print_warning(format!(
+                    "could not load the `{}` plugin. The analysis will continue, though. Error: \
+                     {}",
+                    path.display(),
+                    e
+                ));
+                if status.is_ok() {
+                    status = StageStatus::failed(
+                        e.context(format!("could not load the `{}` plugin", path.display())),
+                    );
+                }
+                continue;
+            }
+        };
+
+        if let Err(e) = run_plugin(
+            plugin,
+            config,
+            package.as_ref(),
+            &dist_folder,
+            manifest,
+            &metadata,
+            &files,
+            results,
+        ) {
+            print_warning(format!(
+                "the `{}` plugin failed. The analysis will continue, though. Error: {}",
+                path.display(),
+                e
+            ));
+            if status.is_ok() {
+                status = StageStatus::failed(
+                    e.context(format!("the `{}` plugin failed", path.display())),
+                );
+            }
+        }
+    }
+    results.set_stage_status("plugins", status);
+
+    if config.is_verbose() {
+        print_message("");
+        print_message(tr("The plugins were run correctly!").green());
+    } else if !config.is_quiet() {
+        print_message(tr("Plugins run."));
+    }
+}
+
+/// Runs the given plugin over the manifest and the files of the application.
+///
+/// The files are given to the plugin with the given manifest metadata.
+#[allow(clippy::too_many_arguments)]
+fn run_plugin(
+    mut plugin: Plugin,
+    config: &Config,
+    package: &str,
+    dist_folder: &Path,
+    manifest: Option<&Manifest>,
+    metadata: &Value,
+    files: &[DirEntry],
+    results: &mut Results,
+) -> Result<(), Error> {
+    if let Some(manifest) = manifest {
+        if plugin.exports(MANIFEST_FN) {
+            let input = manifest_input(manifest);
+            for finding in plugin.call(MANIFEST_FN, &input)? {
+                finding.add_to(config, package, results);
+            }
+        }
+    }
+
+    if plugin.exports(FILE_FN) {
+        for file in files {
+            let path = file.path();
+            let contents = match fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    print_warning(format!(
+                        "could not read `{}` for the plugins. Error: {}",
+                        path.display(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+            let relative_path = path.strip_prefix(dist_folder).unwrap_or(&path);
+            let input = json!({
+                "path": relative_path,
+                "contents": contents,
+                "manifest": metadata,
+            });
+
+            for finding in plugin.call(FILE_FN, &input)? {
+                finding.add_to(config, package, results);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the input of the manifest analysis function of the plugins.
+///
+/// This is part of the plugin interface, so new fields can be added, but the existing ones must
+/// be kept.
+fn manifest_input(manifest: &Manifest) -> Value {
+    let components: Vec<Value> = manifest
+        .components()
+        .map(|component| {
+            json!({
+                "kind": component.kind().as_str(),
+                "name": component.name(),
+                "exported": component.exported(),
+                "exposed": component.is_exposed(manifest.min_sdk()),
+            })
+        })
+        .collect();
+
+    json!({
+        "package": manifest.package(),
+        "label": manifest.label(),
+        "version_name": manifest.version_str(),
+        "version_code": manifest.version_number(),
+        "min_sdk": manifest.min_sdk(),
+        "target_sdk": manifest.target_sdk(),
+        "debuggable": manifest.is_debug(),
+        "allow_backup": manifest.allows_backup(),
+        "unknown_permissions": manifest.unknown_permissions().collect::<Vec<_>>(),
+        "components": components,
+        "xml": manifest.code(),
+    })
+}
+
+/// Gets the metadata of the given manifest that is given to the plugins with each file.
+fn manifest_metadata(manifest: &Manifest) -> Value {
+    json!({
+        "package": manifest.package(),
+        "version_code": manifest.version_number(),
+        "version_name": manifest.version_str(),
+        "min_sdk": manifest.min_sdk(),
+        "target_sdk": manifest.target_sdk(),
+        "debuggable": manifest.is_debug(),
+        "permissions": manifest.requested_permissions().collect::<Vec<_>>(),
+    })
+}
+
+/// Creates the WebAssembly engine of the plugins, that limits the instructions they can execute.
+fn engine() -> Result<Engine, Error> {
+    let mut config = EngineConfig::new();
+    let _ = config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| format_err!("{}", e))
+}
+
+/// Gets the paths of all the plugins in the given folder, sorted by name.
+fn plugin_paths<P: AsRef<Path>>(folder: P) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "wasm") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Loaded WebAssembly plugin.
+struct Plugin {
+    /// Store of the plugin instance.
+    store: Store<()>,
+    /// Plugin instance.
+    instance: Instance,
+    /// Exported memory of the plugin.
+    memory: Memory,
+    /// Maximum number of instructions of each call to an analysis function.
+    fuel: u64,
+}
+
+impl Plugin {
+    /// Loads and instantiates the plugin in the given path, with the given fuel for each call.
+    fn load<P: AsRef<Path>>(engine: &Engine, path: P, fuel: u64) -> Result<Self, Error> {
+        let module = Module::from_file(engine, path.as_ref())
+            .map_err(|e| format_err!("invalid WebAssembly module: {}", e))?;
+        Self::new(engine, &module, fuel)
+    }
+
+    /// Instantiates the given plugin module, with the given fuel for each call.
+    fn new(engine: &Engine, module: &Module, fuel: u64) -> Result<Self, Error> {
+        let mut store = Store::new(engine, ());
+        store
+            .set_fuel(fuel)
+            .map_err(|e| format_err!("could not set the fuel of the plugin: {}", e))?;
+        let instance = Instance::new(&mut store, module, &[])
+            .map_err(|e| format_err!("could not instantiate the module: {}", e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| format_err!("the plugin does not export its `memory`"))?;
+
+        Ok(Self {
+            store,
+            instance,
+            memory,
+            fuel,
+        })
+    }
+
+    /// Checks if the plugin exports the given function.
+    fn exports(&mut self, function: &str) -> bool {
+        self.instance.get_func(&mut self.store, function).is_some()
+    }
+
+    /// Calls the given analysis function with the given input and returns its findings.
+    fn call(&mut self, function: &str, input: &Value) -> Result<Vec<Finding>, Error> {
+        let input = serde_json::to_vec(input)?;
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| format_err!("the plugin input is too big: {} bytes", input.len()))?;
+        self.store
+            .set_fuel(self.fuel)
+            .map_err(|e| format_err!("could not set the fuel of the plugin: {}", e))?;
+
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, ALLOC_FN)
+            .map_err(|e| format_err!("invalid `{}` export: {}", ALLOC_FN, e))?;
+        let analyze = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&mut self.store, function)
+            .map_err(|e| format_err!("invalid `{}` export: {}", function, e))?;
+
+        let input_ptr = alloc
+            .call(&mut self.store, input_len)
+            .map_err(|e| format_err!("`{}` trapped: {}", ALLOC_FN, e))?;
+        self.memory
+            .write(&mut self.store, input_ptr as usize, &input)
+            .map_err(|e| format_err!("could not write the plugin input: {}", e))?;
+
+        let output = analyze
+            .call(&mut self.store, (input_ptr, input_len))
+            .map_err(|e| format_err!("`{}` trapped: {}", function, e))? as u64;
+        let output_ptr = (output >> 32) as usize;
+        let output_len = (output & 0xFFFF_FFFF) as usize;
+
+        let buffer = output_ptr
+            .checked_add(output_len)
+            .and_then(|output_end| self.memory.data(&self.store).get(output_ptr..output_end))
+            .ok_or_else(|| {
+                format_err!(
+                    "could not read the plugin output: {} bytes at {} are out of its memory",
+                    output_len,
+                    output_ptr
+                )
+            })?;
+
+        Ok(serde_json::from_slice(buffer)
+            .context("the plugin output is not a valid list of findings")?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wasmtime::Module;
+
+    use super::{engine, manifest_input, Plugin, FILE_FN};
+    use crate::static_analysis::manifest::Manifest;
+
+    /// Plugin that reports a finding for every file.
+    const FINDING_PLUGIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (data (i32.const 0)
+            "[{\"criticality\":\"high\",\"name\":\"Plugin finding\",\"description\":\"Found by a plugin.\"}]")
+        (func (export "super_alloc") (param i32) (result i32)
+            (i32.const 1024))
+        (func (export "super_analyze_file") (param i32 i32) (result i64)
+            (i64.const 83)))"#;
+
+    /// Plugin whose file analysis traps.
+    const TRAP_PLUGIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "super_alloc") (param i32) (result i32)
+            (i32.const 1024))
+        (func (export "super_analyze_file") (param i32 i32) (result i64)
+            unreachable))"#;
+
+    /// Plugin whose file analysis returns an output out of its memory.
+    const OUT_OF_BOUNDS_PLUGIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "super_alloc") (param i32) (result i32)
+            (i32.const 1024))
+        (func (export "super_analyze_file") (param i32 i32) (result i64)
+            (i64.const 0x0000FFF0FFFFFFFF)))"#;
+
+    /// Plugin whose file analysis never returns.
+    const LOOP_PLUGIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "super_alloc") (param i32) (result i32)
+            (i32.const 1024))
+        (func (export "super_analyze_file") (param i32 i32) (result i64)
+            (loop $forever (br $forever))
+            (i64.const 0)))"#;
+
+    /// Instantiates the plugin with the given source, with the given fuel.
+    fn instantiate(source: &str, fuel: u64) -> Plugin {
+        let engine = engine().unwrap();
+        let module = Module::new(&engine, source).unwrap();
+        Plugin::new(&engine, &module, fuel).unwrap()
+    }
+
+    /// Checks that the findings of a plugin are read from its memory.
+    #[test]
+    fn it_plugin_findings() {
+        let mut plugin = instantiate(FINDING_PLUGIN, 1_000_000);
+        assert!(plugin.exports(FILE_FN));
+        let input = json!({ "path": "Main.java", "contents": "", "manifest": null });
+
+        let findings = plugin.call(FILE_FN, &input).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(format!("{:?}", findings[0]).contains("Plugin finding"));
+
+        // The fuel is refilled for each call.
+        assert_eq!(plugin.call(FILE_FN, &input).unwrap().len(), 1);
+    }
+
+    /// Checks that the manifest input of the plugins keeps its fields.
+    #[test]
+    fn it_manifest_input() {
+        let input = manifest_input(&Manifest::default());
+        let mut fields: Vec<_> = input.as_object().unwrap().keys().cloned().collect();
+        fields.sort();
+
+        assert_eq!(
+            fields,
+            vec![
+                "allow_backup",
+                "components",
+                "debuggable",
+                "label",
+                "min_sdk",
+                "package",
+                "target_sdk",
+                "unknown_permissions",
+                "version_code",
+                "version_name",
+                "xml",
+            ]
+        );
+    }
+
+    /// Checks that plugins that trap or that never return fail instead of stopping the analysis.
+    #[test]
+    fn it_plugin_failures() {
+        let input = json!({ "path": "Main.java", "contents": "", "manifest": null });
+
+        let mut plugin = instantiate(TRAP_PLUGIN, 1_000_000);
+        assert!(plugin.call(FILE_FN, &input).is_err());
+
+        let mut plugin = instantiate(LOOP_PLUGIN, 1_000_000);
+        let error = plugin.call(FILE_FN, &input).unwrap_err();
+        assert!(error.to_string().contains(FILE_FN));
+
+        let mut plugin = instantiate(OUT_OF_BOUNDS_PLUGIN, 1_000_000);
+        let error = plugin.call(FILE_FN, &input).unwrap_err();
+        assert!(error.to_string().contains("out of its memory"));
+    }
+}
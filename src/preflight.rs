@@ -0,0 +1,226 @@
+//! Pre-flight validation module.
+//!
+//! Before running the decompilation tools, the application package is checked, so that invalid
+//! inputs are reported with a clear error instead of failing deep inside the decompilers. The
+//! package must be a readable file of a plausible size, it must be a ZIP archive and it must
+//! contain an `AndroidManifest.xml` file and at least one DEX file or native library. Android App
+//! Bundles must contain them in their base module, and split APK sets must contain APK files.
+
+use std::{
+    fs::{self, File},
+    io::{Read, Seek},
+    path::Path,
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    bundle::PackageFormat,
+    error::{Kind, PackageProblem},
+};
+
+/// Magic number of the local file headers of ZIP archives.
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Minimum plausible size of an application, in bytes.
+const MIN_SIZE: u64 = 512;
+
+/// Maximum plausible size of an application, in bytes (4 GiB, the limit of ZIP archives without
+/// ZIP64 extensions).
+const MAX_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Validates the given application package.
+pub(crate) fn validate<P: AsRef<Path>>(package: P) -> Result<(), Kind> {
+    let package = package.as_ref();
+    check(package).map_err(|problem| Kind::InvalidPackage {
+        path: package.display().to_string(),
+        problem,
+    })
+}
+
+/// Checks the given application package, returning the problem found, if any.
+fn check(package: &Path) -> Result<(), PackageProblem> {
+    let metadata = fs::metadata(package).map_err(|_| PackageProblem::NotFound)?;
+    if !metadata.is_file() {
+        return Err(PackageProblem::NotAFile);
+    }
+    if metadata.len() < MIN_SIZE || metadata.len() > MAX_SIZE {
+        return Err(PackageProblem::ImplausibleSize {
+            size: metadata.len(),
+        });
+    }
+
+    let file = File::open(package).map_err(|e| PackageProblem::Unreadable {
+        message: e.to_string(),
+    })?;
+    check_archive(file, PackageFormat::of(package))
+}
+
+/// Checks that the given reader contains a ZIP archive with the contents of an application in the
+/// given format.
+fn check_archive<R: Read + Seek>(
+    mut reader: R,
+    format: PackageFormat,
+) -> Result<(), PackageProblem> {
+    let mut magic = [0; 4];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| PackageProblem::NotAZipArchive)?;
+    if &magic != ZIP_MAGIC {
+        return Err(PackageProblem::NotAZipArchive);
+    }
+
+    let mut archive = ZipArchive::new(reader).map_err(|e| PackageProblem::CorruptArchive {
+        message: e.to_string(),
+    })?;
+    let mut has_manifest = false;
+    let mut has_code = false;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| PackageProblem::CorruptArchive {
+                message: e.to_string(),
+            })?;
+        let name = entry.name();
+        match format {
+            PackageFormat::Apk => {
+                if name == "AndroidManifest.xml" {
+                    has_manifest = true;
+                } else if is_code(name) {
+                    has_code = true;
+                }
+            }
+            PackageFormat::Bundle => {
+                if name == "base/manifest/AndroidManifest.xml" {
+                    has_manifest = true;
+                } else if name.splitn(2, '/').nth(1).map_or(false, is_bundle_code) {
+                    has_code = true;
+                }
+            }
+            PackageFormat::SplitSet => {
+                if name.ends_with(".apk") {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    if format == PackageFormat::SplitSet {
+        return Err(PackageProblem::MissingApks);
+    }
+
+    if !has_manifest {
+        Err(PackageProblem::MissingManifest)
+    } else if !has_code {
+        Err(PackageProblem::MissingCode)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks if the given archive entry is a DEX file or a native library.
+pub(crate) fn is_code(name: &str) -> bool {
+    (!name.contains('/') && name.starts_with("classes") && name.ends_with(".dex"))
+        || (name.starts_with("lib/") && name.ends_with(".so"))
+}
+
+/// Checks if the given path of a bundle module is a DEX file or a native library.
+fn is_bundle_code(path: &str) -> bool {
+    (path.starts_with("dex/") && path.ends_with(".dex"))
+        || (path.starts_with("lib/") && path.ends_with(".so"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::{check_archive, is_code};
+    use crate::{bundle::PackageFormat, error::PackageProblem};
+
+    /// Creates a ZIP archive with the given files.
+    fn archive(files: &[&str]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for file in files {
+            writer.start_file(*file, FileOptions::default()).unwrap();
+            writer.write_all(b"content").unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    /// Checks the validation of the application archives.
+    #[test]
+    fn it_check_archive() {
+        assert_eq!(
+            check_archive(
+                archive(&["AndroidManifest.xml", "classes.dex"]),
+                PackageFormat::Apk
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_archive(
+                archive(&["AndroidManifest.xml", "lib/arm64-v8a/libapp.so"]),
+                PackageFormat::Apk
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_archive(archive(&["classes.dex"]), PackageFormat::Apk),
+            Err(PackageProblem::MissingManifest)
+        );
+        assert_eq!(
+            check_archive(
+                archive(&["AndroidManifest.xml", "assets/data.bin"]),
+                PackageFormat::Apk
+            ),
+            Err(PackageProblem::MissingCode)
+        );
+        assert_eq!(
+            check_archive(Cursor::new(b"%PDF-1.4".to_vec()), PackageFormat::Apk),
+            Err(PackageProblem::NotAZipArchive)
+        );
+
+        assert_eq!(
+            check_archive(
+                archive(&[
+                    "base/manifest/AndroidManifest.xml",
+                    "base/dex/classes.dex",
+                    "BundleConfig.pb",
+                ]),
+                PackageFormat::Bundle
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_archive(
+                archive(&["AndroidManifest.xml", "classes.dex"]),
+                PackageFormat::Bundle
+            ),
+            Err(PackageProblem::MissingManifest)
+        );
+        assert_eq!(
+            check_archive(
+                archive(&["toc.pb", "splits/base-master.apk"]),
+                PackageFormat::SplitSet
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_archive(archive(&["manifest.json"]), PackageFormat::SplitSet),
+            Err(PackageProblem::MissingApks)
+        );
+    }
+
+    /// Checks the detection of code files.
+    #[test]
+    fn it_is_code() {
+        assert!(is_code("classes.dex"));
+        assert!(is_code("classes2.dex"));
+        assert!(is_code("lib/x86/libnative.so"));
+        assert!(!is_code("assets/classes.dex"));
+        assert!(!is_code("res/raw/lib.so"));
+    }
+}
@@ -0,0 +1,219 @@
+//! Progress reporting module.
+//!
+//! The analysis of an application goes through several phases, and some of them, such as the
+//! code analysis, process many files. Library users and the CLI can follow the progress of the
+//! analysis by registering a [`ProgressObserver`] with [`set_observer()`]: it is notified when each
+//! phase starts and finishes, and every time a file of the phase is processed.
+//!
+//! Only one observer is registered at a time, and it is called from the analysis threads, so it
+//! must be thread-safe.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+
+use crate::{i18n::tr_args, print_message};
+
+lazy_static! {
+    /// Registered progress observer.
+    static ref OBSERVER: RwLock<Option<Box<dyn ProgressObserver>>> = RwLock::new(None);
+}
+
+/// Phase of the analysis of an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Decompression of the application package.
+    Decompression,
+    /// Conversion of the DEX files to JAR.
+    DexToJar,
+    /// Decompilation of the JAR files.
+    Decompilation,
+    /// Analysis of the manifest, certificate, resources and native libraries.
+    Manifest,
+    /// Analysis of the source code files.
+    Code,
+    /// Generation of the reports.
+    Report,
+}
+
+impl Phase {
+    /// Gets the name of the phase.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Decompression => "decompress",
+            Phase::DexToJar => "dex2jar",
+            Phase::Decompilation => "decompile",
+            Phase::Manifest => "manifest",
+            Phase::Code => "code",
+            Phase::Report => "report",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Observer of the progress of the analysis.
+///
+/// All the methods do nothing by default, so observers only need to implement the notifications
+/// they are interested in.
+pub trait ProgressObserver: Send + Sync {
+    /// Called when the given phase of the analysis of the given package starts.
+    fn phase_started(&self, _package: &str, _phase: Phase) {}
+
+    /// Called when the given phase of the analysis of the given package finishes.
+    fn phase_finished(&self, _package: &str, _phase: Phase, _duration: Duration) {}
+
+    /// Called every time a file of the given phase has been processed, with the number of files
+    /// already processed and the total number of files of the phase.
+    fn file_processed(&self, _phase: Phase, _done: usize, _total: usize) {}
+}
+
+/// Registers the observer of the progress of the analysis, replacing the previous one.
+pub fn set_observer<O: ProgressObserver + 'static>(observer: O) {
+    if let Ok(mut registered) = OBSERVER.write() {
+        *registered = Some(Box::new(observer));
+    }
+}
+
+/// Removes the registered progress observer, if any.
+pub fn clear_observer() {
+    if let Ok(mut registered) = OBSERVER.write() {
+        *registered = None;
+    }
+}
+
+/// Calls the registered observer, if any.
+fn notify<F: FnOnce(&dyn ProgressObserver)>(notification: F) {
+    if let Ok(registered) = OBSERVER.read() {
+        if let Some(ref observer) = *registered {
+            notification(observer.as_ref());
+        }
+    }
+}
+
+/// Notifies that the given phase of the analysis of the given package has started.
+pub(crate) fn phase_started(package: &str, phase: Phase) {
+    notify(|observer| observer.phase_started(package, phase));
+}
+
+/// Notifies that the given phase of the analysis of the given package has finished.
+pub(crate) fn phase_finished(package: &str, phase: Phase, duration: Duration) {
+    notify(|observer| observer.phase_finished(package, phase, duration));
+}
+
+/// Notifies that a file of the given phase has been processed.
+pub(crate) fn file_processed(phase: Phase, done: usize, total: usize) {
+    notify(|observer| observer.file_processed(phase, done, total));
+}
+
+/// Progress observer of the CLI in verbose mode.
+///
+/// It prints the number of analyzed source files every time another 10% of them is analyzed.
+#[derive(Debug, Default)]
+pub struct VerboseProgress {
+    /// Number of analyzed files when the progress was last printed.
+    last_print: AtomicUsize,
+}
+
+impl ProgressObserver for VerboseProgress {
+    fn phase_started(&self, _package: &str, phase: Phase) {
+        if phase == Phase::Code {
+            self.last_print.store(0, Ordering::SeqCst);
+        }
+    }
+
+    fn file_processed(&self, phase: Phase, done: usize, total: usize) {
+        if phase != Phase::Code {
+            return;
+        }
+        let last_print = self.last_print.load(Ordering::SeqCst);
+        if done > last_print + total / 10
+            && self
+                .last_print
+                .compare_exchange(last_print, done, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        {
+            print_message(tr_args("{} files already analyzed.", &[&done]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::{
+        clear_observer, file_processed, phase_finished, phase_started, set_observer, Phase,
+        ProgressObserver,
+    };
+
+    /// Observer that records the notifications it receives.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        /// Received notifications.
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ProgressObserver for Recorder {
+        fn phase_started(&self, package: &str, phase: Phase) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {} {}", package, phase));
+        }
+
+        fn phase_finished(&self, package: &str, phase: Phase, _duration: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("finish {} {}", package, phase));
+        }
+
+        fn file_processed(&self, phase: Phase, done: usize, total: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("{} {}/{}", phase, done, total));
+        }
+    }
+
+    /// Checks that the registered observer receives the notifications.
+    #[test]
+    fn it_observer() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        set_observer(Recorder {
+            events: Arc::clone(&events),
+        });
+
+        phase_started("com.example", Phase::Code);
+        file_processed(Phase::Code, 1, 2);
+        file_processed(Phase::Code, 2, 2);
+        phase_finished("com.example", Phase::Code, Duration::from_secs(1));
+        clear_observer();
+        phase_started("com.example", Phase::Report);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "start com.example code",
+                "code 1/2",
+                "code 2/2",
+                "finish com.example code",
+            ]
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! Baseline module.
+//!
+//! A baseline records the findings that are already known, so that they are suppressed in
+//! subsequent analyses and only new findings are reported. This allows running SUPER in CI without
+//! failing because of historical issues. Baselines are stored in JSON files, by default in the
+//! `super.baseline.json` file of the current directory:
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "findings": [
+//!         {
+//!             "package": "com.example",
+//!             "rule_id": "weak-algorithms",
+//!             "file": "classes/com/example/Hash.java",
+//!             "fingerprint": "3f2a9c01d4b7e856",
+//!             "name": "Weak Algorithms"
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Findings are identified by the package of their application, their rule ID (or their name, if
+//! they have no rule), their file and their fingerprint, so they are still suppressed if unrelated
+//! changes move them to other lines. The findings of a package are created, or replaced, with the
+//! `--update-baseline` flag, keeping the ones of the other packages, so a single baseline can be
+//! shared by the applications analyzed in the same run. Findings without a package apply to every
+//! application, and are dropped when the baseline is updated.
+//!
+//! Fingerprints only take into account the evidence or the vulnerable lines of the findings, not
+//! the context lines around them, so editing the code around a known finding does not make it
+//! new. Findings of the same rule with the same snippet in the same file are told apart by their
+//! order in the file.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::{
+    i18n::tr_args,
+    print_message, print_warning,
+    results::{Results, Vulnerability},
+    Config,
+};
+
+/// Name of the default baseline file.
+const DEFAULT_BASELINE_FILE: &str = "super.baseline.json";
+
+/// Version of the baseline format.
+const BASELINE_VERSION: u32 = 1;
+
+/// Baseline of known findings.
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    /// Version of the baseline format.
+    version: u32,
+    /// Known findings.
+    findings: Vec<BaselineEntry>,
+}
+
+/// Known finding in the baseline.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct BaselineEntry {
+    /// Package of the application of the finding, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+    /// Rule ID of the finding, or its name if it has no rule.
+    rule_id: String,
+    /// File of the finding, if any.
+    #[serde(default)]
+    file: Option<PathBuf>,
+    /// Fingerprint of the finding.
+    fingerprint: String,
+    /// Name of the finding, to make the baseline easier to review.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl BaselineEntry {
+    /// Creates the baseline entry of the given vulnerability of the given package.
+    fn from_vulnerability(package: &str, vulnerability: &Vulnerability) -> Self {
+        Self {
+            package: Some(package.to_owned()),
+            rule_id: vulnerability
+                .rule_id()
+                .unwrap_or_else(|| vulnerability.name())
+                .to_owned(),
+            file: vulnerability.file().map(Path::to_path_buf),
+            fingerprint: vulnerability.fingerprint(),
+            name: Some(vulnerability.name().to_owned()),
+        }
+    }
+
+    /// Checks if the entry applies to the given package.
+    fn applies_to(&self, package: &str) -> bool {
+        self.package.as_ref().map_or(true, |entry| entry == package)
+    }
+
+    /// Gets the key that identifies the finding of the entry.
+    fn key(&self) -> FindingKey<'_> {
+        (
+            self.rule_id.as_str(),
+            self.file.as_ref().map(PathBuf::as_path),
+            self.fingerprint.as_str(),
+        )
+    }
+}
+
+/// Key that identifies a finding: its rule ID, its file and its fingerprint.
+type FindingKey<'k> = (&'k str, Option<&'k Path>, &'k str);
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            findings: Vec::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Replaces the findings of the package of the given results with the ones in the results.
+    ///
+    /// The findings of other packages are kept, and the ones without a package are dropped.
+    fn update(&mut self, results: &Results) {
+        let package = results.app_package();
+        self.findings.retain(|entry| {
+            entry
+                .package
+                .as_ref()
+                .map_or(false, |entry| entry != package)
+        });
+        self.findings.extend(
+            results
+                .vulnerabilities()
+                .map(|vulnerability| BaselineEntry::from_vulnerability(package, vulnerability)),
+        );
+    }
+
+    /// Loads the baseline in the given file.
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let baseline: Self = serde_json::from_str(&fs::read_to_string(path).context(
+            format_err!("could not read the baseline file `{}`", path.display()),
+        )?)
+        .context(format_err!("invalid baseline file `{}`", path.display()))?;
+        if baseline.version != BASELINE_VERSION {
+            bail!(
+                "unsupported version {} of the baseline file `{}`",
+                baseline.version,
+                path.display()
+            );
+        }
+        Ok(baseline)
+    }
+
+    /// Gets the keys of the known findings of the given package.
+    fn keys(&self, package: &str) -> HashSet<FindingKey<'_>> {
+        self.findings
+            .iter()
+            .filter(|entry| entry.applies_to(package))
+            .map(BaselineEntry::key)
+            .collect()
+    }
+}
+
+/// Checks if the given vulnerability is one of the known findings with the given keys.
+fn is_known(keys: &HashSet<FindingKey<'_>>, vulnerability: &Vulnerability) -> bool {
+    let fingerprint = vulnerability.fingerprint();
+    keys.contains(&(
+        vulnerability
+            .rule_id()
+            .unwrap_or_else(|| vulnerability.name()),
+        vulnerability.file(),
+        fingerprint.as_str(),
+    ))
+}
+
+/// Gets the baseline file to use, if any.
+///
+/// The configured file is always used, and the default file is used if it exists or if the
+/// baseline has to be updated.
+pub(crate) fn baseline_file(config: &Config) -> Option<PathBuf> {
+    config.baseline().map(Path::to_path_buf).or_else(|| {
+        let default = PathBuf::from(DEFAULT_BASELINE_FILE);
+        if config.is_update_baseline() || default.is_file() {
+            Some(default)
+        } else {
+            None
+        }
+    })
+}
+
+/// Applies the baseline to the results, suppressing the known findings.
+///
+/// If the baseline has to be updated, it is replaced with the findings of the results instead, and
+/// no finding is suppressed.
+pub fn apply_baseline(config: &Config, results: &mut Results) {
+    let path = match baseline_file(config) {
+        Some(path) => path,
+        None => return,
+    };
+
+    if config.is_update_baseline() {
+        let mut baseline = if path.is_file() {
+            match Baseline::from_file(&path) {
+                Ok(baseline) => baseline,
+                Err(e) => {
+                    print_warning(format!("{}, it will be replaced", e));
+                    Baseline::default()
+                }
+            }
+        } else {
+            Baseline::default()
+        };
+        baseline.update(results);
+        let written = serde_json::to_string_pretty(&baseline)
+            .map_err(Error::from)
+            .and_then(|json| fs::write(&path, json).map_err(Error::from));
+        match written {
+            Ok(()) if !config.is_quiet() => print_message(tr_args(
+                "The baseline has been updated with {} findings.",
+                &[&results.vulnerabilities().count()],
+            )),
+            Ok(()) => {}
+            Err(e) => print_warning(format!(
+                "could not write the baseline file `{}`: {}",
+                path.display(),
+                e
+            )),
+        }
+        return;
+    }
+
+    let baseline = match Baseline::from_file(&path) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            print_warning(format!("{}, known findings will not be suppressed", e));
+            return;
+        }
+    };
+    let keys = baseline.keys(results.app_package());
+    let suppressed =
+        results.suppress_vulnerabilities(|vulnerability| is_known(&keys, vulnerability));
+    if config.is_verbose() {
+        print_message(tr_args(
+            "{} known findings have been suppressed by the baseline.",
+            &[&suppressed],
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_known, Baseline, BaselineEntry};
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the matching of the baseline entries.
+    #[test]
+    fn it_contains() {
+        let known = Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+            .file("classes/com/example/Hash.java")
+            .lines(9, 9)
+            .rule_id("weak-algorithms")
+            .evidence("MessageDigest.getInstance(\"MD5\")")
+            .build();
+        let baseline = Baseline {
+            version: 1,
+            findings: vec![BaselineEntry::from_vulnerability("com.example", &known)],
+        };
+        let keys = baseline.keys("com.example");
+
+        let moved = Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+            .file("classes/com/example/Hash.java")
+            .lines(42, 42)
+            .rule_id("weak-algorithms")
+            .evidence("MessageDigest.getInstance(\"MD5\")")
+            .build();
+        let other_file = Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+            .file("classes/com/example/Other.java")
+            .rule_id("weak-algorithms")
+            .evidence("MessageDigest.getInstance(\"MD5\")")
+            .build();
+        let no_rule = Vulnerability::builder(Criticality::Low, "Allows Backup", "").build();
+        let other_code = Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+            .file("classes/com/example/Hash.java")
+            .lines(9, 9)
+            .code("MessageDigest.getInstance(\"SHA-1\");")
+            .rule_id("weak-algorithms")
+            .build();
+        let reformatted_code = Vulnerability::builder(Criticality::High, "Weak Algorithms", "")
+            .file("classes/com/example/Hash.java")
+            .lines(12, 12)
+            .code("        MessageDigest.getInstance(\"SHA-1\");\n")
+            .rule_id("weak-algorithms")
+            .build();
+
+        assert!(is_known(&keys, &known));
+        assert!(is_known(&keys, &moved));
+        assert!(!is_known(&keys, &other_file));
+        assert!(!is_known(&keys, &no_rule));
+        assert!(!is_known(&keys, &other_code));
+        assert_ne!(other_code.id(), known.id());
+        assert_ne!(
+            other_code.id(),
+            Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+                .file("classes/com/example/Hash.java")
+                .rule_id("weak-algorithms")
+                .build()
+                .id()
+        );
+        assert_eq!(reformatted_code.id(), other_code.id());
+
+        let windowed_code = Vulnerability::builder(Criticality::High, "Weak Algorithms", "")
+            .file("classes/com/example/Hash.java")
+            .lines(20, 20)
+            .code("a();\nb();\nc();\nd();\nMessageDigest.getInstance(\"SHA-1\");\ne();\n")
+            .rule_id("weak-algorithms")
+            .build();
+        let edited_context = Vulnerability::builder(Criticality::High, "Weak Algorithms", "")
+            .file("classes/com/example/Hash.java")
+            .lines(2, 2)
+            .code("x();\ny();\nMessageDigest.getInstance(\"SHA-1\");\nz();\n")
+            .rule_id("weak-algorithms")
+            .build();
+        assert_eq!(windowed_code.id(), other_code.id());
+        assert_eq!(edited_context.id(), other_code.id());
+
+        let mut repeated = known.clone();
+        repeated.set_occurrence(1);
+        assert_ne!(repeated.id(), known.id());
+        assert!(!is_known(&keys, &repeated));
+        repeated.set_occurrence(0);
+        assert_eq!(repeated.id(), known.id());
+
+        assert!(!is_known(&baseline.keys("com.other"), &known));
+
+        let json = serde_json::to_value(&baseline).unwrap();
+        assert_eq!(json["findings"][0]["package"], "com.example");
+        assert_eq!(json["findings"][0]["rule_id"], "weak-algorithms");
+        assert_eq!(json["findings"][0]["file"], "classes/com/example/Hash.java");
+        let parsed: Baseline = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.findings, baseline.findings);
+    }
+
+    /// Checks that the findings without a package apply to every package.
+    #[test]
+    fn it_legacy_entries() {
+        let known = Vulnerability::builder(Criticality::Low, "Allows Backup", "").build();
+        let baseline: Baseline = serde_json::from_str(&format!(
+            r#"{{"version": 1, "findings": [{{"rule_id": "Allows Backup", "fingerprint": "{}"}}]}}"#,
+            known.fingerprint()
+        ))
+        .unwrap();
+
+        assert!(is_known(&baseline.keys("com.example"), &known));
+        assert!(is_known(&baseline.keys("com.other"), &known));
+    }
+}
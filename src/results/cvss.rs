@@ -0,0 +1,258 @@
+//! CVSS scoring module.
+//!
+//! Findings can optionally carry a [CVSS v3.1][spec] base vector, such as
+//! `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`. The numeric base score is computed from the
+//! vector, so that reports can show it in addition to the criticality.
+//!
+//! [spec]: https://www.first.org/cvss/v3.1/specification-document
+
+use std::{fmt, str::FromStr};
+
+use failure::{bail, format_err, Error};
+use serde::{
+    de::{self, Deserializer},
+    ser::{SerializeStruct, Serializer},
+    Deserialize, Serialize,
+};
+
+use crate::criticality::Criticality;
+
+/// Prefix of CVSS v3.1 vectors.
+const CVSS_PREFIX: &str = "CVSS:3.1";
+
+/// CVSS v3.1 base vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cvss {
+    /// Attack vector: `N`etwork, `A`djacent, `L`ocal or `P`hysical.
+    attack_vector: char,
+    /// Attack complexity: `L`ow or `H`igh.
+    attack_complexity: char,
+    /// Privileges required: `N`one, `L`ow or `H`igh.
+    privileges_required: char,
+    /// User interaction: `N`one or `R`equired.
+    user_interaction: char,
+    /// Scope: `U`nchanged or `C`hanged.
+    scope: char,
+    /// Confidentiality impact: `H`igh, `L`ow or `N`one.
+    confidentiality: char,
+    /// Integrity impact: `H`igh, `L`ow or `N`one.
+    integrity: char,
+    /// Availability impact: `H`igh, `L`ow or `N`one.
+    availability: char,
+}
+
+impl Cvss {
+    /// Computes the base score of the vector, between 0.0 and 10.0.
+    pub fn score(&self) -> f64 {
+        let scope_changed = self.scope == 'C';
+        let impact_subscore = 1.0
+            - (1.0 - impact_weight(self.confidentiality))
+                * (1.0 - impact_weight(self.integrity))
+                * (1.0 - impact_weight(self.availability));
+        let impact = if scope_changed {
+            7.52 * (impact_subscore - 0.029) - 3.25 * (impact_subscore - 0.02).powi(15)
+        } else {
+            6.42 * impact_subscore
+        };
+        if impact <= 0.0 {
+            return 0.0;
+        }
+
+        let attack_vector = match self.attack_vector {
+            'N' => 0.85,
+            'A' => 0.62,
+            'L' => 0.55,
+            _ => 0.2,
+        };
+        let attack_complexity = if self.attack_complexity == 'L' {
+            0.77
+        } else {
+            0.44
+        };
+        let privileges_required = match (self.privileges_required, scope_changed) {
+            ('N', _) => 0.85,
+            ('L', false) => 0.62,
+            ('L', true) => 0.68,
+            (_, false) => 0.27,
+            (_, true) => 0.5,
+        };
+        let user_interaction = if self.user_interaction == 'N' {
+            0.85
+        } else {
+            0.62
+        };
+        let exploitability =
+            8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+        if scope_changed {
+            round_up((1.08 * (impact + exploitability)).min(10.0))
+        } else {
+            round_up((impact + exploitability).min(10.0))
+        }
+    }
+
+    /// Gets the criticality corresponding to the qualitative severity rating of the score.
+    pub fn criticality(&self) -> Criticality {
+        let score = self.score();
+        if score >= 9.0 {
+            Criticality::Critical
+        } else if score >= 7.0 {
+            Criticality::High
+        } else if score >= 4.0 {
+            Criticality::Medium
+        } else if score > 0.0 {
+            Criticality::Low
+        } else {
+            Criticality::Warning
+        }
+    }
+}
+
+/// Gets the weight of the given confidentiality, integrity or availability impact.
+fn impact_weight(impact: char) -> f64 {
+    match impact {
+        'H' => 0.56,
+        'L' => 0.22,
+        _ => 0.0,
+    }
+}
+
+/// Rounds the given score up to one decimal, as defined in the CVSS v3.1 specification.
+fn round_up(score: f64) -> f64 {
+    let int_input = (score * 100_000.0).round() as u64;
+    if int_input % 10_000 == 0 {
+        int_input as f64 / 100_000.0
+    } else {
+        ((int_input / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+impl FromStr for Cvss {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        if parts.next() != Some(CVSS_PREFIX) {
+            bail!("the CVSS vector `{}` must start with `{}`", s, CVSS_PREFIX);
+        }
+
+        let mut metrics = [None; 8];
+        for part in parts {
+            let mut metric = part.splitn(2, ':');
+            let (name, value) = match (metric.next(), metric.next()) {
+                (Some(name), Some(value)) if value.len() == 1 => {
+                    (name, value.chars().next().unwrap())
+                }
+                _ => bail!("invalid metric `{}` in the CVSS vector `{}`", part, s),
+            };
+            let (index, values) = match name {
+                "AV" => (0, "NALP"),
+                "AC" => (1, "LH"),
+                "PR" => (2, "NLH"),
+                "UI" => (3, "NR"),
+                "S" => (4, "UC"),
+                "C" => (5, "HLN"),
+                "I" => (6, "HLN"),
+                "A" => (7, "HLN"),
+                _ => bail!("unknown metric `{}` in the CVSS vector `{}`", name, s),
+            };
+            if !values.contains(value) {
+                bail!("invalid value for `{}` in the CVSS vector `{}`", name, s);
+            }
+            metrics[index] = Some(value);
+        }
+
+        let metric = |index: usize| {
+            metrics[index].ok_or_else(|| format_err!("the CVSS vector `{}` is incomplete", s))
+        };
+        Ok(Self {
+            attack_vector: metric(0)?,
+            attack_complexity: metric(1)?,
+            privileges_required: metric(2)?,
+            user_interaction: metric(3)?,
+            scope: metric(4)?,
+            confidentiality: metric(5)?,
+            integrity: metric(6)?,
+            availability: metric(7)?,
+        })
+    }
+}
+
+impl fmt::Display for Cvss {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/AV:{}/AC:{}/PR:{}/UI:{}/S:{}/C:{}/I:{}/A:{}",
+            CVSS_PREFIX,
+            self.attack_vector,
+            self.attack_complexity,
+            self.privileges_required,
+            self.user_interaction,
+            self.scope,
+            self.confidentiality,
+            self.integrity,
+            self.availability
+        )
+    }
+}
+
+impl Serialize for Cvss {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("Cvss", 2)?;
+        ser_struct.serialize_field("vector", &self.to_string())?;
+        ser_struct.serialize_field("score", &self.score())?;
+        ser_struct.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Cvss {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let vector = String::deserialize(de)?;
+        vector.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cvss;
+    use crate::criticality::Criticality;
+
+    /// Checks the base scores of some known vectors.
+    #[test]
+    fn it_score() {
+        let scores = [
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N", 6.1),
+            ("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N", 5.5),
+            ("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:H/A:N", 7.4),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H", 10.0),
+            ("CVSS:3.1/AV:P/AC:H/PR:H/UI:R/S:U/C:N/I:N/A:N", 0.0),
+        ];
+        for &(vector, score) in &scores {
+            let cvss: Cvss = vector.parse().unwrap();
+            assert!((cvss.score() - score).abs() < 1e-9, "{}", vector);
+            assert_eq!(cvss.to_string(), vector);
+        }
+
+        let cvss: Cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+            .parse()
+            .unwrap();
+        assert_eq!(cvss.criticality(), Criticality::Critical);
+
+        assert!("AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+            .parse::<Cvss>()
+            .is_err());
+        assert!("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"
+            .parse::<Cvss>()
+            .is_err());
+        assert!("CVSS:3.1/AV:X/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+            .parse::<Cvss>()
+            .is_err());
+    }
+}
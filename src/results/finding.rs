@@ -0,0 +1,121 @@
+//! External findings module.
+//!
+//! Findings produced outside of the built-in analysis, such as the ones returned by plugins or
+//! external analyzers, share a common JSON contract:
+//!
+//! ```json
+//! [{
+//!     "criticality": "warning|low|medium|high|critical",
+//!     "name": "Name of the finding",
+//!     "description": "Long description of the finding",
+//!     "file": "optional/path/to/the/file.java",
+//!     "start_line": 1,
+//!     "end_line": 1,
+//!     "rule_id": "optional-rule-id",
+//!     "cwe": 200
+//! }]
+//! ```
+//!
+//! Line numbers start at 1, and they will only be used if a file is given. Files are relative to
+//! the distribution folder of the application.
+
+use std::{fs, path::PathBuf};
+
+use crate::{
+    criticality::Criticality, get_code, print_vulnerability, results::Results, Config,
+    Vulnerability,
+};
+
+/// Finding reported by an external source.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Finding {
+    /// Criticality of the finding.
+    criticality: Criticality,
+    /// Name of the finding.
+    name: String,
+    /// Description of the finding.
+    description: String,
+    /// File where the finding was found, relative to the distribution folder.
+    file: Option<PathBuf>,
+    /// First line of the finding, starting at 1.
+    start_line: Option<usize>,
+    /// Last line of the finding, starting at 1.
+    end_line: Option<usize>,
+    /// Identifier of the rule that generated the finding.
+    rule_id: Option<String>,
+    /// CWE identifier of the finding.
+    cwe: Option<u32>,
+}
+
+impl Finding {
+    /// Adds the finding to the results, if it reaches the minimum criticality.
+    ///
+    /// The affected code is read from the distribution folder of the given package.
+    pub(crate) fn add_to<S: AsRef<str>>(self, config: &Config, package: S, results: &mut Results) {
+        if self.criticality < config.min_criticality() {
+            return;
+        }
+
+        let mut builder = Vulnerability::builder(
+            self.criticality,
+            self.name.as_str(),
+            self.description.as_str(),
+        );
+        if let Some(file) = self.file {
+            if let Some(start_line) = self.start_line.filter(|l| *l > 0) {
+                let start_line = start_line - 1;
+                let end_line = self
+                    .end_line
+                    .filter(|l| *l > start_line)
+                    .map_or(start_line, |l| l - 1);
+                let path = config.dist_folder().join(package.as_ref()).join(&file);
+                if let Ok(code) = fs::read_to_string(path) {
+                    builder = builder.code(get_code(code, start_line, end_line));
+                }
+                builder = builder.lines(start_line, end_line);
+            }
+            builder = builder.file(file);
+        }
+        if let Some(rule_id) = self.rule_id {
+            builder = builder.rule_id(rule_id);
+        }
+        if let Some(cwe) = self.cwe {
+            builder = builder.cwe(cwe);
+        }
+
+        print_vulnerability(self.description.as_str(), self.criticality);
+        results.add_vulnerability(builder.build());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Finding;
+    use crate::criticality::Criticality;
+
+    /// Checks the deserialization of the findings contract.
+    #[test]
+    fn it_deserialize() {
+        let findings: Vec<Finding> = serde_json::from_str(
+            r#"[
+                {"criticality": "high", "name": "Name", "description": "Description"},
+                {
+                    "criticality": "low",
+                    "name": "Other",
+                    "description": "Other description",
+                    "file": "A.java",
+                    "start_line": 3,
+                    "cwe": 200
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].criticality, Criticality::High);
+        assert!(findings[0].file.is_none());
+        assert_eq!(findings[1].start_line, Some(3));
+        assert_eq!(findings[1].end_line, None);
+        assert_eq!(findings[1].cwe, Some(200));
+    }
+}
@@ -3,6 +3,25 @@ use handlebars::{Context, Handlebars as Registry, Helper, Output, RenderContext,
 use serde_json::Value;
 
 use super::utils::{html_escape, split_indent};
+use crate::i18n::tr;
+
+/// Translates the given message to the language of the messages of the CLI.
+///
+/// This allows templates to use the same message catalogs as the CLI, with `{{t "Message"}}`.
+pub fn translate(
+    h: &Helper,
+    _: &Registry,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut Output,
+) -> Result<(), RenderError> {
+    let message = h
+        .param(0)
+        .and_then(|v| v.value().as_str())
+        .ok_or_else(|| RenderError::new("the message to translate must be a string"))?;
+    out.write(&html_escape(tr(message)))?;
+    Ok(())
+}
 
 /// Generates a list of line numbers for the given vulnerability.
 ///
@@ -0,0 +1,428 @@
+//! Importers for reports generated by other scanners.
+//!
+//! Findings from MobSF JSON reports and AndroBugs text reports can be merged into the SUPER
+//! results. Imported findings that look like an already reported vulnerability (same file, close
+//! lines and similar name or CWE) are discarded, so that each issue is only reported once.
+
+use std::{
+    collections::BTreeSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use failure::{format_err, Error, ResultExt};
+use serde_json::Value;
+
+use crate::{
+    criticality::Criticality,
+    get_code, print_warning,
+    results::{Results, Vulnerability, VulnerabilityBuilder},
+    Config,
+};
+
+/// Maximum distance between the lines of two findings to consider them the same.
+const LINE_TOLERANCE: usize = 3;
+/// Minimum name similarity to consider two findings the same.
+const NAME_SIMILARITY: f64 = 0.5;
+
+/// Format of an imported report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// MobSF JSON report.
+    MobSF,
+    /// AndroBugs text report.
+    AndroBugs,
+}
+
+impl ImportFormat {
+    /// Returns the name of the format.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormat::MobSF => "mobsf",
+            ImportFormat::AndroBugs => "androbugs",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mobsf" => Ok(ImportFormat::MobSF),
+            "androbugs" => Ok(ImportFormat::AndroBugs),
+            _ => Err(format_err!("unknown report format `{}`", s)),
+        }
+    }
+}
+
+/// Imports the reports configured for the analysis into the results of the given package.
+pub fn import_reports<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+
+    for (format, path) in config.imported_reports() {
+        let imported = fs::read_to_string(path)
+            .context("could not read the report")
+            .map_err(Error::from)
+            .and_then(|report| match format {
+                ImportFormat::MobSF => parse_mobsf(&report, &dist_folder),
+                ImportFormat::AndroBugs => Ok(parse_androbugs(&report)),
+            });
+
+        match imported {
+            Ok(vulnerabilities) => {
+                for vulnerability in vulnerabilities {
+                    if vulnerability.get_criticality() >= config.min_criticality()
+                        && !results
+                            .vulnerabilities()
+                            .any(|existing| is_duplicate(existing, &vulnerability))
+                    {
+                        results.add_vulnerability(vulnerability);
+                    }
+                }
+            }
+            Err(e) => print_warning(format!(
+                "could not import the `{}` {} report. The analysis will continue, though. \
+                 Error: {}",
+                path.display(),
+                format,
+                e
+            )),
+        }
+    }
+}
+
+/// Parses a MobSF JSON report.
+///
+/// Both the code analysis and the manifest analysis sections are imported, in their old and new
+/// layouts.
+fn parse_mobsf<P: AsRef<Path>>(report: &str, dist_folder: P) -> Result<Vec<Vulnerability>, Error> {
+    let report: Value = serde_json::from_str(report).context("invalid MobSF JSON report")?;
+    let mut vulnerabilities = Vec::new();
+
+    let code_analysis = &report["code_analysis"];
+    let code_findings = code_analysis
+        .get("findings")
+        .unwrap_or(code_analysis)
+        .as_object();
+    for (rule, finding) in code_findings.into_iter().flatten() {
+        let metadata = &finding["metadata"];
+        let criticality = match metadata["severity"].as_str().and_then(mobsf_criticality) {
+            Some(c) => c,
+            None => continue,
+        };
+        let description = metadata["description"].as_str().unwrap_or_default();
+        let name = metadata["title"].as_str().unwrap_or(rule.as_str());
+        let cwe = metadata["cwe"].as_str().and_then(parse_cwe);
+
+        let new_vulnerability = || {
+            let builder = Vulnerability::builder(criticality, name, description)
+                .rule_id(format!("mobsf:{}", rule));
+            match cwe {
+                Some(cwe) => builder.cwe(cwe),
+                None => builder,
+            }
+        };
+
+        let files = finding["files"].as_object();
+        if files.map_or(true, |f| f.is_empty()) {
+            vulnerabilities.push(new_vulnerability().build());
+        }
+        for (file, lines) in files.into_iter().flatten() {
+            let lines = lines.as_str().unwrap_or_default().split(',');
+            for line in lines.filter_map(|l| l.trim().parse::<usize>().ok()) {
+                let builder = new_vulnerability();
+                vulnerabilities.push(
+                    locate(builder, dist_folder.as_ref(), file, line.saturating_sub(1)).build(),
+                );
+            }
+        }
+    }
+
+    let manifest_analysis = &report["manifest_analysis"];
+    let manifest_findings = manifest_analysis
+        .get("manifest_findings")
+        .unwrap_or(manifest_analysis)
+        .as_array();
+    for finding in manifest_findings.into_iter().flatten() {
+        let criticality = match finding["severity"]
+            .as_str()
+            .or_else(|| finding["stat"].as_str())
+            .and_then(mobsf_criticality)
+        {
+            Some(c) => c,
+            None => continue,
+        };
+        let name = finding["title"].as_str().unwrap_or_default();
+        let description = finding["description"]
+            .as_str()
+            .or_else(|| finding["desc"].as_str())
+            .unwrap_or(name);
+
+        let mut builder = Vulnerability::builder(criticality, strip_html(name), description);
+        if let Some(rule) = finding["rule"].as_str() {
+            builder = builder.rule_id(format!("mobsf:{}", rule));
+        }
+        vulnerabilities.push(builder.build());
+    }
+
+    Ok(vulnerabilities)
+}
+
+/// Gets the criticality of a MobSF severity, if it represents an issue.
+fn mobsf_criticality(severity: &str) -> Option<Criticality> {
+    match severity.to_lowercase().as_str() {
+        "high" => Some(Criticality::High),
+        "warning" | "medium" => Some(Criticality::Medium),
+        "info" | "low" => Some(Criticality::Low),
+        _ => None,
+    }
+}
+
+/// Parses an AndroBugs text report.
+///
+/// Each vector starts with a line such as `[Critical] Title:`, followed by its details. `Info`
+/// vectors represent checks that passed, so they are not imported.
+fn parse_androbugs(report: &str) -> Vec<Vulnerability> {
+    let mut vulnerabilities = Vec::new();
+    let mut current: Option<(Criticality, String, Vec<&str>)> = None;
+
+    for line in report.lines() {
+        let vector = line.trim_start().strip_prefix('[').and_then(|line| {
+            let end = line.find(']')?;
+            Some((&line[..end], line[end + 1..].trim()))
+        });
+
+        match vector {
+            Some((level, title)) if !title.is_empty() => {
+                vulnerabilities.extend(current.take().map(androbugs_vulnerability));
+                current = match level {
+                    "Critical" => Some(Criticality::Critical),
+                    "Warning" => Some(Criticality::Medium),
+                    "Notice" => Some(Criticality::Low),
+                    _ => None,
+                }
+                .map(|c| (c, title.trim_end_matches(':').to_owned(), Vec::new()));
+            }
+            _ => {
+                if let Some((_, _, ref mut details)) = current {
+                    if !line.trim().is_empty() {
+                        details.push(line.trim());
+                    }
+                }
+            }
+        }
+    }
+    vulnerabilities.extend(current.map(androbugs_vulnerability));
+
+    vulnerabilities
+}
+
+/// Creates the vulnerability for an AndroBugs vector.
+fn androbugs_vulnerability(
+    (criticality, title, details): (Criticality, String, Vec<&str>),
+) -> Vulnerability {
+    let description = if details.is_empty() {
+        title.clone()
+    } else {
+        details.join("\n")
+    };
+
+    Vulnerability::builder(criticality, title, description)
+        .rule_id("androbugs")
+        .build()
+}
+
+/// Sets the location of an imported finding.
+///
+/// Scanners report Java files relative to the source root, so the `classes` folder of the
+/// distribution folder is also checked. The code is only added if the file could be found.
+fn locate<F: AsRef<str>>(
+    builder: VulnerabilityBuilder,
+    dist_folder: &Path,
+    file: F,
+    line: usize,
+) -> VulnerabilityBuilder {
+    let file = PathBuf::from(file.as_ref());
+    let candidates = [file.clone(), Path::new("classes").join(&file)];
+
+    for candidate in &candidates {
+        if let Ok(code) = fs::read_to_string(dist_folder.join(candidate)) {
+            return builder
+                .file(candidate)
+                .lines(line, line)
+                .code(get_code(code, line, line));
+        }
+    }
+
+    builder.file(file).lines(line, line)
+}
+
+/// Parses the CWE identifier of a string like `CWE-312: Cleartext Storage`.
+fn parse_cwe(cwe: &str) -> Option<u32> {
+    let cwe = cwe.trim_start().strip_prefix("CWE-")?;
+    let end = cwe
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| cwe.len());
+    cwe[..end].parse().ok()
+}
+
+/// Removes the HTML tags of the given text.
+fn strip_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+/// Checks if the imported vulnerability is a duplicate of the existing one.
+///
+/// Findings are considered the same if they are in the same file and close lines (or both have
+/// no location) and they have the same CWE or similar names.
+fn is_duplicate(existing: &Vulnerability, imported: &Vulnerability) -> bool {
+    let same_location = match (existing.file(), imported.file()) {
+        (Some(a), Some(b)) => {
+            (a.ends_with(b) || b.ends_with(a))
+                && match (existing.start_line(), imported.start_line()) {
+                    (Some(a), Some(b)) => a.max(b) - a.min(b) <= LINE_TOLERANCE,
+                    _ => true,
+                }
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    same_location
+        && ((existing.cwe().is_some() && existing.cwe() == imported.cwe())
+            || name_similarity(existing.name(), imported.name()) >= NAME_SIMILARITY)
+}
+
+/// Computes the similarity between two names, as the Jaccard index of their words.
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let words = |s: &str| -> BTreeSet<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_duplicate, parse_androbugs, parse_cwe, parse_mobsf, ImportFormat};
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the parsing of MobSF reports.
+    #[test]
+    fn it_parse_mobsf() {
+        let report = r#"{
+            "code_analysis": {
+                "findings": {
+                    "android_logging": {
+                        "files": {"com/example/A.java": "12,30"},
+                        "metadata": {
+                            "severity": "info",
+                            "description": "The App logs information.",
+                            "cwe": "CWE-532: Insertion of Sensitive Information into Log File"
+                        }
+                    },
+                    "android_secure": {
+                        "files": {},
+                        "metadata": {"severity": "secure", "description": "Good."}
+                    }
+                }
+            },
+            "manifest_analysis": [
+                {"title": "<strong>Debug Enabled</strong>", "stat": "high", "desc": "Debug."}
+            ]
+        }"#;
+        let vulnerabilities = parse_mobsf(report, "dist").unwrap();
+
+        assert_eq!(vulnerabilities.len(), 3);
+        assert_eq!(vulnerabilities[0].get_criticality(), Criticality::Low);
+        assert_eq!(vulnerabilities[0].rule_id(), Some("mobsf:android_logging"));
+        assert_eq!(vulnerabilities[0].cwe(), Some(532));
+        assert_eq!(vulnerabilities[0].start_line(), Some(11));
+        assert_eq!(vulnerabilities[1].start_line(), Some(29));
+        assert_eq!(vulnerabilities[2].get_criticality(), Criticality::High);
+        assert_eq!(vulnerabilities[2].name(), "Debug Enabled");
+    }
+
+    /// Checks the parsing of AndroBugs reports.
+    #[test]
+    fn it_parse_androbugs() {
+        let report = "[Critical] App Sandbox Permission Checking:\n\
+                      \x20          Security issues found.\n\
+                      [Info] Checking Base64:\n\
+                      \x20          No encoding found.\n\
+                      [Warning] WebView Potential XSS Attacks Checking:\n\
+                      \x20          Found \"setJavaScriptEnabled(true)\".\n";
+        let vulnerabilities = parse_androbugs(report);
+
+        assert_eq!(vulnerabilities.len(), 2);
+        assert_eq!(vulnerabilities[0].get_criticality(), Criticality::Critical);
+        assert_eq!(vulnerabilities[0].name(), "App Sandbox Permission Checking");
+        assert_eq!(vulnerabilities[0].description(), "Security issues found.");
+        assert_eq!(vulnerabilities[1].get_criticality(), Criticality::Medium);
+    }
+
+    /// Checks the detection of duplicated findings.
+    #[test]
+    fn it_is_duplicate() {
+        let existing = Vulnerability::builder(Criticality::Medium, "Sensitive data logged", "")
+            .file("classes/com/example/A.java")
+            .lines(10, 10)
+            .build();
+        let similar = Vulnerability::builder(Criticality::Low, "Sensitive information logged", "")
+            .file("com/example/A.java")
+            .lines(11, 11)
+            .build();
+        let far = Vulnerability::builder(Criticality::Low, "Sensitive information logged", "")
+            .file("com/example/A.java")
+            .lines(40, 40)
+            .build();
+        let unrelated = Vulnerability::builder(Criticality::Low, "Weak hashing algorithm", "")
+            .file("com/example/A.java")
+            .lines(10, 10)
+            .build();
+
+        assert!(is_duplicate(&existing, &similar));
+        assert!(!is_duplicate(&existing, &far));
+        assert!(!is_duplicate(&existing, &unrelated));
+    }
+
+    /// Checks the parsing of auxiliary values.
+    #[test]
+    fn it_parse_values() {
+        assert_eq!(parse_cwe("CWE-312: Cleartext Storage"), Some(312));
+        assert_eq!(parse_cwe("CWE-89"), Some(89));
+        assert_eq!(parse_cwe("OWASP"), None);
+        assert_eq!(
+            "MobSF".parse::<ImportFormat>().unwrap(),
+            ImportFormat::MobSF
+        );
+        assert!("nessus".parse::<ImportFormat>().is_err());
+    }
+}
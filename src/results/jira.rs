@@ -0,0 +1,271 @@
+//! Jira integration module.
+//!
+//! If the `integrations` feature is enabled and Jira is configured, an issue is created for each
+//! vulnerability reaching the configured criticality. Issues are labeled with the fingerprint of
+//! the vulnerability, so that subsequent analyses update the existing issues instead of creating
+//! duplicates.
+
+#[cfg(feature = "integrations")]
+use failure::{Error, ResultExt};
+#[cfg(feature = "integrations")]
+use reqwest::Client;
+#[cfg(feature = "integrations")]
+use serde_json::{json, Value};
+
+use crate::criticality::Criticality;
+#[cfg(feature = "integrations")]
+use crate::{
+    print_message, print_warning,
+    results::{Results, Vulnerability},
+    Config,
+};
+
+/// Prefix of the labels used to store the fingerprint of the vulnerabilities.
+#[cfg(feature = "integrations")]
+const FINGERPRINT_LABEL_PREFIX: &str = "super-";
+
+/// Jira configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraConfig {
+    /// Base URL of the Jira instance.
+    url: String,
+    /// User used to authenticate in Jira.
+    user: String,
+    /// API token (or password) of the user.
+    token: String,
+    /// Key of the project where the issues will be created.
+    project: String,
+    /// Type of the created issues.
+    #[serde(default = "JiraConfig::default_issue_type")]
+    issue_type: String,
+    /// Additional labels for the created issues.
+    #[serde(default)]
+    labels: Vec<String>,
+    /// Minimum criticality of the vulnerabilities to create issues for.
+    #[serde(default = "JiraConfig::default_min_criticality")]
+    min_criticality: Criticality,
+}
+
+impl JiraConfig {
+    /// Default issue type.
+    fn default_issue_type() -> String {
+        String::from("Bug")
+    }
+
+    /// Default minimum criticality.
+    fn default_min_criticality() -> Criticality {
+        Criticality::High
+    }
+
+    /// Returns the base URL of the Jira instance.
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// Returns the user used to authenticate in Jira.
+    pub fn user(&self) -> &str {
+        self.user.as_str()
+    }
+
+    /// Returns the API token of the user.
+    pub fn token(&self) -> &str {
+        self.token.as_str()
+    }
+
+    /// Returns the key of the project where the issues will be created.
+    pub fn project(&self) -> &str {
+        self.project.as_str()
+    }
+
+    /// Returns the type of the created issues.
+    pub fn issue_type(&self) -> &str {
+        self.issue_type.as_str()
+    }
+
+    /// Returns the additional labels of the created issues.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the minimum criticality of the vulnerabilities to create issues for.
+    pub fn min_criticality(&self) -> Criticality {
+        self.min_criticality
+    }
+}
+
+/// Creates or updates the Jira issues for the vulnerabilities in the results.
+#[cfg(feature = "integrations")]
+pub fn sync_issues(config: &Config, jira: &JiraConfig, results: &Results) {
+    let client = Client::new();
+    let (mut created, mut updated) = (0, 0);
+
+    for vulnerability in results
+        .vulnerabilities()
+        .filter(|v| v.get_criticality() >= jira.min_criticality())
+    {
+        let fields = issue_fields(jira, results.app_package(), vulnerability);
+        let label = format!(
+            "{}{}",
+            FINGERPRINT_LABEL_PREFIX,
+            vulnerability.fingerprint()
+        );
+
+        let result = find_issue(&client, jira, &label).and_then(|issue| match issue {
+            Some(key) => update_issue(&client, jira, &key, &fields).map(|()| updated += 1),
+            None => create_issue(&client, jira, &fields).map(|()| created += 1),
+        });
+        if let Err(e) = result {
+            print_warning(format!(
+                "could not synchronize the Jira issue for `{}`. Error: {}",
+                vulnerability.name(),
+                e
+            ));
+        }
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} Jira issues created and {} updated.",
+            created, updated
+        ));
+    } else if !config.is_quiet() {
+        print_message("Jira issues synchronized.");
+    }
+}
+
+/// Generates the fields of the Jira issue for the given vulnerability.
+#[cfg(feature = "integrations")]
+fn issue_fields(jira: &JiraConfig, package: &str, vulnerability: &Vulnerability) -> Value {
+    let mut description = String::from(vulnerability.description());
+    if let Some(file) = vulnerability.file() {
+        description.push_str(&format!("\n\n*File:* {}", file.display()));
+        if let Some(line) = vulnerability.start_line() {
+            description.push_str(&format!(":{}", line + 1));
+        }
+    }
+    if let Some(code) = vulnerability.code() {
+        description.push_str(&format!("\n\n{{code}}\n{}\n{{code}}", code));
+    }
+
+    let mut labels = jira.labels().to_vec();
+    labels.push(format!(
+        "{}{}",
+        FINGERPRINT_LABEL_PREFIX,
+        vulnerability.fingerprint()
+    ));
+
+    json!({
+        "project": { "key": jira.project() },
+        "issuetype": { "name": jira.issue_type() },
+        "summary": format!(
+            "[{}] {}: {}",
+            package,
+            vulnerability.get_criticality(),
+            vulnerability.name()
+        ),
+        "description": description,
+        "labels": labels,
+    })
+}
+
+/// Finds the issue with the given fingerprint label, returning its key.
+#[cfg(feature = "integrations")]
+fn find_issue(client: &Client, jira: &JiraConfig, label: &str) -> Result<Option<String>, Error> {
+    let jql = format!(
+        "project = \"{}\" AND labels = \"{}\"",
+        jira.project(),
+        label
+    );
+    let response: Value = client
+        .get(&format!(
+            "{}/rest/api/2/search",
+            jira.url().trim_end_matches('/')
+        ))
+        .basic_auth(jira.user(), Some(jira.token()))
+        .query(&[
+            ("jql", jql.as_str()),
+            ("fields", "key"),
+            ("maxResults", "1"),
+        ])
+        .send()
+        .context("could not connect to Jira")?
+        .error_for_status()
+        .context("Jira rejected the issue search")?
+        .json()
+        .context("invalid Jira search response")?;
+
+    Ok(response["issues"][0]["key"].as_str().map(str::to_owned))
+}
+
+/// Creates a new issue with the given fields.
+#[cfg(feature = "integrations")]
+fn create_issue(client: &Client, jira: &JiraConfig, fields: &Value) -> Result<(), Error> {
+    let _ = client
+        .post(&format!(
+            "{}/rest/api/2/issue",
+            jira.url().trim_end_matches('/')
+        ))
+        .basic_auth(jira.user(), Some(jira.token()))
+        .json(&json!({ "fields": fields }))
+        .send()
+        .context("could not connect to Jira")?
+        .error_for_status()
+        .context("Jira rejected the new issue")?;
+
+    Ok(())
+}
+
+/// Updates the summary and description of the given issue.
+#[cfg(feature = "integrations")]
+fn update_issue(
+    client: &Client,
+    jira: &JiraConfig,
+    key: &str,
+    fields: &Value,
+) -> Result<(), Error> {
+    let _ = client
+        .put(&format!(
+            "{}/rest/api/2/issue/{}",
+            jira.url().trim_end_matches('/'),
+            key
+        ))
+        .basic_auth(jira.user(), Some(jira.token()))
+        .json(&json!({
+            "fields": {
+                "summary": fields["summary"],
+                "description": fields["description"],
+            }
+        }))
+        .send()
+        .context("could not connect to Jira")?
+        .error_for_status()
+        .context("Jira rejected the issue update")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JiraConfig;
+    use crate::criticality::Criticality;
+
+    /// Checks the parsing of the Jira configuration.
+    #[test]
+    fn it_jira_config() {
+        let jira: JiraConfig = toml::from_str(
+            r#"
+            url = "https://jira.example.com"
+            user = "super"
+            token = "secret"
+            project = "SEC"
+            labels = ["mobile"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(jira.project(), "SEC");
+        assert_eq!(jira.issue_type(), "Bug");
+        assert_eq!(jira.labels(), &[String::from("mobile")]);
+        assert_eq!(jira.min_criticality(), Criticality::High);
+    }
+}
@@ -1,33 +1,73 @@
 //! Results generation module.
 
-use std::{collections::BTreeSet, fs, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, mem,
+    path::Path,
+};
 
 use chrono::Local;
 use clap::crate_version;
 use failure::{Error, ResultExt};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
+mod baseline;
+mod cvss;
+mod finding;
 mod handlebars_helpers;
+mod import;
+mod jira;
 mod report;
+mod risk;
 mod sdk_number;
+mod similarity;
+mod ssdeep;
+mod stage;
+mod summary;
 mod utils;
 
-pub use self::utils::{html_escape, split_indent, Vulnerability};
+#[cfg(feature = "integrations")]
+pub use self::jira::sync_issues as sync_jira_issues;
+pub use self::{
+    baseline::apply_baseline,
+    cvss::Cvss,
+    import::{import_reports, ImportFormat},
+    jira::JiraConfig,
+    report::{register_generator, DefectDojoApi, DependencyTrackConfig, Generator},
+    risk::{Grade, Risk, RiskWeights},
+    stage::{DexFiles, SlowRule, Stage, StageStatus},
+    utils::{html_escape, split_indent, Confidence, Vulnerability, VulnerabilityBuilder},
+};
+pub(crate) use self::{
+    baseline::baseline_file, finding::Finding, similarity::find_similar_applications,
+    summary::Summary, utils::FingerPrint,
+};
 use self::{
+    risk::RiskFactors,
     sdk_number::{prettify_android_version, SdkNumber},
-    utils::FingerPrint,
+    similarity::SimilarApplication,
 };
 use crate::{
     criticality::Criticality,
-    print_warning,
-    results::report::{Generator, HandlebarsReport, Json},
+    error,
+    i18n::{tr, tr_args},
+    print_message, print_warning,
+    results::report::generators,
+    static_analysis::{
+        endpoints::Endpoint, frameworks::Framework, libraries::Library,
+        permission_usage::PermissionUsage, resilience::ProtectionCheck, secrets::Secret,
+    },
+    verify::Provenance,
     Config,
 };
 
 /// Results representation structure.
+#[derive(Debug)]
 pub struct Results {
     /// Application package name.
     app_package: String,
+    /// Name of the package in the distribution folder.
+    package_name: String,
     /// Application label.
     app_label: String,
     /// Application description.
@@ -42,6 +82,8 @@ pub struct Results {
     app_target_sdk: Option<SdkNumber>,
     /// Fingerprint of the application,
     app_fingerprint: FingerPrint,
+    /// Provenance of the analysis, if it could be computed.
+    provenance: Option<Provenance>,
     /// Certificate of the application.
     #[cfg(feature = "certificate")]
     certificate: String,
@@ -55,12 +97,46 @@ pub struct Results {
     high: BTreeSet<Vulnerability>,
     /// List of the potential critical vulnerabilities in the application.
     critical: BTreeSet<Vulnerability>,
+    /// Free-form annotations added to the results.
+    annotations: BTreeMap<String, String>,
+    /// Libraries bundled in the application.
+    libraries: Vec<Library>,
+    /// Cross-platform frameworks used to build the application.
+    frameworks: Vec<Framework>,
+    /// Secrets found in the application.
+    secrets: Vec<Secret>,
+    /// Network endpoints found in the string resources and assets.
+    endpoints: Vec<Endpoint>,
+    /// Usage in the code of the permissions requested by the application.
+    permission_usages: Vec<PermissionUsage>,
+    /// Resilience checks searched in the code of the application.
+    protection_checks: Vec<ProtectionCheck>,
+    /// Previously analyzed applications similar to this one.
+    similar_applications: Vec<SimilarApplication>,
+    /// Status of each stage of the analysis.
+    stages: Vec<Stage>,
+    /// DEX files of the application, with the ones that could not be processed.
+    dex_files: Option<DexFiles>,
+    /// Code rules that exceeded their time budget.
+    slow_rules: Vec<SlowRule>,
+    /// Number of source files analyzed.
+    files_analyzed: usize,
+    /// Number of source files that could not be analyzed.
+    files_skipped: usize,
+    /// Number of known findings suppressed by the baseline.
+    suppressed: usize,
+    /// Number of dangerous permissions requested by the application.
+    dangerous_permissions: usize,
+    /// Number of issues found in the certificate of the application.
+    certificate_issues: usize,
+    /// Overall risk of the application, once computed.
+    risk: Option<Risk>,
 }
 
 impl Results {
     /// Initializes the results structure.
-    #[allow(clippy::print_stdout)]
     pub fn init<P: AsRef<Path>>(config: &Config, package: P) -> Result<Self, Error> {
+        let package_name = config.package_name(package.as_ref());
         let fingerprint = match FingerPrint::from_package(package) {
             Ok(f) => f,
             Err(e) => {
@@ -72,20 +148,31 @@ impl Results {
                 return Err(e)?;
             }
         };
+        let provenance = match Provenance::compute(config) {
+            Ok(provenance) => Some(provenance),
+            Err(e) => {
+                print_warning(format!(
+                    "could not compute the provenance of the analysis, the report will not be \
+                     verifiable: {}",
+                    e
+                ));
+                None
+            }
+        };
         if config.is_verbose() {
-            println!(
-                "The results struct has been created. All the vulnerabilities will now \
-                 be recorded and when the analysis ends, they will be written to result \
-                 files."
-            );
+            print_message(tr(
+                "The results struct has been created. All the vulnerabilities will now be \
+                 recorded and when the analysis ends, they will be written to result files.",
+            ));
         } else if !config.is_quiet() {
-            println!("Results structure created.");
+            print_message(tr("Results structure created."));
         }
 
         #[cfg(feature = "certificate")]
         {
             Ok(Self {
                 app_package: String::new(),
+                package_name,
                 app_label: String::new(),
                 app_description: String::new(),
                 app_version: String::new(),
@@ -93,12 +180,30 @@ impl Results {
                 app_min_sdk: SdkNumber::Unknown(0),
                 app_target_sdk: None,
                 app_fingerprint: fingerprint,
+                provenance,
                 certificate: String::new(),
                 warnings: BTreeSet::new(),
                 low: BTreeSet::new(),
                 medium: BTreeSet::new(),
                 high: BTreeSet::new(),
                 critical: BTreeSet::new(),
+                annotations: BTreeMap::new(),
+                libraries: Vec::new(),
+                frameworks: Vec::new(),
+                secrets: Vec::new(),
+                endpoints: Vec::new(),
+                permission_usages: Vec::new(),
+                protection_checks: Vec::new(),
+                similar_applications: Vec::new(),
+                stages: Vec::new(),
+                dex_files: None,
+                slow_rules: Vec::new(),
+                files_analyzed: 0,
+                files_skipped: 0,
+                suppressed: 0,
+                dangerous_permissions: 0,
+                certificate_issues: 0,
+                risk: None,
             })
         }
 
@@ -106,6 +211,7 @@ impl Results {
         {
             Ok(Self {
                 app_package: String::new(),
+                package_name,
                 app_label: String::new(),
                 app_description: String::new(),
                 app_version: String::new(),
@@ -113,11 +219,29 @@ impl Results {
                 app_min_sdk: SdkNumber::Unknown(0),
                 app_target_sdk: None,
                 app_fingerprint: fingerprint,
+                provenance,
                 warnings: BTreeSet::new(),
                 low: BTreeSet::new(),
                 medium: BTreeSet::new(),
                 high: BTreeSet::new(),
                 critical: BTreeSet::new(),
+                annotations: BTreeMap::new(),
+                libraries: Vec::new(),
+                frameworks: Vec::new(),
+                secrets: Vec::new(),
+                endpoints: Vec::new(),
+                permission_usages: Vec::new(),
+                protection_checks: Vec::new(),
+                similar_applications: Vec::new(),
+                stages: Vec::new(),
+                dex_files: None,
+                slow_rules: Vec::new(),
+                files_analyzed: 0,
+                files_skipped: 0,
+                suppressed: 0,
+                dangerous_permissions: 0,
+                certificate_issues: 0,
+                risk: None,
             })
         }
     }
@@ -132,6 +256,11 @@ impl Results {
         &self.app_package
     }
 
+    /// Gets the name of the package in the distribution folder.
+    pub(crate) fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
     /// Sets the certificate string.
     #[cfg(feature = "certificate")]
     pub fn set_certificate<S: Into<String>>(&mut self, certificate: S) {
@@ -143,31 +272,66 @@ impl Results {
         self.app_label = label.into();
     }
 
+    /// Gets the application's label.
+    pub fn app_label(&self) -> &str {
+        &self.app_label
+    }
+
     /// Sets the application description
     pub fn set_app_description<S: Into<String>>(&mut self, description: S) {
         self.app_description = description.into();
     }
 
+    /// Gets the application description.
+    pub fn app_description(&self) -> &str {
+        &self.app_description
+    }
+
     /// Sets the application version string.
     pub fn set_app_version<S: Into<String>>(&mut self, version: S) {
         self.app_version = version.into();
     }
 
+    /// Gets the application version string.
+    pub fn app_version(&self) -> &str {
+        &self.app_version
+    }
+
     /// Sets the application version number.
     pub fn set_app_version_num(&mut self, version: u32) {
         self.app_version_num = version;
     }
 
+    /// Gets the application version number.
+    pub fn app_version_num(&self) -> u32 {
+        self.app_version_num
+    }
+
     /// Sets the application's minimum SDK number.
     pub fn set_app_min_sdk(&mut self, sdk: u32) {
         self.app_min_sdk = SdkNumber::from(sdk);
     }
 
+    /// Gets the application's minimum SDK number.
+    pub fn app_min_sdk(&self) -> u32 {
+        self.app_min_sdk.number()
+    }
+
     /// Sets the application's target SDK number.
     pub fn set_app_target_sdk(&mut self, sdk: u32) {
         self.app_target_sdk = Some(SdkNumber::from(sdk));
     }
 
+    /// Gets the application's target SDK number, if it was declared.
+    pub fn app_target_sdk(&self) -> Option<u32> {
+        self.app_target_sdk.map(SdkNumber::number)
+    }
+
+    /// Gets the SHA-256 hash of the application package, in hexadecimal.
+    pub fn app_sha256(&self) -> String {
+        self.app_fingerprint.sha256_hex()
+    }
+
     /// Adds a vulnerability to the results.
     #[allow(unused_variables)] // Until we remove the debug assertions
     pub fn add_vulnerability(&mut self, vulnerability: Vulnerability) {
@@ -212,102 +376,381 @@ impl Results {
         }
     }
 
-    /// Generates the report.
-    #[allow(clippy::print_stdout)]
-    pub fn generate_report<S: AsRef<str>>(&self, config: &Config, package: S) -> Result<(), Error> {
+    /// Numbers the vulnerabilities of the same rule with the same snippet in the same file by
+    /// their position in the file, so that each of them gets a different ID.
+    pub(crate) fn number_occurrences(&mut self) {
+        let mut vulnerabilities = Vec::new();
+        for set in &mut [
+            &mut self.critical,
+            &mut self.high,
+            &mut self.medium,
+            &mut self.low,
+            &mut self.warnings,
+        ] {
+            vulnerabilities.extend(
+                mem::replace(&mut **set, BTreeSet::new())
+                    .into_iter()
+                    .map(|vulnerability| (vulnerability.snippet_fingerprint(), vulnerability)),
+            );
+        }
+        vulnerabilities.sort_by(|(key_a, a), (key_b, b)| {
+            (key_a, a.start_line(), a.end_line()).cmp(&(key_b, b.start_line(), b.end_line()))
+        });
+
+        let mut previous = None;
+        let mut occurrence = 0;
+        for (key, mut vulnerability) in vulnerabilities {
+            occurrence = if previous.as_ref() == Some(&key) {
+                occurrence + 1
+            } else {
+                0
+            };
+            vulnerability.set_occurrence(occurrence);
+            previous = Some(key);
+            self.add_vulnerability(vulnerability);
+        }
+    }
+
+    /// Returns an iterator over all the vulnerabilities found so far, from the most critical.
+    pub fn vulnerabilities(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.critical
+            .iter()
+            .chain(self.high.iter())
+            .chain(self.medium.iter())
+            .chain(self.low.iter())
+            .chain(self.warnings.iter())
+    }
+
+    /// Groups the vulnerabilities found in the classes of the application by their class.
+    ///
+    /// The vulnerabilities of each class are sorted by their criticality, the most critical
+    /// first.
+    pub fn vulnerabilities_by_class(&self) -> BTreeMap<&str, Vec<&Vulnerability>> {
+        let mut classes = BTreeMap::new();
+        for vulnerability in self.vulnerabilities() {
+            if let Some(class) = vulnerability.class() {
+                classes
+                    .entry(class)
+                    .or_insert_with(Vec::new)
+                    .push(vulnerability);
+            }
+        }
+        classes
+    }
+
+    /// Returns an iterator over the vulnerabilities found with the given criticality.
+    pub fn vulnerabilities_with(
+        &self,
+        criticality: Criticality,
+    ) -> impl Iterator<Item = &Vulnerability> {
+        match criticality {
+            Criticality::Warning => self.warnings.iter(),
+            Criticality::Low => self.low.iter(),
+            Criticality::Medium => self.medium.iter(),
+            Criticality::High => self.high.iter(),
+            Criticality::Critical => self.critical.iter(),
+        }
+    }
+
+    /// Returns an iterator over the warnings found.
+    pub fn warnings(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.warnings.iter()
+    }
+
+    /// Returns an iterator over the low criticality vulnerabilities found.
+    pub fn low(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.low.iter()
+    }
+
+    /// Returns an iterator over the medium criticality vulnerabilities found.
+    pub fn medium(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.medium.iter()
+    }
+
+    /// Returns an iterator over the high criticality vulnerabilities found.
+    pub fn high(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.high.iter()
+    }
+
+    /// Returns an iterator over the critical vulnerabilities found.
+    pub fn critical(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.critical.iter()
+    }
+
+    /// Consumes the results, returning all the vulnerabilities found, from the most critical.
+    pub fn into_vulnerabilities(self) -> Vec<Vulnerability> {
+        self.critical
+            .into_iter()
+            .chain(self.high)
+            .chain(self.medium)
+            .chain(self.low)
+            .chain(self.warnings)
+            .collect()
+    }
+
+    /// Escalates the vulnerabilities with the given IDs to the given criticality, if they have a
+    /// lower one.
+    pub(crate) fn escalate_vulnerabilities(
+        &mut self,
+        ids: &BTreeSet<String>,
+        criticality: Criticality,
+    ) {
+        let escalated = self
+            .vulnerabilities()
+            .filter(|v| v.get_criticality() < criticality && ids.contains(&v.id()))
+            .cloned()
+            .collect::<Vec<_>>();
+        for vulnerability in escalated {
+            let _ = match vulnerability.get_criticality() {
+                Criticality::Warning => self.warnings.remove(&vulnerability),
+                Criticality::Low => self.low.remove(&vulnerability),
+                Criticality::Medium => self.medium.remove(&vulnerability),
+                Criticality::High => self.high.remove(&vulnerability),
+                Criticality::Critical => self.critical.remove(&vulnerability),
+            };
+            self.add_vulnerability(vulnerability.escalated(criticality));
+        }
+    }
+
+    /// Removes the vulnerabilities that match the given predicate, counting them as suppressed.
+    ///
+    /// Returns the number of suppressed vulnerabilities.
+    pub(crate) fn suppress_vulnerabilities<F>(&mut self, predicate: F) -> usize
+    where
+        F: Fn(&Vulnerability) -> bool,
+    {
+        let before = self.vulnerabilities().count();
+        for set in &mut [
+            &mut self.warnings,
+            &mut self.low,
+            &mut self.medium,
+            &mut self.high,
+            &mut self.critical,
+        ] {
+            set.retain(|vulnerability| !predicate(vulnerability));
+        }
+        let suppressed = before - self.vulnerabilities().count();
+        self.suppressed += suppressed;
+        suppressed
+    }
+
+    /// Returns the number of known findings suppressed by the baseline.
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Adds a library bundled in the application.
+    pub fn add_library(&mut self, library: Library) {
+        self.libraries.push(library);
+    }
+
+    /// Returns the libraries bundled in the application.
+    pub fn libraries(&self) -> &[Library] {
+        &self.libraries
+    }
+
+    /// Adds a cross-platform framework used to build the application.
+    pub fn add_framework(&mut self, framework: Framework) {
+        self.frameworks.push(framework);
+    }
+
+    /// Returns the cross-platform frameworks used to build the application.
+    pub fn frameworks(&self) -> &[Framework] {
+        &self.frameworks
+    }
+
+    /// Adds a secret found in the application.
+    pub fn add_secret(&mut self, secret: Secret) {
+        self.secrets.push(secret);
+    }
+
+    /// Returns the secrets found in the application.
+    pub fn secrets(&self) -> &[Secret] {
+        &self.secrets
+    }
+
+    /// Adds a network endpoint found in the application.
+    pub fn add_endpoint(&mut self, endpoint: Endpoint) {
+        self.endpoints.push(endpoint);
+    }
+
+    /// Returns the network endpoints found in the application.
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Adds the usage in the code of a permission requested by the application.
+    pub fn add_permission_usage(&mut self, usage: PermissionUsage) {
+        self.permission_usages.push(usage);
+    }
+
+    /// Returns the usage in the code of the permissions requested by the application.
+    pub fn permission_usages(&self) -> &[PermissionUsage] {
+        &self.permission_usages
+    }
+
+    /// Adds a resilience check searched in the code of the application.
+    pub fn add_protection_check(&mut self, check: ProtectionCheck) {
+        self.protection_checks.push(check);
+    }
+
+    /// Returns the resilience checks searched in the code of the application.
+    pub fn protection_checks(&self) -> &[ProtectionCheck] {
+        &self.protection_checks
+    }
+
+    /// Sets the previously analyzed applications similar to this one.
+    pub(crate) fn set_similar_applications(&mut self, applications: Vec<SimilarApplication>) {
+        self.similar_applications = applications;
+    }
+
+    /// Sets the status of the given stage of the analysis, replacing any previous status.
+    pub fn set_stage_status<N: Into<String>>(&mut self, stage: N, status: StageStatus) {
+        let stage = Stage::new(stage, status);
+        match self.stages.iter_mut().find(|s| s.name() == stage.name()) {
+            Some(previous) => *previous = stage,
+            None => self.stages.push(stage),
+        }
+    }
+
+    /// Returns the status of each stage of the analysis, in order.
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// Sets the DEX files of the application, with the ones that could not be processed.
+    pub fn set_dex_files(&mut self, dex_files: DexFiles) {
+        self.dex_files = Some(dex_files);
+    }
+
+    /// Returns the DEX files of the application, with the ones that could not be processed, if
+    /// they were converted.
+    pub fn dex_files(&self) -> Option<&DexFiles> {
+        self.dex_files.as_ref()
+    }
+
+    /// Adds a code rule that exceeded its time budget.
+    pub fn add_slow_rule(&mut self, slow_rule: SlowRule) {
+        self.slow_rules.push(slow_rule);
+    }
+
+    /// Returns the code rules that exceeded their time budget.
+    pub fn slow_rules(&self) -> &[SlowRule] {
+        &self.slow_rules
+    }
+
+    /// Returns whether any stage of the analysis did not finish successfully, any DEX file could
+    /// not be processed or any code rule was disabled for being too slow, which means that the
+    /// results might be incomplete.
+    pub fn is_degraded(&self) -> bool {
+        self.stages.iter().any(|stage| !stage.status().is_ok())
+            || self
+                .dex_files
+                .as_ref()
+                .map_or(false, |dex_files| dex_files.processed() < dex_files.total())
+            || self.slow_rules.iter().any(SlowRule::is_disabled)
+    }
+
+    /// Sets the number of source files analyzed and skipped in the code analysis.
+    pub fn set_file_counts(&mut self, analyzed: usize, skipped: usize) {
+        self.files_analyzed = analyzed;
+        self.files_skipped = skipped;
+    }
+
+    /// Returns the number of source files analyzed.
+    pub fn files_analyzed(&self) -> usize {
+        self.files_analyzed
+    }
+
+    /// Returns the number of source files that could not be analyzed.
+    pub fn files_skipped(&self) -> usize {
+        self.files_skipped
+    }
+
+    /// Sets the number of dangerous permissions requested by the application.
+    pub fn set_dangerous_permissions(&mut self, count: usize) {
+        self.dangerous_permissions = count;
+    }
+
+    /// Records an issue found in the certificate of the application.
+    pub fn add_certificate_issue(&mut self) {
+        self.certificate_issues += 1;
+    }
+
+    /// Computes the overall risk of the application with the given weights, from the results
+    /// found so far.
+    pub fn compute_risk(&mut self, weights: &RiskWeights) {
+        let mut vulnerabilities = [0; 5];
+        for vulnerability in self.vulnerabilities() {
+            vulnerabilities[vulnerability.get_criticality() as usize] += 1;
+        }
+        let factors = RiskFactors {
+            vulnerabilities,
+            dangerous_permissions: self.dangerous_permissions,
+            // Without a target SDK, Android targets the minimum SDK.
+            target_sdk: self.app_target_sdk().unwrap_or_else(|| self.app_min_sdk()),
+            certificate_issues: self.certificate_issues,
+        };
+        self.risk = Some(Risk::compute(weights, &factors));
+    }
+
+    /// Returns the overall risk of the application, if it was computed.
+    pub fn risk(&self) -> Option<Risk> {
+        self.risk
+    }
+
+    /// Adds an annotation to the results, replacing any previous one with the same key.
+    pub fn add_annotation<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        let _ = self.annotations.insert(key.into(), value.into());
+    }
+
+    /// Generates the reports, running the registered report generators.
+    pub fn generate_report(&self, config: &Config) -> Result<(), Error> {
         let path = config.results_folder().join(&self.app_package);
         if config.is_verbose() {
-            println!("Starting report generation.");
+            print_message(tr("Starting report generation."));
         }
         if !path.exists() {
             if config.is_verbose() {
-                println!("First we'll create the results folder.");
+                print_message(tr("First we'll create the results folder."));
             }
-            fs::create_dir_all(&path)?;
+            fs::create_dir_all(&path).context(error::Kind::ReportWrite {
+                path: path.display().to_string(),
+            })?;
             if config.is_verbose() {
-                println!("Results folder created. Time to create the reports.");
+                print_message(tr("Results folder created. Time to create the reports."));
             }
         }
-        if config.has_to_generate_json() {
-            let path = path.join("results.json");
-
-            if config.is_force() || !path.exists() {
-                if path.exists() {
-                    if config.is_verbose() {
-                        println!("The application JSON results file exists. But no more…");
-                    }
-
-                    if let Err(e) = fs::remove_file(&path) {
-                        print_warning(format!(
-                            "there was an error when removing the JSON results file: {}",
-                            e
-                        ));
-                    }
-                }
-                let mut json_reporter = Json::new();
-
-                if let Err(e) = json_reporter.generate(config, self) {
-                    print_warning(format!("there was en error generating JSON report: {}", e));
-                }
 
-                if !config.is_quiet() {
-                    println!("JSON report generated.");
-                }
-            } else if config.is_verbose() {
-                println!(
-                    "Seems that the JSON report has already been generated. There is no \
-                     need to do it again."
-                );
-            } else {
-                println!("Skipping JSON report generation.");
+        for generator in generators().iter_mut() {
+            if !generator.is_enabled(config) {
+                continue;
             }
-        }
-
-        if config.has_to_generate_html() {
-            let index_path = path.join("index.html");
-
-            if config.is_force() || !index_path.exists() {
-                if path.exists() {
-                    if config.is_verbose() {
-                        println!("The application HTML results exist. But no more…");
-                    }
-
-                    for f in fs::read_dir(path)
-                        .context("there was an error when removing the HTML results")?
-                    {
-                        let f = f?;
-
-                        if f.file_type()?.is_dir() {
-                            fs::remove_dir_all(f.path())
-                                .context("there was an error when removing the HTML results")?;
-                        } else if &f.file_name() != "results.json" {
-                            fs::remove_file(f.path())
-                                .context("there was an error when removing the HTML results")?;
-                        }
-                    }
+            let name = generator.name().to_owned();
+
+            let exists = generator
+                .file_name()
+                .map_or(false, |file_name| path.join(file_name).exists());
+            if generator.keeps_existing() && !config.is_force() && exists {
+                if config.is_verbose() {
+                    print_message(tr_args(
+                        "Seems that the {} report has already been generated. There is no need \
+                         to do it again.",
+                        &[&name],
+                    ));
+                } else {
+                    print_message(tr_args("Skipping {} report generation.", &[&name]));
                 }
+                continue;
+            }
 
-                let handlebars_report_result = HandlebarsReport::from_path(
-                    config.template_path(),
-                    package.as_ref().to_owned(),
-                );
-
-                if let Ok(mut handlebars_reporter) = handlebars_report_result {
-                    if let Err(e) = handlebars_reporter.generate(config, self) {
-                        print_warning(format!("There was en error generating HTML report: {}", e));
-                    }
-
-                    if !config.is_quiet() {
-                        println!("HTML report generated.");
-                    }
-                }
-            } else if config.is_verbose() {
-                println!(
-                    "Seems that the HTML report has already been generated. There is no
-                          need to do it again."
-                );
-            } else {
-                println!("Skipping HTML report generation.");
+            if let Err(e) = generator.generate(config, self) {
+                print_warning(format!(
+                    "there was an error generating the {} report: {}",
+                    name, e
+                ));
+            } else if !config.is_quiet() {
+                print_message(tr_args("{} report generated.", &[&name]));
             }
         }
 
@@ -321,11 +764,51 @@ impl Serialize for Results {
         S: Serializer,
     {
         let now = Local::now();
+        let classes = self.vulnerabilities_by_class();
         let len = {
-            let mut len = 21;
+            let mut len = 24;
             if cfg!(feature = "certificate") {
                 len += 1;
             }
+            if !self.annotations.is_empty() {
+                len += 1;
+            }
+            if !self.libraries.is_empty() {
+                len += 1;
+            }
+            if !self.frameworks.is_empty() {
+                len += 1;
+            }
+            if !self.secrets.is_empty() {
+                len += 1;
+            }
+            if !self.endpoints.is_empty() {
+                len += 1;
+            }
+            if !self.permission_usages.is_empty() {
+                len += 1;
+            }
+            if !self.protection_checks.is_empty() {
+                len += 1;
+            }
+            if !self.similar_applications.is_empty() {
+                len += 1;
+            }
+            if !classes.is_empty() {
+                len += 1;
+            }
+            if self.provenance.is_some() {
+                len += 1;
+            }
+            if self.dex_files.is_some() {
+                len += 1;
+            }
+            if !self.slow_rules.is_empty() {
+                len += 1;
+            }
+            if self.risk.is_some() {
+                len += 1;
+            }
             if self.app_min_sdk.version().is_some() {
                 len += 1;
             }
@@ -350,6 +833,10 @@ impl Serialize for Results {
         ser_struct.serialize_field("app_version_number", &self.app_version_num)?;
         ser_struct.serialize_field("app_fingerprint", &self.app_fingerprint)?;
 
+        if let Some(provenance) = &self.provenance {
+            ser_struct.serialize_field("scanner", provenance)?;
+        }
+
         #[cfg(feature = "certificate")]
         {
             ser_struct.serialize_field("certificate", &self.certificate)?;
@@ -377,6 +864,19 @@ impl Serialize for Results {
             }
         }
 
+        ser_struct.serialize_field("degraded", &self.is_degraded())?;
+        ser_struct.serialize_field("stages", &self.stages)?;
+        if let Some(dex_files) = &self.dex_files {
+            ser_struct.serialize_field("dex_files", dex_files)?;
+        }
+        if !self.slow_rules.is_empty() {
+            ser_struct.serialize_field("slow_rules", &self.slow_rules)?;
+        }
+
+        if let Some(risk) = &self.risk {
+            ser_struct.serialize_field("risk", risk)?;
+        }
+
         ser_struct.serialize_field(
             "total_vulnerabilities",
             &(self.low.len() + self.medium.len() + self.high.len() + self.critical.len()),
@@ -391,6 +891,43 @@ impl Serialize for Results {
         ser_struct.serialize_field("lows_len", &self.low.len())?;
         ser_struct.serialize_field("warnings", &self.warnings)?;
         ser_struct.serialize_field("warnings_len", &self.warnings.len())?;
+        ser_struct.serialize_field("suppressed", &self.suppressed)?;
+
+        if !classes.is_empty() {
+            ser_struct.serialize_field("classes", &classes)?;
+        }
+
+        if !self.annotations.is_empty() {
+            ser_struct.serialize_field("annotations", &self.annotations)?;
+        }
+
+        if !self.libraries.is_empty() {
+            ser_struct.serialize_field("libraries", &self.libraries)?;
+        }
+
+        if !self.frameworks.is_empty() {
+            ser_struct.serialize_field("frameworks", &self.frameworks)?;
+        }
+
+        if !self.secrets.is_empty() {
+            ser_struct.serialize_field("secrets", &self.secrets)?;
+        }
+
+        if !self.endpoints.is_empty() {
+            ser_struct.serialize_field("endpoints", &self.endpoints)?;
+        }
+
+        if !self.permission_usages.is_empty() {
+            ser_struct.serialize_field("permission_usage", &self.permission_usages)?;
+        }
+
+        if !self.protection_checks.is_empty() {
+            ser_struct.serialize_field("resilience", &self.protection_checks)?;
+        }
+
+        if !self.similar_applications.is_empty() {
+            ser_struct.serialize_field("similar_applications", &self.similar_applications)?;
+        }
 
         ser_struct.end()
     }
@@ -0,0 +1,176 @@
+//! CSV report generation module.
+//!
+//! The report contains one row per vulnerability, following [RFC 4180][rfc], so that the findings
+//! can be imported into spreadsheets and other tools. Line numbers start at 1.
+//!
+//! [rfc]: https://tools.ietf.org/html/rfc4180
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use failure::Error;
+
+use crate::{
+    config::Config,
+    print_message,
+    results::{report::Generator, Results, Vulnerability},
+};
+
+/// Name of the CSV report file.
+pub const REPORT_FILE: &str = "results.csv";
+
+/// Columns of the CSV report.
+const HEADER: [&str; 10] = [
+    "id",
+    "criticality",
+    "name",
+    "rule_id",
+    "cwe",
+    "file",
+    "start_line",
+    "end_line",
+    "description",
+    "code",
+];
+
+/// CSV report generator.
+pub struct Csv;
+
+impl Csv {
+    /// Creates a new CSV report generator.
+    pub fn new() -> Self {
+        Csv
+    }
+}
+
+impl Generator for Csv {
+    fn name(&self) -> &str {
+        "CSV"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_csv()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(REPORT_FILE)
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Starting CSV report generation. First we create the file.")
+        }
+        let mut f = BufWriter::new(File::create(report_path(config, results))?);
+        if config.is_verbose() {
+            print_message("The report file has been created. Now it's time to fill it.")
+        }
+
+        f.write_all(table(results.vulnerabilities()).as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Gets the path of the CSV report for the given results.
+fn report_path(config: &Config, results: &Results) -> PathBuf {
+    config
+        .results_folder()
+        .join(&results.app_package())
+        .join(REPORT_FILE)
+}
+
+/// Generates the CSV table of the given vulnerabilities, including the header.
+fn table<'v, I>(vulnerabilities: I) -> String
+where
+    I: Iterator<Item = &'v Vulnerability>,
+{
+    let mut table = row(HEADER.iter().map(|&column| column.to_owned()));
+    for vulnerability in vulnerabilities {
+        let line = |line: Option<usize>| line.map_or_else(String::new, |l| (l + 1).to_string());
+        table.push_str(&row(vec![
+            vulnerability.id(),
+            vulnerability.get_criticality().to_string(),
+            vulnerability.name().to_owned(),
+            vulnerability.rule_id().unwrap_or_default().to_owned(),
+            vulnerability
+                .cwe()
+                .map_or_else(String::new, |cwe| format!("CWE-{}", cwe)),
+            vulnerability
+                .file()
+                .map_or_else(String::new, |file| file.to_string_lossy().into_owned()),
+            line(vulnerability.start_line()),
+            line(vulnerability.end_line()),
+            vulnerability.description().to_owned(),
+            vulnerability.code().unwrap_or_default().to_owned(),
+        ]));
+    }
+    table
+}
+
+/// Generates a CSV row with the given fields, ending with CRLF.
+fn row<I: IntoIterator<Item = String>>(fields: I) -> String {
+    let mut row = fields
+        .into_iter()
+        .map(|field| escape(&field))
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+/// Escapes the given field, quoting it if it contains commas, quotes or line breaks.
+fn escape(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, table};
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the escaping of the CSV fields.
+    #[test]
+    fn it_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a, b"), "\"a, b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    /// Checks the generation of the CSV table.
+    #[test]
+    fn it_table() {
+        let vulnerabilities = vec![
+            Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5, insecure.")
+                .file("classes/com/example/Hash.java")
+                .lines(9, 10)
+                .rule_id("weak-algorithms")
+                .cwe(327)
+                .build(),
+            Vulnerability::builder(Criticality::Warning, "Large heap", "Needs a large heap.")
+                .build(),
+        ];
+        let table = table(vulnerabilities.iter());
+        let rows = table.split("\r\n").collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[0],
+            "id,criticality,name,rule_id,cwe,file,start_line,end_line,description,code"
+        );
+        assert!(rows[1].starts_with(&vulnerabilities[0].id()));
+        assert!(rows[1].ends_with(
+            ",high,Weak Algorithms,weak-algorithms,CWE-327,classes/com/example/Hash.java,10,11,\
+             \"Uses MD5, insecure.\","
+        ));
+        assert!(rows[2].ends_with(",warning,Large heap,,,,,,Needs a large heap.,"));
+        assert_eq!(rows[3], "");
+    }
+}
@@ -0,0 +1,232 @@
+//! CycloneDX SBOM generation module.
+//!
+//! The software bill of materials lists the libraries bundled in the application, in CycloneDX
+//! 1.4 JSON format. If the `integrations` feature is enabled and Dependency-Track is configured,
+//! the SBOM is also uploaded to the Dependency-Track project of the application.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use chrono::Local;
+use clap::crate_version;
+use failure::Error;
+#[cfg(feature = "integrations")]
+use failure::ResultExt;
+#[cfg(feature = "integrations")]
+use reqwest::{multipart::Form, Client};
+use serde_json::{json, ser, Value};
+
+use crate::{
+    config::Config,
+    print_message,
+    results::{report::Generator, Results},
+    static_analysis::libraries::Library,
+};
+#[cfg(feature = "integrations")]
+use crate::{i18n::tr, print_warning};
+
+/// Name of the SBOM file.
+pub const SBOM_FILE: &str = "bom.json";
+
+/// Dependency-Track configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DependencyTrackConfig {
+    /// Base URL of the Dependency-Track instance.
+    url: String,
+    /// API key with the `BOM_UPLOAD` permission.
+    api_key: String,
+    /// Project names for each application package. The package is used if not present.
+    #[serde(default)]
+    projects: BTreeMap<String, String>,
+}
+
+impl DependencyTrackConfig {
+    /// Returns the base URL of the Dependency-Track instance.
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// Returns the API key.
+    pub fn api_key(&self) -> &str {
+        self.api_key.as_str()
+    }
+
+    /// Returns the Dependency-Track project name for the given application package.
+    pub fn project<'s>(&'s self, package: &'s str) -> &'s str {
+        self.projects.get(package).map_or(package, String::as_str)
+    }
+}
+
+/// CycloneDX SBOM generator.
+#[derive(Default)]
+pub struct CycloneDx;
+
+impl CycloneDx {
+    /// Creates a new CycloneDX SBOM generator.
+    pub fn new() -> Self {
+        CycloneDx
+    }
+
+    /// Uploads the generated SBOM to the Dependency-Track project of the application.
+    ///
+    /// The project is created if it does not exist.
+    #[cfg(feature = "integrations")]
+    pub fn upload(
+        config: &Config,
+        dependency_track: &DependencyTrackConfig,
+        results: &Results,
+    ) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Uploading the SBOM to Dependency-Track.");
+        }
+        let form = Form::new()
+            .text("autoCreate", "true")
+            .text(
+                "projectName",
+                dependency_track.project(results.app_package()).to_owned(),
+            )
+            .text("projectVersion", results.app_version().to_owned())
+            .file("bom", sbom_path(config, results))
+            .context("could not read the SBOM")?;
+
+        let _ = Client::new()
+            .post(&format!(
+                "{}/api/v1/bom",
+                dependency_track.url().trim_end_matches('/')
+            ))
+            .header("X-Api-Key", dependency_track.api_key())
+            .multipart(form)
+            .send()
+            .context("could not connect to Dependency-Track")?
+            .error_for_status()
+            .context("Dependency-Track rejected the SBOM")?;
+
+        Ok(())
+    }
+}
+
+impl Generator for CycloneDx {
+    fn name(&self) -> &str {
+        "SBOM"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_sbom()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(SBOM_FILE)
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Starting SBOM generation. First we create the file.")
+        }
+        let mut f = BufWriter::new(File::create(sbom_path(config, results))?);
+        if config.is_verbose() {
+            print_message("The SBOM file has been created. Now it's time to fill it.")
+        }
+        ser::to_writer(&mut f, &sbom(results))?;
+        f.flush()?;
+
+        #[cfg(feature = "integrations")]
+        {
+            if let Some(dependency_track) = config.dependency_track() {
+                match Self::upload(config, dependency_track, results) {
+                    Ok(()) if !config.is_quiet() => {
+                        print_message(tr("SBOM uploaded to Dependency-Track."));
+                    }
+                    Ok(()) => {}
+                    Err(e) => print_warning(format!(
+                        "there was an error uploading the SBOM to Dependency-Track: {}",
+                        e
+                    )),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets the path of the SBOM for the given results.
+fn sbom_path(config: &Config, results: &Results) -> PathBuf {
+    config
+        .results_folder()
+        .join(&results.app_package())
+        .join(SBOM_FILE)
+}
+
+/// Generates the CycloneDX SBOM for the given results.
+fn sbom(results: &Results) -> Value {
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.4",
+        "version": 1,
+        "metadata": {
+            "timestamp": Local::now().to_rfc3339(),
+            "tools": [{
+                "vendor": "SUPER Team",
+                "name": "super-analyzer",
+                "version": crate_version!(),
+            }],
+            "component": {
+                "type": "application",
+                "bom-ref": results.app_package(),
+                "name": results.app_package(),
+                "version": results.app_version(),
+            },
+        },
+        "components": results.libraries().iter().map(component).collect::<Vec<_>>(),
+    })
+}
+
+/// Converts a library to a CycloneDX component.
+fn component(library: &Library) -> Value {
+    json!({
+        "type": "library",
+        "bom-ref": library.purl(),
+        "group": library.group(),
+        "name": library.name(),
+        "version": library.version(),
+        "purl": library.purl(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{component, DependencyTrackConfig};
+    use crate::static_analysis::libraries::Library;
+
+    /// Checks the conversion of libraries to CycloneDX components.
+    #[test]
+    fn it_component() {
+        let bom_component = component(&Library::new("androidx.core", "core", "1.0.1"));
+
+        assert_eq!(bom_component["type"], "library");
+        assert_eq!(bom_component["group"], "androidx.core");
+        assert_eq!(bom_component["purl"], "pkg:maven/androidx.core/core@1.0.1");
+    }
+
+    /// Checks the project mapping of the Dependency-Track configuration.
+    #[test]
+    fn it_dependency_track_config() {
+        let config: DependencyTrackConfig = toml::from_str(
+            r#"
+            url = "https://dtrack.example.com"
+            api_key = "key"
+
+            [projects]
+            "com.example.app" = "Example"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.project("com.example.app"), "Example");
+        assert_eq!(config.project("com.example.other"), "com.example.other");
+    }
+}
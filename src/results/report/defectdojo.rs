@@ -0,0 +1,229 @@
+//! DefectDojo report generation module.
+//!
+//! The report uses the _Generic Findings Import_ format of DefectDojo, so that it can be imported
+//! in any engagement. If the `integrations` feature is enabled and the DefectDojo API is
+//! configured, the report is also uploaded to the configured engagement.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use chrono::Local;
+use failure::Error;
+#[cfg(feature = "integrations")]
+use failure::ResultExt;
+#[cfg(feature = "integrations")]
+use reqwest::{multipart::Form, Client};
+use serde_json::{json, ser, Value};
+
+use crate::{
+    config::Config,
+    criticality::Criticality,
+    print_message,
+    results::{report::Generator, Results, Vulnerability},
+};
+#[cfg(feature = "integrations")]
+use crate::{i18n::tr, print_warning};
+
+/// Name of the DefectDojo report file.
+pub const REPORT_FILE: &str = "defectdojo.json";
+
+/// DefectDojo API configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DefectDojoApi {
+    /// Base URL of the DefectDojo instance.
+    url: String,
+    /// API v2 token.
+    token: String,
+    /// Identifier of the engagement where the findings will be imported.
+    engagement: u64,
+}
+
+impl DefectDojoApi {
+    /// Returns the base URL of the DefectDojo instance.
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// Returns the API token.
+    pub fn token(&self) -> &str {
+        self.token.as_str()
+    }
+
+    /// Returns the identifier of the engagement.
+    pub fn engagement(&self) -> u64 {
+        self.engagement
+    }
+}
+
+/// DefectDojo report generator.
+#[derive(Default)]
+pub struct DefectDojo;
+
+impl DefectDojo {
+    /// Creates a new DefectDojo report generator.
+    pub fn new() -> Self {
+        DefectDojo
+    }
+
+    /// Uploads the generated report to the configured DefectDojo engagement.
+    #[cfg(feature = "integrations")]
+    pub fn upload(config: &Config, api: &DefectDojoApi, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Uploading the report to DefectDojo.");
+        }
+        let form = Form::new()
+            .text("scan_type", "Generic Findings Import")
+            .text("engagement", api.engagement().to_string())
+            .text("minimum_severity", "Info")
+            .file("file", report_path(config, results))
+            .context("could not read the DefectDojo report")?;
+
+        let _ = Client::new()
+            .post(&format!(
+                "{}/api/v2/import-scan/",
+                api.url().trim_end_matches('/')
+            ))
+            .header("Authorization", format!("Token {}", api.token()))
+            .multipart(form)
+            .send()
+            .context("could not connect to DefectDojo")?
+            .error_for_status()
+            .context("DefectDojo rejected the report")?;
+
+        Ok(())
+    }
+}
+
+impl Generator for DefectDojo {
+    fn name(&self) -> &str {
+        "DefectDojo"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_defectdojo()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(REPORT_FILE)
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Starting DefectDojo report generation. First we create the file.")
+        }
+        let mut f = BufWriter::new(File::create(report_path(config, results))?);
+        if config.is_verbose() {
+            print_message("The report file has been created. Now it's time to fill it.")
+        }
+
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        let findings: Vec<Value> = results
+            .vulnerabilities()
+            .map(|v| finding(v, &date))
+            .collect();
+        ser::to_writer(&mut f, &json!({ "findings": findings }))?;
+        f.flush()?;
+
+        #[cfg(feature = "integrations")]
+        {
+            if let Some(api) = config.defectdojo_api() {
+                match Self::upload(config, api, results) {
+                    Ok(()) if !config.is_quiet() => {
+                        print_message(tr("DefectDojo report uploaded."));
+                    }
+                    Ok(()) => {}
+                    Err(e) => print_warning(format!(
+                        "there was an error uploading the DefectDojo report: {}",
+                        e
+                    )),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets the path of the DefectDojo report for the given results.
+fn report_path(config: &Config, results: &Results) -> PathBuf {
+    config
+        .results_folder()
+        .join(&results.app_package())
+        .join(REPORT_FILE)
+}
+
+/// Converts a vulnerability to a DefectDojo finding.
+fn finding(vulnerability: &Vulnerability, date: &str) -> Value {
+    let mut finding = json!({
+        "unique_id_from_tool": vulnerability.id(),
+        "title": vulnerability.name(),
+        "description": vulnerability.description(),
+        "severity": severity(vulnerability.get_criticality()),
+        "date": date,
+        "static_finding": true,
+        "dynamic_finding": false,
+    });
+
+    if let Some(file) = vulnerability.file() {
+        finding["file_path"] = json!(file);
+    }
+    if let Some(line) = vulnerability.start_line() {
+        finding["line"] = json!(line + 1);
+    }
+    if let Some(cwe) = vulnerability.cwe() {
+        finding["cwe"] = json!(cwe);
+    }
+    if let Some(rule_id) = vulnerability.rule_id() {
+        finding["vuln_id_from_tool"] = json!(rule_id);
+    }
+
+    finding
+}
+
+/// Gets the DefectDojo severity of the given criticality.
+fn severity(criticality: Criticality) -> &'static str {
+    match criticality {
+        Criticality::Warning => "Info",
+        Criticality::Low => "Low",
+        Criticality::Medium => "Medium",
+        Criticality::High => "High",
+        Criticality::Critical => "Critical",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::finding;
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the conversion of vulnerabilities to DefectDojo findings.
+    #[test]
+    fn it_finding() {
+        let vulnerability = Vulnerability::builder(Criticality::Warning, "Name", "Description")
+            .file("classes/A.java")
+            .lines(9, 10)
+            .rule_id("rule")
+            .cwe(200)
+            .build();
+        let dojo_finding = finding(&vulnerability, "2019-01-01");
+
+        assert_eq!(dojo_finding["unique_id_from_tool"], vulnerability.id());
+        assert_eq!(dojo_finding["title"], "Name");
+        assert_eq!(dojo_finding["severity"], "Info");
+        assert_eq!(dojo_finding["file_path"], "classes/A.java");
+        assert_eq!(dojo_finding["line"], 10);
+        assert_eq!(dojo_finding["cwe"], 200);
+        assert_eq!(dojo_finding["vuln_id_from_tool"], "rule");
+        assert_eq!(dojo_finding["date"], "2019-01-01");
+
+        let dojo_finding = finding(
+            &Vulnerability::builder(Criticality::Critical, "Name", "Description").build(),
+            "2019-01-01",
+        );
+        assert_eq!(dojo_finding["severity"], "Critical");
+        assert!(dojo_finding.get("file_path").is_none());
+    }
+}
@@ -15,9 +15,11 @@ use serde_json::{value::Value, Map};
 use crate::{
     config::Config,
     copy_folder, error,
+    i18n::tr,
+    print_message,
     results::{
         handlebars_helpers::{
-            all_code, all_lines, generate_menu, html_code, line_numbers, report_index,
+            all_code, all_lines, generate_menu, html_code, line_numbers, report_index, translate,
         },
         report::Generator,
         utils::html_escape,
@@ -58,6 +60,7 @@ impl Report {
         let _ = handlebars.register_helper("all_code", Box::new(all_code));
         let _ = handlebars.register_helper("all_lines", Box::new(all_lines));
         let _ = handlebars.register_helper("generate_menu", Box::new(generate_menu));
+        let _ = handlebars.register_helper("t", Box::new(translate));
         for dir_entry in fs::read_dir(template_path)? {
             let dir_entry = dir_entry?;
             if let Some(ext) = dir_entry.path().extension() {
@@ -237,13 +240,11 @@ impl Report {
 
         Ok(())
     }
-}
 
-impl Generator for Report {
-    #[allow(clippy::print_stdout)]
-    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+    /// Renders the HTML report of the given results, copying the template assets.
+    fn render(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
         if config.is_verbose() {
-            println!("Starting HTML report generation. First we create the file.")
+            print_message("Starting HTML report generation. First we create the file.")
         }
         let mut f = File::create(
             config
@@ -252,7 +253,7 @@ impl Generator for Report {
                 .join("index.html"),
         )?;
         if config.is_verbose() {
-            println!("The report file has been created. Now it's time to fill it.")
+            print_message("The report file has been created. Now it's time to fill it.")
         }
 
         f.write_all(self.handler.render("report", results)?.as_bytes())?;
@@ -288,6 +289,78 @@ impl Generator for Report {
     }
 }
 
+/// HTML report generator.
+///
+/// It removes the previous HTML report, if any, and renders a new one with the Handlebars
+/// templates of the configured template path.
+pub struct Html;
+
+impl Html {
+    /// Creates a new HTML report generator.
+    pub fn new() -> Self {
+        Html
+    }
+
+    /// Removes the files of a previous HTML report, keeping the rest of the reports.
+    fn clean(config: &Config, results: &Results) -> Result<(), Error> {
+        let path = config.results_folder().join(&results.app_package());
+        if !path.join("index.html").exists() {
+            return Ok(());
+        }
+        if config.is_verbose() {
+            print_message(tr("The application HTML results exist. But no more…"));
+        }
+
+        let mut generated = vec![path.join("index.html"), path.join("src")];
+        for entry in fs::read_dir(config.template_path())? {
+            let entry = entry?;
+            let is_asset = entry.file_type()?.is_dir()
+                || entry
+                    .path()
+                    .extension()
+                    .map_or(false, |extension| extension != "hbs");
+            if is_asset {
+                generated.push(path.join(entry.file_name()));
+            }
+        }
+
+        for generated in generated {
+            if generated.is_dir() {
+                fs::remove_dir_all(&generated)
+                    .context("there was an error when removing the HTML results")?;
+            } else if generated.exists() {
+                fs::remove_file(&generated)
+                    .context("there was an error when removing the HTML results")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Generator for Html {
+    fn name(&self) -> &str {
+        "HTML"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_html()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some("index.html")
+    }
+
+    fn keeps_existing(&self) -> bool {
+        true
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        Self::clean(config, results)?;
+        Report::from_path(config.template_path(), results.package_name())?.render(config, results)
+    }
+}
+
 /// Handlebars templates testing module.
 #[cfg(test)]
 mod test {
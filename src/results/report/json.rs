@@ -7,9 +7,13 @@ use serde_json::ser;
 
 use crate::{
     config::Config,
+    print_message,
     results::{report::Generator, Results},
 };
 
+/// Name of the JSON report file.
+pub const REPORT_FILE: &str = "results.json";
+
 /// JSON report generator.
 pub struct Json;
 
@@ -21,19 +25,34 @@ impl Json {
 }
 
 impl Generator for Json {
-    #[allow(clippy::print_stdout)]
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_json()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(REPORT_FILE)
+    }
+
+    fn keeps_existing(&self) -> bool {
+        true
+    }
+
     fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
         if config.is_verbose() {
-            println!("Starting JSON report generation. First we create the file.")
+            print_message("Starting JSON report generation. First we create the file.")
         }
         let mut f = BufWriter::new(File::create(
             config
                 .results_folder()
                 .join(&results.app_package())
-                .join("results.json"),
+                .join(REPORT_FILE),
         )?);
         if config.is_verbose() {
-            println!("The report file has been created. Now it's time to fill it.")
+            print_message("The report file has been created. Now it's time to fill it.")
         }
         ser::to_writer(&mut f, results)?;
 
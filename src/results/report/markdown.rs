@@ -0,0 +1,212 @@
+//! Markdown report generation module.
+//!
+//! The report contains a summary table of the vulnerabilities, followed by a section for each of
+//! them, so that it can be pasted directly into tickets, pull requests and wikis.
+
+use std::{
+    fmt::Write as FmtWrite,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use failure::Error;
+
+use crate::{
+    config::Config,
+    print_message,
+    results::{report::Generator, Results, Vulnerability},
+};
+
+/// Name of the Markdown report file.
+pub const REPORT_FILE: &str = "results.md";
+
+/// Markdown report generator.
+pub struct Markdown;
+
+impl Markdown {
+    /// Creates a new Markdown report generator.
+    pub fn new() -> Self {
+        Markdown
+    }
+}
+
+impl Generator for Markdown {
+    fn name(&self) -> &str {
+        "Markdown"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_markdown()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(REPORT_FILE)
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Starting Markdown report generation. First we create the file.")
+        }
+        let mut f = BufWriter::new(File::create(report_path(config, results))?);
+        if config.is_verbose() {
+            print_message("The report file has been created. Now it's time to fill it.")
+        }
+
+        let title = format!(
+            "{} ({}) {}",
+            results.app_label(),
+            results.app_package(),
+            results.app_version()
+        );
+        f.write_all(document(&title, results.vulnerabilities()).as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Gets the path of the Markdown report for the given results.
+fn report_path(config: &Config, results: &Results) -> PathBuf {
+    config
+        .results_folder()
+        .join(&results.app_package())
+        .join(REPORT_FILE)
+}
+
+/// Generates the Markdown document with the given title for the given vulnerabilities.
+fn document<'v, I>(title: &str, vulnerabilities: I) -> String
+where
+    I: Iterator<Item = &'v Vulnerability>,
+{
+    let vulnerabilities = vulnerabilities.collect::<Vec<_>>();
+    let mut document = String::new();
+    let _ = writeln!(document, "# {}\n", escape(title.trim()));
+
+    if vulnerabilities.is_empty() {
+        document.push_str("No vulnerabilities were found.\n");
+        return document;
+    }
+
+    document.push_str("| ID | Criticality | Name | Location |\n");
+    document.push_str("|----|-------------|------|----------|\n");
+    for vulnerability in &vulnerabilities {
+        let _ = writeln!(
+            document,
+            "| [{0}](#{1}) | {2} | {3} | {4} |",
+            vulnerability.id(),
+            vulnerability.id().to_lowercase(),
+            vulnerability.get_criticality(),
+            escape(vulnerability.name()),
+            escape(&location(vulnerability))
+        );
+    }
+
+    for vulnerability in &vulnerabilities {
+        let _ = write!(
+            document,
+            "\n<a id=\"{}\"></a>\n\n## {}\n\n- **ID:** {}\n- **Criticality:** {}\n",
+            vulnerability.id().to_lowercase(),
+            escape(vulnerability.name()),
+            vulnerability.id(),
+            vulnerability.get_criticality()
+        );
+        if let Some(rule_id) = vulnerability.rule_id() {
+            let _ = writeln!(document, "- **Rule:** `{}`", rule_id);
+        }
+        if let Some(category) = vulnerability.category() {
+            let _ = writeln!(document, "- **Category:** {}", escape(category));
+        }
+        if let Some(cwe) = vulnerability.cwe() {
+            let _ = writeln!(
+                document,
+                "- **CWE:** [CWE-{0}](https://cwe.mitre.org/data/definitions/{0}.html)",
+                cwe
+            );
+        }
+        let location = location(vulnerability);
+        if !location.is_empty() {
+            let _ = writeln!(document, "- **Location:** `{}`", location);
+        }
+        if let Some(class_location) = vulnerability.location() {
+            let _ = writeln!(document, "- **Class:** `{}`", class_location);
+        }
+        let _ = writeln!(document, "\n{}", vulnerability.description().trim());
+        if let Some(code) = vulnerability.code() {
+            let fence = if code.contains("```") { "~~~" } else { "```" };
+            let _ = writeln!(document, "\n{0}java\n{1}\n{0}", fence, code.trim_end());
+        }
+    }
+
+    document
+}
+
+/// Gets the location of the vulnerability, as `file:line`, with lines starting at 1.
+fn location(vulnerability: &Vulnerability) -> String {
+    match (vulnerability.file(), vulnerability.start_line()) {
+        (Some(file), Some(line)) => format!("{}:{}", file.display(), line + 1),
+        (Some(file), None) => file.display().to_string(),
+        (None, _) => String::new(),
+    }
+}
+
+/// Escapes the given text so that it can be used in a table cell or in a heading.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{document, escape};
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the escaping of the Markdown text.
+    #[test]
+    fn it_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a | b"), "a \\| b");
+        assert_eq!(escape("two\nlines"), "two lines");
+    }
+
+    /// Checks the generation of the Markdown document.
+    #[test]
+    fn it_document() {
+        let vulnerabilities = vec![
+            Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+                .file("classes/com/example/Hash.java")
+                .lines(9, 10)
+                .class("com.example.Hash")
+                .method("digest")
+                .rule_id("weak-algorithms")
+                .cwe(327)
+                .build(),
+            Vulnerability::builder(Criticality::Warning, "Large heap", "Needs a large heap.")
+                .build(),
+        ];
+        let id = vulnerabilities[0].id();
+        let markdown = document("Example (com.example) 1.0", vulnerabilities.iter());
+
+        assert!(markdown.starts_with("# Example (com.example) 1.0\n\n| ID |"));
+        assert!(markdown.contains(&format!(
+            "| [{}](#{}) | high | Weak Algorithms | classes/com/example/Hash.java:10 |\n",
+            id,
+            id.to_lowercase()
+        )));
+        assert!(markdown.contains(&format!(
+            "\n<a id=\"{}\"></a>\n\n## Weak Algorithms\n\n- **ID:** {}\n\
+             - **Criticality:** high\n",
+            id.to_lowercase(),
+            id
+        )));
+        assert!(markdown.contains("- **Rule:** `weak-algorithms`\n"));
+        assert!(markdown.contains("- **Location:** `classes/com/example/Hash.java:10`\n"));
+        assert!(markdown.contains("- **Class:** `com.example.Hash#digest`\n"));
+        assert!(markdown.contains("\nNeeds a large heap.\n"));
+
+        assert_eq!(
+            document("Empty", Vec::<Vulnerability>::new().iter()),
+            "# Empty\n\nNo vulnerabilities were found.\n"
+        );
+    }
+}
@@ -1,15 +1,86 @@
 //! Report generation module.
+//!
+//! Reports are generated by a registry of [`Generator`]s, run in the order they were registered
+//! after each analysis. The registry contains the built-in generators (HTML, JSON, DefectDojo,
+//! SARIF, CSV, Markdown and the CycloneDX SBOM), and library users can add their own reporters
+//! with [`register_generator()`].
 
+mod csv;
+mod cyclonedx;
+mod defectdojo;
 mod handlebars;
 mod json;
+mod markdown;
+mod sarif;
+
+use std::sync::{Mutex, MutexGuard};
 
 use failure::Error;
+use lazy_static::lazy_static;
 
-pub use self::{handlebars::Report as HandlebarsReport, json::Json};
+pub use self::{
+    csv::Csv,
+    cyclonedx::{CycloneDx, DependencyTrackConfig},
+    defectdojo::{DefectDojo, DefectDojoApi},
+    handlebars::Html,
+    json::Json,
+    markdown::Markdown,
+    sarif::Sarif,
+};
 use crate::{config::Config, results::Results};
 
+lazy_static! {
+    /// Registered report generators, in the order they are run.
+    static ref GENERATORS: Mutex<Vec<Box<dyn Generator>>> = Mutex::new(vec![
+        Box::new(Json::new()),
+        Box::new(DefectDojo::new()),
+        Box::new(Sarif::new()),
+        Box::new(Csv::new()),
+        Box::new(Markdown::new()),
+        Box::new(CycloneDx::new()),
+        Box::new(Html::new()),
+    ]);
+}
+
 /// Trait that represents a type that can generate a report.
-pub trait Generator {
+pub trait Generator: Send {
+    /// Gets the name of the report format, used in the messages for the user, such as `JSON`.
+    fn name(&self) -> &str;
+
+    /// Checks if the report has to be generated with the given configuration.
+    ///
+    /// Reports are generated after every analysis by default.
+    fn is_enabled(&self, _config: &Config) -> bool {
+        true
+    }
+
+    /// Gets the name of the main file of the report, in the results folder of the application.
+    ///
+    /// It is shown in the summary of the analysis, if it exists.
+    fn file_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Checks if an existing report can be kept, instead of generating it again, if the analysis
+    /// is not forced.
+    ///
+    /// Reports are always generated again by default.
+    fn keeps_existing(&self) -> bool {
+        false
+    }
+
     /// Generates an actual report.
     fn generate(&mut self, config: &Config, result: &Results) -> Result<(), Error>;
 }
+
+/// Registers the given report generator, that will run after the generators already registered.
+pub fn register_generator<G: Generator + 'static>(generator: G) {
+    generators().push(Box::new(generator));
+}
+
+/// Gets the registered report generators.
+pub(crate) fn generators() -> MutexGuard<'static, Vec<Box<dyn Generator>>> {
+    GENERATORS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
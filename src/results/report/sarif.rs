@@ -0,0 +1,302 @@
+//! SARIF report generation module.
+//!
+//! The report follows the [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/) format,
+//! so that it can be uploaded to GitHub Code Scanning and to any other SARIF-aware tool. File
+//! locations are relative to the distribution folder of the application, identified by the
+//! `%SRCROOT%` base URI.
+
+use std::{collections::BTreeMap, fs::File, io::BufWriter, path::PathBuf};
+
+use clap::crate_version;
+use failure::Error;
+use serde_json::{json, ser, Value};
+
+use crate::{
+    config::Config,
+    criticality::Criticality,
+    print_message,
+    results::{report::Generator, Results, Vulnerability},
+};
+
+/// Name of the SARIF report file.
+pub const REPORT_FILE: &str = "results.sarif";
+
+/// URL of the SARIF 2.1.0 JSON schema.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF report generator.
+#[derive(Default)]
+pub struct Sarif;
+
+impl Sarif {
+    /// Creates a new SARIF report generator.
+    pub fn new() -> Self {
+        Sarif
+    }
+}
+
+impl Generator for Sarif {
+    fn name(&self) -> &str {
+        "SARIF"
+    }
+
+    fn is_enabled(&self, config: &Config) -> bool {
+        config.has_to_generate_sarif()
+    }
+
+    fn file_name(&self) -> Option<&str> {
+        Some(REPORT_FILE)
+    }
+
+    fn generate(&mut self, config: &Config, results: &Results) -> Result<(), Error> {
+        if config.is_verbose() {
+            print_message("Starting SARIF report generation. First we create the file.")
+        }
+        let mut f = BufWriter::new(File::create(report_path(config, results))?);
+        if config.is_verbose() {
+            print_message("The report file has been created. Now it's time to fill it.")
+        }
+
+        ser::to_writer(&mut f, &log(results.vulnerabilities()))?;
+
+        Ok(())
+    }
+}
+
+/// Gets the path of the SARIF report for the given results.
+fn report_path(config: &Config, results: &Results) -> PathBuf {
+    config
+        .results_folder()
+        .join(&results.app_package())
+        .join(REPORT_FILE)
+}
+
+/// Generates the SARIF log of the given vulnerabilities.
+fn log<'v, I>(vulnerabilities: I) -> Value
+where
+    I: Iterator<Item = &'v Vulnerability>,
+{
+    let mut rules: BTreeMap<&str, (usize, &Vulnerability)> = BTreeMap::new();
+    let mut sarif_results = Vec::new();
+    for vulnerability in vulnerabilities {
+        let rule_id = vulnerability
+            .rule_id()
+            .unwrap_or_else(|| vulnerability.name());
+        let next_index = rules.len();
+        let rule_index = rules
+            .entry(rule_id)
+            .and_modify(|(_, rule)| {
+                if vulnerability.get_criticality() > rule.get_criticality() {
+                    *rule = vulnerability;
+                }
+            })
+            .or_insert((next_index, vulnerability))
+            .0;
+        sarif_results.push(result(vulnerability, rule_id, rule_index));
+    }
+
+    let mut rules = rules.into_iter().collect::<Vec<_>>();
+    rules.sort_by_key(|&(_, (index, _))| index);
+    let rules = rules
+        .into_iter()
+        .map(|(id, (_, vulnerability))| rule(id, vulnerability))
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "SUPER Android Analyzer",
+                    "informationUri": "https://superanalyzer.rocks",
+                    "version": crate_version!(),
+                    "rules": rules,
+                }
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": {
+                    "description": { "text": "Distribution folder of the application." }
+                }
+            },
+            "results": sarif_results,
+        }]
+    })
+}
+
+/// Converts a vulnerability to a SARIF reporting descriptor, describing its rule.
+///
+/// The vulnerability with the highest criticality of the rule is used.
+fn rule(id: &str, vulnerability: &Vulnerability) -> Value {
+    let mut tags = vec![String::from("security")];
+    if let Some(cwe) = vulnerability.cwe() {
+        tags.push(format!("external/cwe/cwe-{}", cwe));
+    }
+    tags.extend(
+        vulnerability
+            .masvs()
+            .iter()
+            .map(|masvs| format!("external/owasp-masvs/{}", masvs)),
+    );
+
+    let mut rule = json!({
+        "id": id,
+        "name": vulnerability.name(),
+        "shortDescription": { "text": vulnerability.name() },
+        "fullDescription": { "text": vulnerability.description() },
+        "defaultConfiguration": { "level": level(vulnerability.get_criticality()) },
+        "properties": {
+            "tags": tags,
+            "security-severity": vulnerability.cvss().map_or_else(
+                || security_severity(vulnerability.get_criticality()).to_owned(),
+                |cvss| format!("{:.1}", cvss.score()),
+            ),
+        },
+    });
+    if let Some(cwe) = vulnerability.cwe() {
+        rule["helpUri"] = json!(format!(
+            "https://cwe.mitre.org/data/definitions/{}.html",
+            cwe
+        ));
+    }
+
+    rule
+}
+
+/// Converts a vulnerability to a SARIF result.
+fn result(vulnerability: &Vulnerability, rule_id: &str, rule_index: usize) -> Value {
+    let mut result = json!({
+        "ruleId": rule_id,
+        "ruleIndex": rule_index,
+        "level": level(vulnerability.get_criticality()),
+        "message": { "text": vulnerability.description() },
+        "partialFingerprints": { "superFingerprint/v1": vulnerability.fingerprint() },
+        "properties": { "id": vulnerability.id() },
+    });
+
+    if let Some(file) = vulnerability.file() {
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": file.to_string_lossy().replace('\\', "/"),
+                    "uriBaseId": "%SRCROOT%",
+                }
+            }
+        });
+        if let Some(start_line) = vulnerability.start_line() {
+            let end_line = vulnerability.end_line().unwrap_or(start_line);
+            location["physicalLocation"]["region"] = json!({
+                "startLine": start_line + 1,
+                "endLine": end_line + 1,
+            });
+        }
+        if let Some(class) = vulnerability.class() {
+            let logical_location = match vulnerability.method() {
+                Some(method) => json!({
+                    "name": method,
+                    "fullyQualifiedName": format!("{}.{}", class, method),
+                    "kind": "function",
+                }),
+                None => json!({
+                    "name": class.rsplit('.').next().unwrap_or(class),
+                    "fullyQualifiedName": class,
+                    "kind": "type",
+                }),
+            };
+            location["logicalLocations"] = json!([logical_location]);
+        }
+        result["locations"] = json!([location]);
+    }
+
+    result
+}
+
+/// Gets the SARIF level of the given criticality.
+fn level(criticality: Criticality) -> &'static str {
+    match criticality {
+        Criticality::Warning | Criticality::Low => "note",
+        Criticality::Medium => "warning",
+        Criticality::High | Criticality::Critical => "error",
+    }
+}
+
+/// Gets the security severity of the given criticality, as used by GitHub Code Scanning.
+fn security_severity(criticality: Criticality) -> &'static str {
+    match criticality {
+        Criticality::Warning => "0.0",
+        Criticality::Low => "3.0",
+        Criticality::Medium => "5.5",
+        Criticality::High => "8.0",
+        Criticality::Critical => "9.5",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::log;
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the generation of SARIF logs.
+    #[test]
+    fn it_log() {
+        let vulnerabilities = vec![
+            Vulnerability::builder(Criticality::High, "Weak Algorithms", "Uses MD5.")
+                .file("classes/com/example/Hash.java")
+                .lines(9, 10)
+                .rule_id("weak-algorithms")
+                .cwe(327)
+                .masvs(vec!["MASVS-CRYPTO-1"])
+                .cvss(
+                    "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:L/A:N"
+                        .parse()
+                        .unwrap(),
+                )
+                .build(),
+            Vulnerability::builder(Criticality::Medium, "Weak Algorithms", "Uses SHA-1.")
+                .file("classes/com/example/Sign.java")
+                .lines(4, 4)
+                .class("com.example.Sign")
+                .method("sign")
+                .rule_id("weak-algorithms")
+                .cwe(327)
+                .build(),
+            Vulnerability::builder(Criticality::Warning, "Large heap", "Needs a large heap.")
+                .build(),
+        ];
+        let log = log(vulnerabilities.iter());
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "weak-algorithms");
+        assert_eq!(rules[0]["defaultConfiguration"]["level"], "error");
+        assert_eq!(rules[0]["properties"]["tags"][1], "external/cwe/cwe-327");
+        assert_eq!(
+            rules[0]["properties"]["tags"][2],
+            "external/owasp-masvs/MASVS-CRYPTO-1"
+        );
+        assert_eq!(rules[0]["properties"]["security-severity"], "8.2");
+        assert_eq!(rules[1]["id"], "Large heap");
+        assert_eq!(rules[1]["properties"]["security-severity"], "0.0");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1]["ruleIndex"], 0);
+        assert_eq!(results[1]["level"], "warning");
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "classes/com/example/Sign.java"
+        );
+        assert_eq!(location["region"]["startLine"], 5);
+        let logical_location = &results[1]["locations"][0]["logicalLocations"][0];
+        assert_eq!(
+            logical_location["fullyQualifiedName"],
+            "com.example.Sign.sign"
+        );
+        assert_eq!(logical_location["kind"], "function");
+        assert_eq!(results[2]["ruleIndex"], 1);
+        assert!(results[2].get("locations").is_none());
+    }
+}
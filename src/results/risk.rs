@@ -0,0 +1,257 @@
+//! Risk grade module.
+//!
+//! Every analysis gets an overall risk score, from 0 to 100, and its grade, from A to F, so that
+//! applications can be compared at a glance. The score starts at 100, and every risk factor
+//! subtracts its weight from it:
+//!
+//! * Every vulnerability, depending on its criticality.
+//! * Every dangerous permission requested by the application, the ones with at least medium
+//!   criticality in the permission list of the configuration.
+//! * Every API level that the target SDK of the application is below the latest target SDK.
+//! * Every issue of the certificate, such as a debug or expired certificate.
+//!
+//! The weights can be changed in the `[risk]` section of the configuration:
+//!
+//! ```toml
+//! [risk]
+//! critical = 20
+//! high = 8
+//! medium = 3
+//! low = 1
+//! warning = 0
+//! dangerous_permission = 1
+//! target_sdk_level = 2
+//! latest_target_sdk = 34
+//! certificate_issue = 15
+//! ```
+
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::criticality::Criticality;
+
+/// Weights of the risk factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RiskWeights {
+    /// Weight of each critical vulnerability.
+    critical: u32,
+    /// Weight of each high criticality vulnerability.
+    high: u32,
+    /// Weight of each medium criticality vulnerability.
+    medium: u32,
+    /// Weight of each low criticality vulnerability.
+    low: u32,
+    /// Weight of each warning.
+    warning: u32,
+    /// Weight of each dangerous permission requested by the application.
+    dangerous_permission: u32,
+    /// Weight of each API level that the target SDK is below the latest target SDK.
+    target_sdk_level: u32,
+    /// Latest target SDK, that does not subtract any points.
+    latest_target_sdk: u32,
+    /// Weight of each issue of the certificate.
+    certificate_issue: u32,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            critical: 20,
+            high: 8,
+            medium: 3,
+            low: 1,
+            warning: 0,
+            dangerous_permission: 1,
+            target_sdk_level: 2,
+            latest_target_sdk: 34,
+            certificate_issue: 15,
+        }
+    }
+}
+
+impl RiskWeights {
+    /// Returns the weight of each vulnerability with the given criticality.
+    pub fn criticality(&self, criticality: Criticality) -> u32 {
+        match criticality {
+            Criticality::Warning => self.warning,
+            Criticality::Low => self.low,
+            Criticality::Medium => self.medium,
+            Criticality::High => self.high,
+            Criticality::Critical => self.critical,
+        }
+    }
+
+    /// Returns the weight of each dangerous permission requested by the application.
+    pub fn dangerous_permission(&self) -> u32 {
+        self.dangerous_permission
+    }
+
+    /// Returns the weight of each API level that the target SDK is below the latest target SDK.
+    pub fn target_sdk_level(&self) -> u32 {
+        self.target_sdk_level
+    }
+
+    /// Returns the latest target SDK, that does not subtract any points.
+    pub fn latest_target_sdk(&self) -> u32 {
+        self.latest_target_sdk
+    }
+
+    /// Returns the weight of each issue of the certificate.
+    pub fn certificate_issue(&self) -> u32 {
+        self.certificate_issue
+    }
+}
+
+/// Risk factors of an application.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RiskFactors {
+    /// Number of vulnerabilities of each criticality, from warnings to critical vulnerabilities.
+    pub(crate) vulnerabilities: [usize; 5],
+    /// Number of dangerous permissions requested by the application.
+    pub(crate) dangerous_permissions: usize,
+    /// Target SDK of the application.
+    pub(crate) target_sdk: u32,
+    /// Number of issues of the certificate.
+    pub(crate) certificate_issues: usize,
+}
+
+/// Grade of the risk of an application, from A (lowest risk) to F (highest risk).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    /// Score of at least 90.
+    A,
+    /// Score of at least 80.
+    B,
+    /// Score of at least 65.
+    C,
+    /// Score of at least 50.
+    D,
+    /// Score below 50.
+    F,
+}
+
+impl Grade {
+    /// Gets the grade of the given score.
+    fn from_score(score: u32) -> Self {
+        if score >= 90 {
+            Grade::A
+        } else if score >= 80 {
+            Grade::B
+        } else if score >= 65 {
+            Grade::C
+        } else if score >= 50 {
+            Grade::D
+        } else {
+            Grade::F
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    #[allow(clippy::use_debug)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Overall risk of an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Risk {
+    /// Score, from 0 (highest risk) to 100 (lowest risk).
+    score: u32,
+    /// Grade of the score.
+    grade: Grade,
+}
+
+impl Risk {
+    /// Computes the risk of the given factors with the given weights.
+    pub(crate) fn compute(weights: &RiskWeights, factors: &RiskFactors) -> Self {
+        let criticalities = [
+            Criticality::Warning,
+            Criticality::Low,
+            Criticality::Medium,
+            Criticality::High,
+            Criticality::Critical,
+        ];
+        let penalty = criticalities
+            .iter()
+            .zip(&factors.vulnerabilities)
+            .map(|(&criticality, &count)| weights.criticality(criticality) as usize * count)
+            .sum::<usize>()
+            + weights.dangerous_permission as usize * factors.dangerous_permissions
+            + weights.target_sdk_level as usize
+                * weights.latest_target_sdk.saturating_sub(factors.target_sdk) as usize
+            + weights.certificate_issue as usize * factors.certificate_issues;
+
+        let score = 100_usize.saturating_sub(penalty) as u32;
+        Self {
+            score,
+            grade: Grade::from_score(score),
+        }
+    }
+
+    /// Returns the score, from 0 (highest risk) to 100 (lowest risk).
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Returns the grade of the score.
+    pub fn grade(&self) -> Grade {
+        self.grade
+    }
+}
+
+impl Serialize for Risk {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("Risk", 2)?;
+        ser_struct.serialize_field("score", &self.score)?;
+        ser_struct.serialize_field("grade", &self.grade.to_string())?;
+        ser_struct.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grade, Risk, RiskFactors, RiskWeights};
+
+    /// Checks the computation of the risk score and grade.
+    #[test]
+    fn it_compute() {
+        let weights = RiskWeights::default();
+        let clean = RiskFactors {
+            target_sdk: 34,
+            ..RiskFactors::default()
+        };
+        let risk = Risk::compute(&weights, &clean);
+        assert_eq!(risk.score(), 100);
+        assert_eq!(risk.grade(), Grade::A);
+
+        let risky = RiskFactors {
+            vulnerabilities: [4, 2, 1, 1, 0],
+            dangerous_permissions: 3,
+            target_sdk: 30,
+            certificate_issues: 0,
+        };
+        // 2 low + 3 medium + 8 high + 3 permissions + 4 API levels.
+        let risk = Risk::compute(&weights, &risky);
+        assert_eq!(risk.score(), 100 - 2 - 3 - 8 - 3 - 8);
+        assert_eq!(risk.grade(), Grade::C);
+
+        let insecure = RiskFactors {
+            vulnerabilities: [0, 0, 0, 0, 3],
+            certificate_issues: 1,
+            ..clean
+        };
+        let risk = Risk::compute(&weights, &insecure);
+        assert_eq!(risk.score(), 25);
+        assert_eq!(risk.grade(), Grade::F);
+
+        let weights: RiskWeights = toml::from_str("critical = 5").unwrap();
+        assert_eq!(Risk::compute(&weights, &insecure).score(), 70);
+    }
+}
@@ -0,0 +1,268 @@
+//! Corpus similarity module.
+//!
+//! Every analysis leaves a JSON report in the results folder, that contains the fingerprints, the
+//! certificate and the bundled libraries of the application. New applications are compared with
+//! the reports of the previously analyzed applications, so that repackaged or related
+//! applications can be spotted, such as a repackaged version of a known application signed by a
+//! different certificate.
+//!
+//! Applications are compared using the ssdeep fuzzy hashes of their packages and of their DEX
+//! files and native libraries. Only the JSON reports are used, so the JSON report must be
+//! generated for the applications to be part of the corpus.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use failure::{Error, ResultExt};
+use serde_json::Value;
+
+use crate::{
+    print_warning,
+    results::{ssdeep, Results},
+    Config,
+};
+
+/// Minimum similarity of the applications to be reported.
+const MIN_SIMILARITY: u32 = 50;
+
+/// Maximum number of similar applications to report.
+const MAX_MATCHES: usize = 5;
+
+/// Previously analyzed application similar to the current one.
+#[derive(Debug, Serialize)]
+pub(crate) struct SimilarApplication {
+    /// Package of the application.
+    package: String,
+    /// Version of the application.
+    version: String,
+    /// Similarity of the applications, from 0 to 100.
+    similarity: u32,
+    /// Number of libraries bundled in both applications.
+    shared_libraries: usize,
+    /// Whether both applications are signed by the same certificate, if known.
+    same_certificate: Option<bool>,
+    /// Description of the match.
+    description: String,
+}
+
+/// Finds the previously analyzed applications that are similar to the given results.
+pub(crate) fn find_similar_applications(config: &Config, results: &mut Results) {
+    let current = match serde_json::to_value(&*results)
+        .ok()
+        .and_then(|report| Sample::from_report(&report))
+    {
+        Some(sample) => sample,
+        None => return,
+    };
+    let corpus = match load_corpus(config.results_folder()) {
+        Ok(corpus) => corpus,
+        Err(e) => {
+            print_warning(format!(
+                "could not load the previous analyses to find similar applications: {}",
+                e
+            ));
+            return;
+        }
+    };
+
+    let mut matches = corpus
+        .iter()
+        .filter(|sample| sample.sha256 != current.sha256)
+        .filter_map(|sample| current.compare(sample))
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| {
+        b.similarity
+            .cmp(&a.similarity)
+            .then_with(|| a.package.cmp(&b.package))
+    });
+    matches.truncate(MAX_MATCHES);
+
+    results.set_similar_applications(matches);
+}
+
+/// Loads the JSON reports of the previous analyses in the results folder.
+fn load_corpus<P: AsRef<Path>>(results_folder: P) -> Result<Vec<Sample>, Error> {
+    let mut corpus = Vec::new();
+    if !results_folder.as_ref().exists() {
+        return Ok(corpus);
+    }
+
+    for entry in fs::read_dir(results_folder).context("could not read the results folder")? {
+        let report_path = entry?.path().join("results.json");
+        if !report_path.is_file() {
+            continue;
+        }
+        let report = fs::read_to_string(&report_path)
+            .map_err(Error::from)
+            .and_then(|report| Ok(serde_json::from_str::<Value>(&report)?));
+        match report {
+            Ok(report) => corpus.extend(Sample::from_report(&report)),
+            Err(e) => print_warning(format!(
+                "could not read the report `{}`: {}",
+                report_path.display(),
+                e
+            )),
+        }
+    }
+
+    Ok(corpus)
+}
+
+/// Analyzed application, as stored in its JSON report.
+#[derive(Debug)]
+struct Sample {
+    /// Package of the application.
+    package: String,
+    /// Version of the application.
+    version: String,
+    /// SHA-256 hash of the application package.
+    sha256: String,
+    /// ssdeep hash of the application package.
+    ssdeep: String,
+    /// ssdeep hashes of the code files, by path.
+    files: BTreeMap<String, String>,
+    /// Bundled libraries, as `group:name`.
+    libraries: BTreeSet<String>,
+    /// Certificate of the application, if known.
+    certificate: Option<String>,
+}
+
+impl Sample {
+    /// Gets the sample from the given JSON report.
+    fn from_report(report: &Value) -> Option<Self> {
+        let fingerprint = report.get("app_fingerprint")?;
+        let string = |value: &Value, key: &str| value.get(key)?.as_str().map(str::to_owned);
+
+        let files = fingerprint
+            .get("files")
+            .and_then(Value::as_array)
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|file| Some((string(file, "path")?, string(file, "ssdeep")?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let libraries = report
+            .get("libraries")
+            .and_then(Value::as_array)
+            .map(|libraries| {
+                libraries
+                    .iter()
+                    .filter_map(|library| {
+                        Some(format!(
+                            "{}:{}",
+                            string(library, "group")?,
+                            string(library, "name")?
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            package: string(report, "app_package")?,
+            version: string(report, "app_version").unwrap_or_default(),
+            sha256: string(fingerprint, "sha256")?,
+            ssdeep: string(fingerprint, "ssdeep")?,
+            files,
+            libraries,
+            certificate: string(report, "certificate").filter(|c| !c.is_empty()),
+        })
+    }
+
+    /// Compares this sample with the given one, returning the match if they are similar.
+    fn compare(&self, other: &Self) -> Option<SimilarApplication> {
+        let similarity = self
+            .files
+            .iter()
+            .filter_map(|(path, hash)| {
+                other
+                    .files
+                    .get(path)
+                    .map(|other_hash| ssdeep::compare(hash, other_hash))
+            })
+            .chain(Some(ssdeep::compare(&self.ssdeep, &other.ssdeep)))
+            .max()
+            .unwrap_or(0);
+        if similarity < MIN_SIMILARITY {
+            return None;
+        }
+
+        let same_certificate = match (&self.certificate, &other.certificate) {
+            (Some(certificate), Some(other_certificate)) => Some(certificate == other_certificate),
+            _ => None,
+        };
+        let description = format!(
+            "{}% similar to {}{}{}",
+            similarity,
+            other.package,
+            if other.version.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", other.version)
+            },
+            match same_certificate {
+                Some(true) => ", signed by the same certificate",
+                Some(false) => ", signed by a different certificate",
+                None => "",
+            }
+        );
+
+        Some(SimilarApplication {
+            package: other.package.clone(),
+            version: other.version.clone(),
+            similarity,
+            shared_libraries: self.libraries.intersection(&other.libraries).count(),
+            same_certificate,
+            description,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Sample;
+
+    /// Checks the comparison of samples.
+    #[test]
+    fn it_compare() {
+        let report = |package: &str, sha256: &str, dex: &str, certificate: &str| {
+            json!({
+                "app_package": package,
+                "app_version": "1.2",
+                "app_fingerprint": {
+                    "sha256": sha256,
+                    "ssdeep": dex,
+                    "files": [{ "path": "classes.dex", "ssdeep": dex }],
+                },
+                "certificate": certificate,
+                "libraries": [{ "group": "com.squareup.okhttp3", "name": "okhttp" }],
+            })
+        };
+        let dex = "24:IxzxpxlDlZNn3hv3Zhbp/17DlX759hplbFS9PdvUbtxhZh/:IxzxpPFt3Z3/ZpdkrcxxhZx";
+        let repacked_dex =
+            "24:IxzxpxlDlZNn3hv3Zhbp/17DlX759hpNbFS9PdvUbtxhZh/:IxzxpPFt3Z3/ZpVkrcxxhZx";
+
+        let original = Sample::from_report(&report("com.foo.bar", "a", dex, "CN=Foo")).unwrap();
+        let repacked =
+            Sample::from_report(&report("com.foo.bar", "b", repacked_dex, "CN=Evil")).unwrap();
+        let unrelated =
+            Sample::from_report(&report("com.other", "c", "3:abcdefgh:abcd", "CN=Foo")).unwrap();
+
+        let similar = repacked.compare(&original).unwrap();
+        assert_eq!(similar.similarity, 99);
+        assert_eq!(similar.shared_libraries, 1);
+        assert_eq!(similar.same_certificate, Some(false));
+        assert_eq!(
+            similar.description,
+            "99% similar to com.foo.bar 1.2, signed by a different certificate"
+        );
+        assert!(repacked.compare(&unrelated).is_none());
+    }
+}
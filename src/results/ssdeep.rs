@@ -0,0 +1,261 @@
+//! Fuzzy hashing module.
+//!
+//! Implements the context triggered piecewise hashing of
+//! [ssdeep](https://ssdeep-project.github.io/), so that similar applications, or similar DEX
+//! files, get similar hashes. Hashes have the `block_size:hash:double_block_size_hash` format
+//! used by ssdeep, and can be clustered with it.
+
+/// Size of the rolling hash window.
+const ROLLING_WINDOW: u32 = 7;
+/// Minimum block size.
+const MIN_BLOCK_SIZE: u32 = 3;
+/// Maximum length of the hash for the block size.
+const SPAMSUM_LENGTH: usize = 64;
+/// Prime of the FNV based piecewise hash.
+const HASH_PRIME: u32 = 0x0100_0193;
+/// Initial value of the FNV based piecewise hash.
+const HASH_INIT: u32 = 0x2802_1967;
+/// Characters of the hashes.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Computes the ssdeep hash of the given data.
+pub(crate) fn hash(data: &[u8]) -> String {
+    let mut block_size = MIN_BLOCK_SIZE;
+    while (block_size as usize) * SPAMSUM_LENGTH < data.len() {
+        block_size *= 2;
+    }
+
+    loop {
+        let (first, second) = piecewise_hash(data, block_size);
+        if block_size > MIN_BLOCK_SIZE && first.len() < SPAMSUM_LENGTH / 2 {
+            block_size /= 2;
+        } else {
+            return format!("{}:{}:{}", block_size, first, second);
+        }
+    }
+}
+
+/// Compares two ssdeep hashes, returning their similarity, from 0 to 100.
+///
+/// Only hashes with the same block size, or with one block size double than the other, can be
+/// compared. Otherwise, their similarity is 0.
+pub(crate) fn compare(first: &str, second: &str) -> u32 {
+    let (first_size, first_hash, first_double) = match parse(first) {
+        Some(parsed) => parsed,
+        None => return 0,
+    };
+    let (second_size, second_hash, second_double) = match parse(second) {
+        Some(parsed) => parsed,
+        None => return 0,
+    };
+
+    if first_size == second_size && first_hash == second_hash {
+        100
+    } else if first_size == second_size {
+        score(&first_hash, &second_hash, first_size).max(score(
+            &first_double,
+            &second_double,
+            first_size * 2,
+        ))
+    } else if first_size == second_size * 2 {
+        score(&first_hash, &second_double, first_size)
+    } else if second_size == first_size * 2 {
+        score(&first_double, &second_hash, second_size)
+    } else {
+        0
+    }
+}
+
+/// Parses an ssdeep hash, removing the sequences of more than three repeated characters, that
+/// do not carry information.
+fn parse(hash: &str) -> Option<(u32, Vec<u8>, Vec<u8>)> {
+    let mut parts = hash.trim().splitn(3, ':');
+    let block_size = parts.next()?.parse().ok()?;
+    let hash = eliminate_sequences(parts.next()?.as_bytes());
+    let double = eliminate_sequences(parts.next()?.as_bytes());
+    Some((block_size, hash, double))
+}
+
+/// Removes the sequences of more than three repeated characters.
+fn eliminate_sequences(hash: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(hash.len());
+    for (i, &c) in hash.iter().enumerate() {
+        if i < 3 || c != hash[i - 1] || c != hash[i - 2] || c != hash[i - 3] {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Scores the similarity of two piecewise hashes with the given block size.
+fn score(first: &[u8], second: &[u8], block_size: u32) -> u32 {
+    if first.len() > SPAMSUM_LENGTH
+        || second.len() > SPAMSUM_LENGTH
+        || !has_common_substring(first, second)
+    {
+        return 0;
+    }
+
+    let total_len = (first.len() + second.len()) as u32;
+    let distance = edit_distance(first, second) * SPAMSUM_LENGTH as u32 / total_len;
+    let distance = 100 * distance / SPAMSUM_LENGTH as u32;
+    if distance >= 100 {
+        return 0;
+    }
+    let score = 100 - distance;
+
+    // Small block sizes cannot give high similarities with short hashes.
+    if block_size >= (99 + ROLLING_WINDOW) / ROLLING_WINDOW * MIN_BLOCK_SIZE {
+        score
+    } else {
+        score.min(block_size / MIN_BLOCK_SIZE * first.len().min(second.len()) as u32)
+    }
+}
+
+/// Checks if the two hashes have a common substring of the size of the rolling window.
+fn has_common_substring(first: &[u8], second: &[u8]) -> bool {
+    let window = ROLLING_WINDOW as usize;
+    first.windows(window).any(|substring| {
+        second
+            .windows(window)
+            .any(|other_substring| substring == other_substring)
+    })
+}
+
+/// Computes the edit distance of two hashes, where insertions and deletions cost 1 and
+/// substitutions cost 2.
+fn edit_distance(first: &[u8], second: &[u8]) -> u32 {
+    let mut previous = (0..=second.len() as u32).collect::<Vec<_>>();
+    let mut current = vec![0; second.len() + 1];
+    for (i, &a) in first.iter().enumerate() {
+        current[0] = i as u32 + 1;
+        for (j, &b) in second.iter().enumerate() {
+            let substitution = previous[j] + if a == b { 0 } else { 2 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous.copy_from_slice(&current);
+    }
+    previous[second.len()]
+}
+
+/// Computes the piecewise hashes of the data for the given block size and its double.
+///
+/// A character of the piecewise hash is added each time the rolling hash triggers, until the
+/// hash is full. Then, the last character accumulates the rest of the data.
+fn piecewise_hash(data: &[u8], block_size: u32) -> (String, String) {
+    let mut rolling = RollingHash::default();
+    let (mut first, mut second) = (String::new(), String::new());
+    let (mut first_hash, mut second_hash) = (HASH_INIT, HASH_INIT);
+    let mut roll = 0;
+
+    for &byte in data {
+        first_hash = first_hash.wrapping_mul(HASH_PRIME) ^ u32::from(byte);
+        second_hash = second_hash.wrapping_mul(HASH_PRIME) ^ u32::from(byte);
+        roll = rolling.update(byte);
+
+        if roll % block_size == block_size - 1 && first.len() < SPAMSUM_LENGTH - 1 {
+            first.push(base64(first_hash));
+            first_hash = HASH_INIT;
+        }
+        if roll % (block_size * 2) == block_size * 2 - 1 && second.len() < SPAMSUM_LENGTH / 2 - 1 {
+            second.push(base64(second_hash));
+            second_hash = HASH_INIT;
+        }
+    }
+
+    if roll != 0 {
+        first.push(base64(first_hash));
+        second.push(base64(second_hash));
+    }
+
+    (first, second)
+}
+
+/// Gets the hash character for the given piecewise hash.
+fn base64(hash: u32) -> char {
+    char::from(BASE64[(hash % 64) as usize])
+}
+
+/// Rolling hash over the last bytes of the data.
+#[derive(Debug, Default)]
+struct RollingHash {
+    /// Last bytes of the data.
+    window: [u8; ROLLING_WINDOW as usize],
+    /// Position of the next byte in the window.
+    n: usize,
+    /// Sum of the bytes in the window.
+    h1: u32,
+    /// Sum of the bytes in the window, weighted by their position.
+    h2: u32,
+    /// Shift/XOR hash of the last bytes.
+    h3: u32,
+}
+
+impl RollingHash {
+    /// Adds a byte to the rolling hash, returning the new hash.
+    fn update(&mut self, byte: u8) -> u32 {
+        let value = u32::from(byte);
+        let position = self.n % ROLLING_WINDOW as usize;
+        self.h2 = self
+            .h2
+            .wrapping_sub(self.h1)
+            .wrapping_add(ROLLING_WINDOW * value);
+        self.h1 = self
+            .h1
+            .wrapping_add(value)
+            .wrapping_sub(u32::from(self.window[position]));
+        self.window[position] = byte;
+        self.n += 1;
+        self.h3 = (self.h3 << 5) ^ value;
+
+        self.h1.wrapping_add(self.h2).wrapping_add(self.h3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, hash};
+
+    /// Checks the format of the fuzzy hashes.
+    #[test]
+    fn it_hash() {
+        assert_eq!(hash(b""), "3::");
+        assert_eq!(
+            hash(b"Also called fuzzy hashes, Ctph can match inputs that have homologies."),
+            "3:AXGBicFlgVNhBGcL6wCrFQEv:AXGHsNhxLsr2C"
+        );
+
+        let data = (0..10_000_u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let fuzzy = hash(&data);
+        let parts = fuzzy.split(':').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+        let block_size: usize = parts[0].parse().unwrap();
+        assert_eq!(block_size % 3, 0);
+        assert!(parts[1].len() <= 64 && (parts[1].len() >= 32 || block_size == 3));
+        assert!(parts[2].len() <= 32);
+        assert_eq!(hash(&data), fuzzy);
+    }
+
+    /// Checks the comparison of fuzzy hashes.
+    #[test]
+    fn it_compare() {
+        let data = (0..20_000_u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let mut modified = data.clone();
+        for byte in &mut modified[10_000..10_100] {
+            *byte = 0;
+        }
+        let other = (0..20_000_u32)
+            .map(|i| (i.wrapping_mul(40_503) >> 7) as u8)
+            .collect::<Vec<_>>();
+
+        assert_eq!(compare(&hash(&data), &hash(&data)), 100);
+        assert!(compare(&hash(&data), &hash(&modified)) > 50);
+        assert_eq!(compare(&hash(&data), &hash(&other)), 0);
+        assert_eq!(compare("3:abc:def", "96:abc:def"), 0);
+        assert_eq!(compare("invalid", "3::"), 0);
+    }
+}
@@ -0,0 +1,250 @@
+//! Analysis stages module.
+//!
+//! The analysis of an application goes through several stages, such as the decompilation or the
+//! manifest analysis, and some of them can fail without stopping the rest. The status of each
+//! stage is recorded in the results, so that a degraded report can be told apart from a clean
+//! one. Failed stages include the machine-readable code of their error:
+//!
+//! ```json
+//! "stages": [
+//!     { "name": "decompression", "status": "ok" },
+//!     {
+//!         "name": "dex_to_jar",
+//!         "status": "failed",
+//!         "reason": "dex2jar failed: exited with code 1",
+//!         "code": "tool_failed"
+//!     },
+//!     { "name": "decompilation", "status": "skipped", "reason": "there is no JAR file" }
+//! ]
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use failure::Error;
+
+use crate::error;
+
+/// Stage of the analysis, with its status.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stage {
+    /// Name of the stage.
+    name: String,
+    /// Status of the stage.
+    #[serde(flatten)]
+    status: StageStatus,
+}
+
+impl Stage {
+    /// Creates a new stage with the given status.
+    pub fn new<N: Into<String>>(name: N, status: StageStatus) -> Self {
+        Self {
+            name: name.into(),
+            status,
+        }
+    }
+
+    /// Returns the name of the stage.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the status of the stage.
+    pub fn status(&self) -> &StageStatus {
+        &self.status
+    }
+}
+
+/// Status of a stage of the analysis.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum StageStatus {
+    /// The stage finished successfully.
+    Ok,
+    /// The stage did not run, for the given reason.
+    Skipped {
+        /// Reason why the stage did not run.
+        reason: String,
+    },
+    /// The stage failed, for the given reason.
+    Failed {
+        /// Reason of the failure.
+        reason: String,
+        /// Machine-readable code of the error.
+        code: &'static str,
+    },
+}
+
+impl StageStatus {
+    /// Creates the status of a stage that did not run for the given reason.
+    pub fn skipped<R: Into<String>>(reason: R) -> Self {
+        StageStatus::Skipped {
+            reason: reason.into(),
+        }
+    }
+
+    /// Creates the status of a stage that failed with the given error.
+    pub fn failed<E: Into<Error>>(error: E) -> Self {
+        let error = error.into();
+        StageStatus::Failed {
+            reason: error.to_string(),
+            code: error::code(&error),
+        }
+    }
+
+    /// Returns whether the stage finished successfully.
+    pub fn is_ok(&self) -> bool {
+        *self == StageStatus::Ok
+    }
+}
+
+/// DEX files of the application, with the ones that could not be converted or decompiled.
+///
+/// ```json
+/// "dex_files": { "total": 5, "failed": { "classes3.dex": "dex2jar exited with code 1" } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DexFiles {
+    /// Number of DEX files of the application.
+    total: usize,
+    /// DEX files that could not be processed, with the reason.
+    failed: BTreeMap<String, String>,
+}
+
+impl DexFiles {
+    /// Creates the DEX files of an application with the given number of them.
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            failed: BTreeMap::new(),
+        }
+    }
+
+    /// Records that the given DEX file could not be processed, for the given reason.
+    ///
+    /// Only the first failure of each DEX file is kept.
+    pub fn add_failure<N: Into<String>, E: Display>(&mut self, dex: N, error: E) {
+        let _ = self
+            .failed
+            .entry(dex.into())
+            .or_insert_with(|| error.to_string());
+    }
+
+    /// Returns the number of DEX files of the application.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of DEX files that were processed successfully.
+    pub fn processed(&self) -> usize {
+        self.total.saturating_sub(self.failed.len())
+    }
+
+    /// Returns the DEX files that could not be processed, with the reason.
+    pub fn failed(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.failed.iter()
+    }
+}
+
+/// Code rule that exceeded its time budget in the code analysis.
+///
+/// ```json
+/// "slow_rules": [ { "rule": "hardcoded-password", "seconds": 72.4, "disabled": true } ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlowRule {
+    /// Identifier of the rule.
+    rule: String,
+    /// Evaluation time of the rule, in seconds.
+    seconds: f64,
+    /// Whether the rule stopped being checked once it exceeded its budget.
+    disabled: bool,
+}
+
+impl SlowRule {
+    /// Creates a rule that took the given time, in seconds.
+    pub fn new<R: Into<String>>(rule: R, seconds: f64, disabled: bool) -> Self {
+        Self {
+            rule: rule.into(),
+            seconds,
+            disabled,
+        }
+    }
+
+    /// Returns the identifier of the rule.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Returns the evaluation time of the rule, in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    /// Returns whether the rule stopped being checked once it exceeded its budget, so its
+    /// findings might be incomplete.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{DexFiles, Stage, StageStatus};
+    use crate::error::Kind;
+
+    /// Checks the serialization of the stages.
+    #[test]
+    fn it_serialize() {
+        let stages = vec![
+            Stage::new("decompression", StageStatus::Ok),
+            Stage::new(
+                "dex_to_jar",
+                StageStatus::failed(Kind::ToolFailed {
+                    tool: "dex2jar".to_owned(),
+                    stderr: "exited with code 1".to_owned(),
+                }),
+            ),
+            Stage::new(
+                "decompilation",
+                StageStatus::skipped("there is no JAR file"),
+            ),
+        ];
+
+        assert_eq!(
+            serde_json::to_value(&stages).unwrap(),
+            json!([
+                { "name": "decompression", "status": "ok" },
+                {
+                    "name": "dex_to_jar",
+                    "status": "failed",
+                    "reason": "dex2jar failed: exited with code 1",
+                    "code": "tool_failed",
+                },
+                {
+                    "name": "decompilation",
+                    "status": "skipped",
+                    "reason": "there is no JAR file",
+                },
+            ])
+        );
+        assert!(stages[0].status().is_ok());
+        assert!(!stages[1].status().is_ok());
+    }
+
+    /// Checks the recording of the failed DEX files.
+    #[test]
+    fn it_dex_files() {
+        let mut dex_files = DexFiles::new(3);
+        dex_files.add_failure("classes3.dex", "dex2jar exited with code 1");
+        dex_files.add_failure("classes3.dex", "no JAR file");
+
+        assert_eq!(dex_files.total(), 3);
+        assert_eq!(dex_files.processed(), 2);
+        assert_eq!(
+            serde_json::to_value(&dex_files).unwrap(),
+            json!({ "total": 3, "failed": { "classes3.dex": "dex2jar exited with code 1" } })
+        );
+    }
+}
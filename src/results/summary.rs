@@ -0,0 +1,166 @@
+//! End-of-run summary module.
+//!
+//! After the analysis of each package, a compact summary is printed with the number of
+//! vulnerabilities by criticality, the rules with most findings and the paths of the generated
+//! reports, so that it is not necessary to open the reports to know what happened.
+
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+use colored::Colorize;
+
+use crate::{
+    criticality::Criticality,
+    i18n::{tr, tr_args},
+    results::{report::generators, Results},
+    Config,
+};
+
+/// Maximum number of rules shown in the summary.
+const TOP_RULES: usize = 5;
+
+/// Summary of the results of an analysis.
+#[derive(Debug)]
+pub(crate) struct Summary {
+    /// Package of the application.
+    package: String,
+    /// Number of vulnerabilities by criticality, from most to least critical.
+    counts: Vec<(Criticality, usize)>,
+    /// Rules with most findings, along with their number of findings.
+    top_rules: Vec<(String, usize)>,
+    /// Generated reports, with their name.
+    reports: Vec<(String, PathBuf)>,
+}
+
+impl Summary {
+    /// Creates the summary of the given results.
+    pub(crate) fn new(config: &Config, results: &Results) -> Self {
+        let mut counts = BTreeMap::new();
+        let mut rules = BTreeMap::new();
+        for vulnerability in results.vulnerabilities() {
+            *counts.entry(vulnerability.get_criticality()).or_insert(0) += 1;
+            *rules.entry(vulnerability.name()).or_insert(0) += 1;
+        }
+
+        let counts = [
+            Criticality::Critical,
+            Criticality::High,
+            Criticality::Medium,
+            Criticality::Low,
+            Criticality::Warning,
+        ]
+        .iter()
+        .filter(|&&criticality| criticality >= config.min_criticality())
+        .map(|&criticality| (criticality, counts.get(&criticality).cloned().unwrap_or(0)))
+        .collect();
+
+        let mut top_rules = rules
+            .into_iter()
+            .map(|(name, count)| (name.to_owned(), count))
+            .collect::<Vec<_>>();
+        top_rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_rules.truncate(TOP_RULES);
+
+        let folder = config.results_folder().join(results.app_package());
+        let reports = generators()
+            .iter()
+            .filter(|generator| generator.is_enabled(config))
+            .filter_map(|generator| {
+                let path = folder.join(generator.file_name()?);
+                if path.exists() {
+                    Some((generator.name().to_owned(), path))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            package: results.app_package().to_owned(),
+            counts,
+            top_rules,
+            reports,
+        }
+    }
+
+    /// Returns the total number of vulnerabilities.
+    fn total(&self) -> usize {
+        self.counts.iter().map(|&(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            tr_args("Summary of {}:", &[&self.package.italic()]).bold()
+        )?;
+        for &(criticality, count) in &self.counts {
+            let line = format!("  {:<10} {:>5}", criticality.to_string(), count);
+            let line = match criticality {
+                _ if count == 0 => line.normal(),
+                Criticality::Critical | Criticality::High => line.red(),
+                Criticality::Medium => line.yellow(),
+                Criticality::Low => line.cyan(),
+                Criticality::Warning => line.normal(),
+            };
+            writeln!(f, "{}", line)?;
+        }
+        writeln!(f, "  {:<10} {:>5}", tr("total"), self.total())?;
+
+        if !self.top_rules.is_empty() {
+            writeln!(f, "{}", tr("Top rules:").bold())?;
+            for (name, count) in &self.top_rules {
+                writeln!(f, "  {:>5}  {}", count, name)?;
+            }
+        }
+
+        if !self.reports.is_empty() {
+            writeln!(f, "{}", tr("Reports:").bold())?;
+            for (name, path) in &self.reports {
+                writeln!(f, "  {:<10} {}", name, path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use regex::Regex;
+
+    use super::Summary;
+    use crate::criticality::Criticality;
+
+    /// Checks the rendering of the summary.
+    #[test]
+    fn it_summary() {
+        let summary = Summary {
+            package: String::from("com.example.app"),
+            counts: vec![(Criticality::High, 2), (Criticality::Medium, 0)],
+            top_rules: vec![(String::from("Weak algorithm"), 2)],
+            reports: vec![(
+                String::from("JSON"),
+                PathBuf::from("results/com.example.app/results.json"),
+            )],
+        };
+
+        assert_eq!(summary.total(), 2);
+        // Colors depend on the terminal, so their escape codes are removed.
+        let colors = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+        assert_eq!(
+            colors.replace_all(&summary.to_string(), ""),
+            "Summary of com.example.app:\n  \
+             high           2\n  \
+             medium         0\n  \
+             total          2\n\
+             Top rules:\n      \
+             2  Weak algorithm\n\
+             Reports:\n  \
+             JSON       results/com.example.app/results.json\n"
+        );
+    }
+}
@@ -5,22 +5,36 @@
 
 use std::{
     borrow::Cow,
-    cmp::Ordering,
+    cmp::{self, Ordering},
+    fmt,
     fs::File,
-    io::Read,
+    io::{Cursor, Read},
     path::{Path, PathBuf},
 };
 
 use failure::Error;
-use hex::ToHex;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::ser::{Serialize, SerializeStruct, Serializer};
+use zip::ZipArchive;
 use {md5, sha1, sha2};
 
-use crate::criticality::Criticality;
+use crate::{
+    criticality::Criticality,
+    preflight::is_code,
+    results::{ssdeep, Cvss},
+};
+
+/// Number of context lines that `get_code()` adds before the vulnerable lines of code snippets.
+const CODE_CONTEXT_LINES: usize = 4;
 
 /// Structure to store information about a vulnerability.
+///
+/// New vulnerabilities should be created with the [`VulnerabilityBuilder`], so that adding new
+/// optional information to vulnerabilities does not break existing code.
+///
+/// [`VulnerabilityBuilder`]: struct.VulnerabilityBuilder.html
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Ord)]
 pub struct Vulnerability {
     /// Vulnerability criticality.
@@ -37,10 +51,35 @@ pub struct Vulnerability {
     end_line: Option<usize>,
     /// The vulnerable code snippet.
     code: Option<String>,
+    /// Optional identifier of the rule that found the vulnerability.
+    rule_id: Option<String>,
+    /// Optional CWE identifier of the vulnerability.
+    cwe: Option<u32>,
+    /// OWASP MASVS controls or MSTG test cases related to the vulnerability.
+    masvs: Vec<String>,
+    /// Optional CVSS v3.1 base vector of the vulnerability.
+    cvss: Option<Cvss>,
+    /// Optional confidence of the finding.
+    confidence: Option<Confidence>,
+    /// Optional evidence of the vulnerability, such as the matched text.
+    evidence: Option<String>,
+    /// Optional category of the vulnerability in the report, such as `hygiene`.
+    category: Option<String>,
+    /// IDs of the findings this one is composed of, for correlated findings.
+    related: Vec<String>,
+    /// Optional fully qualified name of the class where the vulnerability was present.
+    class: Option<String>,
+    /// Optional name of the method where the vulnerability was present.
+    method: Option<String>,
+    /// Index of the vulnerability among the ones of the same rule with the same snippet in the
+    /// same file, by their position in the file.
+    occurrence: usize,
 }
 
 impl Vulnerability {
     /// Creates a new vulnerability.
+    ///
+    /// Use `Vulnerability::builder()` to set the optional fields of the vulnerability.
     pub fn new<N: Into<String>, D: Into<String>, P: AsRef<Path>, C: Into<String>>(
         criticality: Criticality,
         name: N,
@@ -50,19 +89,44 @@ impl Vulnerability {
         end_line: Option<usize>,
         code: Option<C>,
     ) -> Self {
-        Self {
-            criticality,
-            name: name.into(),
-            description: description.into(),
-            file: match file {
-                Some(p) => Some(p.as_ref().to_path_buf()),
-                None => None,
-            },
-            start_line,
-            end_line,
-            code: match code {
-                Some(c) => Some(c.into()),
-                None => None,
+        let mut builder = Self::builder(criticality, name, description);
+        if let Some(file) = file {
+            builder = builder.file(file);
+        }
+        if let Some(code) = code {
+            builder = builder.code(code);
+        }
+        builder.vulnerability.start_line = start_line;
+        builder.vulnerability.end_line = end_line;
+        builder.build()
+    }
+
+    /// Starts building a new vulnerability with the given criticality, name and description.
+    pub fn builder<N: Into<String>, D: Into<String>>(
+        criticality: Criticality,
+        name: N,
+        description: D,
+    ) -> VulnerabilityBuilder {
+        VulnerabilityBuilder {
+            vulnerability: Self {
+                criticality,
+                name: name.into(),
+                description: description.into(),
+                file: None,
+                start_line: None,
+                end_line: None,
+                code: None,
+                rule_id: None,
+                cwe: None,
+                masvs: Vec::new(),
+                cvss: None,
+                confidence: None,
+                evidence: None,
+                category: None,
+                related: Vec::new(),
+                class: None,
+                method: None,
+                occurrence: 0,
             },
         }
     }
@@ -71,6 +135,336 @@ impl Vulnerability {
     pub fn get_criticality(&self) -> Criticality {
         self.criticality
     }
+
+    /// Gets the name of the vulnerability.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the description of the vulnerability.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Gets the file where the vulnerability was found, if any.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_ref().map(PathBuf::as_path)
+    }
+
+    /// Gets the starting line of the vulnerability in its file, if any.
+    pub fn start_line(&self) -> Option<usize> {
+        self.start_line
+    }
+
+    /// Gets the ending line of the vulnerability in its file, if any.
+    pub fn end_line(&self) -> Option<usize> {
+        self.end_line
+    }
+
+    /// Gets the vulnerable code snippet, if any.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_ref().map(String::as_str)
+    }
+
+    /// Gets the identifier of the rule that found the vulnerability, if any.
+    pub fn rule_id(&self) -> Option<&str> {
+        self.rule_id.as_ref().map(String::as_str)
+    }
+
+    /// Gets the CWE identifier of the vulnerability, if any.
+    pub fn cwe(&self) -> Option<u32> {
+        self.cwe
+    }
+
+    /// Gets the OWASP MASVS controls or MSTG test cases related to the vulnerability, such as
+    /// `MASVS-CRYPTO-1`.
+    pub fn masvs(&self) -> &[String] {
+        &self.masvs
+    }
+
+    /// Gets the CVSS v3.1 base vector of the vulnerability, if any.
+    pub fn cvss(&self) -> Option<Cvss> {
+        self.cvss
+    }
+
+    /// Gets the confidence of the finding, if any.
+    pub fn confidence(&self) -> Option<Confidence> {
+        self.confidence
+    }
+
+    /// Gets the evidence of the vulnerability, if any.
+    pub fn evidence(&self) -> Option<&str> {
+        self.evidence.as_ref().map(String::as_str)
+    }
+
+    /// Gets the category of the vulnerability in the report, if any.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_ref().map(String::as_str)
+    }
+
+    /// Gets the IDs of the findings this one is composed of, for correlated findings.
+    pub fn related(&self) -> &[String] {
+        &self.related
+    }
+
+    /// Gets the fully qualified name of the class where the vulnerability was found, if any.
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_ref().map(String::as_str)
+    }
+
+    /// Gets the name of the method where the vulnerability was found, if any.
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_ref().map(String::as_str)
+    }
+
+    /// Gets the location of the vulnerability in the code, such as
+    /// `com.example.LoginActivity#onCreate`, if its class is known.
+    pub fn location(&self) -> Option<String> {
+        let class = self.class()?;
+        Some(match self.method() {
+            Some(method) => format!("{}#{}", class, method),
+            None => class.to_owned(),
+        })
+    }
+
+    /// Sets the class and the method where the vulnerability was found.
+    pub(crate) fn set_location(&mut self, class: String, method: Option<String>) {
+        self.class = Some(class);
+        self.method = method;
+    }
+
+    /// Sets the index of the vulnerability among the ones of the same rule with the same snippet
+    /// in the same file.
+    pub(crate) fn set_occurrence(&mut self, occurrence: usize) {
+        self.occurrence = occurrence;
+    }
+
+    /// Returns the vulnerability escalated to the given criticality.
+    pub(crate) fn escalated(mut self, criticality: Criticality) -> Self {
+        self.criticality = self.criticality.max(criticality);
+        self
+    }
+
+    /// Gets a fingerprint of the vulnerability that is stable across analyses.
+    ///
+    /// It is computed from the rule (or the name, if no rule is known), the file and the evidence
+    /// of the vulnerability (or the vulnerable lines of its code snippet, if it has no evidence),
+    /// ignoring whitespace, so that it does not change if unrelated changes move the vulnerable
+    /// code to other lines, and so that findings of the same rule in the same file get different
+    /// fingerprints. The context lines around the vulnerable lines in the snippet are not taken
+    /// into account, so editing the code around a finding does not change its fingerprint.
+    ///
+    /// Findings of the same rule with the same snippet in the same file are told apart by their
+    /// occurrence index, which is only hashed after the first one, so the fingerprint of a single
+    /// finding does not depend on it.
+    pub fn fingerprint(&self) -> String {
+        use sha2::Digest;
+
+        let mut hasher = self.snippet_hasher();
+        if self.occurrence > 0 {
+            hasher.input(&[0]);
+            hasher.input(self.occurrence.to_string().as_bytes());
+        }
+
+        hex::encode(&hasher.result()[..8])
+    }
+
+    /// Gets the fingerprint of the rule, the file and the snippet of the vulnerability, without
+    /// its occurrence index.
+    pub(crate) fn snippet_fingerprint(&self) -> String {
+        use sha2::Digest;
+
+        hex::encode(&self.snippet_hasher().result()[..8])
+    }
+
+    /// Gets a hasher fed with the rule, the file and the snippet of the vulnerability.
+    fn snippet_hasher(&self) -> sha2::Sha256 {
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::default();
+        hasher.input(self.rule_id().unwrap_or(&self.name).as_bytes());
+        hasher.input(&[0]);
+        if let Some(file) = self.file() {
+            hasher.input(file.to_string_lossy().as_bytes());
+        }
+        hasher.input(&[0]);
+        let words: Box<dyn Iterator<Item = &str>> = match self.evidence() {
+            Some(evidence) => Box::new(evidence.split_whitespace()),
+            None => Box::new(self.vulnerable_lines().flat_map(str::split_whitespace)),
+        };
+        for word in words {
+            hasher.input(word.as_bytes());
+            hasher.input(b" ");
+        }
+        hasher
+    }
+
+    /// Gets the lines of the code snippet between the starting and ending lines of the
+    /// vulnerability, skipping the context lines that `get_code()` adds around them.
+    ///
+    /// If the lines of the vulnerability are unknown, or the snippet has no context lines, the
+    /// whole snippet is returned.
+    fn vulnerable_lines(&self) -> impl Iterator<Item = &str> {
+        let code = self.code().unwrap_or_default();
+        let line_count = code.lines().count();
+        let (offset, span) = match (self.start_line, self.end_line) {
+            (Some(start), Some(end)) if end >= start && line_count > end - start + 1 => {
+                let span = end - start + 1;
+                (
+                    cmp::min(cmp::min(start, CODE_CONTEXT_LINES), line_count - span),
+                    span,
+                )
+            }
+            _ => (0, line_count),
+        };
+        code.lines().skip(offset).take(span)
+    }
+
+    /// Gets the identifier of the vulnerability, such as `SUPER-3f2a9c01d4b7e856`.
+    ///
+    /// It is derived from the fingerprint of the vulnerability, so it is kept when the report is
+    /// regenerated, and it is used as the anchor of the vulnerability in the HTML report.
+    pub fn id(&self) -> String {
+        format!("SUPER-{}", self.fingerprint())
+    }
+}
+
+/// Builder for vulnerabilities.
+///
+/// It allows setting all the optional information of a vulnerability:
+///
+/// ```
+/// use super_analyzer_core::{Confidence, Criticality, Vulnerability};
+///
+/// let vulnerability = Vulnerability::builder(
+///     Criticality::Medium,
+///     "Weak algorithm",
+///     "The application uses a weak hashing algorithm.",
+/// )
+/// .file("classes/com/example/Hash.java")
+/// .lines(12, 12)
+/// .class("com.example.Hash")
+/// .method("digest")
+/// .rule_id("weak-algorithm")
+/// .cwe(327)
+/// .masvs(vec!["MASVS-CRYPTO-1"])
+/// .confidence(Confidence::High)
+/// .evidence("MessageDigest.getInstance(\"MD5\")")
+/// .build();
+///
+/// assert_eq!(vulnerability.cwe(), Some(327));
+/// assert_eq!(
+///     vulnerability.location().as_ref().map(String::as_str),
+///     Some("com.example.Hash#digest")
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct VulnerabilityBuilder {
+    /// Vulnerability being built.
+    vulnerability: Vulnerability,
+}
+
+impl VulnerabilityBuilder {
+    /// Sets the file where the vulnerability was found.
+    pub fn file<P: AsRef<Path>>(mut self, file: P) -> Self {
+        self.vulnerability.file = Some(file.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the starting and ending lines of the vulnerability in its file.
+    pub fn lines(mut self, start_line: usize, end_line: usize) -> Self {
+        self.vulnerability.start_line = Some(start_line);
+        self.vulnerability.end_line = Some(end_line);
+        self
+    }
+
+    /// Sets the vulnerable code snippet.
+    ///
+    /// The snippet will only be shown in reports if the file and the lines of the vulnerability
+    /// are also set.
+    pub fn code<C: Into<String>>(mut self, code: C) -> Self {
+        self.vulnerability.code = Some(code.into());
+        self
+    }
+
+    /// Sets the identifier of the rule that found the vulnerability.
+    pub fn rule_id<I: Into<String>>(mut self, rule_id: I) -> Self {
+        self.vulnerability.rule_id = Some(rule_id.into());
+        self
+    }
+
+    /// Sets the CWE identifier of the vulnerability.
+    pub fn cwe(mut self, cwe: u32) -> Self {
+        self.vulnerability.cwe = Some(cwe);
+        self
+    }
+
+    /// Sets the OWASP MASVS controls or MSTG test cases related to the vulnerability.
+    pub fn masvs<I: IntoIterator<Item = S>, S: Into<String>>(mut self, masvs: I) -> Self {
+        self.vulnerability.masvs = masvs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the CVSS v3.1 base vector of the vulnerability.
+    pub fn cvss(mut self, cvss: Cvss) -> Self {
+        self.vulnerability.cvss = Some(cvss);
+        self
+    }
+
+    /// Sets the confidence of the finding.
+    pub fn confidence(mut self, confidence: Confidence) -> Self {
+        self.vulnerability.confidence = Some(confidence);
+        self
+    }
+
+    /// Sets the evidence of the vulnerability, such as the matched text.
+    pub fn evidence<E: Into<String>>(mut self, evidence: E) -> Self {
+        self.vulnerability.evidence = Some(evidence.into());
+        self
+    }
+
+    /// Sets the category of the vulnerability in the report.
+    pub fn category<C: Into<String>>(mut self, category: C) -> Self {
+        self.vulnerability.category = Some(category.into());
+        self
+    }
+
+    /// Sets the fully qualified name of the class where the vulnerability was found.
+    pub fn class<C: Into<String>>(mut self, class: C) -> Self {
+        self.vulnerability.class = Some(class.into());
+        self
+    }
+
+    /// Sets the name of the method where the vulnerability was found.
+    ///
+    /// The method will only be shown in reports if the class of the vulnerability is also set.
+    pub fn method<M: Into<String>>(mut self, method: M) -> Self {
+        self.vulnerability.method = Some(method.into());
+        self
+    }
+
+    /// Sets the IDs of the findings this one is composed of.
+    pub fn related<I: IntoIterator<Item = String>>(mut self, related: I) -> Self {
+        self.vulnerability.related = related.into_iter().collect();
+        self
+    }
+
+    /// Builds the vulnerability.
+    pub fn build(self) -> Vulnerability {
+        self.vulnerability
+    }
+}
+
+/// Confidence of a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// The finding might well be a false positive.
+    Low,
+    /// The finding is probably a real issue.
+    Medium,
+    /// The finding is almost certainly a real issue.
+    High,
 }
 
 impl Serialize for Vulnerability {
@@ -78,23 +472,70 @@ impl Serialize for Vulnerability {
     where
         S: Serializer,
     {
+        let optional_fields = [
+            self.rule_id.is_some(),
+            self.cwe.is_some(),
+            !self.masvs.is_empty(),
+            self.cvss.is_some(),
+            self.confidence.is_some(),
+            self.evidence.is_some(),
+            self.category.is_some(),
+            !self.related.is_empty(),
+            self.class.is_some(),
+            self.class.is_some() && self.method.is_some(),
+        ]
+        .iter()
+        .filter(|present| **present)
+        .count();
+        let has_code = self.code.is_some() && self.file.is_some() && self.start_line.is_some();
         let mut ser_struct = serializer.serialize_struct(
             "Vulnerability",
-            if self.code.is_some() {
+            if has_code {
                 if self.start_line == self.end_line {
-                    7
-                } else {
                     8
+                } else {
+                    9
                 }
             } else {
-                4
-            },
+                5
+            } + optional_fields,
         )?;
+        ser_struct.serialize_field("id", &self.id())?;
         ser_struct.serialize_field("criticality", &self.criticality)?;
         ser_struct.serialize_field("name", self.name.as_str())?;
         ser_struct.serialize_field("description", self.description.as_str())?;
         ser_struct.serialize_field("file", &self.file)?;
-        if self.code.is_some() {
+        if let Some(ref rule_id) = self.rule_id {
+            ser_struct.serialize_field("rule_id", rule_id)?;
+        }
+        if let Some(cwe) = self.cwe {
+            ser_struct.serialize_field("cwe", &cwe)?;
+        }
+        if !self.masvs.is_empty() {
+            ser_struct.serialize_field("masvs", &self.masvs)?;
+        }
+        if let Some(ref cvss) = self.cvss {
+            ser_struct.serialize_field("cvss", cvss)?;
+        }
+        if let Some(confidence) = self.confidence {
+            ser_struct.serialize_field("confidence", &confidence)?;
+        }
+        if let Some(ref evidence) = self.evidence {
+            ser_struct.serialize_field("evidence", evidence)?;
+        }
+        if let Some(ref category) = self.category {
+            ser_struct.serialize_field("category", category)?;
+        }
+        if !self.related.is_empty() {
+            ser_struct.serialize_field("related", &self.related)?;
+        }
+        if let Some(ref class) = self.class {
+            ser_struct.serialize_field("class", class)?;
+            if let Some(ref method) = self.method {
+                ser_struct.serialize_field("method", method)?;
+            }
+        }
+        if has_code {
             ser_struct.serialize_field(
                 "language",
                 &self
@@ -102,7 +543,7 @@ impl Serialize for Vulnerability {
                     .as_ref()
                     .unwrap()
                     .extension()
-                    .unwrap()
+                    .unwrap_or_default()
                     .to_string_lossy(),
             )?;
             if self.start_line == self.end_line {
@@ -139,40 +580,55 @@ impl PartialOrd for Vulnerability {
 }
 
 /// Structure to store the application fingerprint.
+///
+/// It contains the hashes of the application package, and of each DEX file and native library in
+/// it.
+#[derive(Debug)]
 pub struct FingerPrint {
-    /// MD5 hash.
-    md5: md5::Digest,
-    /// SHA-1 hash.
-    sha1: sha1::Digest,
-    /// SHA-256 hash.
-    sha256: [u8; 32],
+    /// Hashes of the application package.
+    hashes: Hashes,
+    /// Hashes of the DEX files and native libraries of the application.
+    files: Vec<FileFingerPrint>,
 }
 
 impl FingerPrint {
     /// Creates a new fingerprint.
     ///
-    /// This function will read the complete file and generate its MD5, SHA-1 and SHA-256 hashes.
+    /// This function will read the complete file and generate its MD5, SHA-1, SHA-256 and ssdeep
+    /// hashes, along with the hashes of each DEX file and native library in it.
     pub fn from_package<P: AsRef<Path>>(package: P) -> Result<Self, Error> {
-        use sha2::Digest;
-
         let mut f = File::open(package)?;
         let mut buffer = Vec::with_capacity(f.metadata()?.len() as usize);
         let _ = f.read_to_end(&mut buffer)?;
 
-        let mut sha1 = sha1::Sha1::new();
-        sha1.update(&buffer);
-
-        let mut sha256 = sha2::Sha256::default();
-        sha256.input(&buffer);
+        let mut archive = ZipArchive::new(Cursor::new(&buffer))?;
+        let mut files = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !is_code(entry.name()) {
+                continue;
+            }
+            // The size in the archive headers is not trusted, so the buffer grows as it is read.
+            let mut contents = Vec::new();
+            let _ = entry.read_to_end(&mut contents)?;
+            files.push(FileFingerPrint {
+                path: entry.name().to_owned(),
+                size: entry.size(),
+                hashes: Hashes::compute(&contents),
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
 
-        let mut sha256_res = [0_u8; 32];
-        sha256_res.clone_from_slice(&sha256.result()[..]);
         Ok(Self {
-            md5: md5::compute(&buffer),
-            sha1: sha1.digest(),
-            sha256: sha256_res,
+            hashes: Hashes::compute(&buffer),
+            files,
         })
     }
+
+    /// Gets the SHA-256 hash of the application package, in hexadecimal.
+    pub(crate) fn sha256_hex(&self) -> String {
+        self.hashes.sha256_hex()
+    }
 }
 
 impl Serialize for FingerPrint {
@@ -180,20 +636,101 @@ impl Serialize for FingerPrint {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("fingerprint", 3)?;
-        ser_struct.serialize_field("md5", &format!("{:x}", self.md5))?;
-        ser_struct.serialize_field("sha1", &self.sha1.to_string())?;
-        let mut sha256_hex = String::new();
-        // It should never fail, we are writing directly to memory, without I/O access
-        // That's why the `expect()` should never panic.
-        self.sha256
-            .write_hex(&mut sha256_hex)
-            .expect("the SHA-256 fingerprinting of the application failed");
-        ser_struct.serialize_field("sha256", &sha256_hex)?;
+        let mut ser_struct = serializer.serialize_struct("fingerprint", 5)?;
+        ser_struct.serialize_field("md5", &self.hashes.md5_hex())?;
+        ser_struct.serialize_field("sha1", &self.hashes.sha1_hex())?;
+        ser_struct.serialize_field("sha256", &self.hashes.sha256_hex())?;
+        ser_struct.serialize_field("ssdeep", &self.hashes.ssdeep)?;
+        ser_struct.serialize_field("files", &self.files)?;
         ser_struct.end()
     }
 }
 
+/// Fingerprint of a file inside the application package.
+#[derive(Debug)]
+struct FileFingerPrint {
+    /// Path of the file in the package.
+    path: String,
+    /// Uncompressed size of the file, in bytes.
+    size: u64,
+    /// Hashes of the file.
+    hashes: Hashes,
+}
+
+impl Serialize for FileFingerPrint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("file_fingerprint", 6)?;
+        ser_struct.serialize_field("path", &self.path)?;
+        ser_struct.serialize_field("size", &self.size)?;
+        ser_struct.serialize_field("md5", &self.hashes.md5_hex())?;
+        ser_struct.serialize_field("sha1", &self.hashes.sha1_hex())?;
+        ser_struct.serialize_field("sha256", &self.hashes.sha256_hex())?;
+        ser_struct.serialize_field("ssdeep", &self.hashes.ssdeep)?;
+        ser_struct.end()
+    }
+}
+
+/// MD5, SHA-1, SHA-256 and ssdeep hashes of some data.
+struct Hashes {
+    /// MD5 hash.
+    md5: md5::Digest,
+    /// SHA-1 hash.
+    sha1: sha1::Digest,
+    /// SHA-256 hash.
+    sha256: [u8; 32],
+    /// ssdeep fuzzy hash.
+    ssdeep: String,
+}
+
+impl fmt::Debug for Hashes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Hashes")
+            .field("md5", &self.md5_hex())
+            .field("sha1", &self.sha1_hex())
+            .field("sha256", &self.sha256_hex())
+            .field("ssdeep", &self.ssdeep)
+            .finish()
+    }
+}
+
+impl Hashes {
+    /// Computes the hashes of the given data.
+    fn compute(data: &[u8]) -> Self {
+        use sha2::Digest;
+
+        let mut sha1 = sha1::Sha1::new();
+        sha1.update(data);
+
+        let mut sha256 = [0_u8; 32];
+        sha256.clone_from_slice(&sha2::Sha256::digest(data)[..]);
+
+        Self {
+            md5: md5::compute(data),
+            sha1: sha1.digest(),
+            sha256,
+            ssdeep: ssdeep::hash(data),
+        }
+    }
+
+    /// Gets the MD5 hash, in hexadecimal.
+    fn md5_hex(&self) -> String {
+        format!("{:x}", self.md5)
+    }
+
+    /// Gets the SHA-1 hash, in hexadecimal.
+    fn sha1_hex(&self) -> String {
+        self.sha1.to_string()
+    }
+
+    /// Gets the SHA-256 hash, in hexadecimal.
+    fn sha256_hex(&self) -> String {
+        hex::encode(self.sha256)
+    }
+}
+
 /// Split line into indentation and the rest of the line.
 pub fn split_indent(line: &str) -> (&str, &str) {
     match line.find(|c: char| !c.is_whitespace()) {
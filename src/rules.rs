@@ -0,0 +1,675 @@
+//! Rule set commands.
+//!
+//! This module implements the `rules` subcommand of the CLI, that works with the loaded rule set
+//! instead of analyzing applications:
+//!
+//! * `rules doc` generates the reference documentation of the rules, in Markdown or HTML, so that
+//!   the effective rule catalogue can be published.
+//! * `rules update` downloads the signed rules bundle of the configuration, and installs it once
+//!   its signature is verified (see the `rules_update` module).
+//!
+//! It also implements the `--list-rules` flag, that prints a summary of every loaded rule, the
+//! `--explain <RULE_ID>` flag, that prints everything known about a single rule, and the
+//! `test-rules` subcommand, that checks each rule against its own examples. Rules can include
+//! `examples`, code snippets that the rule must match, and `counter_examples`, code snippets that
+//! the rule must not match:
+//!
+//! ```json
+//! {
+//!     "label": "Weak Algorithms",
+//!     "regex": "getInstance\\(\"(MD5|SHA-?1)\"\\)",
+//!     "examples": ["MessageDigest.getInstance(\"MD5\")"],
+//!     "counter_examples": ["MessageDigest.getInstance(\"SHA-256\")"]
+//! }
+//! ```
+
+use std::{fmt::Write, fs, str::FromStr};
+
+use clap::ArgMatches;
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::{
+    results::html_escape,
+    static_analysis::code::{load_rules, Rule},
+    Config,
+};
+
+/// Runs the `rules` subcommand.
+#[allow(clippy::print_stdout)]
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    match cli.subcommand() {
+        ("doc", Some(doc_cli)) => {
+            let format = doc_cli.value_of("format").unwrap_or("markdown").parse()?;
+            let rules = load_rules(config).context("could not load the rules")?;
+            let doc = generate_doc(&rules, format, config);
+
+            match doc_cli.value_of("output") {
+                Some(output) => fs::write(output, doc).context(format_err!(
+                    "could not write the rules documentation to `{}`",
+                    output
+                ))?,
+                None => print!("{}", doc),
+            }
+            Ok(())
+        }
+        ("update", Some(update_cli)) => update(config, update_cli),
+        _ => bail!("a subcommand of `rules` is required, see `rules --help`"),
+    }
+}
+
+/// Downloads, verifies and installs the rules bundle.
+#[cfg(feature = "integrations")]
+fn update(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    crate::rules_update::run(config, cli)
+}
+
+/// Downloads, verifies and installs the rules bundle.
+///
+/// Updating the rules requires the `integrations` feature.
+#[cfg(not(feature = "integrations"))]
+fn update(_config: &Config, _cli: &ArgMatches<'static>) -> Result<(), Error> {
+    bail!("updating the rules requires the `integrations` feature")
+}
+
+/// Lists the loaded rules, with their criticality, target files and required permissions.
+#[allow(clippy::print_stdout)]
+pub fn list(config: &Config) -> Result<(), Error> {
+    let rules = load_rules(config).context("could not load the rules")?;
+    print!("{}", rule_list(&rules));
+    println!(
+        "\n{} rules with `{}` criticality or higher, loaded from {}.",
+        rules.len(),
+        config.min_criticality(),
+        rules_paths(config)
+    );
+    Ok(())
+}
+
+/// Explains in detail the loaded rule with the given ID.
+#[allow(clippy::print_stdout)]
+pub fn explain(config: &Config, id: &str) -> Result<(), Error> {
+    let rules = load_rules(config).context("could not load the rules")?;
+    let rule = rules.iter().find(|rule| rule.id() == id).ok_or_else(|| {
+        format_err!(
+            "there is no loaded rule with the `{}` ID, use `--list-rules` to see the loaded rules",
+            id
+        )
+    })?;
+    print!("{}", explanation(rule));
+    Ok(())
+}
+
+/// Checks every loaded rule against its own examples and counter-examples.
+///
+/// An error is returned if any rule fails, so that the command can be used in CI.
+#[allow(clippy::print_stdout)]
+pub fn test(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let rules = load_rules(config).context("could not load the rules")?;
+    let rules = match cli.value_of("rule") {
+        Some(id) => {
+            let rules = rules
+                .into_iter()
+                .filter(|rule| rule.id() == id)
+                .collect::<Vec<_>>();
+            if rules.is_empty() {
+                bail!("there is no loaded rule with the `{}` ID", id);
+            }
+            rules
+        }
+        None => rules,
+    };
+
+    let (mut failed, mut untested) = (0, 0);
+    for rule in &rules {
+        if rule.examples().is_empty() && rule.counter_examples().is_empty() {
+            untested += 1;
+            if config.is_verbose() {
+                println!("SKIP  {}", rule.id());
+            }
+            continue;
+        }
+
+        let failures = test_rule(rule);
+        if failures.is_empty() {
+            if config.is_verbose() {
+                println!("ok    {}", rule.id());
+            }
+        } else {
+            failed += 1;
+            println!("FAIL  {}", rule.id());
+            for failure in failures {
+                println!("      {}", failure);
+            }
+        }
+    }
+
+    println!(
+        "\n{} rules tested: {} passed, {} failed, {} without examples.",
+        rules.len() - untested,
+        rules.len() - untested - failed,
+        failed,
+        untested
+    );
+    if failed > 0 {
+        bail!("{} rules failed their own examples", failed);
+    }
+    Ok(())
+}
+
+/// Gets the list of the paths the rules are loaded from.
+fn rules_paths(config: &Config) -> String {
+    config
+        .rules_paths()
+        .iter()
+        .map(|path| format!("`{}`", path.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Runs the given rule against its examples and counter-examples, returning the failures.
+fn test_rule(rule: &Rule) -> Vec<String> {
+    let mut failures = Vec::new();
+    let cases = rule.examples().iter().map(|example| (example, true)).chain(
+        rule.counter_examples()
+            .iter()
+            .map(|example| (example, false)),
+    );
+    for (example, should_match) in cases {
+        match rule.matches(example) {
+            Ok(matches) if matches == should_match => {}
+            Ok(true) => failures.push(format!("counter-example matched: {}", example)),
+            Ok(false) => failures.push(format!("example not matched: {}", example)),
+            Err(e) => failures.push(format!("{}, in: {}", e, example)),
+        }
+    }
+    failures
+}
+
+/// Generates the table with the summary of the given rules.
+fn rule_list(rules: &[Rule]) -> String {
+    let header = [
+        "ID".to_owned(),
+        "CRITICALITY".to_owned(),
+        "FILES".to_owned(),
+        "PERMISSIONS".to_owned(),
+    ];
+    let rows = rules
+        .iter()
+        .map(|rule| {
+            [
+                rule.id().into_owned(),
+                rule.criticality().to_string(),
+                target_files(rule),
+                permissions(rule).unwrap_or_else(|| "-".to_owned()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let width = |column: usize| {
+        rows.iter()
+            .chain(Some(&header))
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (id_width, criticality_width, files_width) = (width(0), width(1), width(2));
+
+    let mut out = String::new();
+    for row in Some(&header).into_iter().chain(&rows) {
+        let _ = writeln!(
+            out,
+            "{:<id$}  {:<criticality$}  {:<files$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            id = id_width,
+            criticality = criticality_width,
+            files = files_width
+        );
+    }
+    out
+}
+
+/// Generates the detailed explanation of the given rule.
+fn explanation(rule: &Rule) -> String {
+    let mut out = format!(
+        "{} ({})\n\n{}\n\n",
+        rule.label(),
+        rule.id(),
+        rule.description()
+    );
+
+    let _ = writeln!(out, "Criticality:   {}", rule.criticality());
+    if let Some(category) = rule.category() {
+        let _ = writeln!(out, "Category:      {}", category);
+    }
+    if let Some(cwe) = rule.cwe() {
+        let _ = writeln!(out, "CWE:           CWE-{} <{}>", cwe, cwe_url(cwe));
+    }
+    if !rule.masvs().is_empty() {
+        let _ = writeln!(out, "MASVS:         {}", rule.masvs().join(", "));
+    }
+    if let Some(cvss) = rule.cvss() {
+        let _ = writeln!(out, "CVSS:          {} ({:.1})", cvss, cvss.score());
+    }
+    if let Some(max_sdk) = rule.max_sdk() {
+        let _ = writeln!(out, "Maximum SDK:   {}", max_sdk);
+    }
+    if let Some(min_target_sdk) = rule.min_target_sdk() {
+        let _ = writeln!(out, "Min. target:   {}", min_target_sdk);
+    }
+    if let Some(criticality) = rule.exposed_provider_criticality() {
+        let _ = writeln!(out, "In providers:  {}", criticality);
+    }
+    if let Some(permissions) = permissions(rule) {
+        let _ = writeln!(out, "Permissions:   {}", permissions);
+    }
+    let _ = writeln!(out, "Files:         {}", target_files(rule));
+
+    let _ = writeln!(out, "\nPattern:\n    {}", rule.regex());
+    if let Some(forward_check) = rule.forward_check() {
+        let _ = writeln!(out, "\nForward check:\n    {}", forward_check);
+    }
+    if let Some(requirement) = rule.requires() {
+        let _ = writeln!(out, "\nRequires:\n    {}", requirement);
+    }
+    if let Some(flow) = rule.flow() {
+        let _ = writeln!(out, "\nFlow:\n    {}", flow);
+    }
+    if let Some(query) = rule.query() {
+        let _ = writeln!(out, "\nSyntax query:\n    {}", query);
+    }
+    if rule.whitelist().next().is_some() {
+        let _ = writeln!(out, "\nIgnored matches:");
+        for white in rule.whitelist() {
+            let _ = writeln!(out, "    {}", white);
+        }
+    }
+    if !rule.references().is_empty() {
+        let _ = writeln!(out, "\nReferences:");
+        for reference in rule.references() {
+            let _ = writeln!(out, "    {}", reference);
+        }
+    }
+    if !rule.examples().is_empty() {
+        let _ = writeln!(out, "\nExample matches:");
+        for example in rule.examples() {
+            let _ = writeln!(out, "    {}", example);
+        }
+    }
+    if !rule.counter_examples().is_empty() {
+        let _ = writeln!(out, "\nExample non-matches:");
+        for example in rule.counter_examples() {
+            let _ = writeln!(out, "    {}", example);
+        }
+    }
+
+    out
+}
+
+/// Gets the files the given rule is applied to.
+fn target_files(rule: &Rule) -> String {
+    let files = match (rule.include_file_regex(), rule.exclude_file_regex()) {
+        (None, None) => "all".to_owned(),
+        (Some(include), None) => include.as_str().to_owned(),
+        (None, Some(exclude)) => format!("all but {}", exclude),
+        (Some(include), Some(exclude)) => format!("{} but {}", include, exclude),
+    };
+    if rule.file_types().is_empty() {
+        files
+    } else {
+        format!("{} ({})", files, rule.file_types().join(", "))
+    }
+}
+
+/// Gets the permissions required by the given rule, if any.
+fn permissions(rule: &Rule) -> Option<String> {
+    let permissions = rule
+        .permissions()
+        .map(|permission| permission.as_str())
+        .collect::<Vec<_>>();
+    if permissions.is_empty() {
+        None
+    } else {
+        Some(permissions.join(", "))
+    }
+}
+
+/// Format of the rules documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    /// Markdown document.
+    Markdown,
+    /// Standalone HTML page.
+    Html,
+}
+
+impl FromStr for DocFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(DocFormat::Markdown),
+            "html" => Ok(DocFormat::Html),
+            _ => bail!(
+                "unknown rules documentation format `{}`, it must be `markdown` or `html`",
+                s
+            ),
+        }
+    }
+}
+
+/// Generates the documentation of the given rules in the given format.
+fn generate_doc(rules: &[Rule], format: DocFormat, config: &Config) -> String {
+    let intro = format!(
+        "{} rules with `{}` criticality or higher, loaded from {}.",
+        rules.len(),
+        config.min_criticality(),
+        rules_paths(config)
+    );
+
+    match format {
+        DocFormat::Markdown => markdown(rules, &intro),
+        DocFormat::Html => html(rules, &intro),
+    }
+}
+
+/// Generates the Markdown documentation of the given rules.
+fn markdown(rules: &[Rule], intro: &str) -> String {
+    let mut out = format!("# SUPER rules reference\n\n{}\n", intro);
+
+    let _ = writeln!(
+        out,
+        "\n| ID | Rule | Criticality | CWE |\n|---|---|---|---|"
+    );
+    for rule in rules {
+        let _ = writeln!(
+            out,
+            "| [`{id}`](#{id}) | {} | {} | {} |",
+            rule.label(),
+            rule.criticality(),
+            rule.cwe()
+                .map_or_else(String::new, |cwe| format!("CWE-{}", cwe)),
+            id = rule.id(),
+        );
+    }
+
+    for rule in rules {
+        let _ = writeln!(
+            out,
+            "\n<a id=\"{}\"></a>\n## {}\n\n{}\n",
+            rule.id(),
+            rule.label(),
+            rule.description()
+        );
+        let _ = writeln!(out, "* **ID:** `{}`", rule.id());
+        let _ = writeln!(out, "* **Criticality:** {}", rule.criticality());
+        if let Some(cwe) = rule.cwe() {
+            let _ = writeln!(out, "* **CWE:** [CWE-{0}]({1})", cwe, cwe_url(cwe));
+        }
+        if let Some(max_sdk) = rule.max_sdk() {
+            let _ = writeln!(out, "* **Maximum SDK:** {}", max_sdk);
+        }
+        if let Some(min_target_sdk) = rule.min_target_sdk() {
+            let _ = writeln!(out, "* **Minimum target SDK:** {}", min_target_sdk);
+        }
+        if let Some(criticality) = rule.exposed_provider_criticality() {
+            let _ = writeln!(
+                out,
+                "* **Criticality in exposed content providers:** {}",
+                criticality
+            );
+        }
+        let permissions = rule
+            .permissions()
+            .map(|permission| format!("`{}`", permission.as_str()))
+            .collect::<Vec<_>>();
+        if !permissions.is_empty() {
+            let _ = writeln!(out, "* **Permissions:** {}", permissions.join(", "));
+        }
+        let _ = writeln!(out, "\nPattern:\n\n```regex\n{}\n```", rule.regex());
+
+        if !rule.references().is_empty() {
+            let _ = writeln!(out, "\nReferences:\n");
+            for reference in rule.references() {
+                let _ = writeln!(out, "* <{}>", reference);
+            }
+        }
+        if !rule.examples().is_empty() {
+            let _ = writeln!(out, "\nExample matches:");
+            for example in rule.examples() {
+                let _ = writeln!(out, "\n```java\n{}\n```", example);
+            }
+        }
+    }
+
+    out
+}
+
+/// Generates the HTML documentation of the given rules.
+fn html(rules: &[Rule], intro: &str) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>SUPER rules reference</title>\n</head>\n<body>\n\
+         <h1>SUPER rules reference</h1>\n<p>{}</p>\n",
+        html_escape(intro)
+    );
+
+    let _ = writeln!(
+        out,
+        "<table>\n<tr><th>ID</th><th>Rule</th><th>Criticality</th><th>CWE</th></tr>"
+    );
+    for rule in rules {
+        let _ = writeln!(
+            out,
+            "<tr><td><a href=\"#{id}\"><code>{id}</code></a></td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            html_escape(rule.label()),
+            rule.criticality(),
+            rule.cwe()
+                .map_or_else(String::new, |cwe| format!("CWE-{}", cwe)),
+            id = html_escape(rule.id()),
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    for rule in rules {
+        let _ = writeln!(
+            out,
+            "<section id=\"{}\">\n<h2>{}</h2>\n<p>{}</p>\n<ul>",
+            html_escape(rule.id()),
+            html_escape(rule.label()),
+            html_escape(rule.description())
+        );
+        let _ = writeln!(
+            out,
+            "<li><strong>ID:</strong> <code>{}</code></li>",
+            html_escape(rule.id())
+        );
+        let _ = writeln!(
+            out,
+            "<li><strong>Criticality:</strong> {}</li>",
+            rule.criticality()
+        );
+        if let Some(cwe) = rule.cwe() {
+            let _ = writeln!(
+                out,
+                "<li><strong>CWE:</strong> <a href=\"{}\">CWE-{}</a></li>",
+                cwe_url(cwe),
+                cwe
+            );
+        }
+        if let Some(max_sdk) = rule.max_sdk() {
+            let _ = writeln!(out, "<li><strong>Maximum SDK:</strong> {}</li>", max_sdk);
+        }
+        if let Some(min_target_sdk) = rule.min_target_sdk() {
+            let _ = writeln!(
+                out,
+                "<li><strong>Minimum target SDK:</strong> {}</li>",
+                min_target_sdk
+            );
+        }
+        if let Some(criticality) = rule.exposed_provider_criticality() {
+            let _ = writeln!(
+                out,
+                "<li><strong>Criticality in exposed content providers:</strong> {}</li>",
+                criticality
+            );
+        }
+        let permissions = rule
+            .permissions()
+            .map(|permission| format!("<code>{}</code>", permission.as_str()))
+            .collect::<Vec<_>>();
+        if !permissions.is_empty() {
+            let _ = writeln!(
+                out,
+                "<li><strong>Permissions:</strong> {}</li>",
+                permissions.join(", ")
+            );
+        }
+        let _ = writeln!(
+            out,
+            "</ul>\n<p>Pattern:</p>\n<pre><code>{}</code></pre>",
+            html_escape(rule.regex().as_str())
+        );
+
+        if !rule.references().is_empty() {
+            let _ = writeln!(out, "<p>References:</p>\n<ul>");
+            for reference in rule.references() {
+                let _ = writeln!(
+                    out,
+                    "<li><a href=\"{0}\">{0}</a></li>",
+                    html_escape(reference.as_str())
+                );
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+        if !rule.examples().is_empty() {
+            let _ = writeln!(out, "<p>Example matches:</p>");
+            for example in rule.examples() {
+                let _ = writeln!(
+                    out,
+                    "<pre><code>{}</code></pre>",
+                    html_escape(example.as_str())
+                );
+            }
+        }
+        let _ = writeln!(out, "</section>");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Gets the URL of the MITRE definition of the given CWE.
+fn cwe_url(cwe: u32) -> String {
+    format!("https://cwe.mitre.org/data/definitions/{}.html", cwe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explanation, html, markdown, rule_list, test_rule, DocFormat};
+    use crate::static_analysis::code::Rule;
+
+    /// Gets a rule for the tests.
+    fn rule() -> Rule {
+        serde_json::from_str(
+            r#"{
+                "regex": "setJavaScriptEnabled\\(true\\)",
+                "label": "WebView <XSS>",
+                "description": "JavaScript is enabled in a WebView.",
+                "criticality": "medium",
+                "cwe": 79,
+                "references": ["https://developer.android.com/guide/webapps/webview"],
+                "examples": ["webView.getSettings().setJavaScriptEnabled(true);"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    /// Checks the parsing of the documentation formats.
+    #[test]
+    fn it_doc_format() {
+        assert_eq!("md".parse::<DocFormat>().unwrap(), DocFormat::Markdown);
+        assert_eq!("HTML".parse::<DocFormat>().unwrap(), DocFormat::Html);
+        assert!("pdf".parse::<DocFormat>().is_err());
+    }
+
+    /// Checks the generation of the Markdown documentation.
+    #[test]
+    fn it_markdown() {
+        let doc = markdown(&[rule()], "1 rule.");
+
+        assert!(doc.contains("| [`webview-xss`](#webview-xss) | WebView <XSS> | medium | CWE-79 |"));
+        assert!(doc.contains("## WebView <XSS>\n\nJavaScript is enabled in a WebView."));
+        assert!(doc.contains("[CWE-79](https://cwe.mitre.org/data/definitions/79.html)"));
+        assert!(doc.contains("* <https://developer.android.com/guide/webapps/webview>"));
+        assert!(doc.contains("webView.getSettings().setJavaScriptEnabled(true);"));
+    }
+
+    /// Checks the generation of the HTML documentation.
+    #[test]
+    fn it_html() {
+        let doc = html(&[rule()], "1 rule.");
+
+        assert!(doc.contains("<section id=\"webview-xss\">\n<h2>WebView &lt;XSS&gt;</h2>"));
+        assert!(doc.contains("<pre><code>setJavaScriptEnabled\\(true\\)</code></pre>"));
+        assert!(doc.ends_with("</body>\n</html>\n"));
+    }
+
+    /// Checks the summary table of the rules.
+    #[test]
+    fn it_rule_list() {
+        let list = rule_list(&[rule()]);
+        let mut lines = list.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("ID           CRITICALITY  FILES  PERMISSIONS")
+        );
+        assert_eq!(lines.next(), Some("webview-xss  medium       all    -"));
+        assert_eq!(lines.next(), None);
+    }
+
+    /// Checks the detailed explanation of a rule.
+    #[test]
+    fn it_explanation() {
+        let explanation = explanation(&rule());
+
+        assert!(explanation
+            .starts_with("WebView <XSS> (webview-xss)\n\nJavaScript is enabled in a WebView.\n\n"));
+        assert!(explanation.contains("Criticality:   medium\n"));
+        assert!(explanation
+            .contains("CWE:           CWE-79 <https://cwe.mitre.org/data/definitions/79.html>\n"));
+        assert!(explanation.contains("Files:         all\n"));
+        assert!(explanation.contains("\nPattern:\n    setJavaScriptEnabled\\(true\\)\n"));
+        assert!(!explanation.contains("Permissions:"));
+    }
+
+    /// Checks the testing of the rules against their own examples.
+    #[test]
+    fn it_test_rule() {
+        assert!(test_rule(&rule()).is_empty());
+
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "getInstance\\(\"(MD5|SHA-?1)\"\\)",
+                "whitelist": ["SHA1"],
+                "label": "Weak Algorithms",
+                "description": "Weak hashing algorithms are used.",
+                "criticality": "high",
+                "examples": ["getInstance(\"MD5\")", "getInstance(\"SHA1\")"],
+                "counter_examples": ["getInstance(\"SHA-256\")", "getInstance(\"SHA-1\")"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            test_rule(&rule),
+            vec![
+                "example not matched: getInstance(\"SHA1\")".to_owned(),
+                "counter-example matched: getInstance(\"SHA-1\")".to_owned(),
+            ]
+        );
+    }
+}
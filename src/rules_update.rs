@@ -0,0 +1,202 @@
+//! Rules update module.
+//!
+//! The `rules update` subcommand downloads the rules bundle published at the `rules_update_url`
+//! of the configuration, so that organizations can distribute their rules to every analyst from a
+//! single place. The bundle is a rules file in JSON, YAML or TOML, published along with its
+//! minisign signature (`.minisig`) and its SHA-256 checksum (`.sha256`):
+//!
+//! ```text
+//! https://rules.example.com/rules.json
+//! https://rules.example.com/rules.json.minisig
+//! https://rules.example.com/rules.json.sha256
+//! ```
+//!
+//! The signature is verified with the `trusted_keys` of the configuration, and bundles without a
+//! valid signature are refused, unless `--allow-unsigned` is given, in which case only the
+//! checksum is verified. The rules are also parsed before they are installed, so that a broken
+//! bundle never replaces working rules. They are installed in the first rules path, with their
+//! signature next to them, so that it is verified again every time the rules are loaded.
+//!
+//! Proxies can be set with the `--proxy` option or with the `HTTPS_PROXY` and `ALL_PROXY`
+//! environment variables.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+
+use crate::{
+    http::{client, download, verify_checksum},
+    print_warning,
+    signature::{self, signature_path},
+    static_analysis::code::parse_rules,
+    Config,
+};
+
+/// Runs the `rules update` subcommand.
+#[allow(clippy::print_stdout)]
+pub(crate) fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let url = match cli.value_of("url").or_else(|| config.rules_update_url()) {
+        Some(url) => url,
+        None => bail!(
+            "there is no rules bundle to download, set the `rules_update_url` of the \
+             configuration or use `--url`"
+        ),
+    };
+    let path = match cli.value_of("output") {
+        Some(output) => PathBuf::from(output),
+        None => match config.rules_paths().first() {
+            Some(rules_path) => destination(rules_path, url),
+            None => bail!("there is no rules path to install the rules in, use `--output`"),
+        },
+    };
+
+    let client = client(cli.value_of("proxy"))?;
+    let rules = download(&client, url).context("could not download the rules")?;
+    let keys = config
+        .trusted_keys()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    let signature = if keys.is_empty() {
+        if !cli.is_present("allow-unsigned") {
+            bail!(
+                "there are no trusted keys to verify the signature of the rules, add them to the \
+                 `trusted_keys` of the configuration or use `--allow-unsigned`"
+            );
+        }
+        print_warning(
+            "the signature of the rules cannot be verified, only their checksum will be checked",
+        );
+        let checksum = download(&client, &format!("{}.sha256", url))
+            .context("could not download the checksum of the rules")?;
+        verify_checksum(
+            &rules,
+            &String::from_utf8(checksum).context("the checksum is not valid UTF-8")?,
+        )
+        .context("could not verify the rules")?;
+        None
+    } else {
+        let signature = download(&client, &format!("{}.minisig", url))
+            .context("could not download the signature of the rules")?;
+        let signature = String::from_utf8(signature).context("the signature is not valid UTF-8")?;
+        signature::verify(&rules, &signature, &keys).context("could not verify the rules")?;
+        Some(signature)
+    };
+
+    let count = parse_rules(&path, &rules)
+        .context("the downloaded rules are not valid")?
+        .len();
+    install(&path, &rules, signature.as_ref().map(String::as_str)).context(format_err!(
+        "could not install the rules in `{}`",
+        path.display()
+    ))?;
+
+    println!(
+        "{}",
+        format!(
+            "{} rules were installed in `{}`.",
+            count.to_string().bold(),
+            path.display()
+        )
+        .green()
+    );
+    Ok(())
+}
+
+/// Gets the file where the rules bundle in the given URL is installed, for the given rules path.
+///
+/// If the rules path is a folder of rule packs, the bundle is installed in it, named after the
+/// URL.
+fn destination(rules_path: &Path, url: &str) -> PathBuf {
+    if rules_path.is_dir() {
+        let name = url
+            .split(|c| c == '?' || c == '#')
+            .next()
+            .and_then(|url| url.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("rules.json");
+        rules_path.join(name)
+    } else {
+        rules_path.to_path_buf()
+    }
+}
+
+/// Installs the given rules, with their signature, if any, in the given path.
+///
+/// The rules are written next to the path and then moved over it, so that the installed rules are
+/// never left half-written. The signature of the previous rules is removed if the new ones are
+/// not signed.
+fn install(path: &Path, rules: &[u8], signature: Option<&str>) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut new_name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    new_name.push(".new");
+    let new_path = path.with_file_name(new_name);
+    fs::write(&new_path, rules)?;
+    fs::rename(&new_path, path)?;
+
+    let signature_path = signature_path(path);
+    match signature {
+        Some(signature) => fs::write(&signature_path, signature)?,
+        None if signature_path.exists() => fs::remove_file(&signature_path)?,
+        None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path};
+
+    use super::{destination, install};
+    use crate::http::verify_checksum;
+
+    /// Checks the file where the rules are installed.
+    #[test]
+    fn it_destination() {
+        let url = "https://rules.example.com/packs/org.yaml?token=secret";
+        assert_eq!(
+            destination(Path::new("rules.json"), url),
+            Path::new("rules.json")
+        );
+        assert_eq!(
+            destination(Path::new("src"), url),
+            Path::new("src/org.yaml")
+        );
+        assert_eq!(
+            destination(Path::new("src"), "https://rules.example.com/"),
+            Path::new("src/rules.json")
+        );
+    }
+
+    /// Checks the verification of the checksum and the installation of the rules.
+    #[test]
+    fn it_install() {
+        let checksum =
+            "37517e5f3dc66819f61f5a7bb8ace1921282415f10551d2defa5c3eb0985b570  rules.json";
+        assert!(verify_checksum(b"[]\n", checksum).is_ok());
+        assert!(verify_checksum(b"[{}]\n", checksum).is_err());
+
+        let folder = env::temp_dir().join("super-rules-update-test");
+        let _ = fs::remove_dir_all(&folder);
+        let path = folder.join("rules.json");
+        install(&path, b"[]\n", Some("signature")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"[]\n");
+        assert!(folder.join("rules.json.minisig").is_file());
+
+        install(&path, b"[ ]\n", None).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"[ ]\n");
+        assert!(!folder.join("rules.json.minisig").exists());
+        assert!(!folder.join("rules.json.new").exists());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,500 @@
+//! Rhai scripting module.
+//!
+//! Scripts are [Rhai](https://rhai.rs) files stored in the configured scripts folder with the
+//! `.rhai` extension. They are a lightweight alternative to the WebAssembly plugins for custom
+//! checks, and they can define any of these hook functions:
+//!
+//! * `on_manifest(manifest)`: called once the manifest has been parsed, with an object map
+//!   containing the manifest data: `package`, `label`, `version_name`, `version_code`,
+//!   `min_sdk`, `target_sdk`, `debuggable`, `allow_backup`, `uses_cleartext_traffic`,
+//!   `network_security_config`, `test_only`, `unknown_permissions`, `custom_permissions`,
+//!   `components` and `xml`, the source of the manifest. Each component has its `kind`, `name`,
+//!   `exported`, `exposed`, `launch_mode`, `task_affinity` and `intent_filters`.
+//! * `on_file(path, contents)`: called for each source file of the application, with its path
+//!   relative to the application's distribution folder and its contents.
+//! * `on_analysis(results)`: called once the static analysis has finished, with an object map
+//!   containing the current results.
+//!
+//! Hooks can use these functions to report back to SUPER:
+//!
+//! * `add_finding(criticality, name, description)`: adds a new vulnerability to the results.
+//! * `add_finding(criticality, name, description, file, line)`: adds a new vulnerability found
+//!   in the given line (starting at 1) of the given file.
+//! * `annotate(key, value)`: adds an annotation to the results of the analysis.
+//!
+//! Each hook call can run as many operations as the `plugin_fuel` option allows, and up to 64
+//! nested function calls, so that a script that never returns cannot hang the analysis.
+//!
+//! For example:
+//!
+//! ```rhai
+//! fn on_file(path, contents) {
+//!     if contents.contains("TODO: remove backdoor") {
+//!         add_finding("high", "Backdoor", "A backdoor was left in the code.", path, 1);
+//!     }
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    fs::{self, DirEntry},
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+};
+
+use colored::Colorize;
+use failure::Error;
+use rhai::{Dynamic, Engine, EvalAltResult, FuncArgs, Scope, AST, INT};
+use serde_json::{json, Value};
+
+use crate::{
+    criticality::Criticality,
+    get_code,
+    i18n::tr,
+    print_message, print_vulnerability, print_warning,
+    results::{Results, Vulnerability},
+    static_analysis::{code::add_files_to_vec, manifest::Manifest},
+    Config,
+};
+
+/// Name of the hook called after parsing the manifest.
+const MANIFEST_HOOK: &str = "on_manifest";
+/// Name of the hook called for each source file.
+const FILE_HOOK: &str = "on_file";
+/// Name of the hook called after the analysis.
+const ANALYSIS_HOOK: &str = "on_analysis";
+/// Maximum depth of nested function calls of the scripts.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// Compiled user scripts, ready to be run at each point of the pipeline.
+pub struct Scripts<'c> {
+    /// Current configuration.
+    config: &'c Config,
+    /// Distribution folder of the analyzed application.
+    dist_folder: PathBuf,
+    /// Scripting engine, with the SUPER functions registered.
+    engine: Engine,
+    /// Compiled scripts, along with their paths.
+    scripts: Vec<(PathBuf, AST)>,
+    /// Output of the scripts, shared with the registered functions.
+    output: Rc<RefCell<Output>>,
+}
+
+impl<'c> Scripts<'c> {
+    /// Loads and compiles all the scripts in the scripts folder.
+    ///
+    /// Scripts that can't be compiled are skipped with a warning.
+    pub fn load<S: AsRef<str>>(config: &'c Config, package: S) -> Self {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let engine = create_engine(&output, config.plugin_fuel());
+
+        let mut scripts = Vec::new();
+        if config.scripts_folder().exists() {
+            match script_paths(config.scripts_folder()) {
+                Ok(paths) => {
+                    for path in paths {
+                        match engine.compile_file(path.clone()) {
+                            Ok(ast) => scripts.push((path, ast)),
+                            Err(e) => print_warning(format!(
+                                "could not compile the `{}` script. The analysis will continue, \
+                                 though. Error: {}",
+                                path.display(),
+                                e
+                            )),
+                        }
+                    }
+                }
+                Err(e) => print_warning(format!(
+                    "An error occurred when reading the scripts folder. Scripts will not run. \
+                     Error: {}",
+                    e
+                )),
+            }
+        }
+
+        Self {
+            config,
+            dist_folder: config.dist_folder().join(package.as_ref()),
+            engine,
+            scripts,
+            output,
+        }
+    }
+
+    /// Runs the `on_manifest` hook of the scripts.
+    pub fn on_manifest(&self, manifest: Option<&Manifest>, results: &mut Results) {
+        let manifest = match manifest {
+            Some(m) => m,
+            None => return,
+        };
+        if !self.has_hook(MANIFEST_HOOK, 1) {
+            return;
+        }
+
+        match rhai::serde::to_dynamic(manifest_map(manifest)) {
+            Ok(manifest) => self.call_hook(MANIFEST_HOOK, 1, || (manifest.clone(),), results),
+            Err(e) => print_warning(format!(
+                "could not send the manifest to the scripts. Error: {}",
+                e
+            )),
+        }
+    }
+
+    /// Runs the `on_file` hook of the scripts for every source file of the application.
+    pub fn on_files(&self, package: &str, results: &mut Results) {
+        if !self.has_hook(FILE_HOOK, 2) {
+            return;
+        }
+
+        let mut files: Vec<DirEntry> = Vec::new();
+        if let Err(e) = add_files_to_vec("", &mut files, package, self.config) {
+            print_warning(format!(
+                "An error occurred when reading files for the scripts, the results might be \
+                 incomplete. Error: {}",
+                e
+            ));
+        }
+
+        for file in files {
+            let path = file.path();
+            let contents = match fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    print_warning(format!(
+                        "could not read `{}` for the scripts. Error: {}",
+                        path.display(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+            let relative_path = path
+                .strip_prefix(&self.dist_folder)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .into_owned();
+
+            self.call_hook(
+                FILE_HOOK,
+                2,
+                || (relative_path.clone(), contents.clone()),
+                results,
+            );
+        }
+    }
+
+    /// Runs the `on_analysis` hook of the scripts.
+    pub fn on_analysis(&self, results: &mut Results) {
+        if self.has_hook(ANALYSIS_HOOK, 1) {
+            match rhai::serde::to_dynamic(&*results) {
+                Ok(current) => self.call_hook(ANALYSIS_HOOK, 1, || (current.clone(),), results),
+                Err(e) => print_warning(format!(
+                    "could not send the results to the scripts. Error: {}",
+                    e
+                )),
+            }
+        }
+
+        if self.scripts.is_empty() {
+            return;
+        }
+        if self.config.is_verbose() {
+            print_message("");
+            print_message(tr("The scripts were run correctly!").green());
+        } else if !self.config.is_quiet() {
+            print_message(tr("Scripts run."));
+        }
+    }
+
+    /// Checks if any of the scripts defines the given hook.
+    fn has_hook(&self, hook: &str, arity: usize) -> bool {
+        self.scripts
+            .iter()
+            .any(|(_, ast)| defines(ast, hook, arity))
+    }
+
+    /// Calls the given hook in every script defining it, and adds their output to the results.
+    fn call_hook<F, A>(&self, hook: &str, arity: usize, args: F, results: &mut Results)
+    where
+        F: Fn() -> A,
+        A: FuncArgs,
+    {
+        for (path, ast) in self
+            .scripts
+            .iter()
+            .filter(|(_, ast)| defines(ast, hook, arity))
+        {
+            if let Err(e) = self
+                .engine
+                .call_fn::<Dynamic>(&mut Scope::new(), ast, hook, args())
+            {
+                print_warning(format!(
+                    "the `{}` hook of the `{}` script failed. The analysis will continue, \
+                     though. Error: {}",
+                    hook,
+                    path.display(),
+                    e
+                ));
+            }
+            self.flush(results);
+        }
+    }
+
+    /// Moves the findings and annotations generated by the scripts to the results.
+    fn flush(&self, results: &mut Results) {
+        let mut output = self.output.borrow_mut();
+        for finding in output.findings.drain(..) {
+            self.add_finding(finding, results);
+        }
+        for (key, value) in output.annotations.drain(..) {
+            results.add_annotation(key, value);
+        }
+    }
+
+    /// Adds the given script finding to the results.
+    fn add_finding(&self, finding: Finding, results: &mut Results) {
+        if finding.criticality < self.config.min_criticality() {
+            return;
+        }
+
+        let mut builder = Vulnerability::builder(
+            finding.criticality,
+            finding.name.as_str(),
+            finding.description.as_str(),
+        );
+        if let Some((file, line)) = finding.location {
+            if let Some(line) = line.checked_sub(1) {
+                if let Ok(code) = fs::read_to_string(self.dist_folder.join(&file)) {
+                    builder = builder.code(get_code(code, line, line));
+                }
+                builder = builder.lines(line, line);
+            }
+            builder = builder.file(file);
+        }
+
+        print_vulnerability(finding.description.as_str(), finding.criticality);
+        results.add_vulnerability(builder.build());
+    }
+}
+
+/// Output generated by the scripts, pending to be added to the results.
+#[derive(Debug, Default)]
+struct Output {
+    /// Findings added by the scripts.
+    findings: Vec<Finding>,
+    /// Annotations added by the scripts.
+    annotations: Vec<(String, String)>,
+}
+
+/// Finding added by a script.
+#[derive(Debug)]
+struct Finding {
+    /// Criticality of the finding.
+    criticality: Criticality,
+    /// Name of the finding.
+    name: String,
+    /// Description of the finding.
+    description: String,
+    /// File and line (starting at 1) of the finding, if any.
+    location: Option<(PathBuf, usize)>,
+}
+
+/// Creates the scripting engine, registering the functions available to the scripts.
+///
+/// Each call to the engine can run up to the given number of operations.
+fn create_engine(output: &Rc<RefCell<Output>>, max_operations: u64) -> Engine {
+    let mut engine = Engine::new();
+    let _ = engine
+        .set_max_operations(max_operations)
+        .set_max_call_levels(MAX_CALL_LEVELS);
+
+    let findings = Rc::clone(output);
+    let _ = engine.register_fn(
+        "add_finding",
+        move |criticality: &str, name: &str, description: &str| -> Result<(), Box<EvalAltResult>> {
+            findings
+                .borrow_mut()
+                .findings
+                .push(new_finding(criticality, name, description, None)?);
+            Ok(())
+        },
+    );
+
+    let findings = Rc::clone(output);
+    let _ = engine.register_fn(
+        "add_finding",
+        move |criticality: &str,
+              name: &str,
+              description: &str,
+              file: &str,
+              line: INT|
+              -> Result<(), Box<EvalAltResult>> {
+            let line = if line > 0 { line as usize } else { 0 };
+            findings.borrow_mut().findings.push(new_finding(
+                criticality,
+                name,
+                description,
+                Some((PathBuf::from(file), line)),
+            )?);
+            Ok(())
+        },
+    );
+
+    let annotations = Rc::clone(output);
+    let _ = engine.register_fn("annotate", move |key: &str, value: &str| {
+        annotations
+            .borrow_mut()
+            .annotations
+            .push((key.to_owned(), value.to_owned()));
+    });
+
+    engine
+}
+
+/// Gets the manifest data given to the `on_manifest` hook of the scripts.
+fn manifest_map(manifest: &Manifest) -> Value {
+    let components: Vec<Value> = manifest
+        .components()
+        .map(|component| {
+            json!({
+                "kind": component.kind().as_str(),
+                "name": component.name(),
+                "exported": component.exported(),
+                "exposed": component.is_exposed(manifest.min_sdk()),
+                "launch_mode": component.launch_mode(),
+                "task_affinity": component.task_affinity(),
+                "intent_filters": component.intent_filters().collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    json!({
+        "package": manifest.package(),
+        "label": manifest.label(),
+        "version_name": manifest.version_str(),
+        "version_code": manifest.version_number(),
+        "min_sdk": manifest.min_sdk(),
+        "target_sdk": manifest.target_sdk(),
+        "debuggable": manifest.is_debug(),
+        "allow_backup": manifest.allows_backup(),
+        "uses_cleartext_traffic": manifest.uses_cleartext_traffic(),
+        "network_security_config": manifest.network_security_config(),
+        "test_only": manifest.is_test_only(),
+        "unknown_permissions": manifest.unknown_permissions().collect::<Vec<_>>(),
+        "custom_permissions": manifest.custom_permissions().collect::<Vec<_>>(),
+        "components": components,
+        "xml": manifest.code(),
+    })
+}
+
+/// Creates a new finding, checking its criticality.
+fn new_finding(
+    criticality: &str,
+    name: &str,
+    description: &str,
+    location: Option<(PathBuf, usize)>,
+) -> Result<Finding, Box<EvalAltResult>> {
+    let criticality = Criticality::from_str(criticality)
+        .map_err(|e| format!("invalid criticality `{}`: {}", criticality, e))?;
+
+    Ok(Finding {
+        criticality,
+        name: name.to_owned(),
+        description: description.to_owned(),
+        location,
+    })
+}
+
+/// Checks if the given script defines a function with the given name and number of parameters.
+fn defines(ast: &AST, name: &str, arity: usize) -> bool {
+    ast.iter_functions()
+        .any(|f| f.name == name && f.params.len() == arity)
+}
+
+/// Gets the paths of all the scripts in the given folder, sorted by name.
+fn script_paths<P: AsRef<Path>>(folder: P) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "rhai") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{create_engine, defines, Output};
+    use crate::criticality::Criticality;
+
+    /// Checks that scripts can add findings and annotations.
+    #[test]
+    fn it_script_output() {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let engine = create_engine(&output, 1_000_000);
+        let ast = engine
+            .compile(
+                r#"
+                fn on_file(path, contents) {
+                    if contents.contains("secret") {
+                        add_finding("high", "Secret", "A secret was found.", path, 3);
+                    }
+                    annotate("last_file", path);
+                }
+                "#,
+            )
+            .unwrap();
+
+        assert!(defines(&ast, "on_file", 2));
+        assert!(!defines(&ast, "on_manifest", 1));
+
+        let _: rhai::Dynamic = engine
+            .call_fn(
+                &mut rhai::Scope::new(),
+                &ast,
+                "on_file",
+                (
+                    String::from("A.java"),
+                    String::from("String secret = \"\";"),
+                ),
+            )
+            .unwrap();
+
+        let output = output.borrow();
+        assert_eq!(output.findings.len(), 1);
+        assert_eq!(output.findings[0].criticality, Criticality::High);
+        assert_eq!(output.findings[0].name, "Secret");
+        assert_eq!(
+            output.annotations,
+            vec![(String::from("last_file"), String::from("A.java"))]
+        );
+    }
+
+    /// Checks that invalid criticalities are reported as script errors.
+    #[test]
+    fn it_invalid_criticality() {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let engine = create_engine(&output, 1_000_000);
+
+        assert!(engine
+            .run(r#"add_finding("super", "Name", "Description");"#)
+            .is_err());
+        assert!(output.borrow().findings.is_empty());
+    }
+
+    /// Checks that scripts that never return or recurse forever fail instead of hanging.
+    #[test]
+    fn it_script_limits() {
+        let output = Rc::new(RefCell::new(Output::default()));
+        let engine = create_engine(&output, 1_000_000);
+
+        assert!(engine.run("loop {}").is_err());
+        assert!(engine.run("fn f(x) { f(x + 1) } f(0);").is_err());
+    }
+}
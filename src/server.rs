@@ -0,0 +1,338 @@
+//! Server module.
+//!
+//! This module implements the `serve` subcommand, that runs SUPER as a service with a small REST
+//! API, so that CI pipelines and internal portals can request analyses over HTTP. It is only
+//! available with the `server` feature.
+//!
+//! * `POST /analyze` uploads an application in the request body and queues its analysis. The
+//!   application is named after the `name` query parameter, or after the hash of its contents if
+//!   it is not given: `curl --data-binary @app.apk http://localhost:8080/analyze?name=app`.
+//! * `GET /results/<package>` returns the JSON results of the analysis of the given package, or
+//!   its status if it has not finished yet.
+//! * `GET /metrics` returns the metrics of the analyses run by the server, in the Prometheus text
+//!   exposition format, so that they can be scraped.
+//!
+//! Analyses are run one by one, in the order they were requested, by a single worker thread.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Cursor, Read},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use clap::ArgMatches;
+use failure::{format_err, Error, ResultExt};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    analyze_package,
+    i18n::tr_args,
+    metrics, print_message, print_warning, stats,
+    utils::{is_valid_package_name, sha256_hex},
+    Config,
+};
+
+/// Default address of the server.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Maximum size of an uploaded application, in bytes.
+const MAX_UPLOAD_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Status of a requested analysis.
+#[derive(Debug, Clone, PartialEq)]
+enum JobStatus {
+    /// Waiting for the previous analyses to finish.
+    Queued,
+    /// Being analyzed.
+    Running,
+    /// Analyzed, its results are available.
+    Done,
+    /// The analysis failed with the given error.
+    Failed(String),
+}
+
+impl JobStatus {
+    /// Gets the JSON representation of the status of the given package.
+    fn to_json(&self, package: &str) -> Value {
+        let status = match *self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Done => "done",
+            JobStatus::Failed(_) => "failed",
+        };
+        let mut json = json!({
+            "package": package,
+            "status": status,
+            "results": format!("/results/{}", package),
+        });
+        if let JobStatus::Failed(ref error) = *self {
+            json["error"] = Value::from(error.as_str());
+        }
+        json
+    }
+}
+
+/// Endpoint of the API requested by a client.
+#[derive(Debug, PartialEq)]
+enum Route<'u> {
+    /// Uploads an application, with an optional name, and queues its analysis.
+    Analyze(Option<&'u str>),
+    /// Gets the results of the given package.
+    Results(&'u str),
+    /// Gets the metrics of the analyses.
+    Metrics,
+    /// Unknown endpoint.
+    NotFound,
+}
+
+impl<'u> Route<'u> {
+    /// Gets the route of the given request.
+    fn of(method: &Method, url: &'u str) -> Self {
+        let mut url = url.splitn(2, '?');
+        let path = url.next().unwrap_or("");
+        let query = url.next().unwrap_or("");
+
+        match (method, path.trim_end_matches('/')) {
+            (&Method::Post, "/analyze") => Route::Analyze(
+                query
+                    .split('&')
+                    .find(|param| param.starts_with("name="))
+                    .map(|param| &param["name=".len()..]),
+            ),
+            (&Method::Get, path) if path.starts_with("/results/") => {
+                Route::Results(&path["/results/".len()..])
+            }
+            (&Method::Get, "/metrics") => Route::Metrics,
+            _ => Route::NotFound,
+        }
+    }
+}
+
+/// State shared by the request handlers.
+#[derive(Debug)]
+struct Api {
+    /// Folder where the uploaded applications are stored.
+    downloads_folder: PathBuf,
+    /// Folder where the results are generated.
+    results_folder: PathBuf,
+    /// Status of the requested analyses, by package.
+    jobs: Arc<Mutex<BTreeMap<String, JobStatus>>>,
+    /// Queue of the analyses, with the package and its path.
+    queue: Sender<(String, PathBuf)>,
+}
+
+impl Api {
+    /// Handles the given request.
+    fn handle(&self, request: &mut Request) -> Response<Cursor<Vec<u8>>> {
+        let url = request.url().to_owned();
+        match Route::of(request.method(), &url) {
+            Route::Analyze(name) => match self.analyze(request, name) {
+                Ok(json) => json_response(202, &json),
+                Err(e) => json_response(400, &json!({ "error": e.to_string() })),
+            },
+            Route::Results(package) => self.results(package),
+            Route::Metrics => Response::from_string(metrics::render())
+                .with_status_code(200)
+                .with_header(metrics_header()),
+            Route::NotFound => json_response(404, &json!({ "error": "not found" })),
+        }
+    }
+
+    /// Stores the uploaded application and queues its analysis.
+    fn analyze(&self, request: &mut Request, name: Option<&str>) -> Result<Value, Error> {
+        let mut apk = Vec::new();
+        let _ = request
+            .as_reader()
+            .take(MAX_UPLOAD_SIZE + 1)
+            .read_to_end(&mut apk)
+            .context("could not read the uploaded application")?;
+        if apk.is_empty() {
+            return Err(format_err!("no application was uploaded"));
+        }
+        if apk.len() as u64 > MAX_UPLOAD_SIZE {
+            return Err(format_err!(
+                "the uploaded application is bigger than {} bytes",
+                MAX_UPLOAD_SIZE
+            ));
+        }
+
+        let package = match name {
+            Some(name) if is_valid_package_name(name) => name.to_owned(),
+            Some(name) => return Err(format_err!("invalid package name `{}`", name)),
+            None => format!("upload-{}", &sha256_hex(&apk)[..16]),
+        };
+
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|_| format_err!("the analysis queue is poisoned"))?;
+        if let Some(status) = jobs.get(&package) {
+            if *status == JobStatus::Queued || *status == JobStatus::Running {
+                return Err(format_err!("`{}` is already being analyzed", package));
+            }
+        }
+
+        let path = self.downloads_folder.join(format!("{}.apk", package));
+        fs::write(&path, apk).context(format_err!(
+            "could not store the application in `{}`",
+            path.display()
+        ))?;
+        self.queue
+            .send((package.clone(), path))
+            .map_err(|_| format_err!("the analysis worker is not running"))?;
+        let _ = jobs.insert(package.clone(), JobStatus::Queued);
+
+        Ok(JobStatus::Queued.to_json(&package))
+    }
+
+    /// Gets the results of the given package, or the status of its analysis.
+    fn results(&self, package: &str) -> Response<Cursor<Vec<u8>>> {
+        if !is_valid_package_name(package) {
+            return json_response(400, &json!({ "error": "invalid package name" }));
+        }
+
+        let status = self
+            .jobs
+            .lock()
+            .ok()
+            .and_then(|jobs| jobs.get(package).cloned());
+        match status {
+            Some(status @ JobStatus::Queued) | Some(status @ JobStatus::Running) => {
+                json_response(202, &status.to_json(package))
+            }
+            Some(failed @ JobStatus::Failed(_)) => json_response(500, &failed.to_json(package)),
+            Some(JobStatus::Done) | None => {
+                let path = self.results_folder.join(package).join("results.json");
+                match fs::read(&path) {
+                    Ok(results) => Response::from_data(results)
+                        .with_status_code(200)
+                        .with_header(json_header()),
+                    Err(_) => json_response(404, &json!({ "error": "no results for the package" })),
+                }
+            }
+        }
+    }
+}
+
+/// Gets the content type header of the JSON responses.
+fn json_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("invalid content type header")
+}
+
+/// Gets the content type header of the metrics responses.
+fn metrics_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..])
+        .expect("invalid content type header")
+}
+
+/// Creates a JSON response with the given status code.
+fn json_response(status: u16, json: &Value) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(json_header())
+}
+
+/// Runs the `serve` subcommand.
+///
+/// The configuration is used for every analysis, always generating the JSON results.
+pub fn run(mut config: Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let address = cli.value_of("address").unwrap_or(DEFAULT_ADDRESS);
+    let server = Server::http(address)
+        .map_err(|e| format_err!("could not listen on `{}`: {}", address, e))?;
+    config.set_json();
+
+    let jobs = Arc::new(Mutex::new(BTreeMap::new()));
+    let (queue, analyses) = mpsc::channel::<(String, PathBuf)>();
+    let api = Api {
+        downloads_folder: config.downloads_folder().to_path_buf(),
+        results_folder: config.results_folder().to_path_buf(),
+        jobs: Arc::clone(&jobs),
+        queue,
+    };
+
+    let worker_jobs = Arc::clone(&jobs);
+    let _ = thread::spawn(move || {
+        for (package, path) in analyses {
+            let set_status = |status| {
+                if let Ok(mut jobs) = worker_jobs.lock() {
+                    let _ = jobs.insert(package.clone(), status);
+                }
+            };
+            set_status(JobStatus::Running);
+
+            config.select_package(&path);
+            config.set_force();
+            let status = match analyze_package(&path, &mut config, &mut BTreeMap::new()) {
+                Ok(_) => JobStatus::Done,
+                Err(e) => {
+                    print_warning(format!("the analysis of `{}` failed: {}", package, e));
+                    JobStatus::Failed(
+                        e.iter_chain()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(": "),
+                    )
+                }
+            };
+            // The server does not write run statistics, so they are not kept.
+            stats::remove_package(&config.package_name(&path));
+            set_status(status);
+        }
+    });
+
+    print_message(tr_args("Listening on http://{}.", &[&address]));
+    for mut request in server.incoming_requests() {
+        let response = api.handle(&mut request);
+        if let Err(e) = request.respond(response) {
+            print_warning(format!("could not send the response: {}", e));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tiny_http::Method;
+
+    use super::{JobStatus, Route};
+
+    /// Checks the routing of the requests.
+    #[test]
+    fn it_route() {
+        assert_eq!(Route::of(&Method::Post, "/analyze"), Route::Analyze(None));
+        assert_eq!(
+            Route::of(&Method::Post, "/analyze/?name=app&force=1"),
+            Route::Analyze(Some("app"))
+        );
+        assert_eq!(
+            Route::of(&Method::Get, "/results/com.example"),
+            Route::Results("com.example")
+        );
+        assert_eq!(Route::of(&Method::Get, "/metrics"), Route::Metrics);
+        assert_eq!(Route::of(&Method::Get, "/metrics/"), Route::Metrics);
+        assert_eq!(Route::of(&Method::Get, "/analyze"), Route::NotFound);
+        assert_eq!(Route::of(&Method::Post, "/metrics"), Route::NotFound);
+        assert_eq!(Route::of(&Method::Post, "/results/app"), Route::NotFound);
+    }
+
+    /// Checks the JSON representation of the status of the analyses.
+    #[test]
+    fn it_status_json() {
+        let json = JobStatus::Queued.to_json("app");
+        assert_eq!(json["status"], "queued");
+        assert_eq!(json["results"], "/results/app");
+        assert!(json.get("error").is_none());
+
+        let json = JobStatus::Failed("no manifest".to_owned()).to_json("app");
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["error"], "no manifest");
+    }
+}
@@ -0,0 +1,321 @@
+//! Tool setup module.
+//!
+//! SUPER needs some Java tools to convert and decompile the code of the applications. This module
+//! implements the `setup` subcommand, that downloads pinned versions of them from their official
+//! releases, verifies their SHA-256 checksum and installs them in the tools folder of the
+//! configuration, so that they are used when the configured paths do not exist:
+//!
+//! ```text
+//! tools/
+//! ├── dex2jar-2.1/
+//! └── jadx-1.4.7/
+//! ```
+//!
+//! Tools without a pinned checksum are only installed with `--allow-unpinned`, and their checksum
+//! is printed so that it can be pinned. Downloading requires the `setup` feature, while the
+//! installed tools are always used.
+//!
+//! Not every tool used by SUPER is installed by the setup:
+//!
+//! - _jd-cmd_ (`jd-cli`), the default decompiler, is a single JAR file shipped with SUPER in the
+//!   `vendor` folder of the packages, so there is nothing to download.
+//! - _Apktool_ is not needed, since the resources and the binary XML files of the applications
+//!   are decoded natively with `abxml`.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "setup")]
+use std::{
+    fs::{self, File},
+    io::{self, Cursor},
+};
+
+use clap::ArgMatches;
+use colored::Colorize;
+use failure::Error;
+#[cfg(feature = "setup")]
+use failure::{bail, format_err, ResultExt};
+#[cfg(feature = "setup")]
+use zip::ZipArchive;
+
+#[cfg(feature = "setup")]
+use crate::{
+    http::{client, download},
+    print_message, print_warning,
+    utils::sha256_hex,
+};
+use crate::{i18n::tr_args, Config};
+
+/// Tools installed by the setup.
+///
+/// Every tool must have its checksum pinned before a release, by downloading the archive of the
+/// pinned version from its official release page and computing its SHA-256 checksum. Until then,
+/// it can only be installed with `--allow-unpinned`, that prints the checksum of the downloaded
+/// archive.
+pub(crate) const TOOLS: [Tool; 2] = [
+    Tool {
+        kind: ToolKind::Dex2jar,
+        name: "dex2jar",
+        version: "2.1",
+        url: "https://github.com/pxb1988/dex2jar/releases/download/v2.1/dex2jar-2.1.zip",
+        sha256: None,
+    },
+    Tool {
+        kind: ToolKind::Jadx,
+        name: "jadx",
+        version: "1.4.7",
+        url: "https://github.com/skylot/jadx/releases/download/v1.4.7/jadx-1.4.7.zip",
+        sha256: None,
+    },
+];
+
+/// Kind of tool, that determines the option of the configuration it sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ToolKind {
+    /// _Dex2jar_, that sets the `dex2jar_folder`.
+    Dex2jar,
+    /// _jadx_, that sets the `jadx_file`.
+    Jadx,
+}
+
+/// Tool installed by the setup.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tool {
+    /// Kind of the tool.
+    kind: ToolKind,
+    /// Name of the tool.
+    name: &'static str,
+    /// Pinned version of the tool.
+    version: &'static str,
+    /// URL of the ZIP archive of the pinned version.
+    #[cfg_attr(not(feature = "setup"), allow(dead_code))]
+    url: &'static str,
+    /// Pinned SHA-256 checksum of the ZIP archive, if it has been pinned.
+    #[cfg_attr(not(feature = "setup"), allow(dead_code))]
+    sha256: Option<&'static str>,
+}
+
+impl Tool {
+    /// Gets the kind of the tool.
+    pub(crate) fn kind(&self) -> ToolKind {
+        self.kind
+    }
+
+    /// Gets the folder where the tool is installed, inside the given tools folder.
+    pub(crate) fn folder(&self, tools_folder: &Path) -> PathBuf {
+        tools_folder.join(format!("{}-{}", self.name, self.version))
+    }
+
+    /// Gets the path that the configuration has to point to, inside the given tools folder.
+    pub(crate) fn path(&self, tools_folder: &Path) -> PathBuf {
+        let folder = self.folder(tools_folder);
+        match self.kind {
+            ToolKind::Dex2jar => folder,
+            ToolKind::Jadx => folder.join("bin").join(if cfg!(target_family = "windows") {
+                "jadx.bat"
+            } else {
+                "jadx"
+            }),
+        }
+    }
+
+    /// Downloads the tool, verifies its checksum and installs it in the given tools folder.
+    ///
+    /// Any previous installation of the same version is replaced.
+    #[cfg(feature = "setup")]
+    pub(crate) fn install(
+        &self,
+        tools_folder: &Path,
+        proxy: Option<&str>,
+        allow_unpinned: bool,
+    ) -> Result<PathBuf, Error> {
+        let data = download(&client(proxy)?, self.url)
+            .context(format_err!("could not download {}", self.name))?;
+
+        let checksum = sha256_hex(&data);
+        match self.sha256 {
+            Some(expected) if !expected.eq_ignore_ascii_case(&checksum) => bail!(
+                "the checksum of {} {} does not match: expected {}, got {}",
+                self.name,
+                self.version,
+                expected,
+                checksum
+            ),
+            Some(_) => {}
+            None if allow_unpinned => print_warning(format!(
+                "the checksum of {} {} is not pinned, it was installed with SHA-256 {}",
+                self.name, self.version, checksum
+            )),
+            None => bail!(
+                "the checksum of {} {} is not pinned, use `--allow-unpinned` to install it anyway",
+                self.name,
+                self.version
+            ),
+        }
+
+        let folder = self.folder(tools_folder);
+        if folder.exists() {
+            fs::remove_dir_all(&folder).context(format_err!(
+                "could not remove the previous installation at `{}`",
+                folder.display()
+            ))?;
+        }
+        extract(&data, &folder).context(format_err!(
+            "could not install {} {}",
+            self.name,
+            self.version
+        ))?;
+
+        Ok(self.path(tools_folder))
+    }
+}
+
+/// Runs the `setup` subcommand.
+#[allow(clippy::print_stdout)]
+pub fn run(config: &mut Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    config.ensure_tools(
+        cli.is_present("force"),
+        cli.value_of("proxy"),
+        cli.is_present("allow-unpinned"),
+    )?;
+    println!(
+        "{}",
+        tr_args(
+            "The tools are installed in {}.",
+            &[&config.tools_folder().display().to_string().bold()]
+        )
+    );
+    Ok(())
+}
+
+/// Installs the given tool, if the `setup` feature is enabled.
+pub(crate) fn install(
+    tool: &Tool,
+    tools_folder: &Path,
+    proxy: Option<&str>,
+    allow_unpinned: bool,
+) -> Result<PathBuf, Error> {
+    #[cfg(feature = "setup")]
+    {
+        print_message(format!("Installing {} {}…", tool.name, tool.version));
+        tool.install(tools_folder, proxy, allow_unpinned)
+    }
+
+    #[cfg(not(feature = "setup"))]
+    {
+        let _ = (tools_folder, proxy, allow_unpinned);
+        Err(failure::format_err!(
+            "{} is not installed and SUPER was built without the `setup` feature, so it cannot \
+             be downloaded",
+            tool.name
+        ))
+    }
+}
+
+/// Extracts the given ZIP archive to the given folder.
+///
+/// If all the files of the archive are in the same top-level folder, that folder is skipped, so
+/// that the tools are always installed in the same layout.
+#[cfg(feature = "setup")]
+fn extract(data: &[u8], folder: &Path) -> Result<(), Error> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).context("invalid ZIP archive")?;
+    let mut names = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        names.push(archive.by_index(i)?.sanitized_name());
+    }
+    let prefix = common_prefix(&names);
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.sanitized_name();
+        let relative = name.strip_prefix(&prefix).unwrap_or(&name);
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let path = folder.join(relative);
+        if entry.name().ends_with('/') {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&path).context(format_err!(
+            "could not create the file `{}`",
+            path.display()
+        ))?;
+        let _ = io::copy(&mut entry, &mut file)?;
+
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            if let Some(mode) = entry.unix_mode() {
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Gets the top-level folder shared by all the given paths, or an empty path if there is none.
+#[cfg(feature = "setup")]
+fn common_prefix(paths: &[PathBuf]) -> PathBuf {
+    let first = match paths
+        .first()
+        .and_then(|path| path.components().next())
+        .map(|component| PathBuf::from(component.as_os_str()))
+    {
+        Some(first) => first,
+        None => return PathBuf::new(),
+    };
+    if paths.iter().all(|path| path.starts_with(&first)) && paths.iter().any(|path| path != &first)
+    {
+        first
+    } else {
+        PathBuf::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{ToolKind, TOOLS};
+
+    /// Checks the installation paths of the tools.
+    #[test]
+    fn it_tool_paths() {
+        let tools = Path::new("tools");
+        let dex2jar = TOOLS
+            .iter()
+            .find(|tool| tool.kind() == ToolKind::Dex2jar)
+            .unwrap();
+        assert_eq!(dex2jar.path(tools), tools.join("dex2jar-2.1"));
+
+        let jadx = TOOLS
+            .iter()
+            .find(|tool| tool.kind() == ToolKind::Jadx)
+            .unwrap();
+        assert!(jadx
+            .path(tools)
+            .starts_with(tools.join("jadx-1.4.7").join("bin")));
+    }
+
+    /// Checks the detection of the top-level folder of the archives.
+    #[cfg(feature = "setup")]
+    #[test]
+    fn it_common_prefix() {
+        use super::common_prefix;
+        use std::path::PathBuf;
+
+        let paths = vec![
+            PathBuf::from("dex-tools-2.1"),
+            PathBuf::from("dex-tools-2.1/d2j-dex2jar.sh"),
+            PathBuf::from("dex-tools-2.1/lib/dex-tools-2.1.jar"),
+        ];
+        assert_eq!(common_prefix(&paths), Path::new("dex-tools-2.1"));
+
+        let paths = vec![PathBuf::from("bin/jadx"), PathBuf::from("lib/jadx.jar")];
+        assert_eq!(common_prefix(&paths), Path::new(""));
+    }
+}
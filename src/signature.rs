@@ -0,0 +1,135 @@
+//! Rule pack signature verification module.
+//!
+//! Rule packs can be signed with [minisign](https://jedisct1.github.io/minisign/), by running
+//! `minisign -Sm rules.json`. The signature is stored in a `.minisig` file next to the rules file,
+//! and it is verified against the `trusted_keys` of the configuration before loading the rules:
+//!
+//! ```toml
+//! trusted_keys = ["RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3"]
+//! require_signed_rules = true
+//! ```
+//!
+//! A rule pack with an invalid signature is never loaded. Unsigned rule packs are loaded with a
+//! warning, unless signed rules are required.
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use failure::{bail, format_err, Error, ResultExt};
+use minisign_verify::{PublicKey, Signature};
+
+use crate::{print_warning, Config};
+
+/// Extension of the signature files.
+const SIGNATURE_EXTENSION: &str = "minisig";
+
+/// Reads the given rule pack, verifying its signature.
+///
+/// The contents of the rule pack are only returned if they are correctly signed by one of the
+/// trusted keys, or if they are not signed and signed rules are not required.
+pub(crate) fn read_rules<P: AsRef<Path>>(config: &Config, path: P) -> Result<Vec<u8>, Error> {
+    let path = path.as_ref();
+    let rules = fs::read(path).context(format_err!(
+        "could not read the rules file `{}`",
+        path.display()
+    ))?;
+
+    let signature_path = signature_path(path);
+    if signature_path.exists() {
+        let signature = fs::read_to_string(&signature_path).context(format_err!(
+            "could not read the signature file `{}`",
+            signature_path.display()
+        ))?;
+        let keys = config
+            .trusted_keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        verify(&rules, &signature, &keys).context(format_err!(
+            "the signature of the rules file `{}` is not valid",
+            path.display()
+        ))?;
+    } else if config.is_require_signed_rules() {
+        bail!(
+            "the rules file `{}` is not signed, and signed rules are required",
+            path.display()
+        );
+    } else if config.trusted_keys().next().is_some() {
+        print_warning(format!(
+            "the rules file `{}` is not signed, its provenance cannot be verified",
+            path.display()
+        ));
+    }
+
+    Ok(rules)
+}
+
+/// Gets the path of the signature file for the given rules file.
+pub(crate) fn signature_path(path: &Path) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map_or_else(OsString::new, |name| name.to_os_string());
+    file_name.push(".");
+    file_name.push(SIGNATURE_EXTENSION);
+    path.with_file_name(file_name)
+}
+
+/// Verifies the given minisign signature of the data with the given trusted keys.
+pub(crate) fn verify(data: &[u8], signature: &str, keys: &[&str]) -> Result<(), Error> {
+    if keys.is_empty() {
+        bail!("there are no trusted keys to verify the signature");
+    }
+    let signature =
+        Signature::decode(signature).map_err(|e| format_err!("invalid signature format: {}", e))?;
+
+    for key in keys {
+        let key = PublicKey::from_base64(key)
+            .map_err(|e| format_err!("invalid trusted key `{}`: {}", key, e))?;
+        if key.verify(data, &signature, false).is_ok() {
+            return Ok(());
+        }
+    }
+
+    bail!("the data was not signed by any of the trusted keys")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{signature_path, verify};
+
+    /// Public key used to sign the test data.
+    const PUBLIC_KEY: &str = "RWRTVVBFUjEyMwOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+
+    /// Signature of the test data.
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RURTVVBFUjEyM7pJjwBJu8Wh5eFJlbC/3HyGyI6mtlEkvd5QyzmTbxYba3TH+bO4vjaUxaFqGiB9aD1ovgmQaEGLwkEprb3ZNAY=
+trusted comment: timestamp:1700000000\tfile:rules.json
+eDG8oh1m4JEid4b0xMVqoKchSEZVq76PnacOFH+MxlUD7bHeOMthyhYVXgiBRTh2mq5jI+BLqB3gsmHtcwsNAg==
+";
+
+    /// Checks the verification of rule pack signatures.
+    #[test]
+    fn it_verify() {
+        let other_key = "RWQAAAAAAAAAAHm1Vi6P5lT5QHixEuipi6eQH4U65pW+1+DjkQutBJZk";
+
+        assert!(verify(b"[]\n", SIGNATURE, &[PUBLIC_KEY]).is_ok());
+        assert!(verify(b"[]\n", SIGNATURE, &[other_key, PUBLIC_KEY]).is_ok());
+        assert!(verify(b"[{}]\n", SIGNATURE, &[PUBLIC_KEY]).is_err());
+        assert!(verify(b"[]\n", SIGNATURE, &[other_key]).is_err());
+        assert!(verify(b"[]\n", SIGNATURE, &[]).is_err());
+        assert!(verify(b"[]\n", "invalid", &[PUBLIC_KEY]).is_err());
+    }
+
+    /// Checks the path of the signature files.
+    #[test]
+    fn it_signature_path() {
+        assert_eq!(
+            signature_path(Path::new("/etc/super-analyzer/rules.json")),
+            Path::new("/etc/super-analyzer/rules.json.minisig")
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Custom analyzers module.
+//!
+//! Applications that embed SUPER as a library can add their own analysis passes by implementing
+//! the [`Analyzer`] trait and registering them with [`register_analyzer()`]. Registered analyzers
+//! run in the order they were registered, after the built-in manifest, certificate and code
+//! analyses, so that they can inspect the results of those analyses, and they receive an
+//! [`AnalysisContext`] with the configuration, the package and the manifest of the application.
+//!
+//! The analyzers run over a snapshot of the registry, so they can register new analyzers, that
+//! will run in the following analyses.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use lazy_static::lazy_static;
+
+use crate::{
+    i18n::tr_args, print_message, results::Results, static_analysis::manifest::Manifest, Config,
+};
+
+lazy_static! {
+    /// Registered analyzers, in the order they are run.
+    static ref ANALYZERS: Mutex<Vec<Arc<dyn Analyzer>>> = Mutex::new(Vec::new());
+}
+
+/// Context of the analysis given to the custom analyzers.
+#[derive(Debug)]
+pub struct AnalysisContext<'a> {
+    /// Configuration of the analysis.
+    config: &'a Config,
+    /// Package of the analyzed application.
+    package: &'a str,
+    /// Manifest of the application, if it could be analyzed.
+    manifest: Option<&'a Manifest>,
+    /// Folder with the extracted and decompiled files of the application.
+    dist_folder: PathBuf,
+}
+
+impl<'a> AnalysisContext<'a> {
+    /// Creates the context for the analysis of the given package.
+    pub fn new(config: &'a Config, package: &'a str, manifest: Option<&'a Manifest>) -> Self {
+        Self {
+            config,
+            package,
+            manifest,
+            dist_folder: config.dist_folder().join(package),
+        }
+    }
+
+    /// Gets the configuration of the analysis.
+    pub fn config(&self) -> &Config {
+        self.config
+    }
+
+    /// Gets the package of the analyzed application.
+    pub fn package(&self) -> &str {
+        self.package
+    }
+
+    /// Gets the manifest of the application, if it could be analyzed.
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest
+    }
+
+    /// Gets the folder with the extracted and decompiled files of the application.
+    ///
+    /// The decompiled sources are in its `classes` folder, unless the analysis profile skips the
+    /// decompilation.
+    pub fn dist_folder(&self) -> &Path {
+        &self.dist_folder
+    }
+}
+
+/// Trait that represents a custom analysis pass.
+///
+/// Analyzers are shared by all the analyses, that can run in parallel, so they must be `Sync`.
+pub trait Analyzer: Send + Sync {
+    /// Gets the name of the analyzer, used in the messages for the user.
+    fn name(&self) -> &str;
+
+    /// Checks if the analyzer has to run with the given configuration.
+    ///
+    /// Analyzers run in every analysis by default.
+    fn is_enabled(&self, _config: &Config) -> bool {
+        true
+    }
+
+    /// Analyzes the application, adding its findings to the results.
+    fn analyze(&self, ctx: &AnalysisContext<'_>, results: &mut Results);
+}
+
+/// Registers the given analyzer, that will run after the analyzers already registered.
+pub fn register_analyzer<A: Analyzer + 'static>(analyzer: A) {
+    analyzers().push(Arc::new(analyzer));
+}
+
+/// Gets the registered analyzers.
+fn analyzers() -> MutexGuard<'static, Vec<Arc<dyn Analyzer>>> {
+    ANALYZERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Runs the registered analyzers over the given application.
+pub fn analysis<S: AsRef<str>>(
+    config: &Config,
+    package: S,
+    manifest: Option<&Manifest>,
+    results: &mut Results,
+) {
+    let ctx = AnalysisContext::new(config, package.as_ref(), manifest);
+    // The registry is not locked while the analyzers run, so that they can register analyzers.
+    let snapshot = analyzers().clone();
+    for analyzer in snapshot {
+        if !analyzer.is_enabled(config) {
+            continue;
+        }
+        if config.is_verbose() {
+            print_message(tr_args("Running the {} analyzer.", &[&analyzer.name()]));
+        }
+        analyzer.analyze(&ctx, results);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs::File};
+
+    use zip::ZipWriter;
+
+    use super::{analysis, register_analyzer, AnalysisContext, Analyzer};
+    use crate::{
+        criticality::Criticality,
+        results::{Results, Vulnerability},
+        Config,
+    };
+
+    /// Analyzer that is only enabled in verbose analyses.
+    struct VerboseAnalyzer;
+
+    impl Analyzer for VerboseAnalyzer {
+        fn name(&self) -> &str {
+            "verbose"
+        }
+
+        fn is_enabled(&self, config: &Config) -> bool {
+            config.is_verbose()
+        }
+
+        fn analyze(&self, _ctx: &AnalysisContext<'_>, _results: &mut Results) {}
+    }
+
+    /// Analyzer that reports a finding in the test package, and registers another analyzer.
+    struct FindingAnalyzer;
+
+    impl Analyzer for FindingAnalyzer {
+        fn name(&self) -> &str {
+            "finding"
+        }
+
+        fn analyze(&self, ctx: &AnalysisContext<'_>, results: &mut Results) {
+            if ctx.package() != "com.example.analyzer" {
+                return;
+            }
+            results.add_vulnerability(
+                Vulnerability::builder(Criticality::High, "Custom finding", "Found by a test.")
+                    .rule_id("custom-analyzer")
+                    .build(),
+            );
+            register_analyzer(VerboseAnalyzer);
+        }
+    }
+
+    /// Checks that the findings of the registered analyzers are added to the results, and that
+    /// analyzers can register analyzers while they run.
+    #[test]
+    fn it_analysis() {
+        let package = env::temp_dir().join("super-analyzer-test.apk");
+        let _ = ZipWriter::new(File::create(&package).unwrap())
+            .finish()
+            .unwrap();
+        let config = Config::default();
+        let mut results = Results::init(&config, &package).unwrap();
+
+        register_analyzer(FindingAnalyzer);
+        analysis(&config, "com.example.analyzer", None, &mut results);
+        assert!(results
+            .vulnerabilities()
+            .any(|vulnerability| vulnerability.rule_id() == Some("custom-analyzer")));
+    }
+
+    /// Checks the context given to the analyzers.
+    #[test]
+    fn it_analysis_context() {
+        let config = Config::default();
+        let ctx = AnalysisContext::new(&config, "com.example", None);
+        assert_eq!(ctx.package(), "com.example");
+        assert!(ctx.manifest().is_none());
+        assert_eq!(ctx.dist_folder(), config.dist_folder().join("com.example"));
+
+        let analyzer = VerboseAnalyzer;
+        assert_eq!(analyzer.name(), "verbose");
+        assert_eq!(analyzer.is_enabled(&config), config.is_verbose());
+    }
+}
@@ -0,0 +1,161 @@
+//! Syntax-based rule matching module.
+//!
+//! Rules can include a `query` attribute with a [tree-sitter query][queries] over the syntax tree
+//! of the Java code, so that they match on the structure of the code, such as a method invocation
+//! with a string literal as argument, instead of its raw text. Commented-out code and code
+//! embedded in strings is never matched by a query.
+//!
+//! ```json
+//! {
+//!     "label": "Weak Algorithms",
+//!     "regex": "getInstance",
+//!     "query": "(method_invocation name: (identifier) @name (#eq? @name \"getInstance\") arguments: (argument_list . (string_literal) @match (#match? @match \"MD5|SHA-?1\")))"
+//! }
+//! ```
+//!
+//! A query rule reports the text of its `@match` capture, or of all its captures if it has no
+//! `@match` capture. Its regular expression only selects the files where the query is run.
+//!
+//! Queries are only run if SUPER is built with the `ast` feature. Otherwise, they are validated
+//! but rules fall back to their regular expression.
+//!
+//! [queries]: https://tree-sitter.github.io/tree-sitter/using-parsers#query-syntax
+
+use std::fmt;
+#[cfg(feature = "ast")]
+use std::ops::Range;
+
+#[cfg(feature = "ast")]
+use failure::format_err;
+use failure::Error;
+use serde::de::{self, Deserialize, Deserializer};
+#[cfg(feature = "ast")]
+use tree_sitter::{Parser, Query, QueryCursor, Tree};
+
+/// Name of the capture that delimits the reported code of a match.
+#[cfg(feature = "ast")]
+const MATCH_CAPTURE: &str = "match";
+
+/// Query over the syntax tree of the Java code.
+pub struct AstQuery {
+    /// Source of the query.
+    source: String,
+    /// Compiled query.
+    #[cfg(feature = "ast")]
+    query: Query,
+}
+
+impl AstQuery {
+    /// Compiles the given query.
+    ///
+    /// Without the `ast` feature, the query is stored but never compiled.
+    pub fn new<S: Into<String>>(source: S) -> Result<Self, Error> {
+        let source = source.into();
+
+        #[cfg(feature = "ast")]
+        {
+            let query = Query::new(tree_sitter_java::language(), &source).map_err(|e| {
+                format_err!(
+                    "invalid syntax query at line {}, column {}: {}",
+                    e.row + 1,
+                    e.column + 1,
+                    e.message
+                )
+            })?;
+            Ok(Self { source, query })
+        }
+
+        #[cfg(not(feature = "ast"))]
+        {
+            Ok(Self { source })
+        }
+    }
+
+    /// Finds the matches of the query in the given syntax tree of the given code.
+    ///
+    /// Matches are returned as byte ranges of the code, sorted and without duplicates.
+    #[cfg(feature = "ast")]
+    pub fn find(&self, tree: &Tree, code: &str) -> Vec<Range<usize>> {
+        let match_index = self.query.capture_index_for_name(MATCH_CAPTURE);
+        let mut cursor = QueryCursor::new();
+        let mut ranges = cursor
+            .matches(&self.query, tree.root_node(), code.as_bytes())
+            .filter_map(|m| {
+                let captures = m
+                    .captures
+                    .iter()
+                    .filter(|capture| match_index.map_or(true, |index| capture.index == index));
+                let start = captures.clone().map(|c| c.node.start_byte()).min()?;
+                let end = captures.map(|c| c.node.end_byte()).max()?;
+                Some(start..end)
+            })
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| (range.start, range.end));
+        ranges.dedup();
+        ranges
+    }
+}
+
+impl fmt::Debug for AstQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("AstQuery").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for AstQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for AstQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        Self::new(source).map_err(de::Error::custom)
+    }
+}
+
+/// Parses the given Java code.
+#[cfg(feature = "ast")]
+pub fn parse(code: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(tree_sitter_java::language()).ok()?;
+    parser.parse(code, None)
+}
+
+#[cfg(all(test, feature = "ast"))]
+mod tests {
+    use super::{parse, AstQuery};
+
+    /// Checks the matching of a query, ignoring comments and strings.
+    #[test]
+    fn it_find() {
+        let query = AstQuery::new(
+            r#"(method_invocation
+                name: (identifier) @name (#eq? @name "getInstance")
+                arguments: (argument_list . (string_literal) @match (#match? @match "MD5")))"#,
+        )
+        .unwrap();
+        let code = "class Hash {\n    void hash() {\n        \
+                    // MessageDigest.getInstance(\"MD5\");\n        \
+                    log(\"MessageDigest.getInstance(\\\"MD5\\\")\");\n        \
+                    MessageDigest.getInstance(\"MD5\");\n        \
+                    MessageDigest.getInstance(\"SHA-256\");\n    }\n}\n";
+
+        let tree = parse(code).unwrap();
+        let matches = query.find(&tree, code);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(&code[matches[0].clone()], "\"MD5\"");
+        assert_eq!(code[..matches[0].start].lines().count(), 5);
+    }
+
+    /// Checks that invalid queries are rejected.
+    #[test]
+    fn it_invalid_query() {
+        assert!(AstQuery::new("(method_invocation").is_err());
+        assert!(AstQuery::new("(unknown_node) @match").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Backup rules module.
+//!
+//! Applications that allow backups can choose which files are backed up with XML resources
+//! referenced from the `<application>` element: `android:fullBackupContent` for Android 11 and
+//! lower, and `android:dataExtractionRules` for Android 12 and higher, that has separate rules for
+//! cloud backups and device-to-device transfers. Without rules, everything is backed up, including
+//! the databases and shared preferences, where applications usually store tokens and personal
+//! data.
+//!
+//! A section with `<include>` rules only backs up the included paths, while a section with only
+//! `<exclude>` rules backs up everything but the excluded paths.
+
+use std::{fmt, fs, path::Path, slice::Iter};
+
+use failure::{format_err, Error, ResultExt};
+use xml::{
+    attribute::OwnedAttribute,
+    reader::{EventReader, XmlEvent},
+};
+
+use crate::{axml, criticality::Criticality, PARSER_CONFIG};
+
+/// Domains of the backed up files that usually contain sensitive data.
+const SENSITIVE_DOMAINS: [&str; 2] = ["database", "sharedpref"];
+
+/// Path included in or excluded from backups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupPath {
+    /// Domain of the path, such as `database` or `sharedpref`.
+    domain: String,
+    /// Path inside the domain, `.` for the whole domain.
+    path: String,
+}
+
+impl BackupPath {
+    /// Creates a backup path from the attributes of an `<include>` or `<exclude>` element.
+    fn from_attributes<A>(attributes: A) -> Self
+    where
+        A: IntoIterator<Item = OwnedAttribute>,
+    {
+        let mut domain = String::new();
+        let mut path = String::from(".");
+        for attr in attributes {
+            match attr.name.local_name.as_str() {
+                "domain" => domain = attr.value,
+                "path" => path = attr.value,
+                _ => {}
+            }
+        }
+
+        Self { domain, path }
+    }
+
+    /// Returns the domain of the path.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Returns the path inside the domain.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Checks if the path covers the whole domain.
+    pub fn is_whole_domain(&self) -> bool {
+        self.path.is_empty() || self.path == "." || self.path == "/"
+    }
+
+    /// Checks if the path can contain sensitive data: a sensitive domain, or the root of the
+    /// application data, that contains them.
+    fn is_sensitive(&self) -> bool {
+        SENSITIVE_DOMAINS.contains(&self.domain.as_str())
+            || (self.domain == "root" && self.is_whole_domain())
+    }
+}
+
+impl fmt::Display for BackupPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_whole_domain() {
+            write!(f, "{}", self.domain)
+        } else {
+            write!(f, "{}/{}", self.domain, self.path.trim_start_matches('/'))
+        }
+    }
+}
+
+/// Section of the backup rules, with its own `<include>` and `<exclude>` rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupSection {
+    /// Name of the section: `full-backup-content`, `cloud-backup` or `device-transfer`.
+    name: String,
+    /// Included paths.
+    includes: Vec<BackupPath>,
+    /// Excluded paths.
+    excludes: Vec<BackupPath>,
+}
+
+impl BackupSection {
+    /// Creates an empty section with the given name.
+    fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            includes: Vec::new(),
+            excludes: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the section.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the included paths.
+    pub fn includes(&self) -> Iter<BackupPath> {
+        self.includes.iter()
+    }
+
+    /// Returns the excluded paths.
+    pub fn excludes(&self) -> Iter<BackupPath> {
+        self.excludes.iter()
+    }
+
+    /// Checks if the section only backs up the included paths.
+    pub fn is_selective(&self) -> bool {
+        !self.includes.is_empty()
+    }
+
+    /// Checks if the given path is excluded from the backups.
+    fn is_excluded(&self, path: &BackupPath) -> bool {
+        self.excludes.iter().any(|exclude| {
+            exclude.domain == path.domain
+                && (exclude.is_whole_domain() || exclude.path == path.path)
+        })
+    }
+
+    /// Gets the backed up paths that can contain sensitive data.
+    pub fn sensitive_paths(&self) -> Vec<BackupPath> {
+        if self.is_selective() {
+            self.includes
+                .iter()
+                .filter(|include| include.is_sensitive() && !self.is_excluded(include))
+                .cloned()
+                .collect()
+        } else {
+            SENSITIVE_DOMAINS
+                .iter()
+                .map(|&domain| BackupPath {
+                    domain: domain.to_owned(),
+                    path: String::from("."),
+                })
+                .filter(|path| !self.is_excluded(path))
+                .collect()
+        }
+    }
+
+    /// Gets the criticality of the backups of this section.
+    ///
+    /// Explicitly including sensitive data is worse than not excluding it, and excluding all the
+    /// sensitive data makes the backups mostly harmless.
+    pub fn criticality(&self) -> Criticality {
+        if self.sensitive_paths().is_empty() {
+            Criticality::Low
+        } else if self.is_selective() {
+            Criticality::High
+        } else {
+            Criticality::Medium
+        }
+    }
+}
+
+/// Backup rules of an application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupRules {
+    /// Sections of the rules.
+    sections: Vec<BackupSection>,
+}
+
+impl BackupRules {
+    /// Loads the backup rules referenced by the given `@xml/` resource in the decompressed
+    /// application in the given folder.
+    ///
+    /// Returns `None` if the reference is not an XML resource or the resource does not exist.
+    pub fn load<P: AsRef<Path>>(app_folder: P, reference: &str) -> Result<Option<Self>, Error> {
+        let name = match reference.strip_prefix("@xml/") {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let path = app_folder
+            .as_ref()
+            .join("res")
+            .join("xml")
+            .join(format!("{}.xml", name));
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path)?;
+        let code = if axml::is_binary_xml(&bytes) {
+            axml::decode_in(&bytes, app_folder.as_ref())?
+        } else {
+            String::from_utf8(bytes)?
+        };
+
+        Ok(Some(Self::from_code(&code).context(format_err!(
+            "invalid backup rules `{}`",
+            path.display()
+        ))?))
+    }
+
+    /// Parses the given backup rules XML.
+    pub fn from_code(code: &str) -> Result<Self, Error> {
+        let parser = EventReader::new_with_config(code.as_bytes(), PARSER_CONFIG.clone());
+        let mut sections: Vec<BackupSection> = Vec::new();
+        for e in parser {
+            if let XmlEvent::StartElement {
+                name, attributes, ..
+            } = e?
+            {
+                match name.local_name.as_str() {
+                    section @ "full-backup-content"
+                    | section @ "cloud-backup"
+                    | section @ "device-transfer" => sections.push(BackupSection::new(section)),
+                    "include" => {
+                        if let Some(section) = sections.last_mut() {
+                            section
+                                .includes
+                                .push(BackupPath::from_attributes(attributes));
+                        }
+                    }
+                    "exclude" => {
+                        if let Some(section) = sections.last_mut() {
+                            section
+                                .excludes
+                                .push(BackupPath::from_attributes(attributes));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Returns the sections of the rules.
+    pub fn sections(&self) -> Iter<BackupSection> {
+        self.sections.iter()
+    }
+
+    /// Gets the criticality of the backups, the highest of all the sections.
+    ///
+    /// Rules without sections do not restrict the backups.
+    pub fn criticality(&self) -> Criticality {
+        self.sections
+            .iter()
+            .map(BackupSection::criticality)
+            .max()
+            .unwrap_or(Criticality::Medium)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BackupRules;
+    use crate::criticality::Criticality;
+
+    /// Checks the analysis of the backup rules.
+    #[test]
+    fn it_backup_rules() {
+        let rules = BackupRules::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<full-backup-content>
+    <exclude domain="sharedpref" path="."/>
+    <exclude domain="database" path="cache.db"/>
+</full-backup-content>"#,
+        )
+        .unwrap();
+        let section = rules.sections().next().unwrap();
+        assert!(!section.is_selective());
+        assert_eq!(
+            section
+                .sensitive_paths()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["database"]
+        );
+        assert_eq!(rules.criticality(), Criticality::Medium);
+
+        let rules = BackupRules::from_code(
+            r#"<data-extraction-rules>
+    <cloud-backup>
+        <include domain="file" path="notes"/>
+    </cloud-backup>
+    <device-transfer>
+        <include domain="database" path="accounts.db"/>
+    </device-transfer>
+</data-extraction-rules>"#,
+        )
+        .unwrap();
+        let sections = rules.sections().collect::<Vec<_>>();
+        assert_eq!(sections[0].name(), "cloud-backup");
+        assert_eq!(sections[0].criticality(), Criticality::Low);
+        assert_eq!(
+            sections[1].sensitive_paths()[0].to_string(),
+            "database/accounts.db"
+        );
+        assert_eq!(rules.criticality(), Criticality::High);
+
+        let rules = BackupRules::from_code(
+            r#"<full-backup-content>
+    <exclude domain="database" path="."/>
+    <exclude domain="sharedpref" path="."/>
+</full-backup-content>"#,
+        )
+        .unwrap();
+        assert_eq!(rules.criticality(), Criticality::Low);
+        assert_eq!(
+            BackupRules::from_code("<data-extraction-rules/>")
+                .unwrap()
+                .criticality(),
+            Criticality::Medium
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Embedded strings extraction module.
+//!
+//! Flutter and Xamarin or .NET MAUI applications barely have Java code: the Dart code of Flutter
+//! applications is compiled ahead of time to the `libapp.so` native library, and the C# code of
+//! .NET applications is shipped as assemblies in the `assemblies` folder, or packed in an assembly
+//! blob. This module extracts the printable strings of these binaries, both ASCII and UTF-16, the
+//! encoding of the .NET string literals, and searches them for network endpoints and secrets, so
+//! that the reports of these applications are not nearly empty.
+//!
+//! Assemblies compressed with LZ4 (`XALZ` files) cannot be read, and are skipped with a warning.
+//! Endpoints and secrets found in binaries have no line number.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use failure::Error;
+
+use super::{endpoints, secrets};
+use crate::{
+    print_message, print_warning,
+    results::{Results, StageStatus},
+    Config,
+};
+
+/// Minimum length of the extracted strings.
+const MIN_STRING_LENGTH: usize = 6;
+
+/// Prefixes of the assemblies of the .NET runtime and of Xamarin, that are not analyzed.
+const FRAMEWORK_ASSEMBLIES: [&str; 7] = [
+    "System.",
+    "Microsoft.",
+    "Mono.",
+    "Xamarin.",
+    "Java.Interop",
+    "mscorlib",
+    "netstandard",
+];
+
+/// Magic number of the .NET assemblies compressed with LZ4.
+const COMPRESSED_ASSEMBLY_MAGIC: &[u8] = b"XALZ";
+
+/// Checks if the given byte is a printable ASCII character.
+fn is_printable(byte: u8) -> bool {
+    byte == b'\t' || (0x20..0x7f).contains(&byte)
+}
+
+/// Extracts the printable strings of at least the given length from the given binary data.
+///
+/// Both ASCII strings and UTF-16 little-endian strings of ASCII characters are extracted, in the
+/// order they appear in the data.
+pub fn extract_strings(data: &[u8], min_length: usize) -> Vec<String> {
+    let mut found = Vec::new();
+
+    let mut start = None;
+    for (i, &byte) in data.iter().chain(&[0]).enumerate() {
+        match (is_printable(byte), start) {
+            (true, None) => start = Some(i),
+            (false, Some(begin)) => {
+                if i - begin >= min_length {
+                    found.push((begin, String::from_utf8_lossy(&data[begin..i]).into_owned()));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    for offset in 0..2 {
+        let mut current = String::new();
+        let mut begin = offset;
+        for (i, pair) in data[offset.min(data.len())..].chunks(2).enumerate() {
+            if pair.len() == 2 && pair[1] == 0 && is_printable(pair[0]) {
+                if current.is_empty() {
+                    begin = offset + i * 2;
+                }
+                current.push(char::from(pair[0]));
+            } else {
+                if current.len() >= min_length {
+                    found.push((begin, current.clone()));
+                }
+                current.clear();
+            }
+        }
+        if current.len() >= min_length {
+            found.push((begin, current));
+        }
+    }
+
+    found.sort();
+    found.into_iter().map(|(_, string)| string).collect()
+}
+
+/// Gets the binaries of the Flutter and .NET code of the decompressed application in the given
+/// folder.
+///
+/// Native libraries are only taken from the first ABI that ships them, since the rest of ABIs
+/// contain the same strings.
+fn find_binaries(dist_folder: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut binaries = Vec::new();
+
+    let lib_folder = dist_folder.join("lib");
+    if lib_folder.is_dir() {
+        let mut abis = fs::read_dir(&lib_folder)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        abis.sort();
+        let mut names = Vec::new();
+        for abi in abis.into_iter().filter(|abi| abi.is_dir()) {
+            let mut libraries = fs::read_dir(&abi)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            libraries.sort();
+            for library in libraries {
+                let name = match library.file_name().and_then(|name| name.to_str()) {
+                    Some(name) => name.to_owned(),
+                    None => continue,
+                };
+                if (name == "libapp.so" || name.starts_with("libassemblies."))
+                    && !names.contains(&name)
+                {
+                    names.push(name);
+                    binaries.push(library);
+                }
+            }
+        }
+    }
+
+    add_assemblies(dist_folder.join("assemblies"), &mut binaries)?;
+    Ok(binaries)
+}
+
+/// Adds the assemblies and assembly blobs in the given folder, recursively, to the list.
+///
+/// The assemblies of the .NET runtime and of Xamarin are skipped.
+fn add_assemblies(folder: PathBuf, binaries: &mut Vec<PathBuf>) -> Result<(), Error> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(folder)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            add_assemblies(entry, binaries)?;
+        } else {
+            let name = entry
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if (name.ends_with(".dll") || name.ends_with(".blob"))
+                && !FRAMEWORK_ASSEMBLIES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            {
+                binaries.push(entry);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the strings of the Flutter and .NET binaries of the application, and adds the
+/// endpoints and secrets found in them to the results.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let binaries = match find_binaries(&dist_folder) {
+        Ok(binaries) => binaries,
+        Err(e) => {
+            print_warning(format!(
+                "there was an error listing the Flutter and .NET binaries: {}",
+                e
+            ));
+            results.set_stage_status("binary_strings", StageStatus::failed(e));
+            return;
+        }
+    };
+    if binaries.is_empty() {
+        return;
+    }
+
+    let mut count = 0;
+    for path in &binaries {
+        let file = path.strip_prefix(&dist_folder).unwrap_or(path);
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) => {
+                print_warning(format!(
+                    "could not extract the strings of `{}`: {}",
+                    file.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+        if data.starts_with(COMPRESSED_ASSEMBLY_MAGIC) {
+            print_warning(format!(
+                "the assembly `{}` is compressed, its strings will not be extracted",
+                file.display()
+            ));
+            continue;
+        }
+
+        let strings = extract_strings(&data, MIN_STRING_LENGTH);
+        count += strings.len();
+        for endpoint in endpoints::scan_binary(&strings, file) {
+            if endpoint.is_debug() {
+                endpoints::report_debug(config, &endpoint, "", results);
+            }
+            results.add_endpoint(endpoint);
+        }
+        for secret in secrets::scan_binary(&strings, file) {
+            results.add_secret(secret);
+        }
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} strings were extracted from {} Flutter and .NET binaries.",
+            format!("{}", count).bold(),
+            format!("{}", binaries.len()).bold()
+        ));
+    }
+    results.set_stage_status("binary_strings", StageStatus::Ok);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_strings;
+
+    /// Checks the extraction of ASCII and UTF-16 strings from binary data.
+    #[test]
+    fn it_extract_strings() {
+        let mut data =
+            b"\x7fELF\x02\x01\x00https://api.example.com/v1\x00\x01short\x00\x01".to_vec();
+        for c in "Bearer token".chars() {
+            data.push(c as u8);
+            data.push(0);
+        }
+        data.extend_from_slice(b"\x00\x00\xffend");
+
+        assert_eq!(
+            extract_strings(&data, 6),
+            vec!["https://api.example.com/v1", "Bearer token"]
+        );
+        assert_eq!(
+            extract_strings(&data, 4),
+            vec!["https://api.example.com/v1", "short", "Bearer token"]
+        );
+        assert!(extract_strings(&[], 6).is_empty());
+    }
+}
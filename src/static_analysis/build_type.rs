@@ -0,0 +1,313 @@
+//! Build type analysis.
+//!
+//! The `android:debuggable` flag of the manifest is not the only sign of a debug build. This
+//! module looks for other evidence that the application is not a release build: a `BuildConfig`
+//! class with `DEBUG = true` or a debug `BUILD_TYPE`, a debug suffix in the application ID, such
+//! as `com.example.app.debug`, and debugging tools that should never be shipped, such as
+//! LeakCanary, Stetho or Flipper.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use failure::Error;
+
+use crate::{
+    criticality::Criticality,
+    get_code, print_vulnerability, print_warning,
+    results::{Confidence, Results, Vulnerability},
+    static_analysis::manifest::Manifest,
+    Config,
+};
+
+/// Suffixes added to the application ID of debug builds.
+const DEBUG_SUFFIXES: [&str; 4] = [".debug", ".dbg", ".dev", ".development"];
+
+/// Debugging tools, with the source folders and the library groups that identify them.
+const DEBUG_TOOLS: [(&str, &[&str], &str); 3] = [
+    (
+        "LeakCanary",
+        &["classes/leakcanary", "classes/com/squareup/leakcanary"],
+        "com.squareup.leakcanary",
+    ),
+    (
+        "Stetho",
+        &["classes/com/facebook/stetho"],
+        "com.facebook.stetho",
+    ),
+    (
+        "Flipper",
+        &["classes/com/facebook/flipper"],
+        "com.facebook.flipper",
+    ),
+];
+
+/// Evidence of a non-release build.
+#[derive(Debug, Clone, PartialEq)]
+enum Indicator {
+    /// `BuildConfig` class of a debug build, with its path, line and code.
+    BuildConfig(PathBuf, usize, String),
+    /// Debug suffix in the application ID.
+    ApplicationId(&'static str),
+    /// Debugging tool bundled in the application.
+    Tool(&'static str),
+}
+
+impl Indicator {
+    /// Describes the indicator.
+    fn description(&self) -> String {
+        match self {
+            Indicator::BuildConfig(path, ..) => format!(
+                "the `{}` class is generated for a debug build",
+                path.display()
+            ),
+            Indicator::ApplicationId(suffix) => {
+                format!("the application ID has the `{}` debug suffix", suffix)
+            }
+            Indicator::Tool(tool) => format!("the {} debugging tool is bundled", tool),
+        }
+    }
+}
+
+/// Performs the build type analysis.
+pub fn analysis<S: AsRef<str>>(
+    config: &Config,
+    package: S,
+    manifest: Option<&Manifest>,
+    results: &mut Results,
+) {
+    let criticality = Criticality::High;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let application_id = manifest.map(Manifest::package);
+    let mut indicators = match find_build_config(&dist_folder, application_id) {
+        Ok(indicator) => indicator.into_iter().collect::<Vec<_>>(),
+        Err(e) => {
+            print_warning(format!(
+                "there was an error looking for the `BuildConfig` class: {}",
+                e
+            ));
+            Vec::new()
+        }
+    };
+    indicators.extend(
+        application_id
+            .and_then(debug_suffix)
+            .map(Indicator::ApplicationId),
+    );
+    for (tool, folders, group) in &DEBUG_TOOLS {
+        if folders
+            .iter()
+            .any(|folder| dist_folder.join(folder).is_dir())
+            || results
+                .libraries()
+                .iter()
+                .any(|library| library.group() == *group)
+        {
+            indicators.push(Indicator::Tool(*tool));
+        }
+    }
+
+    if indicators.is_empty() {
+        return;
+    }
+
+    let evidence = indicators
+        .iter()
+        .map(Indicator::description)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let description = format!(
+        "The application seems to be a debug build instead of a release build: {}. Debug builds \
+         can expose logs, debugging endpoints and internal tools, and they should never be \
+         shipped. Make sure that the release variant of the application is being analyzed.",
+        evidence
+    );
+
+    let mut builder =
+        Vulnerability::builder(criticality, "Non-release Build", description.as_str())
+            .rule_id("build-type")
+            .cwe(489)
+            .evidence(evidence);
+    builder = match indicators.first() {
+        Some(Indicator::BuildConfig(path, line, code)) => builder
+            .file(path)
+            .lines(*line, *line)
+            .code(code.as_str())
+            .confidence(Confidence::High),
+        _ => builder.confidence(Confidence::Medium),
+    };
+
+    results.add_vulnerability(builder.build());
+    print_vulnerability(description, criticality);
+}
+
+/// Finds the `BuildConfig` class of the application, if it was generated for a debug build.
+///
+/// Libraries also have their own `BuildConfig` classes, so only the one with the given
+/// application ID is checked. If the application ID is not known, any `BuildConfig` class with an
+/// application ID is checked.
+fn find_build_config(
+    dist_folder: &Path,
+    application_id: Option<&str>,
+) -> Result<Option<Indicator>, Error> {
+    let mut files = Vec::new();
+    find_files(&dist_folder.join("classes"), "BuildConfig.java", &mut files)?;
+
+    for path in files {
+        let code = fs::read_to_string(&path)?;
+        let build_config = parse_build_config(&code);
+        let is_application = match (build_config.application_id, application_id) {
+            (Some(id), Some(application_id)) => id == application_id,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if is_application {
+            if let Some(line) = build_config.debug_line {
+                let file = path
+                    .strip_prefix(dist_folder)
+                    .unwrap_or(&path)
+                    .to_path_buf();
+                return Ok(Some(Indicator::BuildConfig(
+                    file,
+                    line,
+                    get_code(&code, line, line),
+                )));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the files with the given name in the given folder, recursively.
+fn find_files(folder: &Path, name: &str, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_files(&path, name, files)?;
+        } else if path
+            .file_name()
+            .map_or(false, |file_name| file_name == name)
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Fields of a decompiled `BuildConfig` class.
+#[derive(Debug, Default, PartialEq)]
+struct BuildConfig<'c> {
+    /// Application ID of the class, not present in the classes of libraries.
+    application_id: Option<&'c str>,
+    /// Line where a debug build is declared, if any.
+    debug_line: Option<usize>,
+}
+
+/// Parses the decompiled code of a `BuildConfig` class.
+fn parse_build_config(code: &str) -> BuildConfig<'_> {
+    let mut build_config = BuildConfig::default();
+    for (i, line) in code.lines().enumerate() {
+        let (field, value) = match parse_field(line) {
+            Some(field) => field,
+            None => continue,
+        };
+        let is_debug = match field {
+            "APPLICATION_ID" => {
+                build_config.application_id = Some(value.trim_matches('"'));
+                false
+            }
+            "DEBUG" => value == "true" || value == "Boolean.parseBoolean(\"true\")",
+            "BUILD_TYPE" => value == "\"debug\"",
+            _ => false,
+        };
+        if is_debug && build_config.debug_line.is_none() {
+            build_config.debug_line = Some(i);
+        }
+    }
+
+    build_config
+}
+
+/// Parses a `static final` field declaration, returning its name and value.
+fn parse_field(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if !line.contains("static final ") || !line.ends_with(';') {
+        return None;
+    }
+    let mut parts = line[..line.len() - 1].splitn(2, '=');
+    let field = parts.next()?.split_whitespace().last()?;
+    let value = parts.next()?.trim();
+    Some((field, value))
+}
+
+/// Returns the debug suffix of the given application ID, if any.
+fn debug_suffix(application_id: &str) -> Option<&'static str> {
+    DEBUG_SUFFIXES
+        .iter()
+        .find(|suffix| application_id.ends_with(*suffix))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{debug_suffix, parse_build_config, BuildConfig};
+
+    /// Checks the parsing of `BuildConfig` classes.
+    #[test]
+    fn it_parse_build_config() {
+        let debug = parse_build_config(
+            "package com.example.app;\n\
+             \n\
+             public final class BuildConfig {\n    \
+             public static final String APPLICATION_ID = \"com.example.app.debug\";\n    \
+             public static final String BUILD_TYPE = \"debug\";\n    \
+             public static final boolean DEBUG = Boolean.parseBoolean(\"true\");\n\
+             }\n",
+        );
+        assert_eq!(
+            debug,
+            BuildConfig {
+                application_id: Some("com.example.app.debug"),
+                debug_line: Some(4),
+            }
+        );
+
+        let release = parse_build_config(
+            "public final class BuildConfig {\n    \
+             public static final String APPLICATION_ID = \"com.example.app\";\n    \
+             public static final String BUILD_TYPE = \"release\";\n    \
+             public static final boolean DEBUG = false;\n\
+             }\n",
+        );
+        assert_eq!(release.application_id, Some("com.example.app"));
+        assert_eq!(release.debug_line, None);
+
+        let library = parse_build_config(
+            "public final class BuildConfig {\n    \
+             public static final boolean DEBUG = true;\n\
+             }\n",
+        );
+        assert_eq!(library.application_id, None);
+        assert_eq!(library.debug_line, Some(1));
+    }
+
+    /// Checks the detection of debug application IDs.
+    #[test]
+    fn it_debug_suffix() {
+        assert_eq!(debug_suffix("com.example.app.debug"), Some(".debug"));
+        assert_eq!(debug_suffix("com.example.app.dev"), Some(".dev"));
+        assert_eq!(debug_suffix("com.example.app"), None);
+        assert_eq!(debug_suffix("com.example.devices"), None);
+    }
+}
@@ -8,7 +8,7 @@ use failure::{bail, Error, ResultExt};
 
 use crate::{
     criticality::Criticality,
-    print_vulnerability, print_warning,
+    print_message, print_vulnerability, print_warning,
     results::{Results, Vulnerability},
     Config,
 };
@@ -43,7 +43,7 @@ pub fn certificate_analysis<S: AsRef<str>>(
     results: &mut Results,
 ) -> Result<(), Error> {
     if config.is_verbose() {
-        println!("Reading and analyzing the certificates…")
+        print_message("Reading and analyzing the certificates…")
     }
 
     // Gets the path to the certificate files.
@@ -106,12 +106,12 @@ pub fn certificate_analysis<S: AsRef<str>>(
 
             let cmd = String::from_utf8_lossy(&output.stdout);
             if config.is_verbose() {
-                println!(
+                print_message(format!(
                     "The application is signed with the following certificate: {}",
                     path_file.bold()
-                );
+                ));
 
-                println!("{}", cmd);
+                print_message(cmd);
             }
             results.set_certificate(cmd.borrow());
 
@@ -151,6 +151,7 @@ pub fn certificate_analysis<S: AsRef<str>>(
                     None::<String>,
                 );
                 results.add_vulnerability(vulnerability);
+                results.add_certificate_issue();
                 print_vulnerability(description, criticality);
             }
             if issuer.nth(1) == subject.nth(1) {
@@ -191,17 +192,18 @@ pub fn certificate_analysis<S: AsRef<str>>(
                     None::<String>,
                 );
                 results.add_vulnerability(vulnerability);
+                results.add_certificate_issue();
                 print_vulnerability(description, criticality);
             }
         }
     }
 
     if config.is_verbose() {
-        println!();
-        println!("{}", "The certificates were analyzed correctly!".green());
-        println!();
+        print_message("");
+        print_message("The certificates were analyzed correctly!".green());
+        print_message("");
     } else if !config.is_quiet() {
-        println!("Certificates analyzed.");
+        print_message("Certificates analyzed.");
     }
     Ok(())
 }
@@ -1,264 +1,926 @@
 //! Code analysis module.
 
 use std::{
-    borrow::Borrow,
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
     fs::{self, DirEntry, File},
-    path::Path,
+    io::Read,
+    path::{Path, PathBuf},
     slice::Iter,
-    sync::{Arc, Mutex},
+    str,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 use colored::Colorize;
 use failure::{Error, Fail, ResultExt};
-use regex::Regex;
+use memmap::Mmap;
+use regex::{Regex, RegexSet, RegexSetBuilder};
 use serde::de::{self, Deserializer, SeqAccess, Visitor};
 use serde_json;
 
-use super::manifest::{Manifest, Permission};
+#[cfg(feature = "ast")]
+use super::ast;
+use super::{
+    ast::AstQuery,
+    dataflow::{self, Flow, FlowSpec},
+    forward_check::{self, ForwardCheck},
+    frameworks::is_hermes_bundle,
+    location::Locations,
+    manifest::{Manifest, Permission},
+};
 use crate::{
+    bench::{self, FileTypeProfile, RuleProfile},
+    cancellation,
     criticality::Criticality,
-    error, get_code, print_vulnerability, print_warning,
-    results::{Results, Vulnerability},
-    Config,
+    error, get_code, print_message, print_vulnerability, print_warning,
+    progress::{self, Phase},
+    results::{Cvss, Results, SlowRule, StageStatus, Vulnerability, VulnerabilityBuilder},
+    signature, Config,
 };
 
+/// Category of the rules that check the hygiene of release builds, such as sensitive data in
+/// debug logs.
+pub(crate) const HYGIENE_CATEGORY: &str = "hygiene";
+
 /// Analyzes the whole codebase of the application.
+///
+/// The manifest is shared with the analysis threads, and with the custom analyzers that run after
+/// the code analysis.
 pub fn analysis<S: AsRef<str>>(
-    manifest: Option<Manifest>,
+    manifest: Arc<Option<Manifest>>,
     config: &Config,
     package: S,
     results: &mut Results,
 ) {
-    let rules = match load_rules(config) {
+    let mut rules = match load_rules(config) {
         Ok(r) => r,
         Err(e) => {
             print_warning(format!(
                 "An error occurred when loading code analysis rules. Error: {}",
                 e
             ));
+            results.set_stage_status("code", StageStatus::failed(e));
             return;
         }
     };
+    if config.is_hygiene_release_only() && (*manifest).as_ref().map_or(false, Manifest::is_debug) {
+        rules.retain(|rule| rule.category() != Some(HYGIENE_CATEGORY));
+    }
 
     let mut files: Vec<DirEntry> = Vec::new();
+    let mut status = StageStatus::Ok;
     if let Err(e) = add_files_to_vec("", &mut files, package.as_ref(), config) {
         print_warning(format!(
             "An error occurred when reading files for analysis, the results might be incomplete. \
              Error: {}",
             e
         ));
+        status = StageStatus::failed(e);
     }
     let total_files = files.len();
+    // Largest files first, so that the slowest files do not end up being analyzed at the end by a
+    // single thread.
+    files.sort_by_cached_key(|f| Reverse(f.metadata().map(|m| m.len()).unwrap_or(0)));
+
+    let prefilter = match build_prefilter(&rules) {
+        Ok(prefilter) => Some(prefilter),
+        Err(e) => {
+            print_warning(format!(
+                "the rules could not be combined in a single regex set, each rule will be \
+                 searched separately. Error: {}",
+                e
+            ));
+            None
+        }
+    };
+    let budget = RuleBudget::new(
+        rules.len(),
+        Duration::from_secs(config.rule_time_budget()),
+        config.is_disable_slow_rules(),
+    );
 
-    let rules = Arc::new(rules);
-    let manifest = Arc::new(manifest);
-    let found_vulnerabilities: Arc<Mutex<Vec<Vulnerability>>> = Arc::new(Mutex::new(Vec::new()));
-    let files = Arc::new(Mutex::new(files));
-    let dist_folder = Arc::new(config.dist_folder().join(package.as_ref()));
+    let requirements = RequirementIndex::new(&rules);
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    // The manifest is not in the analyzed files, so its decoded code is searched here.
+    if let Some(manifest) = (*manifest).as_ref() {
+        requirements.record(&rules, Path::new("AndroidManifest.xml"), manifest.code());
+    }
+
+    let rules = Arc::new(RuleSet {
+        rules,
+        prefilter,
+        budget,
+        requirements,
+    });
+    let files = Arc::new(files);
+    let queue = Arc::new(WorkQueue::new(total_files, config.threads()));
+    let dist_folder = Arc::new(dist_folder);
+    let done_files = Arc::new(AtomicUsize::new(0));
+    let min_criticality = config.min_criticality();
 
     if config.is_verbose() {
-        println!(
+        print_message(format!(
             "Starting analysis of the code with {} threads. {} files to go!",
             format!("{}", config.threads()).bold(),
             format!("{}", total_files).bold()
-        );
+        ));
     }
 
-    let handles: Vec<_> = (0..config.threads())
-        .map(|_| {
+    let handles: Vec<_> = (0..queue.workers())
+        .map(|worker| {
             let thread_manifest = Arc::clone(&manifest);
             let thread_files = Arc::clone(&files);
+            let thread_queue = Arc::clone(&queue);
             let thread_rules = Arc::clone(&rules);
-            let thread_vulnerabilities = Arc::clone(&found_vulnerabilities);
             let thread_dist_folder = Arc::clone(&dist_folder);
+            let thread_done_files = Arc::clone(&done_files);
 
-            thread::spawn(move || loop {
-                let f = {
-                    let mut files = thread_files.lock().unwrap();
-                    files.pop()
-                };
-                match f {
-                    Some(f) => {
-                        if let Err(e) = analyze_file(
-                            f.path(),
-                            &*thread_dist_folder,
-                            &thread_rules,
-                            &thread_manifest,
-                            &thread_vulnerabilities,
-                        ) {
-                            print_warning(format!(
-                                "could not analyze `{}`. The analysis will continue, though. \
-                                 Error: {}",
-                                f.path().display(),
-                                e
-                            ))
-                        }
+            thread::spawn(move || {
+                let mut output = WorkerOutput::new(thread_rules.rules.len());
+                while let Some(index) = thread_queue.next(worker) {
+                    if cancellation::is_cancelled() {
+                        break;
+                    }
+                    let f = &thread_files[index];
+                    let start = Instant::now();
+                    if let Err(e) = analyze_file(
+                        f.path(),
+                        &*thread_dist_folder,
+                        &thread_rules,
+                        &thread_manifest,
+                        min_criticality,
+                        &mut output,
+                    ) {
+                        output.skipped += 1;
+                        print_warning(format!(
+                            "could not analyze `{}`. The analysis will continue, though. \
+                             Error: {}",
+                            f.path().display(),
+                            e
+                        ))
                     }
-                    None => break,
+                    output.timings.push((index, start.elapsed()));
+                    let done = thread_done_files.fetch_add(1, Ordering::SeqCst) + 1;
+                    progress::file_processed(Phase::Code, done, total_files);
                 }
+                output
             })
         })
         .collect();
 
+    let mut skipped_files = 0;
+    let mut pending = Vec::new();
+    let mut timings = Vec::with_capacity(total_files);
+    let mut rule_timings = vec![RuleTiming::default(); rules.rules.len()];
+    for t in handles {
+        match t.join() {
+            Ok(output) => {
+                for vulnerability in output.vulnerabilities {
+                    results.add_vulnerability(vulnerability);
+                }
+                pending.extend(output.pending);
+                skipped_files += output.skipped;
+                timings.extend(output.timings);
+                for (total, timing) in rule_timings.iter_mut().zip(output.rule_timings) {
+                    total.evaluations += timing.evaluations;
+                    total.duration += timing.duration;
+                }
+            }
+            Err(e) => {
+                #[allow(clippy::use_debug)]
+                print_warning(format!(
+                    "an error occurred when joining analysis threads: Error: {:?}",
+                    e
+                ));
+            }
+        }
+    }
+    for (index, vulnerability) in pending {
+        if rules.requirements.is_found(index) {
+            results.add_vulnerability(vulnerability);
+        }
+    }
+    let analyzed_files = timings.len() - skipped_files;
+    if cancellation::is_cancelled() {
+        status = StageStatus::failed(error::Kind::Cancelled);
+    }
+    results.set_file_counts(analyzed_files, total_files - analyzed_files);
+    results.set_stage_status("code", status);
+    for (index, (rule, timing)) in rules.rules.iter().zip(&rule_timings).enumerate() {
+        if timing.duration > rules.budget.budget {
+            results.add_slow_rule(SlowRule::new(
+                rule.id(),
+                (bench::seconds(timing.duration) * 1000.0).round() / 1000.0,
+                rules.budget.is_disabled(index),
+            ));
+        }
+    }
+
+    if config.is_bench() {
+        let mut file_types = BTreeMap::new();
+        for &(index, duration) in &timings {
+            let path = files[index].path();
+            let extension = path
+                .file_name()
+                .and_then(|name| file_type(&name.to_string_lossy()).map(str::to_owned))
+                .unwrap_or_default();
+            file_types
+                .entry(extension)
+                .or_insert_with(FileTypeProfile::default)
+                .record(duration);
+        }
+        let rules = rules
+            .rules
+            .iter()
+            .zip(&rule_timings)
+            .map(|(rule, timing)| {
+                RuleProfile::new(rule.id().into_owned(), timing.evaluations, timing.duration)
+            })
+            .collect();
+        bench::record_code_profile(package.as_ref(), rules, file_types);
+    }
+
     if config.is_verbose() {
-        let mut last_print = 0;
+        timings.sort_by_key(|&(_, duration)| Reverse(duration));
+        print_message("");
+        print_message("Slowest files to analyze:");
+        for &(index, duration) in timings.iter().take(SLOWEST_FILES) {
+            let path = files[index].path();
+            print_message(format!(
+                "\t{} ({}.{:03}s)",
+                path.strip_prefix(&*dist_folder)
+                    .unwrap_or(path.as_path())
+                    .display(),
+                duration.as_secs(),
+                duration.subsec_millis()
+            ));
+        }
+        print_message("");
+        print_message("The source code was analyzed correctly!".green());
+    } else if !config.is_quiet() {
+        print_message("Source code analyzed.");
+    }
+}
 
-        while match files.lock() {
-            Ok(f) => f.len(),
-            Err(_) => 1,
-        } > 0
-        {
-            let left = match files.lock() {
-                Ok(f) => f.len(),
-                Err(_) => continue,
-            };
-            let done = total_files - left;
-            if done - last_print > total_files / 10 {
-                last_print = done;
-                println!("{} files already analyzed.", last_print);
-            }
+/// Number of slowest files to show in verbose mode.
+const SLOWEST_FILES: usize = 5;
+
+/// Output of an analysis thread.
+#[derive(Debug, Default)]
+struct WorkerOutput {
+    /// Vulnerabilities found by the thread.
+    vulnerabilities: Vec<Vulnerability>,
+    /// Vulnerabilities found by the thread for the rules with a requirement, by the index of the
+    /// rule, that are only reported if the requirement is found.
+    pending: Vec<(usize, Vulnerability)>,
+    /// Analysis time of each file analyzed by the thread, by its index.
+    timings: Vec<(usize, Duration)>,
+    /// Time spent in each rule by the thread, by the index of the rule.
+    rule_timings: Vec<RuleTiming>,
+    /// Number of files the thread could not analyze.
+    skipped: usize,
+}
+
+impl WorkerOutput {
+    /// Creates the empty output of a thread that checks the given number of rules.
+    fn new(rules: usize) -> Self {
+        Self {
+            rule_timings: vec![RuleTiming::default(); rules],
+            ..Self::default()
         }
     }
+}
 
-    for t in handles {
-        if let Err(e) = t.join() {
-            #[allow(clippy::use_debug)]
+/// Time spent evaluating a rule.
+#[derive(Debug, Clone, Copy, Default)]
+struct RuleTiming {
+    /// Number of files, or chunks of large files, the rule was evaluated in.
+    evaluations: usize,
+    /// Total evaluation time.
+    duration: Duration,
+}
+
+impl RuleTiming {
+    /// Records an evaluation of the rule that took the given time.
+    fn record(&mut self, duration: Duration) {
+        self.evaluations += 1;
+        self.duration += duration;
+    }
+}
+
+/// Rules of the code analysis, shared by the analysis threads.
+#[derive(Debug)]
+struct RuleSet {
+    /// Rules to check.
+    rules: Vec<Rule>,
+    /// Prefilter of the rules, if they could be combined in a regex set.
+    prefilter: Option<RegexSet>,
+    /// Time budget of the rules.
+    budget: RuleBudget,
+    /// Secondary matches of the rules with a requirement.
+    requirements: RequirementIndex,
+}
+
+/// Time budget of the rules, shared by the analysis threads.
+///
+/// A single pathological regex can take longer than the rest of the analysis together, so the
+/// rules that exceed their budget are reported and, if configured, they are not checked in the
+/// rest of files.
+#[derive(Debug)]
+struct RuleBudget {
+    /// Maximum evaluation time of each rule.
+    budget: Duration,
+    /// Whether the rules that exceed the budget stop being checked.
+    disable: bool,
+    /// Evaluation time of each rule so far, in microseconds, by the index of the rule.
+    spent: Vec<AtomicU64>,
+    /// Whether each rule has stopped being checked, by the index of the rule.
+    disabled: Vec<AtomicBool>,
+}
+
+impl RuleBudget {
+    /// Creates the budget of the given number of rules.
+    fn new(rules: usize, budget: Duration, disable: bool) -> Self {
+        Self {
+            budget,
+            disable,
+            spent: (0..rules).map(|_| AtomicU64::new(0)).collect(),
+            disabled: (0..rules).map(|_| AtomicBool::new(false)).collect(),
+        }
+    }
+
+    /// Checks if the rule with the given index has stopped being checked.
+    fn is_disabled(&self, index: usize) -> bool {
+        self.disabled[index].load(Ordering::Relaxed)
+    }
+
+    /// Records an evaluation of the given rule that took the given time, and warns if the rule
+    /// has just exceeded its budget.
+    fn record(&self, index: usize, rule: &Rule, duration: Duration) {
+        let budget = self.budget.as_micros() as u64;
+        let micros = duration.as_micros() as u64;
+        let spent = self.spent[index].fetch_add(micros, Ordering::Relaxed);
+        if spent <= budget && spent + micros > budget {
+            if self.disable {
+                self.disabled[index].store(true, Ordering::Relaxed);
+            }
             print_warning(format!(
-                "an error occurred when joining analysis threads: Error: {:?}",
-                e
+                "the rule `{}` exceeded its time budget of {}s{}",
+                rule.id(),
+                self.budget.as_secs(),
+                if self.disable {
+                    ", it will not be checked in the rest of files"
+                } else {
+                    ""
+                }
             ));
         }
     }
+}
 
-    for vulnerability in Arc::try_unwrap(found_vulnerabilities)
-        .unwrap()
-        .into_inner()
-        .unwrap()
-    {
-        results.add_vulnerability(vulnerability);
+/// Index of the requirements of the rules found in the application, shared by the analysis
+/// threads.
+///
+/// Each file is searched for the requirements that have not been found yet, so that the matches
+/// of the rules with a requirement can be reported once all the files have been analyzed.
+#[derive(Debug)]
+struct RequirementIndex {
+    /// Indexes of the rules with a requirement.
+    rules: Vec<usize>,
+    /// File where the requirement of each rule was first found, by the index of the rule.
+    found: Mutex<BTreeMap<usize, PathBuf>>,
+}
+
+impl RequirementIndex {
+    /// Creates the index of the requirements of the given rules.
+    fn new(rules: &[Rule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .enumerate()
+                .filter(|(_, rule)| rule.requires().is_some())
+                .map(|(index, _)| index)
+                .collect(),
+            found: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Searches the requirements that have not been found yet in the given file.
+    fn record(&self, rules: &[Rule], file: &Path, code: &str) {
+        for &index in &self.rules {
+            if self.is_found(index) {
+                continue;
+            }
+            let requirement = match rules[index].requires() {
+                Some(requirement) => requirement,
+                None => continue,
+            };
+            if requirement.is_match(file, code) {
+                let _ = self
+                    .found()
+                    .entry(index)
+                    .or_insert_with(|| file.to_path_buf());
+            }
+        }
     }
 
-    if config.is_verbose() {
-        println!();
-        println!("{}", "The source code was analyzed correctly!".green());
-    } else if !config.is_quiet() {
-        println!("Source code analyzed.");
+    /// Checks if the requirement of the rule with the given index was found.
+    fn is_found(&self, index: usize) -> bool {
+        self.found().contains_key(&index)
+    }
+
+    /// Gets the requirements found so far.
+    fn found(&self) -> MutexGuard<'_, BTreeMap<usize, PathBuf>> {
+        self.found
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Work-stealing queue of the files to analyze.
+///
+/// Each thread takes files from the front of its own queue, so threads do not contend for a single
+/// lock. Once its queue is empty, a thread steals files from the back of the queues of the rest of
+/// threads, so that no thread stays idle while there are files left.
+#[derive(Debug)]
+struct WorkQueue {
+    /// Queue of file indexes of each thread.
+    queues: Vec<Mutex<VecDeque<usize>>>,
+}
+
+impl WorkQueue {
+    /// Distributes the given number of jobs between the given number of workers.
+    fn new(jobs: usize, workers: usize) -> Self {
+        let workers = workers.max(1);
+        let mut queues = vec![VecDeque::with_capacity(jobs / workers + 1); workers];
+        for job in 0..jobs {
+            queues[job % workers].push_back(job);
+        }
+
+        Self {
+            queues: queues.into_iter().map(Mutex::new).collect(),
+        }
+    }
+
+    /// Gets the number of workers of the queue.
+    fn workers(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Gets the next job for the given worker, stealing it from another worker if needed.
+    fn next(&self, worker: usize) -> Option<usize> {
+        let own = self.queues[worker]
+            .lock()
+            .ok()
+            .and_then(|mut queue| queue.pop_front());
+        own.or_else(|| {
+            (1..self.workers())
+                .map(|offset| (worker + offset) % self.workers())
+                .find_map(|victim| {
+                    self.queues[victim]
+                        .lock()
+                        .ok()
+                        .and_then(|mut queue| queue.pop_back())
+                })
+        })
     }
 }
 
+/// Number of bytes read from the beginning of a file to detect if it is binary.
+const BINARY_CHECK_SIZE: usize = 8 * 1024;
+
+/// Size from which files are analyzed in chunks, instead of reading them whole into memory.
+const CHUNKED_ANALYSIS_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Approximate size of the chunks in which large files are analyzed.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Maximum size of the compiled regex set of the rules prefilter, in bytes.
+const PREFILTER_SIZE_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Builds the prefilter of the given rules: a regex set with the regexes of all of them.
+///
+/// Searching the regex set finds which rules match a file in a single pass over the file, so only
+/// the rules that match have to be checked one by one. The index of each regex in the set is the
+/// index of its rule.
+fn build_prefilter(rules: &[Rule]) -> Result<RegexSet, Error> {
+    let prefilter = RegexSetBuilder::new(rules.iter().map(|rule| rule.regex().as_str()))
+        .size_limit(PREFILTER_SIZE_LIMIT)
+        .build()?;
+    Ok(prefilter)
+}
+
+/// Checks if the file in the given path is binary.
+///
+/// Text files do not contain NUL bytes, so files with a NUL byte in their first bytes are
+/// considered binary.
+fn is_binary<P: AsRef<Path>>(path: P) -> Result<bool, Error> {
+    let mut head = Vec::with_capacity(BINARY_CHECK_SIZE);
+    let _ = File::open(path)?
+        .take(BINARY_CHECK_SIZE as u64)
+        .read_to_end(&mut head)?;
+    Ok(head.contains(&0))
+}
+
 /// Analyzes the given file.
+///
+/// Binary files are not analyzed, and files bigger than 16 MiB are analyzed in chunks, so that
+/// huge assets do not exhaust the memory. The rest of files are memory mapped, and only the rules
+/// that match the prefilter, if any, are checked.
 fn analyze_file<P: AsRef<Path>, T: AsRef<Path>>(
     path: P,
     dist_folder: T,
-    rules: &[Rule],
+    rules: &RuleSet,
     manifest: &Option<Manifest>,
-    results: &Mutex<Vec<Vulnerability>>,
+    min_criticality: Criticality,
+    output: &mut WorkerOutput,
 ) -> Result<(), Error> {
-    let code = fs::read_to_string(&path)?;
+    // Binary files are skipped silently, they are not analysis errors.
+    if is_binary(&path)? {
+        return Ok(());
+    }
+    let size = fs::metadata(&path)?.len();
+    if size > CHUNKED_ANALYSIS_SIZE {
+        return analyze_file_in_chunks(path, dist_folder, rules, manifest, min_criticality, output);
+    } else if size == 0 {
+        return Ok(());
+    }
 
-    'check: for rule in rules {
-        if manifest.is_some()
-            && rule.max_sdk().is_some()
-            && rule.max_sdk().unwrap() < manifest.as_ref().unwrap().min_sdk()
-        {
-            continue 'check;
+    let mapped_file = File::open(&path)?;
+    // The decompiled files are not modified while the code is analyzed, so the mapped memory is
+    // not changed underneath.
+    #[allow(unsafe_code)]
+    let map = unsafe { Mmap::map(&mapped_file)? };
+    let code = str::from_utf8(&map)?;
+    let candidates = rules
+        .prefilter
+        .as_ref()
+        .map(|prefilter| prefilter.matches(code));
+    let file = path.as_ref().strip_prefix(&dist_folder).unwrap();
+    rules.requirements.record(&rules.rules, file, code);
+    let filename = path.as_ref().file_name().and_then(|f| f.to_str());
+    let found = (output.vulnerabilities.len(), output.pending.len());
+    let mut flows = None;
+    #[cfg(feature = "ast")]
+    let mut tree = None;
+    #[cfg(feature = "ast")]
+    let is_java = path
+        .as_ref()
+        .extension()
+        .map_or(false, |extension| extension == "java");
+    let class = class_name(path.as_ref(), dist_folder.as_ref());
+    let in_exposed_provider = match (manifest, &class) {
+        (Some(manifest), Some(class)) => manifest.is_exposed_provider(class),
+        _ => false,
+    };
+
+    let mut check_rule = |rule: &Rule, criticality: Criticality, vulnerabilities: &mut Vec<_>| {
+        if rule.flow().is_some() {
+            if !rule.regex().is_match(code) {
+                return;
+            }
+            let flows = flows.get_or_insert_with(|| dataflow::analyze(code));
+            for flow in flows.iter().filter(|flow| rule.accepts_flow(flow, code)) {
+                let start_line = flow.source_line();
+                let end_line = flow.sink_line();
+                vulnerabilities.push(
+                    rule.vulnerability(criticality)
+                        .file(file)
+                        .lines(start_line, end_line)
+                        .code(get_code(code, start_line, end_line))
+                        .evidence(flow.to_string())
+                        .build(),
+                );
+
+                print_vulnerability(rule.description(), criticality);
+            }
+            return;
         }
 
-        let filename = path.as_ref().file_name().and_then(|f| f.to_str());
+        #[cfg(feature = "ast")]
+        {
+            if let (Some(query), true) = (rule.query(), is_java) {
+                if !rule.regex().is_match(code) {
+                    return;
+                }
+                let tree = match tree.get_or_insert_with(|| ast::parse(code)) {
+                    Some(tree) => tree,
+                    None => return,
+                };
+                'query: for range in query.find(tree, code) {
+                    let matched = &code[range.clone()];
+                    for white in rule.whitelist() {
+                        if white.is_match(matched) {
+                            continue 'query;
+                        }
+                    }
+                    let start_line = get_line_for(range.start, code);
+                    let end_line = get_line_for(range.end, code);
+                    vulnerabilities.push(
+                        rule.vulnerability(criticality)
+                            .file(file)
+                            .lines(start_line, end_line)
+                            .code(get_code(code, start_line, end_line))
+                            .evidence(matched)
+                            .build(),
+                    );
 
-        if let Some(f) = filename {
-            if !rule.has_to_check(f) {
-                continue 'check;
+                    print_vulnerability(rule.description(), criticality);
+                }
+                return;
             }
         }
 
-        for permission in rule.permissions() {
-            if manifest.is_none()
-                || !manifest
+        find_regex_matches(rule, criticality, code, 0, file, vulnerabilities);
+    };
+
+    for (index, rule) in rules.rules.iter().enumerate() {
+        if rules.budget.is_disabled(index)
+            || candidates
+                .as_ref()
+                .map_or(false, |candidates| !candidates.matched(index))
+        {
+            continue;
+        }
+        let criticality = match rule_criticality(
+            rule,
+            filename,
+            manifest,
+            in_exposed_provider,
+            min_criticality,
+        ) {
+            Some(criticality) => criticality,
+            None => continue,
+        };
+
+        let start = Instant::now();
+        if rule.requires().is_some() {
+            let mut found = Vec::new();
+            check_rule(rule, criticality, &mut found);
+            output.pending.extend(
+                found
+                    .into_iter()
+                    .map(|vulnerability| (index, vulnerability)),
+            );
+        } else {
+            check_rule(rule, criticality, &mut output.vulnerabilities);
+        }
+        let elapsed = start.elapsed();
+        output.rule_timings[index].record(elapsed);
+        rules.budget.record(index, rule, elapsed);
+    }
+
+    // Locate the vulnerabilities found in Java files in their classes and methods.
+    let mut new_vulnerabilities = output.vulnerabilities[found.0..]
+        .iter_mut()
+        .chain(output.pending[found.1..].iter_mut().map(|(_, v)| v))
+        .peekable();
+    if let (Some(class), true) = (class, new_vulnerabilities.peek().is_some()) {
+        let locations = Locations::parse(code, &class);
+        for vulnerability in new_vulnerabilities {
+            locations.locate(vulnerability);
+        }
+    }
+
+    Ok(())
+}
+
+/// Analyzes the given file in chunks of about 1 MiB, that end at line boundaries.
+///
+/// Only the regexes of the rules are searched, since the flows and the syntax queries need the
+/// whole file. Matches cannot span several chunks, and forward checks are only searched in the
+/// chunk of their match.
+fn analyze_file_in_chunks<P: AsRef<Path>, T: AsRef<Path>>(
+    path: P,
+    dist_folder: T,
+    rule_set: &RuleSet,
+    manifest: &Option<Manifest>,
+    min_criticality: Criticality,
+    output: &mut WorkerOutput,
+) -> Result<(), Error> {
+    let file = path.as_ref().strip_prefix(&dist_folder).unwrap();
+    let filename = path.as_ref().file_name().and_then(|f| f.to_str());
+    let in_exposed_provider = match (manifest, class_name(path.as_ref(), dist_folder.as_ref())) {
+        (Some(manifest), Some(class)) => manifest.is_exposed_provider(&class),
+        _ => false,
+    };
+    let rules = rule_set
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.flow().is_none() && rule.query().is_none())
+        .filter_map(|(index, rule)| {
+            rule_criticality(
+                rule,
+                filename,
+                manifest,
+                in_exposed_provider,
+                min_criticality,
+            )
+            .map(|criticality| (index, rule, criticality))
+        })
+        .collect::<Vec<_>>();
+
+    let mut reader = File::open(&path)?;
+    let mut buffer = Vec::with_capacity(CHUNK_SIZE);
+    let mut first_line = 0;
+    loop {
+        let read = (&mut reader)
+            .take((CHUNK_SIZE - buffer.len()) as u64)
+            .read_to_end(&mut buffer)?;
+        if buffer.is_empty() {
+            break;
+        }
+        let end = if read == 0 {
+            buffer.len()
+        } else if let Some(newline) = buffer.iter().rposition(|&byte| byte == b'\n') {
+            newline + 1
+        } else {
+            // A single line longer than the chunk, split after its last complete character.
+            match str::from_utf8(&buffer) {
+                Ok(_) => buffer.len(),
+                Err(e) => e.valid_up_to(),
+            }
+        };
+
+        let chunk = str::from_utf8(&buffer[..end])?;
+        rule_set.requirements.record(&rule_set.rules, file, chunk);
+        let candidates = rule_set
+            .prefilter
+            .as_ref()
+            .map(|prefilter| prefilter.matches(chunk));
+        for &(index, rule, criticality) in &rules {
+            if !rule_set.budget.is_disabled(index)
+                && candidates
                     .as_ref()
-                    .unwrap()
-                    .permission_checklist()
-                    .needs_permission(*permission)
+                    .map_or(true, |candidates| candidates.matched(index))
             {
-                continue 'check;
+                let start = Instant::now();
+                if rule.requires().is_some() {
+                    let mut found = Vec::new();
+                    find_regex_matches(rule, criticality, chunk, first_line, file, &mut found);
+                    output.pending.extend(
+                        found
+                            .into_iter()
+                            .map(|vulnerability| (index, vulnerability)),
+                    );
+                } else {
+                    find_regex_matches(
+                        rule,
+                        criticality,
+                        chunk,
+                        first_line,
+                        file,
+                        &mut output.vulnerabilities,
+                    );
+                }
+                let elapsed = start.elapsed();
+                output.rule_timings[index].record(elapsed);
+                rule_set.budget.record(index, rule, elapsed);
             }
         }
+        first_line += chunk.matches('\n').count();
+        let _ = buffer.drain(..end);
+    }
 
-        'rule: for m in rule.regex().find_iter(code.as_str()) {
-            for white in rule.whitelist() {
-                if white.is_match(&code[m.start()..m.end()]) {
-                    continue 'rule;
-                }
-            }
-            match rule.forward_check() {
-                None => {
-                    let start_line = get_line_for(m.start(), code.as_str());
-                    let end_line = get_line_for(m.end(), code.as_str());
-                    let mut results = results.lock().unwrap();
-                    results.push(Vulnerability::new(
-                        rule.criticality(),
-                        rule.label(),
-                        rule.description(),
-                        Some(path.as_ref().strip_prefix(&dist_folder).unwrap()),
-                        Some(start_line),
-                        Some(end_line),
-                        Some(get_code(code.as_str(), start_line, end_line)),
-                    ));
-
-                    print_vulnerability(rule.description(), rule.criticality());
-                }
-                Some(check) => {
-                    let caps = rule.regex().captures(&code[m.start()..m.end()]).unwrap();
+    Ok(())
+}
+
+/// Gets the criticality of the vulnerabilities found by the given rule in the file with the given
+/// name, or `None` if the rule does not have to be checked in the file.
+///
+/// The SDK, permission and file restrictions of the rule are checked, as well as the minimum
+/// criticality of the analysis.
+fn rule_criticality(
+    rule: &Rule,
+    filename: Option<&str>,
+    manifest: &Option<Manifest>,
+    in_exposed_provider: bool,
+    min_criticality: Criticality,
+) -> Option<Criticality> {
+    if let (Some(manifest), Some(max_sdk)) = (manifest, rule.max_sdk()) {
+        if max_sdk < manifest.min_sdk() {
+            return None;
+        }
+    }
+    if let (Some(manifest), Some(min_target_sdk)) = (manifest, rule.min_target_sdk()) {
+        let target_sdk = manifest.target_sdk().unwrap_or_else(|| manifest.min_sdk());
+        if target_sdk < min_target_sdk {
+            return None;
+        }
+    }
 
-                    let forward_check1 = caps.name("fc1");
-                    let forward_check2 = caps.name("fc2");
-                    let mut r = check.clone();
+    let criticality = rule.criticality_in(in_exposed_provider);
+    if criticality < min_criticality {
+        return None;
+    }
+    if let Some(f) = filename {
+        if !rule.has_to_check(f) {
+            return None;
+        }
+    }
 
-                    if let Some(fc1) = forward_check1 {
-                        r = r.replace("{fc1}", fc1.as_str());
-                    }
+    for permission in rule.permissions() {
+        if manifest.is_none()
+            || !manifest
+                .as_ref()
+                .unwrap()
+                .permission_checklist()
+                .needs_permission(permission)
+        {
+            return None;
+        }
+    }
+
+    Some(criticality)
+}
 
-                    if let Some(fc2) = forward_check2 {
-                        r = r.replace("{fc2}", fc2.as_str());
+/// Searches the regex of the given rule in the given code, and adds the vulnerabilities found to
+/// the results.
+///
+/// The whitelist and the forward check of the rule are applied. The given first line is the line
+/// of the file where the code starts, for files analyzed in chunks.
+fn find_regex_matches(
+    rule: &Rule,
+    criticality: Criticality,
+    code: &str,
+    first_line: usize,
+    file: &Path,
+    results: &mut Vec<Vulnerability>,
+) {
+    'rule: for m in rule.regex().find_iter(code) {
+        for white in rule.whitelist() {
+            if white.is_match(&code[m.start()..m.end()]) {
+                continue 'rule;
+            }
+        }
+        match rule.forward_check() {
+            None => {
+                let start_line = get_line_for(m.start(), code);
+                let end_line = get_line_for(m.end(), code);
+                results.push(
+                    rule.vulnerability(criticality)
+                        .file(file)
+                        .lines(first_line + start_line, first_line + end_line)
+                        .code(get_code(code, start_line, end_line))
+                        .evidence(m.as_str())
+                        .build(),
+                );
+
+                print_vulnerability(rule.description(), criticality);
+            }
+            Some(_) => {
+                let regex = match rule.forward_check_regex(m.as_str()) {
+                    Ok(Some(r)) => r,
+                    Ok(None) => continue 'rule,
+                    Err(e) => {
+                        print_warning(format!("{}. The rule will be skipped.", e));
+                        break 'rule;
                     }
+                };
 
-                    let regex = match Regex::new(r.as_str()) {
-                        Ok(r) => r,
-                        Err(e) => {
-                            print_warning(format!(
-                                "there was an error creating the forward_check '{}'. The rule will \
-                                 be skipped. {}",
-                                r,
-                                e
-                            ));
-                            break 'rule;
-                        }
-                    };
-
-                    for m in regex.find_iter(code.as_str()) {
-                        let start_line = get_line_for(m.start(), code.as_str());
-                        let end_line = get_line_for(m.end(), code.as_str());
-                        let mut results = results.lock().unwrap();
-                        results.push(Vulnerability::new(
-                            rule.criticality(),
-                            rule.label(),
-                            rule.description(),
-                            Some(path.as_ref().strip_prefix(&dist_folder).unwrap()),
-                            Some(start_line),
-                            Some(end_line),
-                            Some(get_code(code.as_str(), start_line, end_line)),
-                        ));
+                for m in regex.find_iter(code) {
+                    let start_line = get_line_for(m.start(), code);
+                    let end_line = get_line_for(m.end(), code);
+                    results.push(
+                        rule.vulnerability(criticality)
+                            .file(file)
+                            .lines(first_line + start_line, first_line + end_line)
+                            .code(get_code(code, start_line, end_line))
+                            .evidence(m.as_str())
+                            .build(),
+                    );
 
-                        print_vulnerability(rule.description(), rule.criticality());
-                    }
+                    print_vulnerability(rule.description(), criticality);
                 }
             }
         }
     }
+}
 
-    Ok(())
+/// Gets the fully qualified name of the class decompiled to the given Java file, if it is one.
+fn class_name(path: &Path, dist_folder: &Path) -> Option<String> {
+    let class_path = path
+        .strip_prefix(dist_folder)
+        .ok()?
+        .strip_prefix("classes")
+        .ok()?;
+    if class_path.extension()? != "java" {
+        return None;
+    }
+    let components = class_path
+        .with_extension("")
+        .iter()
+        .map(|component| component.to_str().map(str::to_owned))
+        .collect::<Option<Vec<_>>>()?;
+    Some(components.join("."))
 }
 
 fn get_line_for<S: AsRef<str>>(index: usize, text: S) -> usize {
@@ -274,15 +936,40 @@ fn get_line_for<S: AsRef<str>>(index: usize, text: S) -> usize {
     line
 }
 
-fn add_files_to_vec<P: AsRef<Path>, S: AsRef<str>>(
+/// Name of the JavaScript bundle of React Native applications.
+const REACT_NATIVE_BUNDLE: &str = "index.android.bundle";
+
+/// File types checked by the rules that do not select the files they are applied to.
+const DEFAULT_RULE_FILE_TYPES: [&str; 2] = ["java", "xml"];
+
+/// Gets the file type of the given file name, its extension, used to select the files the rules
+/// are applied to.
+///
+/// The React Native bundle is a JavaScript file.
+fn file_type(filename: &str) -> Option<&str> {
+    if filename == REACT_NATIVE_BUNDLE {
+        Some("js")
+    } else {
+        Path::new(filename).extension().and_then(|ext| ext.to_str())
+    }
+}
+
+/// Adds all the files of the application that have to be analyzed to the given vector.
+///
+/// The files with the extensions set in the `code_extensions` option of the configuration are
+/// analyzed, by default the Java code, the XML resources and the JavaScript files of hybrid
+/// applications, as well as the React Native bundle, unless it is compiled to Hermes bytecode.
+/// The folders in the `ignored_folders` option and the files bigger than the `max_file_size`
+/// option are skipped.
+pub(crate) fn add_files_to_vec<P: AsRef<Path>, S: AsRef<str>>(
     path: P,
     vec: &mut Vec<DirEntry>,
     package: S,
     config: &Config,
 ) -> Result<(), Error> {
-    if path.as_ref() == Path::new("classes/android")
-        || path.as_ref() == Path::new("classes/com/google/android/gms")
-        || path.as_ref() == Path::new("smali")
+    if config
+        .ignored_folders()
+        .any(|folder| path.as_ref() == folder.as_path())
     {
         return Ok(());
     }
@@ -311,17 +998,38 @@ fn add_files_to_vec<P: AsRef<Path>, S: AsRef<str>>(
                 package.as_ref(),
                 config,
             )?;
-        } else if f_ext.is_some() {
+        } else if let Some(ext) = f_ext {
             let filename = f_path.file_name().unwrap().to_string_lossy();
-            if filename != "AndroidManifest.xml"
-                && filename != "R.java"
-                && !filename.starts_with("R$")
+            if filename == "AndroidManifest.xml"
+                || filename == "R.java"
+                || filename.starts_with("R$")
             {
-                match f_ext.unwrap().to_string_lossy().borrow() {
-                    "xml" | "java" => vec.push(f),
-                    _ => {}
+                continue;
+            }
+            let ext = ext.to_string_lossy();
+            let analyzed = if filename == REACT_NATIVE_BUNDLE {
+                !is_hermes_bundle(&f_path)
+            } else {
+                config
+                    .code_extensions()
+                    .any(|code_ext| code_ext.trim_start_matches('.') == ext)
+            };
+            if !analyzed {
+                continue;
+            }
+            if let Some(max_size) = config.max_file_size() {
+                if f.metadata()?.len() > max_size * 1024 {
+                    if config.is_verbose() {
+                        print_warning(format!(
+                            "`{}` is bigger than {} KiB, it will not be analyzed",
+                            f_path.display(),
+                            max_size
+                        ));
+                    }
+                    continue;
                 }
             }
+            vec.push(f);
         }
     }
     Ok(())
@@ -329,28 +1037,57 @@ fn add_files_to_vec<P: AsRef<Path>, S: AsRef<str>>(
 
 /// Vulnerability searching rule.
 #[derive(Debug, Deserialize)]
-struct Rule {
+pub(crate) struct Rule {
+    id: Option<String>,
     #[serde(deserialize_with = "deserialize_main_regex")]
     regex: Regex,
     #[serde(default)]
     permissions: Box<[Permission]>,
-    forward_check: Option<String>,
+    forward_check: Option<ForwardCheck>,
     max_sdk: Option<u32>,
+    min_target_sdk: Option<u32>,
     #[serde(deserialize_with = "deserialize_whitelist_regex")]
     #[serde(default)]
     whitelist: Box<[Regex]>,
     label: String,
     description: String,
     criticality: Criticality,
+    exposed_provider_criticality: Option<Criticality>,
     #[serde(deserialize_with = "deserialize_file_regex")]
     #[serde(default)]
     include_file_regex: Option<Regex>,
     #[serde(deserialize_with = "deserialize_file_regex")]
     #[serde(default)]
     exclude_file_regex: Option<Regex>,
+    cwe: Option<u32>,
+    #[serde(default)]
+    masvs: Box<[String]>,
+    category: Option<String>,
+    cvss: Option<Cvss>,
+    #[serde(default)]
+    references: Box<[String]>,
+    #[serde(default)]
+    examples: Box<[String]>,
+    #[serde(default)]
+    counter_examples: Box<[String]>,
+    flow: Option<FlowSpec>,
+    query: Option<AstQuery>,
+    #[serde(default)]
+    file_types: Box<[String]>,
+    requires: Option<Requirement>,
 }
 
 impl Rule {
+    /// Gets the identifier of the rule.
+    ///
+    /// If the rule has no explicit identifier, it is generated from its label.
+    pub fn id(&self) -> Cow<str> {
+        match self.id {
+            Some(ref id) => Cow::Borrowed(id),
+            None => Cow::Owned(slug(&self.label)),
+        }
+    }
+
     /// Gets the regex of the rule.
     pub fn regex(&self) -> &Regex {
         &self.regex
@@ -362,7 +1099,7 @@ impl Rule {
     }
 
     /// Gets the potential forward check of the rule.
-    pub fn forward_check(&self) -> Option<&String> {
+    pub(crate) fn forward_check(&self) -> Option<&ForwardCheck> {
         self.forward_check.as_ref()
     }
 
@@ -371,6 +1108,11 @@ impl Rule {
         self.max_sdk
     }
 
+    /// Gets the minimum target SDK affected by this vulnerability.
+    pub fn min_target_sdk(&self) -> Option<u32> {
+        self.min_target_sdk
+    }
+
     /// Gets the label of the vulnerability.
     pub fn label(&self) -> &str {
         self.label.as_str()
@@ -391,8 +1133,199 @@ impl Rule {
         self.whitelist.iter()
     }
 
+    /// Gets the CWE identifier of the weakness detected by the rule.
+    pub fn cwe(&self) -> Option<u32> {
+        self.cwe
+    }
+
+    /// Gets the OWASP MASVS controls or MSTG test cases related to the rule.
+    pub fn masvs(&self) -> &[String] {
+        &self.masvs
+    }
+
+    /// Gets the category of the findings of the rule in the report, if any.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_ref().map(String::as_str)
+    }
+
+    /// Gets the CVSS v3.1 base vector of the findings of the rule.
+    pub fn cvss(&self) -> Option<Cvss> {
+        self.cvss
+    }
+
+    /// Gets the references with further information about the rule.
+    pub fn references(&self) -> &[String] {
+        &self.references
+    }
+
+    /// Gets the example code snippets matched by the rule.
+    pub fn examples(&self) -> &[String] {
+        &self.examples
+    }
+
+    /// Gets the example code snippets that must not be matched by the rule.
+    pub fn counter_examples(&self) -> &[String] {
+        &self.counter_examples
+    }
+
+    /// Gets the source to sink flows searched by the rule, if it is a flow rule.
+    pub fn flow(&self) -> Option<&FlowSpec> {
+        self.flow.as_ref()
+    }
+
+    /// Gets the syntax query of the rule, if any.
+    pub fn query(&self) -> Option<&AstQuery> {
+        self.query.as_ref()
+    }
+
+    /// Gets the pattern that has to be found anywhere in the application for the matches of the
+    /// rule to be reported, if any.
+    pub(crate) fn requires(&self) -> Option<&Requirement> {
+        self.requires.as_ref()
+    }
+
+    /// Checks if the given flow, found in the given code, is reported by the rule.
+    ///
+    /// The flow must be one of the flows searched by the rule, and the line of its sink must not
+    /// match the whitelist of the rule.
+    fn accepts_flow(&self, flow: &Flow, code: &str) -> bool {
+        let spec = match self.flow {
+            Some(ref spec) => spec,
+            None => return false,
+        };
+        let sink = code.lines().nth(flow.sink_line()).unwrap_or_default();
+        spec.accepts(flow) && !self.whitelist.iter().any(|white| white.is_match(sink))
+    }
+
+    /// Checks if the rule finds any vulnerability in the given code.
+    ///
+    /// The whitelist and the forward check of the rule are applied, but not its permission, SDK
+    /// or file restrictions, nor its requirement. Flow rules search their flows in the code if
+    /// their regex matches, and so do query rules with their syntax query if SUPER is built with
+    /// the `ast` feature.
+    pub fn matches(&self, code: &str) -> Result<bool, Error> {
+        if self.flow.is_some() {
+            return Ok(self.regex.is_match(code)
+                && dataflow::analyze(code)
+                    .iter()
+                    .any(|flow| self.accepts_flow(flow, code)));
+        }
+        #[cfg(feature = "ast")]
+        {
+            if let Some(ref query) = self.query {
+                return Ok(self.regex.is_match(code)
+                    && ast::parse(code).map_or(false, |tree| {
+                        query.find(&tree, code).into_iter().any(|range| {
+                            !self
+                                .whitelist
+                                .iter()
+                                .any(|white| white.is_match(&code[range.clone()]))
+                        })
+                    }));
+            }
+        }
+        for m in self.regex.find_iter(code) {
+            if self
+                .whitelist
+                .iter()
+                .any(|white| white.is_match(m.as_str()))
+            {
+                continue;
+            }
+            match self.forward_check_regex(m.as_str())? {
+                None => return Ok(true),
+                Some(ref regex) if regex.is_match(code) => return Ok(true),
+                Some(_) => {}
+            }
+        }
+        Ok(false)
+    }
+
+    /// Builds the forward check regex for the given match of the rule, if it has a forward check.
+    ///
+    /// The `{fc1}`, `{fc2}`, `{fc3}`… placeholders are replaced with the corresponding capture
+    /// groups, and the compiled regex is cached in the forward check.
+    fn forward_check_regex(&self, matched: &str) -> Result<Option<Regex>, Error> {
+        let check = match self.forward_check {
+            Some(ref check) => check,
+            None => return Ok(None),
+        };
+        let caps = match self.regex.captures(matched) {
+            Some(caps) => caps,
+            None => return Ok(None),
+        };
+
+        check.regex(&caps).map(Some)
+    }
+
+    /// Gets the regex of the files this rule is applied to, if it is restricted.
+    pub fn include_file_regex(&self) -> Option<&Regex> {
+        self.include_file_regex.as_ref()
+    }
+
+    /// Gets the regex of the files this rule is not applied to, if any.
+    pub fn exclude_file_regex(&self) -> Option<&Regex> {
+        self.exclude_file_regex.as_ref()
+    }
+
+    /// Gets the file types, by their extension, this rule is applied to, if it is restricted.
+    pub fn file_types(&self) -> &[String] {
+        &self.file_types
+    }
+
+    /// Gets the criticality of the vulnerabilities found in exposed content providers, if they are
+    /// escalated.
+    pub fn exposed_provider_criticality(&self) -> Option<Criticality> {
+        self.exposed_provider_criticality
+    }
+
+    /// Gets the criticality of the vulnerabilities found by the rule in a file, depending on
+    /// whether the file is the class of a content provider exposed to other applications.
+    fn criticality_in(&self, exposed_provider: bool) -> Criticality {
+        match self.exposed_provider_criticality {
+            Some(criticality) if exposed_provider => criticality.max(self.criticality),
+            _ => self.criticality,
+        }
+    }
+
+    /// Creates the builder for a vulnerability found by this rule with the given criticality.
+    fn vulnerability(&self, criticality: Criticality) -> VulnerabilityBuilder {
+        let builder = Vulnerability::builder(criticality, &self.label, &self.description)
+            .rule_id(self.id())
+            .masvs(self.masvs.iter().map(String::as_str));
+        let builder = match self.cvss {
+            Some(cvss) => builder.cvss(cvss),
+            None => builder,
+        };
+        let builder = match self.category {
+            Some(ref category) => builder.category(category.as_str()),
+            None => builder,
+        };
+        match self.cwe {
+            Some(cwe) => builder.cwe(cwe),
+            None => builder,
+        }
+    }
+
     /// Returns if this rule has to be applied to the given filename
+    ///
+    /// Rules with `file_types` are only applied to the files with those extensions. Files other
+    /// than Java code and XML resources, such as JavaScript files, are only checked by the rules
+    /// that explicitly include them, since the rest of rules are written for Java code and
+    /// resources.
     pub fn has_to_check(&self, filename: &str) -> bool {
+        let file_type = file_type(filename).unwrap_or_default();
+        if self.file_types.is_empty() {
+            if !DEFAULT_RULE_FILE_TYPES.contains(&file_type) && self.include_file_regex.is_none() {
+                return false;
+            }
+        } else if !self
+            .file_types
+            .iter()
+            .any(|t| t.trim_start_matches('.') == file_type)
+        {
+            return false;
+        }
         if self.include_file_regex.is_none() && self.exclude_file_regex.is_none() {
             return true;
         }
@@ -411,6 +1344,51 @@ impl Rule {
     }
 }
 
+/// Pattern that has to be found anywhere in the application for a rule to report its matches.
+///
+/// Many vulnerabilities span several files, such as a permission requested in the manifest and the
+/// API that uses it in the code, so rules can require a second pattern, optionally in the files
+/// matching a regex, such as `AndroidManifest\.xml$`:
+///
+/// ```json
+/// "requires": {
+///     "regex": "android\\.permission\\.SEND_SMS",
+///     "file_regex": "AndroidManifest\\.xml$"
+/// }
+/// ```
+///
+/// Requirements are only searched in the decoded `AndroidManifest.xml` file and in the files that
+/// the code analysis checks, regardless of the `file_types` of the rules: the text files with one
+/// of the `code_extensions` of the configuration, up to the `max_file_size`. Other files, such as
+/// native libraries or images, are never searched.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Requirement {
+    #[serde(deserialize_with = "deserialize_main_regex")]
+    regex: Regex,
+    #[serde(deserialize_with = "deserialize_file_regex")]
+    #[serde(default)]
+    file_regex: Option<Regex>,
+}
+
+impl Requirement {
+    /// Checks if the requirement is found in the given code of the given file, relative to the
+    /// folder of the application.
+    fn is_match(&self, file: &Path, code: &str) -> bool {
+        self.file_regex.as_ref().map_or(true, |file_regex| {
+            file_regex.is_match(&file.to_string_lossy())
+        }) && self.regex.is_match(code)
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.file_regex {
+            Some(ref file_regex) => write!(f, "{} (in files matching {})", self.regex, file_regex),
+            None => write!(f, "{}", self.regex),
+        }
+    }
+}
+
 /// Regular expression serde visitor.
 struct RegexVisitor;
 
@@ -521,16 +1499,153 @@ where
     deserializer.deserialize_option(RegexOptionVisitor)
 }
 
-fn load_rules(config: &Config) -> Result<Vec<Rule>, Error> {
-    let f = File::open(config.rules_json())?;
+/// Generates an identifier from the given label, in lowercase and with words separated by dashes.
+fn slug(label: &str) -> String {
+    label
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Extensions of the supported rule files.
+const RULE_EXTENSIONS: [&str; 4] = ["json", "yaml", "yml", "toml"];
+
+/// TOML rule file, where each rule is a `[[rules]]` table.
+#[derive(Debug, Deserialize)]
+struct TomlRules {
+    /// Rules in the file.
+    rules: Vec<Rule>,
+}
+
+/// Parses the contents of a rule file, in the format given by its extension.
+///
+/// YAML files (`.yaml` or `.yml`) contain a sequence of rules, like JSON files, while TOML files
+/// contain an array of `[[rules]]` tables. Any other file is parsed as JSON.
+pub(crate) fn parse_rules(path: &Path, contents: &[u8]) -> Result<Vec<Rule>, Error> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+    let rules = match extension.as_ref().map(String::as_str) {
+        Some("yaml") | Some("yml") => serde_yaml::from_slice(contents)?,
+        Some("toml") => toml::from_slice::<TomlRules>(contents)?.rules,
+        _ => serde_json::from_slice(contents)?,
+    };
+    Ok(rules)
+}
+
+/// Gets the rule files at the given path.
+///
+/// If the path is a folder, it is considered a set of rule packs, and all the JSON, YAML and TOML
+/// files in it and its subfolders are returned, sorted by path so that rules are always loaded in
+/// the same order.
+pub(crate) fn rule_files(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path).context(format!(
+        "could not read the rules folder `{}`",
+        path.display()
+    ))? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            files.extend(rule_files(&entry_path)?);
+        } else if entry_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| {
+                RULE_EXTENSIONS.contains(&ext.to_lowercase().as_str())
+            })
+        {
+            files.push(entry_path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Adds the given layer of rules to the rules, replacing the rules with the same identifier.
+///
+/// Rules with the same identifier in the same layer are all kept.
+fn merge_rules(rules: &mut Vec<Rule>, layer: Vec<Rule>) {
+    let overridden = layer
+        .iter()
+        .map(|rule| rule.id().into_owned())
+        .collect::<BTreeSet<_>>();
+    rules.retain(|rule| !overridden.contains(rule.id().as_ref()));
+    rules.extend(layer);
+}
+
+/// Checks that the capture groups of the regex of the given rule match the placeholders of its
+/// forward check.
+///
+/// Capture groups without a placeholder are an error, while placeholders without a capture group
+/// are kept as they are, with a warning.
+fn check_forward_check(rule: &Rule) -> Result<(), Error> {
+    let captures = rule
+        .regex()
+        .capture_names()
+        .flatten()
+        .filter(|name| forward_check::is_capture_name(name))
+        .collect::<Vec<_>>();
+    let forward_check = match rule.forward_check() {
+        Some(forward_check) => forward_check,
+        None => return Ok(()),
+    };
+
+    for capture in &captures {
+        if !forward_check.uses(capture) {
+            return Err(error::Kind::Parse
+                .context(format!(
+                    "{} capture group used but no placeholder found in the forward check",
+                    capture
+                ))
+                .into());
+        }
+        let index = capture[2..].parse::<usize>().unwrap_or_default();
+        if let Some(previous) = index.checked_sub(1).filter(|&previous| previous > 0) {
+            let previous = format!("fc{}", previous);
+            if !captures.contains(&previous.as_str()) {
+                print_warning(format!(
+                    "{} capture group used in the `{}` rule's forward check, but no {} capture \
+                     group used",
+                    capture,
+                    rule.label(),
+                    previous
+                ));
+            }
+        }
+    }
+    for placeholder in forward_check.placeholders() {
+        if !captures.contains(&placeholder) {
+            print_warning(format!(
+                "{{{}}} used in the `{}` rule's forward check, but no capture group is checking \
+                 for it",
+                placeholder,
+                rule.label()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Loads the rules with the minimum criticality from the rules files.
+///
+/// Each rules path can be a single rule file or a folder of rule packs, in JSON, YAML or TOML.
+/// The rules of each path override the rules with the same identifier of the previous paths.
+pub(crate) fn load_rules(config: &Config) -> Result<Vec<Rule>, Error> {
     let format_error = format!(
         "rules must be objects with the following structure:\n{}\nAn optional {} attribute can be \
          added: an array of regular expressions that if matched, the found match will be \
          discarded. You can also include an optional {} attribute: an array of the permissions \
          needed for this rule to be checked. And finally, an optional {} attribute can be added \
          where you can specify a second regular expression to check if the one in the {} attribute \
-         matches. You can add one or two capture groups with name from the match to this check, \
-         with names {} and {}. To use them you have to include {} or {} in the forward check.",
+         matches. You can add capture groups with name from the match to this check, with names \
+         {}, {} and so on. To use them you have to include {}, {}… in the forward check.",
         "{\n\t\"label\": \"Label for the rule\",\n\t\"description\": \"Long description for this \
          rule\"\n\t\"criticality\": \"warning|low|medium|high|critical\"\n\t\"regex\": \
          \"regex_to_find_vulnerability\"\n}"
@@ -545,68 +1660,44 @@ fn load_rules(config: &Config) -> Result<Vec<Rule>, Error> {
         "{fc2}".italic()
     );
 
-    let rules: Vec<Rule> = serde_json::from_reader(f).context(format_error.clone())?;
-    let rules =
-        rules
-            .into_iter()
-            .filter_map(|rule| {
-                if rule.criticality >= config.min_criticality() {
-                    let fc1_in_regex = rule.regex().capture_names().any(|c| c == Some("fc1"));
-                    let fc2_in_regex = rule.regex().capture_names().any(|c| c == Some("fc2"));
-
-                    let forward_check = rule.forward_check().cloned();
-                    if let Some(forward_check) = forward_check {
-                        let fc1_in_fc = forward_check.contains("{fc1}");
-                        let fc2_in_fc = forward_check.contains("{fc2}");
-
-                        if fc1_in_regex && !fc1_in_fc {
-                            Some(Err(error::Kind::Parse
-                            .context(
-                                "fc1 capture group used but no placeholder found in the forward \
-                                 check",
-                            ).into()))
-                        } else if fc2_in_regex && !fc2_in_fc {
-                            Some(Err(error::Kind::Parse
-                            .context(
-                                "fc2 capture group used but no placeholder found in the forward \
-                                 check",
-                            ).into()))
-                        } else {
-                            if fc2_in_regex && !fc1_in_regex {
-                                print_warning(format!(
-                                "fc2 capture group used in the `{}` rule's forward check, but no \
-                                 fc1 capture group used",
-                                rule.label()
-                            ));
-                            }
-
-                            if fc1_in_fc && !fc1_in_regex {
-                                print_warning(format!(
-                                "{{fc1}} used in the `{}` rule's forward check, but no capture \
-                                 group is checking for it",
-                                rule.label()
-                            ));
-                            }
-
-                            if fc2_in_fc && !fc2_in_regex {
-                                print_warning(format!(
-                                "{{fc2}} used in the `{}` rule's forward check, but no capture \
-                                 group is checking for it",
-                                rule.label()
-                            ));
-                            }
-
-                            Some(Ok(rule))
-                        }
-                    } else {
-                        Some(Ok(rule))
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect::<Result<Vec<Rule>, Error>>()
-            .context(format_error)?;
+    let mut rules = Vec::new();
+    for path in config.rules_paths() {
+        let mut layer = Vec::new();
+        for file in rule_files(path)? {
+            let contents = signature::read_rules(config, &file).context(error::Kind::RuleLoad {
+                path: file.display().to_string(),
+            })?;
+            layer.extend(
+                parse_rules(&file, &contents)
+                    .context(error::Kind::RuleLoad {
+                        path: file.display().to_string(),
+                    })
+                    .context(format_error.clone())?,
+            );
+        }
+        merge_rules(&mut rules, layer);
+    }
+    let rules = rules
+        .into_iter()
+        .filter_map(|rule| {
+            if rule.criticality_in(true) >= config.min_criticality() {
+                Some(check_forward_check(&rule).map(|()| rule))
+            } else {
+                None
+            }
+        })
+        .collect::<Result<Vec<Rule>, Error>>()
+        .context(format_error)?;
+
+    #[cfg(not(feature = "ast"))]
+    {
+        if rules.iter().any(|rule| rule.query().is_some()) {
+            print_warning(
+                "some rules have syntax queries, but SUPER was built without the `ast` feature, \
+                 so their regular expressions will be used instead",
+            );
+        }
+    }
 
     Ok(rules)
 }
@@ -614,10 +1705,21 @@ fn load_rules(config: &Config) -> Result<Vec<Rule>, Error> {
 #[cfg(test)]
 #[allow(clippy::trivial_regex)]
 mod tests {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+        slice,
+        time::Duration,
+    };
+
     use failure::Error;
     use regex::Regex;
 
-    use super::{load_rules, Rule};
+    use super::{
+        analyze_file, analyze_file_in_chunks, build_prefilter, check_forward_check, class_name,
+        is_binary, load_rules, merge_rules, parse_rules, slug, RequirementIndex, Rule, RuleBudget,
+        RuleSet, WorkQueue, WorkerOutput, CHUNK_SIZE, HYGIENE_CATEGORY,
+    };
     use crate::{config::Config, criticality::Criticality};
 
     /// Prints information about the given error.
@@ -629,6 +1731,80 @@ mod tests {
         }
     }
 
+    /// Checks that the work queue gives every job exactly once, stealing from other workers.
+    #[test]
+    fn it_work_queue() {
+        let queue = WorkQueue::new(10, 3);
+        assert_eq!(queue.workers(), 3);
+
+        let mut jobs: Vec<_> = (0..4).filter_map(|_| queue.next(0)).collect();
+        assert_eq!(jobs, vec![0, 3, 6, 9]);
+
+        while let Some(job) = queue.next(0) {
+            jobs.push(job);
+        }
+        assert_eq!(queue.next(1), None);
+        assert_eq!(queue.next(2), None);
+
+        jobs.sort();
+        assert_eq!(jobs, (0..10).collect::<Vec<_>>());
+
+        let single = WorkQueue::new(2, 0);
+        assert_eq!(single.workers(), 1);
+        assert_eq!(single.next(0), Some(0));
+        assert_eq!(single.next(0), Some(1));
+        assert_eq!(single.next(0), None);
+    }
+
+    /// Checks that rules can be written in YAML and TOML.
+    #[test]
+    fn it_rule_formats() {
+        let yaml = "- id: yaml-rule\n  label: YAML rule\n  description: A rule in YAML.\n  \
+                    criticality: high\n  regex: 'getInstance\\(\"MD5\"\\)'\n  \
+                    masvs: [MSTG-CRYPTO-4]\n";
+        let rules = parse_rules(Path::new("pack.yml"), yaml.as_bytes()).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id(), "yaml-rule");
+        assert_eq!(rules[0].criticality(), Criticality::High);
+        assert!(rules[0].regex().is_match("getInstance(\"MD5\")"));
+
+        let toml = "[[rules]]\nlabel = \"TOML rule\"\ndescription = \"A rule in TOML.\"\n\
+                    criticality = \"low\"\nregex = 'Log\\.d'\nwhitelist = ['Debug']\n";
+        let rules = parse_rules(Path::new("pack.toml"), toml.as_bytes()).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id(), "toml-rule");
+        assert_eq!(rules[0].whitelist().count(), 1);
+        assert!(rules[0].regex().is_match("Log.d(TAG, message)"));
+
+        assert!(parse_rules(Path::new("pack.toml"), yaml.as_bytes()).is_err());
+    }
+
+    /// Checks that the rules of a layer override the rules with the same ID.
+    #[test]
+    fn it_merge_rules() {
+        let official = "- id: weak-hash\n  label: MD5\n  description: Official.\n  \
+                        criticality: high\n  regex: 'MD5'\n- id: logs\n  label: Logs\n  \
+                        description: Official.\n  criticality: low\n  regex: 'Log\\.d'\n";
+        let project = "- id: weak-hash\n  label: MD5\n  description: Project.\n  \
+                       criticality: medium\n  regex: 'MD5'\n- id: custom\n  label: Custom\n  \
+                       description: Project.\n  criticality: low\n  regex: 'Custom'\n";
+
+        let mut rules = Vec::new();
+        merge_rules(
+            &mut rules,
+            parse_rules(Path::new("official.yml"), official.as_bytes()).unwrap(),
+        );
+        merge_rules(
+            &mut rules,
+            parse_rules(Path::new("project.yml"), project.as_bytes()).unwrap(),
+        );
+        assert_eq!(
+            rules.iter().map(|rule| rule.id()).collect::<Vec<_>>(),
+            vec!["logs", "weak-hash", "custom"]
+        );
+        assert_eq!(rules[1].criticality(), Criticality::Medium);
+    }
+
     fn check_match<S: AsRef<str>>(text: S, rule: &Rule) -> bool {
         if rule.regex().is_match(text.as_ref()) {
             for white in rule.whitelist() {
@@ -656,20 +1832,7 @@ mod tests {
                 }
                 Some(check) => {
                     let caps = rule.regex().captures(text.as_ref()).unwrap();
-
-                    let forward_check1 = caps.name("fc1");
-                    let forward_check2 = caps.name("fc2");
-                    let mut r = check.clone();
-
-                    if let Some(fc1) = forward_check1 {
-                        r = r.replace("{fc1}", fc1.as_str());
-                    }
-
-                    if let Some(fc2) = forward_check2 {
-                        r = r.replace("{fc2}", fc2.as_str());
-                    }
-
-                    let regex = Regex::new(r.as_str()).unwrap();
+                    let regex = check.regex(&caps).unwrap();
                     if regex.is_match(text.as_ref()) {
                         let m = regex.find(text.as_ref()).unwrap();
                         println!(
@@ -990,6 +2153,12 @@ mod tests {
             }
         };
         let rule = &rules[8];
+        assert_eq!(rule.cwe(), Some(327));
+        assert_eq!(rule.masvs(), ["MASVS-CRYPTO-1"]);
+        assert_eq!(
+            rule.vulnerability(rule.criticality()).build().masvs(),
+            ["MASVS-CRYPTO-1"]
+        );
 
         let should_match = &[
             "DESKeySpec",
@@ -1250,6 +2419,7 @@ mod tests {
             "android.database.sqlite   .rawQuery(\"INSERT INTO myuser VALUES \
              ('\" + paramView.getText().toString() + \"', '\" + \
              localEditText.getText().toString() + \"');\");",
+            "android.database.sqlite   .rawQuery(query + \" ORDER BY name\", null);",
         ];
 
         let should_not_match = &[
@@ -1266,6 +2436,183 @@ mod tests {
         for m in should_not_match {
             assert!(!check_match(m, rule));
         }
+
+        assert_eq!(rule.criticality_in(false), Criticality::High);
+        assert_eq!(rule.criticality_in(true), Criticality::Critical);
+    }
+
+    /// Checks the detection of binary files and the analysis of large files in chunks.
+    #[test]
+    fn it_analyze_file_in_chunks() {
+        let dist_folder = env::temp_dir().join("super-chunks-test");
+        let _ = fs::remove_dir_all(&dist_folder);
+        fs::create_dir_all(&dist_folder).unwrap();
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "password = \"[^\"]+\"",
+                "label": "Hardcoded password",
+                "description": "Description",
+                "criticality": "high"
+            }"#,
+        )
+        .unwrap();
+        let mut rules = RuleSet {
+            rules: vec![rule],
+            prefilter: None,
+            budget: RuleBudget::new(1, Duration::from_secs(60), false),
+            requirements: RequirementIndex::new(&[]),
+        };
+
+        let binary = dist_folder.join("binary.xml");
+        fs::write(&binary, b"\x03\x00\x08\x00password = \"secret\"").unwrap();
+        assert!(is_binary(&binary).unwrap());
+        let mut output = WorkerOutput::new(rules.rules.len());
+        analyze_file(
+            &binary,
+            &dist_folder,
+            &rules,
+            &None,
+            Criticality::Warning,
+            &mut output,
+        )
+        .unwrap();
+        assert!(output.vulnerabilities.is_empty());
+        assert_eq!(output.skipped, 0);
+
+        let large = dist_folder.join("Large.java");
+        let mut code = "// padding\n".repeat(CHUNK_SIZE / 11 + 1);
+        code.push_str("String password = \"secret\";\n");
+        code.push_str(&"x".repeat(CHUNK_SIZE * 2));
+        code.push_str("\npassword = \"other\"");
+        fs::write(&large, &code).unwrap();
+        assert!(!is_binary(&large).unwrap());
+        rules.prefilter = Some(build_prefilter(&rules.rules).unwrap());
+        analyze_file_in_chunks(
+            &large,
+            &dist_folder,
+            &rules,
+            &None,
+            Criticality::Warning,
+            &mut output,
+        )
+        .unwrap();
+        let results = output.vulnerabilities;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].file(), Some(Path::new("Large.java")));
+        assert_eq!(results[0].start_line(), Some(CHUNK_SIZE / 11 + 1));
+        assert_eq!(results[0].evidence(), Some("password = \"secret\""));
+        assert_eq!(results[1].start_line(), Some(CHUNK_SIZE / 11 + 3));
+        assert_eq!(output.rule_timings[0].evaluations, 2);
+
+        fs::remove_dir_all(&dist_folder).unwrap();
+    }
+
+    /// Checks that the matches of the rules with a requirement are kept apart until the
+    /// requirement is found in any file.
+    #[test]
+    fn it_requirement() {
+        let dist_folder = env::temp_dir().join("super-requirement-test");
+        let _ = fs::remove_dir_all(&dist_folder);
+        fs::create_dir_all(&dist_folder).unwrap();
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "sendTextMessage\\(",
+                "requires": {
+                    "regex": "android\\.permission\\.SEND_SMS",
+                    "file_regex": "AndroidManifest\\.xml$"
+                },
+                "label": "SMS sending",
+                "description": "Description",
+                "criticality": "medium"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            rule.requires().unwrap().to_string(),
+            "android\\.permission\\.SEND_SMS (in files matching AndroidManifest\\.xml$)"
+        );
+        let requirements = RequirementIndex::new(slice::from_ref(&rule));
+        let rules = RuleSet {
+            rules: vec![rule],
+            prefilter: None,
+            budget: RuleBudget::new(1, Duration::from_secs(60), false),
+            requirements,
+        };
+
+        let code = dist_folder.join("Sms.java");
+        fs::write(
+            &code,
+            "// android.permission.SEND_SMS\nsms.sendTextMessage(to, null, text, null, null);\n",
+        )
+        .unwrap();
+        let mut output = WorkerOutput::new(rules.rules.len());
+        analyze_file(
+            &code,
+            &dist_folder,
+            &rules,
+            &None,
+            Criticality::Warning,
+            &mut output,
+        )
+        .unwrap();
+        assert!(output.vulnerabilities.is_empty());
+        assert_eq!(output.pending.len(), 1);
+        assert_eq!(output.pending[0].0, 0);
+        assert!(!rules.requirements.is_found(0));
+
+        rules.requirements.record(
+            &rules.rules,
+            Path::new("AndroidManifest.xml"),
+            "<uses-permission android:name=\"android.permission.SEND_SMS\" />",
+        );
+        assert!(rules.requirements.is_found(0));
+        assert_eq!(
+            rules.requirements.found().get(&0).map(PathBuf::as_path),
+            Some(Path::new("AndroidManifest.xml"))
+        );
+
+        fs::remove_dir_all(&dist_folder).unwrap();
+    }
+
+    /// Checks that the rules that exceed their time budget stop being checked, if configured.
+    #[test]
+    fn it_rule_budget() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "password",
+                "label": "Password",
+                "description": "Description",
+                "criticality": "high"
+            }"#,
+        )
+        .unwrap();
+
+        let budget = RuleBudget::new(1, Duration::from_millis(100), false);
+        budget.record(0, &rule, Duration::from_millis(150));
+        assert!(!budget.is_disabled(0));
+
+        let budget = RuleBudget::new(1, Duration::from_millis(100), true);
+        budget.record(0, &rule, Duration::from_millis(60));
+        assert!(!budget.is_disabled(0));
+        budget.record(0, &rule, Duration::from_millis(60));
+        assert!(budget.is_disabled(0));
+    }
+
+    /// Checks the class names of the decompiled files, used to find content providers.
+    #[test]
+    fn it_class_name() {
+        let dist_folder = Path::new("dist/com.example.app");
+        assert_eq!(
+            class_name(
+                &dist_folder.join("classes/com/example/app/DataProvider.java"),
+                dist_folder
+            ),
+            Some(String::from("com.example.app.DataProvider"))
+        );
+        assert_eq!(
+            class_name(&dist_folder.join("res/xml/backup.xml"), dist_folder),
+            None
+        );
     }
 
     #[test]
@@ -1885,37 +3232,210 @@ mod tests {
         }
     }
 
+    /// Checks the rules for mutable pending intents, implicit intents and broadcasts.
+    #[test]
+    fn it_intent_misuse() {
+        let config = Config::default();
+        let rules = match load_rules(&config) {
+            Ok(r) => r,
+            Err(e) => {
+                print_error(&e);
+                panic!()
+            }
+        };
+        let rule = |id| rules.iter().find(|rule| rule.id() == id).unwrap();
+
+        let pending_intent = rule("mutable-pending-intent");
+        assert_eq!(pending_intent.min_target_sdk(), Some(23));
+        assert!(pending_intent
+            .matches("PendingIntent.getActivity(this, 0, intent, 0);")
+            .unwrap());
+        assert!(!pending_intent
+            .matches("PendingIntent.getActivity(this, 0, intent, PendingIntent.FLAG_IMMUTABLE);")
+            .unwrap());
+
+        let implicit_intent = rule("implicit-intent-sensitive-extra");
+        assert!(implicit_intent
+            .matches("Intent i = new Intent(Intent.ACTION_SEND);\ni.putExtra(\"session_id\", id);")
+            .unwrap());
+        assert!(!implicit_intent
+            .matches("Intent i = new Intent(Intent.ACTION_SEND);\ni.putExtra(\"text\", text);")
+            .unwrap());
+
+        let broadcast = rule("broadcast-without-permission");
+        assert!(broadcast
+            .matches("sendBroadcast(new Intent(ACTION));")
+            .unwrap());
+        assert!(broadcast
+            .matches("getContext().sendBroadcast(intent);")
+            .unwrap());
+        assert!(!broadcast
+            .matches("sendBroadcast(intent, Manifest.permission.RECEIVE);")
+            .unwrap());
+    }
+
+    /// Checks the rules for path traversals in content providers and external files.
+    #[test]
+    fn it_path_traversal() {
+        let config = Config::default();
+        let rules = match load_rules(&config) {
+            Ok(r) => r,
+            Err(e) => {
+                print_error(&e);
+                panic!()
+            }
+        };
+        let rule = |id| rules.iter().find(|rule| rule.id() == id).unwrap();
+
+        let provider = rule("path-traversal-content-provider");
+        assert_eq!(
+            provider.exposed_provider_criticality(),
+            Some(Criticality::Critical)
+        );
+        for example in provider.examples() {
+            assert!(provider.matches(example).unwrap());
+        }
+        for example in provider.counter_examples() {
+            assert!(!provider.matches(example).unwrap());
+        }
+        assert!(provider
+            .matches(
+                "public AssetFileDescriptor openAssetFile(Uri uri, String mode) {\n    \
+                 String path = uri.getPath();\n    \
+                 FileInputStream in = new FileInputStream(base + path);\n}"
+            )
+            .unwrap());
+
+        let external = rule("path-traversal-external-files");
+        for example in external.examples() {
+            assert!(external.matches(example).unwrap());
+        }
+        for example in external.counter_examples() {
+            assert!(!external.matches(example).unwrap());
+        }
+    }
+
+    /// Checks the hygiene rules for debug logs and `StrictMode`.
+    #[test]
+    fn it_hygiene() {
+        let config = Config::default();
+        let rules = match load_rules(&config) {
+            Ok(r) => r,
+            Err(e) => {
+                print_error(&e);
+                panic!()
+            }
+        };
+
+        for id in &["sensitive-data-in-debug-log", "strictmode-permit-all"] {
+            let rule = rules.iter().find(|rule| rule.id() == *id).unwrap();
+            assert_eq!(rule.category(), Some(HYGIENE_CATEGORY));
+            assert_eq!(
+                rule.vulnerability(rule.criticality()).build().category(),
+                Some(HYGIENE_CATEGORY)
+            );
+            for example in rule.examples() {
+                assert!(rule.matches(example).unwrap());
+            }
+            for example in rule.counter_examples() {
+                assert!(!rule.matches(example).unwrap());
+            }
+        }
+    }
+
+    /// Checks the rules of the JavaScript files of hybrid applications.
+    #[test]
+    fn it_javascript_rules() {
+        let config = Config::default();
+        let rules = match load_rules(&config) {
+            Ok(r) => r,
+            Err(e) => {
+                print_error(&e);
+                panic!()
+            }
+        };
+
+        for id in &[
+            "javascript-eval",
+            "javascript-http-url",
+            "javascript-hardcoded-token",
+        ] {
+            let rule = rules.iter().find(|rule| rule.id() == *id).unwrap();
+            assert!(rule.has_to_check("app.js"));
+            assert!(rule.has_to_check("index.android.bundle"));
+            assert!(!rule.has_to_check("MainActivity.java"));
+            for example in rule.examples() {
+                assert!(rule.matches(example).unwrap());
+            }
+            for example in rule.counter_examples() {
+                assert!(!rule.matches(example).unwrap());
+            }
+        }
+    }
+
     #[test]
     fn it_has_to_check_rule_if_exclude_and_include_regexp_are_not_provided() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: None,
             exclude_file_regex: None,
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(rule.has_to_check("filename.xml"));
+        assert!(!rule.has_to_check("app.js"));
+        assert!(!rule.has_to_check("index.android.bundle"));
+        assert!(!rule.has_to_check("MainActivity.kt"));
+        assert!(!rule.has_to_check("config.properties"));
     }
 
     #[test]
     fn it_has_to_check_rule_if_include_regexp_is_match_and_exclude_not_provided() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
             exclude_file_regex: None,
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(rule.has_to_check("filename.xml"));
@@ -1924,16 +3444,30 @@ mod tests {
     #[test]
     fn it_does_not_have_to_check_rule_if_include_regexp_is_non_match_and_exclude_not_provided() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
             exclude_file_regex: None,
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(!rule.has_to_check("filename.yml"));
@@ -1942,16 +3476,30 @@ mod tests {
     #[test]
     fn it_has_to_check_rule_if_include_regexp_is_match_and_exclude_not() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
             exclude_file_regex: Some(Regex::new(r"non_matching").unwrap()),
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(rule.has_to_check("filename.xml"));
@@ -1960,16 +3508,30 @@ mod tests {
     #[test]
     fn it_does_not_have_to_check_rule_if_exclude_is_match() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: Some(Regex::new(r"non_matching").unwrap()),
             exclude_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(!rule.has_to_check("filename.xml"));
@@ -1978,18 +3540,170 @@ mod tests {
     #[test]
     fn it_does_not_have_to_check_if_both_regexps_matches() {
         let rule = Rule {
+            id: None,
             regex: Regex::new("").unwrap(),
             permissions: Box::new([]),
             forward_check: None,
             max_sdk: None,
+            min_target_sdk: None,
             whitelist: Box::new([]),
             label: String::new(),
             description: String::new(),
             criticality: Criticality::Warning,
+            exposed_provider_criticality: None,
             include_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
             exclude_file_regex: Some(Regex::new(r".*\.xml").unwrap()),
+            cwe: None,
+            masvs: Box::new([]),
+            category: None,
+            cvss: None,
+            references: Box::new([]),
+            examples: Box::new([]),
+            counter_examples: Box::new([]),
+            flow: None,
+            query: None,
+            file_types: Box::new([]),
+            requires: None,
         };
 
         assert!(!rule.has_to_check("filename.xml"));
     }
+
+    /// Checks the selection of the files of a rule by their type.
+    #[test]
+    fn it_has_to_check_rule_file_types() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "password",
+                "label": "Label",
+                "description": "Description",
+                "criticality": "low",
+                "file_types": ["properties", ".json", "js"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.file_types(), &["properties", ".json", "js"]);
+        assert!(rule.has_to_check("config.properties"));
+        assert!(rule.has_to_check("google-services.json"));
+        assert!(rule.has_to_check("index.android.bundle"));
+        assert!(!rule.has_to_check("MainActivity.java"));
+        assert!(!rule.has_to_check("strings.xml"));
+        assert!(!rule.has_to_check("Makefile"));
+    }
+
+    /// Checks the identifiers of the rules.
+    #[test]
+    fn it_rule_id() {
+        assert_eq!(slug("URL Disclosure"), "url-disclosure");
+        assert_eq!(
+            slug("SSL: accepting all certificates"),
+            "ssl-accepting-all-certificates"
+        );
+
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "id": "custom-id",
+                "regex": "a",
+                "label": "Label",
+                "description": "Description",
+                "criticality": "low",
+                "cwe": 295
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.id(), "custom-id");
+        assert_eq!(rule.cwe(), Some(295));
+        assert!(rule.references().is_empty());
+    }
+
+    /// Checks the validation of the capture groups of the forward checks.
+    #[test]
+    fn it_check_forward_check() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "(?P<fc99999999999999999999999>\\w+) = (?P<fc2>\\w+)",
+                "forward_check": "{fc99999999999999999999999}.{fc2}",
+                "label": "Label",
+                "description": "Description",
+                "criticality": "low"
+            }"#,
+        )
+        .unwrap();
+        check_forward_check(&rule).unwrap();
+
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "(?P<fc1>\\w+) = (?P<fc2>\\w+)",
+                "forward_check": "{fc1}",
+                "label": "Label",
+                "description": "Description",
+                "criticality": "low"
+            }"#,
+        )
+        .unwrap();
+        assert!(check_forward_check(&rule).is_err());
+    }
+
+    /// Checks the flow rules.
+    #[test]
+    fn it_flow_rule() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "execSQL",
+                "flow": { "sources": ["intent"], "sinks": ["sql"] },
+                "whitelist": ["DebugHelper"],
+                "label": "Label",
+                "description": "Description",
+                "criticality": "high"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.flow().unwrap().to_string(), "intent → sql");
+
+        assert!(rule
+            .matches(
+                "void delete(Intent intent) {\n    String id = intent.getStringExtra(\"id\");\n    \
+                 db.execSQL(\"DELETE FROM users WHERE id = \" + id);\n}"
+            )
+            .unwrap());
+        assert!(!rule
+            .matches(
+                "void delete(Intent intent) {\n    String id = intent.getStringExtra(\"id\");\n    \
+                 DebugHelper.execSQL(db, id);\n}"
+            )
+            .unwrap());
+        assert!(!rule
+            .matches(
+                "void delete(Intent intent) {\n    String id = intent.getStringExtra(\"id\");\n    \
+                 Log.d(TAG, id);\n}"
+            )
+            .unwrap());
+        assert!(!rule
+            .matches("void delete() {\n    db.execSQL(\"DELETE FROM users\");\n}")
+            .unwrap());
+    }
+
+    /// Checks the rules with syntax queries, that ignore commented-out code with the `ast`
+    /// feature.
+    #[test]
+    fn it_query_rule() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "regex": "getInstance",
+                "query": "(method_invocation name: (identifier) @match (#eq? @match \"getInstance\"))",
+                "label": "Label",
+                "description": "Description",
+                "criticality": "high"
+            }"#,
+        )
+        .unwrap();
+        assert!(rule.query().is_some());
+
+        let code = "class Hash {\n    void hash() {\n        \
+                    // MessageDigest.getInstance(\"MD5\");\n    }\n}\n";
+        assert_eq!(rule.matches(code).unwrap(), cfg!(not(feature = "ast")));
+        assert!(rule
+            .matches("class Hash {\n    void hash() {\n        getInstance();\n    }\n}\n")
+            .unwrap());
+    }
 }
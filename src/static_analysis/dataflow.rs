@@ -0,0 +1,624 @@
+//! Intra-procedural dataflow analysis module.
+//!
+//! This module implements a lightweight taint analysis of the decompiled Java code. Each method is
+//! analyzed statement by statement, tracking the local variables that hold untrusted data, coming
+//! from a _source_ (intents, user input or the network), until that data reaches a _sink_ (SQL
+//! queries, command execution, WebView loading, file paths or logging).
+//!
+//! The analysis is purely syntactic and works on each method in isolation, so it does not follow
+//! data through fields or other method calls. Assigning a clean value to a variable, or passing
+//! the untrusted data through a sanitizer such as `Integer.parseInt()`, removes its taint.
+//!
+//! Rules can express source to sink flows with the `flow` attribute, instead of relying on a
+//! single regular expression match:
+//!
+//! ```json
+//! {
+//!     "label": "Untrusted data in SQL query",
+//!     "regex": "\\.(?:rawQuery|execSQL)\\s*\\(",
+//!     "flow": { "sources": ["intent", "user_input"], "sinks": ["sql"] }
+//! }
+//! ```
+//!
+//! In a flow rule, the regular expression only selects the files where the flows are searched.
+
+use std::{collections::BTreeMap, fmt};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// Calls that return untrusted data, by kind of source.
+    static ref SOURCES: [(SourceKind, Regex); 3] = [
+        (
+            SourceKind::Intent,
+            Regex::new(concat!(
+                r"\bgetIntent\s*\(\s*\)|\.get(?:String|Int|Long|Boolean|CharSequence|Parcelable|",
+                r"Serializable|Bundle)(?:Array(?:List)?)?Extra\s*\(|\.getExtras\s*\(\s*\)|",
+                r"\.getData(?:String)?\s*\(\s*\)|\.getQueryParameter\s*\(",
+            ))
+            .unwrap(),
+        ),
+        (
+            SourceKind::UserInput,
+            Regex::new(r"\.getText\s*\(\s*\)|\.getPrimaryClip\s*\(\s*\)|\.nextLine\s*\(\s*\)")
+                .unwrap(),
+        ),
+        (
+            SourceKind::Network,
+            Regex::new(concat!(
+                r"\.getInputStream\s*\(\s*\)|\.readLine\s*\(\s*\)|\.getHeaderField\s*\(|",
+                r"\.body\s*\(\s*\)\s*\.\s*string\s*\(|\bEntityUtils\s*\.\s*toString\s*\(",
+            ))
+            .unwrap(),
+        ),
+    ];
+    /// Calls that must not receive untrusted data, by kind of sink.
+    static ref SINKS: [(SinkKind, Regex); 5] = [
+        (
+            SinkKind::Sql,
+            Regex::new(r"\.(?:rawQuery|rawQueryWithFactory|execSQL|compileStatement)\s*\(")
+                .unwrap(),
+        ),
+        (
+            SinkKind::Exec,
+            Regex::new(r"\.exec\s*\(|\bnew\s+ProcessBuilder\s*\(").unwrap(),
+        ),
+        (
+            SinkKind::WebView,
+            Regex::new(r"\.(?:loadUrl|loadData|loadDataWithBaseURL|evaluateJavascript)\s*\(")
+                .unwrap(),
+        ),
+        (
+            SinkKind::File,
+            Regex::new(concat!(
+                r"\bnew\s+(?:File|FileInputStream|FileOutputStream|FileReader|FileWriter|",
+                r"RandomAccessFile)\s*\(|\bParcelFileDescriptor\s*\.\s*open\s*\(|",
+                r"\.openFile(?:Input|Output)\s*\(",
+            ))
+            .unwrap(),
+        ),
+        (
+            SinkKind::Log,
+            Regex::new(concat!(
+                r"\bLog\s*\.\s*(?:v|d|i|w|e|wtf)\s*\(|\bSystem\s*\.\s*(?:out|err)\s*\.\s*",
+                r"print(?:ln|f)?\s*\(",
+            ))
+            .unwrap(),
+        ),
+    ];
+    /// Calls that sanitize untrusted data.
+    static ref SANITIZERS: Regex = Regex::new(concat!(
+        r"\b(?:Integer\s*\.\s*parseInt|Long\s*\.\s*parseLong|Double\s*\.\s*parseDouble|",
+        r"Boolean\s*\.\s*parseBoolean|DatabaseUtils\s*\.\s*sqlEscapeString|Uri\s*\.\s*encode|",
+        r"URLEncoder\s*\.\s*encode|Html\s*\.\s*escapeHtml|getCanonical(?:Path|File))\s*\(",
+    ))
+    .unwrap();
+    /// Method declaration, up to the opening brace of its body.
+    static ref METHOD: Regex = Regex::new(concat!(
+        r"(?m)^[ \t]*(?:@[\w$.]+(?:\([^)]*\))?\s+)*(?:(?:public|protected|private|static|final|",
+        r"synchronized)[ \t]+)*[\w$<>\[\],.? ]+?[ \t]+([\w$]+)\s*\(([^()]*)\)\s*",
+        r"(?:throws\s+[\w$.,\s]+?)?\{",
+    ))
+    .unwrap();
+    /// Assignment to a local variable, with an optional declaration.
+    static ref ASSIGNMENT: Regex = Regex::new(
+        r"^(?:final\s+)?(?:[\w$.<>\[\]?, ]+\s+)?([\w$]+)\s*(\+?)=([^=][\s\S]*)$",
+    )
+    .unwrap();
+    /// Call that adds data to the object it is called on, such as a string builder.
+    static ref APPEND: Regex =
+        Regex::new(r"\b([\w$]+)\s*\.\s*(?:append|add|put|insert|write|print)\s*\(").unwrap();
+}
+
+/// Keywords that can be followed by a parenthesis and a block, and are not methods.
+const KEYWORDS: [&str; 8] = [
+    "if",
+    "for",
+    "while",
+    "switch",
+    "catch",
+    "synchronized",
+    "try",
+    "return",
+];
+
+/// Kind of source of untrusted data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Data received in an intent or a deep link.
+    Intent,
+    /// Data introduced by the user, in a text field or the clipboard.
+    UserInput,
+    /// Data received from the network.
+    Network,
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SourceKind::Intent => write!(f, "intent"),
+            SourceKind::UserInput => write!(f, "user_input"),
+            SourceKind::Network => write!(f, "network"),
+        }
+    }
+}
+
+/// Kind of sink of untrusted data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    /// SQL query.
+    Sql,
+    /// Command execution.
+    Exec,
+    /// URL or code loaded in a WebView.
+    WebView,
+    /// Path of a file that is opened or created.
+    File,
+    /// Log message.
+    Log,
+}
+
+impl fmt::Display for SinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SinkKind::Sql => write!(f, "sql"),
+            SinkKind::Exec => write!(f, "exec"),
+            SinkKind::WebView => write!(f, "webview"),
+            SinkKind::File => write!(f, "file"),
+            SinkKind::Log => write!(f, "log"),
+        }
+    }
+}
+
+/// Flow of untrusted data from a source to a sink, inside a method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow {
+    /// Kind of source of the data.
+    source: SourceKind,
+    /// Kind of sink reached by the data.
+    sink: SinkKind,
+    /// Line of the source, starting at 0.
+    source_line: usize,
+    /// Line of the sink, starting at 0.
+    sink_line: usize,
+    /// Variable that carries the data to the sink, if the source is not in the sink call itself.
+    variable: Option<String>,
+}
+
+impl Flow {
+    /// Gets the line of the source, starting at 0.
+    pub fn source_line(&self) -> usize {
+        self.source_line
+    }
+
+    /// Gets the line of the sink, starting at 0.
+    pub fn sink_line(&self) -> usize {
+        self.sink_line
+    }
+}
+
+impl fmt::Display for Flow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} → {}", self.source, self.sink)?;
+        if let Some(ref variable) = self.variable {
+            write!(f, " (through `{}`)", variable)?;
+        }
+        Ok(())
+    }
+}
+
+/// Source to sink flows searched by a rule.
+///
+/// An empty list of sources or sinks accepts any kind.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FlowSpec {
+    /// Kinds of sources of the flows.
+    #[serde(default)]
+    sources: Box<[SourceKind]>,
+    /// Kinds of sinks of the flows.
+    #[serde(default)]
+    sinks: Box<[SinkKind]>,
+}
+
+impl FlowSpec {
+    /// Checks if the given flow is one of the searched flows.
+    pub fn accepts(&self, flow: &Flow) -> bool {
+        (self.sources.is_empty() || self.sources.contains(&flow.source))
+            && (self.sinks.is_empty() || self.sinks.contains(&flow.sink))
+    }
+}
+
+impl fmt::Display for FlowSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        /// Joins the given kinds, or returns `any` if there are none.
+        fn join<K: ToString>(kinds: &[K]) -> String {
+            if kinds.is_empty() {
+                String::from("any")
+            } else {
+                kinds
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        }
+
+        write!(f, "{} → {}", join(&self.sources), join(&self.sinks))
+    }
+}
+
+/// Origin of the untrusted data held by a variable.
+#[derive(Debug, Clone, Copy)]
+struct Taint {
+    /// Kind of source of the data.
+    source: SourceKind,
+    /// Line of the source, starting at 0.
+    line: usize,
+}
+
+/// Finds the flows of untrusted data from sources to sinks in the given Java code.
+pub fn analyze(code: &str) -> Vec<Flow> {
+    let code = strip(code);
+    let line_starts = code
+        .match_indices('\n')
+        .map(|(index, _)| index + 1)
+        .collect::<Vec<_>>();
+    let line_of = |offset: usize| match line_starts.binary_search(&offset) {
+        Ok(line) => line + 1,
+        Err(line) => line,
+    };
+
+    let mut flows = Vec::new();
+    let mut analyzed_until = 0;
+    for method in METHOD.captures_iter(&code) {
+        let header = method.get(0).unwrap();
+        if header.start() < analyzed_until || KEYWORDS.contains(&method.get(1).unwrap().as_str()) {
+            continue;
+        }
+        let body_start = header.end();
+        let body_end = closing(&code, body_start, '{', '}').unwrap_or_else(|| code.len());
+        analyzed_until = body_end;
+
+        let mut state = BTreeMap::new();
+        let header_line = line_of(header.start());
+        for parameter in method.get(2).unwrap().as_str().split(',') {
+            let mut words = parameter.split_whitespace().rev();
+            if let (Some(name), Some(kind)) = (words.next(), words.next()) {
+                if kind.ends_with("Intent") || kind.ends_with("Uri") {
+                    let _ = state.insert(
+                        name.to_owned(),
+                        Taint {
+                            source: SourceKind::Intent,
+                            line: header_line,
+                        },
+                    );
+                }
+            }
+        }
+
+        for (offset, statement) in statements(&code[body_start..body_end]) {
+            let line = line_of(body_start + offset);
+            analyze_statement(statement, line, &mut state, &mut flows);
+        }
+    }
+
+    flows.dedup();
+    flows
+}
+
+/// Analyzes a single statement, updating the tainted variables and adding the flows it completes.
+fn analyze_statement(
+    statement: &str,
+    line: usize,
+    state: &mut BTreeMap<String, Taint>,
+    flows: &mut Vec<Flow>,
+) {
+    for (sink, regex) in SINKS.iter() {
+        for call in regex.find_iter(statement) {
+            let arguments_end =
+                closing(statement, call.end(), '(', ')').unwrap_or_else(|| statement.len());
+            let arguments = &statement[call.end()..arguments_end];
+            if let Some((taint, variable)) = taint_of(arguments, line, state) {
+                flows.push(Flow {
+                    source: taint.source,
+                    sink: *sink,
+                    source_line: taint.line,
+                    sink_line: line,
+                    variable,
+                });
+            }
+        }
+    }
+
+    if let Some(assignment) = ASSIGNMENT.captures(statement) {
+        let variable = assignment.get(1).unwrap().as_str();
+        let value = assignment.get(3).unwrap().as_str();
+        let appends = !assignment.get(2).unwrap().as_str().is_empty();
+        match taint_of(value, line, state) {
+            Some((taint, _)) if !SANITIZERS.is_match(value) => {
+                let _ = state.insert(variable.to_owned(), taint);
+            }
+            _ if !appends => {
+                let _ = state.remove(variable);
+            }
+            _ => {}
+        }
+    } else {
+        for call in APPEND.captures_iter(statement) {
+            let call_end = call.get(0).unwrap().end();
+            let arguments_end =
+                closing(statement, call_end, '(', ')').unwrap_or_else(|| statement.len());
+            if let Some((taint, _)) = taint_of(&statement[call_end..arguments_end], line, state) {
+                let _ = state.insert(call.get(1).unwrap().as_str().to_owned(), taint);
+            }
+        }
+    }
+}
+
+/// Gets the origin of the untrusted data in the given expression, if any, along with the variable
+/// that holds it.
+///
+/// A source called directly in the expression takes precedence over the tainted variables.
+fn taint_of(
+    expression: &str,
+    line: usize,
+    state: &BTreeMap<String, Taint>,
+) -> Option<(Taint, Option<String>)> {
+    let source = SOURCES
+        .iter()
+        .filter_map(|(source, regex)| regex.find(expression).map(|m| (m.start(), *source)))
+        .min_by_key(|&(start, _)| start);
+    if let Some((_, source)) = source {
+        return Some((Taint { source, line }, None));
+    }
+
+    state
+        .iter()
+        .find(|(variable, _)| mentions(expression, variable))
+        .map(|(variable, taint)| (*taint, Some(variable.clone())))
+}
+
+/// Checks if the given expression mentions the given variable.
+///
+/// Fields of other objects with the same name, such as `other.name`, are not considered mentions.
+fn mentions(expression: &str, variable: &str) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    expression.match_indices(variable).any(|(start, _)| {
+        let before = expression[..start].trim_end().chars().next_back();
+        let after = expression[start + variable.len()..].chars().next();
+        before.map_or(true, |c| !is_identifier(c) && c != '.')
+            && after.map_or(true, |c| !is_identifier(c))
+    })
+}
+
+/// Splits the given block of code in statements, along with their offset in the block.
+///
+/// Statements end at semicolons and braces outside of parentheses, so that the header of a `for`
+/// loop or a call with an anonymous class stay in a single statement.
+fn statements(block: &str) -> Vec<(usize, &str)> {
+    let mut statements = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (index, c) in block.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' | '{' | '}' if depth == 0 => {
+                let statement = &block[start..index];
+                let trimmed = statement.trim_start();
+                if !trimmed.is_empty() {
+                    statements.push((start + statement.len() - trimmed.len(), trimmed.trim_end()));
+                }
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    statements
+}
+
+/// Finds the position of the delimiter that closes the one right before the given offset.
+fn closing(code: &str, offset: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 1_usize;
+    for (index, c) in code[offset..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(offset + index);
+            }
+        }
+    }
+    None
+}
+
+/// Blanks the comments and the contents of the string and character literals of the given code.
+///
+/// The result has the same length and lines as the code, so that offsets can be shared.
+fn strip(code: &str) -> String {
+    /// State of the scanner.
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        /// Code outside of comments and literals.
+        Code,
+        /// Comment until the end of the line.
+        LineComment,
+        /// Comment until the closing `*/`.
+        BlockComment,
+        /// String or character literal, with the given delimiter.
+        Literal(char),
+    }
+
+    /// Adds the blank replacement of the given character, keeping line breaks.
+    fn blank(stripped: &mut String, c: char) {
+        if c == '\n' {
+            stripped.push('\n');
+        } else {
+            stripped.extend((0..c.len_utf8()).map(|_| ' '));
+        }
+    }
+
+    let mut stripped = String::with_capacity(code.len());
+    let mut state = State::Code;
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match state {
+            State::Code => match (c, chars.peek()) {
+                ('/', Some('/')) => {
+                    state = State::LineComment;
+                    blank(&mut stripped, c);
+                }
+                ('/', Some('*')) => {
+                    let _ = chars.next();
+                    state = State::BlockComment;
+                    stripped.push_str("  ");
+                }
+                ('"', _) | ('\'', _) => {
+                    state = State::Literal(c);
+                    stripped.push(c);
+                }
+                _ => stripped.push(c),
+            },
+            State::LineComment => {
+                if c == '\n' {
+                    state = State::Code;
+                }
+                blank(&mut stripped, c);
+            }
+            State::BlockComment => {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    let _ = chars.next();
+                    state = State::Code;
+                    stripped.push_str("  ");
+                } else {
+                    blank(&mut stripped, c);
+                }
+            }
+            State::Literal(delimiter) => {
+                if c == delimiter {
+                    state = State::Code;
+                    stripped.push(c);
+                } else if c == '\\' {
+                    blank(&mut stripped, c);
+                    if let Some(escaped) = chars.next() {
+                        blank(&mut stripped, escaped);
+                    }
+                } else {
+                    blank(&mut stripped, c);
+                }
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze, mentions, strip, FlowSpec, SinkKind, SourceKind};
+
+    /// Checks the blanking of comments and literals.
+    #[test]
+    fn it_strip() {
+        let code = "a(\"x;\\\"y\"); // c(d);\n/* e;\nf */ g('{');";
+        let stripped = strip(code);
+        assert_eq!(stripped.len(), code.len());
+        assert_eq!(stripped, "a(\"     \");         \n     \n     g(' ');");
+    }
+
+    /// Checks the detection of variable mentions.
+    #[test]
+    fn it_mentions() {
+        assert!(mentions("\"SELECT \" + id", "id"));
+        assert!(mentions("f(id)", "id"));
+        assert!(!mentions("f(uid)", "id"));
+        assert!(!mentions("f(ids)", "id"));
+        assert!(!mentions("other.id", "id"));
+    }
+
+    /// Checks the detection of source to sink flows.
+    #[test]
+    fn it_analyze() {
+        let code = "public class Main extends Activity {\n\
+                    \x20   protected void onCreate(Bundle savedInstanceState) {\n\
+                    \x20       String id = getIntent().getStringExtra(\"id\");\n\
+                    \x20       String query = \"SELECT * FROM users WHERE id = \" + id;\n\
+                    \x20       // db.execSQL(query);\n\
+                    \x20       db.rawQuery(query, null);\n\
+                    \x20       int number = Integer.parseInt(id);\n\
+                    \x20       db.execSQL(\"DELETE FROM users WHERE id = \" + number);\n\
+                    \x20       query = \"SELECT 1\";\n\
+                    \x20       db.execSQL(query);\n\
+                    \x20   }\n\
+                    \n\
+                    \x20   public void onNewIntent(Intent intent) {\n\
+                    \x20       StringBuilder url = new StringBuilder(\"https://example.com/\");\n\
+                    \x20       url.append(intent.getData());\n\
+                    \x20       webView.loadUrl(url.toString());\n\
+                    \x20       Log.d(TAG, editText.getText().toString());\n\
+                    \x20       Log.d(TAG, query);\n\
+                    \x20   }\n\
+                    }\n";
+        let flows = analyze(code);
+        assert_eq!(flows.len(), 3);
+
+        assert_eq!(flows[0].source, SourceKind::Intent);
+        assert_eq!(flows[0].sink, SinkKind::Sql);
+        assert_eq!(flows[0].source_line(), 2);
+        assert_eq!(flows[0].sink_line(), 5);
+        assert_eq!(flows[0].variable, Some(String::from("query")));
+        assert_eq!(flows[0].to_string(), "intent → sql (through `query`)");
+
+        assert_eq!(flows[1].source, SourceKind::Intent);
+        assert_eq!(flows[1].sink, SinkKind::WebView);
+        assert_eq!(flows[1].source_line(), 14);
+        assert_eq!(flows[1].sink_line(), 15);
+        assert_eq!(flows[1].variable, Some(String::from("url")));
+
+        assert_eq!(flows[2].source, SourceKind::UserInput);
+        assert_eq!(flows[2].sink, SinkKind::Log);
+        assert_eq!(flows[2].source_line(), 16);
+        assert_eq!(flows[2].variable, None);
+    }
+
+    /// Checks the flows of caller-controlled URIs to file paths.
+    #[test]
+    fn it_file_sink() {
+        let flows = analyze(
+            "public ParcelFileDescriptor openFile(Uri uri, String mode) {\n    \
+             File file = new File(getContext().getFilesDir(), uri.getLastPathSegment());\n    \
+             return ParcelFileDescriptor.open(file, ParcelFileDescriptor.MODE_READ_ONLY);\n}\n",
+        );
+        assert_eq!(flows.len(), 2);
+        assert_eq!(flows[0].source, SourceKind::Intent);
+        assert_eq!(flows[0].sink, SinkKind::File);
+        assert_eq!(flows[0].sink_line(), 1);
+        assert_eq!(flows[0].variable, Some(String::from("uri")));
+        assert_eq!(flows[1].sink_line(), 2);
+        assert_eq!(flows[1].variable, Some(String::from("file")));
+    }
+
+    /// Checks the selection of flows by a rule.
+    #[test]
+    fn it_flow_spec() {
+        let spec: FlowSpec =
+            serde_json::from_str(r#"{ "sources": ["intent", "user_input"], "sinks": ["sql"] }"#)
+                .unwrap();
+        assert_eq!(spec.to_string(), "intent, user_input → sql");
+
+        let flows = analyze(
+            "void run(Intent intent) {\n    db.execSQL(intent.getStringExtra(\"q\"));\n    \
+             Log.i(TAG, intent.getStringExtra(\"q\"));\n}\n",
+        );
+        assert_eq!(flows.len(), 2);
+        assert!(spec.accepts(&flows[0]));
+        assert!(!spec.accepts(&flows[1]));
+
+        let any: FlowSpec = serde_json::from_str("{}").unwrap();
+        assert_eq!(any.to_string(), "any → any");
+        assert!(any.accepts(&flows[1]));
+    }
+}
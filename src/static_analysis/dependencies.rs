@@ -0,0 +1,471 @@
+//! Vulnerable dependencies module.
+//!
+//! The versions of the bundled libraries detected by the [`libraries`](super::libraries) module
+//! are checked against a dataset of known vulnerabilities, so that applications bundling a library
+//! version affected by a CVE get a finding for it. The dataset is built into the binary, in the
+//! `library_vulnerabilities.toml` file, and it can be extended with the `library_vulnerabilities`
+//! option of the configuration, a TOML file with the same format:
+//!
+//! ```toml
+//! [[vulnerability]]
+//! id = "CVE-2021-0341"
+//! group = "com.squareup.okhttp3"
+//! name = "okhttp"
+//! fixed = "4.9.2"
+//! criticality = "high"
+//! cwe = 295
+//! description = "The hostname verifier of OkHttp accepts certificates of other hosts."
+//! ```
+//!
+//! With `--online-checks` (or `online_checks = true` in the configuration), the libraries are
+//! also looked up in the Sonatype OSS Index, that covers many more vulnerabilities. Online checks
+//! require the `integrations` feature.
+
+use std::{cmp::Ordering, fs, path::Path, slice::Iter};
+
+use failure::{format_err, Error, ResultExt};
+use lazy_static::lazy_static;
+
+use super::libraries::Library;
+use crate::{
+    criticality::Criticality,
+    print_vulnerability, print_warning,
+    results::{Confidence, Results, Vulnerability},
+    Config,
+};
+
+/// URL of the component report API of the Sonatype OSS Index.
+#[cfg(feature = "integrations")]
+const OSS_INDEX_URL: &str = "https://ossindex.sonatype.org/api/v3/component-report";
+
+lazy_static! {
+    /// Known vulnerabilities of the libraries, listed in the `library_vulnerabilities.toml` file.
+    static ref BUILTIN_DATABASE: VulnerabilityDatabase =
+        toml::from_str(include_str!("../../library_vulnerabilities.toml"))
+            .expect("the built-in library vulnerability database is invalid");
+}
+
+/// Known vulnerability of a range of versions of a library.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LibraryVulnerability {
+    /// Identifier of the vulnerability, usually its CVE.
+    id: String,
+    /// Group of the affected library, in Maven terms.
+    group: String,
+    /// Name of the affected library (the Maven artifact).
+    name: String,
+    /// First affected version, all the previous versions if not set.
+    introduced: Option<String>,
+    /// First version with the fix, all the later versions are affected if not set.
+    fixed: Option<String>,
+    /// Criticality of the vulnerability.
+    criticality: Criticality,
+    /// CWE of the vulnerability.
+    cwe: Option<u32>,
+    /// Description of the vulnerability.
+    description: String,
+}
+
+impl LibraryVulnerability {
+    /// Returns the identifier of the vulnerability.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the group of the affected library.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Returns the name of the affected library.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the first affected version, if any.
+    pub fn introduced(&self) -> Option<&str> {
+        self.introduced.as_ref().map(String::as_str)
+    }
+
+    /// Returns the first version with the fix, if any.
+    pub fn fixed(&self) -> Option<&str> {
+        self.fixed.as_ref().map(String::as_str)
+    }
+
+    /// Returns the criticality of the vulnerability.
+    pub fn criticality(&self) -> Criticality {
+        self.criticality
+    }
+
+    /// Returns the CWE of the vulnerability, if any.
+    pub fn cwe(&self) -> Option<u32> {
+        self.cwe
+    }
+
+    /// Returns the description of the vulnerability.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Checks if the vulnerability affects the given library.
+    pub fn affects(&self, library: &Library) -> bool {
+        library.group() == self.group
+            && library.name() == self.name
+            && self.introduced.as_ref().map_or(true, |introduced| {
+                compare_versions(library.version(), introduced) != Ordering::Less
+            })
+            && self.fixed.as_ref().map_or(true, |fixed| {
+                compare_versions(library.version(), fixed) == Ordering::Less
+            })
+    }
+}
+
+/// Dataset of known vulnerabilities of libraries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct VulnerabilityDatabase {
+    /// Known vulnerabilities.
+    #[serde(default)]
+    vulnerability: Vec<LibraryVulnerability>,
+}
+
+impl VulnerabilityDatabase {
+    /// Returns the built-in database.
+    pub fn builtin() -> &'static Self {
+        &BUILTIN_DATABASE
+    }
+
+    /// Loads the database in the given TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).context(format_err!(
+            "could not read the library vulnerability database `{}`",
+            path.display()
+        ))?;
+
+        Ok(toml::from_str(&content).context(format_err!(
+            "invalid library vulnerability database `{}`",
+            path.display()
+        ))?)
+    }
+
+    /// Adds the vulnerabilities of the given database to this database.
+    pub fn extend(&mut self, other: Self) {
+        for vulnerability in other.vulnerability {
+            if !self.contains(&vulnerability) {
+                self.vulnerability.push(vulnerability);
+            }
+        }
+    }
+
+    /// Checks if the given vulnerability is in the database.
+    pub fn contains(&self, vulnerability: &LibraryVulnerability) -> bool {
+        self.vulnerability.contains(vulnerability)
+    }
+
+    /// Returns the known vulnerabilities.
+    pub fn vulnerabilities(&self) -> Iter<LibraryVulnerability> {
+        self.vulnerability.iter()
+    }
+
+    /// Gets the known vulnerabilities that affect the given library.
+    pub fn affecting<'a>(
+        &'a self,
+        library: &'a Library,
+    ) -> impl Iterator<Item = &'a LibraryVulnerability> + 'a {
+        self.vulnerability
+            .iter()
+            .filter(move |vulnerability| vulnerability.affects(library))
+    }
+}
+
+/// Compares two library versions.
+///
+/// Versions are split in parts at dots, dashes, underscores and plus signs. Numeric parts are
+/// compared as numbers, and other parts as text. A numeric part is newer than a text part, and a
+/// missing part is older than a text part, so that pre-releases such as `2.0-rc1` are older than
+/// their release, but `2.0.1` is newer than `2.0`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |version: &str| -> Vec<String> {
+        version
+            .split(|c| c == '.' || c == '-' || c == '_' || c == '+')
+            .filter(|part| !part.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let a = split(a);
+    let b = split(b);
+
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Greater,
+                (Err(_), Ok(_)) => Ordering::Less,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+            (Some(a), None) => a.parse::<u64>().map_or(Ordering::Less, |a| a.cmp(&0)),
+            (None, Some(b)) => b.parse::<u64>().map_or(Ordering::Greater, |b| 0.cmp(&b)),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Reports the known vulnerabilities of the libraries bundled in the application.
+pub fn analysis(config: &Config, results: &mut Results) {
+    let libraries = results.libraries().to_vec();
+    let mut reported = Vec::new();
+    for library in &libraries {
+        for vulnerability in config.library_vulnerabilities().affecting(library) {
+            report(
+                config,
+                library,
+                vulnerability.id(),
+                vulnerability.criticality(),
+                vulnerability.cwe(),
+                vulnerability.description(),
+                vulnerability.fixed(),
+                results,
+            );
+            reported.push((library.purl(), vulnerability.id().to_owned()));
+        }
+    }
+
+    if config.is_online_checks() && !libraries.is_empty() {
+        online_checks(config, &libraries, &reported, results);
+    }
+}
+
+/// Reports a vulnerability of a bundled library.
+#[allow(clippy::too_many_arguments)]
+fn report(
+    config: &Config,
+    library: &Library,
+    id: &str,
+    criticality: Criticality,
+    cwe: Option<u32>,
+    details: &str,
+    fixed: Option<&str>,
+    results: &mut Results,
+) {
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let upgrade = match fixed {
+        Some(fixed) => format!("It was fixed in version {}.", fixed),
+        None => String::from("There is no fixed version yet."),
+    };
+    let description = format!(
+        "The application bundles version {} of the `{}:{}` library, affected by {}. {}. {}",
+        library.version(),
+        library.group(),
+        library.name(),
+        id,
+        details.trim_end_matches('.'),
+        upgrade
+    );
+    let mut builder =
+        Vulnerability::builder(criticality, "Vulnerable Library", description.as_str())
+            .rule_id("dependencies:vulnerable-library")
+            .masvs(vec!["MASVS-CODE-3"])
+            .confidence(Confidence::High)
+            .evidence(library.purl());
+    if let Some(cwe) = cwe {
+        builder = builder.cwe(cwe);
+    }
+
+    results.add_vulnerability(builder.build());
+    print_vulnerability(description, criticality);
+}
+
+/// Looks up the given libraries in the Sonatype OSS Index, and reports the vulnerabilities that
+/// were not reported from the offline dataset.
+#[cfg(feature = "integrations")]
+fn online_checks(
+    config: &Config,
+    libraries: &[Library],
+    reported: &[(String, String)],
+    results: &mut Results,
+) {
+    let purls = libraries.iter().map(Library::purl).collect::<Vec<_>>();
+    let components = match query_oss_index(&purls) {
+        Ok(components) => components,
+        Err(e) => {
+            print_warning(format!(
+                "could not look up the bundled libraries in the OSS Index: {}",
+                e
+            ));
+            return;
+        }
+    };
+
+    for component in components.as_array().into_iter().flatten() {
+        let library = match libraries
+            .iter()
+            .find(|library| Some(library.purl().as_str()) == component["coordinates"].as_str())
+        {
+            Some(library) => library,
+            None => continue,
+        };
+        for vulnerability in component["vulnerabilities"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let id = vulnerability["cve"]
+                .as_str()
+                .or_else(|| vulnerability["id"].as_str())
+                .unwrap_or_default();
+            if reported
+                .iter()
+                .any(|(purl, reported)| purl == &library.purl() && reported == id)
+            {
+                continue;
+            }
+            let score = vulnerability["cvssScore"].as_f64().unwrap_or_default();
+            let criticality = if score >= 9.0 {
+                Criticality::Critical
+            } else if score >= 7.0 {
+                Criticality::High
+            } else if score >= 4.0 {
+                Criticality::Medium
+            } else {
+                Criticality::Low
+            };
+            let cwe = vulnerability["cwe"]
+                .as_str()
+                .and_then(|cwe| cwe.trim_start_matches("CWE-").parse().ok());
+            let details = vulnerability["title"].as_str().unwrap_or_default();
+            report(
+                config,
+                library,
+                id,
+                criticality,
+                cwe,
+                details,
+                None,
+                results,
+            );
+        }
+    }
+}
+
+/// Looks up the given libraries in the Sonatype OSS Index.
+///
+/// Online checks require the `integrations` feature.
+#[cfg(not(feature = "integrations"))]
+fn online_checks(
+    _config: &Config,
+    _libraries: &[Library],
+    _reported: &[(String, String)],
+    _results: &mut Results,
+) {
+    print_warning(
+        "the OSS Index lookup of the bundled libraries was skipped, it requires the \
+         `integrations` feature",
+    );
+}
+
+/// Gets the component report of the given package URLs from the Sonatype OSS Index.
+#[cfg(feature = "integrations")]
+fn query_oss_index(purls: &[String]) -> Result<serde_json::Value, Error> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    Ok(client
+        .post(OSS_INDEX_URL)
+        .json(&serde_json::json!({ "coordinates": purls }))
+        .send()
+        .context("could not connect to the OSS Index")?
+        .error_for_status()
+        .context("the OSS Index rejected the component report request")?
+        .json()
+        .context("invalid OSS Index component report")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare_versions, VulnerabilityDatabase};
+    use crate::static_analysis::libraries::Library;
+
+    /// Checks the comparison of library versions.
+    #[test]
+    fn it_compare_versions() {
+        assert_eq!(compare_versions("2.8.6", "2.8.9"), Ordering::Less);
+        assert_eq!(compare_versions("2.10.0", "2.9.1"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.1", "2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0-rc1", "2.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0-rc1", "2.0-rc2"), Ordering::Less);
+        assert_eq!(compare_versions("2.12.6.1", "2.12.6"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("1.9-SNAPSHOT", "1.9-snapshot"),
+            Ordering::Equal
+        );
+    }
+
+    /// Checks the lookup of the vulnerabilities of the built-in database.
+    #[test]
+    fn it_builtin_database() {
+        let database = VulnerabilityDatabase::builtin();
+        assert!(database.vulnerabilities().len() > 0);
+
+        let gson = Library::new("com.google.code.gson", "gson", "2.8.6");
+        let ids = database
+            .affecting(&gson)
+            .map(|vulnerability| vulnerability.id())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["CVE-2022-25647"]);
+        assert_eq!(
+            database
+                .affecting(&Library::new("com.google.code.gson", "gson", "2.10.1"))
+                .count(),
+            0
+        );
+
+        let okhttp = Library::new("com.squareup.okhttp3", "okhttp", "3.1.0");
+        assert_eq!(database.affecting(&okhttp).count(), 2);
+        let okhttp = Library::new("com.squareup.okhttp3", "okhttp", "4.9.2");
+        assert_eq!(database.affecting(&okhttp).count(), 0);
+
+        let log4j = Library::new("org.apache.logging.log4j", "log4j-core", "1.2.17");
+        assert_eq!(database.affecting(&log4j).count(), 0);
+        let log4j = Library::new("org.apache.logging.log4j", "log4j-core", "2.14.1");
+        assert_eq!(database.affecting(&log4j).count(), 1);
+    }
+
+    /// Checks the extension of the database with a custom file.
+    #[test]
+    fn it_extend_database() {
+        let mut database = VulnerabilityDatabase::builtin().clone();
+        let custom: VulnerabilityDatabase = toml::from_str(
+            r#"
+[[vulnerability]]
+id = "VENDOR-1"
+group = "com.vendor"
+name = "sdk"
+introduced = "1.0"
+criticality = "low"
+description = "Test vulnerability."
+"#,
+        )
+        .unwrap();
+        let count = database.vulnerabilities().len();
+        database.extend(custom.clone());
+        database.extend(custom);
+        assert_eq!(database.vulnerabilities().len(), count + 1);
+
+        let sdk = Library::new("com.vendor", "sdk", "0.9");
+        assert_eq!(database.affecting(&sdk).count(), 0);
+        let sdk = Library::new("com.vendor", "sdk", "3.0");
+        assert_eq!(database.affecting(&sdk).count(), 1);
+    }
+}
@@ -0,0 +1,390 @@
+//! Network endpoint discovery module.
+//!
+//! The URLs and IP addresses an application connects to are often configured outside of the code,
+//! in the string resources of each locale or in text files bundled in the assets. This module
+//! collects every network endpoint found in `res/values*/strings.xml` and in the `assets/` folder,
+//! and lists them in the _Endpoints_ section of the results, so that the attack surface of the
+//! backend can be reviewed.
+//!
+//! Endpoints pointing to the loopback interface, the emulator host or development environments,
+//! such as `staging` or `dev` hosts, are also reported as debug endpoints left in the release.
+//! Credentials in the same files are reported by the secrets scanner and the string resource
+//! analysis.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{secrets::add_files, strings::parse_strings};
+use crate::{
+    criticality::Criticality,
+    get_code, print_message, print_vulnerability, print_warning,
+    results::{Confidence, Results, StageStatus, Vulnerability},
+    Config,
+};
+
+lazy_static! {
+    /// Network URLs.
+    static ref URL: Regex =
+        Regex::new(r#"(?i)\b(?:https?|wss?|ftp)://[^\s"'<>\\`(){}\[\]]+"#).unwrap();
+    /// IPv4 addresses, with an optional port.
+    static ref IP_ADDRESS: Regex = Regex::new(concat!(
+        r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)",
+        r"(?::\d{1,5})?\b",
+    ))
+    .unwrap();
+    /// Host names of development environments.
+    static ref DEBUG_HOST: Regex = Regex::new(concat!(
+        r"(?i)^(?:localhost|127\.\d+\.\d+\.\d+|10\.0\.2\.2|10\.0\.3\.2|0\.0\.0\.0)$",
+        r"|(?:^|[.-])(?:dev|debug|staging|stage|test|testing|qa|sandbox|uat)\d*(?:[.-]|$)",
+    ))
+    .unwrap();
+}
+
+/// Network endpoint found in the application.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Endpoint {
+    /// Address of the endpoint, a URL or an IP address.
+    address: String,
+    /// Host of the endpoint.
+    host: String,
+    /// File where the endpoint was found, relative to the distribution folder.
+    file: PathBuf,
+    /// Line where the endpoint was found, starting at 1, or 0 if it was found in a binary file.
+    line: usize,
+}
+
+impl Endpoint {
+    /// Creates a new endpoint from its address.
+    fn new<P: AsRef<Path>>(address: &str, file: P, line: usize) -> Self {
+        let address = address.trim_end_matches(|c| c == '.' || c == ',' || c == ';');
+        Self {
+            address: address.to_owned(),
+            host: host(address).to_owned(),
+            file: file.as_ref().to_path_buf(),
+            line,
+        }
+    }
+
+    /// Gets the address of the endpoint, a URL or an IP address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Gets the host of the endpoint.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Gets the file where the endpoint was found, relative to the distribution folder.
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Gets the line where the endpoint was found, starting at 1, or 0 if it was found in a
+    /// binary file.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Checks if the endpoint uses a cleartext protocol.
+    pub fn is_cleartext(&self) -> bool {
+        let address = self.address.to_lowercase();
+        ["http://", "ws://", "ftp://"]
+            .iter()
+            .any(|scheme| address.starts_with(scheme))
+    }
+
+    /// Checks if the endpoint points to a development environment.
+    pub fn is_debug(&self) -> bool {
+        DEBUG_HOST.is_match(&self.host)
+    }
+}
+
+impl Serialize for Endpoint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("Endpoint", 6)?;
+        ser_struct.serialize_field("address", &self.address)?;
+        ser_struct.serialize_field("host", &self.host)?;
+        ser_struct.serialize_field("file", &self.file)?;
+        ser_struct.serialize_field("line", &self.line)?;
+        ser_struct.serialize_field("cleartext", &self.is_cleartext())?;
+        ser_struct.serialize_field("debug", &self.is_debug())?;
+        ser_struct.end()
+    }
+}
+
+/// Collects the network endpoints of the string resources and the assets of the application.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut status = StageStatus::Ok;
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(dist_folder.join("res")) {
+        files.extend(
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    entry.file_name().to_str().map_or(false, |name| {
+                        name == "values" || name.starts_with("values-")
+                    })
+                })
+                .map(|entry| entry.path().join("strings.xml"))
+                .filter(|path| path.is_file()),
+        );
+        files.sort();
+    }
+    if let Err(e) = add_files(dist_folder.join("assets"), &mut files) {
+        print_warning(format!(
+            "there was an error listing the assets to search for endpoints: {}",
+            e
+        ));
+        status = StageStatus::failed(e);
+    }
+
+    let mut endpoints = BTreeSet::new();
+    for path in files {
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                print_warning(format!(
+                    "could not search `{}` for endpoints: {}",
+                    path.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+        if contents.iter().take(8_000).any(|&b| b == 0) {
+            continue;
+        }
+
+        let file = path.strip_prefix(&dist_folder).unwrap_or(&path);
+        let text = String::from_utf8_lossy(&contents);
+        let found = if file.starts_with("res") {
+            match parse_strings(&text) {
+                Ok(strings) => scan_strings(&text, &strings, file),
+                Err(e) => {
+                    print_warning(format!(
+                        "could not parse `{}` to search for endpoints: {}",
+                        file.display(),
+                        e
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            scan(&text, file)
+        };
+
+        for endpoint in found {
+            if endpoint.is_debug() {
+                report_debug(config, &endpoint, &text, results);
+            }
+            let _ = endpoints.insert(endpoint);
+        }
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} network endpoints were found.",
+            format!("{}", endpoints.len()).bold()
+        ));
+    }
+    for endpoint in endpoints {
+        results.add_endpoint(endpoint);
+    }
+    results.set_stage_status("endpoints", status);
+}
+
+/// Scans the given text of the given file for endpoints.
+fn scan(text: &str, file: &Path) -> Vec<Endpoint> {
+    let urls = URL.find_iter(text).collect::<Vec<_>>();
+    let mut found = urls
+        .iter()
+        .map(|m| (m.start(), m.as_str()))
+        .chain(
+            IP_ADDRESS
+                .find_iter(text)
+                .filter(|ip| {
+                    !urls
+                        .iter()
+                        .any(|url| url.start() <= ip.start() && ip.end() <= url.end())
+                })
+                .filter(|ip| !is_part_of_version(text, ip.start(), ip.end()))
+                .map(|m| (m.start(), m.as_str())),
+        )
+        .collect::<Vec<_>>();
+    found.sort();
+
+    let mut seen = BTreeSet::new();
+    found
+        .into_iter()
+        .filter(|&(_, address)| seen.insert(address))
+        .map(|(start, address)| {
+            let line = text[..start].matches('\n').count() + 1;
+            Endpoint::new(address, file, line)
+        })
+        .collect()
+}
+
+/// Scans the strings extracted from the given binary file for endpoints.
+///
+/// Binary files have no lines, so the line of the endpoints is 0.
+pub(crate) fn scan_binary(strings: &[String], file: &Path) -> Vec<Endpoint> {
+    scan(&strings.join("\n"), file)
+        .into_iter()
+        .map(|endpoint| Endpoint {
+            line: 0,
+            ..endpoint
+        })
+        .collect()
+}
+
+/// Scans the values of the given strings, parsed from the given text, for endpoints.
+///
+/// Only the values of the strings are scanned, so that XML namespaces are not reported.
+fn scan_strings(text: &str, strings: &[(String, String)], file: &Path) -> Vec<Endpoint> {
+    let mut seen = BTreeSet::new();
+    let mut endpoints = Vec::new();
+    for (key, value) in strings {
+        let line = text
+            .lines()
+            .position(|line| line.contains(&format!("name=\"{}\"", key)))
+            .map_or(0, |line| line + 1);
+        endpoints.extend(
+            scan(value, file)
+                .into_iter()
+                .filter(|endpoint| seen.insert(endpoint.address.clone()))
+                .map(|endpoint| Endpoint { line, ..endpoint }),
+        );
+    }
+    endpoints
+}
+
+/// Checks if the IP address at the given position is part of a longer version number, such as
+/// `1.2.3.4.5`.
+fn is_part_of_version(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().take(2).collect::<String>();
+    before == Some('.')
+        || (after.starts_with('.') && after.chars().nth(1).map_or(false, |c| c.is_ascii_digit()))
+}
+
+/// Gets the host of the given address.
+fn host(address: &str) -> &str {
+    let without_scheme = address.splitn(2, "://").nth(1).unwrap_or(address);
+    let authority = without_scheme
+        .split(|c| c == '/' || c == '?' || c == '#')
+        .next()
+        .unwrap_or(without_scheme);
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    if host.starts_with('[') {
+        return host;
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
+/// Reports the given debug endpoint, found in the given text, as a vulnerability.
+pub(crate) fn report_debug(
+    config: &Config,
+    endpoint: &Endpoint,
+    text: &str,
+    results: &mut Results,
+) {
+    let criticality = Criticality::Medium;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let description = format!(
+        "The application contains the `{}` endpoint, that points to a development environment. \
+         Debug endpoints left in release builds can expose test servers, which are usually less \
+         protected than the production ones, and reveal internal infrastructure.",
+        endpoint.address()
+    );
+    let mut builder = Vulnerability::builder(criticality, "Debug Endpoint", description.as_str())
+        .file(endpoint.file())
+        .rule_id("endpoints:debug")
+        .cwe(489)
+        .confidence(Confidence::Medium)
+        .evidence(endpoint.address());
+    if endpoint.line() > 0 {
+        let line = endpoint.line() - 1;
+        builder = builder.lines(line, line).code(get_code(text, line, line));
+    }
+
+    results.add_vulnerability(builder.build());
+    print_vulnerability(description, criticality);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{host, scan, scan_strings, Endpoint};
+
+    /// Checks the detection of endpoints in text files.
+    #[test]
+    fn it_scan() {
+        let text = "{\n  \"api\": \"https://api.example.com/v1?key=value\",\n  \
+                    \"staging\": \"http://staging.example.com:8080/\",\n  \
+                    \"server\": \"192.168.1.20:9000\",\n  \"version\": \"1.2.3.4.5\",\n  \
+                    \"again\": \"https://api.example.com/v1?key=value\"\n}\n";
+        let endpoints = scan(text, Path::new("assets/config.json"));
+
+        let addresses = endpoints.iter().map(Endpoint::address).collect::<Vec<_>>();
+        assert_eq!(
+            addresses,
+            vec![
+                "https://api.example.com/v1?key=value",
+                "http://staging.example.com:8080/",
+                "192.168.1.20:9000"
+            ]
+        );
+        assert_eq!(endpoints[0].line(), 2);
+        assert_eq!(endpoints[0].host(), "api.example.com");
+        assert!(!endpoints[0].is_cleartext());
+        assert!(!endpoints[0].is_debug());
+        assert_eq!(endpoints[1].host(), "staging.example.com");
+        assert!(endpoints[1].is_cleartext());
+        assert!(endpoints[1].is_debug());
+        assert_eq!(endpoints[2].host(), "192.168.1.20");
+        assert_eq!(endpoints[2].file(), Path::new("assets/config.json"));
+    }
+
+    /// Checks the detection of endpoints in string resources.
+    #[test]
+    fn it_scan_strings() {
+        let text = "<resources xmlns:tools=\"http://schemas.android.com/tools\">\n    \
+                    <string name=\"api\">http://10.0.2.2:3000/api</string>\n</resources>\n";
+        let strings = vec![(
+            String::from("api"),
+            String::from("http://10.0.2.2:3000/api"),
+        )];
+        let endpoints = scan_strings(text, &strings, Path::new("res/values/strings.xml"));
+
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].address(), "http://10.0.2.2:3000/api");
+        assert_eq!(endpoints[0].line(), 2);
+        assert!(endpoints[0].is_debug());
+    }
+
+    /// Checks the extraction of hosts.
+    #[test]
+    fn it_host() {
+        assert_eq!(host("https://user@example.com:8443/path"), "example.com");
+        assert_eq!(host("wss://dev-api.example.com"), "dev-api.example.com");
+        assert_eq!(host("127.0.0.1:8080"), "127.0.0.1");
+        assert_eq!(host("http://localhost"), "localhost");
+    }
+}
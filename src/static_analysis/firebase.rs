@@ -0,0 +1,524 @@
+//! Firebase configuration module.
+//!
+//! The Google services Gradle plugin turns the `google-services.json` file of the project into
+//! string resources, such as `firebase_database_url` or `google_storage_bucket`, and some
+//! applications also bundle the JSON file itself in their assets. This module collects the
+//! Firebase projects, Realtime Database URLs and Cloud Storage buckets of the application from
+//! both sources, and from the database URLs found by the secrets scanner, and reports them so
+//! that their security rules can be reviewed.
+//!
+//! With `--online-checks` (or `online_checks = true` in the configuration), SUPER also reads the
+//! root of each database and lists each bucket without credentials. Databases that can be read
+//! by anyone are reported as critical, and buckets that can be listed by anyone as high. Only
+//! databases hosted by Firebase (`https://*.firebaseio.com` and `https://*.firebasedatabase.app`)
+//! are checked, since their URLs come from the analyzed application. Online checks require the
+//! `integrations` feature.
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use failure::Error;
+#[cfg(feature = "integrations")]
+use failure::{format_err, ResultExt};
+
+use super::{secrets::SecretKind, strings::parse_strings};
+#[cfg(feature = "integrations")]
+use crate::http;
+use crate::{
+    criticality::Criticality,
+    get_code, print_vulnerability, print_warning,
+    results::{Confidence, Results, Vulnerability},
+    Config,
+};
+
+/// String resources generated by the Google services plugin with the database URL.
+const DATABASE_URL_KEY: &str = "firebase_database_url";
+/// String resources generated by the Google services plugin with the storage bucket.
+const STORAGE_BUCKET_KEY: &str = "google_storage_bucket";
+/// String resources generated by the Google services plugin with the project ID.
+const PROJECT_ID_KEY: &str = "project_id";
+/// Domains of the Firebase Realtime Databases.
+#[cfg(feature = "integrations")]
+const DATABASE_DOMAINS: [&str; 2] = [".firebaseio.com", ".firebasedatabase.app"];
+
+/// Firebase configuration of an application.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirebaseConfig {
+    /// IDs of the Firebase projects.
+    project_ids: BTreeSet<String>,
+    /// URLs of the Realtime Databases, without the trailing slash.
+    database_urls: BTreeSet<String>,
+    /// Names of the Cloud Storage buckets.
+    storage_buckets: BTreeSet<String>,
+}
+
+impl FirebaseConfig {
+    /// Adds the configuration in the given string resources, returning the keys of the Firebase
+    /// strings found.
+    fn add_strings(&mut self, code: &str) -> Result<Vec<String>, Error> {
+        let mut keys = Vec::new();
+        for (key, value) in parse_strings(code)? {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match key.as_str() {
+                DATABASE_URL_KEY => self.add_database_url(value),
+                STORAGE_BUCKET_KEY => self.add_storage_bucket(value),
+                PROJECT_ID_KEY => {
+                    let _ = self.project_ids.insert(value.to_owned());
+                }
+                _ => continue,
+            }
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    /// Adds the configuration in the given `google-services.json` file, returning whether it
+    /// contained any.
+    fn add_google_services(&mut self, json: &str) -> Result<bool, Error> {
+        let services: serde_json::Value = serde_json::from_str(json)?;
+        let project = &services["project_info"];
+        let mut found = false;
+        if let Some(project_id) = project["project_id"].as_str() {
+            let _ = self.project_ids.insert(project_id.to_owned());
+            found = true;
+        }
+        if let Some(url) = project["firebase_url"].as_str() {
+            self.add_database_url(url);
+            found = true;
+        }
+        if let Some(bucket) = project["storage_bucket"].as_str() {
+            self.add_storage_bucket(bucket);
+            found = true;
+        }
+        Ok(found)
+    }
+
+    /// Adds the given database URL.
+    fn add_database_url(&mut self, url: &str) {
+        let _ = self
+            .database_urls
+            .insert(url.trim().trim_end_matches('/').to_owned());
+    }
+
+    /// Adds the given storage bucket, with or without the `gs://` scheme.
+    fn add_storage_bucket(&mut self, bucket: &str) {
+        let bucket = bucket.trim();
+        let _ = self.storage_buckets.insert(
+            bucket
+                .strip_prefix("gs://")
+                .unwrap_or(bucket)
+                .trim_end_matches('/')
+                .to_owned(),
+        );
+    }
+
+    /// Adds the configuration of the given one.
+    fn merge(&mut self, other: Self) {
+        self.project_ids.extend(other.project_ids);
+        self.database_urls.extend(other.database_urls);
+        self.storage_buckets.extend(other.storage_buckets);
+    }
+
+    /// Returns the IDs of the Firebase projects.
+    pub fn project_ids(&self) -> impl Iterator<Item = &str> {
+        self.project_ids.iter().map(String::as_str)
+    }
+
+    /// Returns the URLs of the Realtime Databases.
+    pub fn database_urls(&self) -> impl Iterator<Item = &str> {
+        self.database_urls.iter().map(String::as_str)
+    }
+
+    /// Returns the names of the Cloud Storage buckets.
+    pub fn storage_buckets(&self) -> impl Iterator<Item = &str> {
+        self.storage_buckets.iter().map(String::as_str)
+    }
+
+    /// Checks if no Firebase configuration was found.
+    pub fn is_empty(&self) -> bool {
+        self.project_ids.is_empty()
+            && self.database_urls.is_empty()
+            && self.storage_buckets.is_empty()
+    }
+}
+
+/// Collects the Firebase configuration of the application, reports it and, if enabled, checks
+/// whether its databases and buckets are open.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut firebase = FirebaseConfig::default();
+
+    let strings = dist_folder.join("res").join("values").join("strings.xml");
+    if strings.is_file() {
+        match analyze_strings(config, &dist_folder, &strings, results) {
+            Ok(found) => firebase.merge(found),
+            Err(e) => print_warning(format!(
+                "there was an error reading the Firebase configuration in the strings: {}",
+                e
+            )),
+        }
+    }
+
+    let services = dist_folder.join("assets").join("google-services.json");
+    if services.is_file() {
+        match analyze_google_services(config, &dist_folder, &services, results) {
+            Ok(found) => firebase.merge(found),
+            Err(e) => print_warning(format!(
+                "there was an error reading the bundled `google-services.json`: {}",
+                e
+            )),
+        }
+    }
+
+    for secret in results.secrets() {
+        if secret.kind() == SecretKind::FirebaseUrl {
+            firebase.add_database_url(secret.value());
+        }
+    }
+
+    if config.is_online_checks() && !firebase.is_empty() {
+        online_checks(config, &firebase, results);
+    }
+}
+
+/// Reads the Firebase configuration of the default string resources, and reports it.
+fn analyze_strings(
+    config: &Config,
+    dist_folder: &Path,
+    path: &Path,
+    results: &mut Results,
+) -> Result<FirebaseConfig, Error> {
+    let code = fs::read_to_string(path)?;
+    let mut firebase = FirebaseConfig::default();
+    let keys = firebase.add_strings(&code)?;
+    if let Some(key) = keys.first() {
+        let line = code
+            .lines()
+            .position(|line| line.contains(&format!("name=\"{}\"", key)));
+        report_config(
+            config,
+            &firebase,
+            path.strip_prefix(dist_folder).unwrap_or(path),
+            line.map(|line| (line, code.as_str())),
+            results,
+        );
+    }
+    Ok(firebase)
+}
+
+/// Reads the bundled `google-services.json` file, and reports it.
+fn analyze_google_services(
+    config: &Config,
+    dist_folder: &Path,
+    path: &Path,
+    results: &mut Results,
+) -> Result<FirebaseConfig, Error> {
+    let json = fs::read_to_string(path)?;
+    let mut firebase = FirebaseConfig::default();
+    if firebase.add_google_services(&json)? {
+        report_config(
+            config,
+            &firebase,
+            path.strip_prefix(dist_folder).unwrap_or(path),
+            None,
+            results,
+        );
+    }
+    Ok(firebase)
+}
+
+/// Reports the Firebase configuration found in the given file, with the line and the code of the
+/// file, if known.
+fn report_config(
+    config: &Config,
+    firebase: &FirebaseConfig,
+    file: &Path,
+    line: Option<(usize, &str)>,
+    results: &mut Results,
+) {
+    let criticality = Criticality::Warning;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let resources = firebase
+        .project_ids()
+        .map(|id| format!("project `{}`", id))
+        .chain(
+            firebase
+                .database_urls()
+                .map(|url| format!("database `{}`", url)),
+        )
+        .chain(
+            firebase
+                .storage_buckets()
+                .map(|bucket| format!("bucket `{}`", bucket)),
+        )
+        .collect::<Vec<_>>();
+    let description = format!(
+        "The application is configured to use Firebase ({}). Anyone can extract this \
+         configuration from the application and access the Firebase services directly, so their \
+         security rules must not allow reads or writes without authentication.",
+        resources.join(", ")
+    );
+    let mut builder =
+        Vulnerability::builder(criticality, "Firebase Configuration", description.as_str())
+            .file(file)
+            .rule_id("firebase:config")
+            .confidence(Confidence::High)
+            .evidence(resources.join(", "));
+    if let Some((line, code)) = line {
+        builder = builder.lines(line, line).code(get_code(code, line, line));
+    }
+
+    results.add_vulnerability(builder.build());
+    print_vulnerability(description, criticality);
+}
+
+/// Checks whether the databases and buckets of the given configuration can be read without
+/// credentials.
+#[cfg(feature = "integrations")]
+fn online_checks(config: &Config, firebase: &FirebaseConfig, results: &mut Results) {
+    let client = match http::client(None) {
+        Ok(client) => client,
+        Err(e) => {
+            print_warning(format!("could not run the Firebase online checks: {}", e));
+            return;
+        }
+    };
+
+    for url in firebase.database_urls() {
+        if !is_firebase_database(url) {
+            print_warning(format!(
+                "the database `{}` is not hosted by Firebase, so it will not be checked",
+                url
+            ));
+            continue;
+        }
+        match is_readable(&client, &format!("{}/.json?shallow=true", url)) {
+            Ok(true) => report_open(
+                config,
+                Criticality::Critical,
+                "Open Firebase Database",
+                "firebase:open-database",
+                url,
+                format!(
+                    "The Firebase Realtime Database `{}` can be read without authentication. \
+                     Anyone can download all the data stored in it, which usually includes \
+                     personal data of the users, and it might allow writes too. Its security \
+                     rules must require authentication and restrict each user to their own \
+                     data.",
+                    url
+                ),
+                results,
+            ),
+            Ok(false) => {}
+            Err(e) => print_warning(format!("could not check the database `{}`: {}", url, e)),
+        }
+    }
+
+    for bucket in firebase.storage_buckets() {
+        let url = format!(
+            "https://firebasestorage.googleapis.com/v0/b/{}/o?maxResults=1",
+            percent_encode(bucket)
+        );
+        match is_readable(&client, &url) {
+            Ok(true) => report_open(
+                config,
+                Criticality::High,
+                "Public Firebase Storage Bucket",
+                "firebase:public-storage",
+                bucket,
+                format!(
+                    "The files of the Firebase Cloud Storage bucket `{}` can be listed without \
+                     authentication. Anyone can find and download the files uploaded by the \
+                     users. Its security rules must require authentication.",
+                    bucket
+                ),
+                results,
+            ),
+            Ok(false) => {}
+            Err(e) => print_warning(format!("could not check the bucket `{}`: {}", bucket, e)),
+        }
+    }
+}
+
+/// Checks whether the databases and buckets of the given configuration can be read without
+/// credentials.
+///
+/// Online checks require the `integrations` feature.
+#[cfg(not(feature = "integrations"))]
+fn online_checks(_config: &Config, _firebase: &FirebaseConfig, _results: &mut Results) {
+    print_warning(
+        "the Firebase online checks were skipped, they require the `integrations` feature",
+    );
+}
+
+/// Checks if the given database URL is an HTTPS URL of a Firebase Realtime Database, without
+/// credentials, port or path.
+#[cfg(feature = "integrations")]
+fn is_firebase_database(url: &str) -> bool {
+    url.strip_prefix("https://").map_or(false, |host| {
+        let host = host.to_ascii_lowercase();
+        host.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && DATABASE_DOMAINS.iter().any(|domain| {
+                host.strip_suffix(domain).map_or(false, |name| {
+                    !name.is_empty() && !name.starts_with('.') && !name.ends_with('.')
+                })
+            })
+    })
+}
+
+/// Percent-encodes the given path segment.
+#[cfg(feature = "integrations")]
+fn percent_encode(segment: &str) -> String {
+    segment.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+        encoded
+    })
+}
+
+/// Checks whether the given URL can be read without credentials.
+///
+/// Firebase answers with `401 Unauthorized` or `403 Forbidden` when the security rules deny the
+/// read, and with other errors when the database or the bucket does not exist.
+#[cfg(feature = "integrations")]
+fn is_readable(client: &reqwest::Client, url: &str) -> Result<bool, Error> {
+    let response = client
+        .get(url)
+        .header("User-Agent", http::USER_AGENT)
+        .send()
+        .context(format_err!("the request to `{}` failed", url))?;
+    Ok(response.status().is_success())
+}
+
+/// Reports an open database or bucket.
+#[cfg(feature = "integrations")]
+fn report_open(
+    config: &Config,
+    criticality: Criticality,
+    name: &str,
+    rule_id: &str,
+    resource: &str,
+    description: String,
+    results: &mut Results,
+) {
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    results.add_vulnerability(
+        Vulnerability::builder(criticality, name, description.as_str())
+            .rule_id(rule_id)
+            .cwe(284)
+            .masvs(vec!["MASVS-STORAGE-2", "MASVS-NETWORK-1"])
+            .confidence(Confidence::High)
+            .evidence(resource)
+            .build(),
+    );
+    print_vulnerability(description, criticality);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FirebaseConfig;
+
+    /// Checks the collection of the Firebase configuration from the strings and from the
+    /// `google-services.json` file.
+    #[test]
+    fn it_firebase_config() {
+        let mut config = FirebaseConfig::default();
+        let keys = config
+            .add_strings(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <string name="app_name">Example</string>
+    <string name="firebase_database_url">https://example-app.firebaseio.com/</string>
+    <string name="google_storage_bucket">example-app.appspot.com</string>
+    <string name="project_id">example-app</string>
+</resources>"#,
+            )
+            .unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                "firebase_database_url",
+                "google_storage_bucket",
+                "project_id"
+            ]
+        );
+        assert_eq!(
+            config.database_urls().collect::<Vec<_>>(),
+            vec!["https://example-app.firebaseio.com"]
+        );
+
+        assert!(config
+            .add_google_services(
+                r#"{
+                    "project_info": {
+                        "project_number": "123456789",
+                        "firebase_url": "https://other-app.firebaseio.com",
+                        "project_id": "other-app",
+                        "storage_bucket": "gs://other-app.appspot.com"
+                    },
+                    "client": []
+                }"#,
+            )
+            .unwrap());
+        assert_eq!(
+            config.project_ids().collect::<Vec<_>>(),
+            vec!["example-app", "other-app"]
+        );
+        assert_eq!(
+            config.storage_buckets().collect::<Vec<_>>(),
+            vec!["example-app.appspot.com", "other-app.appspot.com"]
+        );
+        assert!(!config.is_empty());
+
+        assert!(!FirebaseConfig::default()
+            .add_google_services(r#"{ "client": [] }"#)
+            .unwrap());
+        assert!(FirebaseConfig::default().is_empty());
+    }
+
+    /// Checks the URLs and buckets that are probed by the online checks.
+    #[cfg(feature = "integrations")]
+    #[test]
+    fn it_online_targets() {
+        use super::{is_firebase_database, percent_encode};
+
+        assert!(is_firebase_database("https://example-app.firebaseio.com"));
+        assert!(is_firebase_database(
+            "https://example-app-default-rtdb.europe-west1.firebasedatabase.app"
+        ));
+        assert!(!is_firebase_database("http://example-app.firebaseio.com"));
+        assert!(!is_firebase_database("https://firebaseio.com"));
+        assert!(!is_firebase_database("https://.firebaseio.com"));
+        assert!(!is_firebase_database("https://internal.example.com"));
+        assert!(!is_firebase_database(
+            "https://169.254.169.254/latest?.firebaseio.com"
+        ));
+        assert!(!is_firebase_database(
+            "https://user@example-app.firebaseio.com"
+        ));
+        assert!(!is_firebase_database(
+            "https://example-app.firebaseio.com:8080"
+        ));
+        assert!(!is_firebase_database(
+            "https://example-app.firebaseio.com/path"
+        ));
+
+        assert_eq!(
+            percent_encode("example-app.appspot.com"),
+            "example-app.appspot.com"
+        );
+        assert_eq!(percent_encode("../o?x=1#"), "..%2Fo%3Fx%3D1%23");
+    }
+}
@@ -0,0 +1,217 @@
+//! Forward check templates.
+//!
+//! The forward check of a rule is a second regular expression, searched in the whole file when the
+//! regular expression of the rule matches. It is a template that can include the capture groups of
+//! the match, named `fc1`, `fc2`, `fc3` and so on, with the `{fc1}`, `{fc2}`, `{fc3}` placeholders.
+//!
+//! Forward checks without placeholders are compiled once, when the rule is loaded. The ones with
+//! placeholders are compiled for each distinct set of captures, and cached, since the same
+//! captures are usually found many times in the code of an application.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Mutex, MutexGuard},
+};
+
+use failure::{format_err, Error};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Deserializer};
+
+/// Maximum number of compiled regular expressions cached for each forward check.
+const CACHE_SIZE: usize = 256;
+
+/// Forward check of a rule.
+#[derive(Debug)]
+pub(crate) struct ForwardCheck {
+    /// Template of the regular expression.
+    template: String,
+    /// Names of the capture groups used in the template, in the order they are first used.
+    placeholders: Box<[String]>,
+    /// Compiled regular expression, if the template has no placeholders.
+    compiled: Option<Regex>,
+    /// Compiled regular expressions, by their source.
+    cache: Mutex<HashMap<String, Regex>>,
+}
+
+impl ForwardCheck {
+    /// Creates the forward check for the given template.
+    ///
+    /// Templates without placeholders are compiled, so it fails if they are not valid regular
+    /// expressions.
+    pub(crate) fn new<S: Into<String>>(template: S) -> Result<Self, Error> {
+        let template = template.into();
+        let placeholders = parse_placeholders(&template);
+        let compiled = if placeholders.is_empty() {
+            Some(compile(&template)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            template,
+            placeholders,
+            compiled,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Checks if the template uses the capture group with the given name.
+    pub(crate) fn uses(&self, name: &str) -> bool {
+        self.placeholders
+            .iter()
+            .any(|placeholder| placeholder == name)
+    }
+
+    /// Gets the names of the capture groups used in the template.
+    pub(crate) fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.placeholders.iter().map(String::as_str)
+    }
+
+    /// Gets the regular expression of the forward check for the given captures of the rule.
+    ///
+    /// Placeholders without a matching capture are kept as they are.
+    pub(crate) fn regex(&self, captures: &Captures<'_>) -> Result<Regex, Error> {
+        if let Some(ref compiled) = self.compiled {
+            return Ok(compiled.clone());
+        }
+
+        let source = self.render(captures);
+        if let Some(regex) = self.cache().get(&source) {
+            return Ok(regex.clone());
+        }
+
+        let regex = compile(&source)?;
+        let mut cache = self.cache();
+        if cache.len() >= CACHE_SIZE {
+            cache.clear();
+        }
+        let _ = cache.insert(source, regex.clone());
+        Ok(regex)
+    }
+
+    /// Replaces the placeholders of the template with the given captures.
+    fn render(&self, captures: &Captures<'_>) -> String {
+        let mut source = self.template.clone();
+        for name in self.placeholders.iter() {
+            if let Some(capture) = captures.name(name) {
+                source = source.replace(&format!("{{{}}}", name), capture.as_str());
+            }
+        }
+        source
+    }
+
+    /// Gets the cache of compiled regular expressions.
+    fn cache(&self) -> MutexGuard<'_, HashMap<String, Regex>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Display for ForwardCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+impl<'de> Deserialize<'de> for ForwardCheck {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error as DeError;
+
+        let template = String::deserialize(de)?;
+        Self::new(template).map_err(DeError::custom)
+    }
+}
+
+/// Checks if the given capture group name is a forward check capture, such as `fc1`.
+pub(crate) fn is_capture_name(name: &str) -> bool {
+    name.len() > 2
+        && name.starts_with("fc")
+        && name[2..].bytes().all(|byte| byte.is_ascii_digit())
+        && !name[2..].starts_with('0')
+}
+
+/// Gets the names of the capture groups used in the given template, in the order they are first
+/// used.
+fn parse_placeholders(template: &str) -> Box<[String]> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(end) = rest.find('}') {
+            let name = &rest[..end];
+            if is_capture_name(name) && !placeholders.iter().any(|p| p == name) {
+                placeholders.push(name.to_owned());
+            }
+        }
+    }
+    placeholders.into_boxed_slice()
+}
+
+/// Compiles the given forward check.
+fn compile(source: &str) -> Result<Regex, Error> {
+    Regex::new(source).map_err(|e| {
+        format_err!(
+            "there was an error creating the forward_check '{}': {}",
+            source,
+            e
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::{is_capture_name, ForwardCheck};
+
+    /// Checks the names of the forward check capture groups.
+    #[test]
+    fn it_is_capture_name() {
+        assert!(is_capture_name("fc1"));
+        assert!(is_capture_name("fc12"));
+        assert!(!is_capture_name("fc"));
+        assert!(!is_capture_name("fc0"));
+        assert!(!is_capture_name("fcx"));
+        assert!(!is_capture_name("name"));
+    }
+
+    /// Checks the compilation and the caching of the forward checks.
+    #[test]
+    fn it_forward_check() {
+        let check = ForwardCheck::new(r"getDeviceId\s*\(").unwrap();
+        assert_eq!(check.placeholders().count(), 0);
+        assert!(check.compiled.is_some());
+        assert!(ForwardCheck::new("(unclosed").is_err());
+
+        let check = ForwardCheck::new(r"{fc1}\.{fc2}\({fc1}{fc3}").unwrap();
+        assert_eq!(
+            check.placeholders().collect::<Vec<_>>(),
+            vec!["fc1", "fc2", "fc3"]
+        );
+        assert!(check.uses("fc2"));
+        assert!(!check.uses("fc4"));
+        assert_eq!(check.to_string(), r"{fc1}\.{fc2}\({fc1}{fc3}");
+
+        let rule = Regex::new(r"(?P<fc1>\w+) = new (?P<fc2>\w+)").unwrap();
+        let captures = rule.captures("web = new WebView").unwrap();
+        assert_eq!(check.render(&captures), r"web\.WebView\(web{fc3}");
+
+        let check = ForwardCheck::new(r"{fc1}\.loadUrl\(").unwrap();
+        let regex = check.regex(&captures).unwrap();
+        assert!(regex.is_match("web.loadUrl(url);"));
+        assert_eq!(check.cache().len(), 1);
+        let _ = check.regex(&captures).unwrap();
+        assert_eq!(check.cache().len(), 1);
+
+        let captures = rule.captures("view = new WebView").unwrap();
+        let regex = check.regex(&captures).unwrap();
+        assert!(regex.is_match("view.loadUrl(url);"));
+        assert!(!regex.is_match("web.loadUrl(url);"));
+        assert_eq!(check.cache().len(), 2);
+    }
+}
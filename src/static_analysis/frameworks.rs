@@ -0,0 +1,161 @@
+//! Cross-platform frameworks detection module.
+//!
+//! Hybrid and cross-platform applications keep most of their logic outside of the Java code:
+//! Cordova applications in the web assets of the `assets/www` folder, React Native applications in
+//! the `assets/index.android.bundle` JavaScript bundle, Flutter applications in the `libapp.so`
+//! native library and Xamarin or .NET MAUI applications in the .NET assemblies of the
+//! `assemblies` folder. This module detects these frameworks from the files they ship, so that
+//! the report shows which parts of the application were analyzed.
+//!
+//! The JavaScript files of Cordova and React Native applications are analyzed with the rules that
+//! include them in their `include_file_regex` or `file_types`, except for React Native bundles
+//! compiled to Hermes bytecode, which are not JavaScript anymore.
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+use crate::{print_message, print_warning, results::Results, Config};
+
+/// Magic number of the Hermes bytecode files.
+const HERMES_MAGIC: [u8; 8] = [0xc6, 0x1f, 0xbc, 0x03, 0xc1, 0x03, 0x19, 0x1f];
+
+/// Cross-platform framework used to build an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framework {
+    /// Apache Cordova, or PhoneGap and Ionic, that are built on it.
+    Cordova,
+    /// React Native.
+    ReactNative,
+    /// Flutter.
+    Flutter,
+    /// Xamarin or .NET MAUI.
+    Xamarin,
+}
+
+impl Framework {
+    /// Detects the frameworks used by the decompressed application in the given folder.
+    pub fn detect<P: AsRef<Path>>(dist_folder: P) -> Vec<Self> {
+        let dist_folder = dist_folder.as_ref();
+        let assets = dist_folder.join("assets");
+        let mut frameworks = Vec::new();
+        if assets.join("www").join("cordova.js").is_file()
+            || dist_folder.join("classes/org/apache/cordova").is_dir()
+        {
+            frameworks.push(Framework::Cordova);
+        }
+        if assets.join("index.android.bundle").is_file()
+            || has_native_library(dist_folder, "libreactnativejni.so")
+        {
+            frameworks.push(Framework::ReactNative);
+        }
+        if assets.join("flutter_assets").is_dir()
+            || has_native_library(dist_folder, "libflutter.so")
+        {
+            frameworks.push(Framework::Flutter);
+        }
+        if dist_folder.join("assemblies").is_dir()
+            || has_native_library(dist_folder, "libmonodroid.so")
+        {
+            frameworks.push(Framework::Xamarin);
+        }
+        frameworks
+    }
+}
+
+impl fmt::Display for Framework {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Framework::Cordova => write!(f, "Cordova"),
+            Framework::ReactNative => write!(f, "React Native"),
+            Framework::Flutter => write!(f, "Flutter"),
+            Framework::Xamarin => write!(f, "Xamarin/.NET MAUI"),
+        }
+    }
+}
+
+/// Checks if the application in the given folder ships the native library with the given name,
+/// for any ABI.
+fn has_native_library(dist_folder: &Path, name: &str) -> bool {
+    fs::read_dir(dist_folder.join("lib")).map_or(false, |entries| {
+        entries
+            .filter_map(Result::ok)
+            .any(|entry| entry.path().join(name).is_file())
+    })
+}
+
+/// Checks if the given JavaScript bundle is compiled to Hermes bytecode.
+pub fn is_hermes_bundle<P: AsRef<Path>>(path: P) -> bool {
+    let mut magic = [0; 8];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && magic == HERMES_MAGIC
+}
+
+/// Detects the frameworks used to build the application and adds them to the results.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let frameworks = Framework::detect(config.dist_folder().join(package.as_ref()));
+    if config.is_verbose() && !frameworks.is_empty() {
+        print_message(format!(
+            "The application is built with {}.",
+            frameworks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let bundle = config
+        .dist_folder()
+        .join(package.as_ref())
+        .join("assets")
+        .join("index.android.bundle");
+    if bundle.is_file() && is_hermes_bundle(&bundle) {
+        print_warning(
+            "the React Native bundle is compiled to Hermes bytecode, its JavaScript code will not \
+             be analyzed",
+        );
+    }
+    for framework in frameworks {
+        results.add_framework(framework);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{is_hermes_bundle, Framework, HERMES_MAGIC};
+
+    /// Checks the detection of the frameworks from the files of the application.
+    #[test]
+    fn it_detect() {
+        let dist_folder = env::temp_dir().join("super-frameworks-test");
+        let _ = fs::remove_dir_all(&dist_folder);
+        fs::create_dir_all(dist_folder.join("assets")).unwrap();
+        let abi_folder = dist_folder.join("lib").join("arm64-v8a");
+        fs::create_dir_all(&abi_folder).unwrap();
+        assert!(Framework::detect(&dist_folder).is_empty());
+
+        fs::write(abi_folder.join("libflutter.so"), b"\x7fELF").unwrap();
+        let bundle = dist_folder.join("assets").join("index.android.bundle");
+        fs::write(&bundle, "__d(function(g, r, i, a, m, e, d) {});").unwrap();
+        assert_eq!(
+            Framework::detect(&dist_folder),
+            vec![Framework::ReactNative, Framework::Flutter]
+        );
+        assert!(!is_hermes_bundle(&bundle));
+
+        let mut hermes = HERMES_MAGIC.to_vec();
+        hermes.extend_from_slice(&[0; 16]);
+        fs::write(&bundle, hermes).unwrap();
+        assert!(is_hermes_bundle(&bundle));
+
+        fs::remove_dir_all(&dist_folder).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! Bundled libraries detection module.
+//!
+//! Libraries are detected from the metadata that build tools leave in the `META-INF` folder of
+//! the application:
+//!
+//! * `META-INF/<group>_<artifact>.version` files, added by the Android Gradle plugin for AndroidX
+//!   and Jetpack libraries, with the version of the library as their contents.
+//! * `META-INF/maven/<group>/<artifact>/pom.properties` files, added by Maven for libraries whose
+//!   resources got merged in the application.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use failure::Error;
+
+use crate::{print_message, print_warning, results::Results, Config};
+
+/// Library bundled in the application.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Library {
+    /// Group of the library, in Maven terms.
+    group: String,
+    /// Name of the library (the Maven artifact).
+    name: String,
+    /// Version of the library.
+    version: String,
+}
+
+impl Library {
+    /// Creates a new library.
+    pub fn new<G: Into<String>, N: Into<String>, V: Into<String>>(
+        group: G,
+        name: N,
+        version: V,
+    ) -> Self {
+        Self {
+            group: group.into(),
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+
+    /// Returns the group of the library.
+    pub fn group(&self) -> &str {
+        self.group.as_str()
+    }
+
+    /// Returns the name of the library.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the version of the library.
+    pub fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    /// Returns the package URL of the library.
+    pub fn purl(&self) -> String {
+        format!("pkg:maven/{}/{}@{}", self.group, self.name, self.version)
+    }
+}
+
+/// Detects the libraries bundled in the application and adds them to the results.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    match detect(config.dist_folder().join(package.as_ref())) {
+        Ok(libraries) => {
+            if config.is_verbose() {
+                print_message(format!(
+                    "{} bundled libraries were detected.",
+                    format!("{}", libraries.len()).bold()
+                ));
+            }
+            for library in libraries {
+                results.add_library(library);
+            }
+        }
+        Err(e) => print_warning(format!(
+            "there was an error detecting the bundled libraries: {}",
+            e
+        )),
+    }
+}
+
+/// Detects the libraries bundled in the application in the given distribution folder.
+pub fn detect<P: AsRef<Path>>(dist_folder: P) -> Result<Vec<Library>, Error> {
+    let meta_inf = dist_folder.as_ref().join("META-INF");
+    let mut libraries = BTreeSet::new();
+    if !meta_inf.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    for entry in fs::read_dir(&meta_inf)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "version") {
+            if let Some(library) = from_version_file(&path)? {
+                let _ = libraries.insert(library);
+            }
+        }
+    }
+
+    let mut pom_files = Vec::new();
+    find_pom_properties(meta_inf.join("maven"), &mut pom_files)?;
+    for path in pom_files {
+        if let Some(library) = parse_pom_properties(&fs::read_to_string(path)?) {
+            let _ = libraries.insert(library);
+        }
+    }
+
+    Ok(libraries.into_iter().collect())
+}
+
+/// Parses a `<group>_<artifact>.version` file.
+fn from_version_file(path: &Path) -> Result<Option<Library>, Error> {
+    let stem = match path.file_stem().and_then(|s| s.to_str()) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let separator = match stem.find('_') {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let version = fs::read_to_string(path)?;
+    let version = version.trim();
+    if version.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Library::new(
+        &stem[..separator],
+        &stem[separator + 1..],
+        version,
+    )))
+}
+
+/// Finds all the `pom.properties` files in the given folder, recursively.
+fn find_pom_properties(folder: PathBuf, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_pom_properties(path, files)?;
+        } else if path
+            .file_name()
+            .map_or(false, |name| name == "pom.properties")
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the contents of a `pom.properties` file.
+fn parse_pom_properties(properties: &str) -> Option<Library> {
+    let (mut group, mut name, mut version) = (None, None, None);
+    for line in properties.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        match (parts.next().map(str::trim), parts.next().map(str::trim)) {
+            (Some("groupId"), Some(value)) => group = Some(value),
+            (Some("artifactId"), Some(value)) => name = Some(value),
+            (Some("version"), Some(value)) => version = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(Library::new(group?, name?, version?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_pom_properties, Library};
+
+    /// Checks the parsing of `pom.properties` files.
+    #[test]
+    fn it_parse_pom_properties() {
+        let library = parse_pom_properties(
+            "#Created by Apache Maven 3.5.0\n\
+             version=3.12.0\n\
+             groupId=com.squareup.okhttp3\n\
+             artifactId=okhttp\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            library,
+            Library::new("com.squareup.okhttp3", "okhttp", "3.12.0")
+        );
+        assert_eq!(
+            library.purl(),
+            "pkg:maven/com.squareup.okhttp3/okhttp@3.12.0"
+        );
+        assert!(parse_pom_properties("groupId=com.example\nversion=1.0").is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! Location of the findings in the decompiled Java code.
+//!
+//! Findings in Java files are reported with the class and the method they were found in, such as
+//! `com.example.LoginActivity#onCreate`, instead of just the file and the line. The structure of
+//! the decompiled code is recovered line by line, following the braces of the class and method
+//! declarations. The decompilers write each declaration in a single line, so no full parser is
+//! needed. Nested classes are named with a `$`, as in the bytecode, while the methods of anonymous
+//! classes and lambdas are reported in the enclosing class.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::results::Vulnerability;
+
+lazy_static! {
+    /// Class, interface and enum declarations.
+    static ref CLASS: Regex =
+        Regex::new(r"\b(?:class|interface|enum)\s+([A-Za-z_$][\w$]*)").unwrap();
+    /// Method and constructor declarations, with the modifiers and the return type before them.
+    static ref METHOD: Regex = Regex::new(
+        r"^\s*((?:[\w$<>\[\]?,.@]+\s+)*)([A-Za-z_$][\w$]*)\s*\([^()]*\)\s*(?:throws\s+[\w$.,\s]+)?\{"
+    )
+    .unwrap();
+    /// String and character literals.
+    static ref LITERAL: Regex = Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'"#).unwrap();
+}
+
+/// Words that precede a block that is not a method, or that are followed by parentheses.
+const NOT_METHODS: [&str; 10] = [
+    "if",
+    "for",
+    "while",
+    "switch",
+    "catch",
+    "synchronized",
+    "try",
+    "return",
+    "new",
+    "else",
+];
+
+/// Block opened by a brace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    /// Body of the class with the given index.
+    Class(usize),
+    /// Body of the method with the given index.
+    Method(usize),
+    /// Any other block.
+    Other,
+}
+
+/// Classes and methods of each line of a Java file.
+#[derive(Debug)]
+pub(crate) struct Locations {
+    /// Fully qualified names of the classes of the file.
+    classes: Vec<String>,
+    /// Names of the methods of the file.
+    methods: Vec<String>,
+    /// Class and method of each line, by their index.
+    lines: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl Locations {
+    /// Recovers the classes and methods of the given code, of the file of the given top-level
+    /// class.
+    pub(crate) fn parse(code: &str, top_level_class: &str) -> Self {
+        let package = top_level_class
+            .rfind('.')
+            .map_or("", |dot| &top_level_class[..=dot]);
+        let mut locations = Self {
+            classes: Vec::new(),
+            methods: Vec::new(),
+            lines: Vec::new(),
+        };
+        let mut blocks = Vec::new();
+        let mut in_comment = false;
+
+        for line in code.lines() {
+            let line = strip_comments(&LITERAL.replace_all(line, "\"\""), &mut in_comment);
+            let mut declared = None;
+            if let Some(caps) = CLASS.captures(&line) {
+                let name = &caps[1];
+                let class = match current(&blocks).0 {
+                    Some(outer) => format!("{}${}", locations.classes[outer], name),
+                    None => format!("{}{}", package, name),
+                };
+                locations.classes.push(class);
+                declared = Some(Block::Class(locations.classes.len() - 1));
+            } else if let Some(caps) = METHOD.captures(&line) {
+                let is_method = !NOT_METHODS.contains(&&caps[2])
+                    && !caps[1]
+                        .split_whitespace()
+                        .any(|word| NOT_METHODS.contains(&word));
+                if is_method && current(&blocks).0.is_some() {
+                    locations.methods.push(caps[2].to_owned());
+                    declared = Some(Block::Method(locations.methods.len() - 1));
+                }
+            }
+
+            let start = current(&blocks);
+            let mut body = declared;
+            for c in line.chars() {
+                match c {
+                    '{' => blocks.push(body.take().unwrap_or(Block::Other)),
+                    '}' => {
+                        let _ = blocks.pop();
+                    }
+                    _ => {}
+                }
+            }
+            let location = match declared {
+                Some(Block::Class(class)) => (Some(class), None),
+                Some(Block::Method(method)) => (start.0, Some(method)),
+                _ => start,
+            };
+            locations.lines.push(location);
+        }
+
+        locations
+    }
+
+    /// Gets the class and the method, if any, of the line with the given index.
+    pub(crate) fn at(&self, line: usize) -> Option<(&str, Option<&str>)> {
+        let (class, method) = *self.lines.get(line)?;
+        Some((
+            self.classes[class?].as_str(),
+            method.map(|method| self.methods[method].as_str()),
+        ))
+    }
+
+    /// Sets the class and the method of the given vulnerability, from its starting line.
+    pub(crate) fn locate(&self, vulnerability: &mut Vulnerability) {
+        let location = vulnerability.start_line().and_then(|line| self.at(line));
+        if let Some((class, method)) = location {
+            vulnerability.set_location(class.to_owned(), method.map(str::to_owned));
+        }
+    }
+}
+
+/// Gets the innermost class, and the method of that class, if any, of the given open blocks.
+fn current(blocks: &[Block]) -> (Option<usize>, Option<usize>) {
+    let mut location = (None, None);
+    for block in blocks {
+        match *block {
+            Block::Class(class) => location = (Some(class), None),
+            Block::Method(method) => location.1 = Some(method),
+            Block::Other => {}
+        }
+    }
+    location
+}
+
+/// Removes the comments of the given line.
+///
+/// The given flag tracks if the line starts inside a block comment, and it is updated for the
+/// next line.
+fn strip_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        if *in_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    rest = &rest[end + 2..];
+                    *in_comment = false;
+                }
+                None => return code,
+            }
+        }
+        let block = rest.find("/*");
+        let line_comment = rest.find("//");
+        match (block, line_comment) {
+            (Some(block), Some(line_comment)) if line_comment < block => {
+                code.push_str(&rest[..line_comment]);
+                return code;
+            }
+            (Some(block), _) => {
+                code.push_str(&rest[..block]);
+                rest = &rest[block + 2..];
+                *in_comment = true;
+            }
+            (None, Some(line_comment)) => {
+                code.push_str(&rest[..line_comment]);
+                return code;
+            }
+            (None, None) => {
+                code.push_str(rest);
+                return code;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locations;
+    use crate::{criticality::Criticality, results::Vulnerability};
+
+    /// Checks the classes and methods recovered from decompiled code.
+    #[test]
+    fn it_locations() {
+        let code = "package com.example;\n\
+                    \n\
+                    public class LoginActivity extends Activity {\n\
+                    \x20   private String url = \"{\";\n\
+                    \n\
+                    \x20   /* access modifiers changed from: protected */\n\
+                    \x20   public void onCreate(Bundle bundle) {\n\
+                    \x20       if (bundle != null) {\n\
+                    \x20           login(\"}\");\n\
+                    \x20       }\n\
+                    \x20       new Thread(new Runnable() {\n\
+                    \x20           public void run() {\n\
+                    \x20               connect();\n\
+                    \x20           }\n\
+                    \x20       }).start();\n\
+                    \x20   }\n\
+                    \n\
+                    \x20   static final class Session implements Serializable {\n\
+                    \x20       Session(String token) throws IOException {\n\
+                    \x20           // }\n\
+                    \x20       }\n\
+                    \x20   }\n\
+                    }\n";
+        let locations = Locations::parse(code, "com.example.LoginActivity");
+
+        assert_eq!(locations.at(0), None);
+        assert_eq!(locations.at(2), Some(("com.example.LoginActivity", None)));
+        assert_eq!(locations.at(3), Some(("com.example.LoginActivity", None)));
+        assert_eq!(
+            locations.at(6),
+            Some(("com.example.LoginActivity", Some("onCreate")))
+        );
+        assert_eq!(
+            locations.at(8),
+            Some(("com.example.LoginActivity", Some("onCreate")))
+        );
+        assert_eq!(
+            locations.at(12),
+            Some(("com.example.LoginActivity", Some("run")))
+        );
+        assert_eq!(
+            locations.at(14),
+            Some(("com.example.LoginActivity", Some("onCreate")))
+        );
+        assert_eq!(locations.at(16), Some(("com.example.LoginActivity", None)));
+        assert_eq!(
+            locations.at(17),
+            Some(("com.example.LoginActivity$Session", None))
+        );
+        assert_eq!(
+            locations.at(19),
+            Some(("com.example.LoginActivity$Session", Some("Session")))
+        );
+        assert_eq!(locations.at(22), Some(("com.example.LoginActivity", None)));
+        assert_eq!(locations.at(30), None);
+
+        let mut vulnerability = Vulnerability::builder(Criticality::High, "Name", "Description")
+            .lines(8, 8)
+            .build();
+        locations.locate(&mut vulnerability);
+        assert_eq!(
+            vulnerability.location().as_ref().map(String::as_str),
+            Some("com.example.LoginActivity#onCreate")
+        );
+    }
+}
@@ -1,19 +1,32 @@
 //! Module containing the manifest analysis logic.
 
-use std::{fs, path::Path, str::FromStr};
+use std::{
+    borrow::Borrow,
+    collections::{btree_set, BTreeSet},
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    slice::Iter,
+    str::FromStr,
+};
 
 use colored::Colorize;
-use failure::Error;
+use failure::{format_err, Error, ResultExt};
+use lazy_static::lazy_static;
 use serde::{self, Deserialize, Deserializer};
 use xml::{
     attribute::OwnedAttribute,
     reader::{EventReader, XmlEvent},
 };
 
+use super::backup::BackupRules;
 use crate::{
+    axml,
+    bundle::PackageFormat,
     criticality::Criticality,
-    error, get_code, get_string, print_vulnerability, print_warning,
-    results::{Results, Vulnerability},
+    error, get_code, print_message, print_vulnerability, print_warning,
+    results::{Results, StageStatus, Vulnerability, VulnerabilityBuilder},
+    utils::get_string_from,
     Config, PARSER_CONFIG,
 };
 
@@ -24,23 +37,38 @@ pub fn analysis<S: AsRef<str>>(
     results: &mut Results,
 ) -> Option<Manifest> {
     if config.is_verbose() {
-        println!(
+        print_message(
             "Loading the manifest file. For this, we first parse the document and then we'll \
-             analyze it."
+             analyze it.",
         )
     }
 
-    let manifest = match Manifest::load(
-        config.dist_folder().join(package.as_ref()),
-        config,
-        package.as_ref(),
-        results,
-    ) {
-        Ok(m) => {
+    let manifest = match Manifest::load(config.dist_folder().join(package.as_ref())).or_else(|e| {
+        let apk = config.app_packages().into_iter().find(|path| {
+            config.package_name(path) == package.as_ref()
+                && PackageFormat::of(path) == PackageFormat::Apk
+        });
+        match apk {
+            Some(apk) => {
+                if config.is_verbose() {
+                    print_message(format!(
+                        "Could not load the decompressed manifest ({}), reading it from the APK \
+                         instead.",
+                        e
+                    ));
+                }
+                Manifest::load_from_apk(apk)
+            }
+            None => Err(e),
+        }
+    }) {
+        Ok(mut m) => {
+            m.classify_permissions(config.permission_catalog());
             if config.is_verbose() {
-                println!("{}", "The manifest was loaded successfully!".green());
-                println!();
+                print_message("The manifest was loaded successfully!".green());
+                print_message("");
             }
+            results.set_stage_status("manifest", StageStatus::Ok);
             m
         }
         Err(e) => {
@@ -48,11 +76,12 @@ pub fn analysis<S: AsRef<str>>(
                 "There was an error when loading the manifest: {}",
                 e
             ));
+            results.set_stage_status("manifest", StageStatus::failed(e));
             if config.is_verbose() {
-                println!(
+                print_message(
                     "The rest of the analysis will continue, but there will be no analysis of the \
                      AndroidManifest.xml file, and code analysis rules requiring permissions will \
-                     not run."
+                     not run.",
                 );
             }
             return None;
@@ -68,12 +97,12 @@ pub fn analysis<S: AsRef<str>>(
         ));
 
         if config.is_verbose() {
-            println!(
+            print_message(format!(
                 "This does not mean that something went wrong, but it's supposed to have the \
                  application in the format {{package}}.apk in the {} folder and use the package as \
                  the application ID for this auditor.",
                 "downloads".italic()
-            );
+            ));
         }
     }
 
@@ -145,71 +174,701 @@ pub fn analysis<S: AsRef<str>>(
     }
 
     if manifest.allows_backup() {
-        let criticality = Criticality::Medium;
+        backup_analysis(
+            config,
+            &config.dist_folder().join(package.as_ref()),
+            &manifest,
+            results,
+        );
+    }
 
-        if criticality >= config.min_criticality() {
-            let description = "This option allows backups of the application data via adb. \
-                               Malicious people with physical access could use adb to get private \
-                               data of your app into their PC.";
+    results.set_dangerous_permissions(
+        config
+            .permissions()
+            .filter(|permission| {
+                permission.criticality() >= Criticality::Medium
+                    && manifest
+                        .permission_checklist()
+                        .needs_permission(permission.name())
+            })
+            .count(),
+    );
 
-            let line = get_line(manifest.code(), "android:allowBackup=\"true\"").ok();
+    for permission in config.permissions() {
+        if manifest
+            .permission_checklist()
+            .needs_permission(permission.name())
+            && permission.criticality() >= config.min_criticality()
+        {
+            let line = get_line(manifest.code(), permission.name().as_str()).ok();
             let code = match line {
                 Some(l) => Some(get_code(manifest.code(), l, l)),
                 None => None,
             };
 
             let vulnerability = Vulnerability::new(
-                criticality,
-                "Allows Backup",
-                description,
+                permission.criticality(),
+                permission.label(),
+                permission.description(),
                 Some("AndroidManifest.xml"),
                 line,
                 line,
                 code,
             );
             results.add_vulnerability(vulnerability);
-            print_vulnerability(description, criticality);
+            print_vulnerability(permission.description(), permission.criticality());
         }
     }
 
-    for permission in config.permissions() {
-        if manifest
-            .permission_checklist()
-            .needs_permission(permission.name())
-            && permission.criticality() >= config.min_criticality()
-        {
-            let line = get_line(manifest.code(), permission.name().as_str()).ok();
+    let criticality = config.unknown_permission_criticality();
+    if criticality > config.min_criticality() {
+        for permission in manifest.unknown_permissions() {
+            let line = get_line(manifest.code(), permission.as_str()).ok();
             let code = match line {
                 Some(l) => Some(get_code(manifest.code(), l, l)),
                 None => None,
             };
 
+            let description = config.unknown_permission_description();
             let vulnerability = Vulnerability::new(
-                permission.criticality(),
-                permission.label(),
-                permission.description(),
+                criticality,
+                "Unknown permission",
+                description,
                 Some("AndroidManifest.xml"),
                 line,
                 line,
                 code,
             );
             results.add_vulnerability(vulnerability);
-            print_vulnerability(permission.description(), permission.criticality());
+            print_vulnerability(description, criticality);
+        }
+    }
+
+    let criticality = Criticality::Warning;
+    if criticality >= config.min_criticality() {
+        for component in manifest.components() {
+            if component.is_exposed(manifest.min_sdk()) {
+                let code = match component.line() {
+                    Some(l) => Some(get_code(manifest.code(), l, l)),
+                    None => None,
+                };
+                let description = format!(
+                    "Exported {} was found. It can be used by other applications.",
+                    component.kind()
+                );
+
+                let vulnerability = Vulnerability::new(
+                    criticality,
+                    format!("Exported {}", component.kind()),
+                    description.as_str(),
+                    Some("AndroidManifest.xml"),
+                    component.line(),
+                    component.line(),
+                    code,
+                );
+                results.add_vulnerability(vulnerability);
+                print_vulnerability(description, criticality);
+            }
         }
     }
 
+    application_attribute_analysis(config, &manifest, results);
+    task_hijacking_analysis(config, &manifest, results);
+    intent_filter_analysis(config, &manifest, results);
+    custom_permission_analysis(config, &manifest, results);
+    permission_combination_analysis(config, &manifest, results);
+
     if config.is_verbose() {
-        println!();
-        println!("{}", "The manifest was analyzed correctly!".green());
-        println!();
+        print_message("");
+        print_message("The manifest was analyzed correctly!".green());
+        print_message("");
     } else if !config.is_quiet() {
-        println!("Manifest analyzed.");
+        print_message("Manifest analyzed.");
     }
 
     Some(manifest)
 }
 
-/// Manifest analysis representation structure.
+/// Analyzes the deep links and browsable components declared in the intent filters.
+fn intent_filter_analysis(config: &Config, manifest: &Manifest, results: &mut Results) {
+    let exposed_activities = manifest.components().filter(|component| {
+        (component.kind() == ComponentKind::Activity
+            || component.kind() == ComponentKind::ActivityAlias)
+            && component.is_exposed(manifest.min_sdk())
+    });
+
+    for component in exposed_activities {
+        let criticality = Criticality::Warning;
+        if component.is_browsable() && criticality >= config.min_criticality() {
+            let description = format!(
+                "The exported activity `{}` can be opened from a web browser, so any web page can \
+                 start it with arbitrary data. All the data received by the activity should be \
+                 validated.",
+                component.name()
+            );
+            add_manifest_vulnerability(
+                results,
+                Vulnerability::builder(criticality, "Browsable activity", description.as_str())
+                    .rule_id("manifest-browsable-activity")
+                    .cwe(926)
+                    .evidence(component.name()),
+                component.line(),
+                manifest.code(),
+            );
+        }
+
+        for filter in component.intent_filters().filter(|f| f.is_deep_link()) {
+            let criticality = Criticality::Medium;
+            if criticality >= config.min_criticality() {
+                for scheme in filter.custom_schemes() {
+                    let description = format!(
+                        "The activity `{}` handles `{}://` deep links. Custom URI schemes cannot be \
+                         verified, so any other application can register the same scheme and \
+                         intercept the links, that might contain sensitive data. Use verified app \
+                         links instead.",
+                        component.name(),
+                        scheme
+                    );
+                    add_manifest_vulnerability(
+                        results,
+                        Vulnerability::builder(
+                            criticality,
+                            "Unverified deep link",
+                            description.as_str(),
+                        )
+                        .rule_id("manifest-unverified-deep-link")
+                        .cwe(939)
+                        .evidence(format!("{}://", scheme)),
+                        get_line(manifest.code(), &format!("android:scheme=\"{}\"", scheme)).ok(),
+                        manifest.code(),
+                    );
+                }
+            }
+
+            let criticality = Criticality::Low;
+            if filter.is_app_link()
+                && !filter.auto_verify()
+                && criticality >= config.min_criticality()
+            {
+                let hosts = filter.hosts().collect::<Vec<_>>().join(", ");
+                let description = format!(
+                    "The activity `{}` handles web links for the hosts `{}`, but the intent \
+                     filter does not set `android:autoVerify=\"true\"`. Without verification, \
+                     other applications can handle the same links and the user will be asked \
+                     which application should open them.",
+                    component.name(),
+                    hosts
+                );
+                add_manifest_vulnerability(
+                    results,
+                    Vulnerability::builder(
+                        criticality,
+                        "Missing app link verification",
+                        description.as_str(),
+                    )
+                    .rule_id("manifest-app-link-verification")
+                    .cwe(940)
+                    .evidence(hosts),
+                    component.line(),
+                    manifest.code(),
+                );
+            }
+        }
+    }
+}
+
+/// Analyzes the backups allowed by the application, using its backup rules to adjust the
+/// criticality.
+fn backup_analysis(config: &Config, app_folder: &Path, manifest: &Manifest, results: &mut Results) {
+    let mut rules = Vec::new();
+    for reference in manifest
+        .full_backup_content()
+        .into_iter()
+        .chain(manifest.data_extraction_rules())
+    {
+        match BackupRules::load(app_folder, reference) {
+            Ok(Some(loaded)) => rules.push((reference, loaded)),
+            Ok(None) => {}
+            Err(e) => print_warning(format!(
+                "could not read the backup rules `{}`: {}",
+                reference, e
+            )),
+        }
+    }
+
+    let line = get_line(manifest.code(), "android:allowBackup=\"true\"").ok();
+    if rules.is_empty() {
+        let criticality = Criticality::Medium;
+
+        if criticality >= config.min_criticality() {
+            let description = "This option allows backups of the application data via adb. \
+                               Malicious people with physical access could use adb to get private \
+                               data of your app into their PC.";
+
+            let code = match line {
+                Some(l) => Some(get_code(manifest.code(), l, l)),
+                None => None,
+            };
+
+            let vulnerability = Vulnerability::new(
+                criticality,
+                "Allows Backup",
+                description,
+                Some("AndroidManifest.xml"),
+                line,
+                line,
+                code,
+            );
+            results.add_vulnerability(vulnerability);
+            print_vulnerability(description, criticality);
+        }
+        return;
+    }
+
+    let criticality = rules
+        .iter()
+        .map(|(_, rules)| rules.criticality())
+        .max()
+        .unwrap_or(Criticality::Medium);
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let files = rules
+        .iter()
+        .map(|(reference, _)| format!("`{}`", reference))
+        .collect::<Vec<_>>()
+        .join(" and ");
+    let paths = rules
+        .iter()
+        .flat_map(|(_, rules)| rules.sections())
+        .flat_map(|section| {
+            section
+                .sensitive_paths()
+                .into_iter()
+                .map(move |path| format!("{} ({})", path, section.name()))
+        })
+        .collect::<Vec<_>>();
+    let description = if paths.is_empty() {
+        format!(
+            "This option allows backups of the application data, but the backup rules in {} \
+             exclude the databases and the shared preferences, so backups should not contain \
+             sensitive data. Check that the rest of the backed up files do not contain private \
+             data either.",
+            files
+        )
+    } else {
+        format!(
+            "This option allows backups of the application data, and the backup rules in {} \
+             back up data that usually contains tokens and personal data: {}. Malicious people \
+             with physical access could use adb to get that data into their PC, and it is also \
+             stored in the cloud backups of the user. Exclude the databases and shared \
+             preferences that contain private data.",
+            files,
+            paths.join(", ")
+        )
+    };
+
+    let mut builder = Vulnerability::builder(criticality, "Allows Backup", description.as_str())
+        .rule_id("manifest-allow-backup")
+        .cwe(530);
+    if !paths.is_empty() {
+        builder = builder.evidence(paths.join(", "));
+    }
+    add_manifest_vulnerability(results, builder, line, manifest.code());
+}
+
+/// Analyzes the network, testing and storage attributes of the `<application>` element.
+fn application_attribute_analysis(config: &Config, manifest: &Manifest, results: &mut Results) {
+    let application_line = get_line(manifest.code(), "<application").ok();
+
+    // The network security configuration replaces `usesCleartextTraffic` from API 24.
+    if manifest.uses_cleartext_traffic()
+        && (manifest.network_security_config().is_none() || manifest.min_sdk() < 24)
+    {
+        let (criticality, description, line) = if manifest.declares_cleartext_traffic() {
+            (
+                Criticality::Medium,
+                String::from(
+                    "The application sets `android:usesCleartextTraffic=\"true\"`, so it can \
+                     send and receive data over unencrypted HTTP and other cleartext protocols. \
+                     Anyone in the same network can read and modify that traffic. Use HTTPS, or a \
+                     network security configuration that only allows cleartext traffic to the \
+                     domains that need it.",
+                ),
+                get_line(manifest.code(), "android:usesCleartextTraffic=\"true\"").ok(),
+            )
+        } else {
+            (
+                Criticality::Low,
+                format!(
+                    "The application targets API {}, so cleartext traffic is allowed by default. \
+                     Anyone in the same network can read and modify unencrypted HTTP traffic. Set \
+                     `android:usesCleartextTraffic=\"false\"` or use a network security \
+                     configuration.",
+                    manifest.target_sdk().unwrap_or_else(|| manifest.min_sdk())
+                ),
+                application_line,
+            )
+        };
+        if criticality >= config.min_criticality() {
+            add_manifest_vulnerability(
+                results,
+                Vulnerability::builder(criticality, "Cleartext traffic allowed", description)
+                    .rule_id("manifest-cleartext-traffic")
+                    .cwe(319),
+                line,
+                manifest.code(),
+            );
+        }
+    }
+
+    let criticality = Criticality::Medium;
+    if manifest.is_test_only() && criticality >= config.min_criticality() {
+        add_manifest_vulnerability(
+            results,
+            Vulnerability::builder(
+                criticality,
+                "Test-only application",
+                "The application sets `android:testOnly=\"true\"`. Test-only builds are meant \
+                 for development, they can only be installed with adb and usually contain \
+                 debugging code and relaxed checks. They should never be distributed.",
+            )
+            .rule_id("manifest-test-only")
+            .cwe(489),
+            get_line(manifest.code(), "android:testOnly=\"true\"").ok(),
+            manifest.code(),
+        );
+    }
+
+    let criticality = Criticality::Low;
+    if manifest.is_direct_boot_aware() && criticality >= config.min_criticality() {
+        add_manifest_vulnerability(
+            results,
+            Vulnerability::builder(
+                criticality,
+                "Direct boot aware application",
+                "The application sets `android:directBootAware=\"true\"`, so it runs before the \
+                 user unlocks the device. The data it needs at that point is stored in the device \
+                 protected storage, that is not protected by the credentials of the user. Check \
+                 that no sensitive data is stored there.",
+            )
+            .rule_id("manifest-direct-boot-aware")
+            .cwe(312),
+            get_line(manifest.code(), "android:directBootAware=\"true\"").ok(),
+            manifest.code(),
+        );
+    }
+
+    let criticality = Criticality::Warning;
+    if !manifest.allows_clear_user_data() && criticality >= config.min_criticality() {
+        add_manifest_vulnerability(
+            results,
+            Vulnerability::builder(
+                criticality,
+                "User data cannot be cleared",
+                "The application sets `android:allowClearUserData=\"false\"` to prevent users \
+                 from clearing its data. The attribute is only honored for system applications, \
+                 but it shows that the application expects its data to persist, so check which \
+                 data it keeps.",
+            )
+            .rule_id("manifest-allow-clear-user-data"),
+            get_line(manifest.code(), "android:allowClearUserData=\"false\"").ok(),
+            manifest.code(),
+        );
+    }
+}
+
+/// Analyzes the activities that can be hijacked by a malicious application with the same task
+/// affinity, such as in the StrandHogg attack.
+fn task_hijacking_analysis(config: &Config, manifest: &Manifest, results: &mut Results) {
+    let criticality = Criticality::Medium;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    for component in manifest.components() {
+        let launch_mode = match component.launch_mode() {
+            Some(mode @ "singleTask")
+            | Some(mode @ "singleInstance")
+            | Some(mode @ "singleInstancePerTask") => mode,
+            _ => continue,
+        };
+        let affinity = manifest.task_affinity_of(component);
+        if affinity.is_empty() {
+            continue;
+        }
+
+        let description = format!(
+            "The activity `{}` uses the `{}` launch mode with the `{}` task affinity. A malicious \
+             application can declare an activity with the same task affinity, so that it is \
+             placed in the task of this application and shown instead of it, to steal \
+             credentials or trick the user (task hijacking, also known as StrandHogg). Set \
+             `android:taskAffinity=\"\"` in the activity or in the application.",
+            component.name(),
+            launch_mode,
+            affinity
+        );
+        add_manifest_vulnerability(
+            results,
+            Vulnerability::builder(criticality, "Task hijacking", description.as_str())
+                .rule_id("manifest-task-hijacking")
+                .cwe(1021)
+                .evidence(format!("{} ({})", component.name(), launch_mode)),
+            component.line(),
+            manifest.code(),
+        );
+    }
+}
+
+/// Analyzes the combinations of permissions requested by the application.
+fn permission_combination_analysis(config: &Config, manifest: &Manifest, results: &mut Results) {
+    for combination in config.permission_combinations() {
+        if combination.criticality() < config.min_criticality() {
+            continue;
+        }
+        let permissions = match combination.matches(manifest) {
+            Some(permissions) => permissions,
+            None => continue,
+        };
+
+        let line = permissions
+            .iter()
+            .filter_map(|permission| get_line(manifest.code(), permission.as_str()).ok())
+            .min();
+        let mut builder = Vulnerability::builder(
+            combination.criticality(),
+            combination.name(),
+            combination.description(),
+        )
+        .rule_id("manifest-permission-combination")
+        .evidence(permissions.join(" + "));
+        if let Some(cwe) = combination.cwe() {
+            builder = builder.cwe(cwe);
+        }
+        add_manifest_vulnerability(results, builder, line, manifest.code());
+    }
+}
+
+/// Combination of permissions that is risky, even if each permission is not risky by itself.
+///
+/// Combinations are configured in the `config.toml` file:
+///
+/// ```toml
+/// [[permission_combinations]]
+/// name = "SMS Interception"
+/// description = "The application can receive SMS messages and send data to the internet."
+/// criticality = "high"
+/// all_of = ["RECEIVE_SMS", "INTERNET"]
+/// cwe = 200
+/// ```
+///
+/// Permissions without a package, such as `RECEIVE_SMS`, are Android permissions. A permission is
+/// present if the application requests it or requires it to access any of its components, such as
+/// the `BIND_ACCESSIBILITY_SERVICE` permission of accessibility services.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PermissionCombination {
+    /// Name of the finding.
+    name: String,
+    /// Description of the finding.
+    description: String,
+    /// Criticality of the finding.
+    criticality: Criticality,
+    /// Permissions that must all be present.
+    all_of: Vec<String>,
+    /// CWE identifier of the finding.
+    #[serde(default)]
+    cwe: Option<u32>,
+}
+
+impl PermissionCombination {
+    /// Returns the name of the finding.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description of the finding.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the criticality of the finding.
+    pub fn criticality(&self) -> Criticality {
+        self.criticality
+    }
+
+    /// Returns the CWE identifier of the finding, if any.
+    pub fn cwe(&self) -> Option<u32> {
+        self.cwe
+    }
+
+    /// Returns the full names of the permissions of the combination, if all of them are present
+    /// in the given manifest.
+    pub fn matches(&self, manifest: &Manifest) -> Option<Vec<String>> {
+        if self.all_of.is_empty() {
+            return None;
+        }
+
+        self.all_of
+            .iter()
+            .map(|permission| {
+                let permission = if permission.contains('.') {
+                    permission.clone()
+                } else {
+                    format!("android.permission.{}", permission)
+                };
+                let present = manifest
+                    .requested_permissions()
+                    .any(|requested| requested == &permission)
+                    || manifest.enforces_permission(&permission);
+                if present {
+                    Some(permission)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Analyzes the permissions declared by the application.
+fn custom_permission_analysis(config: &Config, manifest: &Manifest, results: &mut Results) {
+    for permission in manifest.custom_permissions() {
+        let criticality = match permission.base_protection_level() {
+            "normal" => Criticality::Medium,
+            "dangerous" => Criticality::Low,
+            _ => Criticality::Warning,
+        };
+        if criticality > Criticality::Warning && criticality >= config.min_criticality() {
+            let description = format!(
+                "The application declares the `{}` permission with the `{}` protection level, so \
+                 any other application can get it{}. If it protects sensitive components or data, \
+                 it should use the `signature` protection level.",
+                permission.name(),
+                permission.base_protection_level(),
+                if criticality == Criticality::Low {
+                    " if the user grants it"
+                } else {
+                    " just by requesting it"
+                }
+            );
+            add_manifest_vulnerability(
+                results,
+                Vulnerability::builder(criticality, "Weak custom permission", description.as_str())
+                    .rule_id("manifest-weak-custom-permission")
+                    .cwe(732)
+                    .evidence(permission.name()),
+                permission.line(),
+                manifest.code(),
+            );
+        }
+
+        let criticality = Criticality::Medium;
+        if criticality >= config.min_criticality() {
+            if let Some(imitated) = permission.imitated_permission() {
+                let description = format!(
+                    "The application declares the `{}` permission, that imitates the name of the \
+                     `{}` Android permission. Permissions with confusing names might be used to \
+                     trick users or to squat permissions of other applications.",
+                    permission.name(),
+                    imitated.as_str()
+                );
+                add_manifest_vulnerability(
+                    results,
+                    Vulnerability::builder(
+                        criticality,
+                        "Typo-squatted permission",
+                        description.as_str(),
+                    )
+                    .rule_id("manifest-typosquatted-permission")
+                    .evidence(permission.name()),
+                    permission.line(),
+                    manifest.code(),
+                );
+            }
+        }
+
+        let criticality = Criticality::Warning;
+        if permission.is_signature()
+            && !manifest.enforces_permission(permission.name())
+            && criticality >= config.min_criticality()
+        {
+            let description = format!(
+                "The application declares the `{}` signature permission, but no component \
+                 requires it. Check if some component should be protected by it.",
+                permission.name()
+            );
+            add_manifest_vulnerability(
+                results,
+                Vulnerability::builder(
+                    criticality,
+                    "Unused signature permission",
+                    description.as_str(),
+                )
+                .rule_id("manifest-unused-permission")
+                .evidence(permission.name()),
+                permission.line(),
+                manifest.code(),
+            );
+        }
+    }
+}
+
+/// Adds a vulnerability found in the given line of the manifest to the results.
+fn add_manifest_vulnerability(
+    results: &mut Results,
+    builder: VulnerabilityBuilder,
+    line: Option<usize>,
+    code: &str,
+) {
+    let mut builder = builder.file("AndroidManifest.xml");
+    if let Some(l) = line {
+        builder = builder.lines(l, l).code(get_code(code, l, l));
+    }
+    let vulnerability = builder.build();
+    print_vulnerability(vulnerability.description(), vulnerability.get_criticality());
+    results.add_vulnerability(vulnerability);
+}
+
+/// Parses the given boolean attribute, warning if it is not a boolean.
+fn parse_bool_attribute(attr: &OwnedAttribute) -> Option<bool> {
+    match attr.value.parse() {
+        Ok(b) => Some(b),
+        Err(e) => {
+            print_warning(format!(
+                "An error occurred when parsing the `{}` attribute in the manifest: {}.\nThe \
+                 process will continue, though.",
+                attr.name.local_name, e
+            ));
+            None
+        }
+    }
+}
+
+/// Gets the name of the given launch mode, that binary manifests store as a number.
+fn launch_mode_name(value: String) -> String {
+    match value.as_str() {
+        "0" => String::from("standard"),
+        "1" => String::from("singleTop"),
+        "2" => String::from("singleTask"),
+        "3" => String::from("singleInstance"),
+        "4" => String::from("singleInstancePerTask"),
+        _ => value,
+    }
+}
+
+/// Creates the error of a manifest that could not be parsed.
+fn manifest_parse_error<E: Display>(error: E) -> Error {
+    error::Kind::ManifestParse {
+        message: error.to_string(),
+    }
+    .into()
+}
+
+/// Parsed representation of an `AndroidManifest.xml` file.
+///
+/// The manifest is parsed without performing any analysis on it, so it can be used by other tools
+/// that need access to the application metadata, its permissions or its components.
 #[derive(Debug, Default)]
 pub struct Manifest {
     code: String,
@@ -219,8 +878,21 @@ pub struct Manifest {
     allows_backup: bool,
     has_code: bool,
     large_heap: bool,
+    task_affinity: Option<String>,
+    full_backup_content: Option<String>,
+    data_extraction_rules: Option<String>,
+    uses_cleartext_traffic: Option<bool>,
+    network_security_config: Option<String>,
+    test_only: bool,
+    allow_clear_user_data: Option<bool>,
+    direct_boot_aware: bool,
     install_location: InstallLocation,
     permissions: PermissionChecklist,
+    requested_permissions: Vec<String>,
+    unknown_permissions: Vec<String>,
+    custom_permissions: Vec<CustomPermission>,
+    enforced_permissions: Vec<String>,
+    components: Vec<Component>,
     debug: bool,
     min_sdk: u32,
     target_sdk: Option<u32>,
@@ -229,21 +901,57 @@ pub struct Manifest {
 }
 
 impl Manifest {
-    /// Loads the given manifest in memory and analyzes it.
-    pub fn load<P: AsRef<Path>, S: AsRef<str>>(
-        path: P,
-        config: &Config,
-        package: S,
-        results: &mut Results,
-    ) -> Result<Self, Error> {
-        let code = fs::read_to_string(path.as_ref().join("AndroidManifest.xml"))?;
-        let mut manifest = Self::default();
+    /// Loads the manifest of the decompressed application in the given folder.
+    ///
+    /// The folder must contain the `AndroidManifest.xml` file, that will be decoded if it is
+    /// still in the binary XML format. If the application label references a string resource, it
+    /// will be resolved using the `res` folder inside it.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bytes = fs::read(path.as_ref().join("AndroidManifest.xml"))?;
+        let code = if axml::is_binary_xml(&bytes) {
+            axml::decode_in(&bytes, path.as_ref()).map_err(manifest_parse_error)?
+        } else {
+            String::from_utf8(bytes).map_err(manifest_parse_error)?
+        };
+        let mut manifest = Self::from_code(code);
+
+        if manifest.label.starts_with("@string/") {
+            match get_string_from(&manifest.label[8..], path.as_ref()) {
+                Ok(label) => manifest.set_label(label),
+                Err(e) => print_warning(format!(
+                    "An error occurred when trying to get the string for the app label in the \
+                     manifest: {}.\nThe process will continue, though.",
+                    e
+                )),
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Loads the manifest straight from the given APK package, decoding its binary
+    /// `AndroidManifest.xml` file.
+    ///
+    /// Resource references are decoded with the resource table of the package, but the label
+    /// will not be resolved if it references a string resource.
+    pub fn load_from_apk<P: AsRef<Path>>(apk: P) -> Result<Self, Error> {
+        Ok(Self::from_code(
+            axml::manifest_from_apk(apk).map_err(manifest_parse_error)?,
+        ))
+    }
 
-        manifest.set_code(code.as_str());
+    /// Parses the given decoded `AndroidManifest.xml` contents.
+    ///
+    /// String resources referenced in the manifest will not be resolved.
+    pub fn from_code<S: Into<String>>(code: S) -> Self {
+        let mut manifest = Self::default();
+        manifest.set_code(code);
 
-        let bytes = code.into_bytes();
+        let bytes = manifest.code.clone().into_bytes();
         let parser = EventReader::new_with_config(bytes.as_slice(), PARSER_CONFIG.clone());
 
+        let mut in_component = false;
+        let mut intent_filter: Option<IntentFilter> = None;
         for e in parser {
             match e {
                 Ok(XmlEvent::StartElement {
@@ -251,20 +959,38 @@ impl Manifest {
                 }) => match name.local_name.as_str() {
                     "manifest" => manifest.parse_manifest_attributes(attributes),
                     "uses-sdk" => manifest.parse_sdk_attributes(attributes),
-                    "application" => {
-                        manifest.parse_application_attributes(attributes, config, package.as_ref())
+                    "application" => manifest.parse_application_attributes(attributes),
+                    "uses-permission" => manifest.parse_permission_attributes(attributes),
+                    "permission" => manifest.parse_custom_permission_attributes(attributes),
+                    "path-permission" => manifest.parse_enforced_permissions(attributes),
+                    "intent-filter" if in_component => {
+                        intent_filter = Some(IntentFilter::from_attributes(attributes))
+                    }
+                    tag @ "action" | tag @ "category" | tag @ "data" => {
+                        if let Some(ref mut filter) = intent_filter {
+                            filter.parse_child_attributes(tag, attributes);
+                        }
                     }
-                    "uses-permission" => {
-                        manifest.parse_permission_attributes(attributes, config, results)
+                    tag => {
+                        if let Ok(kind) = ComponentKind::from_str(tag) {
+                            in_component = true;
+                            manifest.parse_component_attributes(kind, attributes)
+                        }
+                    }
+                },
+                Ok(XmlEvent::EndElement { name }) => match name.local_name.as_str() {
+                    "intent-filter" => {
+                        if let (Some(filter), Some(component)) =
+                            (intent_filter.take(), manifest.components.last_mut())
+                        {
+                            component.intent_filters.push(filter);
+                        }
                     }
-                    tag @ "provider"
-                    | tag @ "receiver"
-                    | tag @ "activity"
-                    | tag @ "activity-alias"
-                    | tag @ "service" => {
-                        manifest.check_exported_attributes(tag, attributes, config, results)
+                    tag => {
+                        if ComponentKind::from_str(tag).is_ok() {
+                            in_component = false;
+                        }
                     }
-                    _ => {}
                 },
                 Ok(_) => {}
                 Err(e) => {
@@ -277,7 +1003,7 @@ impl Manifest {
             }
         }
 
-        Ok(manifest)
+        manifest
     }
 
     fn parse_manifest_attributes<A>(&mut self, attributes: A)
@@ -360,10 +1086,9 @@ impl Manifest {
         }
     }
 
-    fn parse_application_attributes<A, S>(&mut self, attributes: A, config: &Config, package: S)
+    fn parse_application_attributes<A>(&mut self, attributes: A)
     where
         A: IntoIterator<Item = OwnedAttribute>,
-        S: AsRef<str>,
     {
         for attr in attributes {
             match attr.name.local_name.as_str() {
@@ -432,145 +1157,135 @@ impl Manifest {
                         self.set_large_heap();
                     }
                 }
-                "label" => self.set_label(
-                    if attr.value.starts_with("@string/") {
-                        match get_string(&attr.value[8..], config, package.as_ref()) {
-                            Ok(s) => s,
-                            Err(e) => {
-                                print_warning(format!(
-                                    "An error occurred when trying to get the string for the app \
-                                     label in the manifest: {}.\nThe process will continue, though.",
-                                    e
-                                ));
-                                break;
-                            }
-                        }
-                    } else {
-                        attr.value
-                    }.as_str(),
-                ),
+                "usesCleartextTraffic" => {
+                    self.uses_cleartext_traffic = parse_bool_attribute(&attr);
+                }
+                "networkSecurityConfig" => self.network_security_config = Some(attr.value),
+                "fullBackupContent" => self.full_backup_content = Some(attr.value),
+                "taskAffinity" => self.task_affinity = Some(attr.value),
+                "dataExtractionRules" => self.data_extraction_rules = Some(attr.value),
+                "testOnly" => self.test_only = parse_bool_attribute(&attr).unwrap_or(false),
+                "allowClearUserData" => {
+                    self.allow_clear_user_data = parse_bool_attribute(&attr);
+                }
+                "directBootAware" => {
+                    self.direct_boot_aware = parse_bool_attribute(&attr).unwrap_or(false);
+                }
+                "label" => self.set_label(attr.value),
+                "permission" => self.enforced_permissions.push(attr.value),
                 _ => {}
             }
         }
     }
 
-    fn parse_permission_attributes<A>(
-        &mut self,
-        attributes: A,
-        config: &Config,
-        results: &mut Results,
-    ) where
+    fn parse_permission_attributes<A>(&mut self, attributes: A)
+    where
         A: IntoIterator<Item = OwnedAttribute>,
     {
         for attr in attributes {
             if let "name" = attr.name.local_name.as_str() {
-                let permission = if let Ok(p) = Permission::from_str(attr.value.as_str()) {
-                    p
-                } else {
-                    let line = get_line(self.code(), attr.value.as_str()).ok();
-                    let code = match line {
-                        Some(l) => Some(get_code(self.code(), l, l)),
-                        None => None,
-                    };
-
-                    let criticality = config.unknown_permission_criticality();
-                    let description = config.unknown_permission_description();
-                    let file = Some("AndroidManifest.xml");
-
-                    if criticality > config.min_criticality() {
-                        let vulnerability = Vulnerability::new(
-                            criticality,
-                            "Unknown permission",
-                            description,
-                            file,
-                            line,
-                            line,
-                            code,
-                        );
-                        results.add_vulnerability(vulnerability);
-
-                        print_vulnerability(description, criticality);
-                    }
-                    break;
-                };
-                self.permissions.set_needs_permission(permission);
+                self.requested_permissions.push(attr.value.clone());
+                if let Ok(permission) = Permission::from_str(attr.value.as_str()) {
+                    self.permissions.set_needs_permission(permission);
+                }
+                if !PermissionCatalog::builtin().contains(&attr.value) {
+                    self.unknown_permissions.push(attr.value);
+                }
+                break;
             }
         }
     }
 
-    fn check_exported_attributes<A>(
-        &mut self,
-        tag: &str,
-        attributes: A,
-        config: &Config,
-        results: &mut Results,
-    ) where
+    fn parse_component_attributes<A>(&mut self, kind: ComponentKind, attributes: A)
+    where
         A: IntoIterator<Item = OwnedAttribute>,
     {
-        {
-            let mut exported = None;
-            let mut name = String::new();
-            for attr in attributes {
-                match attr.name.local_name.as_str() {
-                    "exported" => {
-                        if let Ok(found_exported) = attr.value.as_str().parse() {
-                            exported = Some(found_exported);
-                        }
+        let mut exported = None;
+        let mut name = String::new();
+        let mut permission = None;
+        let mut launch_mode = None;
+        let mut task_affinity = None;
+        for attr in attributes {
+            match attr.name.local_name.as_str() {
+                "launchMode" => launch_mode = Some(launch_mode_name(attr.value)),
+                "taskAffinity" => task_affinity = Some(attr.value),
+                "exported" => {
+                    if let Ok(found_exported) = attr.value.as_str().parse() {
+                        exported = Some(found_exported);
                     }
-                    "name" => name = attr.value,
-                    _ => {}
                 }
-            }
-            match exported {
-                Some(true) | None => {
-                    if tag != "provider" || exported.is_some() || self.min_sdk() < 17 {
-                        let line =
-                            get_line(self.code(), &format!("android:name=\"{}\"", name)).ok();
-                        let code = match line {
-                            Some(l) => Some(get_code(self.code(), l, l)),
-                            None => None,
-                        };
-
-                        let criticality = Criticality::Warning;
-
-                        if criticality >= config.min_criticality() {
-                            let vulnerability = Vulnerability::new(
-                                criticality,
-                                format!("Exported {}", tag),
-                                format!(
-                                    "Exported {} was found. It can be used by other applications.",
-                                    tag
-                                ),
-                                Some("AndroidManifest.xml"),
-                                line,
-                                line,
-                                code,
-                            );
-                            results.add_vulnerability(vulnerability);
-
-                            print_vulnerability(
-                                format!(
-                                    "Exported {} was found. It can be used by other applications.",
-                                    tag
-                                ),
-                                Criticality::Warning,
-                            );
-                        }
-                    }
+                "name" => name = attr.value,
+                "permission" => {
+                    self.enforced_permissions.push(attr.value.clone());
+                    permission = Some(attr.value);
                 }
+                "readPermission" | "writePermission" => self.enforced_permissions.push(attr.value),
                 _ => {}
             }
         }
-    }
-
-    fn set_code<S: Into<String>>(&mut self, code: S) {
-        self.code = code.into();
-    }
 
-    pub fn code(&self) -> &str {
-        &self.code
+        let line = get_line(self.code(), &format!("android:name=\"{}\"", name)).ok();
+        self.components.push(Component {
+            kind,
+            name,
+            exported,
+            permission,
+            launch_mode,
+            task_affinity,
+            line,
+            intent_filters: Vec::new(),
+        });
     }
 
+    fn parse_custom_permission_attributes<A>(&mut self, attributes: A)
+    where
+        A: IntoIterator<Item = OwnedAttribute>,
+    {
+        let mut name = String::new();
+        let mut protection_level = String::from("normal");
+        for attr in attributes {
+            match attr.name.local_name.as_str() {
+                "name" => name = attr.value,
+                "protectionLevel" => protection_level = attr.value,
+                _ => {}
+            }
+        }
+
+        let line = self
+            .code
+            .lines()
+            .position(|l| l.contains("<permission") && l.contains(name.as_str()))
+            .or_else(|| get_line(self.code(), &format!("android:name=\"{}\"", name)).ok());
+        self.custom_permissions.push(CustomPermission {
+            name,
+            protection_level,
+            line,
+        });
+    }
+
+    fn parse_enforced_permissions<A>(&mut self, attributes: A)
+    where
+        A: IntoIterator<Item = OwnedAttribute>,
+    {
+        for attr in attributes {
+            if let "permission" | "readPermission" | "writePermission" =
+                attr.name.local_name.as_str()
+            {
+                self.enforced_permissions.push(attr.value);
+            }
+        }
+    }
+
+    fn set_code<S: Into<String>>(&mut self, code: S) {
+        self.code = code.into();
+    }
+
+    /// Gets the decoded XML code of the manifest.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Gets the package of the application.
     pub fn package(&self) -> &str {
         &self.package
     }
@@ -579,6 +1294,7 @@ impl Manifest {
         self.package = package.into();
     }
 
+    /// Gets the version number (`versionCode`) of the application.
     pub fn version_number(&self) -> u32 {
         self.version_number
     }
@@ -587,6 +1303,7 @@ impl Manifest {
         self.version_number = version_number;
     }
 
+    /// Gets the version string (`versionName`) of the application.
     pub fn version_str(&self) -> &str {
         &self.version_str
     }
@@ -595,6 +1312,7 @@ impl Manifest {
         self.version_str = version_str.into();
     }
 
+    /// Gets the label of the application.
     pub fn label(&self) -> &str {
         &self.label
     }
@@ -603,6 +1321,7 @@ impl Manifest {
         self.label = label.into();
     }
 
+    /// Gets the description of the application.
     pub fn description(&self) -> &str {
         &self.description
     }
@@ -611,26 +1330,34 @@ impl Manifest {
         self.description = description.into();
     }
 
+    /// Gets the minimum SDK supported by the application.
     pub fn min_sdk(&self) -> u32 {
         self.min_sdk
     }
 
-    pub fn set_min_sdk(&mut self, min_sdk: u32) {
+    fn set_min_sdk(&mut self, min_sdk: u32) {
         self.min_sdk = min_sdk;
     }
 
+    /// Gets the target SDK of the application, if it was declared.
     pub fn target_sdk(&self) -> Option<u32> {
         self.target_sdk
     }
 
-    pub fn set_target_sdk(&mut self, target_sdk: u32) {
+    fn set_target_sdk(&mut self, target_sdk: u32) {
         self.target_sdk = Some(target_sdk);
     }
 
+    /// Returns whether the application declares that it contains code.
+    pub fn has_code(&self) -> bool {
+        self.has_code
+    }
+
     fn set_has_code(&mut self) {
         self.has_code = true;
     }
 
+    /// Returns whether the application allows backups of its data.
     pub fn allows_backup(&self) -> bool {
         self.allows_backup
     }
@@ -639,6 +1366,54 @@ impl Manifest {
         self.allows_backup = true;
     }
 
+    /// Gets the default task affinity of the activities, declared in the `<application>` element.
+    pub fn task_affinity(&self) -> Option<&str> {
+        self.task_affinity.as_ref().map(String::as_str)
+    }
+
+    /// Gets the effective task affinity of the given activity: its own task affinity, the one of
+    /// the application or, by default, the package name.
+    pub fn task_affinity_of<'m>(&'m self, component: &'m Component) -> &'m str {
+        component
+            .task_affinity()
+            .or_else(|| self.task_affinity())
+            .unwrap_or(&self.package)
+    }
+
+    /// Gets the fully qualified class name of the given component, resolving the names relative
+    /// to the package, such as `.DataProvider`.
+    pub fn class_of(&self, component: &Component) -> String {
+        if component.name.starts_with('.') {
+            format!("{}{}", self.package, component.name)
+        } else if !component.name.contains('.') {
+            format!("{}.{}", self.package, component.name)
+        } else {
+            component.name.clone()
+        }
+    }
+
+    /// Checks if the given class is a content provider that other applications can use without
+    /// holding any permission.
+    pub fn is_exposed_provider(&self, class: &str) -> bool {
+        self.components.iter().any(|component| {
+            component.kind == ComponentKind::Provider
+                && component.permission.is_none()
+                && component.is_exposed(self.min_sdk)
+                && self.class_of(component) == class
+        })
+    }
+
+    /// Gets the `android:fullBackupContent` backup rules resource of the application, if any.
+    pub fn full_backup_content(&self) -> Option<&str> {
+        self.full_backup_content.as_ref().map(String::as_str)
+    }
+
+    /// Gets the `android:dataExtractionRules` backup rules resource of the application, if any.
+    pub fn data_extraction_rules(&self) -> Option<&str> {
+        self.data_extraction_rules.as_ref().map(String::as_str)
+    }
+
+    /// Returns whether the application requests a large heap.
     pub fn needs_large_heap(&self) -> bool {
         self.large_heap
     }
@@ -647,10 +1422,50 @@ impl Manifest {
         self.large_heap = true;
     }
 
+    /// Returns whether the application allows cleartext network traffic.
+    ///
+    /// If the application does not set `android:usesCleartextTraffic`, it is allowed when it
+    /// targets an API level below 28.
+    pub fn uses_cleartext_traffic(&self) -> bool {
+        self.uses_cleartext_traffic
+            .unwrap_or_else(|| self.target_sdk.unwrap_or(self.min_sdk) < 28)
+    }
+
+    /// Returns whether the application explicitly sets `android:usesCleartextTraffic="true"`.
+    pub fn declares_cleartext_traffic(&self) -> bool {
+        self.uses_cleartext_traffic == Some(true)
+    }
+
+    /// Gets the network security configuration resource of the application, if any.
+    pub fn network_security_config(&self) -> Option<&str> {
+        self.network_security_config.as_ref().map(String::as_str)
+    }
+
+    /// Returns whether the application is a test-only build.
+    pub fn is_test_only(&self) -> bool {
+        self.test_only
+    }
+
+    /// Returns whether the user can clear the data of the application, `true` by default.
+    pub fn allows_clear_user_data(&self) -> bool {
+        self.allow_clear_user_data.unwrap_or(true)
+    }
+
+    /// Returns whether the application can run before the user unlocks the device.
+    pub fn is_direct_boot_aware(&self) -> bool {
+        self.direct_boot_aware
+    }
+
+    /// Gets the install location of the application.
+    pub fn install_location(&self) -> InstallLocation {
+        self.install_location
+    }
+
     fn set_install_location(&mut self, install_location: InstallLocation) {
         self.install_location = install_location;
     }
 
+    /// Returns whether the application is debuggable.
     pub fn is_debug(&self) -> bool {
         self.debug
     }
@@ -659,15 +1474,428 @@ impl Manifest {
         self.debug = true;
     }
 
+    /// Gets the set of well-formed permissions requested by the application.
     pub fn permission_checklist(&self) -> &PermissionChecklist {
         &self.permissions
     }
+
+    /// Gets the names of all the permissions requested by the application, known or not, in the
+    /// order they are declared.
+    pub fn requested_permissions(&self) -> Iter<String> {
+        self.requested_permissions.iter()
+    }
+
+    /// Gets the requested permissions that are not in the [`PermissionCatalog`].
+    ///
+    /// They are classified against the built-in catalog when the manifest is loaded, and against
+    /// the configured catalog once the manifest analysis has run.
+    pub fn unknown_permissions(&self) -> Iter<String> {
+        self.unknown_permissions.iter()
+    }
+
+    /// Classifies the requested permissions against the given catalog, so that the permissions
+    /// that are not in it are the unknown permissions of the manifest.
+    pub fn classify_permissions(&mut self, catalog: &PermissionCatalog) {
+        self.unknown_permissions = self
+            .requested_permissions
+            .iter()
+            .filter(|permission| !catalog.contains(permission))
+            .cloned()
+            .collect();
+    }
+
+    /// Gets the components (activities, services, receivers and providers) of the application.
+    pub fn components(&self) -> Iter<Component> {
+        self.components.iter()
+    }
+
+    /// Gets the permissions declared by the application with `<permission>` elements.
+    pub fn custom_permissions(&self) -> Iter<CustomPermission> {
+        self.custom_permissions.iter()
+    }
+
+    /// Checks if the given permission is required to access the application or any of its
+    /// components.
+    pub fn enforces_permission<S: AsRef<str>>(&self, permission: S) -> bool {
+        self.enforced_permissions
+            .iter()
+            .any(|enforced| enforced == permission.as_ref())
+    }
+}
+
+/// Application component declared in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Kind of the component.
+    kind: ComponentKind,
+    /// Name of the component, as declared in the `android:name` attribute.
+    name: String,
+    /// Value of the `android:exported` attribute, if present.
+    exported: Option<bool>,
+    /// Permission required to use the component, if any.
+    permission: Option<String>,
+    /// Launch mode of the activity, if declared.
+    launch_mode: Option<String>,
+    /// Task affinity of the activity, if declared.
+    task_affinity: Option<String>,
+    /// Line of the manifest where the component is declared.
+    line: Option<usize>,
+    /// Intent filters of the component.
+    intent_filters: Vec<IntentFilter>,
+}
+
+impl Component {
+    /// Gets the kind of the component.
+    pub fn kind(&self) -> ComponentKind {
+        self.kind
+    }
+
+    /// Gets the name of the component.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the value of the `android:exported` attribute, if it was set explicitly.
+    pub fn exported(&self) -> Option<bool> {
+        self.exported
+    }
+
+    /// Gets the permission required to use the component, if any.
+    pub fn permission(&self) -> Option<&str> {
+        self.permission.as_ref().map(String::as_str)
+    }
+
+    /// Gets the launch mode of the activity, such as `singleTask`, if declared.
+    pub fn launch_mode(&self) -> Option<&str> {
+        self.launch_mode.as_ref().map(String::as_str)
+    }
+
+    /// Gets the task affinity declared by the activity, if any.
+    pub fn task_affinity(&self) -> Option<&str> {
+        self.task_affinity.as_ref().map(String::as_str)
+    }
+
+    /// Gets the line of the manifest where the component is declared.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Gets the intent filters of the component.
+    pub fn intent_filters(&self) -> Iter<IntentFilter> {
+        self.intent_filters.iter()
+    }
+
+    /// Returns whether the component can be opened from a web browser.
+    pub fn is_browsable(&self) -> bool {
+        self.intent_filters.iter().any(IntentFilter::is_browsable)
+    }
+
+    /// Returns whether the component can be used by other applications.
+    ///
+    /// Components are exported by default, except for content providers in applications with a
+    /// minimum SDK of 17 or higher.
+    pub fn is_exposed(&self, min_sdk: u32) -> bool {
+        match self.exported {
+            Some(true) => true,
+            Some(false) => false,
+            None => self.kind != ComponentKind::Provider || min_sdk < 17,
+        }
+    }
+}
+
+/// Intent filter of an application component.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IntentFilter {
+    /// Actions accepted by the filter.
+    actions: Vec<String>,
+    /// Categories accepted by the filter.
+    categories: Vec<String>,
+    /// Data specifications of the filter.
+    data: Vec<IntentData>,
+    /// Value of the `android:autoVerify` attribute.
+    auto_verify: bool,
+}
+
+impl IntentFilter {
+    /// Creates an intent filter from the attributes of its `<intent-filter>` element.
+    fn from_attributes<A>(attributes: A) -> Self
+    where
+        A: IntoIterator<Item = OwnedAttribute>,
+    {
+        let mut filter = Self::default();
+        for attr in attributes {
+            if attr.name.local_name == "autoVerify" {
+                filter.auto_verify = attr.value == "true";
+            }
+        }
+        filter
+    }
+
+    /// Parses the attributes of an `<action>`, `<category>` or `<data>` child element.
+    fn parse_child_attributes<A>(&mut self, tag: &str, attributes: A)
+    where
+        A: IntoIterator<Item = OwnedAttribute>,
+    {
+        let mut data = IntentData::default();
+        for attr in attributes {
+            match (tag, attr.name.local_name.as_str()) {
+                ("action", "name") => self.actions.push(attr.value),
+                ("category", "name") => self.categories.push(attr.value),
+                ("data", "scheme") => data.scheme = Some(attr.value),
+                ("data", "host") => data.host = Some(attr.value),
+                _ => {}
+            }
+        }
+        if data != IntentData::default() {
+            self.data.push(data);
+        }
+    }
+
+    /// Gets the actions accepted by the filter.
+    pub fn actions(&self) -> Iter<String> {
+        self.actions.iter()
+    }
+
+    /// Gets the categories accepted by the filter.
+    pub fn categories(&self) -> Iter<String> {
+        self.categories.iter()
+    }
+
+    /// Gets the data specifications of the filter.
+    pub fn data(&self) -> Iter<IntentData> {
+        self.data.iter()
+    }
+
+    /// Returns whether the filter requests the verification of its app links.
+    pub fn auto_verify(&self) -> bool {
+        self.auto_verify
+    }
+
+    /// Returns whether the filter can be triggered from a web browser.
+    pub fn is_browsable(&self) -> bool {
+        self.categories
+            .iter()
+            .any(|category| category == "android.intent.category.BROWSABLE")
+    }
+
+    /// Returns whether the filter declares a deep link, that is, a browsable `VIEW` action with a
+    /// URI scheme.
+    pub fn is_deep_link(&self) -> bool {
+        self.is_browsable()
+            && self
+                .actions
+                .iter()
+                .any(|action| action == "android.intent.action.VIEW")
+            && self.schemes().next().is_some()
+    }
+
+    /// Gets the URI schemes accepted by the filter.
+    ///
+    /// All the `<data>` elements of a filter are merged, so every scheme is combined with every
+    /// host of the filter.
+    pub fn schemes(&self) -> impl Iterator<Item = &str> {
+        self.data
+            .iter()
+            .filter_map(|data| data.scheme.as_ref().map(String::as_str))
+    }
+
+    /// Gets the URI hosts accepted by the filter.
+    pub fn hosts(&self) -> impl Iterator<Item = &str> {
+        self.data
+            .iter()
+            .filter_map(|data| data.host.as_ref().map(String::as_str))
+    }
+
+    /// Gets the custom schemes of the filter, those other than `http` and `https`.
+    ///
+    /// Links with custom schemes cannot be verified, so any other application can register them.
+    pub fn custom_schemes(&self) -> impl Iterator<Item = &str> {
+        self.schemes().filter(|scheme| !is_web_scheme(scheme))
+    }
+
+    /// Returns whether the filter declares web app links, with `http` or `https` schemes.
+    pub fn is_app_link(&self) -> bool {
+        self.is_deep_link() && self.schemes().any(is_web_scheme)
+    }
+}
+
+/// `<data>` specification of an intent filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IntentData {
+    /// URI scheme, from the `android:scheme` attribute.
+    scheme: Option<String>,
+    /// URI host, from the `android:host` attribute.
+    host: Option<String>,
+}
+
+impl IntentData {
+    /// Gets the URI scheme of the data, if any.
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_ref().map(String::as_str)
+    }
+
+    /// Gets the URI host of the data, if any.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_ref().map(String::as_str)
+    }
+}
+
+/// Checks if the given URI scheme is a web scheme.
+fn is_web_scheme(scheme: &str) -> bool {
+    scheme == "http" || scheme == "https"
+}
+
+/// Permission declared by the application in a `<permission>` element.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomPermission {
+    /// Name of the permission.
+    name: String,
+    /// Value of the `android:protectionLevel` attribute, `normal` by default.
+    protection_level: String,
+    /// Line of the manifest where the permission is declared.
+    #[serde(skip)]
+    line: Option<usize>,
+}
+
+impl CustomPermission {
+    /// Gets the name of the permission.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the protection level of the permission, including its flags, such as
+    /// `signature|privileged`.
+    pub fn protection_level(&self) -> &str {
+        &self.protection_level
+    }
+
+    /// Gets the base protection level of the permission, without its flags.
+    pub fn base_protection_level(&self) -> &str {
+        self.protection_level
+            .split('|')
+            .next()
+            .unwrap_or(&self.protection_level)
+    }
+
+    /// Returns whether only applications signed with the same certificate can get the
+    /// permission.
+    pub fn is_signature(&self) -> bool {
+        self.protection_level
+            .split('|')
+            .any(|level| level == "signature" || level == "signatureOrSystem")
+    }
+
+    /// Gets the line of the manifest where the permission is declared.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Gets the known Android permission that the name of this permission imitates, if any.
+    ///
+    /// A permission imitates a known permission if it has its same name in another namespace, such
+    /// as `com.example.permission.READ_SMS`, or if its name is one edit away from it, such as
+    /// `android.permission.INTERNETT`.
+    pub fn imitated_permission(&self) -> Option<Permission> {
+        let catalog = PermissionCatalog::builtin();
+        if catalog.contains(&self.name) {
+            return None;
+        }
+        let local_name = self.name.rsplit('.').next().unwrap_or(&self.name);
+        let known = |candidate: &str| {
+            catalog
+                .get(format!("android.permission.{}", candidate))
+                .cloned()
+        };
+
+        known(local_name).or_else(|| {
+            let chars = local_name.chars().collect::<Vec<_>>();
+            let alphabet = ('A'..='Z').chain(Some('_')).collect::<Vec<_>>();
+            let mut candidates = Vec::new();
+            for i in 0..=chars.len() {
+                if i < chars.len() {
+                    let mut deletion = chars.clone();
+                    let _ = deletion.remove(i);
+                    candidates.push(deletion);
+                }
+                if i + 1 < chars.len() {
+                    let mut transposition = chars.clone();
+                    transposition.swap(i, i + 1);
+                    candidates.push(transposition);
+                }
+                for &c in &alphabet {
+                    if i < chars.len() && chars[i] != c {
+                        let mut substitution = chars.clone();
+                        substitution[i] = c;
+                        candidates.push(substitution);
+                    }
+                    let mut insertion = chars.clone();
+                    insertion.insert(i, c);
+                    candidates.push(insertion);
+                }
+            }
+            candidates
+                .into_iter()
+                .find_map(|candidate| known(&candidate.into_iter().collect::<String>()))
+        })
+    }
+}
+
+/// Kind of application component.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComponentKind {
+    /// `<activity>` component.
+    Activity,
+    /// `<activity-alias>` component.
+    ActivityAlias,
+    /// `<service>` component.
+    Service,
+    /// `<receiver>` component.
+    Receiver,
+    /// `<provider>` component.
+    Provider,
+}
+
+impl ComponentKind {
+    /// Gets the manifest tag of the component kind.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ComponentKind::Activity => "activity",
+            ComponentKind::ActivityAlias => "activity-alias",
+            ComponentKind::Service => "service",
+            ComponentKind::Receiver => "receiver",
+            ComponentKind::Provider => "provider",
+        }
+    }
+}
+
+impl Display for ComponentKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ComponentKind {
+    type Err = error::Kind;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "activity" => Ok(ComponentKind::Activity),
+            "activity-alias" => Ok(ComponentKind::ActivityAlias),
+            "service" => Ok(ComponentKind::Service),
+            "receiver" => Ok(ComponentKind::Receiver),
+            "provider" => Ok(ComponentKind::Provider),
+            _ => Err(error::Kind::Parse),
+        }
+    }
 }
 
+/// Install location of the application.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum InstallLocation {
+    /// The application can only be installed in the internal storage.
     InternalOnly,
+    /// The application can be installed in the external storage, but the system decides.
     Auto,
+    /// The application prefers to be installed in the external storage.
     PreferExternal,
 }
 
@@ -689,7 +1917,8 @@ impl FromStr for InstallLocation {
     }
 }
 
-fn get_line<S: AsRef<str>>(code: S, haystack: S) -> Result<usize, error::Kind> {
+/// Gets the first line of the given code that contains the given text.
+pub(crate) fn get_line<S: AsRef<str>>(code: S, haystack: S) -> Result<usize, error::Kind> {
     for (i, line) in code.as_ref().lines().enumerate() {
         if line.contains(haystack.as_ref()) {
             return Ok(i);
@@ -703,7 +1932,10 @@ fn get_line<S: AsRef<str>>(code: S, haystack: S) -> Result<usize, error::Kind> {
 mod tests {
     use std::str::FromStr;
 
-    use super::{get_line, InstallLocation, Permission, PermissionChecklist};
+    use super::{
+        get_line, ComponentKind, InstallLocation, Manifest, Permission, PermissionCatalog,
+        PermissionChecklist, PermissionCombination,
+    };
 
     #[test]
     fn it_get_line() {
@@ -732,6 +1964,314 @@ mod tests {
         assert!(get_line(code3, "non-matching").is_err());
     }
 
+    /// Checks the parsing of the launch modes and task affinities of the activities.
+    #[test]
+    fn it_task_affinity() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <application android:taskAffinity="com.example.tasks">
+        <activity android:name=".MainActivity" android:launchMode="singleTask"/>
+        <activity android:name=".LoginActivity" android:launchMode="3"
+            android:taskAffinity=""/>
+        <activity android:name=".OtherActivity"/>
+    </application>
+</manifest>"#,
+        );
+        let components = manifest.components().collect::<Vec<_>>();
+
+        assert_eq!(manifest.task_affinity(), Some("com.example.tasks"));
+        assert_eq!(components[0].launch_mode(), Some("singleTask"));
+        assert_eq!(components[0].task_affinity(), None);
+        assert_eq!(
+            manifest.task_affinity_of(components[0]),
+            "com.example.tasks"
+        );
+        assert_eq!(components[1].launch_mode(), Some("singleInstance"));
+        assert_eq!(manifest.task_affinity_of(components[1]), "");
+        assert_eq!(components[2].launch_mode(), None);
+
+        let manifest = Manifest::from_code(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app">
+    <application>
+        <activity android:name=".MainActivity"/>
+    </application>
+</manifest>"#,
+        );
+        let component = manifest.components().next().unwrap();
+        assert_eq!(manifest.task_affinity_of(component), "com.example.app");
+    }
+
+    /// Checks the parsing of the network, testing and storage attributes of the application.
+    #[test]
+    fn it_application_attributes() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <uses-sdk android:minSdkVersion="24" android:targetSdkVersion="30"/>
+    <application android:usesCleartextTraffic="true" android:testOnly="true"
+        android:allowClearUserData="false" android:directBootAware="true"
+        android:networkSecurityConfig="@xml/network_security_config"
+        android:fullBackupContent="@xml/backup_rules"
+        android:dataExtractionRules="@xml/data_extraction_rules"/>
+</manifest>"#,
+        );
+        assert!(manifest.uses_cleartext_traffic());
+        assert!(manifest.declares_cleartext_traffic());
+        assert_eq!(
+            manifest.network_security_config(),
+            Some("@xml/network_security_config")
+        );
+        assert!(manifest.is_test_only());
+        assert_eq!(manifest.full_backup_content(), Some("@xml/backup_rules"));
+        assert_eq!(
+            manifest.data_extraction_rules(),
+            Some("@xml/data_extraction_rules")
+        );
+        assert!(!manifest.allows_clear_user_data());
+        assert!(manifest.is_direct_boot_aware());
+
+        let modern = Manifest::from_code(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <uses-sdk android:minSdkVersion="21" android:targetSdkVersion="28"/>
+    <application/>
+</manifest>"#,
+        );
+        assert!(!modern.uses_cleartext_traffic());
+        assert!(!modern.is_test_only());
+        assert!(modern.allows_clear_user_data());
+        assert!(!modern.is_direct_boot_aware());
+
+        let legacy = Manifest::from_code(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <uses-sdk android:minSdkVersion="21" android:targetSdkVersion="27"/>
+    <application/>
+</manifest>"#,
+        );
+        assert!(legacy.uses_cleartext_traffic());
+        assert!(!legacy.declares_cleartext_traffic());
+    }
+
+    #[test]
+    fn it_from_code() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app" android:versionCode="3" android:versionName="1.2">
+    <uses-sdk android:minSdkVersion="21" android:targetSdkVersion="26"/>
+    <uses-permission android:name="android.permission.INTERNET"/>
+    <uses-permission android:name="com.example.CUSTOM"/>
+    <application android:label="Example" android:allowBackup="true">
+        <activity android:name=".MainActivity" android:exported="true"/>
+        <provider android:name=".DataProvider"/>
+    </application>
+</manifest>"#,
+        );
+
+        assert_eq!(manifest.package(), "com.example.app");
+        assert_eq!(manifest.version_number(), 3);
+        assert_eq!(manifest.version_str(), "1.2");
+        assert_eq!(manifest.min_sdk(), 21);
+        assert_eq!(manifest.target_sdk(), Some(26));
+        assert_eq!(manifest.label(), "Example");
+        assert!(manifest.allows_backup());
+        assert!(!manifest.is_debug());
+        assert!(manifest
+            .permission_checklist()
+            .needs_permission("android.permission.INTERNET"));
+        assert_eq!(
+            manifest.unknown_permissions().collect::<Vec<_>>(),
+            vec!["com.example.CUSTOM"]
+        );
+        assert_eq!(
+            manifest.requested_permissions().collect::<Vec<_>>(),
+            vec!["android.permission.INTERNET", "com.example.CUSTOM"]
+        );
+
+        let components = manifest.components().collect::<Vec<_>>();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].kind(), ComponentKind::Activity);
+        assert_eq!(components[0].name(), ".MainActivity");
+        assert_eq!(components[0].line(), Some(7));
+        assert!(components[0].is_exposed(manifest.min_sdk()));
+        assert_eq!(components[1].kind(), ComponentKind::Provider);
+        assert!(!components[1].is_exposed(manifest.min_sdk()));
+        assert!(components[1].is_exposed(16));
+        assert_eq!(
+            manifest.class_of(components[1]),
+            "com.example.app.DataProvider"
+        );
+        assert!(!manifest.is_exposed_provider("com.example.app.DataProvider"));
+        assert!(!manifest.is_exposed_provider("com.example.app.MainActivity"));
+    }
+
+    /// Checks the parsing of the intent filters of the components.
+    #[test]
+    fn it_intent_filters() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <application>
+        <activity android:name=".LinkActivity" android:exported="true">
+            <intent-filter>
+                <action android:name="android.intent.action.VIEW"/>
+                <category android:name="android.intent.category.DEFAULT"/>
+                <category android:name="android.intent.category.BROWSABLE"/>
+                <data android:scheme="example" android:host="open"/>
+            </intent-filter>
+            <intent-filter android:autoVerify="true">
+                <action android:name="android.intent.action.VIEW"/>
+                <category android:name="android.intent.category.BROWSABLE"/>
+                <data android:scheme="https"/>
+                <data android:host="example.com"/>
+            </intent-filter>
+        </activity>
+        <receiver android:name=".BootReceiver">
+            <intent-filter>
+                <action android:name="android.intent.action.BOOT_COMPLETED"/>
+            </intent-filter>
+        </receiver>
+    </application>
+    <queries>
+        <intent>
+            <action android:name="android.intent.action.SEND"/>
+        </intent>
+    </queries>
+</manifest>"#,
+        );
+
+        let components = manifest.components().collect::<Vec<_>>();
+        assert_eq!(components.len(), 2);
+        assert!(components[0].is_browsable());
+        assert!(!components[1].is_browsable());
+
+        let filters = components[0].intent_filters().collect::<Vec<_>>();
+        assert_eq!(filters.len(), 2);
+        assert!(filters[0].is_deep_link());
+        assert!(!filters[0].is_app_link());
+        assert!(!filters[0].auto_verify());
+        assert_eq!(
+            filters[0].custom_schemes().collect::<Vec<_>>(),
+            vec!["example"]
+        );
+        assert_eq!(filters[0].hosts().collect::<Vec<_>>(), vec!["open"]);
+        assert!(filters[1].is_app_link());
+        assert!(filters[1].auto_verify());
+        assert_eq!(filters[1].custom_schemes().count(), 0);
+        assert_eq!(filters[1].hosts().collect::<Vec<_>>(), vec!["example.com"]);
+
+        let receiver_filters = components[1].intent_filters().collect::<Vec<_>>();
+        assert_eq!(receiver_filters.len(), 1);
+        assert_eq!(
+            receiver_filters[0].actions().collect::<Vec<_>>(),
+            vec!["android.intent.action.BOOT_COMPLETED"]
+        );
+        assert!(!receiver_filters[0].is_deep_link());
+    }
+
+    /// Checks the parsing of the permissions declared by the application.
+    #[test]
+    fn it_custom_permissions() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <permission android:name="com.example.app.permission.SYNC"/>
+    <permission android:name="com.example.app.permission.ADMIN"
+        android:protectionLevel="signature|privileged"/>
+    <permission android:name="com.example.app.permission.DATA" android:protectionLevel="signature"/>
+    <permission android:name="com.example.READ_SMS" android:protectionLevel="dangerous"/>
+    <permission android:name="android.permission.INTERNETT"/>
+    <application>
+        <service android:name=".SyncService" android:permission="com.example.app.permission.SYNC"/>
+        <provider android:name=".DataProvider"
+            android:readPermission="com.example.app.permission.DATA"/>
+    </application>
+</manifest>"#,
+        );
+
+        let permissions = manifest.custom_permissions().collect::<Vec<_>>();
+        assert_eq!(permissions.len(), 5);
+        assert_eq!(permissions[0].protection_level(), "normal");
+        assert!(!permissions[0].is_signature());
+        assert_eq!(permissions[0].line(), Some(2));
+        assert_eq!(permissions[1].base_protection_level(), "signature");
+        assert!(permissions[1].is_signature());
+        assert!(!manifest.enforces_permission(permissions[1].name()));
+        assert!(manifest.enforces_permission(permissions[2].name()));
+        assert!(manifest.enforces_permission("com.example.app.permission.SYNC"));
+
+        assert_eq!(permissions[0].imitated_permission(), None);
+        assert_eq!(
+            permissions[3].imitated_permission(),
+            PermissionCatalog::builtin()
+                .get("android.permission.READ_SMS")
+                .cloned()
+        );
+        assert_eq!(
+            permissions[4].imitated_permission(),
+            PermissionCatalog::builtin()
+                .get("android.permission.INTERNET")
+                .cloned()
+        );
+
+        let service = manifest.components().next().unwrap();
+        assert_eq!(
+            service.permission(),
+            Some("com.example.app.permission.SYNC")
+        );
+    }
+
+    /// Checks the matching of permission combinations.
+    #[test]
+    fn it_permission_combination() {
+        let manifest = Manifest::from_code(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <uses-permission android:name="android.permission.RECEIVE_SMS"/>
+    <uses-permission android:name="android.permission.INTERNET"/>
+    <uses-permission android:name="android.permission.SYSTEM_ALERT_WINDOW"/>
+    <application>
+        <service android:name=".Overlay"
+            android:permission="android.permission.BIND_ACCESSIBILITY_SERVICE"/>
+    </application>
+</manifest>"#,
+        );
+
+        let combination: PermissionCombination = toml::from_str(
+            r#"name = "SMS Interception"
+               description = "Receives SMS and accesses the internet."
+               criticality = "high"
+               all_of = ["RECEIVE_SMS", "android.permission.INTERNET"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            combination.matches(&manifest),
+            Some(vec![
+                String::from("android.permission.RECEIVE_SMS"),
+                String::from("android.permission.INTERNET"),
+            ])
+        );
+
+        let combination: PermissionCombination = toml::from_str(
+            r#"name = "Overlay Attack"
+               description = "Draws overlays and controls the screen."
+               criticality = "critical"
+               all_of = ["SYSTEM_ALERT_WINDOW", "BIND_ACCESSIBILITY_SERVICE"]"#,
+        )
+        .unwrap();
+        assert!(combination.matches(&manifest).is_some());
+
+        let combination: PermissionCombination = toml::from_str(
+            r#"name = "Contact Exfiltration"
+               description = "Reads contacts and accesses the internet."
+               criticality = "medium"
+               all_of = ["READ_CONTACTS", "INTERNET"]"#,
+        )
+        .unwrap();
+        assert_eq!(combination.matches(&manifest), None);
+    }
+
     #[test]
     fn it_install_loc_from_str() {
         assert_eq!(
@@ -752,3397 +2292,236 @@ mod tests {
     #[test]
     fn it_permission_checklist() {
         let mut checklist = PermissionChecklist::default();
-        checklist.set_needs_permission(Permission::AndroidPermissionInternet);
+        checklist
+            .set_needs_permission(Permission::from_str("android.permission.INTERNET").unwrap());
 
-        assert!(checklist.needs_permission(Permission::AndroidPermissionInternet,));
-        assert!(!checklist.needs_permission(Permission::AndroidPermissionWriteExternalStorage,));
+        assert!(checklist.needs_permission("android.permission.INTERNET"));
+        assert!(!checklist.needs_permission("android.permission.WRITE_EXTERNAL_STORAGE"));
+        assert_eq!(checklist.permissions().count(), 1);
     }
 
+    /// Checks the built-in permission catalog and its extension.
     #[test]
-    fn it_permission() {
-        let internet = Permission::from_str("android.permission.INTERNET").unwrap();
-        let storage = Permission::from_str("android.permission.WRITE_EXTERNAL_STORAGE").unwrap();
-
-        assert_eq!(internet, Permission::AndroidPermissionInternet);
-        assert_eq!(storage, Permission::AndroidPermissionWriteExternalStorage);
-        assert_eq!(internet.as_str(), "android.permission.INTERNET");
+    fn it_permission_catalog() {
+        let builtin = PermissionCatalog::builtin();
+        assert!(builtin.contains("android.permission.INTERNET"));
+        assert!(builtin.contains("android.permission.POST_NOTIFICATIONS"));
+        assert!(!builtin.contains("com.example.CUSTOM"));
+
+        let mut catalog = builtin.clone();
+        catalog.extend(
+            toml::from_str(
+                r#"permissions = ["com.example.CUSTOM", "android.permission.INTERNET"]"#,
+            )
+            .unwrap(),
+        );
+        assert!(catalog.contains("com.example.CUSTOM"));
         assert_eq!(
-            storage.as_str(),
-            "android.permission.WRITE_EXTERNAL_STORAGE"
+            catalog.permissions().count(),
+            builtin.permissions().count() + 1
         );
+        assert!(toml::from_str::<PermissionCatalog>(r#"permissions = ["Razican"]"#).is_err());
+
+        let mut manifest = Manifest::from_code(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.app">
+    <uses-permission android:name="android.permission.INTERNET"/>
+    <uses-permission android:name="com.example.CUSTOM"/>
+</manifest>"#,
+        );
+        assert_eq!(
+            manifest.unknown_permissions().collect::<Vec<_>>(),
+            vec!["com.example.CUSTOM"]
+        );
+        manifest.classify_permissions(&catalog);
+        assert_eq!(manifest.unknown_permissions().count(), 0);
+    }
+
+    #[test]
+    fn it_permission() {
+        let internet = Permission::from_str("android.permission.INTERNET").unwrap();
+        let storage = Permission::from_str("android.permission.WRITE_EXTERNAL_STORAGE").unwrap();
+
+        let catalog = PermissionCatalog::builtin();
+        assert_eq!(catalog.get("android.permission.INTERNET"), Some(&internet));
+        assert_eq!(
+            catalog.get("android.permission.WRITE_EXTERNAL_STORAGE"),
+            Some(&storage)
+        );
+        assert_ne!(internet, storage);
+        assert_eq!(internet.as_str(), "android.permission.INTERNET");
+        assert_eq!(
+            storage.as_str(),
+            "android.permission.WRITE_EXTERNAL_STORAGE"
+        );
+        assert_eq!(internet.to_string(), "android.permission.INTERNET");
+
+        let new = Permission::from_str("com.example.permission.NEW").unwrap();
+        assert_eq!(new.as_str(), "com.example.permission.NEW");
+        assert_eq!(catalog.get(&new), None);
         assert!(Permission::from_str("Razican").is_err());
     }
 }
 
-#[derive(Debug)]
+/// Set of the permissions requested by an application.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PermissionChecklist {
-    android_permission_access_all_external_storage: bool,
-    android_permission_access_checkin_properties: bool,
-    android_permission_access_coarse_location: bool,
-    android_permission_access_fine_location: bool,
-    android_permission_access_location_extra_commands: bool,
-    android_permission_access_mock_location: bool,
-    android_permission_access_mtp: bool,
-    android_permission_access_network_state: bool,
-    android_permission_access_notification_policy: bool,
-    android_permission_access_wimax_state: bool,
-    android_permission_access_wifi_state: bool,
-    android_permission_account_manager: bool,
-    android_permission_asec_access: bool,
-    android_permission_asec_create: bool,
-    android_permission_asec_destroy: bool,
-    android_permission_asec_mount_unmount: bool,
-    android_permission_asec_rename: bool,
-    android_permission_authenticate_accounts: bool,
-    android_permission_battery_stats: bool,
-    android_permission_bind_accessibility_service: bool,
-    android_permission_bind_appwidget: bool,
-    android_permission_bind_call_service: bool,
-    android_permission_bind_carrier_messaging_service: bool,
-    android_permission_bind_carrier_services: bool,
-    android_permission_bind_chooser_target_service: bool,
-    android_permission_bind_device_admin: bool,
-    android_permission_bind_directory_search: bool,
-    android_permission_bind_dream_service: bool,
-    android_permission_bind_incall_service: bool,
-    android_permission_bind_input_method: bool,
-    android_permission_bind_keyguard_appwidget: bool,
-    android_permission_bind_midi_device_service: bool,
-    android_permission_bind_nfc_service: bool,
-    android_permission_bind_notification_listener_service: bool,
-    android_permission_bind_print_service: bool,
-    android_permission_bind_remoteviews: bool,
-    android_permission_bind_telecom_connection_service: bool,
-    android_permission_bind_text_service: bool,
-    android_permission_bind_tv_input: bool,
-    android_permission_bind_voice_interaction: bool,
-    android_permission_bind_vpn_service: bool,
-    android_permission_bind_wallpaper: bool,
-    android_permission_bluetooth: bool,
-    android_permission_bluetooth_admin: bool,
-    android_permission_bluetooth_privileged: bool,
-    android_permission_bluetooth_stack: bool,
-    android_permission_body_sensors: bool,
-    android_permission_broadcast_package_removed: bool,
-    android_permission_broadcast_sms: bool,
-    android_permission_broadcast_sticky: bool,
-    android_permission_broadcast_wap_push: bool,
-    android_permission_call_phone: bool,
-    android_permission_call_privileged: bool,
-    android_permission_camera: bool,
-    android_permission_camera_disable_transmit_led: bool,
-    android_permission_capture_audio_output: bool,
-    android_permission_capture_secure_video_output: bool,
-    android_permission_capture_video_output: bool,
-    android_permission_change_background_data_setting: bool,
-    android_permission_change_component_enabled_state: bool,
-    android_permission_change_configuration: bool,
-    android_permission_change_network_state: bool,
-    android_permission_change_wimax_state: bool,
-    android_permission_change_wifi_multicast_state: bool,
-    android_permission_change_wifi_state: bool,
-    android_permission_clear_app_cache: bool,
-    android_permission_connectivity_internal: bool,
-    android_permission_control_location_updates: bool,
-    android_permission_delete_cache_files: bool,
-    android_permission_delete_packages: bool,
-    android_permission_diagnostic: bool,
-    android_permission_disable_keyguard: bool,
-    android_permission_download_without_notification: bool,
-    android_permission_dump: bool,
-    android_permission_expand_status_bar: bool,
-    android_permission_factory_test: bool,
-    android_permission_flashlight: bool,
-    android_permission_force_stop_packages: bool,
-    android_permission_get_accounts: bool,
-    android_permission_get_accounts_privileged: bool,
-    android_permission_get_app_ops_stats: bool,
-    android_permission_get_detailed_tasks: bool,
-    android_permission_get_package_size: bool,
-    android_permission_get_tasks: bool,
-    android_permission_global_search: bool,
-    android_permission_global_search_control: bool,
-    android_permission_hardware_test: bool,
-    android_permission_install_location_provider: bool,
-    android_permission_install_packages: bool,
-    android_permission_interact_across_users: bool,
-    android_permission_interact_across_users_full: bool,
-    android_permission_internet: bool,
-    android_permission_kill_background_processes: bool,
-    android_permission_location_hardware: bool,
-    android_permission_loop_radio: bool,
-    android_permission_manage_accounts: bool,
-    android_permission_manage_activity_stacks: bool,
-    android_permission_manage_documents: bool,
-    android_permission_manage_usb: bool,
-    android_permission_manage_users: bool,
-    android_permission_master_clear: bool,
-    android_permission_media_content_control: bool,
-    android_permission_modify_appwidget_bind_permissions: bool,
-    android_permission_modify_audio_settings: bool,
-    android_permission_modify_phone_state: bool,
-    android_permission_mount_format_filesystems: bool,
-    android_permission_mount_unmount_filesystems: bool,
-    android_permission_net_admin: bool,
-    android_permission_net_tunneling: bool,
-    android_permission_nfc: bool,
-    android_permission_package_usage_stats: bool,
-    android_permission_persistent_activity: bool,
-    android_permission_process_outgoing_calls: bool,
-    android_permission_read_calendar: bool,
-    android_permission_read_call_log: bool,
-    android_permission_read_cell_broadcasts: bool,
-    android_permission_read_contacts: bool,
-    android_permission_read_dream_state: bool,
-    android_permission_read_external_storage: bool,
-    android_permission_read_frame_buffer: bool,
-    android_permission_read_input_state: bool,
-    android_permission_read_logs: bool,
-    android_permission_read_phone_state: bool,
-    android_permission_read_privileged_phone_state: bool,
-    android_permission_read_profile: bool,
-    android_permission_read_sms: bool,
-    android_permission_read_social_stream: bool,
-    android_permission_read_sync_settings: bool,
-    android_permission_read_sync_stats: bool,
-    android_permission_read_user_dictionary: bool,
-    android_permission_reboot: bool,
-    android_permission_receive_boot_completed: bool,
-    android_permission_receive_data_activity_change: bool,
-    android_permission_receive_emergency_broadcast: bool,
-    android_permission_receive_mms: bool,
-    android_permission_receive_sms: bool,
-    android_permission_receive_wap_push: bool,
-    android_permission_record_audio: bool,
-    android_permission_remote_audio_playback: bool,
-    android_permission_remove_tasks: bool,
-    android_permission_reorder_tasks: bool,
-    android_permission_request_ignore_battery_optimizations: bool,
-    android_permission_request_install_packages: bool,
-    android_permission_restart_packages: bool,
-    android_permission_retrieve_window_content: bool,
-    android_permission_send_respond_via_message: bool,
-    android_permission_send_sms: bool,
-    android_permission_set_always_finish: bool,
-    android_permission_set_animation_scale: bool,
-    android_permission_set_debug_app: bool,
-    android_permission_set_preferred_applications: bool,
-    android_permission_set_process_limit: bool,
-    android_permission_set_screen_compatibility: bool,
-    android_permission_set_time: bool,
-    android_permission_set_time_zone: bool,
-    android_permission_set_wallpaper: bool,
-    android_permission_set_wallpaper_component: bool,
-    android_permission_set_wallpaper_hints: bool,
-    android_permission_signal_persistent_processes: bool,
-    android_permission_start_any_activity: bool,
-    android_permission_status_bar: bool,
-    android_permission_subscribed_feeds_read: bool,
-    android_permission_system_alert_window: bool,
-    android_permission_subscribed_feeds_write: bool,
-    android_permission_transmit_ir: bool,
-    android_permission_update_device_stats: bool,
-    android_permission_use_credentials: bool,
-    android_permission_use_fingerprint: bool,
-    android_permission_use_sip: bool,
-    android_permission_vibrate: bool,
-    android_permission_wake_lock: bool,
-    android_permission_write_apn_settings: bool,
-    android_permission_write_calendar: bool,
-    android_permission_write_call_log: bool,
-    android_permission_write_contacts: bool,
-    android_permission_write_dream_state: bool,
-    android_permission_write_external_storage: bool,
-    android_permission_write_gservices: bool,
-    android_permission_write_media_storage: bool,
-    android_permission_write_profile: bool,
-    android_permission_write_secure_settings: bool,
-    android_permission_write_settings: bool,
-    android_permission_write_sms: bool,
-    android_permission_write_social_stream: bool,
-    android_permission_write_sync_settings: bool,
-    android_permission_write_user_dictionary: bool,
-    com_android_alarm_permission_set_alarm: bool,
-    com_android_browser_permission_read_history_bookmarks: bool,
-    com_android_browser_permission_write_history_bookmarks: bool,
-    com_android_email_permission_read_attachment: bool,
-    com_android_launcher_permission_install_shortcut: bool,
-    com_android_launcher_permission_preload_workspace: bool,
-    com_android_launcher_permission_read_settings: bool,
-    com_android_launcher_permission_uninstall_shortcut: bool,
-    com_android_launcher_permission_write_settings: bool,
-    com_android_vending_check_license: bool,
-    com_android_voicemail_permission_add_voicemail: bool,
-    com_android_voicemail_permission_read_voicemail: bool,
-    com_android_voicemail_permission_read_write_all_voicemail: bool,
-    com_android_voicemail_permission_write_voicemail: bool,
-    com_google_android_c2dm_permission_receive: bool,
-    com_google_android_c2dm_permission_send: bool,
-    com_google_android_gms_permission_activity_recognition: bool,
-    com_google_android_googleapps_permission_google_auth: bool,
-    com_google_android_googleapps_permission_google_auth_all_services: bool,
-    com_google_android_googleapps_permission_google_auth_other_services: bool,
-    com_google_android_googleapps_permission_google_auth_youtubeuser: bool,
-    com_google_android_googleapps_permission_google_auth_adsense: bool,
-    com_google_android_googleapps_permission_google_auth_adwords: bool,
-    com_google_android_googleapps_permission_google_auth_ah: bool,
-    com_google_android_googleapps_permission_google_auth_android: bool,
-    com_google_android_googleapps_permission_google_auth_androidsecure: bool,
-    com_google_android_googleapps_permission_google_auth_blogger: bool,
-    com_google_android_googleapps_permission_google_auth_cl: bool,
-    com_google_android_googleapps_permission_google_auth_cp: bool,
-    com_google_android_googleapps_permission_google_auth_dodgeball: bool,
-    com_google_android_googleapps_permission_google_auth_doraemon: bool,
-    com_google_android_googleapps_permission_google_auth_finance: bool,
-    com_google_android_googleapps_permission_google_auth_gbase: bool,
-    com_google_android_googleapps_permission_google_auth_geowiki: bool,
-    com_google_android_googleapps_permission_google_auth_goanna_mobile: bool,
-    com_google_android_googleapps_permission_google_auth_grandcentral: bool,
-    com_google_android_googleapps_permission_google_auth_groups2: bool,
-    com_google_android_googleapps_permission_google_auth_health: bool,
-    com_google_android_googleapps_permission_google_auth_ig: bool,
-    com_google_android_googleapps_permission_google_auth_jotspot: bool,
-    com_google_android_googleapps_permission_google_auth_knol: bool,
-    com_google_android_googleapps_permission_google_auth_lh2: bool,
-    com_google_android_googleapps_permission_google_auth_local: bool,
-    com_google_android_googleapps_permission_google_auth_mail: bool,
-    com_google_android_googleapps_permission_google_auth_mobile: bool,
-    com_google_android_googleapps_permission_google_auth_news: bool,
-    com_google_android_googleapps_permission_google_auth_notebook: bool,
-    com_google_android_googleapps_permission_google_auth_orkut: bool,
-    com_google_android_googleapps_permission_google_auth_panoramio: bool,
-    com_google_android_googleapps_permission_google_auth_print: bool,
-    com_google_android_googleapps_permission_google_auth_reader: bool,
-    com_google_android_googleapps_permission_google_auth_sierra: bool,
-    com_google_android_googleapps_permission_google_auth_sierraqa: bool,
-    com_google_android_googleapps_permission_google_auth_sierrasandbox: bool,
-    com_google_android_googleapps_permission_google_auth_sitemaps: bool,
-    com_google_android_googleapps_permission_google_auth_speech: bool,
-    com_google_android_googleapps_permission_google_auth_speechpersonalization: bool,
-    com_google_android_googleapps_permission_google_auth_talk: bool,
-    com_google_android_googleapps_permission_google_auth_wifi: bool,
-    com_google_android_googleapps_permission_google_auth_wise: bool,
-    com_google_android_googleapps_permission_google_auth_writely: bool,
-    com_google_android_googleapps_permission_google_auth_youtube: bool,
-    com_google_android_gtalkservice_permission_gtalk_service: bool,
-    com_google_android_gtalkservice_permission_send_heartbeat: bool,
-    com_google_android_permission_broadcast_data_message: bool,
-    com_google_android_providers_gsf_permission_read_gservices: bool,
-    com_google_android_providers_talk_permission_read_only: bool,
-    com_google_android_providers_talk_permission_write_only: bool,
-    com_google_android_xmpp_permission_broadcast: bool,
-    com_google_android_xmpp_permission_send_receive: bool,
-    com_google_android_xmpp_permission_use_xmpp_endpoint: bool,
-    com_google_android_xmpp_permission_xmpp_endpoint_broadcast: bool,
+    /// Requested permissions.
+    permissions: BTreeSet<Permission>,
 }
 
 impl PermissionChecklist {
-    pub fn needs_permission(&self, p: Permission) -> bool {
-        match p {
-            Permission::AndroidPermissionAccessAllExternalStorage => {
-                self.android_permission_access_all_external_storage
-            }
-            Permission::AndroidPermissionAccessCheckinProperties => {
-                self.android_permission_access_checkin_properties
-            }
-            Permission::AndroidPermissionAccessCoarseLocation => {
-                self.android_permission_access_coarse_location
-            }
-            Permission::AndroidPermissionAccessFineLocation => {
-                self.android_permission_access_fine_location
-            }
-            Permission::AndroidPermissionAccessLocationExtraCommands => {
-                self.android_permission_access_location_extra_commands
-            }
-            Permission::AndroidPermissionAccessMockLocation => {
-                self.android_permission_access_mock_location
-            }
-            Permission::AndroidPermissionAccessMtp => self.android_permission_access_mtp,
-            Permission::AndroidPermissionAccessNetworkState => {
-                self.android_permission_access_network_state
-            }
-            Permission::AndroidPermissionAccessNotificationPolicy => {
-                self.android_permission_access_notification_policy
-            }
-            Permission::AndroidPermissionAccessWimaxState => {
-                self.android_permission_access_wimax_state
-            }
-            Permission::AndroidPermissionAccessWifiState => {
-                self.android_permission_access_wifi_state
-            }
-            Permission::AndroidPermissionAccountManager => self.android_permission_account_manager,
-            Permission::AndroidPermissionAsecAccess => self.android_permission_asec_access,
-            Permission::AndroidPermissionAsecCreate => self.android_permission_asec_create,
-            Permission::AndroidPermissionAsecDestroy => self.android_permission_asec_destroy,
-            Permission::AndroidPermissionAsecMountUnmount => {
-                self.android_permission_asec_mount_unmount
-            }
-            Permission::AndroidPermissionAsecRename => self.android_permission_asec_rename,
-            Permission::AndroidPermissionAuthenticateAccounts => {
-                self.android_permission_authenticate_accounts
-            }
-            Permission::AndroidPermissionBatteryStats => self.android_permission_battery_stats,
-            Permission::AndroidPermissionBindAccessibilityService => {
-                self.android_permission_bind_accessibility_service
-            }
-            Permission::AndroidPermissionBindAppwidget => self.android_permission_bind_appwidget,
-            Permission::AndroidPermissionBindCallService => {
-                self.android_permission_bind_call_service
-            }
-            Permission::AndroidPermissionBindCarrierMessagingService => {
-                self.android_permission_bind_carrier_messaging_service
-            }
-            Permission::AndroidPermissionBindCarrierServices => {
-                self.android_permission_bind_carrier_services
-            }
-            Permission::AndroidPermissionBindChooserTargetService => {
-                self.android_permission_bind_chooser_target_service
-            }
-            Permission::AndroidPermissionBindDeviceAdmin => {
-                self.android_permission_bind_device_admin
-            }
-            Permission::AndroidPermissionBindDirectorySearch => {
-                self.android_permission_bind_directory_search
-            }
-            Permission::AndroidPermissionBindDreamService => {
-                self.android_permission_bind_dream_service
-            }
-            Permission::AndroidPermissionBindIncallService => {
-                self.android_permission_bind_incall_service
-            }
-            Permission::AndroidPermissionBindInputMethod => {
-                self.android_permission_bind_input_method
-            }
-            Permission::AndroidPermissionBindKeyguardAppwidget => {
-                self.android_permission_bind_keyguard_appwidget
-            }
-            Permission::AndroidPermissionBindMidiDeviceService => {
-                self.android_permission_bind_midi_device_service
-            }
-            Permission::AndroidPermissionBindNfcService => self.android_permission_bind_nfc_service,
-            Permission::AndroidPermissionBindNotificationListenerService => {
-                self.android_permission_bind_notification_listener_service
-            }
-            Permission::AndroidPermissionBindPrintService => {
-                self.android_permission_bind_print_service
-            }
-            Permission::AndroidPermissionBindRemoteviews => {
-                self.android_permission_bind_remoteviews
-            }
-            Permission::AndroidPermissionBindTelecomConnectionService => {
-                self.android_permission_bind_telecom_connection_service
-            }
-            Permission::AndroidPermissionBindTextService => {
-                self.android_permission_bind_text_service
-            }
-            Permission::AndroidPermissionBindTvInput => self.android_permission_bind_tv_input,
-            Permission::AndroidPermissionBindVoiceInteraction => {
-                self.android_permission_bind_voice_interaction
-            }
-            Permission::AndroidPermissionBindVpnService => self.android_permission_bind_vpn_service,
-            Permission::AndroidPermissionBindWallpaper => self.android_permission_bind_wallpaper,
-            Permission::AndroidPermissionBluetooth => self.android_permission_bluetooth,
-            Permission::AndroidPermissionBluetoothAdmin => self.android_permission_bluetooth_admin,
-            Permission::AndroidPermissionBluetoothPrivileged => {
-                self.android_permission_bluetooth_privileged
-            }
-            Permission::AndroidPermissionBluetoothStack => self.android_permission_bluetooth_stack,
-            Permission::AndroidPermissionBodySensors => self.android_permission_body_sensors,
-            Permission::AndroidPermissionBroadcastPackageRemoved => {
-                self.android_permission_broadcast_package_removed
-            }
-            Permission::AndroidPermissionBroadcastSms => self.android_permission_broadcast_sms,
-            Permission::AndroidPermissionBroadcastSticky => {
-                self.android_permission_broadcast_sticky
-            }
-            Permission::AndroidPermissionBroadcastWapPush => {
-                self.android_permission_broadcast_wap_push
-            }
-            Permission::AndroidPermissionCallPhone => self.android_permission_call_phone,
-            Permission::AndroidPermissionCallPrivileged => self.android_permission_call_privileged,
-            Permission::AndroidPermissionCamera => self.android_permission_camera,
-            Permission::AndroidPermissionCameraDisableTransmitLed => {
-                self.android_permission_camera_disable_transmit_led
-            }
-            Permission::AndroidPermissionCaptureAudioOutput => {
-                self.android_permission_capture_audio_output
-            }
-            Permission::AndroidPermissionCaptureSecureVideoOutput => {
-                self.android_permission_capture_secure_video_output
-            }
-            Permission::AndroidPermissionCaptureVideoOutput => {
-                self.android_permission_capture_video_output
-            }
-            Permission::AndroidPermissionChangeBackgroundDataSetting => {
-                self.android_permission_change_background_data_setting
-            }
-            Permission::AndroidPermissionChangeComponentEnabledState => {
-                self.android_permission_change_component_enabled_state
-            }
-            Permission::AndroidPermissionChangeConfiguration => {
-                self.android_permission_change_configuration
-            }
-            Permission::AndroidPermissionChangeNetworkState => {
-                self.android_permission_change_network_state
-            }
-            Permission::AndroidPermissionChangeWimaxState => {
-                self.android_permission_change_wimax_state
-            }
-            Permission::AndroidPermissionChangeWifiMulticastState => {
-                self.android_permission_change_wifi_multicast_state
-            }
-            Permission::AndroidPermissionChangeWifiState => {
-                self.android_permission_change_wifi_state
-            }
-            Permission::AndroidPermissionClearAppCache => self.android_permission_clear_app_cache,
-            Permission::AndroidPermissionConnectivityInternal => {
-                self.android_permission_connectivity_internal
-            }
-            Permission::AndroidPermissionControlLocationUpdates => {
-                self.android_permission_control_location_updates
-            }
-            Permission::AndroidPermissionDeleteCacheFiles => {
-                self.android_permission_delete_cache_files
-            }
-            Permission::AndroidPermissionDeletePackages => self.android_permission_delete_packages,
-            Permission::AndroidPermissionDiagnostic => self.android_permission_diagnostic,
-            Permission::AndroidPermissionDisableKeyguard => {
-                self.android_permission_disable_keyguard
-            }
-            Permission::AndroidPermissionDownloadWithoutNotification => {
-                self.android_permission_download_without_notification
-            }
-            Permission::AndroidPermissionDump => self.android_permission_dump,
-            Permission::AndroidPermissionExpandStatusBar => {
-                self.android_permission_expand_status_bar
-            }
-            Permission::AndroidPermissionFactoryTest => self.android_permission_factory_test,
-            Permission::AndroidPermissionFlashlight => self.android_permission_flashlight,
-            Permission::AndroidPermissionForceStopPackages => {
-                self.android_permission_force_stop_packages
-            }
-            Permission::AndroidPermissionGetAccounts => self.android_permission_get_accounts,
-            Permission::AndroidPermissionGetAccountsPrivileged => {
-                self.android_permission_get_accounts_privileged
-            }
-            Permission::AndroidPermissionGetAppOpsStats => {
-                self.android_permission_get_app_ops_stats
-            }
-            Permission::AndroidPermissionGetDetailedTasks => {
-                self.android_permission_get_detailed_tasks
-            }
-            Permission::AndroidPermissionGetPackageSize => self.android_permission_get_package_size,
-            Permission::AndroidPermissionGetTasks => self.android_permission_get_tasks,
-            Permission::AndroidPermissionGlobalSearch => self.android_permission_global_search,
-            Permission::AndroidPermissionGlobalSearchControl => {
-                self.android_permission_global_search_control
-            }
-            Permission::AndroidPermissionHardwareTest => self.android_permission_hardware_test,
-            Permission::AndroidPermissionInstallLocationProvider => {
-                self.android_permission_install_location_provider
-            }
-            Permission::AndroidPermissionInstallPackages => {
-                self.android_permission_install_packages
-            }
-            Permission::AndroidPermissionInteractAcrossUsers => {
-                self.android_permission_interact_across_users
-            }
-            Permission::AndroidPermissionInteractAcrossUsersFull => {
-                self.android_permission_interact_across_users_full
-            }
-            Permission::AndroidPermissionInternet => self.android_permission_internet,
-            Permission::AndroidPermissionKillBackgroundProcesses => {
-                self.android_permission_kill_background_processes
-            }
-            Permission::AndroidPermissionLocationHardware => {
-                self.android_permission_location_hardware
-            }
-            Permission::AndroidPermissionLoopRadio => self.android_permission_loop_radio,
-            Permission::AndroidPermissionManageAccounts => self.android_permission_manage_accounts,
-            Permission::AndroidPermissionManageActivityStacks => {
-                self.android_permission_manage_activity_stacks
-            }
-            Permission::AndroidPermissionManageDocuments => {
-                self.android_permission_manage_documents
-            }
-            Permission::AndroidPermissionManageUsb => self.android_permission_manage_usb,
-            Permission::AndroidPermissionManageUsers => self.android_permission_manage_users,
-            Permission::AndroidPermissionMasterClear => self.android_permission_master_clear,
-            Permission::AndroidPermissionMediaContentControl => {
-                self.android_permission_media_content_control
-            }
-            Permission::AndroidPermissionModifyAppwidgetBindPermissions => {
-                self.android_permission_modify_appwidget_bind_permissions
-            }
-            Permission::AndroidPermissionModifyAudioSettings => {
-                self.android_permission_modify_audio_settings
-            }
-            Permission::AndroidPermissionModifyPhoneState => {
-                self.android_permission_modify_phone_state
-            }
-            Permission::AndroidPermissionMountFormatFilesystems => {
-                self.android_permission_mount_format_filesystems
-            }
-            Permission::AndroidPermissionMountUnmountFilesystems => {
-                self.android_permission_mount_unmount_filesystems
-            }
-            Permission::AndroidPermissionNetAdmin => self.android_permission_net_admin,
-            Permission::AndroidPermissionNetTunneling => self.android_permission_net_tunneling,
-            Permission::AndroidPermissionNfc => self.android_permission_nfc,
-            Permission::AndroidPermissionPackageUsageStats => {
-                self.android_permission_package_usage_stats
-            }
-            Permission::AndroidPermissionPersistentActivity => {
-                self.android_permission_persistent_activity
-            }
-            Permission::AndroidPermissionProcessOutgoingCalls => {
-                self.android_permission_process_outgoing_calls
-            }
-            Permission::AndroidPermissionReadCalendar => self.android_permission_read_calendar,
-            Permission::AndroidPermissionReadCallLog => self.android_permission_read_call_log,
-            Permission::AndroidPermissionReadCellBroadcasts => {
-                self.android_permission_read_cell_broadcasts
-            }
-            Permission::AndroidPermissionReadContacts => self.android_permission_read_contacts,
-            Permission::AndroidPermissionReadDreamState => self.android_permission_read_dream_state,
-            Permission::AndroidPermissionReadExternalStorage => {
-                self.android_permission_read_external_storage
-            }
-            Permission::AndroidPermissionReadFrameBuffer => {
-                self.android_permission_read_frame_buffer
-            }
-            Permission::AndroidPermissionReadInputState => self.android_permission_read_input_state,
-            Permission::AndroidPermissionReadLogs => self.android_permission_read_logs,
-            Permission::AndroidPermissionReadPhoneState => self.android_permission_read_phone_state,
-            Permission::AndroidPermissionReadPrivilegedPhoneState => {
-                self.android_permission_read_privileged_phone_state
-            }
-            Permission::AndroidPermissionReadProfile => self.android_permission_read_profile,
-            Permission::AndroidPermissionReadSms => self.android_permission_read_sms,
-            Permission::AndroidPermissionReadSocialStream => {
-                self.android_permission_read_social_stream
-            }
-            Permission::AndroidPermissionReadSyncSettings => {
-                self.android_permission_read_sync_settings
-            }
-            Permission::AndroidPermissionReadSyncStats => self.android_permission_read_sync_stats,
-            Permission::AndroidPermissionReadUserDictionary => {
-                self.android_permission_read_user_dictionary
-            }
-            Permission::AndroidPermissionReboot => self.android_permission_reboot,
-            Permission::AndroidPermissionReceiveBootCompleted => {
-                self.android_permission_receive_boot_completed
-            }
-            Permission::AndroidPermissionReceiveDataActivityChange => {
-                self.android_permission_receive_data_activity_change
-            }
-            Permission::AndroidPermissionReceiveEmergencyBroadcast => {
-                self.android_permission_receive_emergency_broadcast
-            }
-            Permission::AndroidPermissionReceiveMms => self.android_permission_receive_mms,
-            Permission::AndroidPermissionReceiveSms => self.android_permission_receive_sms,
-            Permission::AndroidPermissionReceiveWapPush => self.android_permission_receive_wap_push,
-            Permission::AndroidPermissionRecordAudio => self.android_permission_record_audio,
-            Permission::AndroidPermissionRemoteAudioPlayback => {
-                self.android_permission_remote_audio_playback
-            }
-            Permission::AndroidPermissionRemoveTasks => self.android_permission_remove_tasks,
-            Permission::AndroidPermissionReorderTasks => self.android_permission_reorder_tasks,
-            Permission::AndroidPermissionRequestIgnoreBatteryOptimizations => {
-                self.android_permission_request_ignore_battery_optimizations
-            }
-            Permission::AndroidPermissionRequestInstallPackages => {
-                self.android_permission_request_install_packages
-            }
-            Permission::AndroidPermissionRestartPackages => {
-                self.android_permission_restart_packages
-            }
-            Permission::AndroidPermissionRetrieveWindowContent => {
-                self.android_permission_retrieve_window_content
-            }
-            Permission::AndroidPermissionSendRespondViaMessage => {
-                self.android_permission_send_respond_via_message
-            }
-            Permission::AndroidPermissionSendSms => self.android_permission_send_sms,
-            Permission::AndroidPermissionSetAlwaysFinish => {
-                self.android_permission_set_always_finish
-            }
-            Permission::AndroidPermissionSetAnimationScale => {
-                self.android_permission_set_animation_scale
-            }
-            Permission::AndroidPermissionSetDebugApp => self.android_permission_set_debug_app,
-            Permission::AndroidPermissionSetPreferredApplications => {
-                self.android_permission_set_preferred_applications
-            }
-            Permission::AndroidPermissionSetProcessLimit => {
-                self.android_permission_set_process_limit
-            }
-            Permission::AndroidPermissionSetScreenCompatibility => {
-                self.android_permission_set_screen_compatibility
-            }
-            Permission::AndroidPermissionSetTime => self.android_permission_set_time,
-            Permission::AndroidPermissionSetTimeZone => self.android_permission_set_time_zone,
-            Permission::AndroidPermissionSetWallpaper => self.android_permission_set_wallpaper,
-            Permission::AndroidPermissionSetWallpaperComponent => {
-                self.android_permission_set_wallpaper_component
-            }
-            Permission::AndroidPermissionSetWallpaperHints => {
-                self.android_permission_set_wallpaper_hints
-            }
-            Permission::AndroidPermissionSignalPersistentProcesses => {
-                self.android_permission_signal_persistent_processes
-            }
-            Permission::AndroidPermissionStartAnyActivity => {
-                self.android_permission_start_any_activity
-            }
-            Permission::AndroidPermissionStatusBar => self.android_permission_status_bar,
-            Permission::AndroidPermissionSubscribedFeedsRead => {
-                self.android_permission_subscribed_feeds_read
-            }
-            Permission::AndroidPermissionSystemAlertWindow => {
-                self.android_permission_system_alert_window
-            }
-            Permission::AndroidPermissionSubscribedFeedsWrite => {
-                self.android_permission_subscribed_feeds_write
-            }
-            Permission::AndroidPermissionTransmitIr => self.android_permission_transmit_ir,
-            Permission::AndroidPermissionUpdateDeviceStats => {
-                self.android_permission_update_device_stats
-            }
-            Permission::AndroidPermissionUseCredentials => self.android_permission_use_credentials,
-            Permission::AndroidPermissionUseFingerprint => self.android_permission_use_fingerprint,
-            Permission::AndroidPermissionUseSip => self.android_permission_use_sip,
-            Permission::AndroidPermissionVibrate => self.android_permission_vibrate,
-            Permission::AndroidPermissionWakeLock => self.android_permission_wake_lock,
-            Permission::AndroidPermissionWriteApnSettings => {
-                self.android_permission_write_apn_settings
-            }
-            Permission::AndroidPermissionWriteCalendar => self.android_permission_write_calendar,
-            Permission::AndroidPermissionWriteCallLog => self.android_permission_write_call_log,
-            Permission::AndroidPermissionWriteContacts => self.android_permission_write_contacts,
-            Permission::AndroidPermissionWriteDreamState => {
-                self.android_permission_write_dream_state
-            }
-            Permission::AndroidPermissionWriteExternalStorage => {
-                self.android_permission_write_external_storage
-            }
-            Permission::AndroidPermissionWriteGservices => self.android_permission_write_gservices,
-            Permission::AndroidPermissionWriteMediaStorage => {
-                self.android_permission_write_media_storage
-            }
-            Permission::AndroidPermissionWriteProfile => self.android_permission_write_profile,
-            Permission::AndroidPermissionWriteSecureSettings => {
-                self.android_permission_write_secure_settings
-            }
-            Permission::AndroidPermissionWriteSettings => self.android_permission_write_settings,
-            Permission::AndroidPermissionWriteSms => self.android_permission_write_sms,
-            Permission::AndroidPermissionWriteSocialStream => {
-                self.android_permission_write_social_stream
-            }
-            Permission::AndroidPermissionWriteSyncSettings => {
-                self.android_permission_write_sync_settings
-            }
-            Permission::AndroidPermissionWriteUserDictionary => {
-                self.android_permission_write_user_dictionary
-            }
-            Permission::ComAndroidAlarmPermissionSetAlarm => {
-                self.com_android_alarm_permission_set_alarm
-            }
-            Permission::ComAndroidBrowserPermissionReadHistoryBookmarks => {
-                self.com_android_browser_permission_read_history_bookmarks
-            }
-            Permission::ComAndroidBrowserPermissionWriteHistoryBookmarks => {
-                self.com_android_browser_permission_write_history_bookmarks
-            }
-            Permission::ComAndroidEmailPermissionReadAttachment => {
-                self.com_android_email_permission_read_attachment
-            }
-            Permission::ComAndroidLauncherPermissionInstallShortcut => {
-                self.com_android_launcher_permission_install_shortcut
-            }
-            Permission::ComAndroidLauncherPermissionPreloadWorkspace => {
-                self.com_android_launcher_permission_preload_workspace
-            }
-            Permission::ComAndroidLauncherPermissionReadSettings => {
-                self.com_android_launcher_permission_read_settings
-            }
-            Permission::ComAndroidLauncherPermissionUninstallShortcut => {
-                self.com_android_launcher_permission_uninstall_shortcut
-            }
-            Permission::ComAndroidLauncherPermissionWriteSettings => {
-                self.com_android_launcher_permission_write_settings
-            }
-            Permission::ComAndroidVendingCheckLicense => self.com_android_vending_check_license,
-            Permission::ComAndroidVoicemailPermissionAddVoicemail => {
-                self.com_android_voicemail_permission_add_voicemail
-            }
-            Permission::ComAndroidVoicemailPermissionReadVoicemail => {
-                self.com_android_voicemail_permission_read_voicemail
-            }
-            Permission::ComAndroidVoicemailPermissionReadWriteAllVoicemail => {
-                self.com_android_voicemail_permission_read_write_all_voicemail
-            }
-            Permission::ComAndroidVoicemailPermissionWriteVoicemail => {
-                self.com_android_voicemail_permission_write_voicemail
-            }
-            Permission::ComGoogleAndroidC2dmPermissionReceive => {
-                self.com_google_android_c2dm_permission_receive
-            }
-            Permission::ComGoogleAndroidC2dmPermissionSend => {
-                self.com_google_android_c2dm_permission_send
-            }
-            Permission::ComGoogleAndroidGmsPermissionActivityRecognition => {
-                self.com_google_android_gms_permission_activity_recognition
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuth => {
-                self.com_google_android_googleapps_permission_google_auth
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAllServices => {
-                self.com_google_android_googleapps_permission_google_auth_all_services
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOtherServices => {
-                self.com_google_android_googleapps_permission_google_auth_other_services
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutubeuser => {
-                self.com_google_android_googleapps_permission_google_auth_youtubeuser
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdsense => {
-                self.com_google_android_googleapps_permission_google_auth_adsense
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdwords => {
-                self.com_google_android_googleapps_permission_google_auth_adwords
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAh => {
-                self.com_google_android_googleapps_permission_google_auth_ah
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroid => {
-                self.com_google_android_googleapps_permission_google_auth_android
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroidsecure => {
-                self.com_google_android_googleapps_permission_google_auth_androidsecure
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthBlogger => {
-                self.com_google_android_googleapps_permission_google_auth_blogger
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCl => {
-                self.com_google_android_googleapps_permission_google_auth_cl
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCp => {
-                self.com_google_android_googleapps_permission_google_auth_cp
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDodgeball => {
-                self.com_google_android_googleapps_permission_google_auth_dodgeball
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDoraemon => {
-                self.com_google_android_googleapps_permission_google_auth_doraemon
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthFinance => {
-                self.com_google_android_googleapps_permission_google_auth_finance
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGbase => {
-                self.com_google_android_googleapps_permission_google_auth_gbase
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGeowiki => {
-                self.com_google_android_googleapps_permission_google_auth_geowiki
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGoannaMobile => {
-                self.com_google_android_googleapps_permission_google_auth_goanna_mobile
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGrandcentral => {
-                self.com_google_android_googleapps_permission_google_auth_grandcentral
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGroups2 => {
-                self.com_google_android_googleapps_permission_google_auth_groups2
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthHealth => {
-                self.com_google_android_googleapps_permission_google_auth_health
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthIg => {
-                self.com_google_android_googleapps_permission_google_auth_ig
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthJotspot => {
-                self.com_google_android_googleapps_permission_google_auth_jotspot
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthKnol => {
-                self.com_google_android_googleapps_permission_google_auth_knol
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLh2 => {
-                self.com_google_android_googleapps_permission_google_auth_lh2
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLocal => {
-                self.com_google_android_googleapps_permission_google_auth_local
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMail => {
-                self.com_google_android_googleapps_permission_google_auth_mail
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMobile => {
-                self.com_google_android_googleapps_permission_google_auth_mobile
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNews => {
-                self.com_google_android_googleapps_permission_google_auth_news
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNotebook => {
-                self.com_google_android_googleapps_permission_google_auth_notebook
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOrkut => {
-                self.com_google_android_googleapps_permission_google_auth_orkut
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPanoramio => {
-                self.com_google_android_googleapps_permission_google_auth_panoramio
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPrint => {
-                self.com_google_android_googleapps_permission_google_auth_print
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthReader => {
-                self.com_google_android_googleapps_permission_google_auth_reader
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierra => {
-                self.com_google_android_googleapps_permission_google_auth_sierra
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierraqa => {
-                self.com_google_android_googleapps_permission_google_auth_sierraqa
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierrasandbox => {
-                self.com_google_android_googleapps_permission_google_auth_sierrasandbox
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSitemaps => {
-                self.com_google_android_googleapps_permission_google_auth_sitemaps
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeech => {
-                self.com_google_android_googleapps_permission_google_auth_speech
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeechpersonalization => {
-                self.com_google_android_googleapps_permission_google_auth_speechpersonalization
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthTalk => {
-                self.com_google_android_googleapps_permission_google_auth_talk
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWifi => {
-                self.com_google_android_googleapps_permission_google_auth_wifi
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWise => {
-                self.com_google_android_googleapps_permission_google_auth_wise
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWritely => {
-                self.com_google_android_googleapps_permission_google_auth_writely
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutube => {
-                self.com_google_android_googleapps_permission_google_auth_youtube
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionGtalkService => {
-                self.com_google_android_gtalkservice_permission_gtalk_service
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionSendHeartbeat => {
-                self.com_google_android_gtalkservice_permission_send_heartbeat
-            }
-            Permission::ComGoogleAndroidPermissionBroadcastDataMessage => {
-                self.com_google_android_permission_broadcast_data_message
-            }
-            Permission::ComGoogleAndroidProvidersGsfPermissionReadGservices => {
-                self.com_google_android_providers_gsf_permission_read_gservices
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionReadOnly => {
-                self.com_google_android_providers_talk_permission_read_only
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionWriteOnly => {
-                self.com_google_android_providers_talk_permission_write_only
-            }
-            Permission::ComGoogleAndroidXmppPermissionBroadcast => {
-                self.com_google_android_xmpp_permission_broadcast
-            }
-            Permission::ComGoogleAndroidXmppPermissionSendReceive => {
-                self.com_google_android_xmpp_permission_send_receive
-            }
-            Permission::ComGoogleAndroidXmppPermissionUseXmppEndpoint => {
-                self.com_google_android_xmpp_permission_use_xmpp_endpoint
-            }
-            Permission::ComGoogleAndroidXmppPermissionXmppEndpointBroadcast => {
-                self.com_google_android_xmpp_permission_xmpp_endpoint_broadcast
-            }
+    /// Returns whether the application requests the given permission.
+    pub fn needs_permission<S: AsRef<str>>(&self, permission: S) -> bool {
+        self.permissions.contains(permission.as_ref())
+    }
+
+    /// Sets the given permission as requested by the application.
+    pub fn set_needs_permission(&mut self, permission: Permission) {
+        let _ = self.permissions.insert(permission);
+    }
+
+    /// Returns the requested permissions, in alphabetical order.
+    pub fn permissions(&self) -> btree_set::Iter<Permission> {
+        self.permissions.iter()
+    }
+}
+
+lazy_static! {
+    /// Catalog of the permissions known by default, listed in the `permissions.toml` file.
+    static ref BUILTIN_CATALOG: PermissionCatalog =
+        toml::from_str(include_str!("../../permissions.toml"))
+            .expect("the built-in permission catalog is invalid");
+}
+
+/// Catalog of the known permissions.
+///
+/// Requested permissions that are not in the catalog are reported as unknown permissions. The
+/// built-in catalog, in the `permissions.toml` file, lists the Android and Google permissions, and
+/// it can be extended with the `permission_catalog` option of the configuration, a TOML file with
+/// the same format:
+///
+/// ```toml
+/// permissions = [
+///     "android.permission.NEW_PERMISSION",
+///     "com.vendor.permission.VENDOR_PERMISSION",
+/// ]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PermissionCatalog {
+    /// Known permissions.
+    permissions: BTreeSet<Permission>,
+}
+
+impl PermissionCatalog {
+    /// Returns the built-in catalog.
+    pub fn builtin() -> &'static Self {
+        &BUILTIN_CATALOG
+    }
+
+    /// Loads the catalog in the given TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).context(format_err!(
+            "could not read the permission catalog `{}`",
+            path.display()
+        ))?;
+
+        Ok(toml::from_str(&content).context(format_err!(
+            "invalid permission catalog `{}`",
+            path.display()
+        ))?)
+    }
+
+    /// Adds the permissions of the given catalog to this catalog.
+    pub fn extend(&mut self, other: Self) {
+        self.permissions.extend(other.permissions);
+    }
+
+    /// Checks if the given permission is known.
+    pub fn contains<S: AsRef<str>>(&self, permission: S) -> bool {
+        self.permissions.contains(permission.as_ref())
+    }
+
+    /// Gets the given permission, if it is known.
+    pub fn get<S: AsRef<str>>(&self, permission: S) -> Option<&Permission> {
+        self.permissions.get(permission.as_ref())
+    }
+
+    /// Returns the known permissions, in alphabetical order.
+    pub fn permissions(&self) -> btree_set::Iter<Permission> {
+        self.permissions.iter()
+    }
+}
+
+/// Android permission, such as `android.permission.INTERNET`.
+///
+/// Any well-formed permission name is a permission, even if it is not in the
+/// [`PermissionCatalog`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Permission {
+    /// Full name of the permission.
+    name: String,
+}
+
+impl<'de> Deserialize<'de> for Permission {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use failure::Fail;
+        use serde::de::Error;
+
+        let deser_result_str: String = serde::Deserialize::deserialize(de)?;
+
+        match Self::from_str(&deser_result_str) {
+            Ok(permission) => Ok(permission),
+            Err(e) => Err(Error::custom(
+                e.context(format!("invalid permission `{}`", deser_result_str)),
+            )),
         }
     }
+}
 
-    fn set_needs_permission(&mut self, p: Permission) {
-        match p {
-            Permission::AndroidPermissionAccessAllExternalStorage => {
-                self.android_permission_access_all_external_storage = true
-            }
-            Permission::AndroidPermissionAccessCheckinProperties => {
-                self.android_permission_access_checkin_properties = true
-            }
-            Permission::AndroidPermissionAccessCoarseLocation => {
-                self.android_permission_access_coarse_location = true
-            }
-            Permission::AndroidPermissionAccessFineLocation => {
-                self.android_permission_access_fine_location = true
-            }
-            Permission::AndroidPermissionAccessLocationExtraCommands => {
-                self.android_permission_access_location_extra_commands = true
-            }
-            Permission::AndroidPermissionAccessMockLocation => {
-                self.android_permission_access_mock_location = true
-            }
-            Permission::AndroidPermissionAccessMtp => self.android_permission_access_mtp = true,
-            Permission::AndroidPermissionAccessNetworkState => {
-                self.android_permission_access_network_state = true
-            }
-            Permission::AndroidPermissionAccessNotificationPolicy => {
-                self.android_permission_access_notification_policy = true
-            }
-            Permission::AndroidPermissionAccessWimaxState => {
-                self.android_permission_access_wimax_state = true
-            }
-            Permission::AndroidPermissionAccessWifiState => {
-                self.android_permission_access_wifi_state = true
-            }
-            Permission::AndroidPermissionAccountManager => {
-                self.android_permission_account_manager = true
-            }
-            Permission::AndroidPermissionAsecAccess => self.android_permission_asec_access = true,
-            Permission::AndroidPermissionAsecCreate => self.android_permission_asec_create = true,
-            Permission::AndroidPermissionAsecDestroy => self.android_permission_asec_destroy = true,
-            Permission::AndroidPermissionAsecMountUnmount => {
-                self.android_permission_asec_mount_unmount = true
-            }
-            Permission::AndroidPermissionAsecRename => self.android_permission_asec_rename = true,
-            Permission::AndroidPermissionAuthenticateAccounts => {
-                self.android_permission_authenticate_accounts = true
-            }
-            Permission::AndroidPermissionBatteryStats => {
-                self.android_permission_battery_stats = true
-            }
-            Permission::AndroidPermissionBindAccessibilityService => {
-                self.android_permission_bind_accessibility_service = true
-            }
-            Permission::AndroidPermissionBindAppwidget => {
-                self.android_permission_bind_appwidget = true
-            }
-            Permission::AndroidPermissionBindCallService => {
-                self.android_permission_bind_call_service = true
-            }
-            Permission::AndroidPermissionBindCarrierMessagingService => {
-                self.android_permission_bind_carrier_messaging_service = true
-            }
-            Permission::AndroidPermissionBindCarrierServices => {
-                self.android_permission_bind_carrier_services = true
-            }
-            Permission::AndroidPermissionBindChooserTargetService => {
-                self.android_permission_bind_chooser_target_service = true
-            }
-            Permission::AndroidPermissionBindDeviceAdmin => {
-                self.android_permission_bind_device_admin = true
-            }
-            Permission::AndroidPermissionBindDirectorySearch => {
-                self.android_permission_bind_directory_search = true
-            }
-            Permission::AndroidPermissionBindDreamService => {
-                self.android_permission_bind_dream_service = true
-            }
-            Permission::AndroidPermissionBindIncallService => {
-                self.android_permission_bind_incall_service = true
-            }
-            Permission::AndroidPermissionBindInputMethod => {
-                self.android_permission_bind_input_method = true
-            }
-            Permission::AndroidPermissionBindKeyguardAppwidget => {
-                self.android_permission_bind_keyguard_appwidget = true
-            }
-            Permission::AndroidPermissionBindMidiDeviceService => {
-                self.android_permission_bind_midi_device_service = true
-            }
-            Permission::AndroidPermissionBindNfcService => {
-                self.android_permission_bind_nfc_service = true
-            }
-            Permission::AndroidPermissionBindNotificationListenerService => {
-                self.android_permission_bind_notification_listener_service = true
-            }
-            Permission::AndroidPermissionBindPrintService => {
-                self.android_permission_bind_print_service = true
-            }
-            Permission::AndroidPermissionBindRemoteviews => {
-                self.android_permission_bind_remoteviews = true
-            }
-            Permission::AndroidPermissionBindTelecomConnectionService => {
-                self.android_permission_bind_telecom_connection_service = true
-            }
-            Permission::AndroidPermissionBindTextService => {
-                self.android_permission_bind_text_service = true
-            }
-            Permission::AndroidPermissionBindTvInput => {
-                self.android_permission_bind_tv_input = true
-            }
-            Permission::AndroidPermissionBindVoiceInteraction => {
-                self.android_permission_bind_voice_interaction = true
-            }
-            Permission::AndroidPermissionBindVpnService => {
-                self.android_permission_bind_vpn_service = true
-            }
-            Permission::AndroidPermissionBindWallpaper => {
-                self.android_permission_bind_wallpaper = true
-            }
-            Permission::AndroidPermissionBluetooth => self.android_permission_bluetooth = true,
-            Permission::AndroidPermissionBluetoothAdmin => {
-                self.android_permission_bluetooth_admin = true
-            }
-            Permission::AndroidPermissionBluetoothPrivileged => {
-                self.android_permission_bluetooth_privileged = true
-            }
-            Permission::AndroidPermissionBluetoothStack => {
-                self.android_permission_bluetooth_stack = true
-            }
-            Permission::AndroidPermissionBodySensors => self.android_permission_body_sensors = true,
-            Permission::AndroidPermissionBroadcastPackageRemoved => {
-                self.android_permission_broadcast_package_removed = true
-            }
-            Permission::AndroidPermissionBroadcastSms => {
-                self.android_permission_broadcast_sms = true
-            }
-            Permission::AndroidPermissionBroadcastSticky => {
-                self.android_permission_broadcast_sticky = true
-            }
-            Permission::AndroidPermissionBroadcastWapPush => {
-                self.android_permission_broadcast_wap_push = true
-            }
-            Permission::AndroidPermissionCallPhone => self.android_permission_call_phone = true,
-            Permission::AndroidPermissionCallPrivileged => {
-                self.android_permission_call_privileged = true
-            }
-            Permission::AndroidPermissionCamera => self.android_permission_camera = true,
-            Permission::AndroidPermissionCameraDisableTransmitLed => {
-                self.android_permission_camera_disable_transmit_led = true
-            }
-            Permission::AndroidPermissionCaptureAudioOutput => {
-                self.android_permission_capture_audio_output = true
-            }
-            Permission::AndroidPermissionCaptureSecureVideoOutput => {
-                self.android_permission_capture_secure_video_output = true
-            }
-            Permission::AndroidPermissionCaptureVideoOutput => {
-                self.android_permission_capture_video_output = true
-            }
-            Permission::AndroidPermissionChangeBackgroundDataSetting => {
-                self.android_permission_change_background_data_setting = true
-            }
-            Permission::AndroidPermissionChangeComponentEnabledState => {
-                self.android_permission_change_component_enabled_state = true
-            }
-            Permission::AndroidPermissionChangeConfiguration => {
-                self.android_permission_change_configuration = true
-            }
-            Permission::AndroidPermissionChangeNetworkState => {
-                self.android_permission_change_network_state = true
-            }
-            Permission::AndroidPermissionChangeWimaxState => {
-                self.android_permission_change_wimax_state = true
-            }
-            Permission::AndroidPermissionChangeWifiMulticastState => {
-                self.android_permission_change_wifi_multicast_state = true
-            }
-            Permission::AndroidPermissionChangeWifiState => {
-                self.android_permission_change_wifi_state = true
-            }
-            Permission::AndroidPermissionClearAppCache => {
-                self.android_permission_clear_app_cache = true
-            }
-            Permission::AndroidPermissionConnectivityInternal => {
-                self.android_permission_connectivity_internal = true
-            }
-            Permission::AndroidPermissionControlLocationUpdates => {
-                self.android_permission_control_location_updates = true
-            }
-            Permission::AndroidPermissionDeleteCacheFiles => {
-                self.android_permission_delete_cache_files = true
-            }
-            Permission::AndroidPermissionDeletePackages => {
-                self.android_permission_delete_packages = true
-            }
-            Permission::AndroidPermissionDiagnostic => self.android_permission_diagnostic = true,
-            Permission::AndroidPermissionDisableKeyguard => {
-                self.android_permission_disable_keyguard = true
-            }
-            Permission::AndroidPermissionDownloadWithoutNotification => {
-                self.android_permission_download_without_notification = true
-            }
-            Permission::AndroidPermissionDump => self.android_permission_dump = true,
-            Permission::AndroidPermissionExpandStatusBar => {
-                self.android_permission_expand_status_bar = true
-            }
-            Permission::AndroidPermissionFactoryTest => self.android_permission_factory_test = true,
-            Permission::AndroidPermissionFlashlight => self.android_permission_flashlight = true,
-            Permission::AndroidPermissionForceStopPackages => {
-                self.android_permission_force_stop_packages = true
-            }
-            Permission::AndroidPermissionGetAccounts => self.android_permission_get_accounts = true,
-            Permission::AndroidPermissionGetAccountsPrivileged => {
-                self.android_permission_get_accounts_privileged = true
-            }
-            Permission::AndroidPermissionGetAppOpsStats => {
-                self.android_permission_get_app_ops_stats = true
-            }
-            Permission::AndroidPermissionGetDetailedTasks => {
-                self.android_permission_get_detailed_tasks = true
-            }
-            Permission::AndroidPermissionGetPackageSize => {
-                self.android_permission_get_package_size = true
-            }
-            Permission::AndroidPermissionGetTasks => self.android_permission_get_tasks = true,
-            Permission::AndroidPermissionGlobalSearch => {
-                self.android_permission_global_search = true
-            }
-            Permission::AndroidPermissionGlobalSearchControl => {
-                self.android_permission_global_search_control = true
-            }
-            Permission::AndroidPermissionHardwareTest => {
-                self.android_permission_hardware_test = true
-            }
-            Permission::AndroidPermissionInstallLocationProvider => {
-                self.android_permission_install_location_provider = true
-            }
-            Permission::AndroidPermissionInstallPackages => {
-                self.android_permission_install_packages = true
-            }
-            Permission::AndroidPermissionInteractAcrossUsers => {
-                self.android_permission_interact_across_users = true
-            }
-            Permission::AndroidPermissionInteractAcrossUsersFull => {
-                self.android_permission_interact_across_users_full = true
-            }
-            Permission::AndroidPermissionInternet => self.android_permission_internet = true,
-            Permission::AndroidPermissionKillBackgroundProcesses => {
-                self.android_permission_kill_background_processes = true
-            }
-            Permission::AndroidPermissionLocationHardware => {
-                self.android_permission_location_hardware = true
-            }
-            Permission::AndroidPermissionLoopRadio => self.android_permission_loop_radio = true,
-            Permission::AndroidPermissionManageAccounts => {
-                self.android_permission_manage_accounts = true
-            }
-            Permission::AndroidPermissionManageActivityStacks => {
-                self.android_permission_manage_activity_stacks = true
-            }
-            Permission::AndroidPermissionManageDocuments => {
-                self.android_permission_manage_documents = true
-            }
-            Permission::AndroidPermissionManageUsb => self.android_permission_manage_usb = true,
-            Permission::AndroidPermissionManageUsers => self.android_permission_manage_users = true,
-            Permission::AndroidPermissionMasterClear => self.android_permission_master_clear = true,
-            Permission::AndroidPermissionMediaContentControl => {
-                self.android_permission_media_content_control = true
-            }
-            Permission::AndroidPermissionModifyAppwidgetBindPermissions => {
-                self.android_permission_modify_appwidget_bind_permissions = true
-            }
-            Permission::AndroidPermissionModifyAudioSettings => {
-                self.android_permission_modify_audio_settings = true
-            }
-            Permission::AndroidPermissionModifyPhoneState => {
-                self.android_permission_modify_phone_state = true
-            }
-            Permission::AndroidPermissionMountFormatFilesystems => {
-                self.android_permission_mount_format_filesystems = true
-            }
-            Permission::AndroidPermissionMountUnmountFilesystems => {
-                self.android_permission_mount_unmount_filesystems = true
-            }
-            Permission::AndroidPermissionNetAdmin => self.android_permission_net_admin = true,
-            Permission::AndroidPermissionNetTunneling => {
-                self.android_permission_net_tunneling = true
-            }
-            Permission::AndroidPermissionNfc => self.android_permission_nfc = true,
-            Permission::AndroidPermissionPackageUsageStats => {
-                self.android_permission_package_usage_stats = true
-            }
-            Permission::AndroidPermissionPersistentActivity => {
-                self.android_permission_persistent_activity = true
-            }
-            Permission::AndroidPermissionProcessOutgoingCalls => {
-                self.android_permission_process_outgoing_calls = true
-            }
-            Permission::AndroidPermissionReadCalendar => {
-                self.android_permission_read_calendar = true
-            }
-            Permission::AndroidPermissionReadCallLog => {
-                self.android_permission_read_call_log = true
-            }
-            Permission::AndroidPermissionReadCellBroadcasts => {
-                self.android_permission_read_cell_broadcasts = true
-            }
-            Permission::AndroidPermissionReadContacts => {
-                self.android_permission_read_contacts = true
-            }
-            Permission::AndroidPermissionReadDreamState => {
-                self.android_permission_read_dream_state = true
-            }
-            Permission::AndroidPermissionReadExternalStorage => {
-                self.android_permission_read_external_storage = true
-            }
-            Permission::AndroidPermissionReadFrameBuffer => {
-                self.android_permission_read_frame_buffer = true
-            }
-            Permission::AndroidPermissionReadInputState => {
-                self.android_permission_read_input_state = true
-            }
-            Permission::AndroidPermissionReadLogs => self.android_permission_read_logs = true,
-            Permission::AndroidPermissionReadPhoneState => {
-                self.android_permission_read_phone_state = true
-            }
-            Permission::AndroidPermissionReadPrivilegedPhoneState => {
-                self.android_permission_read_privileged_phone_state = true
-            }
-            Permission::AndroidPermissionReadProfile => self.android_permission_read_profile = true,
-            Permission::AndroidPermissionReadSms => self.android_permission_read_sms = true,
-            Permission::AndroidPermissionReadSocialStream => {
-                self.android_permission_read_social_stream = true
-            }
-            Permission::AndroidPermissionReadSyncSettings => {
-                self.android_permission_read_sync_settings = true
-            }
-            Permission::AndroidPermissionReadSyncStats => {
-                self.android_permission_read_sync_stats = true
-            }
-            Permission::AndroidPermissionReadUserDictionary => {
-                self.android_permission_read_user_dictionary = true
-            }
-            Permission::AndroidPermissionReboot => self.android_permission_reboot = true,
-            Permission::AndroidPermissionReceiveBootCompleted => {
-                self.android_permission_receive_boot_completed = true
-            }
-            Permission::AndroidPermissionReceiveDataActivityChange => {
-                self.android_permission_receive_data_activity_change = true
-            }
-            Permission::AndroidPermissionReceiveEmergencyBroadcast => {
-                self.android_permission_receive_emergency_broadcast = true
-            }
-            Permission::AndroidPermissionReceiveMms => self.android_permission_receive_mms = true,
-            Permission::AndroidPermissionReceiveSms => self.android_permission_receive_sms = true,
-            Permission::AndroidPermissionReceiveWapPush => {
-                self.android_permission_receive_wap_push = true
-            }
-            Permission::AndroidPermissionRecordAudio => self.android_permission_record_audio = true,
-            Permission::AndroidPermissionRemoteAudioPlayback => {
-                self.android_permission_remote_audio_playback = true
-            }
-            Permission::AndroidPermissionRemoveTasks => self.android_permission_remove_tasks = true,
-            Permission::AndroidPermissionReorderTasks => {
-                self.android_permission_reorder_tasks = true
-            }
-            Permission::AndroidPermissionRequestIgnoreBatteryOptimizations => {
-                self.android_permission_request_ignore_battery_optimizations = true
-            }
-            Permission::AndroidPermissionRequestInstallPackages => {
-                self.android_permission_request_install_packages = true
-            }
-            Permission::AndroidPermissionRestartPackages => {
-                self.android_permission_restart_packages = true
-            }
-            Permission::AndroidPermissionRetrieveWindowContent => {
-                self.android_permission_retrieve_window_content = true
-            }
-            Permission::AndroidPermissionSendRespondViaMessage => {
-                self.android_permission_send_respond_via_message = true
-            }
-            Permission::AndroidPermissionSendSms => self.android_permission_send_sms = true,
-            Permission::AndroidPermissionSetAlwaysFinish => {
-                self.android_permission_set_always_finish = true
-            }
-            Permission::AndroidPermissionSetAnimationScale => {
-                self.android_permission_set_animation_scale = true
-            }
-            Permission::AndroidPermissionSetDebugApp => {
-                self.android_permission_set_debug_app = true
-            }
-            Permission::AndroidPermissionSetPreferredApplications => {
-                self.android_permission_set_preferred_applications = true
-            }
-            Permission::AndroidPermissionSetProcessLimit => {
-                self.android_permission_set_process_limit = true
-            }
-            Permission::AndroidPermissionSetScreenCompatibility => {
-                self.android_permission_set_screen_compatibility = true
-            }
-            Permission::AndroidPermissionSetTime => self.android_permission_set_time = true,
-            Permission::AndroidPermissionSetTimeZone => {
-                self.android_permission_set_time_zone = true
-            }
-            Permission::AndroidPermissionSetWallpaper => {
-                self.android_permission_set_wallpaper = true
-            }
-            Permission::AndroidPermissionSetWallpaperComponent => {
-                self.android_permission_set_wallpaper_component = true
-            }
-            Permission::AndroidPermissionSetWallpaperHints => {
-                self.android_permission_set_wallpaper_hints = true
-            }
-            Permission::AndroidPermissionSignalPersistentProcesses => {
-                self.android_permission_signal_persistent_processes = true
-            }
-            Permission::AndroidPermissionStartAnyActivity => {
-                self.android_permission_start_any_activity = true
-            }
-            Permission::AndroidPermissionStatusBar => self.android_permission_status_bar = true,
-            Permission::AndroidPermissionSubscribedFeedsRead => {
-                self.android_permission_subscribed_feeds_read = true
-            }
-            Permission::AndroidPermissionSystemAlertWindow => {
-                self.android_permission_system_alert_window = true
-            }
-            Permission::AndroidPermissionSubscribedFeedsWrite => {
-                self.android_permission_subscribed_feeds_write = true
-            }
-            Permission::AndroidPermissionTransmitIr => self.android_permission_transmit_ir = true,
-            Permission::AndroidPermissionUpdateDeviceStats => {
-                self.android_permission_update_device_stats = true
-            }
-            Permission::AndroidPermissionUseCredentials => {
-                self.android_permission_use_credentials = true
-            }
-            Permission::AndroidPermissionUseFingerprint => {
-                self.android_permission_use_fingerprint = true
-            }
-            Permission::AndroidPermissionUseSip => self.android_permission_use_sip = true,
-            Permission::AndroidPermissionVibrate => self.android_permission_vibrate = true,
-            Permission::AndroidPermissionWakeLock => self.android_permission_wake_lock = true,
-            Permission::AndroidPermissionWriteApnSettings => {
-                self.android_permission_write_apn_settings = true
-            }
-            Permission::AndroidPermissionWriteCalendar => {
-                self.android_permission_write_calendar = true
-            }
-            Permission::AndroidPermissionWriteCallLog => {
-                self.android_permission_write_call_log = true
-            }
-            Permission::AndroidPermissionWriteContacts => {
-                self.android_permission_write_contacts = true
-            }
-            Permission::AndroidPermissionWriteDreamState => {
-                self.android_permission_write_dream_state = true
-            }
-            Permission::AndroidPermissionWriteExternalStorage => {
-                self.android_permission_write_external_storage = true
-            }
-            Permission::AndroidPermissionWriteGservices => {
-                self.android_permission_write_gservices = true
-            }
-            Permission::AndroidPermissionWriteMediaStorage => {
-                self.android_permission_write_media_storage = true
-            }
-            Permission::AndroidPermissionWriteProfile => {
-                self.android_permission_write_profile = true
-            }
-            Permission::AndroidPermissionWriteSecureSettings => {
-                self.android_permission_write_secure_settings = true
-            }
-            Permission::AndroidPermissionWriteSettings => {
-                self.android_permission_write_settings = true
-            }
-            Permission::AndroidPermissionWriteSms => self.android_permission_write_sms = true,
-            Permission::AndroidPermissionWriteSocialStream => {
-                self.android_permission_write_social_stream = true
-            }
-            Permission::AndroidPermissionWriteSyncSettings => {
-                self.android_permission_write_sync_settings = true
-            }
-            Permission::AndroidPermissionWriteUserDictionary => {
-                self.android_permission_write_user_dictionary = true
-            }
-            Permission::ComAndroidAlarmPermissionSetAlarm => {
-                self.com_android_alarm_permission_set_alarm = true
-            }
-            Permission::ComAndroidBrowserPermissionReadHistoryBookmarks => {
-                self.com_android_browser_permission_read_history_bookmarks = true
-            }
-            Permission::ComAndroidBrowserPermissionWriteHistoryBookmarks => {
-                self.com_android_browser_permission_write_history_bookmarks = true
-            }
-            Permission::ComAndroidEmailPermissionReadAttachment => {
-                self.com_android_email_permission_read_attachment = true
-            }
-            Permission::ComAndroidLauncherPermissionInstallShortcut => {
-                self.com_android_launcher_permission_install_shortcut = true
-            }
-            Permission::ComAndroidLauncherPermissionPreloadWorkspace => {
-                self.com_android_launcher_permission_preload_workspace = true
-            }
-            Permission::ComAndroidLauncherPermissionReadSettings => {
-                self.com_android_launcher_permission_read_settings = true
-            }
-            Permission::ComAndroidLauncherPermissionUninstallShortcut => {
-                self.com_android_launcher_permission_uninstall_shortcut = true
-            }
-            Permission::ComAndroidLauncherPermissionWriteSettings => {
-                self.com_android_launcher_permission_write_settings = true
-            }
-            Permission::ComAndroidVendingCheckLicense => {
-                self.com_android_vending_check_license = true
-            }
-            Permission::ComAndroidVoicemailPermissionAddVoicemail => {
-                self.com_android_voicemail_permission_add_voicemail = true
-            }
-            Permission::ComAndroidVoicemailPermissionReadVoicemail => {
-                self.com_android_voicemail_permission_read_voicemail = true
-            }
-            Permission::ComAndroidVoicemailPermissionReadWriteAllVoicemail => {
-                self.com_android_voicemail_permission_read_write_all_voicemail = true
-            }
-            Permission::ComAndroidVoicemailPermissionWriteVoicemail => {
-                self.com_android_voicemail_permission_write_voicemail = true
-            }
-            Permission::ComGoogleAndroidC2dmPermissionReceive => {
-                self.com_google_android_c2dm_permission_receive = true
-            }
-            Permission::ComGoogleAndroidC2dmPermissionSend => {
-                self.com_google_android_c2dm_permission_send = true
-            }
-            Permission::ComGoogleAndroidGmsPermissionActivityRecognition => {
-                self.com_google_android_gms_permission_activity_recognition = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuth => {
-                self.com_google_android_googleapps_permission_google_auth = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAllServices => {
-                self.com_google_android_googleapps_permission_google_auth_all_services = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOtherServices => {
-                self.com_google_android_googleapps_permission_google_auth_other_services = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutubeuser => {
-                self.com_google_android_googleapps_permission_google_auth_youtubeuser = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdsense => {
-                self.com_google_android_googleapps_permission_google_auth_adsense = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdwords => {
-                self.com_google_android_googleapps_permission_google_auth_adwords = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAh => {
-                self.com_google_android_googleapps_permission_google_auth_ah = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroid => {
-                self.com_google_android_googleapps_permission_google_auth_android = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroidsecure => {
-                self.com_google_android_googleapps_permission_google_auth_androidsecure = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthBlogger => {
-                self.com_google_android_googleapps_permission_google_auth_blogger = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCl => {
-                self.com_google_android_googleapps_permission_google_auth_cl = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCp => {
-                self.com_google_android_googleapps_permission_google_auth_cp = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDodgeball => {
-                self.com_google_android_googleapps_permission_google_auth_dodgeball = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDoraemon => {
-                self.com_google_android_googleapps_permission_google_auth_doraemon = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthFinance => {
-                self.com_google_android_googleapps_permission_google_auth_finance = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGbase => {
-                self.com_google_android_googleapps_permission_google_auth_gbase = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGeowiki => {
-                self.com_google_android_googleapps_permission_google_auth_geowiki = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGoannaMobile => {
-                self.com_google_android_googleapps_permission_google_auth_goanna_mobile = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGrandcentral => {
-                self.com_google_android_googleapps_permission_google_auth_grandcentral = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGroups2 => {
-                self.com_google_android_googleapps_permission_google_auth_groups2 = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthHealth => {
-                self.com_google_android_googleapps_permission_google_auth_health = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthIg => {
-                self.com_google_android_googleapps_permission_google_auth_ig = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthJotspot => {
-                self.com_google_android_googleapps_permission_google_auth_jotspot = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthKnol => {
-                self.com_google_android_googleapps_permission_google_auth_knol = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLh2 => {
-                self.com_google_android_googleapps_permission_google_auth_lh2 = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLocal => {
-                self.com_google_android_googleapps_permission_google_auth_local = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMail => {
-                self.com_google_android_googleapps_permission_google_auth_mail = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMobile => {
-                self.com_google_android_googleapps_permission_google_auth_mobile = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNews => {
-                self.com_google_android_googleapps_permission_google_auth_news = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNotebook => {
-                self.com_google_android_googleapps_permission_google_auth_notebook = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOrkut => {
-                self.com_google_android_googleapps_permission_google_auth_orkut = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPanoramio => {
-                self.com_google_android_googleapps_permission_google_auth_panoramio = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPrint => {
-                self.com_google_android_googleapps_permission_google_auth_print = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthReader => {
-                self.com_google_android_googleapps_permission_google_auth_reader = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierra => {
-                self.com_google_android_googleapps_permission_google_auth_sierra = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierraqa => {
-                self.com_google_android_googleapps_permission_google_auth_sierraqa = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierrasandbox => {
-                self.com_google_android_googleapps_permission_google_auth_sierrasandbox = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSitemaps => {
-                self.com_google_android_googleapps_permission_google_auth_sitemaps = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeech => {
-                self.com_google_android_googleapps_permission_google_auth_speech = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeechpersonalization => {
-                self.com_google_android_googleapps_permission_google_auth_speechpersonalization =
-                    true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthTalk => {
-                self.com_google_android_googleapps_permission_google_auth_talk = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWifi => {
-                self.com_google_android_googleapps_permission_google_auth_wifi = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWise => {
-                self.com_google_android_googleapps_permission_google_auth_wise = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWritely => {
-                self.com_google_android_googleapps_permission_google_auth_writely = true
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutube => {
-                self.com_google_android_googleapps_permission_google_auth_youtube = true
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionGtalkService => {
-                self.com_google_android_gtalkservice_permission_gtalk_service = true
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionSendHeartbeat => {
-                self.com_google_android_gtalkservice_permission_send_heartbeat = true
-            }
-            Permission::ComGoogleAndroidPermissionBroadcastDataMessage => {
-                self.com_google_android_permission_broadcast_data_message = true
-            }
-            Permission::ComGoogleAndroidProvidersGsfPermissionReadGservices => {
-                self.com_google_android_providers_gsf_permission_read_gservices = true
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionReadOnly => {
-                self.com_google_android_providers_talk_permission_read_only = true
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionWriteOnly => {
-                self.com_google_android_providers_talk_permission_write_only = true
-            }
-            Permission::ComGoogleAndroidXmppPermissionBroadcast => {
-                self.com_google_android_xmpp_permission_broadcast = true
-            }
-            Permission::ComGoogleAndroidXmppPermissionSendReceive => {
-                self.com_google_android_xmpp_permission_send_receive = true
-            }
-            Permission::ComGoogleAndroidXmppPermissionUseXmppEndpoint => {
-                self.com_google_android_xmpp_permission_use_xmpp_endpoint = true
-            }
-            Permission::ComGoogleAndroidXmppPermissionXmppEndpointBroadcast => {
-                self.com_google_android_xmpp_permission_xmpp_endpoint_broadcast = true
-            }
-        }
+impl Permission {
+    /// Gets the string representation of the permission.
+    pub fn as_str(&self) -> &str {
+        &self.name
     }
 }
 
-impl Default for PermissionChecklist {
-    fn default() -> Self {
-        Self {
-            android_permission_access_all_external_storage: false,
-            android_permission_access_checkin_properties: false,
-            android_permission_access_coarse_location: false,
-            android_permission_access_fine_location: false,
-            android_permission_access_location_extra_commands: false,
-            android_permission_access_mock_location: false,
-            android_permission_access_mtp: false,
-            android_permission_access_network_state: false,
-            android_permission_access_notification_policy: false,
-            android_permission_access_wimax_state: false,
-            android_permission_access_wifi_state: false,
-            android_permission_account_manager: false,
-            android_permission_asec_access: false,
-            android_permission_asec_create: false,
-            android_permission_asec_destroy: false,
-            android_permission_asec_mount_unmount: false,
-            android_permission_asec_rename: false,
-            android_permission_authenticate_accounts: false,
-            android_permission_battery_stats: false,
-            android_permission_bind_accessibility_service: false,
-            android_permission_bind_appwidget: false,
-            android_permission_bind_call_service: false,
-            android_permission_bind_carrier_messaging_service: false,
-            android_permission_bind_carrier_services: false,
-            android_permission_bind_chooser_target_service: false,
-            android_permission_bind_device_admin: false,
-            android_permission_bind_directory_search: false,
-            android_permission_bind_dream_service: false,
-            android_permission_bind_incall_service: false,
-            android_permission_bind_input_method: false,
-            android_permission_bind_keyguard_appwidget: false,
-            android_permission_bind_midi_device_service: false,
-            android_permission_bind_nfc_service: false,
-            android_permission_bind_notification_listener_service: false,
-            android_permission_bind_print_service: false,
-            android_permission_bind_remoteviews: false,
-            android_permission_bind_telecom_connection_service: false,
-            android_permission_bind_text_service: false,
-            android_permission_bind_tv_input: false,
-            android_permission_bind_voice_interaction: false,
-            android_permission_bind_vpn_service: false,
-            android_permission_bind_wallpaper: false,
-            android_permission_bluetooth: false,
-            android_permission_bluetooth_admin: false,
-            android_permission_bluetooth_privileged: false,
-            android_permission_bluetooth_stack: false,
-            android_permission_body_sensors: false,
-            android_permission_broadcast_package_removed: false,
-            android_permission_broadcast_sms: false,
-            android_permission_broadcast_sticky: false,
-            android_permission_broadcast_wap_push: false,
-            android_permission_call_phone: false,
-            android_permission_call_privileged: false,
-            android_permission_camera: false,
-            android_permission_camera_disable_transmit_led: false,
-            android_permission_capture_audio_output: false,
-            android_permission_capture_secure_video_output: false,
-            android_permission_capture_video_output: false,
-            android_permission_change_background_data_setting: false,
-            android_permission_change_component_enabled_state: false,
-            android_permission_change_configuration: false,
-            android_permission_change_network_state: false,
-            android_permission_change_wimax_state: false,
-            android_permission_change_wifi_multicast_state: false,
-            android_permission_change_wifi_state: false,
-            android_permission_clear_app_cache: false,
-            android_permission_connectivity_internal: false,
-            android_permission_control_location_updates: false,
-            android_permission_delete_cache_files: false,
-            android_permission_delete_packages: false,
-            android_permission_diagnostic: false,
-            android_permission_disable_keyguard: false,
-            android_permission_download_without_notification: false,
-            android_permission_dump: false,
-            android_permission_expand_status_bar: false,
-            android_permission_factory_test: false,
-            android_permission_flashlight: false,
-            android_permission_force_stop_packages: false,
-            android_permission_get_accounts: false,
-            android_permission_get_accounts_privileged: false,
-            android_permission_get_app_ops_stats: false,
-            android_permission_get_detailed_tasks: false,
-            android_permission_get_package_size: false,
-            android_permission_get_tasks: false,
-            android_permission_global_search: false,
-            android_permission_global_search_control: false,
-            android_permission_hardware_test: false,
-            android_permission_install_location_provider: false,
-            android_permission_install_packages: false,
-            android_permission_interact_across_users: false,
-            android_permission_interact_across_users_full: false,
-            android_permission_internet: false,
-            android_permission_kill_background_processes: false,
-            android_permission_location_hardware: false,
-            android_permission_loop_radio: false,
-            android_permission_manage_accounts: false,
-            android_permission_manage_activity_stacks: false,
-            android_permission_manage_documents: false,
-            android_permission_manage_usb: false,
-            android_permission_manage_users: false,
-            android_permission_master_clear: false,
-            android_permission_media_content_control: false,
-            android_permission_modify_appwidget_bind_permissions: false,
-            android_permission_modify_audio_settings: false,
-            android_permission_modify_phone_state: false,
-            android_permission_mount_format_filesystems: false,
-            android_permission_mount_unmount_filesystems: false,
-            android_permission_net_admin: false,
-            android_permission_net_tunneling: false,
-            android_permission_nfc: false,
-            android_permission_package_usage_stats: false,
-            android_permission_persistent_activity: false,
-            android_permission_process_outgoing_calls: false,
-            android_permission_read_calendar: false,
-            android_permission_read_call_log: false,
-            android_permission_read_cell_broadcasts: false,
-            android_permission_read_contacts: false,
-            android_permission_read_dream_state: false,
-            android_permission_read_external_storage: false,
-            android_permission_read_frame_buffer: false,
-            android_permission_read_input_state: false,
-            android_permission_read_logs: false,
-            android_permission_read_phone_state: false,
-            android_permission_read_privileged_phone_state: false,
-            android_permission_read_profile: false,
-            android_permission_read_sms: false,
-            android_permission_read_social_stream: false,
-            android_permission_read_sync_settings: false,
-            android_permission_read_sync_stats: false,
-            android_permission_read_user_dictionary: false,
-            android_permission_reboot: false,
-            android_permission_receive_boot_completed: false,
-            android_permission_receive_data_activity_change: false,
-            android_permission_receive_emergency_broadcast: false,
-            android_permission_receive_mms: false,
-            android_permission_receive_sms: false,
-            android_permission_receive_wap_push: false,
-            android_permission_record_audio: false,
-            android_permission_remote_audio_playback: false,
-            android_permission_remove_tasks: false,
-            android_permission_reorder_tasks: false,
-            android_permission_request_ignore_battery_optimizations: false,
-            android_permission_request_install_packages: false,
-            android_permission_restart_packages: false,
-            android_permission_retrieve_window_content: false,
-            android_permission_send_respond_via_message: false,
-            android_permission_send_sms: false,
-            android_permission_set_always_finish: false,
-            android_permission_set_animation_scale: false,
-            android_permission_set_debug_app: false,
-            android_permission_set_preferred_applications: false,
-            android_permission_set_process_limit: false,
-            android_permission_set_screen_compatibility: false,
-            android_permission_set_time: false,
-            android_permission_set_time_zone: false,
-            android_permission_set_wallpaper: false,
-            android_permission_set_wallpaper_component: false,
-            android_permission_set_wallpaper_hints: false,
-            android_permission_signal_persistent_processes: false,
-            android_permission_start_any_activity: false,
-            android_permission_status_bar: false,
-            android_permission_subscribed_feeds_read: false,
-            android_permission_system_alert_window: false,
-            android_permission_subscribed_feeds_write: false,
-            android_permission_transmit_ir: false,
-            android_permission_update_device_stats: false,
-            android_permission_use_credentials: false,
-            android_permission_use_fingerprint: false,
-            android_permission_use_sip: false,
-            android_permission_vibrate: false,
-            android_permission_wake_lock: false,
-            android_permission_write_apn_settings: false,
-            android_permission_write_calendar: false,
-            android_permission_write_call_log: false,
-            android_permission_write_contacts: false,
-            android_permission_write_dream_state: false,
-            android_permission_write_external_storage: false,
-            android_permission_write_gservices: false,
-            android_permission_write_media_storage: false,
-            android_permission_write_profile: false,
-            android_permission_write_secure_settings: false,
-            android_permission_write_settings: false,
-            android_permission_write_sms: false,
-            android_permission_write_social_stream: false,
-            android_permission_write_sync_settings: false,
-            android_permission_write_user_dictionary: false,
-            com_android_alarm_permission_set_alarm: false,
-            com_android_browser_permission_read_history_bookmarks: false,
-            com_android_browser_permission_write_history_bookmarks: false,
-            com_android_email_permission_read_attachment: false,
-            com_android_launcher_permission_install_shortcut: false,
-            com_android_launcher_permission_preload_workspace: false,
-            com_android_launcher_permission_read_settings: false,
-            com_android_launcher_permission_uninstall_shortcut: false,
-            com_android_launcher_permission_write_settings: false,
-            com_android_vending_check_license: false,
-            com_android_voicemail_permission_add_voicemail: false,
-            com_android_voicemail_permission_read_voicemail: false,
-            com_android_voicemail_permission_read_write_all_voicemail: false,
-            com_android_voicemail_permission_write_voicemail: false,
-            com_google_android_c2dm_permission_receive: false,
-            com_google_android_c2dm_permission_send: false,
-            com_google_android_gms_permission_activity_recognition: false,
-            com_google_android_googleapps_permission_google_auth: false,
-            com_google_android_googleapps_permission_google_auth_all_services: false,
-            com_google_android_googleapps_permission_google_auth_other_services: false,
-            com_google_android_googleapps_permission_google_auth_youtubeuser: false,
-            com_google_android_googleapps_permission_google_auth_adsense: false,
-            com_google_android_googleapps_permission_google_auth_adwords: false,
-            com_google_android_googleapps_permission_google_auth_ah: false,
-            com_google_android_googleapps_permission_google_auth_android: false,
-            com_google_android_googleapps_permission_google_auth_androidsecure: false,
-            com_google_android_googleapps_permission_google_auth_blogger: false,
-            com_google_android_googleapps_permission_google_auth_cl: false,
-            com_google_android_googleapps_permission_google_auth_cp: false,
-            com_google_android_googleapps_permission_google_auth_dodgeball: false,
-            com_google_android_googleapps_permission_google_auth_doraemon: false,
-            com_google_android_googleapps_permission_google_auth_finance: false,
-            com_google_android_googleapps_permission_google_auth_gbase: false,
-            com_google_android_googleapps_permission_google_auth_geowiki: false,
-            com_google_android_googleapps_permission_google_auth_goanna_mobile: false,
-            com_google_android_googleapps_permission_google_auth_grandcentral: false,
-            com_google_android_googleapps_permission_google_auth_groups2: false,
-            com_google_android_googleapps_permission_google_auth_health: false,
-            com_google_android_googleapps_permission_google_auth_ig: false,
-            com_google_android_googleapps_permission_google_auth_jotspot: false,
-            com_google_android_googleapps_permission_google_auth_knol: false,
-            com_google_android_googleapps_permission_google_auth_lh2: false,
-            com_google_android_googleapps_permission_google_auth_local: false,
-            com_google_android_googleapps_permission_google_auth_mail: false,
-            com_google_android_googleapps_permission_google_auth_mobile: false,
-            com_google_android_googleapps_permission_google_auth_news: false,
-            com_google_android_googleapps_permission_google_auth_notebook: false,
-            com_google_android_googleapps_permission_google_auth_orkut: false,
-            com_google_android_googleapps_permission_google_auth_panoramio: false,
-            com_google_android_googleapps_permission_google_auth_print: false,
-            com_google_android_googleapps_permission_google_auth_reader: false,
-            com_google_android_googleapps_permission_google_auth_sierra: false,
-            com_google_android_googleapps_permission_google_auth_sierraqa: false,
-            com_google_android_googleapps_permission_google_auth_sierrasandbox: false,
-            com_google_android_googleapps_permission_google_auth_sitemaps: false,
-            com_google_android_googleapps_permission_google_auth_speech: false,
-            com_google_android_googleapps_permission_google_auth_speechpersonalization: false,
-            com_google_android_googleapps_permission_google_auth_talk: false,
-            com_google_android_googleapps_permission_google_auth_wifi: false,
-            com_google_android_googleapps_permission_google_auth_wise: false,
-            com_google_android_googleapps_permission_google_auth_writely: false,
-            com_google_android_googleapps_permission_google_auth_youtube: false,
-            com_google_android_gtalkservice_permission_gtalk_service: false,
-            com_google_android_gtalkservice_permission_send_heartbeat: false,
-            com_google_android_permission_broadcast_data_message: false,
-            com_google_android_providers_gsf_permission_read_gservices: false,
-            com_google_android_providers_talk_permission_read_only: false,
-            com_google_android_providers_talk_permission_write_only: false,
-            com_google_android_xmpp_permission_broadcast: false,
-            com_google_android_xmpp_permission_send_receive: false,
-            com_google_android_xmpp_permission_use_xmpp_endpoint: false,
-            com_google_android_xmpp_permission_xmpp_endpoint_broadcast: false,
-        }
+impl AsRef<str> for Permission {
+    fn as_ref(&self) -> &str {
+        &self.name
     }
 }
 
-/// Enumeration describing all the known permissions.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub enum Permission {
-    AndroidPermissionAccessAllExternalStorage,
-    AndroidPermissionAccessCheckinProperties,
-    AndroidPermissionAccessCoarseLocation,
-    AndroidPermissionAccessFineLocation,
-    AndroidPermissionAccessLocationExtraCommands,
-    AndroidPermissionAccessMockLocation,
-    AndroidPermissionAccessMtp,
-    AndroidPermissionAccessNetworkState,
-    AndroidPermissionAccessNotificationPolicy,
-    AndroidPermissionAccessWimaxState,
-    AndroidPermissionAccessWifiState,
-    AndroidPermissionAccountManager,
-    AndroidPermissionAsecAccess,
-    AndroidPermissionAsecCreate,
-    AndroidPermissionAsecDestroy,
-    AndroidPermissionAsecMountUnmount,
-    AndroidPermissionAsecRename,
-    AndroidPermissionAuthenticateAccounts,
-    AndroidPermissionBatteryStats,
-    AndroidPermissionBindAccessibilityService,
-    AndroidPermissionBindAppwidget,
-    AndroidPermissionBindCallService,
-    AndroidPermissionBindCarrierMessagingService,
-    AndroidPermissionBindCarrierServices,
-    AndroidPermissionBindChooserTargetService,
-    AndroidPermissionBindDeviceAdmin,
-    AndroidPermissionBindDirectorySearch,
-    AndroidPermissionBindDreamService,
-    AndroidPermissionBindIncallService,
-    AndroidPermissionBindInputMethod,
-    AndroidPermissionBindKeyguardAppwidget,
-    AndroidPermissionBindMidiDeviceService,
-    AndroidPermissionBindNfcService,
-    AndroidPermissionBindNotificationListenerService,
-    AndroidPermissionBindPrintService,
-    AndroidPermissionBindRemoteviews,
-    AndroidPermissionBindTelecomConnectionService,
-    AndroidPermissionBindTextService,
-    AndroidPermissionBindTvInput,
-    AndroidPermissionBindVoiceInteraction,
-    AndroidPermissionBindVpnService,
-    AndroidPermissionBindWallpaper,
-    AndroidPermissionBluetooth,
-    AndroidPermissionBluetoothAdmin,
-    AndroidPermissionBluetoothPrivileged,
-    AndroidPermissionBluetoothStack,
-    AndroidPermissionBodySensors,
-    AndroidPermissionBroadcastPackageRemoved,
-    AndroidPermissionBroadcastSms,
-    AndroidPermissionBroadcastSticky,
-    AndroidPermissionBroadcastWapPush,
-    AndroidPermissionCallPhone,
-    AndroidPermissionCallPrivileged,
-    AndroidPermissionCamera,
-    AndroidPermissionCameraDisableTransmitLed,
-    AndroidPermissionCaptureAudioOutput,
-    AndroidPermissionCaptureSecureVideoOutput,
-    AndroidPermissionCaptureVideoOutput,
-    AndroidPermissionChangeBackgroundDataSetting,
-    AndroidPermissionChangeComponentEnabledState,
-    AndroidPermissionChangeConfiguration,
-    AndroidPermissionChangeNetworkState,
-    AndroidPermissionChangeWimaxState,
-    AndroidPermissionChangeWifiMulticastState,
-    AndroidPermissionChangeWifiState,
-    AndroidPermissionClearAppCache,
-    AndroidPermissionConnectivityInternal,
-    AndroidPermissionControlLocationUpdates,
-    AndroidPermissionDeleteCacheFiles,
-    AndroidPermissionDeletePackages,
-    AndroidPermissionDiagnostic,
-    AndroidPermissionDisableKeyguard,
-    AndroidPermissionDownloadWithoutNotification,
-    AndroidPermissionDump,
-    AndroidPermissionExpandStatusBar,
-    AndroidPermissionFactoryTest,
-    AndroidPermissionFlashlight,
-    AndroidPermissionForceStopPackages,
-    AndroidPermissionGetAccounts,
-    AndroidPermissionGetAccountsPrivileged,
-    AndroidPermissionGetAppOpsStats,
-    AndroidPermissionGetDetailedTasks,
-    AndroidPermissionGetPackageSize,
-    AndroidPermissionGetTasks,
-    AndroidPermissionGlobalSearch,
-    AndroidPermissionGlobalSearchControl,
-    AndroidPermissionHardwareTest,
-    AndroidPermissionInstallLocationProvider,
-    AndroidPermissionInstallPackages,
-    AndroidPermissionInteractAcrossUsers,
-    AndroidPermissionInteractAcrossUsersFull,
-    AndroidPermissionInternet,
-    AndroidPermissionKillBackgroundProcesses,
-    AndroidPermissionLocationHardware,
-    AndroidPermissionLoopRadio,
-    AndroidPermissionManageAccounts,
-    AndroidPermissionManageActivityStacks,
-    AndroidPermissionManageDocuments,
-    AndroidPermissionManageUsb,
-    AndroidPermissionManageUsers,
-    AndroidPermissionMasterClear,
-    AndroidPermissionMediaContentControl,
-    AndroidPermissionModifyAppwidgetBindPermissions,
-    AndroidPermissionModifyAudioSettings,
-    AndroidPermissionModifyPhoneState,
-    AndroidPermissionMountFormatFilesystems,
-    AndroidPermissionMountUnmountFilesystems,
-    AndroidPermissionNetAdmin,
-    AndroidPermissionNetTunneling,
-    AndroidPermissionNfc,
-    AndroidPermissionPackageUsageStats,
-    AndroidPermissionPersistentActivity,
-    AndroidPermissionProcessOutgoingCalls,
-    AndroidPermissionReadCalendar,
-    AndroidPermissionReadCallLog,
-    AndroidPermissionReadCellBroadcasts,
-    AndroidPermissionReadContacts,
-    AndroidPermissionReadDreamState,
-    AndroidPermissionReadExternalStorage,
-    AndroidPermissionReadFrameBuffer,
-    AndroidPermissionReadInputState,
-    AndroidPermissionReadLogs,
-    AndroidPermissionReadPhoneState,
-    AndroidPermissionReadPrivilegedPhoneState,
-    AndroidPermissionReadProfile,
-    AndroidPermissionReadSms,
-    AndroidPermissionReadSocialStream,
-    AndroidPermissionReadSyncSettings,
-    AndroidPermissionReadSyncStats,
-    AndroidPermissionReadUserDictionary,
-    AndroidPermissionReboot,
-    AndroidPermissionReceiveBootCompleted,
-    AndroidPermissionReceiveDataActivityChange,
-    AndroidPermissionReceiveEmergencyBroadcast,
-    AndroidPermissionReceiveMms,
-    AndroidPermissionReceiveSms,
-    AndroidPermissionReceiveWapPush,
-    AndroidPermissionRecordAudio,
-    AndroidPermissionRemoteAudioPlayback,
-    AndroidPermissionRemoveTasks,
-    AndroidPermissionReorderTasks,
-    AndroidPermissionRequestIgnoreBatteryOptimizations,
-    AndroidPermissionRequestInstallPackages,
-    AndroidPermissionRestartPackages,
-    AndroidPermissionRetrieveWindowContent,
-    AndroidPermissionSendRespondViaMessage,
-    AndroidPermissionSendSms,
-    AndroidPermissionSetAlwaysFinish,
-    AndroidPermissionSetAnimationScale,
-    AndroidPermissionSetDebugApp,
-    AndroidPermissionSetPreferredApplications,
-    AndroidPermissionSetProcessLimit,
-    AndroidPermissionSetScreenCompatibility,
-    AndroidPermissionSetTime,
-    AndroidPermissionSetTimeZone,
-    AndroidPermissionSetWallpaper,
-    AndroidPermissionSetWallpaperComponent,
-    AndroidPermissionSetWallpaperHints,
-    AndroidPermissionSignalPersistentProcesses,
-    AndroidPermissionStartAnyActivity,
-    AndroidPermissionStatusBar,
-    AndroidPermissionSubscribedFeedsRead,
-    AndroidPermissionSystemAlertWindow,
-    AndroidPermissionSubscribedFeedsWrite,
-    AndroidPermissionTransmitIr,
-    AndroidPermissionUpdateDeviceStats,
-    AndroidPermissionUseCredentials,
-    AndroidPermissionUseFingerprint,
-    AndroidPermissionUseSip,
-    AndroidPermissionVibrate,
-    AndroidPermissionWakeLock,
-    AndroidPermissionWriteApnSettings,
-    AndroidPermissionWriteCalendar,
-    AndroidPermissionWriteCallLog,
-    AndroidPermissionWriteContacts,
-    AndroidPermissionWriteDreamState,
-    AndroidPermissionWriteExternalStorage,
-    AndroidPermissionWriteGservices,
-    AndroidPermissionWriteMediaStorage,
-    AndroidPermissionWriteProfile,
-    AndroidPermissionWriteSecureSettings,
-    AndroidPermissionWriteSettings,
-    AndroidPermissionWriteSms,
-    AndroidPermissionWriteSocialStream,
-    AndroidPermissionWriteSyncSettings,
-    AndroidPermissionWriteUserDictionary,
-    ComAndroidAlarmPermissionSetAlarm,
-    ComAndroidBrowserPermissionReadHistoryBookmarks,
-    ComAndroidBrowserPermissionWriteHistoryBookmarks,
-    ComAndroidEmailPermissionReadAttachment,
-    ComAndroidLauncherPermissionInstallShortcut,
-    ComAndroidLauncherPermissionPreloadWorkspace,
-    ComAndroidLauncherPermissionReadSettings,
-    ComAndroidLauncherPermissionUninstallShortcut,
-    ComAndroidLauncherPermissionWriteSettings,
-    ComAndroidVendingCheckLicense,
-    ComAndroidVoicemailPermissionAddVoicemail,
-    ComAndroidVoicemailPermissionReadVoicemail,
-    ComAndroidVoicemailPermissionReadWriteAllVoicemail,
-    ComAndroidVoicemailPermissionWriteVoicemail,
-    ComGoogleAndroidC2dmPermissionReceive,
-    ComGoogleAndroidC2dmPermissionSend,
-    ComGoogleAndroidGmsPermissionActivityRecognition,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuth,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAllServices,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthOtherServices,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutubeuser,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAdsense,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAdwords,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAh,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroid,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroidsecure,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthBlogger,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthCl,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthCp,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthDodgeball,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthDoraemon,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthFinance,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthGbase,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthGeowiki,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthGoannaMobile,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthGrandcentral,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthGroups2,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthHealth,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthIg,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthJotspot,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthKnol,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthLh2,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthLocal,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthMail,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthMobile,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthNews,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthNotebook,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthOrkut,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthPanoramio,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthPrint,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthReader,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSierra,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSierraqa,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSierrasandbox,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSitemaps,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeech,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeechpersonalization,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthTalk,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthWifi,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthWise,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthWritely,
-    ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutube,
-    ComGoogleAndroidGtalkservicePermissionGtalkService,
-    ComGoogleAndroidGtalkservicePermissionSendHeartbeat,
-    ComGoogleAndroidPermissionBroadcastDataMessage,
-    ComGoogleAndroidProvidersGsfPermissionReadGservices,
-    ComGoogleAndroidProvidersTalkPermissionReadOnly,
-    ComGoogleAndroidProvidersTalkPermissionWriteOnly,
-    ComGoogleAndroidXmppPermissionBroadcast,
-    ComGoogleAndroidXmppPermissionSendReceive,
-    ComGoogleAndroidXmppPermissionUseXmppEndpoint,
-    ComGoogleAndroidXmppPermissionXmppEndpointBroadcast,
+impl Borrow<str> for Permission {
+    fn borrow(&self) -> &str {
+        &self.name
+    }
 }
 
-impl<'de> Deserialize<'de> for Permission {
-    fn deserialize<D>(de: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        use failure::Fail;
-        use serde::de::Error;
-
-        let deser_result_str: String = serde::Deserialize::deserialize(de)?;
-
-        match Self::from_str(&deser_result_str) {
-            Ok(permission) => Ok(permission),
-            Err(e) => Err(Error::custom(
-                e.context(format!("unknown permission `{}`", deser_result_str)),
-            )),
-        }
-    }
-}
-
-impl Permission {
-    /// Gets the string representation of the permission.
-    pub fn as_str(&self) -> &str {
-        match self {
-            Permission::AndroidPermissionAccessAllExternalStorage => {
-                "android.permission.ACCESS_ALL_EXTERNAL_STORAGE"
-            }
-            Permission::AndroidPermissionAccessCheckinProperties => {
-                "android.permission.ACCESS_CHECKIN_PROPERTIES"
-            }
-            Permission::AndroidPermissionAccessCoarseLocation => {
-                "android.permission.ACCESS_COARSE_LOCATION"
-            }
-            Permission::AndroidPermissionAccessFineLocation => {
-                "android.permission.ACCESS_FINE_LOCATION"
-            }
-            Permission::AndroidPermissionAccessLocationExtraCommands => {
-                "android.permission.ACCESS_LOCATION_EXTRA_COMMANDS"
-            }
-            Permission::AndroidPermissionAccessMockLocation => {
-                "android.permission.ACCESS_MOCK_LOCATION"
-            }
-            Permission::AndroidPermissionAccessMtp => "android.permission.ACCESS_MTP",
-            Permission::AndroidPermissionAccessNetworkState => {
-                "android.permission.ACCESS_NETWORK_STATE"
-            }
-            Permission::AndroidPermissionAccessNotificationPolicy => {
-                "android.permission.ACCESS_NOTIFICATION_POLICY"
-            }
-            Permission::AndroidPermissionAccessWimaxState => {
-                "android.permission.ACCESS_WIMAX_STATE"
-            }
-            Permission::AndroidPermissionAccessWifiState => "android.permission.ACCESS_WIFI_STATE",
-            Permission::AndroidPermissionAccountManager => "android.permission.ACCOUNT_MANAGER",
-            Permission::AndroidPermissionAsecAccess => "android.permission.ASEC_ACCESS",
-            Permission::AndroidPermissionAsecCreate => "android.permission.ASEC_CREATE",
-            Permission::AndroidPermissionAsecDestroy => "android.permission.ASEC_DESTROY",
-            Permission::AndroidPermissionAsecMountUnmount => {
-                "android.permission.ASEC_MOUNT_UNMOUNT"
-            }
-            Permission::AndroidPermissionAsecRename => "android.permission.ASEC_RENAME",
-            Permission::AndroidPermissionAuthenticateAccounts => {
-                "android.permission.AUTHENTICATE_ACCOUNTS"
-            }
-            Permission::AndroidPermissionBatteryStats => "android.permission.BATTERY_STATS",
-            Permission::AndroidPermissionBindAccessibilityService => {
-                "android.permission.BIND_ACCESSIBILITY_SERVICE"
-            }
-            Permission::AndroidPermissionBindAppwidget => "android.permission.BIND_APPWIDGET",
-            Permission::AndroidPermissionBindCallService => "android.permission.BIND_CALL_SERVICE",
-            Permission::AndroidPermissionBindCarrierMessagingService => {
-                "android.permission.BIND_CARRIER_MESSAGING_SERVICE"
-            }
-            Permission::AndroidPermissionBindCarrierServices => {
-                "android.permission.BIND_CARRIER_SERVICES"
-            }
-            Permission::AndroidPermissionBindChooserTargetService => {
-                "android.permission.BIND_CHOOSER_TARGET_SERVICE"
-            }
-            Permission::AndroidPermissionBindDeviceAdmin => "android.permission.BIND_DEVICE_ADMIN",
-            Permission::AndroidPermissionBindDirectorySearch => {
-                "android.permission.BIND_DIRECTORY_SEARCH"
-            }
-            Permission::AndroidPermissionBindDreamService => {
-                "android.permission.BIND_DREAM_SERVICE"
-            }
-            Permission::AndroidPermissionBindIncallService => {
-                "android.permission.BIND_INCALL_SERVICE"
-            }
-            Permission::AndroidPermissionBindInputMethod => "android.permission.BIND_INPUT_METHOD",
-            Permission::AndroidPermissionBindKeyguardAppwidget => {
-                "android.permission.BIND_KEYGUARD_APPWIDGET"
-            }
-            Permission::AndroidPermissionBindMidiDeviceService => {
-                "android.permission.BIND_MIDI_DEVICE_SERVICE"
-            }
-            Permission::AndroidPermissionBindNfcService => "android.permission.BIND_NFC_SERVICE",
-            Permission::AndroidPermissionBindNotificationListenerService => {
-                "android.permission.BIND_NOTIFICATION_LISTENER_SERVICE"
-            }
-            Permission::AndroidPermissionBindPrintService => {
-                "android.permission.BIND_PRINT_SERVICE"
-            }
-            Permission::AndroidPermissionBindRemoteviews => "android.permission.BIND_REMOTEVIEWS",
-            Permission::AndroidPermissionBindTelecomConnectionService => {
-                "android.permission.BIND_TELECOM_CONNECTION_SERVICE"
-            }
-            Permission::AndroidPermissionBindTextService => "android.permission.BIND_TEXT_SERVICE",
-            Permission::AndroidPermissionBindTvInput => "android.permission.BIND_TV_INPUT",
-            Permission::AndroidPermissionBindVoiceInteraction => {
-                "android.permission.BIND_VOICE_INTERACTION"
-            }
-            Permission::AndroidPermissionBindVpnService => "android.permission.BIND_VPN_SERVICE",
-            Permission::AndroidPermissionBindWallpaper => "android.permission.BIND_WALLPAPER",
-            Permission::AndroidPermissionBluetooth => "android.permission.BLUETOOTH",
-            Permission::AndroidPermissionBluetoothAdmin => "android.permission.BLUETOOTH_ADMIN",
-            Permission::AndroidPermissionBluetoothPrivileged => {
-                "android.permission.BLUETOOTH_PRIVILEGED"
-            }
-            Permission::AndroidPermissionBluetoothStack => "android.permission.BLUETOOTH_STACK",
-            Permission::AndroidPermissionBodySensors => "android.permission.BODY_SENSORS",
-            Permission::AndroidPermissionBroadcastPackageRemoved => {
-                "android.permission.BROADCAST_PACKAGE_REMOVED"
-            }
-            Permission::AndroidPermissionBroadcastSms => "android.permission.BROADCAST_SMS",
-            Permission::AndroidPermissionBroadcastSticky => "android.permission.BROADCAST_STICKY",
-            Permission::AndroidPermissionBroadcastWapPush => {
-                "android.permission.BROADCAST_WAP_PUSH"
-            }
-            Permission::AndroidPermissionCallPhone => "android.permission.CALL_PHONE",
-            Permission::AndroidPermissionCallPrivileged => "android.permission.CALL_PRIVILEGED",
-            Permission::AndroidPermissionCamera => "android.permission.CAMERA",
-            Permission::AndroidPermissionCameraDisableTransmitLed => {
-                "android.permission.CAMERA_DISABLE_TRANSMIT_LED"
-            }
-            Permission::AndroidPermissionCaptureAudioOutput => {
-                "android.permission.CAPTURE_AUDIO_OUTPUT"
-            }
-            Permission::AndroidPermissionCaptureSecureVideoOutput => {
-                "android.permission.CAPTURE_SECURE_VIDEO_OUTPUT"
-            }
-            Permission::AndroidPermissionCaptureVideoOutput => {
-                "android.permission.CAPTURE_VIDEO_OUTPUT"
-            }
-            Permission::AndroidPermissionChangeBackgroundDataSetting => {
-                "android.permission.CHANGE_BACKGROUND_DATA_SETTING"
-            }
-            Permission::AndroidPermissionChangeComponentEnabledState => {
-                "android.permission.CHANGE_COMPONENT_ENABLED_STATE"
-            }
-            Permission::AndroidPermissionChangeConfiguration => {
-                "android.permission.CHANGE_CONFIGURATION"
-            }
-            Permission::AndroidPermissionChangeNetworkState => {
-                "android.permission.CHANGE_NETWORK_STATE"
-            }
-            Permission::AndroidPermissionChangeWimaxState => {
-                "android.permission.CHANGE_WIMAX_STATE"
-            }
-            Permission::AndroidPermissionChangeWifiMulticastState => {
-                "android.permission.CHANGE_WIFI_MULTICAST_STATE"
-            }
-            Permission::AndroidPermissionChangeWifiState => "android.permission.CHANGE_WIFI_STATE",
-            Permission::AndroidPermissionClearAppCache => "android.permission.CLEAR_APP_CACHE",
-            Permission::AndroidPermissionConnectivityInternal => {
-                "android.permission.CONNECTIVITY_INTERNAL"
-            }
-            Permission::AndroidPermissionControlLocationUpdates => {
-                "android.permission.CONTROL_LOCATION_UPDATES"
-            }
-            Permission::AndroidPermissionDeleteCacheFiles => {
-                "android.permission.DELETE_CACHE_FILES"
-            }
-            Permission::AndroidPermissionDeletePackages => "android.permission.DELETE_PACKAGES",
-            Permission::AndroidPermissionDiagnostic => "android.permission.DIAGNOSTIC",
-            Permission::AndroidPermissionDisableKeyguard => "android.permission.DISABLE_KEYGUARD",
-            Permission::AndroidPermissionDownloadWithoutNotification => {
-                "android.permission.DOWNLOAD_WITHOUT_NOTIFICATION"
-            }
-            Permission::AndroidPermissionDump => "android.permission.DUMP",
-            Permission::AndroidPermissionExpandStatusBar => "android.permission.EXPAND_STATUS_BAR",
-            Permission::AndroidPermissionFactoryTest => "android.permission.FACTORY_TEST",
-            Permission::AndroidPermissionFlashlight => "android.permission.FLASHLIGHT",
-            Permission::AndroidPermissionForceStopPackages => {
-                "android.permission.FORCE_STOP_PACKAGES"
-            }
-            Permission::AndroidPermissionGetAccounts => "android.permission.GET_ACCOUNTS",
-            Permission::AndroidPermissionGetAccountsPrivileged => {
-                "android.permission.GET_ACCOUNTS_PRIVILEGED"
-            }
-            Permission::AndroidPermissionGetAppOpsStats => "android.permission.GET_APP_OPS_STATS",
-            Permission::AndroidPermissionGetDetailedTasks => {
-                "android.permission.GET_DETAILED_TASKS"
-            }
-            Permission::AndroidPermissionGetPackageSize => "android.permission.GET_PACKAGE_SIZE",
-            Permission::AndroidPermissionGetTasks => "android.permission.GET_TASKS",
-            Permission::AndroidPermissionGlobalSearch => "android.permission.GLOBAL_SEARCH",
-            Permission::AndroidPermissionGlobalSearchControl => {
-                "android.permission.GLOBAL_SEARCH_CONTROL"
-            }
-            Permission::AndroidPermissionHardwareTest => "android.permission.HARDWARE_TEST",
-            Permission::AndroidPermissionInstallLocationProvider => {
-                "android.permission.INSTALL_LOCATION_PROVIDER"
-            }
-            Permission::AndroidPermissionInstallPackages => "android.permission.INSTALL_PACKAGES",
-            Permission::AndroidPermissionInteractAcrossUsers => {
-                "android.permission.INTERACT_ACROSS_USERS"
-            }
-            Permission::AndroidPermissionInteractAcrossUsersFull => {
-                "android.permission.INTERACT_ACROSS_USERS_FULL"
-            }
-            Permission::AndroidPermissionInternet => "android.permission.INTERNET",
-            Permission::AndroidPermissionKillBackgroundProcesses => {
-                "android.permission.KILL_BACKGROUND_PROCESSES"
-            }
-            Permission::AndroidPermissionLocationHardware => "android.permission.LOCATION_HARDWARE",
-            Permission::AndroidPermissionLoopRadio => "android.permission.LOOP_RADIO",
-            Permission::AndroidPermissionManageAccounts => "android.permission.MANAGE_ACCOUNTS",
-            Permission::AndroidPermissionManageActivityStacks => {
-                "android.permission.MANAGE_ACTIVITY_STACKS"
-            }
-            Permission::AndroidPermissionManageDocuments => "android.permission.MANAGE_DOCUMENTS",
-            Permission::AndroidPermissionManageUsb => "android.permission.MANAGE_USB",
-            Permission::AndroidPermissionManageUsers => "android.permission.MANAGE_USERS",
-            Permission::AndroidPermissionMasterClear => "android.permission.MASTER_CLEAR",
-            Permission::AndroidPermissionMediaContentControl => {
-                "android.permission.MEDIA_CONTENT_CONTROL"
-            }
-            Permission::AndroidPermissionModifyAppwidgetBindPermissions => {
-                "android.permission.MODIFY_APPWIDGET_BIND_PERMISSIONS"
-            }
-            Permission::AndroidPermissionModifyAudioSettings => {
-                "android.permission.MODIFY_AUDIO_SETTINGS"
-            }
-            Permission::AndroidPermissionModifyPhoneState => {
-                "android.permission.MODIFY_PHONE_STATE"
-            }
-            Permission::AndroidPermissionMountFormatFilesystems => {
-                "android.permission.MOUNT_FORMAT_FILESYSTEMS"
-            }
-            Permission::AndroidPermissionMountUnmountFilesystems => {
-                "android.permission.MOUNT_UNMOUNT_FILESYSTEMS"
-            }
-            Permission::AndroidPermissionNetAdmin => "android.permission.NET_ADMIN",
-            Permission::AndroidPermissionNetTunneling => "android.permission.NET_TUNNELING",
-            Permission::AndroidPermissionNfc => "android.permission.NFC",
-            Permission::AndroidPermissionPackageUsageStats => {
-                "android.permission.PACKAGE_USAGE_STATS"
-            }
-            Permission::AndroidPermissionPersistentActivity => {
-                "android.permission.PERSISTENT_ACTIVITY"
-            }
-            Permission::AndroidPermissionProcessOutgoingCalls => {
-                "android.permission.PROCESS_OUTGOING_CALLS"
-            }
-            Permission::AndroidPermissionReadCalendar => "android.permission.READ_CALENDAR",
-            Permission::AndroidPermissionReadCallLog => "android.permission.READ_CALL_LOG",
-            Permission::AndroidPermissionReadCellBroadcasts => {
-                "android.permission.READ_CELL_BROADCASTS"
-            }
-            Permission::AndroidPermissionReadContacts => "android.permission.READ_CONTACTS",
-            Permission::AndroidPermissionReadDreamState => "android.permission.READ_DREAM_STATE",
-            Permission::AndroidPermissionReadExternalStorage => {
-                "android.permission.READ_EXTERNAL_STORAGE"
-            }
-            Permission::AndroidPermissionReadFrameBuffer => "android.permission.READ_FRAME_BUFFER",
-            Permission::AndroidPermissionReadInputState => "android.permission.READ_INPUT_STATE",
-            Permission::AndroidPermissionReadLogs => "android.permission.READ_LOGS",
-            Permission::AndroidPermissionReadPhoneState => "android.permission.READ_PHONE_STATE",
-            Permission::AndroidPermissionReadPrivilegedPhoneState => {
-                "android.permission.READ_PRIVILEGED_PHONE_STATE"
-            }
-            Permission::AndroidPermissionReadProfile => "android.permission.READ_PROFILE",
-            Permission::AndroidPermissionReadSms => "android.permission.READ_SMS",
-            Permission::AndroidPermissionReadSocialStream => {
-                "android.permission.READ_SOCIAL_STREAM"
-            }
-            Permission::AndroidPermissionReadSyncSettings => {
-                "android.permission.READ_SYNC_SETTINGS"
-            }
-            Permission::AndroidPermissionReadSyncStats => "android.permission.READ_SYNC_STATS",
-            Permission::AndroidPermissionReadUserDictionary => {
-                "android.permission.READ_USER_DICTIONARY"
-            }
-            Permission::AndroidPermissionReboot => "android.permission.REBOOT",
-            Permission::AndroidPermissionReceiveBootCompleted => {
-                "android.permission.RECEIVE_BOOT_COMPLETED"
-            }
-            Permission::AndroidPermissionReceiveDataActivityChange => {
-                "android.permission.RECEIVE_DATA_ACTIVITY_CHANGE"
-            }
-            Permission::AndroidPermissionReceiveEmergencyBroadcast => {
-                "android.permission.RECEIVE_EMERGENCY_BROADCAST"
-            }
-            Permission::AndroidPermissionReceiveMms => "android.permission.RECEIVE_MMS",
-            Permission::AndroidPermissionReceiveSms => "android.permission.RECEIVE_SMS",
-            Permission::AndroidPermissionReceiveWapPush => "android.permission.RECEIVE_WAP_PUSH",
-            Permission::AndroidPermissionRecordAudio => "android.permission.RECORD_AUDIO",
-            Permission::AndroidPermissionRemoteAudioPlayback => {
-                "android.permission.REMOTE_AUDIO_PLAYBACK"
-            }
-            Permission::AndroidPermissionRemoveTasks => "android.permission.REMOVE_TASKS",
-            Permission::AndroidPermissionReorderTasks => "android.permission.REORDER_TASKS",
-            Permission::AndroidPermissionRequestIgnoreBatteryOptimizations => {
-                "android.permission.REQUEST_IGNORE_BATTERY_OPTIMIZATIONS"
-            }
-            Permission::AndroidPermissionRequestInstallPackages => {
-                "android.permission.REQUEST_INSTALL_PACKAGES"
-            }
-            Permission::AndroidPermissionRestartPackages => "android.permission.RESTART_PACKAGES",
-            Permission::AndroidPermissionRetrieveWindowContent => {
-                "android.permission.RETRIEVE_WINDOW_CONTENT"
-            }
-            Permission::AndroidPermissionSendRespondViaMessage => {
-                "android.permission.SEND_RESPOND_VIA_MESSAGE"
-            }
-            Permission::AndroidPermissionSendSms => "android.permission.SEND_SMS",
-            Permission::AndroidPermissionSetAlwaysFinish => "android.permission.SET_ALWAYS_FINISH",
-            Permission::AndroidPermissionSetAnimationScale => {
-                "android.permission.SET_ANIMATION_SCALE"
-            }
-            Permission::AndroidPermissionSetDebugApp => "android.permission.SET_DEBUG_APP",
-            Permission::AndroidPermissionSetPreferredApplications => {
-                "android.permission.SET_PREFERRED_APPLICATIONS"
-            }
-            Permission::AndroidPermissionSetProcessLimit => "android.permission.SET_PROCESS_LIMIT",
-            Permission::AndroidPermissionSetScreenCompatibility => {
-                "android.permission.SET_SCREEN_COMPATIBILITY"
-            }
-            Permission::AndroidPermissionSetTime => "android.permission.SET_TIME",
-            Permission::AndroidPermissionSetTimeZone => "android.permission.SET_TIME_ZONE",
-            Permission::AndroidPermissionSetWallpaper => "android.permission.SET_WALLPAPER",
-            Permission::AndroidPermissionSetWallpaperComponent => {
-                "android.permission.SET_WALLPAPER_COMPONENT"
-            }
-            Permission::AndroidPermissionSetWallpaperHints => {
-                "android.permission.SET_WALLPAPER_HINTS"
-            }
-            Permission::AndroidPermissionSignalPersistentProcesses => {
-                "android.permission.SIGNAL_PERSISTENT_PROCESSES"
-            }
-            Permission::AndroidPermissionStartAnyActivity => {
-                "android.permission.START_ANY_ACTIVITY"
-            }
-            Permission::AndroidPermissionStatusBar => "android.permission.STATUS_BAR",
-            Permission::AndroidPermissionSubscribedFeedsRead => {
-                "android.permission.SUBSCRIBED_FEEDS_READ"
-            }
-            Permission::AndroidPermissionSystemAlertWindow => {
-                "android.permission.SYSTEM_ALERT_WINDOW"
-            }
-            Permission::AndroidPermissionSubscribedFeedsWrite => {
-                "android.permission.SUBSCRIBED_FEEDS_WRITE"
-            }
-            Permission::AndroidPermissionTransmitIr => "android.permission.TRANSMIT_IR",
-            Permission::AndroidPermissionUpdateDeviceStats => {
-                "android.permission.UPDATE_DEVICE_STATS"
-            }
-            Permission::AndroidPermissionUseCredentials => "android.permission.USE_CREDENTIALS",
-            Permission::AndroidPermissionUseFingerprint => "android.permission.USE_FINGERPRINT",
-            Permission::AndroidPermissionUseSip => "android.permission.USE_SIP",
-            Permission::AndroidPermissionVibrate => "android.permission.VIBRATE",
-            Permission::AndroidPermissionWakeLock => "android.permission.WAKE_LOCK",
-            Permission::AndroidPermissionWriteApnSettings => {
-                "android.permission.WRITE_APN_SETTINGS"
-            }
-            Permission::AndroidPermissionWriteCalendar => "android.permission.WRITE_CALENDAR",
-            Permission::AndroidPermissionWriteCallLog => "android.permission.WRITE_CALL_LOG",
-            Permission::AndroidPermissionWriteContacts => "android.permission.WRITE_CONTACTS",
-            Permission::AndroidPermissionWriteDreamState => "android.permission.WRITE_DREAM_STATE",
-            Permission::AndroidPermissionWriteExternalStorage => {
-                "android.permission.WRITE_EXTERNAL_STORAGE"
-            }
-            Permission::AndroidPermissionWriteGservices => "android.permission.WRITE_GSERVICES",
-            Permission::AndroidPermissionWriteMediaStorage => {
-                "android.permission.WRITE_MEDIA_STORAGE"
-            }
-            Permission::AndroidPermissionWriteProfile => "android.permission.WRITE_PROFILE",
-            Permission::AndroidPermissionWriteSecureSettings => {
-                "android.permission.WRITE_SECURE_SETTINGS"
-            }
-            Permission::AndroidPermissionWriteSettings => "android.permission.WRITE_SETTINGS",
-            Permission::AndroidPermissionWriteSms => "android.permission.WRITE_SMS",
-            Permission::AndroidPermissionWriteSocialStream => {
-                "android.permission.WRITE_SOCIAL_STREAM"
-            }
-            Permission::AndroidPermissionWriteSyncSettings => {
-                "android.permission.WRITE_SYNC_SETTINGS"
-            }
-            Permission::AndroidPermissionWriteUserDictionary => {
-                "android.permission.WRITE_USER_DICTIONARY"
-            }
-            Permission::ComAndroidAlarmPermissionSetAlarm => {
-                "com.android.alarm.permission.SET_ALARM"
-            }
-            Permission::ComAndroidBrowserPermissionReadHistoryBookmarks => {
-                "com.android.browser.permission.READ_HISTORY_BOOKMARKS"
-            }
-            Permission::ComAndroidBrowserPermissionWriteHistoryBookmarks => {
-                "com.android.browser.permission.WRITE_HISTORY_BOOKMARKS"
-            }
-            Permission::ComAndroidEmailPermissionReadAttachment => {
-                "com.android.email.permission.READ_ATTACHMENT"
-            }
-            Permission::ComAndroidLauncherPermissionInstallShortcut => {
-                "com.android.launcher.permission.INSTALL_SHORTCUT"
-            }
-            Permission::ComAndroidLauncherPermissionPreloadWorkspace => {
-                "com.android.launcher.permission.PRELOAD_WORKSPACE"
-            }
-            Permission::ComAndroidLauncherPermissionReadSettings => {
-                "com.android.launcher.permission.READ_SETTINGS"
-            }
-            Permission::ComAndroidLauncherPermissionUninstallShortcut => {
-                "com.android.launcher.permission.UNINSTALL_SHORTCUT"
-            }
-            Permission::ComAndroidLauncherPermissionWriteSettings => {
-                "com.android.launcher.permission.WRITE_SETTINGS"
-            }
-            Permission::ComAndroidVendingCheckLicense => "com.android.vending.CHECK_LICENSE",
-            Permission::ComAndroidVoicemailPermissionAddVoicemail => {
-                "com.android.voicemail.permission.ADD_VOICEMAIL"
-            }
-            Permission::ComAndroidVoicemailPermissionReadVoicemail => {
-                "com.android.voicemail.permission.READ_VOICEMAIL"
-            }
-            Permission::ComAndroidVoicemailPermissionReadWriteAllVoicemail => {
-                "com.android.voicemail.permission.READ_WRITE_ALL_VOICEMAIL"
-            }
-            Permission::ComAndroidVoicemailPermissionWriteVoicemail => {
-                "com.android.voicemail.permission.WRITE_VOICEMAIL"
-            }
-            Permission::ComGoogleAndroidC2dmPermissionReceive => {
-                "com.google.android.c2dm.permission.RECEIVE"
-            }
-            Permission::ComGoogleAndroidC2dmPermissionSend => {
-                "com.google.android.c2dm.permission.SEND"
-            }
-            Permission::ComGoogleAndroidGmsPermissionActivityRecognition => {
-                "com.google.android.gms.permission.ACTIVITY_RECOGNITION"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuth => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAllServices => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.ALL_SERVICES"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOtherServices => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.OTHER_SERVICES"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutubeuser => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.YouTubeUser"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdsense => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.adsense"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdwords => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.adwords"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAh => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.ah"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroid => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.android"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroidsecure => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.androidsecure"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthBlogger => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.blogger"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCl => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.cl"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCp => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.cp"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDodgeball => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.dodgeball"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDoraemon => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.doraemon"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthFinance => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.finance"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGbase => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.gbase"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGeowiki => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.geowiki"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGoannaMobile => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.goanna_mobile"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGrandcentral => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.grandcentral"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGroups2 => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.groups2"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthHealth => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.health"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthIg => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.ig"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthJotspot => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.jotspot"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthKnol => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.knol"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLh2 => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.lh2"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLocal => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.local"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMail => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.mail"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMobile => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.mobile"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNews => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.news"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNotebook => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.notebook"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOrkut => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.orkut"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPanoramio => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.panoramio"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPrint => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.print"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthReader => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.reader"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierra => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.sierra"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierraqa => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.sierraqa"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierrasandbox => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.sierrasandbox"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSitemaps => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.sitemaps"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeech => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.speech"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeechpersonalization => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.speechpersonalization"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthTalk => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.talk"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWifi => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.wifi"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWise => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.wise"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWritely => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.writely"
-            }
-            Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutube => {
-                "com.google.android.googleapps.permission.GOOGLE_AUTH.youtube"
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionGtalkService => {
-                "com.google.android.gtalkservice.permission.GTALK_SERVICE"
-            }
-            Permission::ComGoogleAndroidGtalkservicePermissionSendHeartbeat => {
-                "com.google.android.gtalkservice.permission.SEND_HEARTBEAT"
-            }
-            Permission::ComGoogleAndroidPermissionBroadcastDataMessage => {
-                "com.google.android.permission.BROADCAST_DATA_MESSAGE"
-            }
-            Permission::ComGoogleAndroidProvidersGsfPermissionReadGservices => {
-                "com.google.android.providers.gsf.permission.READ_GSERVICES"
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionReadOnly => {
-                "com.google.android.providers.talk.permission.READ_ONLY"
-            }
-            Permission::ComGoogleAndroidProvidersTalkPermissionWriteOnly => {
-                "com.google.android.providers.talk.permission.WRITE_ONLY"
-            }
-            Permission::ComGoogleAndroidXmppPermissionBroadcast => {
-                "com.google.android.xmpp.permission.BROADCAST"
-            }
-            Permission::ComGoogleAndroidXmppPermissionSendReceive => {
-                "com.google.android.xmpp.permission.SEND_RECEIVE"
-            }
-            Permission::ComGoogleAndroidXmppPermissionUseXmppEndpoint => {
-                "com.google.android.xmpp.permission.USE_XMPP_ENDPOINT"
-            }
-            Permission::ComGoogleAndroidXmppPermissionXmppEndpointBroadcast => {
-                "com.google.android.xmpp.permission.XMPP_ENDPOINT_BROADCAST"
-            }
-        }
+impl Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)
     }
 }
 
 impl FromStr for Permission {
     type Err = error::Kind;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "android.permission.ACCESS_ALL_EXTERNAL_STORAGE" => {
-                Ok(Permission::AndroidPermissionAccessAllExternalStorage)
-            }
-            "android.permission.ACCESS_CHECKIN_PROPERTIES" => {
-                Ok(Permission::AndroidPermissionAccessCheckinProperties)
-            }
-            "android.permission.ACCESS_COARSE_LOCATION" => {
-                Ok(Permission::AndroidPermissionAccessCoarseLocation)
-            }
-            "android.permission.ACCESS_FINE_LOCATION" => {
-                Ok(Permission::AndroidPermissionAccessFineLocation)
-            }
-            "android.permission.ACCESS_LOCATION_EXTRA_COMMANDS" => {
-                Ok(Permission::AndroidPermissionAccessLocationExtraCommands)
-            }
-            "android.permission.ACCESS_MOCK_LOCATION" => {
-                Ok(Permission::AndroidPermissionAccessMockLocation)
-            }
-            "android.permission.ACCESS_MTP" => Ok(Permission::AndroidPermissionAccessMtp),
-            "android.permission.ACCESS_NETWORK_STATE" => {
-                Ok(Permission::AndroidPermissionAccessNetworkState)
-            }
-            "android.permission.ACCESS_NOTIFICATION_POLICY" => {
-                Ok(Permission::AndroidPermissionAccessNotificationPolicy)
-            }
-            "android.permission.ACCESS_WIMAX_STATE" => {
-                Ok(Permission::AndroidPermissionAccessWimaxState)
-            }
-            "android.permission.ACCESS_WIFI_STATE" => {
-                Ok(Permission::AndroidPermissionAccessWifiState)
-            }
-            "android.permission.ACCOUNT_MANAGER" => Ok(Permission::AndroidPermissionAccountManager),
-            "android.permission.ASEC_ACCESS" => Ok(Permission::AndroidPermissionAsecAccess),
-            "android.permission.ASEC_CREATE" => Ok(Permission::AndroidPermissionAsecCreate),
-            "android.permission.ASEC_DESTROY" => Ok(Permission::AndroidPermissionAsecDestroy),
-            "android.permission.ASEC_MOUNT_UNMOUNT" => {
-                Ok(Permission::AndroidPermissionAsecMountUnmount)
-            }
-            "android.permission.ASEC_RENAME" => Ok(Permission::AndroidPermissionAsecRename),
-            "android.permission.AUTHENTICATE_ACCOUNTS" => {
-                Ok(Permission::AndroidPermissionAuthenticateAccounts)
-            }
-            "android.permission.BATTERY_STATS" => Ok(Permission::AndroidPermissionBatteryStats),
-            "android.permission.BIND_ACCESSIBILITY_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindAccessibilityService)
-            }
-            "android.permission.BIND_APPWIDGET" => Ok(Permission::AndroidPermissionBindAppwidget),
-            "android.permission.BIND_CALL_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindCallService)
-            }
-            "android.permission.BIND_CARRIER_MESSAGING_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindCarrierMessagingService)
-            }
-            "android.permission.BIND_CARRIER_SERVICES" => {
-                Ok(Permission::AndroidPermissionBindCarrierServices)
-            }
-            "android.permission.BIND_CHOOSER_TARGET_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindChooserTargetService)
-            }
-            "android.permission.BIND_DEVICE_ADMIN" => {
-                Ok(Permission::AndroidPermissionBindDeviceAdmin)
-            }
-            "android.permission.BIND_DIRECTORY_SEARCH" => {
-                Ok(Permission::AndroidPermissionBindDirectorySearch)
-            }
-            "android.permission.BIND_DREAM_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindDreamService)
-            }
-            "android.permission.BIND_INCALL_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindIncallService)
-            }
-            "android.permission.BIND_INPUT_METHOD" => {
-                Ok(Permission::AndroidPermissionBindInputMethod)
-            }
-            "android.permission.BIND_KEYGUARD_APPWIDGET" => {
-                Ok(Permission::AndroidPermissionBindKeyguardAppwidget)
-            }
-            "android.permission.BIND_MIDI_DEVICE_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindMidiDeviceService)
-            }
-            "android.permission.BIND_NFC_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindNfcService)
-            }
-            "android.permission.BIND_NOTIFICATION_LISTENER_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindNotificationListenerService)
-            }
-            "android.permission.BIND_PRINT_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindPrintService)
-            }
-            "android.permission.BIND_REMOTEVIEWS" => {
-                Ok(Permission::AndroidPermissionBindRemoteviews)
-            }
-            "android.permission.BIND_TELECOM_CONNECTION_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindTelecomConnectionService)
-            }
-            "android.permission.BIND_TEXT_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindTextService)
-            }
-            "android.permission.BIND_TV_INPUT" => Ok(Permission::AndroidPermissionBindTvInput),
-            "android.permission.BIND_VOICE_INTERACTION" => {
-                Ok(Permission::AndroidPermissionBindVoiceInteraction)
-            }
-            "android.permission.BIND_VPN_SERVICE" => {
-                Ok(Permission::AndroidPermissionBindVpnService)
-            }
-            "android.permission.BIND_WALLPAPER" => Ok(Permission::AndroidPermissionBindWallpaper),
-            "android.permission.BLUETOOTH" => Ok(Permission::AndroidPermissionBluetooth),
-            "android.permission.BLUETOOTH_ADMIN" => Ok(Permission::AndroidPermissionBluetoothAdmin),
-            "android.permission.BLUETOOTH_PRIVILEGED" => {
-                Ok(Permission::AndroidPermissionBluetoothPrivileged)
-            }
-            "android.permission.BLUETOOTH_STACK" => Ok(Permission::AndroidPermissionBluetoothStack),
-            "android.permission.BODY_SENSORS" => Ok(Permission::AndroidPermissionBodySensors),
-            "android.permission.BROADCAST_PACKAGE_REMOVED" => {
-                Ok(Permission::AndroidPermissionBroadcastPackageRemoved)
-            }
-            "android.permission.BROADCAST_SMS" => Ok(Permission::AndroidPermissionBroadcastSms),
-            "android.permission.BROADCAST_STICKY" => {
-                Ok(Permission::AndroidPermissionBroadcastSticky)
-            }
-            "android.permission.BROADCAST_WAP_PUSH" => {
-                Ok(Permission::AndroidPermissionBroadcastWapPush)
-            }
-            "android.permission.CALL_PHONE" => Ok(Permission::AndroidPermissionCallPhone),
-            "android.permission.CALL_PRIVILEGED" => Ok(Permission::AndroidPermissionCallPrivileged),
-            "android.permission.CAMERA" => Ok(Permission::AndroidPermissionCamera),
-            "android.permission.CAMERA_DISABLE_TRANSMIT_LED" => {
-                Ok(Permission::AndroidPermissionCameraDisableTransmitLed)
-            }
-            "android.permission.CAPTURE_AUDIO_OUTPUT" => {
-                Ok(Permission::AndroidPermissionCaptureAudioOutput)
-            }
-            "android.permission.CAPTURE_SECURE_VIDEO_OUTPUT" => {
-                Ok(Permission::AndroidPermissionCaptureSecureVideoOutput)
-            }
-            "android.permission.CAPTURE_VIDEO_OUTPUT" => {
-                Ok(Permission::AndroidPermissionCaptureVideoOutput)
-            }
-            "android.permission.CHANGE_BACKGROUND_DATA_SETTING" => {
-                Ok(Permission::AndroidPermissionChangeBackgroundDataSetting)
-            }
-            "android.permission.CHANGE_COMPONENT_ENABLED_STATE" => {
-                Ok(Permission::AndroidPermissionChangeComponentEnabledState)
-            }
-            "android.permission.CHANGE_CONFIGURATION" => {
-                Ok(Permission::AndroidPermissionChangeConfiguration)
-            }
-            "android.permission.CHANGE_NETWORK_STATE" => {
-                Ok(Permission::AndroidPermissionChangeNetworkState)
-            }
-            "android.permission.CHANGE_WIMAX_STATE" => {
-                Ok(Permission::AndroidPermissionChangeWimaxState)
-            }
-            "android.permission.CHANGE_WIFI_MULTICAST_STATE" => {
-                Ok(Permission::AndroidPermissionChangeWifiMulticastState)
-            }
-            "android.permission.CHANGE_WIFI_STATE" => {
-                Ok(Permission::AndroidPermissionChangeWifiState)
-            }
-            "android.permission.CLEAR_APP_CACHE" => Ok(Permission::AndroidPermissionClearAppCache),
-            "android.permission.CONNECTIVITY_INTERNAL" => {
-                Ok(Permission::AndroidPermissionConnectivityInternal)
-            }
-            "android.permission.CONTROL_LOCATION_UPDATES" => {
-                Ok(Permission::AndroidPermissionControlLocationUpdates)
-            }
-            "android.permission.DELETE_CACHE_FILES" => {
-                Ok(Permission::AndroidPermissionDeleteCacheFiles)
-            }
-            "android.permission.DELETE_PACKAGES" => Ok(Permission::AndroidPermissionDeletePackages),
-            "android.permission.DIAGNOSTIC" => Ok(Permission::AndroidPermissionDiagnostic),
-            "android.permission.DISABLE_KEYGUARD" => {
-                Ok(Permission::AndroidPermissionDisableKeyguard)
-            }
-            "android.permission.DOWNLOAD_WITHOUT_NOTIFICATION" => {
-                Ok(Permission::AndroidPermissionDownloadWithoutNotification)
-            }
-            "android.permission.DUMP" => Ok(Permission::AndroidPermissionDump),
-            "android.permission.EXPAND_STATUS_BAR" => {
-                Ok(Permission::AndroidPermissionExpandStatusBar)
-            }
-            "android.permission.FACTORY_TEST" => Ok(Permission::AndroidPermissionFactoryTest),
-            "android.permission.FLASHLIGHT" => Ok(Permission::AndroidPermissionFlashlight),
-            "android.permission.FORCE_STOP_PACKAGES" => {
-                Ok(Permission::AndroidPermissionForceStopPackages)
-            }
-            "android.permission.GET_ACCOUNTS" => Ok(Permission::AndroidPermissionGetAccounts),
-            "android.permission.GET_ACCOUNTS_PRIVILEGED" => {
-                Ok(Permission::AndroidPermissionGetAccountsPrivileged)
-            }
-            "android.permission.GET_APP_OPS_STATS" => {
-                Ok(Permission::AndroidPermissionGetAppOpsStats)
-            }
-            "android.permission.GET_DETAILED_TASKS" => {
-                Ok(Permission::AndroidPermissionGetDetailedTasks)
-            }
-            "android.permission.GET_PACKAGE_SIZE" => {
-                Ok(Permission::AndroidPermissionGetPackageSize)
-            }
-            "android.permission.GET_TASKS" => Ok(Permission::AndroidPermissionGetTasks),
-            "android.permission.GLOBAL_SEARCH" => Ok(Permission::AndroidPermissionGlobalSearch),
-            "android.permission.GLOBAL_SEARCH_CONTROL" => {
-                Ok(Permission::AndroidPermissionGlobalSearchControl)
-            }
-            "android.permission.HARDWARE_TEST" => Ok(Permission::AndroidPermissionHardwareTest),
-            "android.permission.INSTALL_LOCATION_PROVIDER" => {
-                Ok(Permission::AndroidPermissionInstallLocationProvider)
-            }
-            "android.permission.INSTALL_PACKAGES" => {
-                Ok(Permission::AndroidPermissionInstallPackages)
-            }
-            "android.permission.INTERACT_ACROSS_USERS" => {
-                Ok(Permission::AndroidPermissionInteractAcrossUsers)
-            }
-            "android.permission.INTERACT_ACROSS_USERS_FULL" => {
-                Ok(Permission::AndroidPermissionInteractAcrossUsersFull)
-            }
-            "android.permission.INTERNET" => Ok(Permission::AndroidPermissionInternet),
-            "android.permission.KILL_BACKGROUND_PROCESSES" => {
-                Ok(Permission::AndroidPermissionKillBackgroundProcesses)
-            }
-            "android.permission.LOCATION_HARDWARE" => {
-                Ok(Permission::AndroidPermissionLocationHardware)
-            }
-            "android.permission.LOOP_RADIO" => Ok(Permission::AndroidPermissionLoopRadio),
-            "android.permission.MANAGE_ACCOUNTS" => Ok(Permission::AndroidPermissionManageAccounts),
-            "android.permission.MANAGE_ACTIVITY_STACKS" => {
-                Ok(Permission::AndroidPermissionManageActivityStacks)
-            }
-            "android.permission.MANAGE_DOCUMENTS" => {
-                Ok(Permission::AndroidPermissionManageDocuments)
-            }
-            "android.permission.MANAGE_USB" => Ok(Permission::AndroidPermissionManageUsb),
-            "android.permission.MANAGE_USERS" => Ok(Permission::AndroidPermissionManageUsers),
-            "android.permission.MASTER_CLEAR" => Ok(Permission::AndroidPermissionMasterClear),
-            "android.permission.MEDIA_CONTENT_CONTROL" => {
-                Ok(Permission::AndroidPermissionMediaContentControl)
-            }
-            "android.permission.MODIFY_APPWIDGET_BIND_PERMISSIONS" => {
-                Ok(Permission::AndroidPermissionModifyAppwidgetBindPermissions)
-            }
-            "android.permission.MODIFY_AUDIO_SETTINGS" => {
-                Ok(Permission::AndroidPermissionModifyAudioSettings)
-            }
-            "android.permission.MODIFY_PHONE_STATE" => {
-                Ok(Permission::AndroidPermissionModifyPhoneState)
-            }
-            "android.permission.MOUNT_FORMAT_FILESYSTEMS" => {
-                Ok(Permission::AndroidPermissionMountFormatFilesystems)
-            }
-            "android.permission.MOUNT_UNMOUNT_FILESYSTEMS" => {
-                Ok(Permission::AndroidPermissionMountUnmountFilesystems)
-            }
-            "android.permission.NET_ADMIN" => Ok(Permission::AndroidPermissionNetAdmin),
-            "android.permission.NET_TUNNELING" => Ok(Permission::AndroidPermissionNetTunneling),
-            "android.permission.NFC" => Ok(Permission::AndroidPermissionNfc),
-            "android.permission.PACKAGE_USAGE_STATS" => {
-                Ok(Permission::AndroidPermissionPackageUsageStats)
-            }
-            "android.permission.PERSISTENT_ACTIVITY" => {
-                Ok(Permission::AndroidPermissionPersistentActivity)
-            }
-            "android.permission.PROCESS_OUTGOING_CALLS" => {
-                Ok(Permission::AndroidPermissionProcessOutgoingCalls)
-            }
-            "android.permission.READ_CALENDAR" => Ok(Permission::AndroidPermissionReadCalendar),
-            "android.permission.READ_CALL_LOG" => Ok(Permission::AndroidPermissionReadCallLog),
-            "android.permission.READ_CELL_BROADCASTS" => {
-                Ok(Permission::AndroidPermissionReadCellBroadcasts)
-            }
-            "android.permission.READ_CONTACTS" => Ok(Permission::AndroidPermissionReadContacts),
-            "android.permission.READ_DREAM_STATE" => {
-                Ok(Permission::AndroidPermissionReadDreamState)
-            }
-            "android.permission.READ_EXTERNAL_STORAGE" => {
-                Ok(Permission::AndroidPermissionReadExternalStorage)
-            }
-            "android.permission.READ_FRAME_BUFFER" => {
-                Ok(Permission::AndroidPermissionReadFrameBuffer)
-            }
-            "android.permission.READ_INPUT_STATE" => {
-                Ok(Permission::AndroidPermissionReadInputState)
-            }
-            "android.permission.READ_LOGS" => Ok(Permission::AndroidPermissionReadLogs),
-            "android.permission.READ_PHONE_STATE" => {
-                Ok(Permission::AndroidPermissionReadPhoneState)
-            }
-            "android.permission.READ_PRIVILEGED_PHONE_STATE" => {
-                Ok(Permission::AndroidPermissionReadPrivilegedPhoneState)
-            }
-            "android.permission.READ_PROFILE" => Ok(Permission::AndroidPermissionReadProfile),
-            "android.permission.READ_SMS" => Ok(Permission::AndroidPermissionReadSms),
-            "android.permission.READ_SOCIAL_STREAM" => {
-                Ok(Permission::AndroidPermissionReadSocialStream)
-            }
-            "android.permission.READ_SYNC_SETTINGS" => {
-                Ok(Permission::AndroidPermissionReadSyncSettings)
-            }
-            "android.permission.READ_SYNC_STATS" => Ok(Permission::AndroidPermissionReadSyncStats),
-            "android.permission.READ_USER_DICTIONARY" => {
-                Ok(Permission::AndroidPermissionReadUserDictionary)
-            }
-            "android.permission.REBOOT" => Ok(Permission::AndroidPermissionReboot),
-            "android.permission.RECEIVE_BOOT_COMPLETED" => {
-                Ok(Permission::AndroidPermissionReceiveBootCompleted)
-            }
-            "android.permission.RECEIVE_DATA_ACTIVITY_CHANGE" => {
-                Ok(Permission::AndroidPermissionReceiveDataActivityChange)
-            }
-            "android.permission.RECEIVE_EMERGENCY_BROADCAST" => {
-                Ok(Permission::AndroidPermissionReceiveEmergencyBroadcast)
-            }
-            "android.permission.RECEIVE_MMS" => Ok(Permission::AndroidPermissionReceiveMms),
-            "android.permission.RECEIVE_SMS" => Ok(Permission::AndroidPermissionReceiveSms),
-            "android.permission.RECEIVE_WAP_PUSH" => {
-                Ok(Permission::AndroidPermissionReceiveWapPush)
-            }
-            "android.permission.RECORD_AUDIO" => Ok(Permission::AndroidPermissionRecordAudio),
-            "android.permission.REMOTE_AUDIO_PLAYBACK" => {
-                Ok(Permission::AndroidPermissionRemoteAudioPlayback)
-            }
-            "android.permission.REMOVE_TASKS" => Ok(Permission::AndroidPermissionRemoveTasks),
-            "android.permission.REORDER_TASKS" => Ok(Permission::AndroidPermissionReorderTasks),
-            "android.permission.REQUEST_IGNORE_BATTERY_OPTIMIZATIONS" => {
-                Ok(Permission::AndroidPermissionRequestIgnoreBatteryOptimizations)
-            }
-            "android.permission.REQUEST_INSTALL_PACKAGES" => {
-                Ok(Permission::AndroidPermissionRequestInstallPackages)
-            }
-            "android.permission.RESTART_PACKAGES" => {
-                Ok(Permission::AndroidPermissionRestartPackages)
-            }
-            "android.permission.RETRIEVE_WINDOW_CONTENT" => {
-                Ok(Permission::AndroidPermissionRetrieveWindowContent)
-            }
-            "android.permission.SEND_RESPOND_VIA_MESSAGE" => {
-                Ok(Permission::AndroidPermissionSendRespondViaMessage)
-            }
-            "android.permission.SEND_SMS" => Ok(Permission::AndroidPermissionSendSms),
-            "android.permission.SET_ALWAYS_FINISH" => {
-                Ok(Permission::AndroidPermissionSetAlwaysFinish)
-            }
-            "android.permission.SET_ANIMATION_SCALE" => {
-                Ok(Permission::AndroidPermissionSetAnimationScale)
-            }
-            "android.permission.SET_DEBUG_APP" => Ok(Permission::AndroidPermissionSetDebugApp),
-            "android.permission.SET_PREFERRED_APPLICATIONS" => {
-                Ok(Permission::AndroidPermissionSetPreferredApplications)
-            }
-            "android.permission.SET_PROCESS_LIMIT" => {
-                Ok(Permission::AndroidPermissionSetProcessLimit)
-            }
-            "android.permission.SET_SCREEN_COMPATIBILITY" => {
-                Ok(Permission::AndroidPermissionSetScreenCompatibility)
-            }
-            "android.permission.SET_TIME" => Ok(Permission::AndroidPermissionSetTime),
-            "android.permission.SET_TIME_ZONE" => Ok(Permission::AndroidPermissionSetTimeZone),
-            "android.permission.SET_WALLPAPER" => Ok(Permission::AndroidPermissionSetWallpaper),
-            "android.permission.SET_WALLPAPER_COMPONENT" => {
-                Ok(Permission::AndroidPermissionSetWallpaperComponent)
-            }
-            "android.permission.SET_WALLPAPER_HINTS" => {
-                Ok(Permission::AndroidPermissionSetWallpaperHints)
-            }
-            "android.permission.SIGNAL_PERSISTENT_PROCESSES" => {
-                Ok(Permission::AndroidPermissionSignalPersistentProcesses)
-            }
-            "android.permission.START_ANY_ACTIVITY" => {
-                Ok(Permission::AndroidPermissionStartAnyActivity)
-            }
-            "android.permission.STATUS_BAR" => Ok(Permission::AndroidPermissionStatusBar),
-            "android.permission.SUBSCRIBED_FEEDS_READ" => {
-                Ok(Permission::AndroidPermissionSubscribedFeedsRead)
-            }
-            "android.permission.SYSTEM_ALERT_WINDOW" => {
-                Ok(Permission::AndroidPermissionSystemAlertWindow)
-            }
-            "android.permission.SUBSCRIBED_FEEDS_WRITE" => {
-                Ok(Permission::AndroidPermissionSubscribedFeedsWrite)
-            }
-            "android.permission.TRANSMIT_IR" => Ok(Permission::AndroidPermissionTransmitIr),
-            "android.permission.UPDATE_DEVICE_STATS" => {
-                Ok(Permission::AndroidPermissionUpdateDeviceStats)
-            }
-            "android.permission.USE_CREDENTIALS" => Ok(Permission::AndroidPermissionUseCredentials),
-            "android.permission.USE_FINGERPRINT" => Ok(Permission::AndroidPermissionUseFingerprint),
-            "android.permission.USE_SIP" => Ok(Permission::AndroidPermissionUseSip),
-            "android.permission.VIBRATE" => Ok(Permission::AndroidPermissionVibrate),
-            "android.permission.WAKE_LOCK" => Ok(Permission::AndroidPermissionWakeLock),
-            "android.permission.WRITE_APN_SETTINGS" => {
-                Ok(Permission::AndroidPermissionWriteApnSettings)
-            }
-            "android.permission.WRITE_CALENDAR" => Ok(Permission::AndroidPermissionWriteCalendar),
-            "android.permission.WRITE_CALL_LOG" => Ok(Permission::AndroidPermissionWriteCallLog),
-            "android.permission.WRITE_CONTACTS" => Ok(Permission::AndroidPermissionWriteContacts),
-            "android.permission.WRITE_DREAM_STATE" => {
-                Ok(Permission::AndroidPermissionWriteDreamState)
-            }
-            "android.permission.WRITE_EXTERNAL_STORAGE" => {
-                Ok(Permission::AndroidPermissionWriteExternalStorage)
-            }
-            "android.permission.WRITE_GSERVICES" => Ok(Permission::AndroidPermissionWriteGservices),
-            "android.permission.WRITE_MEDIA_STORAGE" => {
-                Ok(Permission::AndroidPermissionWriteMediaStorage)
-            }
-            "android.permission.WRITE_PROFILE" => Ok(Permission::AndroidPermissionWriteProfile),
-            "android.permission.WRITE_SECURE_SETTINGS" => {
-                Ok(Permission::AndroidPermissionWriteSecureSettings)
-            }
-            "android.permission.WRITE_SETTINGS" => Ok(Permission::AndroidPermissionWriteSettings),
-            "android.permission.WRITE_SMS" => Ok(Permission::AndroidPermissionWriteSms),
-            "android.permission.WRITE_SOCIAL_STREAM" => {
-                Ok(Permission::AndroidPermissionWriteSocialStream)
-            }
-            "android.permission.WRITE_SYNC_SETTINGS" => {
-                Ok(Permission::AndroidPermissionWriteSyncSettings)
-            }
-            "android.permission.WRITE_USER_DICTIONARY" => {
-                Ok(Permission::AndroidPermissionWriteUserDictionary)
-            }
-            "com.android.alarm.permission.SET_ALARM" => {
-                Ok(Permission::ComAndroidAlarmPermissionSetAlarm)
-            }
-            "com.android.browser.permission.READ_HISTORY_BOOKMARKS" => {
-                Ok(Permission::ComAndroidBrowserPermissionReadHistoryBookmarks)
-            }
-            "com.android.browser.permission.WRITE_HISTORY_BOOKMARKS" => {
-                Ok(Permission::ComAndroidBrowserPermissionWriteHistoryBookmarks)
-            }
-            "com.android.email.permission.READ_ATTACHMENT" => {
-                Ok(Permission::ComAndroidEmailPermissionReadAttachment)
-            }
-            "com.android.launcher.permission.INSTALL_SHORTCUT" => {
-                Ok(Permission::ComAndroidLauncherPermissionInstallShortcut)
-            }
-            "com.android.launcher.permission.PRELOAD_WORKSPACE" => {
-                Ok(Permission::ComAndroidLauncherPermissionPreloadWorkspace)
-            }
-            "com.android.launcher.permission.READ_SETTINGS" => {
-                Ok(Permission::ComAndroidLauncherPermissionReadSettings)
-            }
-            "com.android.launcher.permission.UNINSTALL_SHORTCUT" => {
-                Ok(Permission::ComAndroidLauncherPermissionUninstallShortcut)
-            }
-            "com.android.launcher.permission.WRITE_SETTINGS" => {
-                Ok(Permission::ComAndroidLauncherPermissionWriteSettings)
-            }
-            "com.android.vending.CHECK_LICENSE" => Ok(Permission::ComAndroidVendingCheckLicense),
-            "com.android.voicemail.permission.ADD_VOICEMAIL" => {
-                Ok(Permission::ComAndroidVoicemailPermissionAddVoicemail)
-            }
-            "com.android.voicemail.permission.READ_VOICEMAIL" => {
-                Ok(Permission::ComAndroidVoicemailPermissionReadVoicemail)
-            }
-            "com.android.voicemail.permission.READ_WRITE_ALL_VOICEMAIL" => {
-                Ok(Permission::ComAndroidVoicemailPermissionReadWriteAllVoicemail)
-            }
-            "com.android.voicemail.permission.WRITE_VOICEMAIL" => {
-                Ok(Permission::ComAndroidVoicemailPermissionWriteVoicemail)
-            }
-            "com.google.android.c2dm.permission.RECEIVE" => {
-                Ok(Permission::ComGoogleAndroidC2dmPermissionReceive)
-            }
-            "com.google.android.c2dm.permission.SEND" => {
-                Ok(Permission::ComGoogleAndroidC2dmPermissionSend)
-            }
-            "com.google.android.gms.permission.ACTIVITY_RECOGNITION" => {
-                Ok(Permission::ComGoogleAndroidGmsPermissionActivityRecognition)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuth)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.ALL_SERVICES" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAllServices)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.OTHER_SERVICES" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOtherServices)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.YouTubeUser" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutubeuser)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.adsense" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdsense)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.adwords" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAdwords)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.ah" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAh)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.android" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroid)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.androidsecure" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthAndroidsecure)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.blogger" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthBlogger)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.cl" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCl)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.cp" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthCp)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.dodgeball" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDodgeball)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.doraemon" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthDoraemon)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.finance" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthFinance)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.gbase" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGbase)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.geowiki" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGeowiki)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.goanna_mobile" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGoannaMobile)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.grandcentral" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGrandcentral)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.groups2" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthGroups2)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.health" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthHealth)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.ig" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthIg)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.jotspot" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthJotspot)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.knol" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthKnol)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.lh2" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLh2)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.local" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthLocal)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.mail" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMail)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.mobile" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthMobile)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.news" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNews)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.notebook" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthNotebook)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.orkut" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthOrkut)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.panoramio" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPanoramio)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.print" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthPrint)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.reader" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthReader)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.sierra" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierra)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.sierraqa" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierraqa)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.sierrasandbox" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSierrasandbox)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.sitemaps" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSitemaps)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.speech" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeech)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.speechpersonalization" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthSpeechpersonalization)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.talk" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthTalk)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.wifi" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWifi)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.wise" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWise)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.writely" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthWritely)
-            }
-            "com.google.android.googleapps.permission.GOOGLE_AUTH.youtube" => {
-                Ok(Permission::ComGoogleAndroidGoogleappsPermissionGoogleAuthYoutube)
-            }
-            "com.google.android.gtalkservice.permission.GTALK_SERVICE" => {
-                Ok(Permission::ComGoogleAndroidGtalkservicePermissionGtalkService)
-            }
-            "com.google.android.gtalkservice.permission.SEND_HEARTBEAT" => {
-                Ok(Permission::ComGoogleAndroidGtalkservicePermissionSendHeartbeat)
-            }
-            "com.google.android.permission.BROADCAST_DATA_MESSAGE" => {
-                Ok(Permission::ComGoogleAndroidPermissionBroadcastDataMessage)
-            }
-            "com.google.android.providers.gsf.permission.READ_GSERVICES" => {
-                Ok(Permission::ComGoogleAndroidProvidersGsfPermissionReadGservices)
-            }
-            "com.google.android.providers.talk.permission.READ_ONLY" => {
-                Ok(Permission::ComGoogleAndroidProvidersTalkPermissionReadOnly)
-            }
-            "com.google.android.providers.talk.permission.WRITE_ONLY" => {
-                Ok(Permission::ComGoogleAndroidProvidersTalkPermissionWriteOnly)
-            }
-            "com.google.android.xmpp.permission.BROADCAST" => {
-                Ok(Permission::ComGoogleAndroidXmppPermissionBroadcast)
-            }
-            "com.google.android.xmpp.permission.SEND_RECEIVE" => {
-                Ok(Permission::ComGoogleAndroidXmppPermissionSendReceive)
-            }
-            "com.google.android.xmpp.permission.USE_XMPP_ENDPOINT" => {
-                Ok(Permission::ComGoogleAndroidXmppPermissionUseXmppEndpoint)
-            }
-            "com.google.android.xmpp.permission.XMPP_ENDPOINT_BROADCAST" => {
-                Ok(Permission::ComGoogleAndroidXmppPermissionXmppEndpointBroadcast)
-            }
-            _ => Err(error::Kind::Parse),
+        let is_identifier = |segment: &str| {
+            let mut chars = segment.chars();
+            chars
+                .next()
+                .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+
+        if s.contains('.') && s.split('.').all(is_identifier) {
+            Ok(Self { name: s.to_owned() })
+        } else {
+            Err(error::Kind::Parse)
         }
     }
 }
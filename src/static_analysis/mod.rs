@@ -3,43 +3,168 @@
 //! The static analysis of the application's source files is used to search for vulnerable
 //! code, settings and any other form of implementation that might be used as an exploit.
 
+pub mod analyzer;
+pub mod ast;
+pub mod backup;
+pub mod binary_strings;
+pub mod build_type;
 #[cfg(feature = "certificate")]
 pub mod certificate;
 pub mod code;
+pub mod dataflow;
+pub mod dependencies;
+pub mod endpoints;
+pub mod firebase;
+pub(crate) mod forward_check;
+pub mod frameworks;
+pub mod libraries;
+pub(crate) mod location;
 pub mod manifest;
+pub mod native;
+pub mod permission_usage;
+pub mod resilience;
+pub mod secrets;
+pub mod strings;
 
 #[cfg(feature = "certificate")]
 use self::certificate::certificate_analysis;
+use std::{sync::Arc, time::Instant};
+
+use crate::{
+    i18n::tr,
+    print_message,
+    progress::{self, Phase},
+    results::Results,
+    Config,
+};
 #[cfg(feature = "certificate")]
-use crate::print_warning;
-use crate::{results::Results, Config};
+use crate::{print_warning, results::StageStatus};
 
 /// Runs the analysis for manifest, certificate and code files.
 ///
 /// * Benchmarking support.
+/// * Custom analyzers, registered with [`analyzer::register_analyzer()`], run at the end.
 pub fn static_analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
     if config.is_verbose() {
-        println!(
-            "It's time to analyze the application. First, a static analysis will be performed, \
-             starting with the AndroidManifest.xml file and then going through the actual code. \
-             Let's start!"
-        );
+        print_message(tr(
+            "It's time to analyze the application. First, a static analysis will be \
+             performed, starting with the AndroidManifest.xml file and then going through the \
+             actual code. Let's start!",
+        ));
     }
 
+    let manifest_start = Instant::now();
+    progress::phase_started(package.as_ref(), Phase::Manifest);
+
     // Run analysis for manifest file.
     let manifest = manifest::analysis(config, package.as_ref(), results);
 
     #[cfg(feature = "certificate")]
     {
         // Run analysis for certificate file.
-        if let Err(e) = certificate_analysis(config, package.as_ref(), results) {
-            print_warning(format!(
-                "there was an error analyzing the certificate: {}",
-                e
-            ))
-        }
+        let status = match certificate_analysis(config, package.as_ref(), results) {
+            Ok(()) => StageStatus::Ok,
+            Err(e) => {
+                print_warning(format!(
+                    "there was an error analyzing the certificate: {}",
+                    e
+                ));
+                StageStatus::failed(e)
+            }
+        };
+        results.set_stage_status("certificate", status);
+    }
+
+    // Detect the cross-platform frameworks used to build the application.
+    frameworks::analysis(config, package.as_ref(), results);
+
+    // Detect the bundled libraries.
+    libraries::analysis(config, package.as_ref(), results);
+
+    // Look for known vulnerabilities of the bundled libraries.
+    dependencies::analysis(config, results);
+
+    // Check the string resources of every locale.
+    strings::analysis(config, package.as_ref(), results);
+
+    // Look for hardcoded secrets in the code, resources and assets.
+    secrets::analysis(config, package.as_ref(), results);
+
+    // Extract the strings of the Flutter and .NET binaries, for endpoints and secrets.
+    if config.is_native_analysis() {
+        binary_strings::analysis(config, package.as_ref(), results);
+    }
+
+    // Check the Firebase configuration, and its databases and buckets if enabled.
+    firebase::analysis(config, package.as_ref(), results);
+
+    // Collect the network endpoints of the string resources and assets.
+    endpoints::analysis(config, package.as_ref(), results);
+
+    // Check the hardening of the native libraries.
+    if config.is_native_analysis() {
+        native::analysis(config, package.as_ref(), results);
+    }
+
+    // Look for signs of a debug build.
+    build_type::analysis(config, package.as_ref(), manifest.as_ref(), results);
+
+    // Run the manifest hooks of the user scripts.
+    #[cfg(feature = "scripting")]
+    let scripts = crate::scripting::Scripts::load(config, package.as_ref());
+    #[cfg(feature = "scripting")]
+    scripts.on_manifest(manifest.as_ref(), results);
+
+    // Run the analysis plugins.
+    #[cfg(feature = "plugins")]
+    crate::plugins::analysis(config, package.as_ref(), manifest.as_ref(), results);
+
+    progress::phase_finished(package.as_ref(), Phase::Manifest, manifest_start.elapsed());
+
+    if !config.is_decompilation() {
+        #[cfg(feature = "scripting")]
+        scripts.on_analysis(results);
+        analyzer::analysis(config, package.as_ref(), manifest.as_ref(), results);
+        return;
     }
 
     // Run analysis for source code files.
-    code::analysis(manifest, config, package.as_ref(), results)
+    let code_start = Instant::now();
+    progress::phase_started(package.as_ref(), Phase::Code);
+    let requested_permissions = manifest.as_ref().map(|manifest| {
+        (
+            manifest
+                .requested_permissions()
+                .cloned()
+                .collect::<Vec<_>>(),
+            manifest.code().to_owned(),
+        )
+    });
+    let manifest = Arc::new(manifest);
+    code::analysis(Arc::clone(&manifest), config, package.as_ref(), results);
+
+    // Correlate the requested permissions with the APIs used in the code.
+    if let Some((requested, manifest_code)) = requested_permissions {
+        permission_usage::analysis(
+            config,
+            package.as_ref(),
+            &requested,
+            &manifest_code,
+            results,
+        );
+    }
+
+    // Search the code for root, emulator and debugger detection and signature verification.
+    resilience::analysis(config, package.as_ref(), results);
+    progress::phase_finished(package.as_ref(), Phase::Code, code_start.elapsed());
+
+    // Run the file and post-analysis hooks of the user scripts.
+    #[cfg(feature = "scripting")]
+    {
+        scripts.on_files(package.as_ref(), results);
+        scripts.on_analysis(results);
+    }
+
+    // Run the custom analyzers.
+    analyzer::analysis(config, package.as_ref(), (*manifest).as_ref(), results);
 }
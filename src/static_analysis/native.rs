@@ -0,0 +1,631 @@
+//! Native library analysis module.
+//!
+//! Applications can ship native code as ELF shared libraries in the `lib/<abi>` folders. This
+//! module parses them and checks the usual hardening measures of the toolchain: position
+//! independent code, stack canaries, RELRO, non-executable stacks and stripped debug symbols. It
+//! also reports the imports of libc functions that are easy to misuse, such as `strcpy()` or
+//! `system()`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use failure::{bail, format_err, Error};
+
+use crate::{
+    criticality::Criticality,
+    print_message, print_vulnerability, print_warning,
+    results::{Results, StageStatus, Vulnerability},
+    Config,
+};
+
+/// Magic number of ELF files.
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// `ET_DYN` object file type, for shared objects and position independent executables.
+const ET_DYN: u16 = 3;
+
+/// `PT_DYNAMIC` program header type.
+const PT_DYNAMIC: u32 = 2;
+/// `PT_GNU_STACK` program header type.
+const PT_GNU_STACK: u32 = 0x6474_e551;
+/// `PT_GNU_RELRO` program header type.
+const PT_GNU_RELRO: u32 = 0x6474_e552;
+/// Executable segment flag.
+const PF_X: u32 = 1;
+
+/// `SHT_SYMTAB` section type.
+const SHT_SYMTAB: u32 = 2;
+/// `SHT_DYNSYM` section type.
+const SHT_DYNSYM: u32 = 11;
+
+/// `DT_BIND_NOW` dynamic entry tag.
+const DT_BIND_NOW: u64 = 24;
+/// `DT_FLAGS` dynamic entry tag.
+const DT_FLAGS: u64 = 30;
+/// `DT_FLAGS_1` dynamic entry tag.
+const DT_FLAGS_1: u64 = 0x6fff_fffb;
+/// `DF_BIND_NOW` flag of the `DT_FLAGS` entry.
+const DF_BIND_NOW: u64 = 0x8;
+/// `DF_1_NOW` flag of the `DT_FLAGS_1` entry.
+const DF_1_NOW: u64 = 0x1;
+
+/// Functions that are easy to misuse, leading to buffer overflows or command injections.
+const DANGEROUS_FUNCTIONS: [&str; 10] = [
+    "gets", "strcpy", "strcat", "sprintf", "vsprintf", "system", "popen", "execl", "execlp",
+    "execvp",
+];
+
+/// RELRO protection of a library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relro {
+    /// The relocations are writable.
+    None,
+    /// Some relocations are read-only, but the GOT is still writable.
+    Partial,
+    /// All relocations are resolved at load time and read-only.
+    Full,
+}
+
+/// Hardening information of a native library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeLibrary {
+    /// Whether the library is position independent.
+    pie: bool,
+    /// Whether the library uses stack canaries.
+    stack_canary: bool,
+    /// RELRO protection of the library.
+    relro: Relro,
+    /// Whether the stack of the library is non-executable.
+    nx: bool,
+    /// Whether the library contains debug symbols.
+    debug_symbols: bool,
+    /// Imported functions that are easy to misuse.
+    dangerous_imports: Vec<String>,
+}
+
+impl NativeLibrary {
+    /// Parses the given ELF file.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let elf = Elf::new(data)?;
+        let mut library = Self {
+            pie: elf.u16(16)? == ET_DYN,
+            stack_canary: false,
+            relro: Relro::None,
+            nx: false,
+            debug_symbols: false,
+            dangerous_imports: Vec::new(),
+        };
+
+        let (ph_offset, ph_size, ph_count) = if elf.is_64 {
+            (elf.u64(32)?, elf.u16(54)?, elf.u16(56)?)
+        } else {
+            (u64::from(elf.u32(28)?), elf.u16(42)?, elf.u16(44)?)
+        };
+        let mut has_relro = false;
+        let mut dynamic = None;
+        for i in 0..usize::from(ph_count) {
+            let header = elf.entry(to_usize(ph_offset)?, i, usize::from(ph_size))?;
+            let flags = elf.u32(header + if elf.is_64 { 4 } else { 24 })?;
+            match elf.u32(header)? {
+                PT_GNU_STACK => library.nx = flags & PF_X == 0,
+                PT_GNU_RELRO => has_relro = true,
+                PT_DYNAMIC => {
+                    dynamic = Some(if elf.is_64 {
+                        (elf.u64(header + 8)?, elf.u64(header + 32)?)
+                    } else {
+                        (
+                            u64::from(elf.u32(header + 4)?),
+                            u64::from(elf.u32(header + 16)?),
+                        )
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        if has_relro {
+            library.relro = match dynamic {
+                Some((offset, size)) if elf.binds_now(offset, size)? => Relro::Full,
+                _ => Relro::Partial,
+            };
+        }
+
+        let sections = elf.sections()?;
+        for section in &sections {
+            let name = elf.section_name(&sections, section).unwrap_or_default();
+            if name.starts_with(".debug_") || section.kind == SHT_SYMTAB {
+                library.debug_symbols = true;
+            }
+            if section.kind != SHT_DYNSYM {
+                continue;
+            }
+            for (symbol, undefined) in elf.symbols(&sections, section)? {
+                if symbol == "__stack_chk_fail" || symbol == "__stack_chk_guard" {
+                    library.stack_canary = true;
+                } else if undefined && DANGEROUS_FUNCTIONS.contains(&symbol.as_str()) {
+                    library.dangerous_imports.push(symbol);
+                }
+            }
+        }
+        library.dangerous_imports.sort();
+        library.dangerous_imports.dedup();
+
+        Ok(library)
+    }
+
+    /// Returns whether the library is position independent.
+    pub fn is_pie(&self) -> bool {
+        self.pie
+    }
+
+    /// Returns whether the library uses stack canaries.
+    pub fn has_stack_canary(&self) -> bool {
+        self.stack_canary
+    }
+
+    /// Gets the RELRO protection of the library.
+    pub fn relro(&self) -> Relro {
+        self.relro
+    }
+
+    /// Returns whether the stack of the library is non-executable.
+    pub fn is_nx(&self) -> bool {
+        self.nx
+    }
+
+    /// Returns whether the library contains debug symbols.
+    pub fn has_debug_symbols(&self) -> bool {
+        self.debug_symbols
+    }
+
+    /// Gets the imported functions that are easy to misuse.
+    pub fn dangerous_imports(&self) -> &[String] {
+        &self.dangerous_imports
+    }
+}
+
+/// Section header of an ELF file.
+#[derive(Debug, Clone, Copy)]
+struct Section {
+    /// Offset of the name of the section in the section name table.
+    name: u32,
+    /// Type of the section.
+    kind: u32,
+    /// Offset of the section in the file.
+    offset: u64,
+    /// Size of the section.
+    size: u64,
+    /// Index of the linked section, the string table for symbol tables.
+    link: u32,
+    /// Size of each entry of the section.
+    entry_size: u64,
+}
+
+/// ELF file reader.
+#[derive(Debug)]
+struct Elf<'d> {
+    /// Contents of the file.
+    data: &'d [u8],
+    /// Whether the file is a 64-bit ELF file.
+    is_64: bool,
+    /// Whether the file is little endian.
+    little_endian: bool,
+}
+
+impl<'d> Elf<'d> {
+    /// Creates a reader for the given ELF file.
+    fn new(data: &'d [u8]) -> Result<Self, Error> {
+        if !data.starts_with(ELF_MAGIC) || data.len() < 0x34 {
+            bail!("not an ELF file");
+        }
+        let is_64 = match data[4] {
+            1 => false,
+            2 => true,
+            class => bail!("unknown ELF class {}", class),
+        };
+        let little_endian = match data[5] {
+            1 => true,
+            2 => false,
+            encoding => bail!("unknown ELF data encoding {}", encoding),
+        };
+        Ok(Self {
+            data,
+            is_64,
+            little_endian,
+        })
+    }
+
+    /// Reads the bytes at the given offset.
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'d [u8], Error> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| format_err!("the ELF file is truncated at offset {}", offset))
+    }
+
+    /// Gets the offset of the entry with the given index of the table at the given offset,
+    /// checking that the whole entry is in the file.
+    fn entry(&self, table: usize, index: usize, entry_size: usize) -> Result<usize, Error> {
+        let offset = index
+            .checked_mul(entry_size)
+            .and_then(|offset| offset.checked_add(table))
+            .ok_or_else(|| format_err!("the ELF file is truncated at offset {}", table))?;
+        let _ = self.bytes(offset, entry_size)?;
+        Ok(offset)
+    }
+
+    /// Reads an integer of the given number of bytes at the given offset.
+    fn uint(&self, offset: usize, len: usize) -> Result<u64, Error> {
+        let bytes = self.bytes(offset, len)?;
+        let fold = |value: u64, byte: &u8| value << 8 | u64::from(*byte);
+        Ok(if self.little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        })
+    }
+
+    /// Reads a 16-bit integer at the given offset.
+    fn u16(&self, offset: usize) -> Result<u16, Error> {
+        Ok(self.uint(offset, 2)? as u16)
+    }
+
+    /// Reads a 32-bit integer at the given offset.
+    fn u32(&self, offset: usize) -> Result<u32, Error> {
+        Ok(self.uint(offset, 4)? as u32)
+    }
+
+    /// Reads a 64-bit integer at the given offset.
+    fn u64(&self, offset: usize) -> Result<u64, Error> {
+        self.uint(offset, 8)
+    }
+
+    /// Reads an address-sized integer at the given offset.
+    fn word(&self, offset: usize) -> Result<u64, Error> {
+        self.uint(offset, if self.is_64 { 8 } else { 4 })
+    }
+
+    /// Reads the null-terminated string at the given offset.
+    fn string(&self, offset: usize) -> Result<String, Error> {
+        let tail = self
+            .data
+            .get(offset..)
+            .ok_or_else(|| format_err!("invalid string offset {} in the ELF file", offset))?;
+        let end = tail
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or_else(|| tail.len());
+        Ok(String::from_utf8_lossy(&tail[..end]).into_owned())
+    }
+
+    /// Reads the section headers.
+    fn sections(&self) -> Result<Vec<Section>, Error> {
+        let (offset, size, count) = if self.is_64 {
+            (self.u64(40)?, self.u16(58)?, self.u16(60)?)
+        } else {
+            (u64::from(self.u32(32)?), self.u16(46)?, self.u16(48)?)
+        };
+        (0..usize::from(count))
+            .map(|i| {
+                let header = self.entry(to_usize(offset)?, i, usize::from(size))?;
+                Ok(if self.is_64 {
+                    Section {
+                        name: self.u32(header)?,
+                        kind: self.u32(header + 4)?,
+                        offset: self.u64(header + 24)?,
+                        size: self.u64(header + 32)?,
+                        link: self.u32(header + 40)?,
+                        entry_size: self.u64(header + 56)?,
+                    }
+                } else {
+                    Section {
+                        name: self.u32(header)?,
+                        kind: self.u32(header + 4)?,
+                        offset: u64::from(self.u32(header + 16)?),
+                        size: u64::from(self.u32(header + 20)?),
+                        link: self.u32(header + 24)?,
+                        entry_size: u64::from(self.u32(header + 36)?),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Gets the name of the given section.
+    fn section_name(&self, sections: &[Section], section: &Section) -> Option<String> {
+        let index = usize::from(self.u16(if self.is_64 { 62 } else { 50 }).ok()?);
+        let names = sections.get(index)?;
+        self.string(
+            to_usize(names.offset)
+                .ok()?
+                .checked_add(section.name as usize)?,
+        )
+        .ok()
+    }
+
+    /// Reads the symbols of the given symbol table, with whether they are undefined.
+    fn symbols(
+        &self,
+        sections: &[Section],
+        section: &Section,
+    ) -> Result<Vec<(String, bool)>, Error> {
+        let strings = sections
+            .get(section.link as usize)
+            .ok_or_else(|| format_err!("invalid string table for the ELF symbol table"))?;
+        let entry_size = to_usize(section.entry_size)?;
+        if entry_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        (0..to_usize(section.size)? / entry_size)
+            .map(|i| {
+                let entry = self.entry(to_usize(section.offset)?, i, entry_size)?;
+                let name = self.u32(entry)?;
+                let index = self.u16(entry + if self.is_64 { 6 } else { 14 })?;
+                let name_offset = to_usize(strings.offset)?
+                    .checked_add(name as usize)
+                    .ok_or_else(|| format_err!("invalid string offset {} in the ELF file", name))?;
+                Ok((self.string(name_offset)?, index == 0))
+            })
+            .collect()
+    }
+
+    /// Checks if the dynamic section at the given offset requests binding all symbols at load
+    /// time.
+    fn binds_now(&self, offset: u64, size: u64) -> Result<bool, Error> {
+        let entry_size = if self.is_64 { 16 } else { 8 };
+        for i in 0..to_usize(size)? / entry_size {
+            let entry = self.entry(to_usize(offset)?, i, entry_size)?;
+            let tag = self.word(entry)?;
+            let value = self.word(entry + entry_size / 2)?;
+            match tag {
+                0 => break,
+                DT_BIND_NOW => return Ok(true),
+                DT_FLAGS if value & DF_BIND_NOW != 0 => return Ok(true),
+                DT_FLAGS_1 if value & DF_1_NOW != 0 => return Ok(true),
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Converts an offset or a size of the ELF file to `usize`.
+fn to_usize(value: u64) -> Result<usize, Error> {
+    if value > usize::max_value() as u64 {
+        bail!("invalid offset {} in the ELF file", value);
+    }
+    Ok(value as usize)
+}
+
+/// Analyzes the native libraries of the application.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut files = Vec::new();
+    if let Err(e) = find_libraries(dist_folder.join("lib"), &mut files) {
+        print_warning(format!(
+            "there was an error listing the native libraries: {}",
+            e
+        ));
+        results.set_stage_status("native", StageStatus::failed(e));
+        return;
+    }
+
+    let mut status = StageStatus::Ok;
+    for path in &files {
+        let file = path.strip_prefix(&dist_folder).unwrap_or(path);
+        let library = match fs::read(path)
+            .map_err(Error::from)
+            .and_then(|data| NativeLibrary::parse(&data))
+        {
+            Ok(library) => library,
+            Err(e) => {
+                print_warning(format!(
+                    "could not analyze the native library `{}`: {}",
+                    file.display(),
+                    e
+                ));
+                status = StageStatus::failed(e);
+                continue;
+            }
+        };
+        report(config, file, &library, results);
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} native libraries were analyzed.",
+            format!("{}", files.len()).bold()
+        ));
+    }
+    results.set_stage_status("native", status);
+}
+
+/// Finds the ELF files in the given folder, recursively.
+fn find_libraries(folder: PathBuf, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(folder)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            find_libraries(path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "so") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Reports the missing hardening measures of the given library.
+fn report(config: &Config, file: &Path, library: &NativeLibrary, results: &mut Results) {
+    let name = file
+        .file_name()
+        .map_or_else(|| file.to_string_lossy(), |name| name.to_string_lossy());
+    let mut issues = Vec::new();
+    if !library.is_nx() {
+        issues.push((
+            Criticality::Medium,
+            "Executable stack in native library",
+            format!(
+                "The native library `{}` has an executable stack, so memory corruption bugs can \
+                 be exploited by injecting code in the stack. It should be linked with \
+                 `-z noexecstack`.",
+                name
+            ),
+            "native-nx",
+            Some(119),
+        ));
+    }
+    if !library.is_pie() {
+        issues.push((
+            Criticality::Medium,
+            "Native library not position independent",
+            format!(
+                "The native library `{}` is not position independent, so ASLR cannot randomize \
+                 its addresses. It should be compiled with `-fPIC` and linked as a shared object.",
+                name
+            ),
+            "native-pie",
+            Some(119),
+        ));
+    }
+    if !library.has_stack_canary() {
+        issues.push((
+            Criticality::Low,
+            "Native library without stack canaries",
+            format!(
+                "The native library `{}` does not use stack canaries, so stack buffer overflows \
+                 will not be detected. It should be compiled with `-fstack-protector-strong`.",
+                name
+            ),
+            "native-stack-canary",
+            Some(121),
+        ));
+    }
+    if library.relro() != Relro::Full {
+        issues.push((
+            Criticality::Low,
+            "Native library without full RELRO",
+            format!(
+                "The native library `{}` has {} RELRO, so its global offset table can be \
+                 overwritten to hijack the control flow. It should be linked with `-z relro -z \
+                 now`.",
+                name,
+                if library.relro() == Relro::Partial {
+                    "partial"
+                } else {
+                    "no"
+                }
+            ),
+            "native-relro",
+            None,
+        ));
+    }
+    if library.has_debug_symbols() {
+        issues.push((
+            Criticality::Warning,
+            "Debug symbols in native library",
+            format!(
+                "The native library `{}` is not stripped, so its symbols and debug information \
+                 make reverse engineering easier.",
+                name
+            ),
+            "native-debug-symbols",
+            None,
+        ));
+    }
+    if !library.dangerous_imports().is_empty() {
+        issues.push((
+            if library
+                .dangerous_imports()
+                .iter()
+                .any(|import| import == "system" || import == "popen" || import == "gets")
+            {
+                Criticality::Medium
+            } else {
+                Criticality::Low
+            },
+            "Dangerous functions in native library",
+            format!(
+                "The native library `{}` imports functions that are easy to misuse: {}. Check \
+                 that they are not used with untrusted input, and replace them with their bounded \
+                 or safer alternatives.",
+                name,
+                library.dangerous_imports().join(", ")
+            ),
+            "native-dangerous-functions",
+            Some(676),
+        ));
+    }
+
+    for (criticality, title, description, rule_id, cwe) in issues {
+        if criticality < config.min_criticality() {
+            continue;
+        }
+        let mut builder = Vulnerability::builder(criticality, title, description.as_str())
+            .file(file)
+            .rule_id(rule_id)
+            .evidence(name.as_ref());
+        if let Some(cwe) = cwe {
+            builder = builder.cwe(cwe);
+        }
+        print_vulnerability(&description, criticality);
+        results.add_vulnerability(builder.build());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NativeLibrary, Relro, PT_GNU_RELRO, PT_GNU_STACK};
+
+    /// Builds a minimal 64-bit little endian ELF file with the given type and program headers.
+    fn elf(kind: u16, program_headers: &[(u32, u32)]) -> Vec<u8> {
+        let mut data = vec![0; 64];
+        data[..4].copy_from_slice(b"\x7fELF");
+        data[4] = 2;
+        data[5] = 1;
+        data[16..18].copy_from_slice(&kind.to_le_bytes());
+        data[32..40].copy_from_slice(&64_u64.to_le_bytes());
+        data[54..56].copy_from_slice(&56_u16.to_le_bytes());
+        data[56..58].copy_from_slice(&(program_headers.len() as u16).to_le_bytes());
+        for &(kind, flags) in program_headers {
+            let mut header = vec![0; 56];
+            header[..4].copy_from_slice(&kind.to_le_bytes());
+            header[4..8].copy_from_slice(&flags.to_le_bytes());
+            data.extend(header);
+        }
+        data
+    }
+
+    /// Checks the parsing of the hardening information of ELF files.
+    #[test]
+    fn it_parse() {
+        let hardened =
+            NativeLibrary::parse(&elf(3, &[(PT_GNU_STACK, 6), (PT_GNU_RELRO, 4)])).unwrap();
+        assert!(hardened.is_pie());
+        assert!(hardened.is_nx());
+        assert_eq!(hardened.relro(), Relro::Partial);
+        assert!(!hardened.has_stack_canary());
+        assert!(!hardened.has_debug_symbols());
+        assert!(hardened.dangerous_imports().is_empty());
+
+        let weak = NativeLibrary::parse(&elf(2, &[(PT_GNU_STACK, 7)])).unwrap();
+        assert!(!weak.is_pie());
+        assert!(!weak.is_nx());
+        assert_eq!(weak.relro(), Relro::None);
+
+        assert!(NativeLibrary::parse(b"not an ELF file").is_err());
+
+        let mut overflowing = elf(3, &[(PT_GNU_STACK, 6), (PT_GNU_RELRO, 4)]);
+        overflowing[32..40].copy_from_slice(&(u64::max_value() - 8).to_le_bytes());
+        assert!(NativeLibrary::parse(&overflowing).is_err());
+    }
+}
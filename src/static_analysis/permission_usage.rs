@@ -0,0 +1,544 @@
+//! Permission usage module.
+//!
+//! Applications often request more permissions than they need, which increases the damage of any
+//! compromise of the application and trains users to accept permissions blindly. After the code
+//! analysis, this module searches the Java code for the APIs that need each permission, and
+//! correlates them with the permissions requested in the manifest:
+//!
+//! * Requested permissions whose APIs are never used are reported as unused permissions, so that
+//!   over-permissioned applications can be spotted. Permissions can also be used through
+//!   reflection or native code, so these findings have low confidence.
+//! * Dangerous APIs used without requesting the permission they expect are reported as warnings,
+//!   since they either fail at runtime or are never reached.
+//!
+//! The usage of every requested permission that can be detected is listed in the _Permission
+//! usage_ section of the results.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{code::add_files_to_vec, manifest::get_line};
+use crate::{
+    criticality::Criticality,
+    get_code, print_message, print_vulnerability, print_warning,
+    results::{Confidence, Results, StageStatus, Vulnerability},
+    Config,
+};
+
+/// Maximum number of files listed for each permission.
+const MAX_FILES: usize = 10;
+
+/// APIs that need a group of permissions.
+struct ProtectedApi {
+    /// Name of the API.
+    name: &'static str,
+    /// Permissions that allow using the API. Any of them is enough.
+    permissions: &'static [&'static str],
+    /// Whether using the API without any of its permissions has to be reported.
+    dangerous: bool,
+    /// Usages of the API in the Java code.
+    regex: Regex,
+}
+
+impl ProtectedApi {
+    /// Creates a new protected API.
+    fn new(
+        name: &'static str,
+        permissions: &'static [&'static str],
+        dangerous: bool,
+        regex: &str,
+    ) -> Self {
+        Self {
+            name,
+            permissions,
+            dangerous,
+            regex: Regex::new(regex).unwrap(),
+        }
+    }
+}
+
+lazy_static! {
+    /// APIs that need permissions, with the permissions they need.
+    static ref PROTECTED_APIS: Vec<ProtectedApi> = vec![
+        ProtectedApi::new(
+            "Camera",
+            &["android.permission.CAMERA"],
+            true,
+            r"\bCamera\.open\s*\(|\bopenCamera\s*\(",
+        ),
+        ProtectedApi::new(
+            "Location",
+            &[
+                "android.permission.ACCESS_FINE_LOCATION",
+                "android.permission.ACCESS_COARSE_LOCATION",
+            ],
+            true,
+            concat!(
+                r"\brequestLocationUpdates\s*\(|\bgetLastKnownLocation\s*\(",
+                r"|\bFusedLocationProviderClient\b|\bgetCurrentLocation\s*\(",
+            ),
+        ),
+        ProtectedApi::new(
+            "Audio recording",
+            &["android.permission.RECORD_AUDIO"],
+            true,
+            r"\bAudioRecord\b|\bsetAudioSource\s*\(",
+        ),
+        ProtectedApi::new(
+            "Contacts",
+            &["android.permission.READ_CONTACTS", "android.permission.WRITE_CONTACTS"],
+            false,
+            r"\bContactsContract\b",
+        ),
+        ProtectedApi::new(
+            "Phone state",
+            &["android.permission.READ_PHONE_STATE", "android.permission.READ_PHONE_NUMBERS"],
+            true,
+            concat!(
+                r"\bgetDeviceId\s*\(|\bgetImei\s*\(|\bgetSubscriberId\s*\(",
+                r"|\bgetSimSerialNumber\s*\(|\bgetLine1Number\s*\(",
+            ),
+        ),
+        ProtectedApi::new(
+            "SMS sending",
+            &["android.permission.SEND_SMS"],
+            true,
+            r"\bsendTextMessage\s*\(|\bsendMultipartTextMessage\s*\(",
+        ),
+        ProtectedApi::new(
+            "SMS reading",
+            &["android.permission.READ_SMS", "android.permission.RECEIVE_SMS"],
+            true,
+            r#"\bTelephony\.Sms\b|"content://sms|\bgetMessagesFromIntent\s*\("#,
+        ),
+        ProtectedApi::new(
+            "Phone calls",
+            &["android.permission.CALL_PHONE"],
+            true,
+            r#"\bACTION_CALL\b|"android\.intent\.action\.CALL""#,
+        ),
+        ProtectedApi::new(
+            "Call log",
+            &["android.permission.READ_CALL_LOG", "android.permission.WRITE_CALL_LOG"],
+            true,
+            r"\bCallLog\.Calls\b",
+        ),
+        ProtectedApi::new(
+            "Calendar",
+            &["android.permission.READ_CALENDAR", "android.permission.WRITE_CALENDAR"],
+            false,
+            r"\bCalendarContract\b",
+        ),
+        ProtectedApi::new(
+            "Accounts",
+            &["android.permission.GET_ACCOUNTS"],
+            false,
+            r"\bgetAccounts(?:ByType)?\s*\(",
+        ),
+        ProtectedApi::new(
+            "External storage",
+            &[
+                "android.permission.READ_EXTERNAL_STORAGE",
+                "android.permission.WRITE_EXTERNAL_STORAGE",
+            ],
+            false,
+            concat!(
+                r"\bgetExternalStorageDirectory\s*\(|\bgetExternalStoragePublicDirectory\s*\(",
+                r"|\bMediaStore\b",
+            ),
+        ),
+        ProtectedApi::new(
+            "Bluetooth",
+            &[
+                "android.permission.BLUETOOTH",
+                "android.permission.BLUETOOTH_ADMIN",
+                "android.permission.BLUETOOTH_CONNECT",
+                "android.permission.BLUETOOTH_SCAN",
+            ],
+            false,
+            r"\bBluetoothAdapter\b|\bBluetoothManager\b",
+        ),
+        ProtectedApi::new(
+            "Wi-Fi state",
+            &["android.permission.ACCESS_WIFI_STATE", "android.permission.CHANGE_WIFI_STATE"],
+            false,
+            r"\bWifiManager\b",
+        ),
+        ProtectedApi::new(
+            "Network state",
+            &["android.permission.ACCESS_NETWORK_STATE"],
+            false,
+            r"\bConnectivityManager\b",
+        ),
+        ProtectedApi::new(
+            "Network access",
+            &["android.permission.INTERNET"],
+            false,
+            concat!(
+                r"\bHttps?URLConnection\b|\bopenConnection\s*\(|\bOkHttpClient\b",
+                r"|\bnew\s+Socket\s*\(|\bWebView\b|\bHttpClient\b",
+            ),
+        ),
+        ProtectedApi::new(
+            "Vibration",
+            &["android.permission.VIBRATE"],
+            false,
+            r"\bVibrator\b|\bVibratorManager\b",
+        ),
+        ProtectedApi::new(
+            "NFC",
+            &["android.permission.NFC"],
+            false,
+            r"\bNfcAdapter\b",
+        ),
+        ProtectedApi::new(
+            "Biometrics",
+            &["android.permission.USE_BIOMETRIC", "android.permission.USE_FINGERPRINT"],
+            false,
+            r"\bBiometricPrompt\b|\bFingerprintManager(?:Compat)?\b",
+        ),
+        ProtectedApi::new(
+            "Wake locks",
+            &["android.permission.WAKE_LOCK"],
+            false,
+            r"\bnewWakeLock\s*\(",
+        ),
+    ];
+}
+
+/// Usage of a permission requested by the application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionUsage {
+    /// Name of the permission.
+    permission: String,
+    /// Names of the APIs of the permission used in the code.
+    apis: BTreeSet<&'static str>,
+    /// Files where the APIs are used, relative to the distribution folder.
+    files: BTreeSet<PathBuf>,
+}
+
+impl PermissionUsage {
+    /// Gets the name of the permission.
+    pub fn permission(&self) -> &str {
+        &self.permission
+    }
+
+    /// Checks if any API of the permission is used in the code.
+    pub fn is_used(&self) -> bool {
+        !self.apis.is_empty()
+    }
+
+    /// Gets the names of the APIs of the permission used in the code.
+    pub fn apis(&self) -> impl Iterator<Item = &str> {
+        self.apis.iter().cloned()
+    }
+
+    /// Gets the first files where the APIs of the permission are used, relative to the
+    /// distribution folder.
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter()
+    }
+}
+
+impl Serialize for PermissionUsage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("PermissionUsage", 4)?;
+        ser_struct.serialize_field("permission", &self.permission)?;
+        ser_struct.serialize_field("used", &self.is_used())?;
+        ser_struct.serialize_field("apis", &self.apis)?;
+        ser_struct.serialize_field("files", &self.files)?;
+        ser_struct.end()
+    }
+}
+
+/// Correlates the permissions requested in the given manifest code with the APIs used in the
+/// code of the application.
+pub fn analysis<S: AsRef<str>>(
+    config: &Config,
+    package: S,
+    requested: &[String],
+    manifest_code: &str,
+    results: &mut Results,
+) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut status = StageStatus::Ok;
+    let mut files = Vec::new();
+    if let Err(e) = add_files_to_vec("", &mut files, package.as_ref(), config) {
+        print_warning(format!(
+            "there was an error listing the code files to search for permission usages: {}",
+            e
+        ));
+        status = StageStatus::failed(e);
+    }
+
+    // Files where each protected API is used, by its index.
+    let mut usages: BTreeMap<usize, BTreeSet<PathBuf>> = BTreeMap::new();
+    for entry in files
+        .iter()
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "java"))
+    {
+        let path = entry.path();
+        let code = match fs::read_to_string(&path) {
+            Ok(code) => code,
+            Err(e) => {
+                print_warning(format!(
+                    "could not search `{}` for permission usages: {}",
+                    path.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+        let file = path.strip_prefix(&dist_folder).unwrap_or(&path);
+        for (index, _) in PROTECTED_APIS
+            .iter()
+            .enumerate()
+            .filter(|(_, api)| api.regex.is_match(&code))
+        {
+            let _ = usages
+                .entry(index)
+                .or_insert_with(BTreeSet::new)
+                .insert(file.to_path_buf());
+        }
+    }
+
+    let (permission_usages, missing) = correlate(requested, &usages);
+    for usage in &permission_usages {
+        if !usage.is_used() {
+            report_unused(config, usage.permission(), manifest_code, results);
+        }
+    }
+    for (api, file) in missing {
+        report_missing(config, api, file, results);
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} of {} detectable permissions are used in the code.",
+            format!(
+                "{}",
+                permission_usages.iter().filter(|u| u.is_used()).count()
+            )
+            .bold(),
+            format!("{}", permission_usages.len()).bold()
+        ));
+    }
+    for usage in permission_usages {
+        results.add_permission_usage(usage);
+    }
+    results.set_stage_status("permission_usage", status);
+}
+
+/// Correlates the requested permissions with the files where each protected API, by its index,
+/// is used.
+///
+/// Returns the usage of each requested permission that can be detected, and the dangerous APIs
+/// that are used without any of their permissions, along with the first file where they are used.
+fn correlate<'a>(
+    requested: &[String],
+    usages: &'a BTreeMap<usize, BTreeSet<PathBuf>>,
+) -> (Vec<PermissionUsage>, Vec<(&'static ProtectedApi, &'a Path)>) {
+    let mut permission_usages = Vec::new();
+    for permission in requested {
+        let mut usage = PermissionUsage {
+            permission: permission.clone(),
+            apis: BTreeSet::new(),
+            files: BTreeSet::new(),
+        };
+        let mut detectable = false;
+        for (index, api) in PROTECTED_APIS.iter().enumerate() {
+            if !api.permissions.contains(&permission.as_str()) {
+                continue;
+            }
+            detectable = true;
+            if let Some(files) = usages.get(&index) {
+                let _ = usage.apis.insert(api.name);
+                for file in files {
+                    if usage.files.len() >= MAX_FILES {
+                        break;
+                    }
+                    let _ = usage.files.insert(file.clone());
+                }
+            }
+        }
+        if detectable && !permission_usages.contains(&usage) {
+            permission_usages.push(usage);
+        }
+    }
+
+    let missing = usages
+        .iter()
+        .filter_map(|(&index, files)| {
+            let api: &'static ProtectedApi = &PROTECTED_APIS[index];
+            let requested = api
+                .permissions
+                .iter()
+                .any(|permission| requested.iter().any(|r| r == permission));
+            if api.dangerous && !requested {
+                Some((api, files.iter().next()?.as_path()))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    (permission_usages, missing)
+}
+
+/// Reports the given permission as requested but unused.
+fn report_unused(config: &Config, permission: &str, manifest_code: &str, results: &mut Results) {
+    let criticality = Criticality::Low;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let description = format!(
+        "The application requests the `{}` permission, but none of the APIs that need it were \
+         found in the code. Requesting permissions that are not needed increases the impact of \
+         any compromise of the application, so it should be removed unless it is used through \
+         reflection or native code.",
+        permission
+    );
+    let mut builder =
+        Vulnerability::builder(criticality, "Unused permission", description.as_str())
+            .file("AndroidManifest.xml")
+            .rule_id("permission-unused")
+            .cwe(250)
+            .confidence(Confidence::Low)
+            .evidence(permission);
+    if let Ok(line) = get_line(manifest_code, permission) {
+        builder = builder
+            .lines(line, line)
+            .code(get_code(manifest_code, line, line));
+    }
+
+    results.add_vulnerability(builder.build());
+    print_vulnerability(description, criticality);
+}
+
+/// Reports the given dangerous API as used without any of its permissions.
+fn report_missing(config: &Config, api: &ProtectedApi, file: &Path, results: &mut Results) {
+    let criticality = Criticality::Warning;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let description = format!(
+        "The code uses the _{}_ API, that needs the {} permission, but the application does not \
+         request it. The code will fail at runtime, unless it is never reached or the permission \
+         is granted through a shared user ID.",
+        api.name,
+        api.permissions
+            .iter()
+            .map(|permission| format!("`{}`", permission))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    );
+    let vulnerability = Vulnerability::builder(
+        criticality,
+        "Protected API without permission",
+        description.as_str(),
+    )
+    .file(file)
+    .rule_id("permission-missing")
+    .confidence(Confidence::Medium)
+    .evidence(api.name)
+    .build();
+
+    results.add_vulnerability(vulnerability);
+    print_vulnerability(description, criticality);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::{Path, PathBuf},
+    };
+
+    use super::{correlate, PROTECTED_APIS};
+
+    /// Gets the index of the protected API with the given name.
+    fn api_index(name: &str) -> usize {
+        PROTECTED_APIS
+            .iter()
+            .position(|api| api.name == name)
+            .unwrap()
+    }
+
+    /// Checks the detection of the protected APIs.
+    #[test]
+    fn it_protected_apis() {
+        let matches = |code: &str| {
+            PROTECTED_APIS
+                .iter()
+                .filter(|api| api.regex.is_match(code))
+                .map(|api| api.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matches("Camera camera = Camera.open(0);"), vec!["Camera"]);
+        assert_eq!(
+            matches("manager.requestLocationUpdates(\"gps\", 0L, 0.0f, listener);"),
+            vec!["Location"]
+        );
+        assert_eq!(
+            matches("SmsManager.getDefault().sendTextMessage(number, null, text, null, null);"),
+            vec!["SMS sending"]
+        );
+        assert!(matches("String opened = \"Camera\";").is_empty());
+    }
+
+    /// Checks the correlation of the requested permissions with the used APIs.
+    #[test]
+    fn it_correlate() {
+        let requested = vec![
+            String::from("android.permission.CAMERA"),
+            String::from("android.permission.ACCESS_FINE_LOCATION"),
+            String::from("android.permission.INTERNET"),
+            String::from("com.example.CUSTOM"),
+        ];
+        let mut usages = BTreeMap::new();
+        let _ = usages.insert(
+            api_index("Location"),
+            vec![PathBuf::from("classes/com/example/Map.java")]
+                .into_iter()
+                .collect::<BTreeSet<_>>(),
+        );
+        let _ = usages.insert(
+            api_index("SMS sending"),
+            vec![PathBuf::from("classes/com/example/Sms.java")]
+                .into_iter()
+                .collect::<BTreeSet<_>>(),
+        );
+
+        let (permission_usages, missing) = correlate(&requested, &usages);
+        assert_eq!(permission_usages.len(), 3);
+        assert_eq!(
+            permission_usages[0].permission(),
+            "android.permission.CAMERA"
+        );
+        assert!(!permission_usages[0].is_used());
+        assert!(permission_usages[1].is_used());
+        assert_eq!(
+            permission_usages[1].apis().collect::<Vec<_>>(),
+            vec!["Location"]
+        );
+        assert!(!permission_usages[2].is_used());
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].0.name, "SMS sending");
+        assert_eq!(missing[0].1, Path::new("classes/com/example/Sms.java"));
+    }
+}
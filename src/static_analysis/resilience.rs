@@ -0,0 +1,381 @@
+//! Resilience inventory module.
+//!
+//! Applications that handle money, health data or corporate devices are expected to defend
+//! themselves against analysis and tampering on compromised devices. This module searches the Java
+//! code for the usual resilience checks:
+//!
+//! * Root detection: `su` binaries, Magisk or SuperSU packages, test-keys builds, or libraries
+//!   such as RootBeer.
+//! * Emulator detection: QEMU properties and the build fingerprints of the emulators.
+//! * Debugger detection: `Debug.isDebuggerConnected()` or the `TracerPid` of the process.
+//! * Signature verification: checks of the signing certificate of the application, or the Play
+//!   Integrity and SafetyNet attestations.
+//!
+//! The checks that were found are listed in the _Resilience_ section of the results. When the
+//! application belongs to one of the sensitive categories of the `resilience_categories`
+//! configuration option (`finance` and `health` by default), detected from its package name and
+//! from the SDKs it uses, each missing check is reported as a warning. Checks implemented in native
+//! code or behind obfuscation are not detected, so these warnings have medium confidence.
+
+use std::{collections::BTreeSet, fmt, fs, path::PathBuf};
+
+use colored::Colorize;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::code::add_files_to_vec;
+use crate::{
+    criticality::Criticality,
+    print_message, print_vulnerability, print_warning,
+    results::{Confidence, Results, StageStatus, Vulnerability},
+    Config,
+};
+
+/// Maximum number of files listed for each protection.
+const MAX_FILES: usize = 10;
+
+lazy_static! {
+    /// Code of each protection, in the order of [`Protection::ALL`].
+    static ref PROTECTION_REGEXES: [Regex; 4] = [
+        Regex::new(concat!(
+            r#"\bcom\.scottyab\.rootbeer\b|\bRootBeer\b|"/(?:system/(?:x?bin|sbin)|sbin)/su""#,
+            r#"|\bSuperuser\.apk\b|"test-keys"|\bcom\.topjohnwu\.magisk\b"#,
+            r"|\beu\.chainfire\.supersu\b",
+            r"|\bis(?:Device)?Rooted\s*\(",
+        ))
+        .unwrap(),
+        Regex::new(concat!(
+            r#""(?:goldfish|ranchu|google_sdk|vbox86p)"|\bro\.kernel\.qemu\b|[Gg]enymotion"#,
+            r"|Android SDK built for x86|\bis(?:Probably(?:An)?)?Emulator\s*\(",
+        ))
+        .unwrap(),
+        Regex::new(concat!(
+            r"\bDebug\.isDebuggerConnected\s*\(|\bDebug\.waitingForDebugger\s*\(|\bTracerPid\b",
+            r"|\bisDebuggerAttached\s*\(",
+        ))
+        .unwrap(),
+        Regex::new(concat!(
+            r"\bGET_SIGNATURES\b|\bGET_SIGNING_CERTIFICATES\b|\bgetApkContentsSigners\s*\(",
+            r"|\bgetSigningCertificateHistory\s*\(|\bIntegrityManager(?:Factory)?\b",
+            r"|\bcom\.google\.android\.play\.core\.integrity\b|\bSafetyNet\.getClient\s*\(",
+        ))
+        .unwrap(),
+    ];
+
+    /// Package names and code of each application category, in the order of
+    /// [`AppCategory::ALL`].
+    static ref CATEGORY_REGEXES: [(Regex, Regex); 3] = [
+        (
+            Regex::new(r"(?i)bank|wallet|financ|invest|trading|crypto|payment").unwrap(),
+            Regex::new(concat!(
+                r"\bcom\.(?:stripe\.android|braintreepayments|paypal\.android|adyen\.checkout)\b",
+                r"|\bcom\.google\.android\.gms\.wallet\b|\bcom\.squareup\.sdk\.pos\b",
+            ))
+            .unwrap(),
+        ),
+        (
+            Regex::new(r"(?i)health|medic|clinic|patient|hospital").unwrap(),
+            Regex::new(concat!(
+                r"\bandroidx\.health\.connect\b|\bcom\.google\.android\.gms\.fitness\b",
+                r"|\bHealthConnectClient\b",
+            ))
+            .unwrap(),
+        ),
+        (
+            Regex::new(r"(?i)enterprise|\bmdm\b").unwrap(),
+            Regex::new(r"\bDevicePolicyManager\b|\bDeviceAdminReceiver\b").unwrap(),
+        ),
+    ];
+}
+
+/// Resilience check against analysis and tampering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Protection {
+    /// Detection of rooted devices.
+    RootDetection,
+    /// Detection of emulators.
+    EmulatorDetection,
+    /// Detection of attached debuggers.
+    DebuggerDetection,
+    /// Verification of the signature or the integrity of the application.
+    SignatureVerification,
+}
+
+impl Protection {
+    /// All the protections.
+    pub const ALL: [Self; 4] = [
+        Protection::RootDetection,
+        Protection::EmulatorDetection,
+        Protection::DebuggerDetection,
+        Protection::SignatureVerification,
+    ];
+
+    /// Checks if the protection is implemented in the given code.
+    pub fn is_implemented_in(self, code: &str) -> bool {
+        PROTECTION_REGEXES[self as usize].is_match(code)
+    }
+
+    /// Gets the MASVS control of the protection.
+    fn masvs(self) -> &'static str {
+        match self {
+            Protection::RootDetection | Protection::EmulatorDetection => "MASVS-RESILIENCE-1",
+            Protection::SignatureVerification => "MASVS-RESILIENCE-2",
+            Protection::DebuggerDetection => "MASVS-RESILIENCE-4",
+        }
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Protection::RootDetection => write!(f, "root detection"),
+            Protection::EmulatorDetection => write!(f, "emulator detection"),
+            Protection::DebuggerDetection => write!(f, "debugger detection"),
+            Protection::SignatureVerification => write!(f, "signature verification"),
+        }
+    }
+}
+
+/// Category of applications that need resilience checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppCategory {
+    /// Banking, payment, investment and cryptocurrency applications.
+    Finance,
+    /// Health and fitness applications that handle medical data.
+    Health,
+    /// Device management and corporate applications.
+    Enterprise,
+}
+
+impl AppCategory {
+    /// All the categories.
+    pub const ALL: [Self; 3] = [
+        AppCategory::Finance,
+        AppCategory::Health,
+        AppCategory::Enterprise,
+    ];
+
+    /// Gets the regular expressions of the package names and of the code of the category.
+    fn regexes(self) -> &'static (Regex, Regex) {
+        &CATEGORY_REGEXES[self as usize]
+    }
+
+    /// Checks if the given package name belongs to the category.
+    pub fn matches_package(self, package: &str) -> bool {
+        self.regexes().0.is_match(package)
+    }
+
+    /// Checks if the given code uses the SDKs of the category.
+    pub fn matches_code(self, code: &str) -> bool {
+        self.regexes().1.is_match(code)
+    }
+}
+
+impl fmt::Display for AppCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppCategory::Finance => write!(f, "finance"),
+            AppCategory::Health => write!(f, "health"),
+            AppCategory::Enterprise => write!(f, "enterprise"),
+        }
+    }
+}
+
+/// Resilience check of the application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectionCheck {
+    /// Protection searched in the code.
+    protection: Protection,
+    /// Files where the protection is implemented, relative to the distribution folder.
+    files: BTreeSet<PathBuf>,
+}
+
+impl ProtectionCheck {
+    /// Gets the protection searched in the code.
+    pub fn protection(&self) -> Protection {
+        self.protection
+    }
+
+    /// Checks if the protection is implemented in the code.
+    pub fn is_implemented(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    /// Gets the first files where the protection is implemented, relative to the distribution
+    /// folder.
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter()
+    }
+}
+
+impl Serialize for ProtectionCheck {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("ProtectionCheck", 3)?;
+        ser_struct.serialize_field("protection", &self.protection)?;
+        ser_struct.serialize_field("implemented", &self.is_implemented())?;
+        ser_struct.serialize_field("files", &self.files)?;
+        ser_struct.end()
+    }
+}
+
+/// Searches the code of the application for resilience checks, and reports the missing ones if
+/// the application belongs to a sensitive category.
+pub fn analysis<S: AsRef<str>>(config: &Config, package: S, results: &mut Results) {
+    let dist_folder = config.dist_folder().join(package.as_ref());
+    let mut status = StageStatus::Ok;
+    let mut files = Vec::new();
+    if let Err(e) = add_files_to_vec("", &mut files, package.as_ref(), config) {
+        print_warning(format!(
+            "there was an error listing the code files to search for resilience checks: {}",
+            e
+        ));
+        status = StageStatus::failed(e);
+    }
+
+    let mut checks = Protection::ALL
+        .iter()
+        .map(|&protection| ProtectionCheck {
+            protection,
+            files: BTreeSet::new(),
+        })
+        .collect::<Vec<_>>();
+    let mut categories = AppCategory::ALL
+        .iter()
+        .cloned()
+        .filter(|category| category.matches_package(results.app_package()))
+        .collect::<BTreeSet<_>>();
+    for entry in files
+        .iter()
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "java"))
+    {
+        let path = entry.path();
+        let code = match fs::read_to_string(&path) {
+            Ok(code) => code,
+            Err(e) => {
+                print_warning(format!(
+                    "could not search `{}` for resilience checks: {}",
+                    path.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+        let file = path.strip_prefix(&dist_folder).unwrap_or(&path);
+        for check in checks
+            .iter_mut()
+            .filter(|check| check.files.len() < MAX_FILES)
+        {
+            if check.protection.is_implemented_in(&code) {
+                let _ = check.files.insert(file.to_path_buf());
+            }
+        }
+        for &category in &AppCategory::ALL {
+            if !categories.contains(&category) && category.matches_code(&code) {
+                let _ = categories.insert(category);
+            }
+        }
+    }
+
+    if config.is_verbose() {
+        print_message(format!(
+            "{} of {} resilience checks are implemented in the code.",
+            format!(
+                "{}",
+                checks.iter().filter(|check| check.is_implemented()).count()
+            )
+            .bold(),
+            format!("{}", checks.len()).bold()
+        ));
+    }
+
+    if let Some(category) = categories
+        .iter()
+        .find(|category| config.resilience_categories().any(|c| c == *category))
+    {
+        for check in checks.iter().filter(|check| !check.is_implemented()) {
+            report_missing(config, check.protection, *category, results);
+        }
+    }
+    for check in checks {
+        results.add_protection_check(check);
+    }
+    results.set_stage_status("resilience", status);
+}
+
+/// Reports the given protection as missing in an application of the given sensitive category.
+fn report_missing(
+    config: &Config,
+    protection: Protection,
+    category: AppCategory,
+    results: &mut Results,
+) {
+    let criticality = Criticality::Warning;
+    if criticality < config.min_criticality() {
+        return;
+    }
+
+    let description = format!(
+        "No {} was found in the code of this {} application. Applications that handle \
+         sensitive data should detect compromised environments and tampering, to make attacks \
+         and the analysis of the application harder. The check might still be implemented in \
+         native code or hidden by obfuscation.",
+        protection, category
+    );
+    let vulnerability = Vulnerability::builder(
+        criticality,
+        "Missing Resilience Check",
+        description.as_str(),
+    )
+    .rule_id(format!("resilience:missing-{}", protection).replace(' ', "-"))
+    .cwe(693)
+    .masvs(vec![protection.masvs()])
+    .confidence(Confidence::Medium)
+    .evidence(protection.to_string())
+    .build();
+
+    results.add_vulnerability(vulnerability);
+    print_vulnerability(description, criticality);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppCategory, Protection};
+
+    /// Checks the detection of the resilience checks and of the application categories.
+    #[test]
+    fn it_resilience_checks() {
+        let code = r#"public class Security {
+    public static boolean isRooted() {
+        return new File("/system/xbin/su").exists() || Build.TAGS.contains("test-keys");
+    }
+
+    public static boolean isDebugged() {
+        return Debug.isDebuggerConnected();
+    }
+}"#;
+        assert!(Protection::RootDetection.is_implemented_in(code));
+        assert!(Protection::DebuggerDetection.is_implemented_in(code));
+        assert!(!Protection::EmulatorDetection.is_implemented_in(code));
+        assert!(!Protection::SignatureVerification.is_implemented_in(code));
+
+        assert!(Protection::EmulatorDetection
+            .is_implemented_in(r#"if (Build.HARDWARE.equals("goldfish")) { exit(); }"#));
+        assert!(Protection::SignatureVerification.is_implemented_in(
+            "pm.getPackageInfo(getPackageName(), PackageManager.GET_SIGNING_CERTIFICATES);"
+        ));
+
+        assert!(AppCategory::Finance.matches_package("com.mybank.mobile"));
+        assert!(!AppCategory::Finance.matches_package("com.example.notes"));
+        assert!(
+            AppCategory::Finance.matches_code("import com.stripe.android.PaymentConfiguration;")
+        );
+        assert!(AppCategory::Health.matches_code("HealthConnectClient.getOrCreate(context)"));
+        assert!(!AppCategory::Enterprise.matches_code("Debug.isDebuggerConnected()"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Run statistics module.
+//!
+//! SUPER keeps some statistics about each analyzed package: the number of findings by
+//! criticality, the duration of each stage of the analysis and the number of source files analyzed
+//! and skipped. They can be written as a small JSON document, independent of the full reports, so
+//! that CI pipelines and dashboards can consume them without parsing the results.
+//!
+//! Statistics are kept by package, so that concurrent analyses of different packages do not mix
+//! their statistics, and a new analysis of a package replaces its previous statistics. Long
+//! running processes, such as the server, remove them once they are no longer needed.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::Duration,
+};
+
+use failure::{format_err, Error, ResultExt};
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+use crate::{criticality::Criticality, results::Results};
+
+lazy_static! {
+    /// Statistics of the packages analyzed in this run, in the order they were analyzed.
+    static ref PACKAGES: Mutex<Vec<PackageStats>> = Mutex::new(Vec::new());
+}
+
+/// Records the start of the analysis of the given package, replacing its previous statistics.
+pub fn start_package<S: Into<String>>(package: S) {
+    let package = package.into();
+    if let Ok(mut packages) = PACKAGES.lock() {
+        packages.retain(|stats| stats.package != package);
+        packages.push(PackageStats::new(package));
+    }
+}
+
+/// Removes the statistics of the given package.
+pub fn remove_package(package: &str) {
+    if let Ok(mut packages) = PACKAGES.lock() {
+        packages.retain(|stats| stats.package != package);
+    }
+}
+
+/// Records the duration of the given stage of the analysis of the given package.
+pub fn record_duration(package: &str, stage: &str, duration: Duration) {
+    with_package(package, |stats| stats.record_duration(stage, duration));
+}
+
+/// Records the results of the analysis of the given package.
+pub(crate) fn record_results(package: &str, results: &Results) {
+    with_package(package, |stats| stats.record_results(results));
+}
+
+/// Updates the statistics of the given package, if its analysis was started.
+fn with_package<F: FnOnce(&mut PackageStats)>(package: &str, update: F) {
+    if let Ok(mut packages) = PACKAGES.lock() {
+        if let Some(stats) = packages.iter_mut().find(|stats| stats.package == package) {
+            update(stats);
+        }
+    }
+}
+
+/// Counts the findings of every analyzed package with the given criticality or higher.
+pub fn count_findings(criticality: Criticality) -> usize {
+    PACKAGES.lock().map_or(0, |packages| {
+        packages
+            .iter()
+            .flat_map(|package| package.findings.range(criticality..))
+            .map(|(_, count)| count)
+            .sum()
+    })
+}
+
+/// Renders the statistics of the run as JSON.
+pub fn render() -> Result<String, Error> {
+    let packages = PACKAGES
+        .lock()
+        .map_err(|_| format_err!("the run statistics are poisoned"))?;
+    Ok(serde_json::to_string_pretty(&Stats {
+        packages: &packages,
+    })?)
+}
+
+/// Writes the statistics of the run to the given file, or to the standard output if the path is
+/// `-`.
+pub fn write<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut stats = render()?;
+    stats.push('\n');
+
+    if path == Path::new("-") {
+        io::stdout()
+            .write_all(stats.as_bytes())
+            .context("could not write the run statistics to the standard output")?;
+    } else {
+        fs::write(path, stats).context(format_err!(
+            "could not write the run statistics to `{}`",
+            path.display()
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Statistics of a run.
+#[derive(Debug, Serialize)]
+struct Stats<'p> {
+    /// Statistics of each analyzed package.
+    packages: &'p [PackageStats],
+}
+
+/// Statistics of the analysis of a package.
+#[derive(Debug, Serialize)]
+struct PackageStats {
+    /// Package of the application.
+    package: String,
+    /// Whether the analysis produced results.
+    completed: bool,
+    /// Number of findings by criticality.
+    findings: BTreeMap<Criticality, usize>,
+    /// Duration of each stage of the analysis, in seconds.
+    durations: BTreeMap<String, f64>,
+    /// Number of source files analyzed.
+    files_analyzed: usize,
+    /// Number of source files that could not be analyzed.
+    files_skipped: usize,
+}
+
+impl PackageStats {
+    /// Creates the empty statistics of the given package.
+    fn new(package: String) -> Self {
+        Self {
+            package,
+            completed: false,
+            findings: BTreeMap::new(),
+            durations: BTreeMap::new(),
+            files_analyzed: 0,
+            files_skipped: 0,
+        }
+    }
+
+    /// Records the duration of the given stage.
+    fn record_duration(&mut self, stage: &str, duration: Duration) {
+        let seconds = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+        *self.durations.entry(stage.to_owned()).or_insert(0.0) += seconds;
+    }
+
+    /// Records the given results.
+    fn record_results(&mut self, results: &Results) {
+        for vulnerability in results.vulnerabilities() {
+            *self
+                .findings
+                .entry(vulnerability.get_criticality())
+                .or_insert(0) += 1;
+        }
+        self.files_analyzed = results.files_analyzed();
+        self.files_skipped = results.files_skipped();
+        self.completed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::{PackageStats, Stats};
+    use crate::criticality::Criticality;
+
+    /// Checks the serialization of the run statistics.
+    #[test]
+    fn it_render() {
+        let mut package = PackageStats::new(String::from("com.example.app"));
+        package.record_duration("decompression", Duration::from_millis(500));
+        package.record_duration("decompression", Duration::from_millis(250));
+        let _ = package.findings.insert(Criticality::High, 3);
+        package.files_analyzed = 10;
+        package.files_skipped = 1;
+        package.completed = true;
+
+        let packages = [package];
+        let stats = serde_json::to_value(&Stats {
+            packages: &packages,
+        })
+        .unwrap();
+        assert_eq!(
+            stats,
+            json!({
+                "packages": [{
+                    "package": "com.example.app",
+                    "completed": true,
+                    "findings": { "high": 3 },
+                    "durations": { "decompression": 0.75 },
+                    "files_analyzed": 10,
+                    "files_skipped": 1,
+                }]
+            })
+        );
+    }
+}
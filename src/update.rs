@@ -0,0 +1,250 @@
+//! Self-update module.
+//!
+//! This module implements the `self-update` subcommand, that checks the latest release of SUPER
+//! in GitHub and, if it is newer than the running version, downloads it and replaces the running
+//! binary with it. It is only available with the `self-update` feature, and it never runs unless
+//! explicitly requested.
+//!
+//! Release binaries are named after the platform, such as `super-linux-x86_64`, and they are
+//! published along with a minisign signature (`.minisig`) and a SHA-256 checksum (`.sha256`). The
+//! signature is verified with the `update_keys` of the configuration, and updates without a valid
+//! signature are refused, unless `--allow-unsigned` is given, in which case only the checksum is
+//! verified. The `trusted_keys` of the rules are never used to verify binaries.
+//!
+//! Proxies can be set with the `--proxy` option or with the `HTTPS_PROXY` and `ALL_PROXY`
+//! environment variables.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{crate_version, ArgMatches};
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+use reqwest::Client;
+use semver::Version;
+use serde::Deserialize;
+
+use crate::{
+    http::{client, download, verify_checksum, USER_AGENT},
+    i18n::tr_args,
+    print_warning, signature, Config,
+};
+
+/// URL of the latest release in the GitHub API.
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/SUPERAndroidAnalyzer/super/releases/latest";
+
+/// Runs the `self-update` subcommand.
+#[allow(clippy::print_stdout)]
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let client = client(cli.value_of("proxy"))?;
+    let release: Release = client
+        .get(LATEST_RELEASE_URL)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .and_then(reqwest::Response::error_for_status)
+        .and_then(|mut response| response.json())
+        .context("could not get the latest release of SUPER")?;
+
+    let current = Version::parse(crate_version!()).context("invalid version of SUPER")?;
+    let latest = release.version()?;
+    if latest <= current {
+        println!(
+            "{}",
+            tr_args(
+                "SUPER is up to date, {} is the latest version.",
+                &[&current.to_string().bold()]
+            )
+        );
+        return Ok(());
+    }
+    println!(
+        "{}",
+        tr_args(
+            "SUPER {} is available, the current version is {}.",
+            &[&latest.to_string().bold(), &current]
+        )
+    );
+    if cli.is_present("check") {
+        return Ok(());
+    }
+
+    let name = asset_name();
+    let binary = release.asset(&name)?;
+    let binary = download(&client, &binary.browser_download_url)
+        .context("could not download the new version")?;
+    verify(
+        config,
+        &client,
+        &release,
+        &name,
+        &binary,
+        cli.is_present("allow-unsigned"),
+    )
+    .context("could not verify the new version")?;
+
+    let executable = env::current_exe().context("could not find the running executable")?;
+    replace(&executable, &binary).context(format_err!(
+        "could not replace the executable `{}`",
+        executable.display()
+    ))?;
+    println!(
+        "{}",
+        tr_args(
+            "SUPER has been updated to {}.",
+            &[&latest.to_string().bold()]
+        )
+    );
+
+    Ok(())
+}
+
+/// Release in the GitHub API.
+#[derive(Debug, Deserialize)]
+struct Release {
+    /// Tag of the release, such as `v0.6.0`.
+    tag_name: String,
+    /// Files attached to the release.
+    assets: Vec<Asset>,
+}
+
+impl Release {
+    /// Gets the version of the release.
+    fn version(&self) -> Result<Version, Error> {
+        Ok(Version::parse(self.tag_name.trim_start_matches('v'))
+            .context(format_err!("invalid release tag `{}`", self.tag_name))?)
+    }
+
+    /// Gets the asset with the given name.
+    fn asset(&self, name: &str) -> Result<&Asset, Error> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| format_err!("the latest release has no `{}` file", name))
+    }
+}
+
+/// File attached to a release in the GitHub API.
+#[derive(Debug, Deserialize)]
+struct Asset {
+    /// Name of the file.
+    name: String,
+    /// URL to download the file.
+    browser_download_url: String,
+}
+
+/// Gets the name of the release binary for the current platform.
+fn asset_name() -> String {
+    format!(
+        "super-{}-{}{}",
+        env::consts::OS,
+        env::consts::ARCH,
+        env::consts::EXE_SUFFIX
+    )
+}
+
+/// Verifies the signature or, if unsigned updates are allowed, the checksum of the new binary.
+fn verify(
+    config: &Config,
+    client: &Client,
+    release: &Release,
+    name: &str,
+    binary: &[u8],
+    allow_unsigned: bool,
+) -> Result<(), Error> {
+    let keys = config.update_keys().map(String::as_str).collect::<Vec<_>>();
+    if !keys.is_empty() {
+        let signature = release.asset(&format!("{}.minisig", name))?;
+        let signature = String::from_utf8(download(client, &signature.browser_download_url)?)
+            .context("the signature is not valid UTF-8")?;
+        return signature::verify(binary, &signature, &keys);
+    }
+    if !allow_unsigned {
+        bail!(
+            "there are no trusted keys to verify the signature of the new version, add them to \
+             the `update_keys` of the configuration or use `--allow-unsigned`"
+        );
+    }
+
+    print_warning(
+        "the signature of the new version cannot be verified, only its checksum will be checked",
+    );
+    let checksum = release.asset(&format!("{}.sha256", name))?;
+    let checksum = String::from_utf8(download(client, &checksum.browser_download_url)?)
+        .context("the checksum is not valid UTF-8")?;
+    verify_checksum(binary, &checksum)
+}
+
+/// Replaces the given executable with the given binary.
+///
+/// The new binary is written next to the executable and then moved over it, so that the
+/// executable is never left half-written. On Windows, the running executable cannot be
+/// overwritten, so it is renamed first, and kept with the `.old` extension.
+fn replace(executable: &Path, binary: &[u8]) -> Result<(), Error> {
+    let new_path = sibling(executable, "new");
+    fs::write(&new_path, binary).context("could not write the new version")?;
+    let permissions = fs::metadata(executable)
+        .context("could not read the permissions of the executable")?
+        .permissions();
+    fs::set_permissions(&new_path, permissions)
+        .context("could not set the permissions of the new version")?;
+
+    if cfg!(target_family = "windows") {
+        let old_path = sibling(executable, "old");
+        if old_path.exists() {
+            fs::remove_file(&old_path).context("could not remove the previous version")?;
+        }
+        fs::rename(executable, &old_path).context("could not move the current version")?;
+    }
+    fs::rename(&new_path, executable).context("could not move the new version")?;
+
+    Ok(())
+}
+
+/// Gets the path of a file next to the given one, with the given extension appended.
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{sibling, Release};
+
+    /// Checks the parsing of the release versions.
+    #[test]
+    fn it_release_version() {
+        let release: Release = serde_json::from_str(
+            r#"{
+                "tag_name": "v0.6.0",
+                "assets": [{
+                    "name": "super-linux-x86_64",
+                    "browser_download_url": "https://example.com/super-linux-x86_64"
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(release.version().unwrap().to_string(), "0.6.0");
+        assert!(release.asset("super-linux-x86_64").is_ok());
+        assert!(release.asset("super-macos-x86_64").is_err());
+    }
+
+    /// Checks the paths of the temporary executables.
+    #[test]
+    fn it_sibling() {
+        assert_eq!(
+            sibling(Path::new("/usr/bin/super"), "new"),
+            Path::new("/usr/bin/super.new")
+        );
+    }
+}
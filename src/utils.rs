@@ -1,17 +1,26 @@
 //! General utilities module.
 
-use std::{fmt, fs, path::Path, thread::sleep, time::Duration};
+use std::{
+    env, fmt, fs,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::sleep,
+    time::Duration,
+};
 
+use atty::Stream;
 use colored::Colorize;
-use failure::Error;
+use failure::{format_err, Error};
 use lazy_static::lazy_static;
 use log::Level::Debug;
+use sha2::{Digest, Sha256};
 use xml::{
     reader::{EventReader, XmlEvent},
     ParserConfig,
 };
 
-use crate::{config::Config, criticality::Criticality};
+use crate::{config::Config, criticality::Criticality, i18n::tr_args};
 
 /// Configuration for the XML parser.
 lazy_static! {
@@ -25,31 +34,110 @@ lazy_static! {
     .coalesce_characters(true);
 }
 
+/// Current output mode, as the index of the mode in `OutputMode::ALL`.
+static OUTPUT_MODE: AtomicUsize = AtomicUsize::new(0);
+
+/// Destination of the messages for the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Messages are printed to the standard output, and warnings are logged.
+    Console,
+    /// Messages and warnings are only sent to the logger, so that the host application decides
+    /// where they go.
+    Log,
+    /// No message or warning is printed nor logged.
+    Silent,
+}
+
+impl OutputMode {
+    /// All the output modes.
+    const ALL: [OutputMode; 3] = [OutputMode::Console, OutputMode::Log, OutputMode::Silent];
+}
+
+impl Default for OutputMode {
+    fn default() -> Self {
+        OutputMode::Console
+    }
+}
+
+impl FromStr for OutputMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "console" => Ok(OutputMode::Console),
+            "log" => Ok(OutputMode::Log),
+            "silent" => Ok(OutputMode::Silent),
+            _ => Err(format_err!(
+                "unknown output mode `{}`, it must be `console`, `log` or `silent`",
+                s
+            )),
+        }
+    }
+}
+
+/// Sets the destination of the messages for the user.
+pub fn set_output_mode(mode: OutputMode) {
+    let index = OutputMode::ALL
+        .iter()
+        .position(|&m| m == mode)
+        .expect("all output modes must be listed");
+    OUTPUT_MODE.store(index, Ordering::SeqCst);
+}
+
+/// Gets the current destination of the messages for the user.
+pub fn output_mode() -> OutputMode {
+    OutputMode::ALL[OUTPUT_MODE.load(Ordering::SeqCst)]
+}
+
+/// Prints a message for the user, following the output mode.
+///
+/// Empty messages, used to separate blocks of messages in the console, are not logged.
+#[allow(clippy::print_stdout)]
+pub fn print_message<D: fmt::Display>(message: D) {
+    match output_mode() {
+        OutputMode::Console => println!("{}", message),
+        OutputMode::Log => {
+            let message = message.to_string();
+            if !message.is_empty() {
+                info!("{}", message);
+            }
+        }
+        OutputMode::Silent => {}
+    }
+}
+
 /// Prints a warning to `stderr` in yellow.
 #[allow(clippy::print_stdout)]
 pub fn print_warning<S: AsRef<str>>(warning: S) {
     if cfg!(not(test)) {
+        let mode = output_mode();
+        if mode == OutputMode::Silent {
+            return;
+        }
         warn!("{}", warning.as_ref());
 
         if log_enabled!(Debug) {
             sleep(Duration::from_millis(200));
-        } else {
+        } else if mode == OutputMode::Console {
             println!(
-                "If you need more information, try to run the program again with the {} flag.",
-                "-v".bold()
+                "{}",
+                tr_args(
+                    "If you need more information, try to run the program again with the {} flag.",
+                    &[&"-v".bold()]
+                )
             )
         }
     }
 }
 
 /// Prints a vulnerability to `stdout` in a color depending on the criticality.
-#[allow(clippy::print_stdout)]
 pub fn print_vulnerability<S: AsRef<str>>(text: S, criticality: Criticality) {
     if cfg!(not(test)) && log_enabled!(Debug) {
-        let message = format!(
+        let message = tr_args(
             "Possible {} criticality vulnerability found!: {}",
-            criticality,
-            text.as_ref()
+            &[&criticality, &text.as_ref()],
         );
 
         let formatted_message = match criticality {
@@ -59,7 +147,7 @@ pub fn print_vulnerability<S: AsRef<str>>(text: S, criticality: Criticality) {
             _ => return,
         };
 
-        println!("{}", formatted_message);
+        print_message(formatted_message);
         sleep(Duration::from_millis(200));
     }
 }
@@ -75,6 +163,20 @@ pub fn get_package_name<P: AsRef<Path>>(path: P) -> String {
         .into_owned()
 }
 
+/// Checks if the given package name is safe to use as a file name.
+pub(crate) fn is_valid_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+}
+
+/// Computes the SHA-256 hash of the given data, in hexadecimal.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
 /// Gets the code snippet near the start and end lines.
 ///
 /// It will return 5 lines above and 5 lines below the vulnerability.
@@ -97,11 +199,18 @@ pub fn get_string<L: AsRef<str>, P: AsRef<str>>(
     label: L,
     config: &Config,
     package: P,
+) -> Result<String, Error> {
+    get_string_from(label, config.dist_folder().join(package.as_ref()))
+}
+
+/// Gets a string from the strings XML file of the decompressed application in the given folder.
+pub(crate) fn get_string_from<L: AsRef<str>, P: AsRef<Path>>(
+    label: L,
+    app_folder: P,
 ) -> Result<String, Error> {
     let code = fs::read_to_string({
-        let path = config
-            .dist_folder()
-            .join(package.as_ref())
+        let path = app_folder
+            .as_ref()
             .join("res")
             .join("values-en")
             .join("strings.xml");
@@ -109,9 +218,8 @@ pub fn get_string<L: AsRef<str>, P: AsRef<str>>(
         if path.exists() {
             path
         } else {
-            config
-                .dist_folder()
-                .join(package.as_ref())
+            app_folder
+                .as_ref()
                 .join("res")
                 .join("values")
                 .join("strings.xml")
@@ -146,6 +254,59 @@ pub fn get_string<L: AsRef<str>, P: AsRef<str>>(
     Ok(String::new())
 }
 
+/// When to use colors and styles in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use colors if the output is a terminal and the `NO_COLOR` environment variable is not set.
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns true if colors should be used in the standard output.
+    pub fn use_colors(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => !is_no_color_set() && atty::is(Stream::Stdout),
+        }
+    }
+
+    /// Applies the choice to the colored output of the standard output.
+    ///
+    /// The logger, that writes to the standard error, is configured separately in
+    /// `initialize_logger()`.
+    pub fn apply(self) {
+        colored::control::set_override(self.use_colors());
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format_err!(
+                "unknown color choice `{}`, it must be `auto`, `always` or `never`",
+                s
+            )),
+        }
+    }
+}
+
+/// Checks if the `NO_COLOR` environment variable is set to a non-empty value.
+///
+/// See <https://no-color.org/>.
+pub fn is_no_color_set() -> bool {
+    env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty())
+}
+
 /// Structure to store a benchmark information.
 #[derive(Debug)]
 pub struct Benchmark {
@@ -163,6 +324,16 @@ impl Benchmark {
             duration,
         }
     }
+
+    /// Gets the label of the benchmark.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Gets the duration of the benchmark.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
 }
 
 impl fmt::Display for Benchmark {
@@ -179,6 +350,7 @@ impl fmt::Display for Benchmark {
 
 #[cfg(test)]
 mod test {
+    use super::{is_valid_package_name, output_mode, set_output_mode, ColorChoice, OutputMode};
     use crate::get_code;
 
     #[test]
@@ -245,4 +417,45 @@ mod test {
              Sed lacinia, urna non tincidunt mattis, tortor neque adipiscing\n"
         );
     }
+    #[test]
+    fn it_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"hello"),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn it_valid_package_name() {
+        assert!(is_valid_package_name("com.example_app-1"));
+        assert!(!is_valid_package_name(""));
+        assert!(!is_valid_package_name(".."));
+        assert!(!is_valid_package_name("../etc/passwd"));
+        assert!(!is_valid_package_name("app name"));
+    }
+
+    #[test]
+    fn it_color_choice() {
+        assert_eq!("auto".parse::<ColorChoice>().unwrap(), ColorChoice::Auto);
+        assert_eq!(
+            "Always".parse::<ColorChoice>().unwrap(),
+            ColorChoice::Always
+        );
+        assert_eq!("never".parse::<ColorChoice>().unwrap(), ColorChoice::Never);
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+        assert!(ColorChoice::Always.use_colors());
+        assert!(!ColorChoice::Never.use_colors());
+    }
+
+    #[test]
+    fn it_output_mode() {
+        assert_eq!("log".parse::<OutputMode>().unwrap(), OutputMode::Log);
+        assert_eq!("Silent".parse::<OutputMode>().unwrap(), OutputMode::Silent);
+        assert!("stdout".parse::<OutputMode>().is_err());
+
+        set_output_mode(OutputMode::Silent);
+        assert_eq!(output_mode(), OutputMode::Silent);
+        set_output_mode(OutputMode::Console);
+        assert_eq!(output_mode(), OutputMode::Console);
+    }
 }
@@ -0,0 +1,425 @@
+//! Report verification module.
+//!
+//! Reports record the fingerprint of the analyzed application, and the provenance of the
+//! analysis: the version of SUPER and the hashes of the rules and of the configuration that
+//! produced them. This module implements the `verify` subcommand, that recomputes them for a given
+//! application and the current configuration, to confirm that a `results.json` report really
+//! corresponds to that binary and to that scanner configuration.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{crate_version, ArgMatches};
+use colored::Colorize;
+use failure::{bail, format_err, Error, ResultExt};
+use serde_json::Value;
+
+use crate::{
+    config::Profile,
+    correlation::CorrelationRule,
+    criticality::Criticality,
+    decompilation::Decompiler,
+    external::ExternalAnalyzer,
+    results::{baseline_file, FingerPrint, RiskWeights},
+    static_analysis::{
+        code::rule_files,
+        dependencies::{LibraryVulnerability, VulnerabilityDatabase},
+        manifest::{Permission, PermissionCatalog, PermissionCombination},
+        resilience::AppCategory,
+    },
+    utils::sha256_hex,
+    Config,
+};
+
+/// Provenance of an analysis: the scanner version, rules and configuration that produced it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Provenance {
+    /// Version of SUPER.
+    super_version: String,
+    /// SHA-256 hash of the rules file, or of all the rule packs in order if it is a folder.
+    rules_sha256: String,
+    /// SHA-256 hash of the configuration settings that affect the findings.
+    config_sha256: String,
+}
+
+impl Provenance {
+    /// Computes the provenance of an analysis with the given configuration.
+    pub(crate) fn compute(config: &Config) -> Result<Self, Error> {
+        let mut rules = Vec::new();
+        for path in config.rules_paths() {
+            for file in rule_files(path)? {
+                rules.extend(fs::read(&file).context(format_err!(
+                    "could not read the rules file `{}`",
+                    file.display()
+                ))?);
+            }
+        }
+
+        Ok(Self {
+            super_version: crate_version!().to_owned(),
+            rules_sha256: sha256_hex(&rules),
+            config_sha256: sha256_hex(&serde_json::to_vec(&FindingSettings::new(config))?),
+        })
+    }
+}
+
+/// Settings of the configuration that affect the findings.
+///
+/// Folders, output formats and integrations do not change the findings, so they are not part of
+/// them, and the same report can be verified from a different machine. The files used by the
+/// analysis, such as the plugins, the scripts, the baseline and the imported reports, are
+/// represented by the SHA-256 hashes of their contents.
+#[derive(Debug, Serialize)]
+struct FindingSettings<'c> {
+    /// Minimum criticality of the findings.
+    min_criticality: Criticality,
+    /// Analysis profile.
+    profile: Profile,
+    /// Whether the hygiene findings are only reported for release applications.
+    hygiene_release_only: bool,
+    /// Categories of applications whose missing resilience checks are reported.
+    resilience_categories: Vec<AppCategory>,
+    /// Extensions of the files analyzed with the code rules.
+    code_extensions: Vec<&'c str>,
+    /// Folders that are not analyzed, with forward slashes.
+    ignored_folders: Vec<String>,
+    /// Maximum size of the analyzed files, in KiB.
+    max_file_size: Option<u64>,
+    /// Time budget of each code rule, in seconds.
+    rule_time_budget: u64,
+    /// Whether the slow code rules are disabled.
+    disable_slow_rules: bool,
+    /// Whether the online checks run.
+    online_checks: bool,
+    /// Decompiler used first.
+    decompiler: Decompiler,
+    /// Criticality and description of the unknown permissions.
+    unknown_permission: (Criticality, &'c str),
+    /// Analyzed permissions, with their criticality, label and description.
+    permissions: Vec<(&'c str, Criticality, &'c str, &'c str)>,
+    /// Known permissions added to the built-in catalog.
+    known_permissions: Vec<&'c str>,
+    /// Library vulnerabilities added to the built-in dataset.
+    library_vulnerabilities: Vec<&'c LibraryVulnerability>,
+    /// Risky combinations of permissions.
+    permission_combinations: Vec<&'c PermissionCombination>,
+    /// Correlation rules.
+    correlations: Vec<&'c CorrelationRule>,
+    /// External analyzers.
+    external_analyzers: Vec<&'c ExternalAnalyzer>,
+    /// Hashes of the WebAssembly plugins, by file name.
+    plugins: Vec<(String, String)>,
+    /// Maximum number of instructions of each call to a plugin or a script.
+    plugin_fuel: u64,
+    /// Hashes of the Rhai scripts, by file name.
+    scripts: Vec<(String, String)>,
+    /// Hash of the baseline that suppresses the known findings, if any.
+    baseline: Option<String>,
+    /// Formats and hashes of the imported reports.
+    imported_reports: Vec<(&'static str, String)>,
+    /// Weights of the risk factors.
+    risk: &'c RiskWeights,
+}
+
+impl<'c> FindingSettings<'c> {
+    /// Gets the settings of the given configuration that affect the findings.
+    fn new(config: &'c Config) -> Self {
+        let builtin_permissions = PermissionCatalog::builtin();
+        let builtin_vulnerabilities = VulnerabilityDatabase::builtin();
+        Self {
+            min_criticality: config.min_criticality(),
+            profile: config.profile(),
+            hygiene_release_only: config.is_hygiene_release_only(),
+            resilience_categories: config.resilience_categories().copied().collect(),
+            code_extensions: config.code_extensions().map(String::as_str).collect(),
+            ignored_folders: config
+                .ignored_folders()
+                .map(|folder| folder.to_string_lossy().replace('\\', "/"))
+                .collect(),
+            max_file_size: config.max_file_size(),
+            rule_time_budget: config.rule_time_budget(),
+            disable_slow_rules: config.is_disable_slow_rules(),
+            online_checks: config.is_online_checks(),
+            decompiler: config.decompiler(),
+            unknown_permission: (
+                config.unknown_permission_criticality(),
+                config.unknown_permission_description(),
+            ),
+            permissions: config
+                .permissions()
+                .map(|permission| {
+                    (
+                        permission.name().as_str(),
+                        permission.criticality(),
+                        permission.label(),
+                        permission.description(),
+                    )
+                })
+                .collect(),
+            known_permissions: config
+                .permission_catalog()
+                .permissions()
+                .filter(|permission| !builtin_permissions.contains(permission))
+                .map(Permission::as_str)
+                .collect(),
+            library_vulnerabilities: config
+                .library_vulnerabilities()
+                .vulnerabilities()
+                .filter(|vulnerability| !builtin_vulnerabilities.contains(vulnerability))
+                .collect(),
+            permission_combinations: config.permission_combinations().collect(),
+            correlations: config.correlations().collect(),
+            external_analyzers: config.external_analyzers().collect(),
+            plugins: if cfg!(feature = "plugins") {
+                file_hashes(config.plugins_folder(), "wasm")
+            } else {
+                Vec::new()
+            },
+            plugin_fuel: config.plugin_fuel(),
+            scripts: if cfg!(feature = "scripting") {
+                file_hashes(config.scripts_folder(), "rhai")
+            } else {
+                Vec::new()
+            },
+            baseline: baseline_file(config)
+                .filter(|_| !config.is_update_baseline())
+                .and_then(|path| fs::read(path).ok())
+                .map(|baseline| sha256_hex(&baseline)),
+            imported_reports: config
+                .imported_reports()
+                .map(|(format, path)| {
+                    (
+                        format.as_str(),
+                        fs::read(path).map_or_else(|_| String::new(), |report| sha256_hex(&report)),
+                    )
+                })
+                .collect(),
+            risk: config.risk(),
+        }
+    }
+}
+
+/// Computes the SHA-256 hashes of the files with the given extension in the given folder, sorted
+/// by file name.
+fn file_hashes(folder: &Path, extension: &str) -> Vec<(String, String)> {
+    let mut hashes = fs::read_dir(folder)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == extension))
+        .filter_map(|path| {
+            let contents = fs::read(&path).ok()?;
+            Some((
+                path.file_name()?.to_string_lossy().into_owned(),
+                sha256_hex(&contents),
+            ))
+        })
+        .collect::<Vec<_>>();
+    hashes.sort();
+    hashes
+}
+
+/// Runs the `verify` subcommand.
+#[allow(clippy::print_stdout)]
+pub fn run(config: &Config, cli: &ArgMatches<'static>) -> Result<(), Error> {
+    let package = PathBuf::from(cli.value_of("package").expect("the package is required"));
+    let report_path = Path::new(cli.value_of("report").expect("the report is required"));
+    let report: Value = serde_json::from_str(&fs::read_to_string(report_path).context(
+        format_err!("could not read the report `{}`", report_path.display()),
+    )?)
+    .context(format_err!("invalid report `{}`", report_path.display()))?;
+
+    let fingerprint = serde_json::to_value(
+        FingerPrint::from_package(&package)
+            .context(format_err!("could not fingerprint `{}`", package.display()))?,
+    )?;
+    let provenance = serde_json::to_value(Provenance::compute(config)?)?;
+
+    let checks = check(&report, &fingerprint, &provenance);
+    for check in &checks {
+        let status = match check.result {
+            CheckResult::Match => "✓".green(),
+            CheckResult::Mismatch => "✗".red(),
+            CheckResult::Missing => "?".yellow(),
+        };
+        println!("{} {}", status, check.name);
+    }
+
+    let mismatches = checks
+        .iter()
+        .filter(|check| check.result != CheckResult::Match)
+        .filter(|check| check.name != "SUPER version")
+        .count();
+    if mismatches > 0 {
+        bail!(
+            "the report `{}` does not correspond to `{}` with the current configuration, {} \
+             checks failed",
+            report_path.display(),
+            package.display(),
+            mismatches
+        );
+    }
+
+    println!(
+        "{}",
+        format!(
+            "The report corresponds to `{}` with the current configuration.",
+            package.display()
+        )
+        .green()
+    );
+    Ok(())
+}
+
+/// Result of a verification check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckResult {
+    /// The value in the report matches the recomputed value.
+    Match,
+    /// The value in the report does not match the recomputed value.
+    Mismatch,
+    /// The report does not contain the value.
+    Missing,
+}
+
+/// Verification check.
+#[derive(Debug)]
+struct Check {
+    /// Name of the check.
+    name: &'static str,
+    /// Result of the check.
+    result: CheckResult,
+}
+
+/// Checks the report against the recomputed fingerprint and provenance.
+///
+/// A different version of SUPER is reported, but it does not make the verification fail by
+/// itself, since the rules and the configuration are checked separately.
+fn check(report: &Value, fingerprint: &Value, provenance: &Value) -> Vec<Check> {
+    let compare = |name, reported: Option<&Value>, expected: &Value| Check {
+        name,
+        result: match reported {
+            Some(reported) if reported == expected => CheckResult::Match,
+            Some(_) => CheckResult::Mismatch,
+            None => CheckResult::Missing,
+        },
+    };
+    let app_fingerprint = report.get("app_fingerprint");
+    let scanner = report.get("scanner");
+
+    vec![
+        compare(
+            "application SHA-256",
+            app_fingerprint.and_then(|f| f.get("sha256")),
+            &fingerprint["sha256"],
+        ),
+        compare(
+            "application SHA-1",
+            app_fingerprint.and_then(|f| f.get("sha1")),
+            &fingerprint["sha1"],
+        ),
+        compare(
+            "application MD5",
+            app_fingerprint.and_then(|f| f.get("md5")),
+            &fingerprint["md5"],
+        ),
+        compare(
+            "code files",
+            app_fingerprint.and_then(|f| f.get("files")),
+            &fingerprint["files"],
+        ),
+        compare(
+            "rules",
+            scanner.and_then(|s| s.get("rules_sha256")),
+            &provenance["rules_sha256"],
+        ),
+        compare(
+            "configuration",
+            scanner.and_then(|s| s.get("config_sha256")),
+            &provenance["config_sha256"],
+        ),
+        compare(
+            "SUPER version",
+            report.get("super_version"),
+            &provenance["super_version"],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{check, CheckResult, FindingSettings};
+    use crate::config::Config;
+
+    /// Checks the verification of reports.
+    #[test]
+    fn it_check() {
+        let fingerprint = json!({
+            "md5": "a", "sha1": "b", "sha256": "c", "ssdeep": "3::",
+            "files": [{ "path": "classes.dex", "sha256": "d" }],
+        });
+        let provenance = json!({
+            "super_version": "0.5.1", "rules_sha256": "e", "config_sha256": "f",
+        });
+        let report = json!({
+            "super_version": "0.5.0",
+            "app_fingerprint": fingerprint,
+            "scanner": { "super_version": "0.5.0", "rules_sha256": "e", "config_sha256": "g" },
+        });
+
+        let results = check(&report, &fingerprint, &provenance)
+            .into_iter()
+            .map(|check| (check.name, check.result))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                ("application SHA-256", CheckResult::Match),
+                ("application SHA-1", CheckResult::Match),
+                ("application MD5", CheckResult::Match),
+                ("code files", CheckResult::Match),
+                ("rules", CheckResult::Match),
+                ("configuration", CheckResult::Mismatch),
+                ("SUPER version", CheckResult::Mismatch),
+            ]
+        );
+
+        let results = check(&json!({}), &fingerprint, &provenance);
+        assert!(results
+            .iter()
+            .all(|check| check.result == CheckResult::Missing));
+    }
+
+    /// Checks that the settings that affect the findings are part of the configuration hash.
+    #[test]
+    fn it_finding_settings() {
+        let config = Config::default();
+        let settings = serde_json::to_value(FindingSettings::new(&config)).unwrap();
+        for setting in &[
+            "min_criticality",
+            "profile",
+            "decompiler",
+            "permission_combinations",
+            "correlations",
+            "external_analyzers",
+            "plugins",
+            "scripts",
+            "baseline",
+            "imported_reports",
+            "risk",
+        ] {
+            assert!(settings.get(setting).is_some(), "missing `{}`", setting);
+        }
+        assert_eq!(settings["profile"], "standard");
+        assert!(settings["known_permissions"].as_array().unwrap().is_empty());
+        assert!(settings["library_vulnerabilities"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+}
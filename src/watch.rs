@@ -0,0 +1,333 @@
+//! Watch folder module.
+//!
+//! With `--watch <dir>`, SUPER monitors the given folder and analyzes every new application
+//! package that appears in it, so that it can be used as a drop folder for batch scans. Packages
+//! are only analyzed once their size has not changed between two checks, to avoid analyzing files
+//! that are still being copied.
+//!
+//! Packages are deduplicated by the SHA-256 hash of their contents: a package that was already
+//! analyzed is skipped even if it is copied again with a different name. After each analysis, a
+//! summary index of every analyzed package is written to the results folder, both as JSON
+//! (`index.json`) and as HTML (`index.html`). The JSON index is also read when the watch starts,
+//! so packages analyzed in previous runs are not analyzed again.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use failure::{format_err, Error, ResultExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analyze_package,
+    bundle::is_package_extension,
+    criticality::Criticality,
+    i18n::tr_args,
+    print_message, print_warning,
+    results::{html_escape, Results},
+    utils::sha256_hex,
+    Config,
+};
+
+/// Time between two checks of the watched folder.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Name of the JSON summary index, in the results folder.
+pub const INDEX_JSON_FILE: &str = "index.json";
+
+/// Name of the HTML summary index, in the results folder.
+pub const INDEX_HTML_FILE: &str = "index.html";
+
+/// Analysis of a package of the watched folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    /// SHA-256 hash of the package, in hexadecimal.
+    sha256: String,
+    /// Name of the package file.
+    file: String,
+    /// Package of the application, if the analysis finished.
+    package: Option<String>,
+    /// Version of the application, if the analysis finished.
+    version: Option<String>,
+    /// Date of the analysis.
+    analyzed: DateTime<Local>,
+    /// Number of findings by criticality.
+    findings: BTreeMap<Criticality, usize>,
+    /// Error of the analysis, if it failed.
+    error: Option<String>,
+}
+
+/// Summary index of the analyzed packages.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Index {
+    /// Analyses of the packages, in the order they were analyzed.
+    entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Loads the JSON index of the given results folder, or an empty index if it does not exist.
+    fn load<P: AsRef<Path>>(results_folder: P) -> Result<Self, Error> {
+        let path = results_folder.as_ref().join(INDEX_JSON_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let index = fs::read_to_string(&path)
+            .context(format_err!("could not read the index `{}`", path.display()))?;
+        Ok(serde_json::from_str(&index)
+            .context(format_err!("invalid index `{}`", path.display()))?)
+    }
+
+    /// Checks if a package with the given hash has already been analyzed.
+    fn contains(&self, sha256: &str) -> bool {
+        self.entries.iter().any(|entry| entry.sha256 == sha256)
+    }
+
+    /// Adds the result of the analysis of the given package file to the index.
+    fn add(&mut self, sha256: String, file: &Path, analysis: Result<&Results, &Error>) {
+        let file = file
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let mut entry = IndexEntry {
+            sha256,
+            file,
+            package: None,
+            version: None,
+            analyzed: Local::now(),
+            findings: BTreeMap::new(),
+            error: None,
+        };
+        match analysis {
+            Ok(results) => {
+                entry.package = Some(results.app_package().to_owned());
+                entry.version = Some(results.app_version().to_owned());
+                for vulnerability in results.vulnerabilities() {
+                    *entry
+                        .findings
+                        .entry(vulnerability.get_criticality())
+                        .or_insert(0) += 1;
+                }
+            }
+            Err(e) => {
+                entry.error = Some(
+                    e.iter_chain()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(": "),
+                );
+            }
+        }
+        self.entries.push(entry);
+    }
+
+    /// Renders the index as an HTML page, linking to the report of each application.
+    fn to_html(&self) -> String {
+        let criticalities = [
+            Criticality::Critical,
+            Criticality::High,
+            Criticality::Medium,
+            Criticality::Low,
+            Criticality::Warning,
+        ];
+
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>SUPER Android Analyzer</title>\n</head>\n<body>\n<table>\n<tr>\
+             <th>File</th><th>Package</th><th>Version</th><th>Analyzed</th>",
+        );
+        for criticality in &criticalities {
+            let _ = write!(html, "<th>{}</th>", criticality);
+        }
+        html.push_str("</tr>\n");
+
+        for entry in self.entries.iter().rev() {
+            let _ = write!(html, "<tr><td>{}</td>", html_escape(entry.file.as_str()));
+            match entry.package {
+                Some(ref package) => {
+                    let package = html_escape(package.as_str());
+                    let _ = write!(
+                        html,
+                        "<td><a href=\"{0}/{1}\">{0}</a></td>",
+                        package, INDEX_HTML_FILE
+                    );
+                }
+                None => html.push_str("<td></td>"),
+            }
+            let _ = write!(
+                html,
+                "<td>{}</td><td>{}</td>",
+                html_escape(entry.version.as_ref().map_or("", String::as_str)),
+                entry.analyzed.format("%Y-%m-%d %H:%M:%S")
+            );
+            match entry.error {
+                Some(ref error) => {
+                    let _ = write!(
+                        html,
+                        "<td colspan=\"{}\">{}</td>",
+                        criticalities.len(),
+                        html_escape(error.as_str())
+                    );
+                }
+                None => {
+                    for criticality in &criticalities {
+                        let _ = write!(
+                            html,
+                            "<td>{}</td>",
+                            entry.findings.get(criticality).cloned().unwrap_or(0)
+                        );
+                    }
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Writes the JSON and HTML index to the given results folder.
+    fn write<P: AsRef<Path>>(&self, results_folder: P) -> Result<(), Error> {
+        let results_folder = results_folder.as_ref();
+        fs::create_dir_all(results_folder).context(format_err!(
+            "could not create the results folder `{}`",
+            results_folder.display()
+        ))?;
+
+        let json_path = results_folder.join(INDEX_JSON_FILE);
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).context(format_err!(
+            "could not write the index `{}`",
+            json_path.display()
+        ))?;
+        let html_path = results_folder.join(INDEX_HTML_FILE);
+        fs::write(&html_path, self.to_html()).context(format_err!(
+            "could not write the index `{}`",
+            html_path.display()
+        ))?;
+
+        Ok(())
+    }
+}
+
+/// Lists the application packages of the given folder, along with their size.
+fn list_packages<P: AsRef<Path>>(folder: P) -> Result<BTreeMap<PathBuf, u64>, Error> {
+    let folder = folder.as_ref();
+    let mut packages = BTreeMap::new();
+    for entry in fs::read_dir(folder).context(format_err!(
+        "could not read the watched folder `{}`",
+        folder.display()
+    ))? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().map_or(false, is_package_extension) && entry.file_type()?.is_file() {
+            let _ = packages.insert(path, entry.metadata()?.len());
+        }
+    }
+    Ok(packages)
+}
+
+/// Watches the given folder, analyzing every new application package that appears in it.
+///
+/// It only returns if the watched folder cannot be read or the index cannot be written.
+pub fn run<P: AsRef<Path>>(mut config: Config, folder: P) -> Result<(), Error> {
+    let folder = folder.as_ref();
+    let mut index = Index::load(config.results_folder())?;
+    // Packages seen in the previous check that have not been analyzed yet, with their size.
+    let mut pending = BTreeMap::new();
+
+    print_message(tr_args(
+        "Watching {} for new applications.",
+        &[&folder.display()],
+    ));
+    loop {
+        let packages = list_packages(folder)?;
+        for (path, &size) in &packages {
+            // Wait until the package is completely written.
+            if pending.get(path) != Some(&size) {
+                continue;
+            }
+
+            let sha256 = match fs::read(path) {
+                Ok(contents) => sha256_hex(&contents),
+                Err(e) => {
+                    print_warning(format!("could not read `{}`: {}", path.display(), e));
+                    continue;
+                }
+            };
+            if index.contains(&sha256) {
+                continue;
+            }
+
+            config.select_package(path);
+            let analysis = analyze_package(path, &mut config, &mut BTreeMap::new());
+            if let Err(ref e) = analysis {
+                print_warning(format!(
+                    "the analysis of `{}` failed: {}",
+                    path.display(),
+                    e
+                ));
+            }
+            index.add(sha256, path, analysis.as_ref());
+            index.write(config.results_folder())?;
+        }
+
+        pending = packages;
+        sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
+    use chrono::Local;
+    use failure::format_err;
+
+    use super::{Index, IndexEntry};
+    use crate::criticality::Criticality;
+
+    /// Checks the deduplication, serialization and rendering of the index.
+    #[test]
+    fn it_index() {
+        let mut index = Index::default();
+        let mut findings = BTreeMap::new();
+        let _ = findings.insert(Criticality::High, 2);
+        index.entries.push(IndexEntry {
+            sha256: String::from("aabb"),
+            file: String::from("app.apk"),
+            package: Some(String::from("com.example.app")),
+            version: Some(String::from("1.0")),
+            analyzed: Local::now(),
+            findings,
+            error: None,
+        });
+        index.add(
+            String::from("ccdd"),
+            Path::new("/srv/drop/broken.apk"),
+            Err(&format_err!("invalid <package>")),
+        );
+
+        assert!(index.contains("aabb"));
+        assert!(index.contains("ccdd"));
+        assert!(!index.contains("eeff"));
+        assert_eq!(index.entries[1].file, "broken.apk");
+        assert_eq!(
+            index.entries[1].error.as_ref().map(String::as_str),
+            Some("invalid <package>")
+        );
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert!(json.contains("\"high\":2"));
+        assert_eq!(serde_json::from_str::<Index>(&json).unwrap(), index);
+
+        let html = index.to_html();
+        assert!(html.contains("<a href=\"com.example.app/index.html\">com.example.app</a>"));
+        assert!(html.contains("<td>2</td>"));
+        assert!(html.contains("invalid &lt;package&gt;"));
+    }
+}